@@ -0,0 +1,136 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+#ifndef UP_RUST_H
+#define UP_RUST_H
+
+/* Generated by cbindgen from up-rust's ffi module, do not edit manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct UMessage UMessage;
+
+typedef struct UUri UUri;
+
+// An opaque handle to a [`LocalTransport`].
+typedef struct UpLocalTransport UpLocalTransport;
+
+// The type of function that is invoked for messages received by the local transport.
+typedef void (*UpMessageCallback)(const UMessage *message, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Releases a string that has been returned by one of the functions of this module.
+void up_string_free(char *value);
+
+// Releases a byte buffer that has been returned by one of the functions of this module.
+void up_bytes_free(uint8_t *buf, uintptr_t len);
+
+// Creates a URI from its string representation.
+int32_t up_uri_parse(const char *uri, UUri **out);
+
+// Creates a URI from its components.
+int32_t up_uri_new(const char *authority,
+                   uint32_t entity_id,
+                   uint8_t entity_version,
+                   uint16_t resource_id,
+                   UUri **out);
+
+// Gets the string representation of a URI.
+char *up_uri_to_string(const UUri *uri);
+
+// Gets the authority name of a URI.
+char *up_uri_get_authority(const UUri *uri);
+
+// Gets the uEntity ID of a URI.
+uint32_t up_uri_get_entity_id(const UUri *uri);
+
+// Gets the uEntity major version of a URI.
+uint8_t up_uri_get_entity_version(const UUri *uri);
+
+// Gets the resource ID of a URI.
+uint16_t up_uri_get_resource_id(const UUri *uri);
+
+// Releases a URI.
+void up_uri_free(UUri *uri);
+
+// Creates a Publish message.
+int32_t up_message_build_publish(const UUri *topic,
+                                 const uint8_t *payload,
+                                 uintptr_t payload_len,
+                                 int32_t payload_format,
+                                 UMessage **out);
+
+// Creates a Notification message.
+int32_t up_message_build_notification(const UUri *origin,
+                                      const UUri *destination,
+                                      const uint8_t *payload,
+                                      uintptr_t payload_len,
+                                      int32_t payload_format,
+                                      UMessage **out);
+
+// Serializes a message to its protobuf representation.
+int32_t up_message_serialize(const UMessage *message, uint8_t **buf, uintptr_t *len);
+
+// Parses a message from its protobuf representation.
+int32_t up_message_parse(const uint8_t *buf, uintptr_t len, UMessage **out);
+
+// Gets the type of a message (a `UMessageType` value).
+int32_t up_message_get_type(const UMessage *message);
+
+// Gets the source address of a message.
+UUri *up_message_get_source(const UMessage *message);
+
+// Gets the sink address of a message.
+UUri *up_message_get_sink(const UMessage *message);
+
+// Gets the payload of a message.
+int32_t up_message_get_payload(const UMessage *message, const uint8_t **data, uintptr_t *len);
+
+// Gets the format of a message's payload (a `UPayloadFormat` value).
+int32_t up_message_get_payload_format(const UMessage *message);
+
+// Releases a message.
+void up_message_free(UMessage *message);
+
+// Creates a new local transport.
+UpLocalTransport *up_local_transport_new(void);
+
+// Sends a message via a local transport.
+int32_t up_local_transport_send(const UpLocalTransport *transport, const UMessage *message);
+
+// Registers a callback for messages matching given source and sink filters.
+int32_t up_local_transport_register_listener(const UpLocalTransport *transport,
+                                             const UUri *source_filter,
+                                             const UUri *sink_filter,
+                                             UpMessageCallback callback,
+                                             void *user_data,
+                                             uint64_t *listener_id);
+
+// Unregisters a callback.
+int32_t up_local_transport_unregister_listener(const UpLocalTransport *transport,
+                                               uint64_t listener_id);
+
+// Releases a local transport.
+void up_local_transport_free(UpLocalTransport *transport);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* UP_RUST_H */
@@ -0,0 +1,22 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+C bindings for the core types of up-rust.
+
+This crate packages the C ABI provided by up-rust's `ffi` module as a static and a dynamic library.
+The functions are declared in `include/up_rust.h`, which is generated from the `ffi` module by means
+of [cbindgen](https://github.com/mozilla/cbindgen).
+*/
+
+pub use up_rust::ffi::*;
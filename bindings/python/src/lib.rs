@@ -0,0 +1,315 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Python bindings for the core types of up-rust.
+
+Enum values (message types, priorities and payload formats) are exposed as module level
+integer constants named after the corresponding protobuf enum values, e.g. `UPRIORITY_CS4`.
+*/
+
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use protobuf::{Enum, Message};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use up_rust::{
+    UAttributesValidators, UMessage, UMessageBuilder, UMessageType, UPayloadFormat, UPriority,
+    UUri, UUID,
+};
+
+fn value_error<E: ToString>(err: E) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// A uProtocol URI.
+#[pyclass(name = "UUri", module = "up_rust", eq, frozen)]
+#[derive(Clone, PartialEq)]
+struct PyUUri(UUri);
+
+#[pymethods]
+impl PyUUri {
+    #[new]
+    fn new(
+        authority: &str,
+        entity_id: u32,
+        entity_version: u8,
+        resource_id: u16,
+    ) -> PyResult<Self> {
+        UUri::try_from_parts(authority, entity_id, entity_version, resource_id)
+            .map(PyUUri)
+            .map_err(value_error)
+    }
+
+    /// Creates a URI from its string representation.
+    #[staticmethod]
+    fn parse(uri: &str) -> PyResult<Self> {
+        UUri::from_str(uri).map(PyUUri).map_err(value_error)
+    }
+
+    #[getter]
+    fn authority(&self) -> &str {
+        &self.0.authority_name
+    }
+
+    #[getter]
+    fn entity_id(&self) -> u32 {
+        self.0.ue_id
+    }
+
+    #[getter]
+    fn entity_version(&self) -> u8 {
+        self.0.uentity_major_version()
+    }
+
+    #[getter]
+    fn resource_id(&self) -> u16 {
+        self.0.resource_id()
+    }
+
+    /// Checks if a given URI matches this URI, which may contain wildcards.
+    fn matches(&self, candidate: &PyUUri) -> bool {
+        self.0.matches(&candidate.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_uri(false)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("UUri('{}')", self.0.to_uri(false))
+    }
+}
+
+/// A uProtocol UUID.
+#[pyclass(name = "UUID", module = "up_rust", eq, frozen)]
+#[derive(Clone, PartialEq)]
+struct PyUUID(UUID);
+
+#[pymethods]
+impl PyUUID {
+    /// Creates a new UUID for the current point in time.
+    #[staticmethod]
+    fn build() -> Self {
+        PyUUID(UUID::build())
+    }
+
+    /// Creates a UUID from its hyphenated string representation.
+    #[staticmethod]
+    fn parse(uuid: &str) -> PyResult<Self> {
+        UUID::from_str(uuid).map(PyUUID).map_err(value_error)
+    }
+
+    /// The point in time (milliseconds since the Unix epoch) that the UUID has been created at,
+    /// or `None` if this is not a uProtocol UUID.
+    #[getter]
+    fn time(&self) -> Option<u64> {
+        self.0.get_time()
+    }
+
+    fn is_uprotocol_uuid(&self) -> bool {
+        self.0.is_uprotocol_uuid()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_hyphenated_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("UUID('{}')", self.0.to_hyphenated_string())
+    }
+}
+
+/// A builder for uProtocol messages.
+#[pyclass(name = "UMessageBuilder", module = "up_rust")]
+struct PyUMessageBuilder(UMessageBuilder);
+
+#[pymethods]
+impl PyUMessageBuilder {
+    #[staticmethod]
+    fn publish(topic: &PyUUri) -> Self {
+        PyUMessageBuilder(UMessageBuilder::publish(topic.0.clone()))
+    }
+
+    #[staticmethod]
+    fn notification(origin: &PyUUri, destination: &PyUUri) -> Self {
+        PyUMessageBuilder(UMessageBuilder::notification(
+            origin.0.clone(),
+            destination.0.clone(),
+        ))
+    }
+
+    #[staticmethod]
+    fn request(method: &PyUUri, reply_to: &PyUUri, ttl: u32) -> Self {
+        PyUMessageBuilder(UMessageBuilder::request(
+            method.0.clone(),
+            reply_to.0.clone(),
+            ttl,
+        ))
+    }
+
+    #[staticmethod]
+    fn response(reply_to: &PyUUri, request_id: &PyUUID, method: &PyUUri) -> Self {
+        PyUMessageBuilder(UMessageBuilder::response(
+            reply_to.0.clone(),
+            request_id.0.clone(),
+            method.0.clone(),
+        ))
+    }
+
+    fn with_message_id<'a>(mut slf: PyRefMut<'a, Self>, message_id: &PyUUID) -> PyRefMut<'a, Self> {
+        slf.0.with_message_id(message_id.0.clone());
+        slf
+    }
+
+    fn with_priority(mut slf: PyRefMut<'_, Self>, priority: i32) -> PyResult<PyRefMut<'_, Self>> {
+        let priority = UPriority::from_i32(priority)
+            .ok_or_else(|| PyValueError::new_err("unknown priority"))?;
+        slf.0.with_priority(priority);
+        Ok(slf)
+    }
+
+    fn with_ttl(mut slf: PyRefMut<'_, Self>, ttl: u32) -> PyRefMut<'_, Self> {
+        slf.0.with_ttl(ttl);
+        slf
+    }
+
+    fn with_token(mut slf: PyRefMut<'_, Self>, token: String) -> PyRefMut<'_, Self> {
+        slf.0.with_token(token);
+        slf
+    }
+
+    fn with_traceparent(mut slf: PyRefMut<'_, Self>, traceparent: String) -> PyRefMut<'_, Self> {
+        slf.0.with_traceparent(traceparent);
+        slf
+    }
+
+    /// Creates the message, failing if the builder's state does not result in a valid message.
+    #[pyo3(signature = (payload=None, payload_format=0))]
+    fn build(&mut self, payload: Option<Vec<u8>>, payload_format: i32) -> PyResult<PyUMessage> {
+        let result = match payload {
+            Some(payload) => {
+                let format = UPayloadFormat::from_i32(payload_format)
+                    .ok_or_else(|| PyValueError::new_err("unknown payload format"))?;
+                self.0.build_with_payload(payload, format)
+            }
+            None => self.0.build(),
+        };
+        result.map(PyUMessage).map_err(value_error)
+    }
+}
+
+/// A uProtocol message.
+#[pyclass(name = "UMessage", module = "up_rust", eq, frozen)]
+#[derive(Clone, PartialEq)]
+struct PyUMessage(UMessage);
+
+#[pymethods]
+impl PyUMessage {
+    /// Creates a message from its protobuf representation, failing if the message is invalid.
+    #[staticmethod]
+    fn parse(data: &[u8]) -> PyResult<Self> {
+        let message = PyUMessage(UMessage::parse_from_bytes(data).map_err(value_error)?);
+        validate_message(&message)?;
+        Ok(message)
+    }
+
+    /// Gets the message's protobuf representation.
+    fn serialize(&self) -> PyResult<Cow<'_, [u8]>> {
+        self.0.write_to_bytes().map(Cow::Owned).map_err(value_error)
+    }
+
+    #[getter]
+    fn id(&self) -> Option<PyUUID> {
+        self.0
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.id.as_ref())
+            .map(|id| PyUUID(id.to_owned()))
+    }
+
+    #[getter]
+    fn r#type(&self) -> i32 {
+        self.0
+            .attributes
+            .as_ref()
+            .map_or(0, |attributes| attributes.type_.value())
+    }
+
+    #[getter]
+    fn source(&self) -> Option<PyUUri> {
+        self.0
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.source.as_ref())
+            .map(|uri| PyUUri(uri.to_owned()))
+    }
+
+    #[getter]
+    fn sink(&self) -> Option<PyUUri> {
+        self.0
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.sink.as_ref())
+            .map(|uri| PyUUri(uri.to_owned()))
+    }
+
+    #[getter]
+    fn payload(&self) -> Option<Cow<'_, [u8]>> {
+        self.0
+            .payload
+            .as_ref()
+            .map(|payload| Cow::Borrowed(payload.as_ref()))
+    }
+
+    #[getter]
+    fn payload_format(&self) -> i32 {
+        self.0
+            .attributes
+            .as_ref()
+            .map_or(0, |attributes| attributes.payload_format.value())
+    }
+}
+
+/// Checks if a message's attributes are valid for the message's type.
+#[pyfunction]
+fn validate_message(message: &PyUMessage) -> PyResult<()> {
+    let Some(attributes) = message.0.attributes.as_ref() else {
+        return Err(PyValueError::new_err("message has no attributes"));
+    };
+    UAttributesValidators::get_validator_for_attributes(attributes)
+        .validate(attributes)
+        .map_err(value_error)
+}
+
+#[pymodule]
+#[pyo3(name = "up_rust")]
+fn up_rust_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyUUri>()?;
+    m.add_class::<PyUUID>()?;
+    m.add_class::<PyUMessageBuilder>()?;
+    m.add_class::<PyUMessage>()?;
+    m.add_function(wrap_pyfunction!(validate_message, m)?)?;
+    for message_type in UMessageType::VALUES {
+        m.add(format!("{:?}", message_type), message_type.value())?;
+    }
+    for priority in UPriority::VALUES {
+        m.add(format!("{:?}", priority), priority.value())?;
+    }
+    for format in UPayloadFormat::VALUES {
+        m.add(format!("{:?}", format), format.value())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+# Copyright (c) 2024 Contributors to the Eclipse Foundation
+#
+# See the NOTICE file(s) distributed with this work for additional
+# information regarding copyright ownership.
+#
+# This program and the accompanying materials are made available under the
+# terms of the Apache License Version 2.0 which is available at
+# https://www.apache.org/licenses/LICENSE-2.0
+#
+# SPDX-License-Identifier: Apache-2.0
+
+import pytest
+
+import up_rust
+
+
+def test_uri_round_trip():
+    uri = up_rust.UUri("my-vehicle", 0xA8000, 0x02, 0x8A50)
+    assert str(uri) == "//my-vehicle/A8000/2/8A50"
+    assert up_rust.UUri.parse(str(uri)) == uri
+
+
+def test_invalid_uri_raises_value_error():
+    with pytest.raises(ValueError):
+        up_rust.UUri.parse("up://my-vehicle/not-a-number/2/8A50")
+
+
+def test_message_round_trip():
+    topic = up_rust.UUri.parse("//my-vehicle/A8000/2/8A50")
+    message = (
+        up_rust.UMessageBuilder.publish(topic)
+        .with_priority(up_rust.UPRIORITY_CS2)
+        .build(b"locked", up_rust.UPAYLOAD_FORMAT_TEXT)
+    )
+    parsed = up_rust.UMessage.parse(message.serialize())
+    assert parsed == message
+    assert parsed.type == up_rust.UMESSAGE_TYPE_PUBLISH
+    assert parsed.source == topic
+    assert parsed.payload == b"locked"
+    assert parsed.id.is_uprotocol_uuid()
+
+
+def test_build_rejects_invalid_attributes():
+    topic = up_rust.UUri.parse("//my-vehicle/A8000/2/1")
+    with pytest.raises(ValueError):
+        up_rust.UMessageBuilder.publish(topic).build()
@@ -0,0 +1,476 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a synchronous facade for the [Communication Layer API](crate::communication).
+
+The types in this module wrap the (asynchronous) Communication Layer API and block the
+calling thread until the corresponding operation has completed. This allows code bases that
+are not (yet) based on `async`/`await` to use uProtocol.
+
+All wrappers share a single, internal Tokio runtime which is created on first use.
+Listeners and request handlers that are registered by means of the wrappers are usually
+invoked on one of the runtime's worker threads. However, transports that dispatch messages
+inline while sending them, like the local, in-memory `LocalTransport`, invoke listeners on
+the thread that has sent the message, from within the wrapper's blocking call. Such listeners
+must therefore not invoke any of the wrappers' functions themselves.
+
+Note that none of the functions may be invoked from within the context of an
+(asynchronous) Tokio runtime, because blocking the runtime's threads would prevent it from
+making progress.
+
+# Examples
+
+```rust
+use std::sync::Arc;
+use up_rust::{
+    blocking,
+    communication::CallOptions,
+    StaticUriProvider, UMessage, UStatus, UTransport,
+};
+
+// a transport that discards all messages
+struct DiscardingTransport;
+
+#[async_trait::async_trait]
+impl UTransport for DiscardingTransport {
+    async fn send(&self, _message: UMessage) -> Result<(), UStatus> {
+        Ok(())
+    }
+}
+
+let transport = Arc::new(DiscardingTransport);
+let uri_provider = Arc::new(StaticUriProvider::new("my-vehicle", 0xa34b, 0x01));
+let publisher = blocking::Publisher::new(transport, uri_provider);
+publisher
+    .publish(
+        0xb4c1,
+        CallOptions::for_publish(None, None, None),
+        None,
+    )
+    .expect("failed to publish message");
+```
+*/
+
+use std::sync::Arc;
+
+use protobuf::MessageFull;
+
+use crate::communication::{
+    self, CallOptions, InMemoryRpcClient, InMemoryRpcServer, InMemorySubscriber, NotificationError,
+    PubSubError, RegistrationError, RequestHandler, ServiceInvocationError, SimpleNotifier,
+    SimplePublisher, SubscriptionChangeHandler, UPayload,
+};
+use crate::{internal_runtime::runtime, LocalUriProvider, UListener, UTransport, UUri};
+
+/// A client for performing Remote Procedure Calls (RPC) on (other) uEntities.
+///
+/// This is the synchronous counterpart of [`communication::RpcClient`].
+pub struct RpcClient {
+    delegate: Arc<dyn communication::RpcClient>,
+}
+
+impl RpcClient {
+    /// Creates a new client based on an [`InMemoryRpcClient`].
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The uProtocol Transport Layer implementation to use for invoking service operations.
+    /// * `uri_provider` - The helper for creating URIs that represent local resources.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the generic RPC Response listener could not be
+    /// registered with the given transport.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn new(
+        transport: Arc<dyn UTransport>,
+        uri_provider: Arc<dyn LocalUriProvider>,
+    ) -> Result<Self, RegistrationError> {
+        let client = runtime().block_on(InMemoryRpcClient::new(transport, uri_provider))?;
+        Ok(Self::for_client(Arc::new(client)))
+    }
+
+    /// Creates a new client for an existing (asynchronous) client.
+    pub fn for_client(delegate: Arc<dyn communication::RpcClient>) -> Self {
+        RpcClient { delegate }
+    }
+
+    /// Invokes a method on a service and waits for the response.
+    ///
+    /// See [`communication::RpcClient::invoke_method`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn invoke_method(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        runtime().block_on(self.delegate.invoke_method(method, call_options, payload))
+    }
+
+    /// Invokes a method on a service without waiting for a response.
+    ///
+    /// See [`communication::RpcClient::invoke_no_response`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn invoke_no_response(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<(), ServiceInvocationError> {
+        runtime().block_on(
+            self.delegate
+                .invoke_no_response(method, call_options, payload),
+        )
+    }
+
+    /// Invokes a method on a service using and returning proto-generated `Message` objects.
+    ///
+    /// See the `invoke_proto_method` function of [`communication::RpcClient`] trait objects
+    /// for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn invoke_proto_method<T, R>(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        request_message: T,
+    ) -> Result<R, ServiceInvocationError>
+    where
+        T: MessageFull,
+        R: MessageFull,
+    {
+        runtime().block_on(
+            self.delegate
+                .invoke_proto_method(method, call_options, request_message),
+        )
+    }
+}
+
+/// A server for exposing Remote Procedure Call (RPC) endpoints.
+///
+/// This is the synchronous counterpart of [`communication::RpcServer`].
+pub struct RpcServer {
+    delegate: Arc<dyn communication::RpcServer + Send + Sync>,
+}
+
+impl RpcServer {
+    /// Creates a new server based on an [`InMemoryRpcServer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The uProtocol Transport Layer implementation to use for receiving requests.
+    /// * `uri_provider` - The helper for creating URIs that represent local resources.
+    pub fn new(transport: Arc<dyn UTransport>, uri_provider: Arc<dyn LocalUriProvider>) -> Self {
+        Self::for_server(Arc::new(InMemoryRpcServer::new(transport, uri_provider)))
+    }
+
+    /// Creates a new server for an existing (asynchronous) server.
+    pub fn for_server(delegate: Arc<dyn communication::RpcServer + Send + Sync>) -> Self {
+        RpcServer { delegate }
+    }
+
+    /// Registers an endpoint for RPC requests.
+    ///
+    /// See [`communication::RpcServer::register_endpoint`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn register_endpoint(
+        &self,
+        origin_filter: Option<&UUri>,
+        resource_id: u16,
+        request_handler: Arc<dyn RequestHandler>,
+    ) -> Result<(), RegistrationError> {
+        runtime().block_on(self.delegate.register_endpoint(
+            origin_filter,
+            resource_id,
+            request_handler,
+        ))
+    }
+
+    /// Unregisters an endpoint for RPC requests.
+    ///
+    /// See [`communication::RpcServer::unregister_endpoint`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn unregister_endpoint(
+        &self,
+        origin_filter: Option<&UUri>,
+        resource_id: u16,
+        request_handler: Arc<dyn RequestHandler>,
+    ) -> Result<(), RegistrationError> {
+        runtime().block_on(self.delegate.unregister_endpoint(
+            origin_filter,
+            resource_id,
+            request_handler,
+        ))
+    }
+}
+
+/// A client for publishing messages to topics.
+///
+/// This is the synchronous counterpart of [`communication::Publisher`].
+pub struct Publisher {
+    delegate: Arc<dyn communication::Publisher>,
+}
+
+impl Publisher {
+    /// Creates a new publisher based on a [`SimplePublisher`].
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to use for sending messages.
+    /// * `uri_provider` - The service to use for creating the event messages' _source_ address.
+    pub fn new(transport: Arc<dyn UTransport>, uri_provider: Arc<dyn LocalUriProvider>) -> Self {
+        Self::for_publisher(Arc::new(SimplePublisher::new(transport, uri_provider)))
+    }
+
+    /// Creates a new publisher for an existing (asynchronous) publisher.
+    pub fn for_publisher(delegate: Arc<dyn communication::Publisher>) -> Self {
+        Publisher { delegate }
+    }
+
+    /// Publishes a message to a topic.
+    ///
+    /// See [`communication::Publisher::publish`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn publish(
+        &self,
+        resource_id: u16,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<(), PubSubError> {
+        runtime().block_on(self.delegate.publish(resource_id, call_options, payload))
+    }
+}
+
+/// A client for subscribing to topics.
+///
+/// This is the synchronous counterpart of [`communication::Subscriber`].
+pub struct Subscriber {
+    delegate: Arc<dyn communication::Subscriber>,
+}
+
+impl Subscriber {
+    /// Creates a new subscriber based on an [`InMemorySubscriber`].
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to use for interacting with the USubscription service and
+    ///                 for receiving events.
+    /// * `uri_provider` - The service to use for creating topic addresses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Notifier cannot register a listener for notifications from the USubscription service.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn new(
+        transport: Arc<dyn UTransport>,
+        uri_provider: Arc<dyn LocalUriProvider>,
+    ) -> Result<Self, RegistrationError> {
+        let subscriber = runtime().block_on(InMemorySubscriber::new(transport, uri_provider))?;
+        Ok(Self::for_subscriber(Arc::new(subscriber)))
+    }
+
+    /// Creates a new subscriber for an existing (asynchronous) subscriber.
+    pub fn for_subscriber(delegate: Arc<dyn communication::Subscriber>) -> Self {
+        Subscriber { delegate }
+    }
+
+    /// Registers a handler to invoke for messages that have been published to a given topic.
+    ///
+    /// See [`communication::Subscriber::subscribe`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn subscribe(
+        &self,
+        topic: &UUri,
+        handler: Arc<dyn UListener>,
+        subscription_change_handler: Option<Arc<dyn SubscriptionChangeHandler>>,
+    ) -> Result<(), RegistrationError> {
+        runtime().block_on(
+            self.delegate
+                .subscribe(topic, handler, subscription_change_handler),
+        )
+    }
+
+    /// Unregisters a previously [registered handler](`Self::subscribe`).
+    ///
+    /// See [`communication::Subscriber::unsubscribe`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn unsubscribe(
+        &self,
+        topic: &UUri,
+        handler: Arc<dyn UListener>,
+    ) -> Result<(), RegistrationError> {
+        runtime().block_on(self.delegate.unsubscribe(topic, handler))
+    }
+}
+
+/// A client for sending notifications to and receiving notifications from other uEntities.
+///
+/// This is the synchronous counterpart of [`communication::Notifier`].
+pub struct Notifier {
+    delegate: Arc<dyn communication::Notifier>,
+}
+
+impl Notifier {
+    /// Creates a new notifier based on a [`SimpleNotifier`].
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to use for sending and receiving notifications.
+    /// * `uri_provider` - The service to use for creating the notifications' _source_ address.
+    pub fn new(transport: Arc<dyn UTransport>, uri_provider: Arc<dyn LocalUriProvider>) -> Self {
+        Self::for_notifier(Arc::new(SimpleNotifier::new(transport, uri_provider)))
+    }
+
+    /// Creates a new notifier for an existing (asynchronous) notifier.
+    pub fn for_notifier(delegate: Arc<dyn communication::Notifier>) -> Self {
+        Notifier { delegate }
+    }
+
+    /// Sends a notification to a uEntity.
+    ///
+    /// See [`communication::Notifier::notify`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn notify(
+        &self,
+        resource_id: u16,
+        destination: &UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<(), NotificationError> {
+        runtime().block_on(
+            self.delegate
+                .notify(resource_id, destination, call_options, payload),
+        )
+    }
+
+    /// Starts listening to a notification topic.
+    ///
+    /// See [`communication::Notifier::start_listening`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn start_listening(
+        &self,
+        topic: &UUri,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), RegistrationError> {
+        runtime().block_on(self.delegate.start_listening(topic, listener))
+    }
+
+    /// Unregisters a previously [registered handler](`Self::start_listening`).
+    ///
+    /// See [`communication::Notifier::stop_listening`] for details.
+    ///
+    /// # Panics
+    ///
+    /// if invoked from within the context of a Tokio runtime.
+    pub fn stop_listening(
+        &self,
+        topic: &UUri,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), RegistrationError> {
+        runtime().block_on(self.delegate.stop_listening(topic, listener))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use communication::{MockNotifier, MockRpcClient};
+    use protobuf::well_known_types::wrappers::StringValue;
+
+    #[test]
+    fn test_invoke_proto_method_blocks_until_response_is_available() {
+        // GIVEN an RPC client that responds after some time
+        let mut delegate = MockRpcClient::new();
+        delegate.expect_invoke_method().once().returning(|_, _, _| {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            let response = StringValue {
+                value: "pong".to_string(),
+                ..Default::default()
+            };
+            Ok(Some(UPayload::try_from_protobuf(response).unwrap()))
+        });
+        let client = RpcClient::for_client(Arc::new(delegate));
+
+        // WHEN invoking a method from a synchronous context
+        let request = StringValue {
+            value: "ping".to_string(),
+            ..Default::default()
+        };
+        let response: StringValue = client
+            .invoke_proto_method(
+                UUri::try_from_parts("other", 0x1000, 0x01, 0x0001).unwrap(),
+                CallOptions::for_rpc_request(5_000, None, None, None),
+                request,
+            )
+            .expect("invocation should succeed");
+
+        // THEN the response is returned to the caller
+        assert_eq!(response.value, "pong");
+    }
+
+    #[test]
+    fn test_notify_propagates_error() {
+        let mut delegate = MockNotifier::new();
+        delegate
+            .expect_notify()
+            .once()
+            .returning(|_, _, _, _| Err(NotificationError::InvalidArgument("test".to_string())));
+        let notifier = Notifier::for_notifier(Arc::new(delegate));
+
+        let result = notifier.notify(
+            0xa1b2,
+            &UUri::try_from_parts("other", 0x1000, 0x01, 0x0000).unwrap(),
+            CallOptions::for_notification(None, None, None),
+            None,
+        );
+        assert!(result.is_err_and(|e| matches!(e, NotificationError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,443 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides means to record uProtocol messages to and replay them from capture files.
+
+# File format
+
+A capture file consists of a header followed by an arbitrary number of records.
+All integers are encoded in big endian byte order.
+
+The header consists of 8 bytes:
+
+| Offset | Length | Content                                        |
+|--------|--------|------------------------------------------------|
+| 0      | 5      | the magic bytes `UPCAP` (ASCII)                |
+| 5      | 1      | the format version, currently `1`              |
+| 6      | 2      | reserved, must be `0`                          |
+
+Each record consists of the capture metadata and the length delimited message:
+
+| Offset | Length | Content                                                            |
+|--------|--------|--------------------------------------------------------------------|
+| 0      | 8      | the point in time at which the message has been captured (milliseconds since UNIX epoch) |
+| 8      | 1      | the [direction](`CaptureDirection`) of the message                 |
+| 9      | 4      | the length `n` of the message                                      |
+| 13     | n      | the message's protobuf encoding                                    |
+
+# Recording
+
+A [`RecordingTransport`] writes all messages that are sent and received via an underlying transport
+to a capture. Recording can be enabled, disabled and restricted to particular addresses at runtime.
+If the `rpc-server` feature is enabled, a [`CaptureControlHandler`] can be registered with an RPC server,
+which allows privileged peers to change these settings remotely, e.g. for capturing the traffic of
+a misbehaving ECU without redeploying it.
+*/
+
+use std::io::{ErrorKind, Read, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use protobuf::Message;
+
+use crate::{UMessage, UUri};
+
+#[cfg(feature = "rpc-server")]
+pub use control::{CaptureControlHandler, CAPTURE_CONTROL_RESOURCE_ID};
+pub use recording_transport::{CaptureSettings, RecordingTransport};
+
+#[cfg(feature = "rpc-server")]
+mod control;
+mod recording_transport;
+
+const MAGIC: &[u8; 5] = b"UPCAP";
+const FORMAT_VERSION: u8 = 1;
+// protects readers from allocating huge buffers for corrupted records
+const MAX_MESSAGE_LENGTH: usize = 16 * 1024 * 1024;
+
+/// The direction in which a captured message has been transferred.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaptureDirection {
+    /// The direction is unknown, e.g. because the message has been captured by a passive observer.
+    #[default]
+    Unknown,
+    /// The message has been received by the capturing uEntity.
+    Received,
+    /// The message has been sent by the capturing uEntity.
+    Sent,
+}
+
+impl CaptureDirection {
+    fn to_byte(self) -> u8 {
+        match self {
+            CaptureDirection::Unknown => 0,
+            CaptureDirection::Received => 1,
+            CaptureDirection::Sent => 2,
+        }
+    }
+
+    fn from_byte(value: u8) -> std::io::Result<Self> {
+        match value {
+            0 => Ok(CaptureDirection::Unknown),
+            1 => Ok(CaptureDirection::Received),
+            2 => Ok(CaptureDirection::Sent),
+            _ => Err(invalid_data("unknown capture direction")),
+        }
+    }
+}
+
+/// A message that has been captured.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaptureRecord {
+    /// The point in time at which the message has been captured.
+    pub timestamp: SystemTime,
+    /// The direction in which the message has been transferred.
+    pub direction: CaptureDirection,
+    /// The captured message.
+    pub message: UMessage,
+}
+
+fn invalid_data(msg: &str) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+// checks if a message's source and sink addresses match the given patterns (if any)
+fn matches_address_filters(
+    message: &UMessage,
+    source_filter: Option<&UUri>,
+    sink_filter: Option<&UUri>,
+) -> bool {
+    let attributes = message.attributes.as_ref();
+    if let Some(pattern) = source_filter {
+        if !attributes
+            .and_then(|attribs| attribs.source.as_ref())
+            .is_some_and(|source| pattern.matches(source))
+        {
+            return false;
+        }
+    }
+    if let Some(pattern) = sink_filter {
+        if !attributes
+            .and_then(|attribs| attribs.sink.as_ref())
+            .is_some_and(|sink| pattern.matches(sink))
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// Writes captured messages to a sink, e.g. a file.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{
+///     capture::{CaptureDirection, CaptureReader, CaptureWriter},
+///     UMessageBuilder, UUri,
+/// };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let message = UMessageBuilder::publish(UUri::try_from("//my-vehicle/4210/1/B24D")?).build()?;
+/// let mut writer = CaptureWriter::new(Vec::new())?;
+/// writer.write_message(CaptureDirection::Sent, &message)?;
+///
+/// let capture = writer.into_inner();
+/// let mut reader = CaptureReader::new(capture.as_slice())?;
+/// assert_eq!(reader.next().unwrap()?.message, message);
+/// assert!(reader.next().is_none());
+/// # Ok(())
+/// # }
+/// ```
+pub struct CaptureWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Creates a new writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capture file header cannot be written to the given sink.
+    pub fn new(mut writer: W) -> std::io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION, 0, 0])?;
+        Ok(CaptureWriter { writer })
+    }
+
+    /// Writes a record.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be serialized or written to the underlying sink.
+    pub fn write_record(&mut self, record: &CaptureRecord) -> std::io::Result<()> {
+        let timestamp = record
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "capture timestamp lies before UNIX epoch",
+                )
+            })?
+            .as_millis() as u64;
+        let message = record
+            .message
+            .write_to_bytes()
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidInput, e))?;
+        if message.len() > MAX_MESSAGE_LENGTH {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "message exceeds maximum length",
+            ));
+        }
+        self.writer.write_all(&timestamp.to_be_bytes())?;
+        self.writer.write_all(&[record.direction.to_byte()])?;
+        self.writer
+            .write_all(&(message.len() as u32).to_be_bytes())?;
+        self.writer.write_all(&message)
+    }
+
+    /// Writes a message that has been captured just now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be serialized or written to the underlying sink.
+    pub fn write_message(
+        &mut self,
+        direction: CaptureDirection,
+        message: &UMessage,
+    ) -> std::io::Result<()> {
+        self.write_record(&CaptureRecord {
+            timestamp: SystemTime::now(),
+            direction,
+            message: message.to_owned(),
+        })
+    }
+
+    /// Flushes the underlying sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying sink cannot be flushed.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Gets the underlying sink.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads captured messages from a source, e.g. a file.
+///
+/// The reader is an iterator over the records contained in the capture. Records can be filtered by
+/// the source and sink addresses of the captured messages and by the point in time at which they
+/// have been captured.
+pub struct CaptureReader<R: Read> {
+    reader: R,
+    source_filter: Option<UUri>,
+    sink_filter: Option<UUri>,
+    not_before: Option<SystemTime>,
+    not_after: Option<SystemTime>,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Creates a new reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capture file header cannot be read or if the format
+    /// version is not supported.
+    pub fn new(mut reader: R) -> std::io::Result<Self> {
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        if &header[..5] != MAGIC {
+            return Err(invalid_data("not a uProtocol capture"));
+        }
+        if header[5] != FORMAT_VERSION {
+            return Err(invalid_data("unsupported capture format version"));
+        }
+        Ok(CaptureReader {
+            reader,
+            source_filter: None,
+            sink_filter: None,
+            not_before: None,
+            not_after: None,
+        })
+    }
+
+    /// Only yields messages whose source address matches a given pattern.
+    pub fn with_source_filter(mut self, pattern: UUri) -> Self {
+        self.source_filter = Some(pattern);
+        self
+    }
+
+    /// Only yields messages that have a sink address which matches a given pattern.
+    pub fn with_sink_filter(mut self, pattern: UUri) -> Self {
+        self.sink_filter = Some(pattern);
+        self
+    }
+
+    /// Only yields messages that have been captured within a given time range.
+    ///
+    /// # Arguments
+    ///
+    /// * `not_before` - The earliest capture time (inclusive), or `None` if unbounded.
+    /// * `not_after` - The latest capture time (inclusive), or `None` if unbounded.
+    pub fn with_time_range(
+        mut self,
+        not_before: Option<SystemTime>,
+        not_after: Option<SystemTime>,
+    ) -> Self {
+        self.not_before = not_before;
+        self.not_after = not_after;
+        self
+    }
+
+    fn matches(&self, record: &CaptureRecord) -> bool {
+        if self.not_before.is_some_and(|t| record.timestamp < t)
+            || self.not_after.is_some_and(|t| record.timestamp > t)
+        {
+            return false;
+        }
+        matches_address_filters(
+            &record.message,
+            self.source_filter.as_ref(),
+            self.sink_filter.as_ref(),
+        )
+    }
+
+    // returns None if the end of the capture has been reached
+    fn read_record(&mut self) -> std::io::Result<Option<CaptureRecord>> {
+        let mut timestamp = [0u8; 8];
+        match self.reader.read_exact(&mut timestamp) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let mut direction = [0u8; 1];
+        self.reader.read_exact(&mut direction)?;
+        let mut length = [0u8; 4];
+        self.reader.read_exact(&mut length)?;
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_MESSAGE_LENGTH {
+            return Err(invalid_data("message exceeds maximum length"));
+        }
+        let mut message = vec![0u8; length];
+        self.reader.read_exact(&mut message)?;
+        let message = UMessage::parse_from_bytes(&message)
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        Ok(Some(CaptureRecord {
+            timestamp: UNIX_EPOCH + Duration::from_millis(u64::from_be_bytes(timestamp)),
+            direction: CaptureDirection::from_byte(direction[0])?,
+            message,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = std::io::Result<CaptureRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.read_record() {
+                Ok(Some(record)) if !self.matches(&record) => continue,
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::UMessageBuilder;
+
+    fn record(topic: &str, millis: u64) -> CaptureRecord {
+        CaptureRecord {
+            timestamp: UNIX_EPOCH + Duration::from_millis(millis),
+            direction: CaptureDirection::Received,
+            message: UMessageBuilder::publish(UUri::try_from(topic).unwrap())
+                .build()
+                .unwrap(),
+        }
+    }
+
+    fn capture(records: &[CaptureRecord]) -> Vec<u8> {
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        records
+            .iter()
+            .for_each(|record| writer.write_record(record).unwrap());
+        writer.into_inner()
+    }
+
+    #[test]
+    fn test_reader_yields_written_records() {
+        let records = vec![
+            record("//vehicle/A/1/8001", 1_000),
+            record("//vehicle/B/1/8001", 2_000),
+        ];
+        let capture = capture(&records);
+
+        let read_records = CaptureReader::new(capture.as_slice())
+            .unwrap()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(read_records, records);
+    }
+
+    #[test]
+    fn test_reader_applies_filters() {
+        // GIVEN a capture containing messages from different sources and points in time
+        let records = vec![
+            record("//vehicle/A/1/8001", 1_000),
+            record("//vehicle/B/1/8001", 2_000),
+            record("//vehicle/A/1/8002", 3_000),
+            record("//vehicle/A/1/8003", 4_000),
+        ];
+        let capture = capture(&records);
+
+        // WHEN reading the messages published by uEntity A within a given time range
+        let read_records = CaptureReader::new(capture.as_slice())
+            .unwrap()
+            .with_source_filter(UUri::try_from("//vehicle/A/1/FFFF").unwrap())
+            .with_time_range(
+                Some(UNIX_EPOCH + Duration::from_millis(1_500)),
+                Some(UNIX_EPOCH + Duration::from_millis(3_000)),
+            )
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+
+        // THEN only the matching message is yielded
+        assert_eq!(read_records, vec![records[2].clone()]);
+    }
+
+    #[test]
+    fn test_reader_rejects_invalid_capture() {
+        assert!(CaptureReader::new(b"PCAP\x01\0\0\0".as_slice())
+            .is_err_and(|e| e.kind() == ErrorKind::InvalidData));
+
+        // a truncated record
+        let mut capture = capture(&[record("//vehicle/A/1/8001", 1_000)]);
+        capture.truncate(capture.len() - 1);
+        let mut reader = CaptureReader::new(capture.as_slice()).unwrap();
+        assert!(reader
+            .next()
+            .is_some_and(|result| result.is_err_and(|e| e.kind() == ErrorKind::UnexpectedEof)));
+    }
+}
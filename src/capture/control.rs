@@ -0,0 +1,302 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use protobuf::well_known_types::struct_::{value::Kind, Struct, Value};
+use tracing::{debug, info};
+
+use crate::{
+    communication::{RequestHandler, ServiceInvocationError, UPayload},
+    UAttributes, UUri,
+};
+
+use super::{CaptureSettings, RecordingTransport};
+
+/// The resource ID of the method for controlling a [`RecordingTransport`] at runtime.
+pub const CAPTURE_CONTROL_RESOURCE_ID: u16 = crate::wellknown::CAPTURE_CONTROL_RESOURCE_ID;
+
+const FIELD_ENABLED: &str = "enabled";
+const FIELD_SOURCE_FILTER: &str = "source_filter";
+const FIELD_SINK_FILTER: &str = "sink_filter";
+
+/// A [`RequestHandler`] that lets privileged peers change the [settings](`CaptureSettings`)
+/// of a [`RecordingTransport`] at runtime.
+///
+/// The handler is not registered automatically. Applications that want to expose the capture
+/// settings need to explicitly register it with their RPC server, usually using the
+/// [well-known resource ID](`CAPTURE_CONTROL_RESOURCE_ID`).
+///
+/// Requests are only accepted from uEntities whose URI matches one of the authorized peer patterns.
+/// Requests from other uEntities are rejected with [`ServiceInvocationError::PermissionDenied`].
+///
+/// The request payload is an (optional) protobuf `Struct` containing any of the following fields:
+///
+/// * `enabled` - A boolean indicating whether messages should be recorded.
+/// * `source_filter` - A UUri pattern (in string form) that the source of recorded messages needs to match.
+///   An empty string removes the filter.
+/// * `sink_filter` - A UUri pattern (in string form) that the sink of recorded messages needs to match.
+///   An empty string removes the filter.
+///
+/// Fields that are not set retain their current value. A request without payload does not change
+/// the settings at all. The response payload is a `Struct` containing the resulting settings.
+///
+/// The recorded messages are flushed to the underlying sink when recording gets disabled.
+pub struct CaptureControlHandler {
+    recording_transport: Arc<RecordingTransport>,
+    authorized_peers: Vec<UUri>,
+}
+
+impl CaptureControlHandler {
+    /// Creates a new handler for a recording transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `recording_transport` - The transport to control.
+    /// * `authorized_peers` - The patterns that the URIs of uEntities need to match in order to be
+    ///                        allowed to change the settings.
+    pub fn new(recording_transport: Arc<RecordingTransport>, authorized_peers: Vec<UUri>) -> Self {
+        CaptureControlHandler {
+            recording_transport,
+            authorized_peers,
+        }
+    }
+
+    fn is_authorized(&self, peer: &UUri) -> bool {
+        self.authorized_peers
+            .iter()
+            .any(|pattern| pattern.matches(peer))
+    }
+}
+
+fn parse_filter(request: &Struct, field_name: &str) -> Result<Option<Option<UUri>>, String> {
+    match request.fields.get(field_name).and_then(|v| v.kind.as_ref()) {
+        None => Ok(None),
+        Some(Kind::StringValue(uri)) if uri.is_empty() => Ok(Some(None)),
+        Some(Kind::StringValue(uri)) => UUri::try_from(uri.as_str())
+            .map(|pattern| Some(Some(pattern)))
+            .map_err(|e| format!("{field_name} is not a valid UUri: {e}")),
+        Some(_) => Err(format!("{field_name} must be a string")),
+    }
+}
+
+fn apply_request(settings: &mut CaptureSettings, request: &Struct) -> Result<(), String> {
+    match request
+        .fields
+        .get(FIELD_ENABLED)
+        .and_then(|v| v.kind.as_ref())
+    {
+        None => {}
+        Some(Kind::BoolValue(enabled)) => settings.enabled = *enabled,
+        Some(_) => return Err(format!("{FIELD_ENABLED} must be a boolean")),
+    }
+    if let Some(source_filter) = parse_filter(request, FIELD_SOURCE_FILTER)? {
+        settings.source_filter = source_filter;
+    }
+    if let Some(sink_filter) = parse_filter(request, FIELD_SINK_FILTER)? {
+        settings.sink_filter = sink_filter;
+    }
+    Ok(())
+}
+
+fn settings_to_struct(settings: &CaptureSettings) -> Struct {
+    let string_value = |uri: &Option<UUri>| Value {
+        kind: Some(Kind::StringValue(
+            uri.as_ref().map(String::from).unwrap_or_default(),
+        )),
+        ..Default::default()
+    };
+    let mut response = Struct::new();
+    response.fields.insert(
+        FIELD_ENABLED.to_string(),
+        Value {
+            kind: Some(Kind::BoolValue(settings.enabled)),
+            ..Default::default()
+        },
+    );
+    response.fields.insert(
+        FIELD_SOURCE_FILTER.to_string(),
+        string_value(&settings.source_filter),
+    );
+    response.fields.insert(
+        FIELD_SINK_FILTER.to_string(),
+        string_value(&settings.sink_filter),
+    );
+    response
+}
+
+#[async_trait]
+impl RequestHandler for CaptureControlHandler {
+    async fn handle_request(
+        &self,
+        _resource_id: u16,
+        message_attributes: &UAttributes,
+        request_payload: Option<UPayload>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        let Some(peer) = message_attributes.source.as_ref() else {
+            return Err(ServiceInvocationError::InvalidArgument(
+                "request has no source".to_string(),
+            ));
+        };
+        if !self.is_authorized(peer) {
+            debug!(
+                peer = %peer.to_uri(false),
+                "rejecting capture control request from unauthorized peer"
+            );
+            return Err(ServiceInvocationError::PermissionDenied(
+                "peer is not authorized to control protocol capture".to_string(),
+            ));
+        }
+
+        let mut settings = self.recording_transport.settings();
+        if let Some(payload) = request_payload {
+            let request = payload.extract_protobuf::<Struct>().map_err(|_e| {
+                ServiceInvocationError::InvalidArgument(
+                    "request payload is not a google.protobuf.Struct".to_string(),
+                )
+            })?;
+            apply_request(&mut settings, &request)
+                .map_err(ServiceInvocationError::InvalidArgument)?;
+            info!(
+                peer = %peer.to_uri(false),
+                enabled = settings.enabled,
+                "changing protocol capture settings"
+            );
+            self.recording_transport.set_settings(settings.clone());
+            if !settings.enabled {
+                if let Err(e) = self.recording_transport.flush() {
+                    debug!("failed to flush protocol capture: {}", e);
+                }
+            }
+        }
+
+        UPayload::try_from_protobuf(settings_to_struct(&settings))
+            .map(Some)
+            .map_err(|e| ServiceInvocationError::Internal(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utransport::MockTransport;
+
+    fn new_handler() -> (Arc<RecordingTransport>, CaptureControlHandler) {
+        let transport =
+            Arc::new(RecordingTransport::new(Arc::new(MockTransport::new()), Vec::new()).unwrap());
+        let handler = CaptureControlHandler::new(
+            transport.clone(),
+            vec![UUri::try_from("//diagnostics/D100/1/0").unwrap()],
+        );
+        (transport, handler)
+    }
+
+    fn request_attributes(source: &str) -> UAttributes {
+        UAttributes {
+            source: Some(UUri::try_from(source).unwrap()).into(),
+            ..Default::default()
+        }
+    }
+
+    fn string_value(value: &str) -> Value {
+        Value {
+            kind: Some(Kind::StringValue(value.to_string())),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_updates_settings() {
+        // GIVEN a recording transport that records all messages
+        let (transport, handler) = new_handler();
+
+        // WHEN an authorized peer disables recording and sets a source filter
+        let mut request = Struct::new();
+        request.fields.insert(
+            FIELD_ENABLED.to_string(),
+            Value {
+                kind: Some(Kind::BoolValue(false)),
+                ..Default::default()
+            },
+        );
+        request.fields.insert(
+            FIELD_SOURCE_FILTER.to_string(),
+            string_value("//vehicle/A100/1/FFFF"),
+        );
+        let response = handler
+            .handle_request(
+                CAPTURE_CONTROL_RESOURCE_ID,
+                &request_attributes("//diagnostics/D100/1/0"),
+                Some(UPayload::try_from_protobuf(request).unwrap()),
+            )
+            .await
+            .expect("request should have been processed successfully")
+            .expect("response should contain payload");
+
+        // THEN the transport's settings have been changed accordingly
+        let expected_settings = CaptureSettings {
+            enabled: false,
+            source_filter: Some(UUri::try_from("//vehicle/A100/1/FFFF").unwrap()),
+            sink_filter: None,
+        };
+        assert_eq!(transport.settings(), expected_settings);
+        // and the response contains the resulting settings
+        assert_eq!(
+            response.extract_protobuf::<Struct>().unwrap(),
+            settings_to_struct(&expected_settings)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_rejects_unauthorized_peer() {
+        let (transport, handler) = new_handler();
+        let mut request = Struct::new();
+        request.fields.insert(
+            FIELD_SINK_FILTER.to_string(),
+            string_value("//vehicle/A100/1/0"),
+        );
+
+        let result = handler
+            .handle_request(
+                CAPTURE_CONTROL_RESOURCE_ID,
+                &request_attributes("//other/B100/1/0"),
+                Some(UPayload::try_from_protobuf(request).unwrap()),
+            )
+            .await;
+
+        assert!(result.is_err_and(|e| matches!(e, ServiceInvocationError::PermissionDenied(_))));
+        assert_eq!(transport.settings(), CaptureSettings::default());
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_rejects_invalid_filter() {
+        let (transport, handler) = new_handler();
+        let mut request = Struct::new();
+        request.fields.insert(
+            FIELD_SOURCE_FILTER.to_string(),
+            string_value("not a valid uri"),
+        );
+
+        let result = handler
+            .handle_request(
+                CAPTURE_CONTROL_RESOURCE_ID,
+                &request_attributes("//diagnostics/D100/1/0"),
+                Some(UPayload::try_from_protobuf(request).unwrap()),
+            )
+            .await;
+
+        assert!(result.is_err_and(|e| matches!(e, ServiceInvocationError::InvalidArgument(_))));
+        assert_eq!(transport.settings(), CaptureSettings::default());
+    }
+}
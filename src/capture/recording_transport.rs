@@ -0,0 +1,355 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::{ComparableListener, UCode, UListener, UMessage, UStatus, UTransport, UUri};
+
+use super::{matches_address_filters, CaptureDirection, CaptureWriter};
+
+/// Determines which messages a [`RecordingTransport`] records.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaptureSettings {
+    /// Indicates whether messages are being recorded at all.
+    pub enabled: bool,
+    /// The pattern that the source address of recorded messages needs to match, any source if not set.
+    pub source_filter: Option<UUri>,
+    /// The pattern that the sink address of recorded messages needs to match, any sink if not set.
+    ///
+    /// Messages without a sink address, i.e. Publish messages, do not match any pattern.
+    pub sink_filter: Option<UUri>,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        CaptureSettings {
+            enabled: true,
+            source_filter: None,
+            sink_filter: None,
+        }
+    }
+}
+
+struct Recorder {
+    writer: Mutex<CaptureWriter<Box<dyn Write + Send>>>,
+    settings: RwLock<CaptureSettings>,
+}
+
+impl Recorder {
+    fn record(&self, direction: CaptureDirection, message: &UMessage) {
+        let Ok(settings) = self.settings.read() else {
+            return;
+        };
+        if !settings.enabled
+            || !matches_address_filters(
+                message,
+                settings.source_filter.as_ref(),
+                settings.sink_filter.as_ref(),
+            )
+        {
+            return;
+        }
+        if let Err(e) = self
+            .writer
+            .lock()
+            .map_err(|_e| std::io::Error::other("capture writer is poisoned"))
+            .and_then(|mut writer| writer.write_message(direction, message))
+        {
+            debug!("failed to record message: {}", e);
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Hash)]
+struct Registration {
+    source_filter: UUri,
+    sink_filter: Option<UUri>,
+    listener: ComparableListener,
+}
+
+/// A [`UListener`] which records received messages before passing them on.
+struct RecordingListener {
+    recorder: Arc<Recorder>,
+    listener: Arc<dyn UListener>,
+}
+
+#[async_trait]
+impl UListener for RecordingListener {
+    async fn on_receive(&self, msg: UMessage) {
+        self.recorder.record(CaptureDirection::Received, &msg);
+        self.listener.on_receive(msg).await;
+    }
+}
+
+/// A transport that records all messages being exchanged via an underlying transport.
+///
+/// Messages are written to a capture by means of a [`CaptureWriter`]. Messages being sent are
+/// recorded before they are handed over to the underlying transport, regardless of whether sending
+/// succeeds. Received messages are recorded before they are passed on to registered listeners.
+/// Failure to record a message does not affect the exchange of the message.
+///
+/// The [settings](`CaptureSettings`) can be changed at any time.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use up_rust::{
+///     capture::{CaptureSettings, RecordingTransport},
+///     local_transport::LocalTransport,
+///     UUri,
+/// };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let transport = RecordingTransport::new(Arc::new(LocalTransport::default()), Vec::new())?;
+/// // only record messages originating from a particular uEntity
+/// transport.set_settings(CaptureSettings {
+///     source_filter: Some(UUri::try_from("//*/A100/1/FFFF")?),
+///     ..Default::default()
+/// });
+/// assert!(transport.settings().enabled);
+/// # Ok(())
+/// # }
+/// ```
+pub struct RecordingTransport {
+    transport: Arc<dyn UTransport>,
+    recorder: Arc<Recorder>,
+    // registered listener -> recording listener registered with underlying transport
+    registrations: Mutex<HashMap<Registration, Arc<dyn UListener>>>,
+}
+
+impl RecordingTransport {
+    /// Creates a new transport for a given underlying transport.
+    ///
+    /// Recording is enabled for all messages initially.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to delegate to.
+    /// * `writer` - The sink to write the capture to, e.g. a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capture file header cannot be written to the given sink.
+    pub fn new<W: Write + Send + 'static>(
+        transport: Arc<dyn UTransport>,
+        writer: W,
+    ) -> std::io::Result<Self> {
+        let writer: Box<dyn Write + Send> = Box::new(writer);
+        Ok(RecordingTransport {
+            transport,
+            recorder: Arc::new(Recorder {
+                writer: Mutex::new(CaptureWriter::new(writer)?),
+                settings: RwLock::new(CaptureSettings::default()),
+            }),
+            registrations: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Sets the initial settings.
+    pub fn with_settings(self, settings: CaptureSettings) -> Self {
+        self.set_settings(settings);
+        self
+    }
+
+    /// Gets the current settings.
+    pub fn settings(&self) -> CaptureSettings {
+        self.recorder
+            .settings
+            .read()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    /// Changes the settings.
+    ///
+    /// The new settings apply to all messages being sent or received from now on.
+    pub fn set_settings(&self, settings: CaptureSettings) {
+        if let Ok(mut current_settings) = self.recorder.settings.write() {
+            *current_settings = settings;
+        }
+    }
+
+    /// Flushes the messages recorded so far to the underlying sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying sink cannot be flushed.
+    pub fn flush(&self) -> std::io::Result<()> {
+        self.recorder
+            .writer
+            .lock()
+            .map_err(|_e| std::io::Error::other("capture writer is poisoned"))
+            .and_then(|mut writer| writer.flush())
+    }
+}
+
+#[async_trait]
+impl UTransport for RecordingTransport {
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        self.recorder.record(CaptureDirection::Sent, &message);
+        self.transport.send(message).await
+    }
+
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        for message in &messages {
+            self.recorder.record(CaptureDirection::Sent, message);
+        }
+        self.transport.send_batch(messages).await
+    }
+
+    async fn receive(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Result<UMessage, UStatus> {
+        let message = self.transport.receive(source_filter, sink_filter).await?;
+        self.recorder.record(CaptureDirection::Received, &message);
+        Ok(message)
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let registration = Registration {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.cloned(),
+            listener: ComparableListener::new(listener.clone()),
+        };
+        let recording_listener = self
+            .registrations
+            .lock()
+            .ok()
+            .and_then(|registrations| registrations.get(&registration).cloned())
+            .unwrap_or_else(|| {
+                Arc::new(RecordingListener {
+                    recorder: self.recorder.clone(),
+                    listener,
+                })
+            });
+        self.transport
+            .register_listener(source_filter, sink_filter, recording_listener.clone())
+            .await?;
+        if let Ok(mut registrations) = self.registrations.lock() {
+            registrations.insert(registration, recording_listener);
+        }
+        Ok(())
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let registration = Registration {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.cloned(),
+            listener: ComparableListener::new(listener),
+        };
+        let Some(recording_listener) = self
+            .registrations
+            .lock()
+            .ok()
+            .and_then(|registrations| registrations.get(&registration).cloned())
+        else {
+            return Err(UStatus::fail_with_code(
+                UCode::NOT_FOUND,
+                "no such listener registered",
+            ));
+        };
+        self.transport
+            .unregister_listener(source_filter, sink_filter, recording_listener)
+            .await?;
+        if let Ok(mut registrations) = self.registrations.lock() {
+            registrations.remove(&registration);
+        }
+        Ok(())
+    }
+
+    async fn probe(&self, peer_authority: &str) -> Result<(), UStatus> {
+        self.transport.probe(peer_authority).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture::CaptureReader, utransport::MockTransport, UMessageBuilder};
+
+    // a sink which can be inspected while it is being written to
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_records_messages_matching_settings() {
+        let mut underlying_transport = MockTransport::new();
+        underlying_transport
+            .expect_do_send()
+            .times(3)
+            .returning(|_msg| Ok(()));
+        let buffer = SharedBuffer::default();
+        let transport =
+            RecordingTransport::new(Arc::new(underlying_transport), buffer.clone()).unwrap();
+        let message_a = UMessageBuilder::publish(UUri::try_from("//vehicle/A/1/8001").unwrap())
+            .build()
+            .unwrap();
+        let message_b = UMessageBuilder::publish(UUri::try_from("//vehicle/B/1/8001").unwrap())
+            .build()
+            .unwrap();
+
+        // WHEN restricting recording to messages from uEntity A
+        transport.set_settings(CaptureSettings {
+            source_filter: Some(UUri::try_from("//vehicle/A/1/FFFF").unwrap()),
+            ..Default::default()
+        });
+        transport.send(message_a.clone()).await.unwrap();
+        transport.send(message_b.clone()).await.unwrap();
+        // and disabling recording altogether afterwards
+        transport.set_settings(CaptureSettings {
+            enabled: false,
+            ..Default::default()
+        });
+        transport.send(message_a.clone()).await.unwrap();
+
+        // THEN only the first message has been recorded
+        let capture = buffer.0.lock().unwrap().clone();
+        let records = CaptureReader::new(capture.as_slice())
+            .unwrap()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].direction, CaptureDirection::Sent);
+        assert_eq!(records[0].message, message_a);
+    }
+}
@@ -0,0 +1,266 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a UTransport which exchanges messages by means of channels.
+
+The [`ChannelTransport`] can be used in tests for connecting two communication stacks
+running in the same process, e.g. an RPC client stack and an RPC server stack, while
+being able to inspect and manipulate the messages being exchanged between them.
+*/
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::{
+    task_tracker::TaskTracker,
+    utransport::scaffold::{self, ListenerRegistry},
+    UListener, UMessage, UStatus, UTransport, UUri,
+};
+
+/// The far end of the channels that a [`ChannelTransport`] uses for exchanging messages.
+pub struct ChannelEndpoint {
+    /// The sender to use for delivering messages to the listeners registered with the transport.
+    pub sender: UnboundedSender<UMessage>,
+    /// The receiver to use for consuming the messages sent via the transport.
+    pub receiver: UnboundedReceiver<UMessage>,
+}
+
+/// A [`UTransport`] that sends messages to a channel and dispatches the messages received
+/// from another channel to the registered listeners.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{channel_transport::ChannelTransport, UMessageBuilder, UTransport, UUri};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (transport, mut endpoint) = ChannelTransport::new();
+/// let topic = UUri::try_from("//my-vehicle/D45/1/A001").unwrap();
+/// let message = UMessageBuilder::publish(topic).build().unwrap();
+///
+/// transport.send(message.clone()).await.unwrap();
+/// assert_eq!(endpoint.receiver.recv().await, Some(message));
+/// # }
+/// ```
+pub struct ChannelTransport {
+    outgoing: UnboundedSender<UMessage>,
+    listeners: Arc<ListenerRegistry>,
+    tasks: TaskTracker,
+}
+
+impl ChannelTransport {
+    /// Creates a new transport using a given pair of channels.
+    ///
+    /// Messages received from the incoming channel are dispatched to the matching listeners by
+    /// a task that runs until all senders of the incoming channel have been dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `outgoing` - The channel to send messages to.
+    /// * `incoming` - The channel to receive messages from.
+    ///
+    /// # Panics
+    ///
+    /// if not called from within the context of a Tokio runtime.
+    pub fn from_channels(
+        outgoing: UnboundedSender<UMessage>,
+        incoming: UnboundedReceiver<UMessage>,
+    ) -> Self {
+        let listeners = Arc::new(ListenerRegistry::new());
+        let tasks = TaskTracker::new("channel-transport");
+        scaffold::spawn_dispatch_loop(&tasks, listeners.clone(), incoming);
+        ChannelTransport {
+            outgoing,
+            listeners,
+            tasks,
+        }
+    }
+
+    /// Gets the tracker of the task dispatching incoming messages.
+    pub fn task_tracker(&self) -> &TaskTracker {
+        &self.tasks
+    }
+
+    /// Creates a new transport along with the far end of its channels.
+    ///
+    /// # Panics
+    ///
+    /// if not called from within the context of a Tokio runtime.
+    pub fn new() -> (Self, ChannelEndpoint) {
+        let (outgoing_tx, outgoing_rx) = unbounded_channel();
+        let (incoming_tx, incoming_rx) = unbounded_channel();
+        let transport = Self::from_channels(outgoing_tx, incoming_rx);
+        (
+            transport,
+            ChannelEndpoint {
+                sender: incoming_tx,
+                receiver: outgoing_rx,
+            },
+        )
+    }
+
+    /// Creates two transports that are connected to each other.
+    ///
+    /// All messages sent via one of the transports are dispatched to the listeners
+    /// registered with the other transport.
+    ///
+    /// # Panics
+    ///
+    /// if not called from within the context of a Tokio runtime.
+    pub fn pair() -> (Self, Self) {
+        let (a_to_b_tx, a_to_b_rx) = unbounded_channel();
+        let (b_to_a_tx, b_to_a_rx) = unbounded_channel();
+        (
+            Self::from_channels(a_to_b_tx, b_to_a_rx),
+            Self::from_channels(b_to_a_tx, a_to_b_rx),
+        )
+    }
+}
+
+#[async_trait]
+impl UTransport for ChannelTransport {
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        self.outgoing
+            .send(message)
+            .map_err(|_e| scaffold::unavailable("channel has been closed"))
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.listeners
+            .register(source_filter, sink_filter, listener)
+            .await
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.listeners
+            .unregister(source_filter, sink_filter, listener)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "rpc-client", feature = "rpc-server"))]
+mod tests {
+    use protobuf::well_known_types::wrappers::StringValue;
+
+    use super::*;
+    use crate::{
+        communication::{
+            CallOptions, InMemoryRpcClient, InMemoryRpcServer, RequestHandler, RpcClient,
+            RpcServer, ServiceInvocationError, UPayload,
+        },
+        LocalUriProvider, StaticUriProvider, UCode, UMessageBuilder,
+    };
+
+    struct EchoHandler;
+
+    #[async_trait]
+    impl RequestHandler for EchoHandler {
+        async fn handle_request(
+            &self,
+            _resource_id: u16,
+            _message_attributes: &crate::UAttributes,
+            request_payload: Option<UPayload>,
+        ) -> Result<Option<UPayload>, ServiceInvocationError> {
+            Ok(request_payload)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_exposes_messages_sent_via_transport() {
+        let (transport, mut endpoint) = ChannelTransport::new();
+        let topic = UUri::try_from_parts("my-vehicle", 0xD45, 0x01, 0xA001).unwrap();
+        let message = UMessageBuilder::publish(topic).build().unwrap();
+
+        assert!(transport.send(message.clone()).await.is_ok());
+        assert_eq!(endpoint.receiver.recv().await, Some(message.clone()));
+
+        // sending fails once the far end has been dropped
+        drop(endpoint);
+        assert!(transport
+            .send(message)
+            .await
+            .is_err_and(|e| e.get_code() == UCode::UNAVAILABLE));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_between_connected_stacks() {
+        // GIVEN an RPC server stack and an RPC client stack connected via channels
+        let (client_transport, mut client_endpoint) = ChannelTransport::new();
+        let (server_transport, mut server_endpoint) = ChannelTransport::new();
+        let server_uri_provider = Arc::new(StaticUriProvider::new("server", 0x2000, 0x01));
+        let rpc_server =
+            InMemoryRpcServer::new(Arc::new(server_transport), server_uri_provider.clone());
+        rpc_server
+            .register_endpoint(None, 0x0001, Arc::new(EchoHandler))
+            .await
+            .unwrap();
+        let rpc_client = InMemoryRpcClient::new(
+            Arc::new(client_transport),
+            Arc::new(StaticUriProvider::new("client", 0x1000, 0x01)),
+        )
+        .await
+        .unwrap();
+
+        // WHEN the client invokes the server's method
+        let payload = UPayload::try_from_protobuf(StringValue {
+            value: "hello".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let invocation = tokio::spawn(async move {
+            rpc_client
+                .invoke_method(
+                    server_uri_provider.get_resource_uri(0x0001),
+                    CallOptions::for_rpc_request(5_000, None, None, None),
+                    Some(payload),
+                )
+                .await
+        });
+
+        // THEN the request and response messages can be inspected while being relayed
+        let request = client_endpoint.receiver.recv().await.unwrap();
+        assert!(request.is_request());
+        server_endpoint.sender.send(request.clone()).unwrap();
+        let response = server_endpoint.receiver.recv().await.unwrap();
+        assert!(response.is_response());
+        assert_eq!(
+            response.attributes.reqid,
+            request.attributes.as_ref().unwrap().id
+        );
+        client_endpoint.sender.send(response).unwrap();
+
+        // and the client receives the echoed payload
+        let response_payload = invocation.await.unwrap().unwrap().unwrap();
+        assert_eq!(
+            response_payload
+                .extract_protobuf::<StringValue>()
+                .unwrap()
+                .value,
+            "hello"
+        );
+    }
+}
@@ -0,0 +1,489 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides entry points for command line tools that compose, validate and explain uProtocol artifacts.
+
+The functions in this module accept human input, i.e. strings, and report problems by means of
+structured [`Diagnostic`]s which refer to the offending input. This allows command line tools to
+be implemented as thin wrappers that merely forward their arguments and print the outcome.
+*/
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use protobuf::{EnumFull, Message};
+
+use crate::{
+    UAttributesValidators, UMessage, UMessageBuilder, UMessageType, UPayloadFormat, UPriority,
+    UUri, UUID,
+};
+
+const ARG_TYPE: &str = "type";
+const ARG_SOURCE: &str = "source";
+const ARG_SINK: &str = "sink";
+const ARG_TTL: &str = "ttl";
+const ARG_PRIORITY: &str = "priority";
+const ARG_TOKEN: &str = "token";
+const ARG_TRACEPARENT: &str = "traceparent";
+const ARG_REQUEST_ID: &str = "reqid";
+const ARG_PAYLOAD: &str = "payload";
+const ARG_PAYLOAD_FORMAT: &str = "format";
+
+/// A problem with a particular piece of input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The name of the argument, attribute or component that the problem refers to.
+    pub subject: String,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new<S: Into<String>, M: Into<String>>(subject: S, message: M) -> Self {
+        Diagnostic {
+            subject: subject.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.subject, self.message)
+    }
+}
+
+fn parse_uri(arg: &str, value: &str) -> Result<UUri, Diagnostic> {
+    UUri::from_str(value).map_err(|e| Diagnostic::new(arg, e.to_string()))
+}
+
+// accepts the full protobuf enum value name or its suffix, e.g. UPRIORITY_CS4 or cs4
+fn parse_enum<E: EnumFull>(arg: &str, prefix: &str, value: &str) -> Result<E, Diagnostic> {
+    let name = value.to_ascii_uppercase();
+    let name = if name.starts_with(prefix) {
+        name
+    } else {
+        format!("{}{}", prefix, name)
+    };
+    E::from_str(&name).ok_or_else(|| {
+        let candidates = E::VALUES
+            .iter()
+            .map(|v| {
+                v.descriptor()
+                    .name()
+                    .trim_start_matches(prefix)
+                    .to_lowercase()
+            })
+            .collect::<Vec<_>>();
+        Diagnostic::new(
+            arg,
+            format!("unknown value, expected one of {}", candidates.join(", ")),
+        )
+    })
+}
+
+/// Creates a message from command line arguments.
+///
+/// Each argument is a `key=value` pair. The following keys are supported:
+///
+/// | Key           | Value                                                        |
+/// | ------------- | ------------------------------------------------------------ |
+/// | `type`        | `publish`, `notification`, `request` or `response` (required) |
+/// | `source`      | the message's source URI (required)                          |
+/// | `sink`        | the message's sink URI (required for all but `publish`)      |
+/// | `ttl`         | the time-to-live in milliseconds (required for `request`)    |
+/// | `priority`    | the priority class, e.g. `cs4`                               |
+/// | `token`       | the access token to include in a `request`                   |
+/// | `traceparent` | the W3C trace context                                        |
+/// | `reqid`       | the ID of the request that a `response` refers to (required for `response`) |
+/// | `payload`     | the (UTF-8) payload                                          |
+/// | `format`      | the payload format, e.g. `text` or `json`                    |
+///
+/// For RPC messages, `source` and `sink` refer to the message's addresses, i.e. the
+/// `sink` of a `request` is the method to invoke.
+///
+/// # Errors
+///
+/// Returns all problems found with the arguments and, if the arguments are fine,
+/// the problem found when validating the resulting message.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::cli::compose_message_from_args;
+///
+/// let message = compose_message_from_args([
+///     "type=publish",
+///     "source=//my-vehicle/4210/1/B24D",
+///     "priority=cs2",
+///     "payload=locked",
+///     "format=text",
+/// ])
+/// .unwrap();
+/// assert!(message.is_publish());
+///
+/// let diagnostics = compose_message_from_args(["type=publish", "ttl=abc"]).unwrap_err();
+/// assert_eq!(diagnostics.len(), 2);
+/// ```
+pub fn compose_message_from_args<I, S>(args: I) -> Result<UMessage, Vec<Diagnostic>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut diagnostics = vec![];
+    let mut message_type = None;
+    let mut source = None;
+    let mut sink = None;
+    let mut ttl = None;
+    let mut priority = None;
+    let mut token = None;
+    let mut traceparent = None;
+    let mut request_id = None;
+    let mut payload = None;
+    let mut payload_format = UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED;
+
+    for arg in args {
+        let Some((key, value)) = arg.as_ref().split_once('=') else {
+            diagnostics.push(Diagnostic::new(arg.as_ref(), "not a key=value pair"));
+            continue;
+        };
+        let result = match key {
+            ARG_TYPE => parse_enum::<UMessageType>(key, "UMESSAGE_TYPE_", value)
+                .map(|v| message_type = Some(v)),
+            ARG_SOURCE => parse_uri(key, value).map(|v| source = Some(v)),
+            ARG_SINK => parse_uri(key, value).map(|v| sink = Some(v)),
+            ARG_TTL => u32::from_str(value)
+                .map(|v| ttl = Some(v))
+                .map_err(|e| Diagnostic::new(key, e.to_string())),
+            ARG_PRIORITY => {
+                parse_enum::<UPriority>(key, "UPRIORITY_", value).map(|v| priority = Some(v))
+            }
+            ARG_TOKEN => {
+                token = Some(value.to_string());
+                Ok(())
+            }
+            ARG_TRACEPARENT => {
+                traceparent = Some(value.to_string());
+                Ok(())
+            }
+            ARG_REQUEST_ID => UUID::from_str(value)
+                .map(|v| request_id = Some(v))
+                .map_err(|e| Diagnostic::new(key, e.to_string())),
+            ARG_PAYLOAD => {
+                payload = Some(value.to_string());
+                Ok(())
+            }
+            ARG_PAYLOAD_FORMAT => parse_enum::<UPayloadFormat>(key, "UPAYLOAD_FORMAT_", value)
+                .map(|v| payload_format = v),
+            _ => Err(Diagnostic::new(key, "unknown argument")),
+        };
+        if let Err(diagnostic) = result {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    let mut require = |arg: &str, present: bool| {
+        if !present {
+            diagnostics.push(Diagnostic::new(arg, "argument is required"));
+        }
+    };
+    require(ARG_TYPE, message_type.is_some());
+    require(ARG_SOURCE, source.is_some());
+    match message_type {
+        Some(UMessageType::UMESSAGE_TYPE_NOTIFICATION) => require(ARG_SINK, sink.is_some()),
+        Some(UMessageType::UMESSAGE_TYPE_REQUEST) => {
+            require(ARG_SINK, sink.is_some());
+            require(ARG_TTL, ttl.is_some());
+        }
+        Some(UMessageType::UMESSAGE_TYPE_RESPONSE) => {
+            require(ARG_SINK, sink.is_some());
+            require(ARG_REQUEST_ID, request_id.is_some());
+        }
+        _ => {}
+    }
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    let (Some(message_type), Some(source)) = (message_type, source) else {
+        return Err(diagnostics);
+    };
+    let mut builder = match (message_type, sink, ttl, request_id) {
+        (UMessageType::UMESSAGE_TYPE_PUBLISH, _, _, _) => UMessageBuilder::publish(source),
+        (UMessageType::UMESSAGE_TYPE_NOTIFICATION, Some(sink), _, _) => {
+            UMessageBuilder::notification(source, sink)
+        }
+        (UMessageType::UMESSAGE_TYPE_REQUEST, Some(sink), Some(ttl), _) => {
+            UMessageBuilder::request(sink, source, ttl)
+        }
+        (UMessageType::UMESSAGE_TYPE_RESPONSE, Some(sink), _, Some(request_id)) => {
+            UMessageBuilder::response(sink, request_id, source)
+        }
+        _ => return Err(vec![Diagnostic::new(ARG_TYPE, "unsupported message type")]),
+    };
+    if let Some(ttl) = ttl {
+        builder.with_ttl(ttl);
+    }
+    if let Some(priority) = priority {
+        builder.with_priority(priority);
+    }
+    if let Some(token) = token {
+        builder.with_token(token);
+    }
+    if let Some(traceparent) = traceparent {
+        builder.with_traceparent(traceparent);
+    }
+    let result = match payload {
+        Some(payload) => builder.build_with_payload(payload, payload_format),
+        None => builder.build(),
+    };
+    result.map_err(|e| vec![Diagnostic::new("message", e.to_string())])
+}
+
+/// The outcome of validating a serialized message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationReport {
+    message: Option<UMessage>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    /// Checks if the message is valid.
+    pub fn is_valid(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Gets the message, if it could be deserialized.
+    pub fn message(&self) -> Option<&UMessage> {
+        self.message.as_ref()
+    }
+
+    /// Gets the problems found with the message.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+}
+
+/// Validates the protobuf encoding of a message.
+///
+/// The message is deserialized and its attributes are checked against the rules defined for
+/// the message's type. Additionally, the message's source and sink URIs are checked for being
+/// valid uProtocol URIs.
+///
+/// # Examples
+///
+/// ```rust
+/// use protobuf::Message;
+/// use up_rust::{cli::validate_message_bytes, UMessageBuilder, UUri};
+///
+/// let message = UMessageBuilder::publish(UUri::try_from("//my-vehicle/4210/1/B24D").unwrap())
+///     .build()
+///     .unwrap();
+/// let report = validate_message_bytes(&message.write_to_bytes().unwrap());
+/// assert!(report.is_valid());
+///
+/// let report = validate_message_bytes(&[0x0a, 0x05, 0x01]);
+/// assert!(!report.is_valid());
+/// assert!(report.message().is_none());
+/// ```
+pub fn validate_message_bytes(bytes: &[u8]) -> ValidationReport {
+    let message = match UMessage::parse_from_bytes(bytes) {
+        Ok(message) => message,
+        Err(e) => {
+            return ValidationReport {
+                message: None,
+                diagnostics: vec![Diagnostic::new("message", e.to_string())],
+            }
+        }
+    };
+    let mut diagnostics = vec![];
+    match message.attributes.as_ref() {
+        Some(attributes) => {
+            for (subject, uri) in [
+                ("source", attributes.source.as_ref()),
+                ("sink", attributes.sink.as_ref()),
+            ] {
+                if let Some(Err(e)) = uri.map(UUri::check_validity) {
+                    diagnostics.push(Diagnostic::new(subject, e.to_string()));
+                }
+            }
+            if let Err(e) =
+                UAttributesValidators::get_validator_for_attributes(attributes).validate(attributes)
+            {
+                diagnostics.push(Diagnostic::new("attributes", e.to_string()));
+            }
+        }
+        None => diagnostics.push(Diagnostic::new("attributes", "message has no attributes")),
+    }
+    ValidationReport {
+        message: Some(message),
+        diagnostics,
+    }
+}
+
+/// A human readable breakdown of a uProtocol URI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UriExplanation {
+    /// The URI's components and their meaning, in order of appearance.
+    pub components: Vec<(String, String)>,
+    /// The kinds of messages the URI can be used in.
+    pub usage: Vec<String>,
+}
+
+impl Display for UriExplanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, meaning) in &self.components {
+            writeln!(f, "{:<16}{}", name, meaning)?;
+        }
+        write!(f, "{:<16}{}", "usage", self.usage.join(", "))
+    }
+}
+
+/// Explains the components of a URI.
+///
+/// # Errors
+///
+/// Returns a diagnostic if the given string is not a valid uProtocol URI.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::cli::explain_uuri;
+///
+/// let explanation = explain_uuri("//my-vehicle/10A10B/1/0").unwrap();
+/// assert_eq!(explanation.components[1].1, "0xA10B");
+/// assert_eq!(explanation.components[2].1, "0x10");
+/// assert_eq!(explanation.usage, vec!["RPC response sink or notification sink"]);
+///
+/// assert!(explain_uuri("//my-vehicle/10A10B").is_err());
+/// ```
+pub fn explain_uuri(uri: &str) -> Result<UriExplanation, Diagnostic> {
+    let uri = parse_uri("uri", uri)?;
+    let wildcard_or = |is_wildcard: bool, value: String| {
+        if is_wildcard {
+            "any (wildcard)".to_string()
+        } else {
+            value
+        }
+    };
+    let authority = if uri.has_empty_authority() {
+        "local (empty)".to_string()
+    } else {
+        wildcard_or(uri.has_wildcard_authority(), uri.authority_name())
+    };
+    let components = vec![
+        ("authority".to_string(), authority),
+        (
+            "entity type".to_string(),
+            wildcard_or(
+                uri.has_wildcard_entity_type(),
+                format!("{:#X}", uri.uentity_type_id()),
+            ),
+        ),
+        (
+            "entity instance".to_string(),
+            wildcard_or(
+                uri.has_wildcard_entity_instance(),
+                format!("{:#X}", uri.uentity_instance_id()),
+            ),
+        ),
+        (
+            "version".to_string(),
+            wildcard_or(
+                uri.has_wildcard_version(),
+                uri.uentity_major_version().to_string(),
+            ),
+        ),
+        (
+            "resource".to_string(),
+            wildcard_or(
+                uri.has_wildcard_resource_id(),
+                format!("{:#X}", uri.resource_id()),
+            ),
+        ),
+    ];
+    let mut usage = vec![];
+    if uri.verify_no_wildcards().is_err() {
+        usage.push("pattern for matching URIs".to_string());
+    } else if uri.is_rpc_response() {
+        usage.push("RPC response sink or notification sink".to_string());
+    } else if uri.is_rpc_method() {
+        usage.push("RPC method".to_string());
+    } else if uri.is_event() {
+        usage.push("topic or notification origin".to_string());
+    }
+    Ok(UriExplanation { components, usage })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case(&["type=notification", "source=//vehicle/A/1/8001", "sink=//vehicle/B/1/0"], UMessageType::UMESSAGE_TYPE_NOTIFICATION; "for notification")]
+    #[test_case(&["type=request", "source=//vehicle/A/1/0", "sink=//vehicle/B/1/1", "ttl=5000", "token=my-token"], UMessageType::UMESSAGE_TYPE_REQUEST; "for request")]
+    #[test_case(&["type=RESPONSE", "source=//vehicle/B/1/1", "sink=//vehicle/A/1/0", "reqid=0190a3d6-4d5b-7f00-8a2a-5e4c42c06d7d"], UMessageType::UMESSAGE_TYPE_RESPONSE; "for response")]
+    fn test_compose_message_from_args_succeeds(args: &[&str], expected_type: UMessageType) {
+        let message = compose_message_from_args(args).expect("message should be valid");
+        assert_eq!(
+            message.attributes.as_ref().unwrap().type_.enum_value(),
+            Ok(expected_type)
+        );
+    }
+
+    #[test]
+    fn test_compose_message_from_args_reports_all_problems() {
+        let diagnostics = compose_message_from_args([
+            "type=request",
+            "source=//vehicle/A/1/0",
+            "priority=cs9",
+            "foo=bar",
+            "payload",
+        ])
+        .unwrap_err();
+        let subjects = diagnostics
+            .iter()
+            .map(|d| d.subject.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(subjects, vec!["priority", "foo", "payload", "sink", "ttl"]);
+    }
+
+    #[test]
+    fn test_validate_message_bytes_reports_invalid_attributes() {
+        let message = UMessage {
+            attributes: Some(crate::UAttributes {
+                type_: UMessageType::UMESSAGE_TYPE_PUBLISH.into(),
+                id: Some(UUID::build()).into(),
+                source: Some(UUri::try_from("//vehicle/A/1/1").unwrap()).into(),
+                ..Default::default()
+            })
+            .into(),
+            ..Default::default()
+        };
+        let report = validate_message_bytes(&message.write_to_bytes().unwrap());
+        assert!(!report.is_valid());
+        assert_eq!(report.message(), Some(&message));
+        assert_eq!(report.diagnostics()[0].subject, "attributes");
+    }
+
+    #[test_case("//*/FFFF/FF/FFFF", "pattern for matching URIs"; "for pattern")]
+    #[test_case("/A/1/1", "RPC method"; "for method")]
+    #[test_case("//vehicle/A/1/8001", "topic or notification origin"; "for topic")]
+    fn test_explain_uuri(uri: &str, expected_usage: &str) {
+        let explanation = explain_uuri(uri).unwrap();
+        assert_eq!(explanation.usage, vec![expected_usage]);
+        assert_eq!(explanation.to_string().lines().count(), 6);
+    }
+}
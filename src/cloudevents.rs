@@ -37,6 +37,7 @@ pub const CONTENT_TYPE_CLOUDEVENTS_PROTOBUF: &str = "application/cloudevents+pro
 const CLOUDEVENTS_SPEC_VERSION: &str = "1.0";
 
 const EXTENSION_NAME_COMMSTATUS: &str = "commstatus";
+const EXTENSION_NAME_CONTENT_ENCODING: &str = "contentencoding";
 const EXTENSION_NAME_PERMISSION_LEVEL: &str = "plevel";
 const EXTENSION_NAME_PFORMAT: &str = "pformat";
 const EXTENSION_NAME_PRIORITY: &str = "priority";
@@ -176,12 +177,10 @@ impl CloudEvent {
     }
 
     fn set_commstatus(&mut self, status: UCode) {
-        if status != UCode::OK {
-            let mut val = CloudEventAttributeValue::new();
-            val.set_ce_integer(status.value());
-            self.attributes
-                .insert(EXTENSION_NAME_COMMSTATUS.to_string(), val);
-        }
+        let mut val = CloudEventAttributeValue::new();
+        val.set_ce_integer(status.value());
+        self.attributes
+            .insert(EXTENSION_NAME_COMMSTATUS.to_string(), val);
     }
 
     fn get_traceparent(&self) -> Option<String> {
@@ -197,6 +196,19 @@ impl CloudEvent {
             .insert(EXTENSION_NAME_TRACEPARENT.to_string(), val);
     }
 
+    fn get_content_encoding(&self) -> Option<String> {
+        self.attributes
+            .get(EXTENSION_NAME_CONTENT_ENCODING)
+            .map(|val| val.ce_string().to_string())
+    }
+
+    fn set_content_encoding<T: Into<String>>(&mut self, encoding: T) {
+        let mut val = CloudEventAttributeValue::new();
+        val.set_ce_string(encoding.into());
+        self.attributes
+            .insert(EXTENSION_NAME_CONTENT_ENCODING.to_string(), val);
+    }
+
     fn get_payload_format(&self) -> Result<UPayloadFormat, UAttributesError> {
         self.attributes
             .get(EXTENSION_NAME_PFORMAT)
@@ -216,6 +228,127 @@ impl CloudEvent {
                 .insert(EXTENSION_NAME_PFORMAT.to_string(), val);
         }
     }
+
+    // Maps the event's context attributes and extensions to uProtocol message attributes.
+    //
+    // # Errors
+    //
+    // Returns an error if the event does not use the expected spec version or if any of the
+    // attribute values cannot be mapped.
+    fn get_attributes(&self) -> Result<UAttributes, UAttributesError> {
+        if !CLOUDEVENTS_SPEC_VERSION.eq(&self.spec_version) {
+            let msg = format!("expected spec version 1.0 but found {}", self.spec_version);
+            return Err(UAttributesError::ValidationError(msg));
+        }
+
+        let mut attributes = UAttributes {
+            commstatus: self.get_commstatus().map(EnumOrUnknown::from),
+            id: MessageField::from_option(Some(self.get_id()?)),
+            type_: EnumOrUnknown::from(self.get_type()?),
+            source: MessageField::from_option(Some(self.get_source()?)),
+            sink: MessageField::from_option(self.get_sink()?),
+            priority: EnumOrUnknown::from(self.get_priority()?),
+            ttl: self.get_ttl(),
+            permission_level: self.get_permission_level(),
+            reqid: MessageField::from_option(self.get_request_id()?),
+            token: self.get_token(),
+            traceparent: self.get_traceparent(),
+            payload_format: self.get_payload_format().map(EnumOrUnknown::from)?,
+            ..Default::default()
+        };
+        if let Some(encoding) = self.get_content_encoding() {
+            attributes.set_content_encoding(encoding);
+        }
+        Ok(attributes)
+    }
+}
+
+/// Validators for CloudEvents that represent uProtocol messages.
+///
+/// The validators check that an event's attributes and extensions can be mapped to
+/// [`UAttributes`] and that the resulting attributes comply with the rules defined for the
+/// type of message that the event represents. The `source` and `sink` attributes of the
+/// event are expected to contain uProtocol URIs, i.e. they are checked for containing
+/// authority name, uEntity ID, major version and resource ID as defined by [`UUri`].
+///
+/// Bridge implementations may use these validators to check inbound events before
+/// converting them to [`UMessage`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloudEventValidators {
+    Publish,
+    Notification,
+    Request,
+    Response,
+}
+
+impl CloudEventValidators {
+    /// Gets the validator to use for checking a given event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event's `type` does not denote a uProtocol message type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{CloudEvent, CloudEventValidators, UMessageBuilder, UUri};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let topic = UUri::try_from("//my-vehicle/D45/23/A001")?;
+    /// let message = UMessageBuilder::publish(topic).build()?;
+    /// let event = CloudEvent::try_from(message)?;
+    /// let validator = CloudEventValidators::get_validator_for_event(&event)?;
+    /// assert_eq!(validator, CloudEventValidators::Publish);
+    /// assert!(validator.validate(&event).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_validator_for_event(event: &CloudEvent) -> Result<Self, UAttributesError> {
+        match event.get_type()? {
+            UMessageType::UMESSAGE_TYPE_PUBLISH => Ok(CloudEventValidators::Publish),
+            UMessageType::UMESSAGE_TYPE_NOTIFICATION => Ok(CloudEventValidators::Notification),
+            UMessageType::UMESSAGE_TYPE_REQUEST => Ok(CloudEventValidators::Request),
+            UMessageType::UMESSAGE_TYPE_RESPONSE => Ok(CloudEventValidators::Response),
+            _ => Err(UAttributesError::validation_error(format!(
+                "unsupported event type: {}",
+                event.type_
+            ))),
+        }
+    }
+
+    /// Checks if a given event represents a valid uProtocol message of the type
+    /// corresponding to this validator.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event's attributes cannot be mapped to [`UAttributes`]
+    /// or if the resulting attributes are not consistent with the rules specified for
+    /// the message type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{CloudEvent, CloudEventValidators, UMessageBuilder, UUri};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let topic = UUri::try_from("//my-vehicle/D45/23/A001")?;
+    /// let message = UMessageBuilder::publish(topic).build()?;
+    /// let event = CloudEvent::try_from(message)?;
+    /// assert!(CloudEventValidators::Publish.validate(&event).is_ok());
+    /// assert!(CloudEventValidators::Request.validate(&event).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(&self, event: &CloudEvent) -> Result<(), UAttributesError> {
+        let attributes = event.get_attributes()?;
+        let validator = match self {
+            CloudEventValidators::Publish => UAttributesValidators::Publish,
+            CloudEventValidators::Notification => UAttributesValidators::Notification,
+            CloudEventValidators::Request => UAttributesValidators::Request,
+            CloudEventValidators::Response => UAttributesValidators::Response,
+        };
+        validator.validator().validate(&attributes)
+    }
 }
 
 impl TryFrom<UMessage> for CloudEvent {
@@ -296,10 +429,18 @@ impl TryFrom<UMessage> for CloudEvent {
         if let Some(traceparent) = attributes.traceparent.as_ref() {
             event.set_traceparent(traceparent);
         }
+        let payload_format = attributes.payload_format.enum_value_or_default();
+        event.set_payload_format(payload_format);
+        let content_encoding = attributes.content_encoding();
+        if let Some(encoding) = content_encoding.as_ref() {
+            event.set_content_encoding(encoding);
+        }
         if let Some(payload) = message.payload {
-            let payload_format = attributes.payload_format.enum_value_or_default();
-            event.set_payload_format(payload_format);
             match payload_format {
+                // the payload format describes the payload after it has been decoded
+                _ if content_encoding.is_some() => {
+                    event.set_binary_data(payload.into());
+                }
                 UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF
                 | UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY => {
                     let data = Any {
@@ -341,28 +482,7 @@ impl TryFrom<CloudEvent> for UMessage {
     // Returns an error if the given event does not contain the necessary information for creating a uProtocol message.
     // Also returns an error if the resulting message is not a valid uProtocol message.
     fn try_from(event: CloudEvent) -> Result<Self, Self::Error> {
-        if !CLOUDEVENTS_SPEC_VERSION.eq(&event.spec_version) {
-            let msg = format!("expected spec version 1.0 but found {}", event.spec_version);
-            return Err(UMessageError::AttributesValidationError(
-                UAttributesError::ValidationError(msg),
-            ));
-        }
-
-        let attributes = UAttributes {
-            commstatus: event.get_commstatus().map(EnumOrUnknown::from),
-            id: MessageField::from_option(Some(event.get_id()?)),
-            type_: EnumOrUnknown::from(event.get_type()?),
-            source: MessageField::from_option(Some(event.get_source()?)),
-            sink: MessageField::from_option(event.get_sink()?),
-            priority: EnumOrUnknown::from(event.get_priority()?),
-            ttl: event.get_ttl(),
-            permission_level: event.get_permission_level(),
-            reqid: MessageField::from_option(event.get_request_id()?),
-            token: event.get_token(),
-            traceparent: event.get_traceparent(),
-            payload_format: event.get_payload_format().map(EnumOrUnknown::from)?,
-            ..Default::default()
-        };
+        let attributes = event.get_attributes()?;
         UAttributesValidators::get_validator_for_attributes(&attributes).validate(&attributes)?;
 
         let payload = if event.has_binary_data() {
@@ -402,7 +522,7 @@ mod tests {
     const PERMISSION_LEVEL: u32 = 5;
     const PRIORITY: UPriority = UPriority::UPRIORITY_CS4;
     const TTL: u32 = 15_000;
-    const TRACEPARENT: &str = "traceparent";
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
     const DATA: [u8; 4] = [0x00, 0x01, 0x02, 0x03];
 
     //
@@ -612,7 +732,7 @@ mod tests {
                 .attributes
                 .get(EXTENSION_NAME_COMMSTATUS)
                 .map(|v| v.ce_integer()),
-            None
+            Some(UCode::OK.value())
         );
         assert_eq!(
             event
@@ -804,7 +924,7 @@ mod tests {
             METHOD,
             Some(REPLY_TO.to_string()),
         );
-        assert_eq!(attribs.commstatus, None);
+        assert_eq!(attribs.commstatus, Some(UCode::OK.into()));
         assert_eq!(attribs.reqid, Some(request_id).into());
         assert_eq!(
             attribs.payload_format.enum_value_or_default(),
@@ -812,4 +932,188 @@ mod tests {
         );
         assert_eq!(umessage.payload, Some(DATA.to_vec().into()))
     }
+
+    //
+    // tests asserting that all attributes round-trip through the mapping
+    // [utest->dsn~cloudevents-umessage-mapping~2]
+    //
+
+    fn publish_message() -> UMessage {
+        UMessageBuilder::publish(UUri::from_str(TOPIC).unwrap())
+            .with_message_id(MESSAGE_ID.parse::<UUID>().unwrap())
+            .with_priority(PRIORITY)
+            .with_ttl(TTL)
+            .with_traceparent(TRACEPARENT)
+            .build_with_payload(DATA.to_vec(), UPayloadFormat::UPAYLOAD_FORMAT_RAW)
+            .unwrap()
+    }
+
+    fn notification_message() -> UMessage {
+        UMessageBuilder::notification(
+            UUri::from_str(TOPIC).unwrap(),
+            UUri::from_str(DESTINATION).unwrap(),
+        )
+        .with_message_id(MESSAGE_ID.parse::<UUID>().unwrap())
+        .with_priority(PRIORITY)
+        .with_ttl(TTL)
+        .with_traceparent(TRACEPARENT)
+        .build_with_payload("{\"count\": 5}", UPayloadFormat::UPAYLOAD_FORMAT_JSON)
+        .unwrap()
+    }
+
+    fn request_message() -> UMessage {
+        UMessageBuilder::request(
+            UUri::from_str(METHOD).unwrap(),
+            UUri::from_str(REPLY_TO).unwrap(),
+            TTL,
+        )
+        .with_message_id(MESSAGE_ID.parse::<UUID>().unwrap())
+        .with_priority(PRIORITY)
+        .with_permission_level(PERMISSION_LEVEL)
+        .with_token("my-token")
+        .with_traceparent(TRACEPARENT)
+        .build_with_payload("Hello", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+        .unwrap()
+    }
+
+    fn response_message(commstatus: UCode) -> UMessage {
+        UMessageBuilder::response(
+            UUri::from_str(REPLY_TO).unwrap(),
+            UUID::build(),
+            UUri::from_str(METHOD).unwrap(),
+        )
+        .with_message_id(MESSAGE_ID.parse::<UUID>().unwrap())
+        .with_priority(PRIORITY)
+        .with_ttl(TTL)
+        .with_comm_status(commstatus)
+        .with_traceparent(TRACEPARENT)
+        .build_with_payload(DATA.to_vec(), UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF)
+        .unwrap()
+    }
+
+    fn response_message_without_payload() -> UMessage {
+        UMessageBuilder::response(
+            UUri::from_str(REPLY_TO).unwrap(),
+            UUID::build(),
+            UUri::from_str(METHOD).unwrap(),
+        )
+        .with_message_id(MESSAGE_ID.parse::<UUID>().unwrap())
+        .with_priority(PRIORITY)
+        .with_comm_status(UCode::NOT_FOUND)
+        .build()
+        .map(|mut msg| {
+            msg.attributes.as_mut().unwrap().payload_format =
+                UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF.into();
+            msg
+        })
+        .unwrap()
+    }
+
+    // a response carrying a (fake) compressed text payload, which is not valid UTF-8
+    fn encoded_response_message() -> UMessage {
+        let mut message = UMessageBuilder::response(
+            UUri::from_str(REPLY_TO).unwrap(),
+            UUID::build(),
+            UUri::from_str(METHOD).unwrap(),
+        )
+        .with_message_id(MESSAGE_ID.parse::<UUID>().unwrap())
+        .with_priority(PRIORITY)
+        .build_with_payload(vec![0xff, 0xfe, 0x00], UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+        .unwrap();
+        message
+            .attributes
+            .as_mut()
+            .unwrap()
+            .set_content_encoding("deflate");
+        message
+    }
+
+    #[test_case::test_case(publish_message(); "for Publish message")]
+    #[test_case::test_case(notification_message(); "for Notification message")]
+    #[test_case::test_case(request_message(); "for Request message")]
+    #[test_case::test_case(response_message(UCode::OK); "for Response message with OK commstatus")]
+    #[test_case::test_case(response_message(UCode::UNAVAILABLE); "for Response message with error commstatus")]
+    #[test_case::test_case(response_message_without_payload(); "for Response message without payload")]
+    #[test_case::test_case(encoded_response_message(); "for Response message with encoded payload")]
+    fn test_message_round_trips_through_cloudevent(message: UMessage) {
+        let event = CloudEvent::try_from(message.clone())
+            .expect("failed to create CloudEvent from UMessage");
+        let round_tripped_message =
+            UMessage::try_from(event).expect("failed to create UMessage from CloudEvent");
+        assert_eq!(round_tripped_message, message);
+    }
+
+    // the attribute mapping table defined by the uProtocol CloudEvents specification
+    #[test_case::test_case(request_message(), EXTENSION_NAME_SINK; "sink maps to sink extension")]
+    #[test_case::test_case(request_message(), EXTENSION_NAME_PRIORITY; "priority maps to priority extension")]
+    #[test_case::test_case(request_message(), EXTENSION_NAME_TTL; "ttl maps to ttl extension")]
+    #[test_case::test_case(request_message(), EXTENSION_NAME_PERMISSION_LEVEL; "permission_level maps to plevel extension")]
+    #[test_case::test_case(request_message(), EXTENSION_NAME_TOKEN; "token maps to token extension")]
+    #[test_case::test_case(request_message(), EXTENSION_NAME_TRACEPARENT; "traceparent maps to traceparent extension")]
+    #[test_case::test_case(request_message(), EXTENSION_NAME_PFORMAT; "payload_format maps to pformat extension")]
+    #[test_case::test_case(response_message(UCode::OK), EXTENSION_NAME_REQUEST_ID; "reqid maps to reqid extension")]
+    #[test_case::test_case(response_message(UCode::OK), EXTENSION_NAME_COMMSTATUS; "commstatus maps to commstatus extension")]
+    #[test_case::test_case(response_message_without_payload(), EXTENSION_NAME_PFORMAT; "payload_format maps to pformat extension without payload")]
+    #[test_case::test_case(encoded_response_message(), EXTENSION_NAME_CONTENT_ENCODING; "content encoding maps to contentencoding extension")]
+    fn test_attribute_maps_to_extension(message: UMessage, extension_name: &str) {
+        let event =
+            CloudEvent::try_from(message).expect("failed to create CloudEvent from UMessage");
+        assert!(event.attributes.contains_key(extension_name));
+    }
+
+    #[test_case::test_case(publish_message(), CloudEventValidators::Publish; "for Publish event")]
+    #[test_case::test_case(notification_message(), CloudEventValidators::Notification; "for Notification event")]
+    #[test_case::test_case(request_message(), CloudEventValidators::Request; "for Request event")]
+    #[test_case::test_case(response_message(UCode::OK), CloudEventValidators::Response; "for Response event")]
+    fn test_get_validator_for_event(message: UMessage, expected_validator: CloudEventValidators) {
+        let event = CloudEvent::try_from(message).unwrap();
+        let validator = CloudEventValidators::get_validator_for_event(&event).unwrap();
+        assert_eq!(validator, expected_validator);
+        assert!(validator.validate(&event).is_ok());
+    }
+
+    #[test]
+    fn test_get_validator_for_event_fails_for_unknown_type() {
+        let mut event = CloudEvent::try_from(publish_message()).unwrap();
+        event.type_ = "com.example.unknown".to_string();
+        assert!(CloudEventValidators::get_validator_for_event(&event).is_err());
+    }
+
+    #[test_case::test_case(CloudEventValidators::Notification; "using Notification validator")]
+    #[test_case::test_case(CloudEventValidators::Request; "using Request validator")]
+    #[test_case::test_case(CloudEventValidators::Response; "using Response validator")]
+    fn test_validate_fails_for_wrong_message_type(validator: CloudEventValidators) {
+        let event = CloudEvent::try_from(publish_message()).unwrap();
+        assert!(validator.validate(&event).is_err());
+    }
+
+    #[test_case::test_case("//my-vehicle/A81B/1/0"; "for source with resource ID 0")]
+    #[test_case::test_case("//my-vehicle/A81B/1/1"; "for source with method resource ID")]
+    #[test_case::test_case("my-vehicle/A81B/1/A9BA"; "for source that is not a uProtocol URI")]
+    fn test_validate_publish_event_fails_for_invalid_source(source: &str) {
+        let mut event = CloudEvent::try_from(publish_message()).unwrap();
+        event.set_source(source);
+        assert!(CloudEventValidators::Publish.validate(&event).is_err());
+    }
+
+    #[test]
+    fn test_validate_request_event_fails_for_missing_sink() {
+        let mut event = CloudEvent::try_from(request_message()).unwrap();
+        event.attributes.remove(EXTENSION_NAME_SINK);
+        assert!(CloudEventValidators::Request.validate(&event).is_err());
+    }
+
+    #[test]
+    fn test_validate_request_event_fails_for_non_method_sink() {
+        let mut event = CloudEvent::try_from(request_message()).unwrap();
+        event.set_sink(DESTINATION);
+        assert!(CloudEventValidators::Request.validate(&event).is_err());
+    }
+
+    #[test]
+    fn test_validate_fails_for_unsupported_spec_version() {
+        let mut event = CloudEvent::try_from(publish_message()).unwrap();
+        event.spec_version = "0.3".to_string();
+        assert!(CloudEventValidators::Publish.validate(&event).is_err());
+    }
 }
@@ -12,46 +12,121 @@
  ********************************************************************************/
 
 use bytes::Bytes;
-use protobuf::{well_known_types::any::Any, Message, MessageFull};
+use protobuf::{well_known_types::any::Any, Enum, Message, MessageFull};
 use std::{error::Error, fmt::Display};
 
+#[cfg(feature = "notification")]
+pub use ack_collector::{acknowledge_notification, AckCollector, AckReport};
+#[cfg(feature = "pubsub")]
+pub use aggregating_publisher::{AggregatingPublisher, SampleStatistics};
+#[cfg(feature = "avro")]
+pub use avro::AvroSchema;
+#[cfg(feature = "compression")]
+pub use compression::{deflate, inflate, DEFAULT_MAX_DECOMPRESSED_SIZE, DEFLATE_ENCODING};
+#[cfg(feature = "notification")]
 pub use default_notifier::SimpleNotifier;
-#[cfg(feature = "usubscription")]
-pub use default_pubsub::{InMemorySubscriber, SimplePublisher};
-pub use in_memory_rpc_client::InMemoryRpcClient;
-pub use in_memory_rpc_server::InMemoryRpcServer;
-#[cfg(any(test, feature = "test-util"))]
+#[cfg(feature = "pubsub")]
+pub use default_pubsub::{
+    DuplicateSubscriptionPolicy, InMemorySubscriber, SimplePublisher, SubscriptionConfig,
+};
+pub use dependency_gate::{
+    DependencyGate, DependencyGateError, DependencyState, DependencyStatus, GateProgress,
+};
+#[cfg(feature = "rpc-server")]
+pub use echo_service::EchoService;
+#[cfg(all(feature = "rpc-server", any(test, feature = "test-util")))]
+pub use idempotency::MockIdempotencyStore;
+#[cfg(feature = "rpc-server")]
+pub use idempotency::{IdempotencyKey, IdempotencyStore, InMemoryIdempotencyStore};
+#[cfg(feature = "rpc-client")]
+pub use in_memory_rpc_client::{
+    CancellationHandle, HedgingPolicy, InMemoryRpcClient, InvocationPermit, PendingRequestLimits,
+    RPC_CANCELLATION_RESOURCE_ID,
+};
+#[cfg(feature = "rpc-server")]
+pub use in_memory_rpc_server::{
+    EndpointConfig, ErrorRateAlarm, ErrorRateAlert, ExecutionWatchdog, InMemoryRpcServer,
+    OrphanAction, OrphanDetection, WatchdogAction,
+};
+#[cfg(all(feature = "notification", any(test, feature = "test-util")))]
 pub use notification::MockNotifier;
+#[cfg(feature = "notification")]
 pub use notification::{NotificationError, Notifier};
-#[cfg(any(test, feature = "test-util"))]
+pub use ping::{ping, ECHO_RESOURCE_ID};
+#[cfg(all(feature = "pubsub", any(test, feature = "test-util")))]
 pub use pubsub::MockSubscriptionChangeHandler;
-#[cfg(feature = "usubscription")]
-pub use pubsub::{PubSubError, Publisher, Subscriber};
+#[cfg(feature = "pubsub")]
+pub use pubsub::{
+    PubSubError, Publisher, Subscriber, SubscriptionChangeHandler, SubscriptionOptions,
+};
+#[cfg(feature = "rpc-client")]
+pub use response_stream::ResponseStream;
+pub use response_stream::ResponseStreamWriter;
 #[cfg(any(test, feature = "test-util"))]
 pub use rpc::{MockRequestHandler, MockRpcClient, MockRpcServerImpl};
-pub use rpc::{RequestHandler, RpcClient, RpcServer, ServiceInvocationError};
+pub use rpc::{RequestHandler, RpcClient, RpcClientExt, RpcServer, ServiceInvocationError};
+pub use session::{open_session, SessionHandshakeHandler, SessionRegistry};
+#[cfg(feature = "pubsub")]
+pub use state_publisher::StatePublisher;
+#[cfg(feature = "pubsub")]
+pub use subscription_state::{SubscriptionState, SubscriptionStateMachine, SubscriptionTransition};
+pub use typed_request_handler::TypedRequestHandler;
 #[cfg(feature = "udiscovery")]
 pub use udiscovery_client::RpcClientUDiscovery;
 #[cfg(feature = "usubscription")]
 pub use usubscription_client::RpcClientUSubscription;
+#[cfg(feature = "utwin")]
+pub use utwin_backfill::{backfill_from_utwin, BackfillReport};
+#[cfg(all(feature = "udiscovery", feature = "pubsub"))]
+pub use wildcard_subscription::{TopicSetChanges, WildcardSubscription};
 
 use crate::{
     umessage::{self, UMessageError},
-    UCode, UMessage, UMessageBuilder, UPayloadFormat, UPriority, UStatus, UUID,
+    RpcPriorityPolicy, UCode, UMessage, UMessageBuilder, UPayloadFormat, UPriority, UStatus, UUID,
 };
 
+#[cfg(feature = "notification")]
+mod ack_collector;
+#[cfg(feature = "pubsub")]
+mod aggregating_publisher;
+#[cfg(feature = "avro")]
+mod avro;
+#[cfg(feature = "compression")]
+pub(crate) mod compression;
+#[cfg(feature = "notification")]
 mod default_notifier;
+#[cfg(feature = "pubsub")]
 mod default_pubsub;
+mod dependency_gate;
+#[cfg(feature = "rpc-server")]
+mod echo_service;
+#[cfg(feature = "rpc-server")]
+mod idempotency;
+#[cfg(feature = "rpc-client")]
 mod in_memory_rpc_client;
+#[cfg(feature = "rpc-server")]
 mod in_memory_rpc_server;
+#[cfg(feature = "notification")]
 mod notification;
-#[cfg(feature = "usubscription")]
+mod ping;
+#[cfg(feature = "pubsub")]
 mod pubsub;
+mod response_stream;
 mod rpc;
+mod session;
+#[cfg(feature = "pubsub")]
+mod state_publisher;
+#[cfg(feature = "pubsub")]
+mod subscription_state;
+mod typed_request_handler;
 #[cfg(feature = "udiscovery")]
 mod udiscovery_client;
 #[cfg(feature = "usubscription")]
 mod usubscription_client;
+#[cfg(feature = "utwin")]
+mod utwin_backfill;
+#[cfg(all(feature = "udiscovery", feature = "pubsub"))]
+mod wildcard_subscription;
 
 /// An error indicating a problem with registering or unregistering a message listener.
 #[derive(Clone, Debug)]
@@ -120,6 +195,9 @@ pub struct CallOptions {
     message_id: Option<UUID>,
     token: Option<String>,
     priority: Option<UPriority>,
+    rpc_priority_policy: RpcPriorityPolicy,
+    idempotency_key: Option<String>,
+    session_id: Option<String>,
 }
 
 impl CallOptions {
@@ -159,9 +237,55 @@ impl CallOptions {
             message_id,
             token,
             priority,
+            rpc_priority_policy: RpcPriorityPolicy::default(),
+            idempotency_key: None,
+            session_id: None,
         }
     }
 
+    /// Creates new call options for an RPC Request using a time-to-live that is derived from the
+    /// request's priority.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeouts` - The table to look up the time-to-live in.
+    /// * `message_id` - The identifier to use for the message or `None` to use a generated identifier.
+    /// * `token` - The token to use for authenticating to infrastructure and service endpoints.
+    /// * `priority` - The message's priority or `None` to use the default priority for RPC Requests
+    ///                ([`UPriority::UPRIORITY_CS4`]).
+    ///
+    /// # Returns
+    ///
+    /// Options suitable for invoking an RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UPriority, communication::{CallOptions, PriorityTimeouts}};
+    ///
+    /// let timeouts = PriorityTimeouts::default();
+    /// let options = CallOptions::for_rpc_request_with_timeouts(&timeouts, None, None, None);
+    /// assert_eq!(options.ttl(), 2_000);
+    /// assert_eq!(options.priority(), None);
+    ///
+    /// let options = CallOptions::for_rpc_request_with_timeouts(
+    ///     &timeouts.with_ttl(UPriority::UPRIORITY_CS5, 300),
+    ///     None,
+    ///     None,
+    ///     Some(UPriority::UPRIORITY_CS5),
+    /// );
+    /// assert_eq!(options.ttl(), 300);
+    /// ```
+    pub fn for_rpc_request_with_timeouts(
+        timeouts: &PriorityTimeouts,
+        message_id: Option<UUID>,
+        token: Option<String>,
+        priority: Option<UPriority>,
+    ) -> Self {
+        let ttl = timeouts.ttl(priority.unwrap_or(UPriority::UPRIORITY_CS4));
+        Self::for_rpc_request(ttl, message_id, token, priority)
+    }
+
     /// Creates new call options for a Notification message.
     ///
     /// # Arguments
@@ -195,6 +319,9 @@ impl CallOptions {
             message_id,
             token: None,
             priority,
+            rpc_priority_policy: RpcPriorityPolicy::default(),
+            idempotency_key: None,
+            session_id: None,
         }
     }
 
@@ -231,6 +358,9 @@ impl CallOptions {
             message_id,
             token: None,
             priority,
+            rpc_priority_policy: RpcPriorityPolicy::default(),
+            idempotency_key: None,
+            session_id: None,
         }
     }
 
@@ -253,6 +383,141 @@ impl CallOptions {
     pub fn priority(&self) -> Option<UPriority> {
         self.priority
     }
+
+    /// Sets the policy to apply if the priority is lower than the minimum priority
+    /// required for RPC messages.
+    ///
+    /// The default policy is [`RpcPriorityPolicy::Reject`], i.e. RPC clients fail to
+    /// send a request with a priority lower than CS4.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{RpcPriorityPolicy, UPriority, communication::CallOptions};
+    ///
+    /// let options = CallOptions::for_rpc_request(15_000, None, None, Some(UPriority::UPRIORITY_CS2))
+    ///     .with_rpc_priority_policy(RpcPriorityPolicy::Upgrade);
+    /// assert_eq!(options.rpc_priority_policy(), RpcPriorityPolicy::Upgrade);
+    /// ```
+    pub fn with_rpc_priority_policy(mut self, policy: RpcPriorityPolicy) -> Self {
+        self.rpc_priority_policy = policy;
+        self
+    }
+
+    /// Gets the policy to apply if the priority is lower than the minimum priority
+    /// required for RPC messages.
+    pub fn rpc_priority_policy(&self) -> RpcPriorityPolicy {
+        self.rpc_priority_policy
+    }
+
+    /// Sets the key that identifies repeated invocations of the same (state changing) operation.
+    ///
+    /// Clients should use the same key when retrying an RPC Request, so that services supporting
+    /// idempotency keys can detect the repeated invocation and respond with the outcome of the
+    /// original invocation instead of performing the operation again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::communication::CallOptions;
+    ///
+    /// let options = CallOptions::for_rpc_request(15_000, None, None, None)
+    ///     .with_idempotency_key("unlock-door-4711");
+    /// assert_eq!(options.idempotency_key(), Some("unlock-door-4711"));
+    /// ```
+    pub fn with_idempotency_key<T: Into<String>>(mut self, key: T) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Gets the key that identifies repeated invocations of the same operation.
+    pub fn idempotency_key(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
+
+    /// Sets the identifier of the session that the RPC Request belongs to.
+    ///
+    /// Session identifiers are handed out by stateful services by means of
+    /// [`open_session`](`crate::communication::open_session`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::communication::CallOptions;
+    ///
+    /// let options = CallOptions::for_rpc_request(15_000, None, None, None)
+    ///     .with_session_id("diag-session-1");
+    /// assert_eq!(options.session_id(), Some("diag-session-1"));
+    /// ```
+    pub fn with_session_id<T: Into<String>>(mut self, session_id: T) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Gets the identifier of the session that the RPC Request belongs to.
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+}
+
+/// Default time-to-live values for messages, per priority class.
+///
+/// Higher priority classes are used for more time critical interactions, which is why
+/// the default table assigns them shorter time-to-live values:
+///
+/// | Priority | TTL (ms) |
+/// |----------|----------|
+/// | CS0      | 30000    |
+/// | CS1      | 20000    |
+/// | CS2      | 10000    |
+/// | CS3      | 5000     |
+/// | CS4      | 2000     |
+/// | CS5      | 500      |
+/// | CS6      | 200      |
+///
+/// Deployments can adapt the values to their latency budgets by means of [`PriorityTimeouts::with_ttl`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriorityTimeouts {
+    // indexed by priority class, i.e. CS0 to CS6
+    ttls: [u32; 7],
+}
+
+impl Default for PriorityTimeouts {
+    fn default() -> Self {
+        PriorityTimeouts {
+            ttls: [30_000, 20_000, 10_000, 5_000, 2_000, 500, 200],
+        }
+    }
+}
+
+impl PriorityTimeouts {
+    /// Sets the time-to-live to use for a priority class.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority class. [`UPriority::UPRIORITY_UNSPECIFIED`] is treated like
+    ///                [`UPriority::UPRIORITY_CS1`], which is the default priority of uProtocol messages.
+    /// * `ttl` - The time-to-live in milliseconds.
+    pub fn with_ttl(mut self, priority: UPriority, ttl: u32) -> Self {
+        self.ttls[Self::index(priority)] = ttl;
+        self
+    }
+
+    /// Gets the time-to-live to use for a priority class.
+    ///
+    /// # Returns
+    ///
+    /// The time-to-live in milliseconds.
+    pub fn ttl(&self, priority: UPriority) -> u32 {
+        self.ttls[Self::index(priority)]
+    }
+
+    fn index(priority: UPriority) -> usize {
+        match priority {
+            UPriority::UPRIORITY_UNSPECIFIED => 1,
+            _ => priority.value() as usize - 1,
+        }
+    }
 }
 
 /// A wrapper around (raw) message payload data and the corresponding payload format.
@@ -373,6 +638,7 @@ impl UPayload {
 /// * ttl
 /// * message ID
 /// * priority
+#[cfg(any(feature = "notification", feature = "pubsub"))]
 pub(crate) fn apply_common_options(
     call_options: CallOptions,
     message_builder: &mut UMessageBuilder,
@@ -0,0 +1,406 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Notify;
+use tracing::{debug, info};
+
+use crate::{UAttributes, UListener, UMessage, UMessageType, UUri, UUID};
+
+use super::{CallOptions, NotificationError, Notifier, RegistrationError, UPayload};
+
+fn entity_uri(uri: &UUri) -> UUri {
+    UUri {
+        resource_id: 0,
+        ..uri.to_owned()
+    }
+}
+
+/// Acknowledges the receipt of a notification that has been sent by means of
+/// [`AckCollector::notify_all`].
+///
+/// The acknowledgement is a notification that is sent back to the originator of the received
+/// notification. It contains the received notification's message ID as payload.
+///
+/// # Arguments
+///
+/// * `notifier` - The notifier to send the acknowledgement with.
+/// * `ack_resource_id` - The resource ID that the originator expects acknowledgements to be sent from.
+/// * `notification_attributes` - The attributes of the notification to acknowledge.
+///
+/// # Errors
+///
+/// Returns an error if the notification does not contain a message ID or source address,
+/// or if the acknowledgement could not be sent.
+pub async fn acknowledge_notification(
+    notifier: &dyn Notifier,
+    ack_resource_id: u16,
+    notification_attributes: &UAttributes,
+) -> Result<(), NotificationError> {
+    let (Some(id), Some(originator)) = (
+        notification_attributes.id.as_ref(),
+        notification_attributes.source.as_ref(),
+    ) else {
+        return Err(NotificationError::InvalidArgument(
+            "notification has no message ID or source".to_string(),
+        ));
+    };
+    let payload = UPayload::try_from_protobuf(id.to_owned())
+        .map_err(|e| NotificationError::InvalidArgument(e.to_string()))?;
+    notifier
+        .notify(
+            ack_resource_id,
+            &entity_uri(originator),
+            CallOptions::for_notification(None, None, None),
+            Some(payload),
+        )
+        .await
+}
+
+/// The outcome of sending a notification to multiple recipients.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AckReport {
+    /// The recipients that have acknowledged the notification.
+    pub confirmed: Vec<UUri>,
+    /// The recipients that have not acknowledged the notification in time, or that
+    /// the notification could not be sent to.
+    pub missing: Vec<UUri>,
+}
+
+impl AckReport {
+    /// Checks if all recipients have acknowledged the notification.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+struct PendingAck {
+    recipient: UUri,
+    confirmed: bool,
+}
+
+/// Sends a notification to multiple recipients and collects their acknowledgements.
+///
+/// This supports workflows that require proof of delivery to all recipients, e.g. safety related
+/// commands sent to a group of controllers. Each recipient receives its own notification message and
+/// acknowledges its receipt by means of [`acknowledge_notification`]. The collector correlates the
+/// acknowledgements with the notifications that have been sent and reports which recipients have
+/// confirmed the receipt within a given amount of time.
+///
+/// The collector needs to [listen](`Self::start_listening`) for the acknowledgements of all recipients
+/// before sending the notification.
+pub struct AckCollector {
+    ack_resource_id: u16,
+    // message ID of notification -> recipient
+    pending_acks: Mutex<HashMap<UUID, PendingAck>>,
+    ack_received: Notify,
+}
+
+impl AckCollector {
+    /// Creates a new collector.
+    ///
+    /// # Arguments
+    ///
+    /// * `ack_resource_id` - The resource ID that recipients send their acknowledgements from.
+    pub fn new(ack_resource_id: u16) -> Self {
+        AckCollector {
+            ack_resource_id,
+            pending_acks: Mutex::new(HashMap::new()),
+            ack_received: Notify::new(),
+        }
+    }
+
+    fn ack_topic(&self, recipient: &UUri) -> UUri {
+        UUri {
+            resource_id: self.ack_resource_id as u32,
+            ..recipient.to_owned()
+        }
+    }
+
+    /// Starts listening for the acknowledgements of recipients.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the collector could not be registered for any of the recipients.
+    pub async fn start_listening(
+        self: &Arc<Self>,
+        notifier: &dyn Notifier,
+        recipients: &[UUri],
+    ) -> Result<(), RegistrationError> {
+        for recipient in recipients {
+            notifier
+                .start_listening(&self.ack_topic(recipient), self.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Stops listening for the acknowledgements of recipients.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the collector could not be unregistered for any of the recipients.
+    pub async fn stop_listening(
+        self: &Arc<Self>,
+        notifier: &dyn Notifier,
+        recipients: &[UUri],
+    ) -> Result<(), RegistrationError> {
+        for recipient in recipients {
+            notifier
+                .stop_listening(&self.ack_topic(recipient), self.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn all_confirmed(&self, message_ids: &[UUID]) -> bool {
+        self.pending_acks.lock().map_or(false, |pending_acks| {
+            message_ids.iter().all(|id| {
+                pending_acks
+                    .get(id)
+                    .map_or(true, |pending_ack| pending_ack.confirmed)
+            })
+        })
+    }
+
+    /// Sends a notification to multiple recipients and waits for their acknowledgements.
+    ///
+    /// Each recipient is sent a separate notification message with its own message ID. The message IDs
+    /// contained in the given call options are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `notifier` - The notifier to send the notifications with.
+    /// * `resource_id` - The (local) resource ID to send the notifications from.
+    /// * `recipients` - The uEntities to notify.
+    /// * `call_options` - Options to include in the notification messages.
+    /// * `payload` - The payload to include in the notification messages.
+    /// * `timeout` - The maximum amount of time to wait for all acknowledgements.
+    ///
+    /// # Returns
+    ///
+    /// A report indicating which recipients have confirmed the receipt of the notification.
+    pub async fn notify_all(
+        &self,
+        notifier: &dyn Notifier,
+        resource_id: u16,
+        recipients: &[UUri],
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+        timeout: Duration,
+    ) -> AckReport {
+        let mut report = AckReport::default();
+        let mut message_ids = vec![];
+        for recipient in recipients {
+            let message_id = UUID::build();
+            if let Ok(mut pending_acks) = self.pending_acks.lock() {
+                pending_acks.insert(
+                    message_id.clone(),
+                    PendingAck {
+                        recipient: entity_uri(recipient),
+                        confirmed: false,
+                    },
+                );
+            }
+            let options = CallOptions {
+                message_id: Some(message_id.clone()),
+                ..call_options.clone()
+            };
+            match notifier
+                .notify(resource_id, recipient, options, payload.clone())
+                .await
+            {
+                Ok(()) => message_ids.push(message_id),
+                Err(e) => {
+                    info!(recipient = %recipient, "failed to send notification: {}", e);
+                    if let Ok(mut pending_acks) = self.pending_acks.lock() {
+                        pending_acks.remove(&message_id);
+                    }
+                    report.missing.push(recipient.to_owned());
+                }
+            }
+        }
+
+        let _ = tokio::time::timeout(timeout, async {
+            loop {
+                // register for wake-up before checking in order to not miss any acknowledgement
+                let ack_received = self.ack_received.notified();
+                if self.all_confirmed(&message_ids) {
+                    break;
+                }
+                ack_received.await;
+            }
+        })
+        .await;
+
+        if let Ok(mut pending_acks) = self.pending_acks.lock() {
+            for pending_ack in message_ids.iter().filter_map(|id| pending_acks.remove(id)) {
+                if pending_ack.confirmed {
+                    report.confirmed.push(pending_ack.recipient);
+                } else {
+                    report.missing.push(pending_ack.recipient);
+                }
+            }
+        }
+        report
+    }
+}
+
+#[async_trait]
+impl UListener for AckCollector {
+    async fn on_receive(&self, msg: UMessage) {
+        let attributes = msg.attributes.get_or_default();
+        if attributes.type_.enum_value_or_default() != UMessageType::UMESSAGE_TYPE_NOTIFICATION {
+            return;
+        }
+        let Some(sender) = attributes.source.as_ref() else {
+            return;
+        };
+        let Ok(message_id) = msg.extract_protobuf::<UUID>() else {
+            debug!("ignoring acknowledgement without message ID");
+            return;
+        };
+        let Ok(mut pending_acks) = self.pending_acks.lock() else {
+            return;
+        };
+        match pending_acks.get_mut(&message_id) {
+            Some(pending_ack) if pending_ack.recipient == entity_uri(sender) => {
+                pending_ack.confirmed = true;
+                self.ack_received.notify_waiters();
+            }
+            Some(_) => {
+                info!(sender = %sender, "ignoring acknowledgement sent by other uEntity than recipient");
+            }
+            None => {
+                debug!(id = %message_id, "ignoring acknowledgement for unknown notification");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::communication::MockNotifier;
+    use crate::UMessageBuilder;
+
+    fn uri(uri: &str) -> UUri {
+        UUri::try_from(uri).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_acknowledge_notification_sends_message_id_to_originator() {
+        let notification =
+            UMessageBuilder::notification(uri("//vehicle/A100/1/8001"), uri("//vehicle/B100/1/0"))
+                .build()
+                .unwrap();
+        let expected_id = notification.attributes.id.clone().unwrap();
+        let mut notifier = MockNotifier::new();
+        notifier
+            .expect_notify()
+            .once()
+            .withf(move |resource_id, destination, _options, payload| {
+                *resource_id == 0x8100
+                    && *destination == uri("//vehicle/A100/1/0")
+                    && payload.as_ref().is_some_and(|payload| {
+                        payload.extract_protobuf::<UUID>().unwrap() == expected_id
+                    })
+            })
+            .returning(|_resource_id, _destination, _options, _payload| Ok(()));
+
+        assert!(acknowledge_notification(
+            &notifier,
+            0x8100,
+            notification.attributes.as_ref().unwrap()
+        )
+        .await
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_notify_all_reports_missing_acknowledgements() {
+        // GIVEN a collector and two recipients
+        let collector = Arc::new(AckCollector::new(0x8100));
+        let recipients = [uri("//vehicle/B100/1/0"), uri("//vehicle/C100/1/0")];
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut notifier = MockNotifier::new();
+        notifier.expect_notify().times(2).returning(
+            move |_resource_id, destination, options, _payload| {
+                tx.send((destination.to_owned(), options.message_id().unwrap()))
+                    .unwrap();
+                Ok(())
+            },
+        );
+
+        // WHEN only the first recipient acknowledges the notification
+        let collector_clone = collector.clone();
+        tokio::spawn(async move {
+            while let Some((recipient, message_id)) = rx.recv().await {
+                if recipient.ue_id != 0xB100 {
+                    continue;
+                }
+                let ack = UMessageBuilder::notification(
+                    UUri {
+                        resource_id: 0x8100,
+                        ..recipient
+                    },
+                    uri("//vehicle/A100/1/0"),
+                )
+                .build_with_wrapped_protobuf_payload(&message_id)
+                .unwrap();
+                collector_clone.on_receive(ack).await;
+            }
+        });
+        let report = collector
+            .notify_all(
+                &notifier,
+                0x8001,
+                &recipients,
+                CallOptions::for_notification(None, None, None),
+                None,
+                Duration::from_millis(200),
+            )
+            .await;
+
+        // THEN the report contains the recipient that has not acknowledged the notification
+        assert!(!report.is_complete());
+        assert_eq!(report.confirmed, vec![recipients[0].clone()]);
+        assert_eq!(report.missing, vec![recipients[1].clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_on_receive_ignores_acknowledgement_from_other_entity() {
+        let collector = AckCollector::new(0x8100);
+        let message_id = UUID::build();
+        collector.pending_acks.lock().unwrap().insert(
+            message_id.clone(),
+            PendingAck {
+                recipient: uri("//vehicle/B100/1/0"),
+                confirmed: false,
+            },
+        );
+        let ack =
+            UMessageBuilder::notification(uri("//vehicle/D100/1/8100"), uri("//vehicle/A100/1/0"))
+                .build_with_wrapped_protobuf_payload(&message_id)
+                .unwrap();
+
+        collector.on_receive(ack).await;
+
+        assert!(!collector.all_confirmed(&[message_id]));
+    }
+}
@@ -0,0 +1,373 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use protobuf::well_known_types::struct_::{value::Kind, Struct, Value};
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::umessage::UMessageError;
+
+use super::{CallOptions, PubSubError, Publisher, UPayload};
+
+const FIELD_COUNT: &str = "count";
+const FIELD_MIN: &str = "min";
+const FIELD_MAX: &str = "max";
+const FIELD_AVG: &str = "avg";
+
+/// Statistics about the numeric samples that have been recorded for a topic within a window.
+///
+/// The statistics are published as a `google.protobuf.Struct` containing the number fields
+/// `count`, `min`, `max` and `avg`.
+///
+/// # Examples
+///
+/// ```rust
+/// use protobuf::well_known_types::struct_::Struct;
+/// use up_rust::communication::SampleStatistics;
+///
+/// let mut statistics = SampleStatistics::default();
+/// statistics.add(2.0);
+/// statistics.add(4.0);
+///
+/// let encoded = Struct::from(&statistics);
+/// let decoded = SampleStatistics::try_from(&encoded).unwrap();
+/// assert_eq!(decoded.count(), 2);
+/// assert_eq!(decoded.avg(), 3.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SampleStatistics {
+    count: u64,
+    min: f64,
+    max: f64,
+    sum: f64,
+}
+
+impl SampleStatistics {
+    /// Adds a sample.
+    pub fn add(&mut self, sample: f64) {
+        if self.count == 0 {
+            self.min = sample;
+            self.max = sample;
+        } else {
+            self.min = self.min.min(sample);
+            self.max = self.max.max(sample);
+        }
+        self.count += 1;
+        self.sum += sample;
+    }
+
+    /// Gets the number of samples.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Gets the smallest sample, or 0.0 if no samples have been added.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Gets the largest sample, or 0.0 if no samples have been added.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Gets the arithmetic mean of the samples, or 0.0 if no samples have been added.
+    pub fn avg(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+}
+
+impl From<&SampleStatistics> for Struct {
+    fn from(value: &SampleStatistics) -> Self {
+        let mut result = Struct::new();
+        for (name, number) in [
+            (FIELD_COUNT, value.count as f64),
+            (FIELD_MIN, value.min),
+            (FIELD_MAX, value.max),
+            (FIELD_AVG, value.avg()),
+        ] {
+            result.fields.insert(
+                name.to_string(),
+                Value {
+                    kind: Some(Kind::NumberValue(number)),
+                    ..Default::default()
+                },
+            );
+        }
+        result
+    }
+}
+
+impl TryFrom<&Struct> for SampleStatistics {
+    type Error = UMessageError;
+
+    fn try_from(value: &Struct) -> Result<Self, Self::Error> {
+        let number = |name: &str| match value.fields.get(name).and_then(|v| v.kind.as_ref()) {
+            Some(Kind::NumberValue(number)) => Ok(*number),
+            _ => Err(UMessageError::PayloadError(format!(
+                "statistics do not contain number field [{name}]"
+            ))),
+        };
+        let count = number(FIELD_COUNT)? as u64;
+        Ok(SampleStatistics {
+            count,
+            min: number(FIELD_MIN)?,
+            max: number(FIELD_MAX)?,
+            sum: number(FIELD_AVG)? * count as f64,
+        })
+    }
+}
+
+/// Publishes statistics about numeric samples instead of the samples themselves.
+///
+/// Sensors producing values at a high frequency can put a significant load on the bus.
+/// The aggregating publisher buffers the samples recorded for each topic and periodically
+/// publishes [statistics](`SampleStatistics`) about the samples recorded within the last window.
+/// A window is closed early if the number of samples recorded for a topic reaches a
+/// [configurable limit](`Self::with_max_samples`).
+///
+/// Periodic publication requires the publisher's [timer](`Self::run_timer`) to be running.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use up_rust::communication::{AggregatingPublisher, Publisher};
+///
+/// async fn publish_wheel_speed(publisher: Arc<dyn Publisher>) {
+///     let aggregating_publisher = Arc::new(
+///         AggregatingPublisher::new(publisher, Duration::from_secs(1)).with_max_samples(500),
+///     );
+///     let timer = aggregating_publisher.clone();
+///     tokio::spawn(async move { timer.run_timer().await });
+///
+///     for speed in [12.3, 12.5, 12.4] {
+///         aggregating_publisher.record(0x8001, speed).await.unwrap();
+///     }
+/// }
+/// ```
+pub struct AggregatingPublisher {
+    publisher: Arc<dyn Publisher>,
+    window: Duration,
+    max_samples: Option<u64>,
+    // resource ID -> statistics of current window
+    windows: Mutex<HashMap<u16, SampleStatistics>>,
+}
+
+impl AggregatingPublisher {
+    /// Creates a new publisher.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The publisher to use for publishing the statistics.
+    /// * `window` - The amount of time to collect samples for before publishing statistics.
+    pub fn new(publisher: Arc<dyn Publisher>, window: Duration) -> Self {
+        AggregatingPublisher {
+            publisher,
+            window,
+            max_samples: None,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the number of samples after which the statistics for a topic are published,
+    /// regardless of the window.
+    pub fn with_max_samples(mut self, max_samples: u64) -> Self {
+        self.max_samples = Some(max_samples);
+        self
+    }
+
+    /// Records a sample for a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_id` - The (local) resource ID of the topic to publish statistics to.
+    /// * `sample` - The sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of samples for the topic has reached the limit and
+    /// the statistics could not be published.
+    pub async fn record(&self, resource_id: u16, sample: f64) -> Result<(), PubSubError> {
+        let full_window = {
+            let mut windows = self.windows.lock().await;
+            let statistics = windows.entry(resource_id).or_default();
+            statistics.add(sample);
+            if self
+                .max_samples
+                .is_some_and(|max_samples| statistics.count() >= max_samples)
+            {
+                windows.remove(&resource_id)
+            } else {
+                None
+            }
+        };
+        match full_window {
+            Some(statistics) => self.publish(resource_id, &statistics).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Publishes the statistics for all topics that samples have been recorded for
+    /// and starts a new window.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error that occurred while publishing. The statistics of
+    /// topics that could not be published are discarded.
+    pub async fn flush(&self) -> Result<(), PubSubError> {
+        let windows: Vec<(u16, SampleStatistics)> = self.windows.lock().await.drain().collect();
+        let mut result = Ok(());
+        for (resource_id, statistics) in windows {
+            if let Err(e) = self.publish(resource_id, &statistics).await {
+                info!(resource_id, "failed to publish statistics: {}", e);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Periodically [flushes](`Self::flush`) the recorded samples.
+    ///
+    /// The returned future never completes. It can be spawned on a runtime and aborted
+    /// when no more samples are being recorded.
+    pub async fn run_timer(&self) {
+        let mut ticker = tokio::time::interval(self.window);
+        // the first tick completes immediately
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let _ = self.flush().await;
+        }
+    }
+
+    async fn publish(
+        &self,
+        resource_id: u16,
+        statistics: &SampleStatistics,
+    ) -> Result<(), PubSubError> {
+        debug!(
+            resource_id,
+            count = statistics.count(),
+            "publishing sample statistics"
+        );
+        let payload = UPayload::try_from_protobuf(Struct::from(statistics))
+            .map_err(|e| PubSubError::InvalidArgument(e.to_string()))?;
+        self.publisher
+            .publish(
+                resource_id,
+                CallOptions::for_publish(None, None, None),
+                Some(payload),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingPublisher {
+        published: std::sync::Mutex<Vec<(u16, SampleStatistics)>>,
+    }
+
+    #[async_trait]
+    impl Publisher for RecordingPublisher {
+        async fn publish(
+            &self,
+            resource_id: u16,
+            _call_options: CallOptions,
+            payload: Option<UPayload>,
+        ) -> Result<(), PubSubError> {
+            let value: Struct = payload.unwrap().extract_protobuf().unwrap();
+            self.published
+                .lock()
+                .unwrap()
+                .push((resource_id, SampleStatistics::try_from(&value).unwrap()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_statistics_conversion_fails_for_missing_field() {
+        let mut encoded = Struct::from(&SampleStatistics::default());
+        encoded.fields.remove(FIELD_MAX);
+        assert!(SampleStatistics::try_from(&encoded)
+            .is_err_and(|e| matches!(e, UMessageError::PayloadError(_msg))));
+    }
+
+    #[tokio::test]
+    async fn test_flush_publishes_statistics_per_topic() {
+        // GIVEN an aggregating publisher
+        let publisher = Arc::new(RecordingPublisher::default());
+        let aggregating_publisher =
+            AggregatingPublisher::new(publisher.clone(), Duration::from_secs(60));
+
+        // WHEN recording samples for two topics
+        for sample in [3.0, 1.0, 2.0] {
+            aggregating_publisher.record(0x8001, sample).await.unwrap();
+        }
+        aggregating_publisher.record(0x8002, -5.0).await.unwrap();
+        assert!(publisher.published.lock().unwrap().is_empty());
+        // and flushing the samples
+        aggregating_publisher.flush().await.unwrap();
+
+        // THEN the statistics for each topic have been published
+        let mut published = publisher.published.lock().unwrap().clone();
+        published.sort_by_key(|(resource_id, _statistics)| *resource_id);
+        assert_eq!(published.len(), 2);
+        let (resource_id, statistics) = published[0];
+        assert_eq!(resource_id, 0x8001);
+        assert_eq!(statistics.count(), 3);
+        assert_eq!(statistics.min(), 1.0);
+        assert_eq!(statistics.max(), 3.0);
+        assert_eq!(statistics.avg(), 2.0);
+        assert_eq!(published[1].1.count(), 1);
+        assert_eq!(published[1].1.min(), -5.0);
+
+        // and a new window has been started
+        aggregating_publisher.flush().await.unwrap();
+        assert_eq!(publisher.published.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_record_publishes_statistics_when_reaching_max_samples() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let aggregating_publisher =
+            AggregatingPublisher::new(publisher.clone(), Duration::from_secs(60))
+                .with_max_samples(2);
+
+        for sample in [1.0, 2.0, 3.0] {
+            aggregating_publisher.record(0x8001, sample).await.unwrap();
+        }
+
+        let published = publisher.published.lock().unwrap().clone();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].1.count(), 2);
+        assert_eq!(published[0].1.avg(), 1.5);
+    }
+}
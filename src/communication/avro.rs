@@ -0,0 +1,221 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+// Support for payloads that are encoded using [Apache Avro](https://avro.apache.org/).
+//
+// The uProtocol specification does not define a dedicated payload format for Avro.
+// Avro encoded data is therefore carried in payloads of format `UPAYLOAD_FORMAT_RAW`,
+// using Avro's [single object encoding](https://avro.apache.org/docs/1.11.1/specification/#single-object-encoding).
+// The encoding prefixes the data with the fingerprint of the schema that has been used for
+// writing the data, which allows consumers to verify that the data can be read using the schema
+// that they have registered for a particular topic or method.
+
+use apache_avro::{from_value, to_value, GenericSingleObjectReader, GenericSingleObjectWriter};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{UMessageError, UPayloadFormat};
+
+use super::UPayload;
+
+pub use apache_avro::Schema as AvroSchema;
+
+impl UPayload {
+    /// Creates a new payload from a value using Avro's single object encoding.
+    ///
+    /// The resulting payload will have `UPayloadFormat::UPAYLOAD_FORMAT_RAW`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to encode.
+    /// * `schema` - The Avro schema to use for encoding the value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value does not comply with the given schema or
+    /// if it cannot be serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{communication::{AvroSchema, UPayload}, UPayloadFormat};
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Temperature {
+    ///     celsius: f64,
+    /// }
+    ///
+    /// let schema = AvroSchema::parse_str(r#"
+    ///     {"type": "record", "name": "Temperature", "fields": [{"name": "celsius", "type": "double"}]}
+    /// "#).unwrap();
+    /// let payload = UPayload::try_from_avro(&Temperature { celsius: 21.5 }, &schema).unwrap();
+    /// assert_eq!(payload.payload_format(), UPayloadFormat::UPAYLOAD_FORMAT_RAW);
+    /// ```
+    pub fn try_from_avro<T: Serialize>(
+        value: &T,
+        schema: &AvroSchema,
+    ) -> Result<Self, UMessageError> {
+        let avro_value = to_value(value)
+            .map_err(Self::avro_error("value cannot be serialized"))?
+            .resolve(schema)
+            .map_err(Self::avro_error("value does not match Avro schema"))?;
+        let mut writer = GenericSingleObjectWriter::new_with_capacity(schema, 64)
+            .map_err(Self::avro_error("invalid Avro schema"))?;
+        let mut buf = vec![];
+        writer
+            .write_value(avro_value, &mut buf)
+            .map_err(Self::avro_error("failed to encode Avro payload"))?;
+        Ok(UPayload::new(buf, UPayloadFormat::UPAYLOAD_FORMAT_RAW))
+    }
+
+    /// Extracts a value from an Avro encoded payload.
+    ///
+    /// This function will only succeed if the payload format is `UPayloadFormat::UPAYLOAD_FORMAT_RAW`
+    /// and the payload data has been encoded using Avro's single object encoding with the given schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The Avro schema that the data is expected to have been written with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload has an unsupported format, if it has been written using
+    /// a different schema or if the data cannot be deserialized into the target type `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::communication::{AvroSchema, UPayload};
+    ///
+    /// #[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+    /// struct Temperature {
+    ///     celsius: f64,
+    /// }
+    ///
+    /// let schema = AvroSchema::parse_str(r#"
+    ///     {"type": "record", "name": "Temperature", "fields": [{"name": "celsius", "type": "double"}]}
+    /// "#).unwrap();
+    /// let payload = UPayload::try_from_avro(&Temperature { celsius: 21.5 }, &schema).unwrap();
+    /// let temperature: Temperature = payload.extract_avro(&schema).unwrap();
+    /// assert_eq!(temperature, Temperature { celsius: 21.5 });
+    /// ```
+    pub fn extract_avro<T: DeserializeOwned>(
+        &self,
+        schema: &AvroSchema,
+    ) -> Result<T, UMessageError> {
+        if self.payload_format != UPayloadFormat::UPAYLOAD_FORMAT_RAW {
+            return Err(UMessageError::PayloadError(format!(
+                "Avro data must be carried in payload of format {}",
+                UPayloadFormat::UPAYLOAD_FORMAT_RAW
+                    .to_media_type()
+                    .unwrap_or_default()
+            )));
+        }
+        let reader = GenericSingleObjectReader::new(schema.to_owned())
+            .map_err(Self::avro_error("invalid Avro schema"))?;
+        let value = reader
+            .read_value(&mut self.payload.as_ref())
+            .map_err(Self::avro_error("failed to decode Avro payload"))?;
+        from_value::<T>(&value).map_err(Self::avro_error("failed to deserialize Avro value"))
+    }
+
+    fn avro_error(msg: &str) -> impl FnOnce(apache_avro::Error) -> UMessageError + '_ {
+        move |e| UMessageError::PayloadError(format!("{msg}: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Position {
+        latitude: f64,
+        longitude: f64,
+        label: Option<String>,
+    }
+
+    const POSITION_SCHEMA: &str = r#"
+    {
+        "type": "record",
+        "name": "Position",
+        "fields": [
+            {"name": "latitude", "type": "double"},
+            {"name": "longitude", "type": "double"},
+            {"name": "label", "type": ["null", "string"], "default": null}
+        ]
+    }
+    "#;
+
+    fn position_schema() -> AvroSchema {
+        AvroSchema::parse_str(POSITION_SCHEMA).unwrap()
+    }
+
+    #[test]
+    fn test_avro_payload_round_trips() {
+        let position = Position {
+            latitude: 48.1,
+            longitude: 11.5,
+            label: Some("Munich".to_string()),
+        };
+        let payload = UPayload::try_from_avro(&position, &position_schema()).unwrap();
+        assert_eq!(
+            payload.payload_format(),
+            UPayloadFormat::UPAYLOAD_FORMAT_RAW
+        );
+        let extracted: Position = payload.extract_avro(&position_schema()).unwrap();
+        assert_eq!(extracted, position);
+    }
+
+    #[test]
+    fn test_try_from_avro_fails_for_value_not_matching_schema() {
+        #[derive(Serialize)]
+        struct Other {
+            name: String,
+        }
+        let value = Other {
+            name: "unknown".to_string(),
+        };
+        assert!(UPayload::try_from_avro(&value, &position_schema()).is_err());
+    }
+
+    #[test]
+    fn test_extract_avro_fails_for_different_schema() {
+        let other_schema = AvroSchema::parse_str(
+            r#"{"type": "record", "name": "Position", "fields": [{"name": "latitude", "type": "double"}]}"#,
+        )
+        .unwrap();
+        let position = Position {
+            latitude: 48.1,
+            longitude: 11.5,
+            label: None,
+        };
+        let payload = UPayload::try_from_avro(&position, &position_schema()).unwrap();
+        assert!(payload.extract_avro::<Position>(&other_schema).is_err());
+    }
+
+    #[test]
+    fn test_extract_avro_fails_for_unsupported_payload_format() {
+        let position = Position {
+            latitude: 48.1,
+            longitude: 11.5,
+            label: None,
+        };
+        let payload = UPayload::try_from_avro(&position, &position_schema()).unwrap();
+        let json_payload = UPayload::new(payload.payload(), UPayloadFormat::UPAYLOAD_FORMAT_JSON);
+        assert!(json_payload
+            .extract_avro::<Position>(&position_schema())
+            .is_err());
+    }
+}
@@ -0,0 +1,204 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::io::{Read, Write};
+
+use bytes::Bytes;
+
+use crate::{UCode, UMessage, UStatus};
+
+/// The name of the [content encoding](`crate::UAttributes::content_encoding`) indicating
+/// that a payload has been compressed using the DEFLATE algorithm (RFC 1951).
+pub const DEFLATE_ENCODING: &str = "deflate";
+
+/// The default maximum number of bytes that a compressed payload may be decompressed to (16 MiB).
+///
+/// Limiting the size of decompressed payloads protects receivers from running out of memory
+/// when processing a small, maliciously crafted payload that decompresses to a huge amount of data.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 16 * 1024 * 1024;
+
+/// Compresses data using the DEFLATE algorithm.
+pub fn deflate(data: &[u8]) -> std::io::Result<Bytes> {
+    let mut encoder = libflate::deflate::Encoder::new(Vec::with_capacity(data.len() / 2));
+    encoder.write_all(data)?;
+    encoder.finish().into_result().map(Bytes::from)
+}
+
+/// Decompresses data that has been compressed using the DEFLATE algorithm.
+///
+/// # Arguments
+///
+/// * `data` - The compressed data.
+/// * `max_size` - The maximum number of bytes that the data may be decompressed to.
+///
+/// # Errors
+///
+/// Returns an error with code [`UCode::RESOURCE_EXHAUSTED`] if the decompressed data exceeds
+/// the maximum size, or an error with code [`UCode::INVALID_ARGUMENT`] if the data cannot be
+/// decompressed.
+pub fn inflate(data: &[u8], max_size: usize) -> Result<Bytes, UStatus> {
+    let decoder = libflate::deflate::Decoder::new(data);
+    let mut decompressed = Vec::with_capacity(data.len().saturating_mul(2).min(max_size));
+    // read one more byte than allowed in order to detect data exceeding the limit
+    decoder
+        .take((max_size as u64).saturating_add(1))
+        .read_to_end(&mut decompressed)
+        .map_err(|e| {
+            UStatus::fail_with_code(
+                UCode::INVALID_ARGUMENT,
+                format!("failed to decompress payload: {e}"),
+            )
+        })?;
+    if decompressed.len() > max_size {
+        return Err(UStatus::fail_with_code(
+            UCode::RESOURCE_EXHAUSTED,
+            format!("decompressed payload exceeds maximum size of {max_size} bytes"),
+        ));
+    }
+    Ok(Bytes::from(decompressed))
+}
+
+/// Compresses a message's payload if it exceeds a given size.
+///
+/// The payload is only replaced, if the compressed payload is actually smaller than the original one.
+///
+/// # Returns
+///
+/// `true` if the payload has been replaced with its compressed version.
+pub(crate) fn deflate_payload(message: &mut UMessage, threshold: usize) -> std::io::Result<bool> {
+    let Some(payload) = message.payload.as_ref().filter(|p| p.len() > threshold) else {
+        return Ok(false);
+    };
+    let compressed = deflate(payload)?;
+    if compressed.len() >= payload.len() {
+        return Ok(false);
+    }
+    message.payload = Some(compressed);
+    message
+        .attributes
+        .mut_or_insert_default()
+        .set_content_encoding(DEFLATE_ENCODING);
+    Ok(true)
+}
+
+/// Decompresses a message's payload according to the message's content encoding.
+///
+/// # Errors
+///
+/// Returns an error if the content encoding is not supported or if the payload cannot be decompressed
+/// to at most `max_size` bytes (see [`inflate`]).
+pub(crate) fn inflate_payload(message: &mut UMessage, max_size: usize) -> Result<(), UStatus> {
+    let Some(encoding) = message
+        .attributes
+        .as_ref()
+        .and_then(|attribs| attribs.content_encoding())
+    else {
+        return Ok(());
+    };
+    if !encoding.eq_ignore_ascii_case(DEFLATE_ENCODING) {
+        return Err(UStatus::fail_with_code(
+            UCode::INVALID_ARGUMENT,
+            format!("unsupported content encoding: {encoding}"),
+        ));
+    }
+    if let Some(payload) = message.payload.as_ref() {
+        message.payload = Some(inflate(payload, max_size)?);
+    }
+    if let Some(attribs) = message.attributes.as_mut() {
+        attribs
+            .special_fields
+            .mut_unknown_fields()
+            .remove(crate::uattributes::CONTENT_ENCODING_FIELD_NUMBER);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UMessageBuilder, UUri};
+
+    #[test]
+    fn test_deflate_inflate_roundtrip() {
+        let data = "compress me ".repeat(100).into_bytes();
+        let compressed = deflate(&data).unwrap();
+        assert!(compressed.len() < data.len());
+        assert_eq!(
+            inflate(&compressed, DEFAULT_MAX_DECOMPRESSED_SIZE).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn test_inflate_fails_for_data_exceeding_max_size() {
+        // GIVEN a small payload which decompresses to 1 MiB
+        let data = vec![0u8; 1024 * 1024];
+        let compressed = deflate(&data).unwrap();
+        assert!(compressed.len() < 10_000);
+
+        // WHEN decompressing the payload using a lower limit
+        let result = inflate(&compressed, data.len() - 1);
+
+        // THEN decompression fails
+        assert!(result.is_err_and(|e| e.get_code() == UCode::RESOURCE_EXHAUSTED));
+        // but succeeds if the limit is not exceeded
+        assert_eq!(inflate(&compressed, data.len()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_payload_compression_respects_threshold() {
+        // GIVEN a message with a 1200 bytes payload
+        let topic = UUri::try_from_parts("vehicle", 0x1000, 0x01, 0x8001).unwrap();
+        let data = "compress me ".repeat(100);
+        let original = UMessageBuilder::publish(topic)
+            .build_with_payload(data.clone(), crate::UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+            .unwrap();
+
+        // WHEN compressing the payload using a higher threshold
+        let mut message = original.clone();
+        // THEN the payload is left untouched
+        assert!(!deflate_payload(&mut message, 2_000).unwrap());
+        assert_eq!(message, original);
+
+        // WHEN compressing the payload using a lower threshold
+        assert!(deflate_payload(&mut message, 1_000).unwrap());
+        // THEN the payload is compressed
+        assert_eq!(
+            message.attributes.get_or_default().content_encoding(),
+            Some(DEFLATE_ENCODING.to_string())
+        );
+        assert!(message.payload.as_ref().unwrap().len() < data.len());
+
+        // and can be restored
+        inflate_payload(&mut message, DEFAULT_MAX_DECOMPRESSED_SIZE).unwrap();
+        assert!(message
+            .attributes
+            .get_or_default()
+            .content_encoding()
+            .is_none());
+        assert_eq!(message.payload, original.payload);
+    }
+
+    #[test]
+    fn test_inflate_payload_fails_for_unsupported_encoding() {
+        let topic = UUri::try_from_parts("vehicle", 0x1000, 0x01, 0x8001).unwrap();
+        let mut message = UMessageBuilder::publish(topic)
+            .build_with_payload("data", crate::UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+            .unwrap();
+        message
+            .attributes
+            .mut_or_insert_default()
+            .set_content_encoding("br");
+        assert!(inflate_payload(&mut message, DEFAULT_MAX_DECOMPRESSED_SIZE).is_err());
+    }
+}
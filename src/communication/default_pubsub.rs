@@ -16,7 +16,8 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
     ops::Deref,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
 };
 
 use async_trait::async_trait;
@@ -26,14 +27,17 @@ use crate::{
     core::usubscription::{
         self, State, SubscriptionRequest, USubscription, UnsubscribeRequest, Update,
     },
-    LocalUriProvider, UListener, UMessage, UMessageBuilder, UStatus, UTransport, UUri,
+    diagnostics::SubscriberDiagnostics,
+    ComparableListener, LocalUriProvider, TopicPolicy, UListener, UMessage, UMessageBuilder,
+    UStatus, UTransport, UUri,
 };
 
 use super::{
-    apply_common_options, build_message, pubsub::SubscriptionChangeHandler, CallOptions,
-    InMemoryRpcClient, Notifier, PubSubError, Publisher, RegistrationError, RpcClientUSubscription,
-    SimpleNotifier, Subscriber, UPayload,
+    apply_common_options, build_message, pubsub::SubscriptionChangeHandler, CallOptions, Notifier,
+    PubSubError, Publisher, RegistrationError, Subscriber, SubscriptionOptions, UPayload,
 };
+#[cfg(feature = "rpc-client")]
+use super::{InMemoryRpcClient, RpcClientUSubscription, SimpleNotifier};
 
 #[derive(Clone)]
 struct ComparableSubscriptionChangeHandler {
@@ -70,9 +74,21 @@ impl PartialEq for ComparableSubscriptionChangeHandler {
 
 impl Eq for ComparableSubscriptionChangeHandler {}
 
-#[derive(Default)]
+/// The number of subscription updates that are buffered for each receiver of the update stream.
+const SUBSCRIPTION_UPDATES_CAPACITY: usize = 64;
+
 struct SubscriptionChangeListener {
     subscription_change_handlers: RwLock<HashMap<UUri, ComparableSubscriptionChangeHandler>>,
+    updates: tokio::sync::broadcast::Sender<Update>,
+}
+
+impl Default for SubscriptionChangeListener {
+    fn default() -> Self {
+        SubscriptionChangeListener {
+            subscription_change_handlers: RwLock::new(HashMap::new()),
+            updates: tokio::sync::broadcast::channel(SUBSCRIPTION_UPDATES_CAPACITY).0,
+        }
+    }
 }
 
 impl SubscriptionChangeListener {
@@ -149,6 +165,18 @@ impl SubscriptionChangeListener {
             })
     }
 
+    /// Gets the topics that handlers are registered for.
+    fn topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = self
+            .subscription_change_handlers
+            .read()
+            .map_or(vec![], |handlers| {
+                handlers.keys().map(|topic| topic.to_uri(false)).collect()
+            });
+        topics.sort();
+        topics
+    }
+
     #[cfg(test)]
     fn has_handler(&self, topic: &UUri) -> bool {
         self.subscription_change_handlers
@@ -174,12 +202,13 @@ impl UListener for SubscriptionChangeListener {
             return;
         };
 
-        let Ok(handlers) = self.subscription_change_handlers.read() else {
-            return;
-        };
-        if let Some(handler) = handlers.get(topic) {
-            handler.on_subscription_change(topic.to_owned(), status.to_owned());
+        if let Ok(handlers) = self.subscription_change_handlers.read() {
+            if let Some(handler) = handlers.get(topic) {
+                handler.on_subscription_change(topic.to_owned(), status.to_owned());
+            }
         }
+        // sending fails if nobody is interested in the updates, which is fine
+        let _ = self.updates.send(subscription_update);
     }
 }
 
@@ -187,6 +216,7 @@ impl UListener for SubscriptionChangeListener {
 pub struct SimplePublisher {
     transport: Arc<dyn UTransport>,
     uri_provider: Arc<dyn LocalUriProvider>,
+    topic_policy: TopicPolicy,
 }
 
 impl SimplePublisher {
@@ -200,8 +230,24 @@ impl SimplePublisher {
         SimplePublisher {
             transport,
             uri_provider,
+            topic_policy: TopicPolicy::default(),
         }
     }
+
+    /// Sets the policy to use for checking the resource IDs of the topics to publish to.
+    ///
+    /// The default policy is [`TopicPolicy::Strict`]. Other policies are intended for
+    /// platform components only.
+    pub fn with_topic_policy(mut self, policy: TopicPolicy) -> Self {
+        self.topic_policy = policy;
+        self
+    }
+
+    fn new_builder(&self, resource_id: u16) -> UMessageBuilder {
+        let mut builder = UMessageBuilder::publish(self.uri_provider.get_resource_uri(resource_id));
+        builder.with_topic_policy(self.topic_policy);
+        builder
+    }
 }
 
 #[async_trait]
@@ -212,7 +258,7 @@ impl Publisher for SimplePublisher {
         call_options: CallOptions,
         payload: Option<UPayload>,
     ) -> Result<(), PubSubError> {
-        let mut builder = UMessageBuilder::publish(self.uri_provider.get_resource_uri(resource_id));
+        let mut builder = self.new_builder(resource_id);
         apply_common_options(call_options, &mut builder);
         match build_message(&mut builder, payload) {
             Ok(publish_message) => self
@@ -226,6 +272,142 @@ impl Publisher for SimplePublisher {
             ))),
         }
     }
+
+    async fn publish_all(
+        &self,
+        messages: Vec<(u16, UPayload)>,
+        call_options: CallOptions,
+    ) -> Result<Vec<Result<(), PubSubError>>, PubSubError> {
+        if messages.len() > 1 && call_options.message_id().is_some() {
+            return Err(PubSubError::InvalidArgument(
+                "call options for multiple messages must not contain a message ID".to_string(),
+            ));
+        }
+        let publish_messages = messages
+            .into_iter()
+            .map(|(resource_id, payload)| {
+                let mut builder = self.new_builder(resource_id);
+                apply_common_options(call_options.clone(), &mut builder);
+                build_message(&mut builder, Some(payload)).map_err(|e| {
+                    PubSubError::InvalidArgument(format!(
+                        "failed to create Publish message for resource ID {:#06x}: {}",
+                        resource_id, e
+                    ))
+                })
+            })
+            .collect::<Result<Vec<UMessage>, PubSubError>>()?;
+        Ok(self
+            .transport
+            .send_batch(publish_messages)
+            .await
+            .into_iter()
+            .map(|outcome| outcome.map_err(PubSubError::PublishError))
+            .collect())
+    }
+}
+
+/// Determines how an [`InMemorySubscriber`] handles requests to subscribe to a topic that
+/// one or more other listeners have already been subscribed to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateSubscriptionPolicy {
+    /// Subscribing succeeds and the additional listener is registered. The USubscription service
+    /// is informed about each subscription and unsubscription, regardless of any other listeners
+    /// being subscribed to the same topic.
+    #[default]
+    Allow,
+    /// Subscribing fails with [`RegistrationError::AlreadyExists`].
+    Reject,
+    /// Subscribing succeeds and the additional listener is registered. The USubscription service is
+    /// informed about the first listener subscribing to a topic and the last listener unsubscribing
+    /// from the topic only.
+    ReferenceCounted,
+}
+
+/// The settings of a subscription that is established by means of [`InMemorySubscriber::apply_config`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct SubscriptionConfig {
+    /// The topic to subscribe to.
+    pub topic: String,
+    /// The name of the listener to subscribe.
+    pub listener: String,
+}
+
+/// A [`UListener`] which drops messages that have been sent by the local uEntity.
+struct LocalEchoFilter {
+    local_uri: UUri,
+    listener: Arc<dyn UListener>,
+}
+
+impl LocalEchoFilter {
+    fn is_local_echo(&self, msg: &UMessage) -> bool {
+        msg.attributes
+            .as_ref()
+            .and_then(|attribs| attribs.source.as_ref())
+            .is_some_and(|source| {
+                source.authority_name == self.local_uri.authority_name
+                    && source.ue_id == self.local_uri.ue_id
+                    && source.ue_version_major == self.local_uri.ue_version_major
+            })
+    }
+}
+
+#[async_trait]
+impl UListener for LocalEchoFilter {
+    async fn on_receive(&self, msg: UMessage) {
+        if self.is_local_echo(&msg) {
+            debug!("ignoring message that has been published by local uEntity");
+            return;
+        }
+        self.listener.on_receive(msg).await;
+    }
+}
+
+/// A [`UListener`] which drops messages that arrive sooner than a given period of time after
+/// the message that has last been passed on.
+struct SamplingFilter {
+    sample_period: Duration,
+    last_delivery: Mutex<Option<Instant>>,
+    listener: Arc<dyn UListener>,
+}
+
+impl SamplingFilter {
+    fn new(sample_period: Duration, listener: Arc<dyn UListener>) -> Self {
+        SamplingFilter {
+            sample_period,
+            last_delivery: Mutex::new(None),
+            listener,
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        let Ok(mut last_delivery) = self.last_delivery.lock() else {
+            return true;
+        };
+        let now = Instant::now();
+        match *last_delivery {
+            Some(instant) if now.duration_since(instant) < self.sample_period => false,
+            _ => {
+                *last_delivery = Some(now);
+                true
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl UListener for SamplingFilter {
+    async fn on_receive(&self, msg: UMessage) {
+        if !self.is_due() {
+            debug!("dropping event that has been received within sample period");
+            return;
+        }
+        self.listener.on_receive(msg).await;
+    }
 }
 
 /// A [`Subscriber`] which keeps all information about registered susbcription change handlers in memory.
@@ -233,6 +415,8 @@ impl Publisher for SimplePublisher {
 /// The subscriber requires a (client) implementation of [`USubscription`] in order to inform the local
 /// USubscription service about newly subscribed and unsubscribed topics. It also needs a [`Notifier`]
 /// for receiving notifications about subscription status updates from the local USubscription service.
+/// A subscription change handler passed to [`Subscriber::subscribe`] is invoked with the initial status
+/// returned by the USubscription service as well as for each subsequent update.
 /// Finally, it needs a [`UTransport`] for receiving events that have been published to subscribed topics.
 ///
 /// During [startup](`Self::for_clients`) the subscriber uses the Notifier to register a generic [`UListener`]
@@ -243,12 +427,29 @@ impl Publisher for SimplePublisher {
 /// about the new subscription and a (client provided) subscription change handler is registered with the
 /// listener. When a subscription change notification arrives from the USubscription service, the corresponding
 /// handler is being looked up and invoked.
+///
+/// uEntities that publish events to the same topics that they subscribe to can
+/// [suppress the local echo](`Self::with_local_echo_suppression`) of their own events.
+///
+/// The sample period passed to [`Subscriber::subscribe_with_options`] is forwarded to the USubscription
+/// service as a hint. Subscribers that cannot rely on the service (or the publisher) honoring the hint can
+/// [down-sample events locally](`Self::with_client_side_sampling`).
 pub struct InMemorySubscriber {
     transport: Arc<dyn UTransport>,
-    _uri_provider: Arc<dyn LocalUriProvider>,
+    uri_provider: Arc<dyn LocalUriProvider>,
     usubscription: Arc<dyn USubscription>,
     notifier: Arc<dyn Notifier>,
     subscription_change_listener: Arc<SubscriptionChangeListener>,
+    // (topic, subscribed listener) -> listener registered with transport
+    subscriptions: RwLock<HashMap<(UUri, ComparableListener), Arc<dyn UListener>>>,
+    duplicate_subscription_policy: DuplicateSubscriptionPolicy,
+    suppress_local_echo: bool,
+    client_side_sampling: bool,
+    topic_policy: TopicPolicy,
+    configured_subscriptions: tokio::sync::Mutex<HashMap<SubscriptionConfig, Arc<dyn UListener>>>,
+    // serializes (un)subscribing, so that checking for other listeners subscribed to the same topic and
+    // informing the USubscription service happens atomically
+    registration_lock: tokio::sync::Mutex<()>,
 }
 
 impl InMemorySubscriber {
@@ -261,6 +462,7 @@ impl InMemorySubscriber {
     /// # Errors
     ///
     /// Returns an error if the Notifier cannot register a listener for notifications from the USubscription service.
+    #[cfg(feature = "rpc-client")]
     pub async fn new(
         transport: Arc<dyn UTransport>,
         uri_provider: Arc<dyn LocalUriProvider>,
@@ -294,9 +496,7 @@ impl InMemorySubscriber {
         // register a generic listener for subscription updates
         // whenever a uE later tries to subscribe to a topic, it can provide an optional callback for
         // handling subscription updates for the topic it tries to subscribe to
-        let subscription_change_listener = Arc::new(SubscriptionChangeListener {
-            subscription_change_handlers: RwLock::new(HashMap::new()),
-        });
+        let subscription_change_listener = Arc::new(SubscriptionChangeListener::default());
         notifier
             .start_listening(
                 &usubscription::usubscription_uri(usubscription::RESOURCE_ID_SUBSCRIPTION_CHANGE),
@@ -305,13 +505,67 @@ impl InMemorySubscriber {
             .await?;
         Ok(InMemorySubscriber {
             transport,
-            _uri_provider: uri_provider,
+            uri_provider,
             usubscription,
             notifier,
             subscription_change_listener,
+            subscriptions: RwLock::new(HashMap::new()),
+            duplicate_subscription_policy: DuplicateSubscriptionPolicy::default(),
+            suppress_local_echo: false,
+            client_side_sampling: false,
+            topic_policy: TopicPolicy::default(),
+            configured_subscriptions: tokio::sync::Mutex::new(HashMap::new()),
+            registration_lock: tokio::sync::Mutex::new(()),
         })
     }
 
+    /// Sets the policy to apply when subscribing listeners to a topic that other listeners have
+    /// already been subscribed to.
+    ///
+    /// The default policy is [`DuplicateSubscriptionPolicy::Allow`].
+    pub fn with_duplicate_subscription_policy(
+        mut self,
+        policy: DuplicateSubscriptionPolicy,
+    ) -> Self {
+        self.duplicate_subscription_policy = policy;
+        self
+    }
+
+    /// Sets whether listeners should be invoked for events that have been published by the local uEntity.
+    ///
+    /// If suppression is enabled, listeners subscribed to topics are not invoked for events whose
+    /// source address refers to the uEntity represented by the subscriber's [`LocalUriProvider`].
+    /// Suppression is applied to listeners that are subscribed after this function has been invoked.
+    ///
+    /// Local echo is not suppressed by default.
+    pub fn with_local_echo_suppression(mut self, suppress: bool) -> Self {
+        self.suppress_local_echo = suppress;
+        self
+    }
+
+    /// Sets whether events should be down-sampled locally according to the sample period requested
+    /// when [subscribing](`Subscriber::subscribe_with_options`) to a topic.
+    ///
+    /// If enabled, a listener is not invoked for events that arrive sooner than the requested sample period
+    /// after the event that the listener has last been invoked for. This is useful if the USubscription
+    /// service ignores the sample period hint. Sampling is applied to listeners that are subscribed after
+    /// this function has been invoked.
+    ///
+    /// Events are not down-sampled locally by default.
+    pub fn with_client_side_sampling(mut self, enabled: bool) -> Self {
+        self.client_side_sampling = enabled;
+        self
+    }
+
+    /// Sets the policy to use for checking the resource IDs of the topics to subscribe to.
+    ///
+    /// The default policy is [`TopicPolicy::Strict`]. Other policies are intended for
+    /// platform components only.
+    pub fn with_topic_policy(mut self, policy: TopicPolicy) -> Self {
+        self.topic_policy = policy;
+        self
+    }
+
     /// Stops this client.
     ///
     /// Clears all internal state and unregisters the listener for subscription updates from the USubscription service.
@@ -329,13 +583,231 @@ impl InMemorySubscriber {
             .and_then(|_ok| self.subscription_change_listener.clear())
     }
 
+    /// Unsubscribes all listeners from the topics that they have been subscribed to using this client.
+    ///
+    /// The subscriber tries to unsubscribe all listeners, regardless of whether unsubscribing any
+    /// of the other listeners fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the topics that (some of) the listeners could not be unsubscribed from along with the
+    /// corresponding error. These listeners remain subscribed.
+    pub async fn unsubscribe_all(&self) -> Result<(), Vec<(UUri, RegistrationError)>> {
+        let subscriptions: Vec<(UUri, ComparableListener)> =
+            self.subscriptions.read().map_or(vec![], |subscriptions| {
+                subscriptions.keys().cloned().collect()
+            });
+        let mut errors = vec![];
+        for (topic, listener) in subscriptions {
+            if let Err(e) = self.unsubscribe(&topic, listener.into_inner()).await {
+                debug!(topic = %topic, "failed to unsubscribe listener: {}", e);
+                errors.push((topic, e));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Subscribes and unsubscribes listeners according to a configuration.
+    ///
+    /// Subscriptions that are contained in the configuration but have not been established yet are
+    /// established using the listeners of the given names. Subscriptions that have been established by
+    /// means of a previous configuration but are no longer contained in the configuration are ended.
+    /// Subscriptions that have been established using [`Subscriber::subscribe`] are not affected.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The subscriptions that should be established.
+    /// * `listeners` - The listeners that the subscriptions refer to, by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns the subscriptions that could not be established or ended along with the corresponding error.
+    /// All other subscriptions are established or ended regardless.
+    pub async fn apply_config(
+        &self,
+        config: &[SubscriptionConfig],
+        listeners: &HashMap<String, Arc<dyn UListener>>,
+    ) -> Result<(), Vec<(SubscriptionConfig, RegistrationError)>> {
+        let mut configured_subscriptions = self.configured_subscriptions.lock().await;
+        let mut errors = vec![];
+        let obsolete_subscriptions: Vec<(SubscriptionConfig, Arc<dyn UListener>)> =
+            configured_subscriptions
+                .iter()
+                .filter(|(subscription_config, _listener)| !config.contains(subscription_config))
+                .map(|(subscription_config, listener)| {
+                    (subscription_config.to_owned(), listener.to_owned())
+                })
+                .collect();
+        for (subscription_config, listener) in obsolete_subscriptions {
+            let result = match UUri::try_from(subscription_config.topic.as_str()) {
+                Ok(topic) => self.unsubscribe(&topic, listener).await,
+                Err(e) => Err(RegistrationError::InvalidFilter(e.to_string())),
+            };
+            match result {
+                Ok(()) => {
+                    configured_subscriptions.remove(&subscription_config);
+                }
+                Err(e) => errors.push((subscription_config, e)),
+            }
+        }
+        for subscription_config in config {
+            if configured_subscriptions.contains_key(subscription_config) {
+                continue;
+            }
+            let Some(listener) = listeners.get(&subscription_config.listener) else {
+                errors.push((
+                    subscription_config.to_owned(),
+                    RegistrationError::Unknown(UStatus::fail_with_code(
+                        crate::UCode::NOT_FOUND,
+                        format!("no listener named [{}]", subscription_config.listener),
+                    )),
+                ));
+                continue;
+            };
+            let result = match UUri::try_from(subscription_config.topic.as_str()) {
+                Ok(topic) => self.subscribe(&topic, listener.clone(), None).await,
+                Err(e) => Err(RegistrationError::InvalidFilter(e.to_string())),
+            };
+            match result {
+                Ok(()) => {
+                    configured_subscriptions
+                        .insert(subscription_config.to_owned(), listener.clone());
+                }
+                Err(e) => errors.push((subscription_config.to_owned(), e)),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Gets the configuration of the subscriptions that have been established by means of
+    /// [`Self::apply_config`], ordered by topic.
+    pub async fn export_config(&self) -> Vec<SubscriptionConfig> {
+        let mut config: Vec<SubscriptionConfig> = self
+            .configured_subscriptions
+            .lock()
+            .await
+            .keys()
+            .cloned()
+            .collect();
+        config.sort();
+        config
+    }
+
+    /// Gets a stream of the subscription updates sent by the USubscription service.
+    ///
+    /// The stream contains the updates for all topics, regardless of whether a
+    /// [`SubscriptionChangeHandler`] has been registered for a topic. This allows supervisory
+    /// components to monitor the state of all subscriptions in a central place.
+    ///
+    /// Only updates received after this function has been invoked are contained in the stream.
+    /// A receiver that does not keep up with the updates loses the oldest ones, which is
+    /// indicated by [`tokio::sync::broadcast::error::RecvError::Lagged`].
+    pub fn subscription_updates(&self) -> tokio::sync::broadcast::Receiver<Update> {
+        self.subscription_change_listener.updates.subscribe()
+    }
+
+    /// Gets a snapshot of this subscriber's state.
+    pub fn diagnostics(&self) -> SubscriberDiagnostics {
+        let mut listeners_per_topic: HashMap<String, usize> = HashMap::new();
+        if let Ok(subscriptions) = self.subscriptions.read() {
+            subscriptions.keys().for_each(|(topic, _listener)| {
+                *listeners_per_topic.entry(topic.to_uri(false)).or_default() += 1;
+            });
+        }
+        let mut subscriptions: Vec<(String, usize)> = listeners_per_topic.into_iter().collect();
+        subscriptions.sort();
+        SubscriberDiagnostics {
+            subscriptions,
+            subscription_change_handlers: self.subscription_change_listener.topics(),
+        }
+    }
+
+    fn add_subscription(
+        &self,
+        topic: &UUri,
+        listener: Arc<dyn UListener>,
+        registered_listener: Arc<dyn UListener>,
+    ) {
+        if let Ok(mut subscriptions) = self.subscriptions.write() {
+            subscriptions.insert(
+                (topic.to_owned(), ComparableListener::new(listener)),
+                registered_listener,
+            );
+        }
+    }
+
+    /// Gets the listener to register with the transport for a listener being subscribed to a topic.
+    fn listener_to_register(
+        &self,
+        topic: &UUri,
+        listener: &Arc<dyn UListener>,
+        options: &SubscriptionOptions,
+    ) -> Arc<dyn UListener> {
+        if let Some(registered_listener) = self.registered_listener(topic, listener) {
+            return registered_listener;
+        }
+        let mut listener_to_register = listener.clone();
+        if let Some(sample_period) = options.sample_period.filter(|_| self.client_side_sampling) {
+            listener_to_register =
+                Arc::new(SamplingFilter::new(sample_period, listener_to_register));
+        }
+        if self.suppress_local_echo {
+            listener_to_register = Arc::new(LocalEchoFilter {
+                local_uri: self.uri_provider.get_source_uri(),
+                listener: listener_to_register,
+            });
+        }
+        listener_to_register
+    }
+
+    /// Gets the listener that has been registered with the transport for a listener subscribed to a topic.
+    fn registered_listener(
+        &self,
+        topic: &UUri,
+        listener: &Arc<dyn UListener>,
+    ) -> Option<Arc<dyn UListener>> {
+        self.subscriptions.read().ok().and_then(|subscriptions| {
+            subscriptions
+                .get(&(topic.to_owned(), ComparableListener::new(listener.clone())))
+                .cloned()
+        })
+    }
+
+    /// Checks if any listeners other than the given one are subscribed to a topic.
+    fn has_other_listeners(&self, topic: &UUri, listener: &Arc<dyn UListener>) -> bool {
+        let listener = ComparableListener::new(listener.clone());
+        self.subscriptions.read().map_or(false, |subscriptions| {
+            subscriptions
+                .keys()
+                .any(|(subscribed_topic, subscribed_listener)| {
+                    subscribed_topic == topic && subscribed_listener != &listener
+                })
+        })
+    }
+
+    fn remove_subscription(&self, topic: &UUri, listener: Arc<dyn UListener>) {
+        if let Ok(mut subscriptions) = self.subscriptions.write() {
+            subscriptions.remove(&(topic.to_owned(), ComparableListener::new(listener)));
+        }
+    }
+
     async fn invoke_subscribe(
         &self,
         topic: &UUri,
         subscription_change_handler: Option<Arc<dyn SubscriptionChangeHandler>>,
+        options: &SubscriptionOptions,
     ) -> Result<State, RegistrationError> {
         let subscription_request = SubscriptionRequest {
             topic: Some(topic.to_owned()).into(),
+            attributes: options.to_subscribe_attributes().into(),
             ..Default::default()
         };
         match self.usubscription.subscribe(subscription_request).await {
@@ -343,7 +815,12 @@ impl InMemorySubscriber {
                 Ok(state) if state == State::SUBSCRIBED || state == State::SUBSCRIBE_PENDING => {
                     if let Some(handler) = subscription_change_handler.clone() {
                         self.subscription_change_listener
-                            .add_handler(topic.to_owned(), handler)?;
+                            .add_handler(topic.to_owned(), handler.clone())?;
+                        // let the handler know about the initial status of the subscription
+                        handler.on_subscription_change(
+                            topic.to_owned(),
+                            response.status.get_or_default().to_owned(),
+                        );
                     }
                     Ok(state)
                 }
@@ -408,11 +885,48 @@ impl Subscriber for InMemorySubscriber {
         handler: Arc<dyn UListener>,
         subscription_change_handler: Option<Arc<dyn SubscriptionChangeHandler>>,
     ) -> Result<(), RegistrationError> {
-        self.invoke_subscribe(topic_filter, subscription_change_handler)
-            .await?;
+        self.subscribe_with_options(
+            topic_filter,
+            handler,
+            subscription_change_handler,
+            SubscriptionOptions::default(),
+        )
+        .await
+    }
+
+    async fn subscribe_with_options(
+        &self,
+        topic_filter: &UUri,
+        handler: Arc<dyn UListener>,
+        subscription_change_handler: Option<Arc<dyn SubscriptionChangeHandler>>,
+        options: SubscriptionOptions,
+    ) -> Result<(), RegistrationError> {
+        self.topic_policy
+            .verify_topic_filter(topic_filter)
+            .map_err(|e| RegistrationError::InvalidFilter(e.to_string()))?;
+        let _registration = self.registration_lock.lock().await;
+        let has_other_listeners = self.has_other_listeners(topic_filter, &handler);
+        match self.duplicate_subscription_policy {
+            DuplicateSubscriptionPolicy::Reject if has_other_listeners => {
+                return Err(RegistrationError::AlreadyExists);
+            }
+            DuplicateSubscriptionPolicy::ReferenceCounted if has_other_listeners => {
+                // the USubscription service already knows about the subscription
+                if let Some(change_handler) = subscription_change_handler {
+                    self.subscription_change_listener
+                        .add_handler(topic_filter.to_owned(), change_handler)?;
+                }
+            }
+            _ => {
+                self.invoke_subscribe(topic_filter, subscription_change_handler, &options)
+                    .await?;
+            }
+        }
+        let registered_listener = self.listener_to_register(topic_filter, &handler, &options);
         self.transport
-            .register_listener(topic_filter, None, handler.clone())
+            .register_listener(topic_filter, None, registered_listener.clone())
             .await
+            .map(|_| self.add_subscription(topic_filter, handler, registered_listener))
             // When this fails, we have ended up in a situation where we
             // have successfully (logically) subscribed to the topic via the USubscriptio service
             // but we have not been able to register the listener with the local transport.
@@ -428,10 +942,19 @@ impl Subscriber for InMemorySubscriber {
         topic: &UUri,
         listener: Arc<dyn UListener>,
     ) -> Result<(), RegistrationError> {
-        self.invoke_unsubscribe(topic).await?;
+        let _registration = self.registration_lock.lock().await;
+        if self.duplicate_subscription_policy != DuplicateSubscriptionPolicy::ReferenceCounted
+            || !self.has_other_listeners(topic, &listener)
+        {
+            self.invoke_unsubscribe(topic).await?;
+        }
+        let registered_listener = self
+            .registered_listener(topic, &listener)
+            .unwrap_or_else(|| listener.clone());
         self.transport
-            .unregister_listener(topic, None, listener)
+            .unregister_listener(topic, None, registered_listener)
             .await
+            .map(|_| self.remove_subscription(topic, listener))
             // When this fails, we have ended up in a situation where we
             // have successfully (logically) unsubscribed from the topic via the USubscriptio service
             // but we have not been able to unregister the listener from the local transport.
@@ -494,6 +1017,27 @@ mod tests {
         assert!(publish_result.is_err_and(|e| matches!(e, PubSubError::InvalidArgument(_msg))));
     }
 
+    #[tokio::test]
+    async fn test_publish_succeeds_for_platform_topic() {
+        // GIVEN a publisher of a platform component
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_send()
+            .once()
+            .withf(|msg| msg.attributes.source.resource_id == 0x1000)
+            .returning(|_msg| Ok(()));
+        let publisher = SimplePublisher::new(Arc::new(transport), new_uri_provider())
+            .with_topic_policy(TopicPolicy::Platform);
+
+        // WHEN publishing to a topic outside of the regular topic range
+        let publish_result = publisher
+            .publish(0x1000, CallOptions::for_publish(None, None, None), None)
+            .await;
+
+        // THEN publishing succeeds
+        assert!(publish_result.is_ok());
+    }
+
     #[tokio::test]
     async fn test_publish_fails_with_transport_error() {
         let message_id = UUID::build();
@@ -576,6 +1120,91 @@ mod tests {
         assert!(publish_result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_publish_all_sends_nothing_if_any_message_is_invalid() {
+        // GIVEN a publisher
+        let uri_provider = new_uri_provider();
+        let mut transport = MockTransport::new();
+        transport.expect_do_send().never();
+        let publisher = SimplePublisher::new(Arc::new(transport), uri_provider);
+
+        // WHEN publishing multiple messages, one of which is to be published to an invalid topic
+        let payload = UPayload::try_from_protobuf(StringValue::new()).unwrap();
+        let publish_result = publisher
+            .publish_all(
+                vec![(0x9A00, payload.clone()), (0x1000, payload)],
+                CallOptions::for_publish(None, None, None),
+            )
+            .await;
+
+        // THEN publishing fails with an InvalidArgument error and no message has been sent
+        assert!(publish_result.is_err_and(|e| matches!(e, PubSubError::InvalidArgument(_msg))));
+    }
+
+    #[tokio::test]
+    async fn test_publish_all_reports_outcome_per_message() {
+        // GIVEN a publisher
+        let uri_provider = new_uri_provider();
+        let mut transport = MockTransport::new();
+        let mut seq = Sequence::new();
+        // whose transport fails to send the second message
+        transport
+            .expect_do_send()
+            .once()
+            .in_sequence(&mut seq)
+            .withf(|msg| msg.attributes.get_or_default().sink.is_none() && msg.is_publish())
+            .returning(|_msg| Ok(()));
+        transport
+            .expect_do_send()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_msg| {
+                Err(UStatus::fail_with_code(
+                    UCode::UNAVAILABLE,
+                    "transport not available",
+                ))
+            });
+        let publisher = SimplePublisher::new(Arc::new(transport), uri_provider);
+
+        // WHEN publishing multiple messages to valid topics
+        let payload = UPayload::try_from_protobuf(StringValue::new()).unwrap();
+        let outcomes = publisher
+            .publish_all(
+                vec![(0x9A00, payload.clone()), (0x9A01, payload)],
+                CallOptions::for_publish(None, None, None),
+            )
+            .await
+            .expect("should have been able to create messages");
+
+        // THEN the outcome of sending each message is reported
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes[0].is_ok());
+        assert!(outcomes[1]
+            .as_ref()
+            .is_err_and(|e| matches!(e, PubSubError::PublishError(_status))));
+    }
+
+    #[tokio::test]
+    async fn test_publish_all_fails_for_shared_message_id() {
+        // GIVEN a publisher
+        let uri_provider = new_uri_provider();
+        let mut transport = MockTransport::new();
+        transport.expect_do_send().never();
+        let publisher = SimplePublisher::new(Arc::new(transport), uri_provider);
+
+        // WHEN publishing multiple messages using the same message ID
+        let payload = UPayload::try_from_protobuf(StringValue::new()).unwrap();
+        let publish_result = publisher
+            .publish_all(
+                vec![(0x9A00, payload.clone()), (0x9A01, payload)],
+                CallOptions::for_publish(None, Some(UUID::build()), None),
+            )
+            .await;
+
+        // THEN publishing fails with an InvalidArgument error
+        assert!(publish_result.is_err_and(|e| matches!(e, PubSubError::InvalidArgument(_msg))));
+    }
+
     #[tokio::test]
     async fn test_subscriber_creation_fails_when_notifier_fails_to_register_listener() {
         // GIVEN a Notifier
@@ -618,10 +1247,17 @@ mod tests {
 
         let subscriber = InMemorySubscriber {
             transport: Arc::new(MockTransport::new()),
-            _uri_provider: new_uri_provider(),
+            uri_provider: new_uri_provider(),
             usubscription: Arc::new(MockUSubscription::new()),
             notifier: Arc::new(notifier),
             subscription_change_listener,
+            subscriptions: RwLock::new(HashMap::new()),
+            duplicate_subscription_policy: DuplicateSubscriptionPolicy::default(),
+            suppress_local_echo: false,
+            client_side_sampling: false,
+            topic_policy: TopicPolicy::default(),
+            configured_subscriptions: tokio::sync::Mutex::new(HashMap::new()),
+            registration_lock: tokio::sync::Mutex::new(()),
         };
 
         // WHEN trying to stop the Subscriber
@@ -634,6 +1270,32 @@ mod tests {
         }));
     }
 
+    #[tokio::test]
+    async fn test_subscribe_fails_for_invalid_topic() {
+        // GIVEN a Subscriber
+        let mut usubscription_client = MockUSubscription::new();
+        usubscription_client.expect_subscribe().never();
+        let mut transport = MockTransport::new();
+        transport.expect_do_register_listener().never();
+        let subscriber = InMemorySubscriber::for_clients(
+            Arc::new(transport),
+            new_uri_provider(),
+            Arc::new(usubscription_client),
+            succeding_notifier(),
+        )
+        .await
+        .unwrap();
+
+        // WHEN subscribing to an RPC method
+        let topic = UUri::try_from_parts("other", 0x1a9a, 0x01, 0x0001).unwrap();
+        let subscribe_attempt = subscriber
+            .subscribe(&topic, Arc::new(MockUListener::new()), None)
+            .await;
+
+        // THEN the attempt fails without invoking the USubscription service
+        assert!(subscribe_attempt.is_err_and(|e| matches!(e, RegistrationError::InvalidFilter(_))));
+    }
+
     #[tokio::test]
     async fn test_subscribe_fails_when_usubscription_invocation_fails() {
         // GIVEN a USubscription client
@@ -768,12 +1430,14 @@ mod tests {
         // WHEN subscribing to a topic
         let topic = UUri::try_from_parts("other", 0x1a9a, 0x01, 0x8100).unwrap();
         let listener = Arc::new(MockUListener::new());
+        let mut handler = MockSubscriptionChangeHandler::new();
+        handler
+            .expect_on_subscription_change()
+            .once()
+            .withf(|_topic, status| status.state.enum_value_or_default() == State::SUBSCRIBED)
+            .return_const(());
         let subscribe_attempt = subscriber
-            .subscribe(
-                &topic,
-                listener.clone(),
-                Some(Arc::new(MockSubscriptionChangeHandler::new())),
-            )
+            .subscribe(&topic, listener.clone(), Some(Arc::new(handler)))
             .await;
 
         // THEN the first attempt fails due to the transport having failed
@@ -852,7 +1516,14 @@ mod tests {
         let mut mock_listener = MockUListener::new();
         mock_listener.expect_on_receive().once().return_const(());
         let listener = Arc::new(mock_listener);
-        let handler = Arc::new(MockSubscriptionChangeHandler::new());
+        let mut handler = MockSubscriptionChangeHandler::new();
+        // which is informed about the initial subscription status on each attempt
+        handler
+            .expect_on_subscription_change()
+            .times(2)
+            .withf(|_topic, status| status.state.enum_value_or_default() == State::SUBSCRIBED)
+            .return_const(());
+        let handler = Arc::new(handler);
         let subscribe_attempt = subscriber
             .subscribe(&topic, listener.clone(), Some(handler.clone()))
             .await;
@@ -873,6 +1544,310 @@ mod tests {
         captured_listener.on_receive(event).await;
     }
 
+    fn subscription_response(
+        request: SubscriptionRequest,
+    ) -> Result<SubscriptionResponse, UStatus> {
+        Ok(SubscriptionResponse {
+            topic: request.topic.clone(),
+            status: Some(SubscriptionStatus {
+                state: State::SUBSCRIBED.into(),
+                ..Default::default()
+            })
+            .into(),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_rejects_duplicate_subscription() {
+        // GIVEN a USubscription client that succeeds to subscribe to topics
+        let mut usubscription_client = MockUSubscription::new();
+        usubscription_client
+            .expect_subscribe()
+            .once()
+            .returning(subscription_response);
+        // and a transport that succeeds to register listeners
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_register_listener()
+            .once()
+            .return_const(Ok(()));
+        // and a Subscriber that rejects duplicate subscriptions
+        let subscriber = InMemorySubscriber::for_clients(
+            Arc::new(transport),
+            new_uri_provider(),
+            Arc::new(usubscription_client),
+            succeding_notifier(),
+        )
+        .await
+        .unwrap()
+        .with_duplicate_subscription_policy(DuplicateSubscriptionPolicy::Reject);
+        let topic = UUri::try_from_parts("other", 0x1a9a, 0x01, 0x8100).unwrap();
+        assert!(subscriber
+            .subscribe(&topic, Arc::new(MockUListener::new()), None)
+            .await
+            .is_ok());
+
+        // WHEN subscribing another listener to the same topic
+        let subscribe_attempt = subscriber
+            .subscribe(&topic, Arc::new(MockUListener::new()), None)
+            .await;
+
+        // THEN the attempt fails with an ALREADY_EXISTS error
+        assert!(subscribe_attempt.is_err_and(|e| matches!(e, RegistrationError::AlreadyExists)));
+    }
+
+    #[tokio::test]
+    async fn test_reference_counted_subscriptions() {
+        // GIVEN a USubscription client which expects to be invoked once for subscribing
+        // and once for unsubscribing
+        let mut usubscription_client = MockUSubscription::new();
+        usubscription_client
+            .expect_subscribe()
+            .once()
+            .returning(subscription_response);
+        usubscription_client
+            .expect_unsubscribe()
+            .once()
+            .return_const(Ok(()));
+        // and a transport that succeeds to (un)register listeners
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_register_listener()
+            .times(2)
+            .return_const(Ok(()));
+        transport
+            .expect_do_unregister_listener()
+            .times(2)
+            .return_const(Ok(()));
+        // and a Subscriber that counts references to subscribed topics
+        let subscriber = InMemorySubscriber::for_clients(
+            Arc::new(transport),
+            new_uri_provider(),
+            Arc::new(usubscription_client),
+            succeding_notifier(),
+        )
+        .await
+        .unwrap()
+        .with_duplicate_subscription_policy(DuplicateSubscriptionPolicy::ReferenceCounted);
+        let topic = UUri::try_from_parts("other", 0x1a9a, 0x01, 0x8100).unwrap();
+        let first_listener: Arc<dyn UListener> = Arc::new(MockUListener::new());
+        let second_listener: Arc<dyn UListener> = Arc::new(MockUListener::new());
+
+        // WHEN subscribing two listeners to the same topic
+        assert!(subscriber
+            .subscribe(&topic, first_listener.clone(), None)
+            .await
+            .is_ok());
+        assert!(subscriber
+            .subscribe(&topic, second_listener.clone(), None)
+            .await
+            .is_ok());
+        assert_eq!(
+            subscriber.diagnostics().subscriptions,
+            vec![(topic.to_uri(false), 2)]
+        );
+
+        // and unsubscribing both of them again
+        assert!(subscriber.unsubscribe(&topic, first_listener).await.is_ok());
+        assert!(subscriber
+            .unsubscribe(&topic, second_listener)
+            .await
+            .is_ok());
+
+        // THEN the USubscription service has been invoked once for subscribing and once for unsubscribing
+        assert!(subscriber.diagnostics().subscriptions.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_reference_counted_subscriptions_of_concurrent_subscribers() {
+        // GIVEN a USubscription client that takes some time to subscribe to topics
+        let mut usubscription_client = MockUSubscription::new();
+        usubscription_client
+            .expect_subscribe()
+            .once()
+            .returning(|request| {
+                std::thread::sleep(Duration::from_millis(100));
+                subscription_response(request)
+            });
+        // and a transport that succeeds to register listeners
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_register_listener()
+            .times(2)
+            .return_const(Ok(()));
+        // and a Subscriber that counts references to subscribed topics
+        let subscriber = Arc::new(
+            InMemorySubscriber::for_clients(
+                Arc::new(transport),
+                new_uri_provider(),
+                Arc::new(usubscription_client),
+                succeding_notifier(),
+            )
+            .await
+            .unwrap()
+            .with_duplicate_subscription_policy(DuplicateSubscriptionPolicy::ReferenceCounted),
+        );
+        let topic = UUri::try_from_parts("other", 0x1a9a, 0x01, 0x8100).unwrap();
+
+        // WHEN subscribing two listeners to the same topic concurrently
+        let subscriptions = [
+            Arc::new(MockUListener::new()) as Arc<dyn UListener>,
+            Arc::new(MockUListener::new()) as Arc<dyn UListener>,
+        ]
+        .map(|listener| {
+            let subscriber = subscriber.clone();
+            let topic = topic.clone();
+            tokio::spawn(async move { subscriber.subscribe(&topic, listener, None).await })
+        });
+        for subscription in subscriptions {
+            assert!(subscription.await.unwrap().is_ok());
+        }
+
+        // THEN the USubscription service has been invoked once only
+        // (verified by the mock's expectations)
+        assert_eq!(
+            subscriber.diagnostics().subscriptions,
+            vec![(topic.to_uri(false), 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_suppresses_local_echo() {
+        // GIVEN a transport that keeps track of registered listeners
+        let uri_provider = new_uri_provider();
+        let registered_listeners = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let registered_listeners_clone = registered_listeners.clone();
+        let mut transport = MockTransport::new();
+        transport.expect_do_register_listener().once().returning(
+            move |_source_filter, _sink_filter, listener| {
+                registered_listeners_clone.lock().unwrap().push(listener);
+                Ok(())
+            },
+        );
+        let registered_listeners_clone = registered_listeners.clone();
+        transport.expect_do_unregister_listener().once().returning(
+            move |_source_filter, _sink_filter, listener| {
+                // THEN the listener that had been registered is unregistered again
+                assert!(Arc::ptr_eq(
+                    &registered_listeners_clone.lock().unwrap()[0],
+                    &listener
+                ));
+                Ok(())
+            },
+        );
+        let mut usubscription_client = MockUSubscription::new();
+        usubscription_client
+            .expect_subscribe()
+            .once()
+            .returning(subscription_response);
+        usubscription_client
+            .expect_unsubscribe()
+            .once()
+            .return_const(Ok(()));
+        // and a Subscriber that suppresses local echo
+        let subscriber = InMemorySubscriber::for_clients(
+            Arc::new(transport),
+            uri_provider.clone(),
+            Arc::new(usubscription_client),
+            succeding_notifier(),
+        )
+        .await
+        .unwrap()
+        .with_local_echo_suppression(true);
+
+        // and a listener which expects to be invoked once only
+        let topic = UUri::try_from_parts("", 0x0005, 0x02, 0x8100).unwrap();
+        let mut listener = MockUListener::new();
+        listener
+            .expect_on_receive()
+            .once()
+            .withf(|msg| {
+                msg.attributes
+                    .get_or_default()
+                    .source
+                    .get_or_default()
+                    .ue_id
+                    == 0x1a9a
+            })
+            .return_const(());
+        let listener: Arc<dyn UListener> = Arc::new(listener);
+        assert!(subscriber
+            .subscribe(&topic, listener.clone(), None)
+            .await
+            .is_ok());
+
+        // WHEN the transport dispatches an event published by the local uEntity
+        let registered_listener = registered_listeners.lock().unwrap()[0].clone();
+        registered_listener
+            .on_receive(UMessageBuilder::publish(topic.clone()).build().unwrap())
+            .await;
+        // and an event published by another uEntity
+        let other_topic = UUri::try_from_parts("", 0x1a9a, 0x01, 0x8100).unwrap();
+        registered_listener
+            .on_receive(UMessageBuilder::publish(other_topic).build().unwrap())
+            .await;
+
+        // THEN the subscribed listener is only invoked for the other uEntity's event
+        // and can be unsubscribed using the original listener
+        assert!(subscriber.unsubscribe(&topic, listener).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_forwards_sample_period_and_samples_events() {
+        // GIVEN a transport that keeps track of registered listeners
+        let registered_listeners = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let registered_listeners_clone = registered_listeners.clone();
+        let mut transport = MockTransport::new();
+        transport.expect_do_register_listener().once().returning(
+            move |_source_filter, _sink_filter, listener| {
+                registered_listeners_clone.lock().unwrap().push(listener);
+                Ok(())
+            },
+        );
+        // and a USubscription service that expects the sample period hint
+        let mut usubscription_client = MockUSubscription::new();
+        usubscription_client
+            .expect_subscribe()
+            .once()
+            .withf(|request| request.attributes.sample_period_ms == Some(1_000))
+            .returning(subscription_response);
+        // and a Subscriber that down-samples events locally
+        let subscriber = InMemorySubscriber::for_clients(
+            Arc::new(transport),
+            new_uri_provider(),
+            Arc::new(usubscription_client),
+            succeding_notifier(),
+        )
+        .await
+        .unwrap()
+        .with_client_side_sampling(true);
+
+        // WHEN subscribing a listener with a sample period
+        let topic = UUri::try_from_parts("", 0x1a9a, 0x01, 0x8100).unwrap();
+        let mut listener = MockUListener::new();
+        listener.expect_on_receive().once().return_const(());
+        assert!(subscriber
+            .subscribe_with_options(
+                &topic,
+                Arc::new(listener),
+                None,
+                SubscriptionOptions::with_sample_period(Duration::from_secs(1)),
+            )
+            .await
+            .is_ok());
+
+        // and the transport dispatches two events in quick succession
+        let registered_listener = registered_listeners.lock().unwrap()[0].clone();
+        for _ in 0..2 {
+            registered_listener
+                .on_receive(UMessageBuilder::publish(topic.clone()).build().unwrap())
+                .await;
+        }
+
+        // THEN the subscribed listener is invoked for the first event only
+    }
+
     #[tokio::test]
     async fn test_unsubscribe_fails_for_unknown_listener() {
         // GIVEN a USubscription client
@@ -1005,6 +1980,92 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_apply_config_subscribes_and_unsubscribes_listeners() {
+        // GIVEN a USubscription client
+        let mut usubscription_client = MockUSubscription::new();
+        // that succeeds to subscribe to topics
+        usubscription_client
+            .expect_subscribe()
+            .times(2)
+            .returning(|request| {
+                let response = SubscriptionResponse {
+                    topic: request.topic.clone(),
+                    status: Some(SubscriptionStatus {
+                        state: State::SUBSCRIBED.into(),
+                        ..Default::default()
+                    })
+                    .into(),
+                    ..Default::default()
+                };
+                Ok(response)
+            });
+        // and to unsubscribe from topics
+        usubscription_client
+            .expect_unsubscribe()
+            .once()
+            .return_const(Ok(()));
+
+        // and a transport which succeeds to register and unregister listeners
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_register_listener()
+            .times(2)
+            .return_const(Ok(()));
+        transport
+            .expect_do_unregister_listener()
+            .once()
+            .return_const(Ok(()));
+
+        // and a Subscriber using that USubscription client and transport
+        let subscriber = InMemorySubscriber::for_clients(
+            Arc::new(transport),
+            new_uri_provider(),
+            Arc::new(usubscription_client),
+            succeding_notifier(),
+        )
+        .await
+        .unwrap();
+        let listeners: HashMap<String, Arc<dyn UListener>> =
+            HashMap::from([("logger".to_string(), Arc::new(MockUListener::new()) as _)]);
+        let temperature = SubscriptionConfig {
+            topic: "//other/1a9a/1/8100".to_string(),
+            listener: "logger".to_string(),
+        };
+        let speed = SubscriptionConfig {
+            topic: "//other/1a9a/1/8200".to_string(),
+            listener: "logger".to_string(),
+        };
+
+        // WHEN applying a configuration containing two subscriptions
+        assert!(subscriber
+            .apply_config(&[temperature.clone(), speed.clone()], &listeners)
+            .await
+            .is_ok());
+        // THEN both subscriptions are exported
+        assert_eq!(
+            subscriber.export_config().await,
+            vec![temperature.clone(), speed.clone()]
+        );
+
+        // WHEN applying a configuration that only contains one of the subscriptions
+        // and a subscription referring to an unknown listener
+        let unknown_listener = SubscriptionConfig {
+            topic: "//other/1a9a/1/8300".to_string(),
+            listener: "unknown".to_string(),
+        };
+        let result = subscriber
+            .apply_config(&[speed.clone(), unknown_listener.clone()], &listeners)
+            .await;
+
+        // THEN the subscription for the unknown listener is reported as failed
+        assert!(result.is_err_and(|errors| errors.len() == 1
+            && errors[0].0 == unknown_listener
+            && matches!(errors[0].1, RegistrationError::Unknown(_))));
+        // and the other subscription has been ended
+        assert_eq!(subscriber.export_config().await, vec![speed]);
+    }
+
     #[tokio::test]
     async fn test_unsubscribe_succeeds_on_second_attempt() {
         // GIVEN a USubscription client
@@ -1067,6 +2128,90 @@ mod tests {
         }));
     }
 
+    #[tokio::test]
+    async fn test_unsubscribe_all_reports_topics_that_could_not_be_unsubscribed_from() {
+        // GIVEN a USubscription client
+        let mut usubscription_client = MockUSubscription::new();
+        // that succeeds to subscribe to and unsubscribe from topics
+        usubscription_client
+            .expect_subscribe()
+            .times(2)
+            .returning(|request| {
+                let response = SubscriptionResponse {
+                    topic: request.topic.clone(),
+                    status: Some(SubscriptionStatus {
+                        state: State::SUBSCRIBED.into(),
+                        ..Default::default()
+                    })
+                    .into(),
+                    ..Default::default()
+                };
+                Ok(response)
+            });
+        usubscription_client
+            .expect_unsubscribe()
+            .times(3)
+            .return_const(Ok(()));
+
+        let failing_topic = UUri::try_from_parts("other", 0x1a9a, 0x01, 0x8100).unwrap();
+        let other_topic = UUri::try_from_parts("other", 0x1a9a, 0x01, 0x8200).unwrap();
+
+        // and a transport
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_register_listener()
+            .times(2)
+            .return_const(Ok(()));
+        // that fails to unregister the listener for one of the topics
+        let topic_to_fail = failing_topic.clone();
+        transport
+            .expect_do_unregister_listener()
+            .times(3)
+            .returning(move |source_filter, _sink_filter, _listener| {
+                if source_filter == &topic_to_fail {
+                    Err(UStatus::fail_with_code(UCode::UNAVAILABLE, "not connected"))
+                } else {
+                    Ok(())
+                }
+            });
+
+        // and a Subscriber using that USubscription client and transport
+        let subscriber = InMemorySubscriber::for_clients(
+            Arc::new(transport),
+            new_uri_provider(),
+            Arc::new(usubscription_client),
+            succeding_notifier(),
+        )
+        .await
+        .unwrap();
+        // which has subscribed listeners to both topics
+        let listener = Arc::new(MockUListener::new());
+        assert!(subscriber
+            .subscribe(&failing_topic, listener.clone(), None)
+            .await
+            .is_ok());
+        assert!(subscriber
+            .subscribe(&other_topic, listener.clone(), None)
+            .await
+            .is_ok());
+
+        // WHEN unsubscribing all listeners
+        let unsubscribe_attempt = subscriber.unsubscribe_all().await;
+
+        // THEN the topic that could not be unsubscribed from is reported
+        assert!(unsubscribe_attempt.is_err_and(|errors| errors.len() == 1
+            && errors[0].0 == failing_topic
+            && matches!(errors[0].1, RegistrationError::Unknown(_))));
+
+        // and the listener remains subscribed to that topic only
+        assert_eq!(
+            subscriber.diagnostics().subscriptions,
+            vec![(failing_topic.to_uri(false), 1)]
+        );
+        let unsubscribe_attempt = subscriber.unsubscribe_all().await;
+        assert!(unsubscribe_attempt.is_err_and(|errors| errors.len() == 1));
+    }
+
     fn message_with_wrong_type(msg_type: UMessageType) -> UMessage {
         let attributes = UAttributes {
             type_: msg_type.into(),
@@ -1200,4 +2345,40 @@ mod tests {
 
         listener.on_receive(notification).await;
     }
+
+    #[tokio::test]
+    async fn test_subscription_change_listener_streams_updates_for_all_topics() {
+        // GIVEN a listener for subscription updates without any handlers
+        let listener = SubscriptionChangeListener::default();
+        // and a receiver of the update stream
+        let mut updates = listener.updates.subscribe();
+
+        // WHEN an update for a topic is received
+        let topic = UUri::try_from_parts("other", 0x1a9a, 0x01, 0x8100).unwrap();
+        let update = Update {
+            topic: Some(topic.clone()).into(),
+            status: Some(SubscriptionStatus {
+                state: State::SUBSCRIBED.into(),
+                ..Default::default()
+            })
+            .into(),
+            ..Default::default()
+        };
+        let payload = UPayload::try_from_protobuf(update.clone())
+            .expect("should have been able to create protobuf");
+        let notification = UMessage {
+            attributes: Some(UAttributes {
+                type_: UMessageType::UMESSAGE_TYPE_NOTIFICATION.into(),
+                payload_format: payload.payload_format().into(),
+                ..Default::default()
+            })
+            .into(),
+            payload: Some(payload.payload()),
+            ..Default::default()
+        };
+        listener.on_receive(notification).await;
+
+        // THEN the update is passed to the receiver
+        assert_eq!(updates.try_recv().unwrap(), update);
+    }
 }
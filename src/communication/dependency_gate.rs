@@ -0,0 +1,453 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::{error::Error, fmt::Display, future::Future, sync::Arc, time::Duration};
+
+use tokio::{sync::watch, time::Instant};
+use tracing::{debug, info};
+
+#[cfg(feature = "udiscovery")]
+use crate::core::udiscovery::UDiscovery;
+use crate::UUri;
+
+use super::{ping, CallOptions, RegistrationError, RpcClient};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+const DEFAULT_PROBE_TTL: u32 = 1_000;
+
+/// An error indicating that a [`DependencyGate`] could not be opened.
+#[derive(Debug)]
+pub enum DependencyGateError {
+    /// Indicates that some of the required services have not become available in time.
+    Timeout(Vec<UUri>),
+    /// Indicates that the endpoints could not be registered after all required services have become available.
+    Registration(RegistrationError),
+}
+
+impl Display for DependencyGateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyGateError::Timeout(services) => f.write_fmt(format_args!(
+                "{} required service(s) did not become available in time",
+                services.len()
+            )),
+            DependencyGateError::Registration(e) => {
+                f.write_fmt(format_args!("failed to register endpoints: {}", e))
+            }
+        }
+    }
+}
+
+impl Error for DependencyGateError {}
+
+/// The availability of a service that a [`DependencyGate`] waits for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DependencyState {
+    /// The service has not been found to be available yet.
+    Waiting {
+        /// The number of times that the service's availability has been checked.
+        attempts: u32,
+    },
+    /// The service is available.
+    Available,
+    /// The service has not become available before the gate's timeout has expired.
+    Unavailable,
+}
+
+/// The availability of a single service that a [`DependencyGate`] waits for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DependencyStatus {
+    /// The URI of the service.
+    pub service: UUri,
+    /// The service's availability.
+    pub state: DependencyState,
+    /// The reason why the most recent availability check has failed, if any.
+    pub last_error: Option<String>,
+}
+
+/// The progress of a [`DependencyGate`] waiting for its required services.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GateProgress {
+    /// The availability of each of the required services.
+    pub dependencies: Vec<DependencyStatus>,
+}
+
+impl GateProgress {
+    /// Gets the services that have not become available yet.
+    ///
+    /// This is useful for displaying a message like _waiting for X_, e.g. on an HMI.
+    pub fn waiting_for(&self) -> Vec<&UUri> {
+        self.dependencies
+            .iter()
+            .filter(|status| status.state != DependencyState::Available)
+            .map(|status| &status.service)
+            .collect()
+    }
+
+    /// Checks if all required services are available.
+    pub fn is_complete(&self) -> bool {
+        self.dependencies
+            .iter()
+            .all(|status| status.state == DependencyState::Available)
+    }
+}
+
+/// Delays the registration of a service's endpoints until the services that it depends on are available.
+///
+/// Services that rely on other services for processing requests should not accept requests
+/// before these services can be reached. During startup, a gate checks the availability of the
+/// required services periodically, with an exponentially increasing delay between the checks,
+/// and [registers the service's endpoints](`Self::open`) once all of them are available.
+///
+/// By default, the availability of a service is checked by means of [pinging](`ping`) it,
+/// which requires the URIs of the required services to not contain any wildcards. If a uDiscovery
+/// service has been [set](`Self::with_udiscovery`), a service is considered available once
+/// the uDiscovery service knows about at least one instance matching the service's URI.
+///
+/// The progress can be observed by means of a [watch channel](`Self::progress`).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::{sync::Arc, time::Duration};
+/// use up_rust::{
+///     communication::{
+///         CallOptions, DependencyGate, RpcClient, ServiceInvocationError, UPayload,
+///     },
+///     UUri,
+/// };
+///
+/// // a client for which all services respond to being pinged
+/// struct ReachableServices;
+///
+/// #[async_trait::async_trait]
+/// impl RpcClient for ReachableServices {
+///     async fn invoke_method(
+///         &self,
+///         _method: UUri,
+///         _call_options: CallOptions,
+///         _payload: Option<UPayload>,
+///     ) -> Result<Option<UPayload>, ServiceInvocationError> {
+///         Ok(None)
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let gate = DependencyGate::new(
+///     Arc::new(ReachableServices),
+///     vec![UUri::try_from("//vehicle/A100/1/0").unwrap()],
+/// )
+/// .with_timeout(Duration::from_secs(10));
+///
+/// let mut progress = gate.progress();
+/// tokio::spawn(async move {
+///     while progress.changed().await.is_ok() {
+///         let waiting_for = progress.borrow().waiting_for().len();
+///         println!("waiting for {waiting_for} service(s)");
+///     }
+/// });
+///
+/// gate.open(|| async {
+///     // register the service's RPC endpoints here
+///     Ok(())
+/// })
+/// .await
+/// .expect("required services should be available");
+/// # }
+/// ```
+pub struct DependencyGate {
+    rpc_client: Arc<dyn RpcClient>,
+    #[cfg(feature = "udiscovery")]
+    udiscovery: Option<Arc<dyn UDiscovery>>,
+    timeout: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    probe_ttl: u32,
+    progress: watch::Sender<GateProgress>,
+}
+
+impl DependencyGate {
+    /// Creates a new gate.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_client` - The client to use for checking the availability of the required services.
+    /// * `required_services` - The URIs of the services to wait for. The resource IDs are ignored.
+    pub fn new(rpc_client: Arc<dyn RpcClient>, required_services: Vec<UUri>) -> Self {
+        let progress = GateProgress {
+            dependencies: required_services
+                .into_iter()
+                .map(|service| DependencyStatus {
+                    service,
+                    state: DependencyState::Waiting { attempts: 0 },
+                    last_error: None,
+                })
+                .collect(),
+        };
+        DependencyGate {
+            rpc_client,
+            #[cfg(feature = "udiscovery")]
+            udiscovery: None,
+            timeout: DEFAULT_TIMEOUT,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            probe_ttl: DEFAULT_PROBE_TTL,
+            progress: watch::Sender::new(progress),
+        }
+    }
+
+    /// Sets the maximum amount of time to wait for the required services.
+    ///
+    /// The default timeout is 60 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the delay between consecutive availability checks.
+    ///
+    /// The delay starts with the initial backoff and doubles after each round of checks,
+    /// until it reaches the maximum backoff. The defaults are 200ms and 5s respectively.
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets the time-to-live (in milliseconds) of the requests used for checking the availability of a service.
+    ///
+    /// The default TTL is 1000ms.
+    pub fn with_probe_ttl(mut self, probe_ttl: u32) -> Self {
+        self.probe_ttl = probe_ttl;
+        self
+    }
+
+    /// Sets the uDiscovery service to use for checking the availability of the required services.
+    #[cfg(feature = "udiscovery")]
+    pub fn with_udiscovery(mut self, udiscovery: Arc<dyn UDiscovery>) -> Self {
+        self.udiscovery = Some(udiscovery);
+        self
+    }
+
+    /// Gets a receiver for observing the progress of waiting for the required services.
+    pub fn progress(&self) -> watch::Receiver<GateProgress> {
+        self.progress.subscribe()
+    }
+
+    async fn check_availability(&self, service: &UUri) -> Result<(), String> {
+        #[cfg(feature = "udiscovery")]
+        if let Some(udiscovery) = self.udiscovery.as_ref() {
+            return match udiscovery.find_services(service.to_owned(), true).await {
+                Ok(instances) if !instances.is_empty() => Ok(()),
+                Ok(_) => Err("no instance of service has been found".to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+        }
+        ping(
+            self.rpc_client.as_ref(),
+            service,
+            CallOptions::for_rpc_request(self.probe_ttl, None, None, None),
+        )
+        .await
+        .map(|_latency| ())
+        .map_err(|e| e.to_string())
+    }
+
+    /// Waits for all required services to become available.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error containing the services that are still unavailable, if the timeout
+    /// expires before all services have become available.
+    pub async fn wait_for_dependencies(&self) -> Result<(), DependencyGateError> {
+        let deadline = Instant::now() + self.timeout;
+        let mut backoff = self.initial_backoff;
+        loop {
+            let pending: Vec<UUri> = self
+                .progress
+                .borrow()
+                .waiting_for()
+                .into_iter()
+                .cloned()
+                .collect();
+            for service in pending {
+                let result = self.check_availability(&service).await;
+                self.progress.send_modify(|progress| {
+                    if let Some(status) = progress
+                        .dependencies
+                        .iter_mut()
+                        .find(|status| status.service == service)
+                    {
+                        match result {
+                            Ok(()) => {
+                                debug!(service = %service, "required service is available");
+                                status.state = DependencyState::Available;
+                                status.last_error = None;
+                            }
+                            Err(e) => {
+                                let attempts = match status.state {
+                                    DependencyState::Waiting { attempts } => attempts + 1,
+                                    _ => 1,
+                                };
+                                status.state = DependencyState::Waiting { attempts };
+                                status.last_error = Some(e);
+                            }
+                        }
+                    }
+                });
+            }
+
+            let unavailable: Vec<UUri> = self
+                .progress
+                .borrow()
+                .waiting_for()
+                .into_iter()
+                .cloned()
+                .collect();
+            if unavailable.is_empty() {
+                info!("all required services are available");
+                return Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                self.progress.send_modify(|progress| {
+                    progress
+                        .dependencies
+                        .iter_mut()
+                        .filter(|status| status.state != DependencyState::Available)
+                        .for_each(|status| status.state = DependencyState::Unavailable);
+                });
+                info!(
+                    unavailable = unavailable.len(),
+                    "required services did not become available in time"
+                );
+                return Err(DependencyGateError::Timeout(unavailable));
+            }
+            tokio::time::sleep(backoff.min(deadline - now)).await;
+            backoff = backoff.saturating_mul(2).min(self.max_backoff);
+        }
+    }
+
+    /// Waits for all required services to become available and then registers the service's endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `register_endpoints` - The function to invoke for registering the service's endpoints,
+    ///   e.g. with an [`RpcServer`](super::RpcServer).
+    ///
+    /// # Returns
+    ///
+    /// The value returned by the registration function.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not all required services have become available in time, in which case
+    /// the registration function is not invoked, or if the registration function has failed.
+    pub async fn open<F, Fut, T>(&self, register_endpoints: F) -> Result<T, DependencyGateError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, RegistrationError>>,
+    {
+        self.wait_for_dependencies().await?;
+        register_endpoints()
+            .await
+            .map_err(DependencyGateError::Registration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use crate::communication::{MockRpcClient, ServiceInvocationError};
+
+    fn new_gate(rpc_client: MockRpcClient, timeout: Duration) -> DependencyGate {
+        DependencyGate::new(
+            Arc::new(rpc_client),
+            vec![
+                UUri::try_from("//vehicle/A100/1/0").unwrap(),
+                UUri::try_from("//vehicle/B100/1/0").unwrap(),
+            ],
+        )
+        .with_timeout(timeout)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+    }
+
+    #[tokio::test]
+    async fn test_open_registers_endpoints_once_services_are_available() {
+        // GIVEN a required service that becomes available after the second check only
+        let mut rpc_client = MockRpcClient::new();
+        let mut b100_attempts = 0;
+        rpc_client
+            .expect_invoke_method()
+            .returning(move |method, _options, _payload| {
+                if method.ue_id == 0xB100 {
+                    b100_attempts += 1;
+                    if b100_attempts < 2 {
+                        return Err(ServiceInvocationError::Unavailable(
+                            "not running".to_string(),
+                        ));
+                    }
+                }
+                Ok(None)
+            });
+        let gate = new_gate(rpc_client, Duration::from_secs(5));
+        let progress = gate.progress();
+        assert_eq!(progress.borrow().waiting_for().len(), 2);
+
+        // WHEN opening the gate
+        let result = gate.open(|| async { Ok("registered") }).await;
+
+        // THEN the endpoints have been registered
+        assert_eq!(result.unwrap(), "registered");
+        // and the progress reflects the availability of all services
+        assert!(progress.borrow().is_complete());
+    }
+
+    #[tokio::test]
+    async fn test_open_fails_if_service_does_not_become_available() {
+        let mut rpc_client = MockRpcClient::new();
+        rpc_client
+            .expect_invoke_method()
+            .returning(|method, _options, _payload| {
+                if method.ue_id == 0xB100 {
+                    Err(ServiceInvocationError::DeadlineExceeded)
+                } else {
+                    Ok(None)
+                }
+            });
+        let gate = new_gate(rpc_client, Duration::from_millis(20));
+        let progress = gate.progress();
+        let registered = AtomicBool::new(false);
+
+        let result = gate
+            .open(|| async {
+                registered.store(true, Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+
+        let unavailable = vec![UUri::try_from("//vehicle/B100/1/0").unwrap()];
+        assert!(result.is_err_and(
+            |e| matches!(e, DependencyGateError::Timeout(services) if services == unavailable)
+        ));
+        assert!(!registered.load(Ordering::SeqCst));
+        let progress = progress.borrow();
+        assert_eq!(progress.dependencies[1].state, DependencyState::Unavailable);
+        assert!(progress.dependencies[1].last_error.is_some());
+    }
+}
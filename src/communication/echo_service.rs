@@ -0,0 +1,144 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::UAttributes;
+
+use super::{
+    RegistrationError, RequestHandler, RpcServer, ServiceInvocationError, UPayload,
+    ECHO_RESOURCE_ID,
+};
+
+/// A service which responds to invocations of the well-known [echo method](`ECHO_RESOURCE_ID`).
+///
+/// The service returns the request's payload as is. Clients can use [`ping`](super::ping) for
+/// checking if a uEntity exposing the service can be reached. The creation time contained in
+/// the ID of the response message can be used for determining the latency of each direction
+/// of the round trip, given that the clocks of both uEntities are synchronized.
+///
+/// The service is also useful for smoke testing new transport implementations.
+#[derive(Default)]
+pub struct EchoService {
+    requests_handled: AtomicU64,
+}
+
+impl EchoService {
+    /// Registers a new echo service with an RPC server.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_server` - The server to register the echo method with.
+    ///
+    /// # Returns
+    ///
+    /// The service that has been registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the echo method could not be registered.
+    pub async fn register(rpc_server: &dyn RpcServer) -> Result<Arc<Self>, RegistrationError> {
+        let service = Arc::new(EchoService::default());
+        rpc_server
+            .register_endpoint(None, ECHO_RESOURCE_ID, service.clone())
+            .await?;
+        Ok(service)
+    }
+
+    /// Unregisters this service from an RPC server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the echo method could not be unregistered.
+    pub async fn unregister(
+        self: &Arc<Self>,
+        rpc_server: &dyn RpcServer,
+    ) -> Result<(), RegistrationError> {
+        rpc_server
+            .unregister_endpoint(None, ECHO_RESOURCE_ID, self.clone())
+            .await
+    }
+
+    /// Gets the number of requests that this service has responded to.
+    pub fn requests_handled(&self) -> u64 {
+        self.requests_handled.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl RequestHandler for EchoService {
+    async fn handle_request(
+        &self,
+        _resource_id: u16,
+        message_attributes: &UAttributes,
+        request_payload: Option<UPayload>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        self.requests_handled.fetch_add(1, Ordering::Relaxed);
+        if let Some(sent_at) = message_attributes.id.as_ref().and_then(|id| id.get_time()) {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64);
+            debug!(
+                source = %message_attributes.source.get_or_default(),
+                transit_time_ms = now.saturating_sub(sent_at),
+                "received echo request"
+            );
+        }
+        Ok(request_payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{communication::MockRpcServerImpl, UPayloadFormat};
+
+    #[tokio::test]
+    async fn test_handle_request_returns_request_payload() {
+        let service = EchoService::default();
+        let payload = UPayload::new("hello", UPayloadFormat::UPAYLOAD_FORMAT_TEXT);
+
+        let response = service
+            .handle_request(
+                ECHO_RESOURCE_ID,
+                &UAttributes::default(),
+                Some(payload.clone()),
+            )
+            .await;
+
+        assert!(response.is_ok_and(|response_payload| response_payload == Some(payload)));
+        assert_eq!(service.requests_handled(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_register_fails_for_occupied_resource_id() {
+        let mut rpc_server = MockRpcServerImpl::new();
+        rpc_server
+            .expect_do_register_endpoint()
+            .once()
+            .withf(|_origin_filter, resource_id, _handler| *resource_id == ECHO_RESOURCE_ID)
+            .return_const(Err(RegistrationError::AlreadyExists));
+
+        assert!(EchoService::register(&rpc_server)
+            .await
+            .is_err_and(|e| matches!(e, RegistrationError::AlreadyExists)));
+    }
+}
@@ -0,0 +1,128 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::UUri;
+
+use super::{ServiceInvocationError, UPayload};
+
+/// Identifies the invocations of an RPC method that are considered to be repetitions
+/// of the same operation.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey {
+    /// The address of the client that has invoked the method.
+    pub caller: UUri,
+    /// The resource ID of the invoked method.
+    pub resource_id: u16,
+    /// The idempotency key contained in the request message.
+    pub key: String,
+}
+
+/// A store for the outcomes of RPC method invocations having an idempotency key.
+///
+/// An RPC server consults the store before invoking a request handler. If the store contains an
+/// outcome for the request's key, the server responds with the stored outcome instead of invoking
+/// the handler again.
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Gets the outcome of a previous invocation.
+    ///
+    /// # Returns
+    ///
+    /// The outcome or `None` if no outcome has been stored for the given key.
+    async fn get(
+        &self,
+        key: &IdempotencyKey,
+    ) -> Option<Result<Option<UPayload>, ServiceInvocationError>>;
+
+    /// Stores the outcome of an invocation.
+    async fn put(
+        &self,
+        key: IdempotencyKey,
+        outcome: Result<Option<UPayload>, ServiceInvocationError>,
+    );
+}
+
+/// An [`IdempotencyStore`] which keeps outcomes in memory for a limited amount of time.
+pub struct InMemoryIdempotencyStore {
+    retention: Duration,
+    outcomes: Mutex<HashMap<IdempotencyKey, StoredOutcome>>,
+}
+
+type StoredOutcome = (Instant, Result<Option<UPayload>, ServiceInvocationError>);
+
+impl InMemoryIdempotencyStore {
+    /// Creates a new store.
+    ///
+    /// # Arguments
+    ///
+    /// * `retention` - The amount of time for which outcomes are kept. This should be
+    ///                 at least as long as the period in which clients retry requests.
+    pub fn new(retention: Duration) -> Self {
+        InMemoryIdempotencyStore {
+            retention,
+            outcomes: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    async fn get(
+        &self,
+        key: &IdempotencyKey,
+    ) -> Option<Result<Option<UPayload>, ServiceInvocationError>> {
+        let outcomes = self.outcomes.lock().ok()?;
+        outcomes
+            .get(key)
+            .filter(|(stored_at, _outcome)| stored_at.elapsed() < self.retention)
+            .map(|(_stored_at, outcome)| outcome.to_owned())
+    }
+
+    async fn put(
+        &self,
+        key: IdempotencyKey,
+        outcome: Result<Option<UPayload>, ServiceInvocationError>,
+    ) {
+        if let Ok(mut outcomes) = self.outcomes.lock() {
+            outcomes.retain(|_key, (stored_at, _outcome)| stored_at.elapsed() < self.retention);
+            outcomes.insert(key, (Instant::now(), outcome));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_outcomes_expire_after_retention_period() {
+        let store = InMemoryIdempotencyStore::new(Duration::from_millis(30));
+        let key = IdempotencyKey {
+            caller: UUri::try_from_parts("client", 0x1000, 0x01, 0x0000).unwrap(),
+            resource_id: 0x0001,
+            key: "4711".to_string(),
+        };
+        store.put(key.clone(), Ok(None)).await;
+        assert!(store.get(&key).await.is_some_and(|outcome| outcome.is_ok()));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(store.get(&key).await.is_none());
+    }
+}
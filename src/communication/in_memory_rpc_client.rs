@@ -15,24 +15,40 @@
 
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex,
+};
+use std::task::Poll;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use tokio::sync::oneshot::{Receiver, Sender};
+use tokio::sync::{
+    mpsc::{UnboundedReceiver, UnboundedSender},
+    oneshot::{Receiver, Sender},
+    AcquireError, Notify, OwnedSemaphorePermit, Semaphore, TryAcquireError,
+};
 use tokio::time::timeout;
 use tracing::{debug, info};
 
 use crate::{
-    LocalUriProvider, UCode, UListener, UMessage, UMessageBuilder, UMessageType, UStatus,
-    UTransport, UUri, UUID,
+    diagnostics::RpcClientDiagnostics, LocalUriProvider, UCode, UListener, UMessage,
+    UMessageBuilder, UMessageType, UStatus, UTransport, UUri, UUID,
 };
 
 use super::{
-    build_message, CallOptions, RegistrationError, RpcClient, ServiceInvocationError, UPayload,
+    build_message, CallOptions, RegistrationError, ResponseStream, RpcClient,
+    ServiceInvocationError, UPayload,
 };
 
-fn handle_response_message(response: UMessage) -> Result<Option<UPayload>, ServiceInvocationError> {
+/// Extracts the result of an invocation from an RPC Response message.
+///
+/// The message's payload is expected to have been decompressed already.
+pub(super) fn handle_response_message(
+    response: UMessage,
+) -> Result<Option<UPayload>, ServiceInvocationError> {
     let Some(attribs) = response.attributes.as_ref() else {
         return Err(ServiceInvocationError::InvalidArgument(
             "response message does not contain attributes".to_string(),
@@ -59,15 +75,45 @@ fn handle_response_message(response: UMessage) -> Result<Option<UPayload>, Servi
     }
 }
 
+enum ResponseSender {
+    // a single response message is expected
+    Single(Sender<UMessage>),
+    // a sequence of response messages carrying chunks of the result is expected
+    Stream(UnboundedSender<UMessage>),
+}
+
+struct PendingRequest {
+    // the method that has been invoked
+    sink: UUri,
+    sender: ResponseSender,
+}
+
+/// Checks if a response has been sent by the method that a request has been sent to.
+///
+/// The authority is ignored if the request has been sent to a local method (using an empty authority).
+fn is_response_source_valid(request_sink: &UUri, response_source: &UUri) -> bool {
+    if request_sink.authority_name.is_empty() {
+        request_sink.ue_id == response_source.ue_id
+            && request_sink.ue_version_major == response_source.ue_version_major
+            && request_sink.resource_id == response_source.resource_id
+    } else {
+        request_sink == response_source
+    }
+}
+
 struct ResponseListener {
-    // request ID -> sender for response message
-    pending_requests: Mutex<HashMap<UUID, Sender<UMessage>>>,
+    // request ID -> sender for response message(s)
+    pending_requests: Mutex<HashMap<UUID, PendingRequest>>,
+    responses_received: AtomicU64,
+    verify_response_source: AtomicBool,
+    responses_rejected: AtomicU64,
 }
 
 impl ResponseListener {
     fn try_add_pending_request(
         &self,
         reqid: UUID,
+        sink: UUri,
     ) -> Result<Receiver<UMessage>, ServiceInvocationError> {
         let Ok(mut pending_requests) = self.pending_requests.lock() else {
             return Err(ServiceInvocationError::Internal(
@@ -77,7 +123,35 @@ impl ResponseListener {
 
         if let Entry::Vacant(entry) = pending_requests.entry(reqid) {
             let (tx, rx) = tokio::sync::oneshot::channel();
-            entry.insert(tx);
+            entry.insert(PendingRequest {
+                sink,
+                sender: ResponseSender::Single(tx),
+            });
+            Ok(rx)
+        } else {
+            Err(ServiceInvocationError::AlreadyExists(
+                "RPC request with given ID already pending".to_string(),
+            ))
+        }
+    }
+
+    fn try_add_pending_stream(
+        &self,
+        reqid: UUID,
+        sink: UUri,
+    ) -> Result<UnboundedReceiver<UMessage>, ServiceInvocationError> {
+        let Ok(mut pending_requests) = self.pending_requests.lock() else {
+            return Err(ServiceInvocationError::Internal(
+                "failed to add response handler".to_string(),
+            ));
+        };
+
+        if let Entry::Vacant(entry) = pending_requests.entry(reqid) {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            entry.insert(PendingRequest {
+                sink,
+                sender: ResponseSender::Stream(tx),
+            });
             Ok(rx)
         } else {
             Err(ServiceInvocationError::AlreadyExists(
@@ -94,7 +168,43 @@ impl ResponseListener {
             );
             return;
         };
-        if let Some(sender) = pending_requests.remove(reqid) {
+        if self.verify_response_source.load(Ordering::Relaxed) {
+            if let Some(pending_request) = pending_requests.get(reqid) {
+                let response_source = response_message
+                    .attributes
+                    .get_or_default()
+                    .source
+                    .get_or_default();
+                if !is_response_source_valid(&pending_request.sink, response_source) {
+                    self.responses_rejected.fetch_add(1, Ordering::Relaxed);
+                    info!(
+                        request_id = reqid.to_hyphenated_string(),
+                        source = response_source.to_uri(true),
+                        "rejecting RPC Response message from other source than invoked method"
+                    );
+                    return;
+                }
+            }
+        }
+        if let Some(PendingRequest {
+            sender: ResponseSender::Stream(sender),
+            ..
+        }) = pending_requests.get(reqid)
+        {
+            // the stream removes the pending request once it has received the last chunk
+            self.responses_received.fetch_add(1, Ordering::Relaxed);
+            if let Err(_e) = sender.send(response_message) {
+                debug!(
+                    request_id = reqid.to_hyphenated_string(),
+                    "failed to deliver RPC Response message, stream already closed"
+                );
+            }
+        } else if let Some(PendingRequest {
+            sender: ResponseSender::Single(sender),
+            ..
+        }) = pending_requests.remove(reqid)
+        {
+            self.responses_received.fetch_add(1, Ordering::Relaxed);
             if let Err(_e) = sender.send(response_message) {
                 // channel seems to be closed already
                 debug!(
@@ -116,10 +226,24 @@ impl ResponseListener {
         }
     }
 
-    fn remove_pending_request(&self, reqid: &UUID) -> Option<Sender<UMessage>> {
-        self.pending_requests
-            .lock()
-            .map_or(None, |mut pending_requests| pending_requests.remove(reqid))
+    fn remove_pending_request(&self, reqid: &UUID) {
+        if let Ok(mut pending_requests) = self.pending_requests.lock() {
+            pending_requests.remove(reqid);
+        }
+    }
+
+    fn pending_request_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> =
+            self.pending_requests
+                .lock()
+                .map_or(vec![], |pending_requests| {
+                    pending_requests
+                        .keys()
+                        .map(UUID::to_hyphenated_string)
+                        .collect()
+                });
+        ids.sort();
+        ids
     }
 
     #[cfg(test)]
@@ -160,6 +284,306 @@ impl UListener for ResponseListener {
     }
 }
 
+/// Options for _hedging_ RPC requests.
+///
+/// When hedging is enabled, the client sends a second, otherwise identical RPC Request message
+/// (using a different message ID) if no response has been received for the original request within
+/// a given amount of time. The client then uses the first successful response and discards the other
+/// request. This may improve tail latency at the expense of additional load on the service provider.
+///
+/// Hedging should only be used for invoking idempotent service operations.
+///
+/// Hedged requests are subject to the client's [pending request limits](`InMemoryRpcClient::with_pending_request_limits`).
+/// A hedged request is not sent if any of the limits has been reached at the time the hedging delay expires,
+/// in which case the client only waits for the response to the original request. The number of hedged requests
+/// that have been skipped for this reason is included in the client's [diagnostics](`InMemoryRpcClient::diagnostics`).
+///
+/// If the original request fails with an error that indicates that retrying the request will not help,
+/// e.g. because the method does not exist or the client is not authorized to invoke it, the invocation fails
+/// with that error right away, without waiting for the response to the hedged request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HedgingPolicy {
+    delay: Duration,
+    alternate_sink: Option<UUri>,
+}
+
+impl HedgingPolicy {
+    /// Creates a new policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - The amount of time to wait for a response to the original request before
+    ///             sending the hedged request.
+    /// * `alternate_sink` - The service instance to send the hedged request to or `None` to send it
+    ///             to the same service instance as the original request. Only the authority, entity ID
+    ///             and major version of this URI are used, the resource ID is taken from the method
+    ///             being invoked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use up_rust::{communication::HedgingPolicy, UUri};
+    ///
+    /// let backup_service = UUri::try_from_parts("backup", 0x0001, 0x01, 0x0000).unwrap();
+    /// let policy = HedgingPolicy::new(Duration::from_millis(50), Some(backup_service));
+    /// assert_eq!(policy.delay(), Duration::from_millis(50));
+    /// ```
+    pub fn new(delay: Duration, alternate_sink: Option<UUri>) -> Self {
+        HedgingPolicy {
+            delay,
+            alternate_sink,
+        }
+    }
+
+    /// Gets the amount of time to wait before sending the hedged request.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Gets the service instance that hedged requests are sent to.
+    pub fn alternate_sink(&self) -> Option<&UUri> {
+        self.alternate_sink.as_ref()
+    }
+}
+
+/// The resource ID of the topic that an [`InMemoryRpcClient`] uses as the origin of
+/// notifications about cancelled RPC invocations.
+///
+/// A cancellation notification is sent to the service provider's entity (resource ID 0) and
+/// contains the [`UUID`] of the cancelled RPC Request message as payload.
+pub const RPC_CANCELLATION_RESOURCE_ID: u16 = crate::wellknown::RPC_CANCELLATION_RESOURCE_ID;
+
+#[derive(Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// A handle for cancelling an RPC invocation that is in progress.
+///
+/// The handle is passed into [`InMemoryRpcClient::invoke_method_cancellable`] while the caller
+/// keeps a clone of it for cancelling the invocation, e.g. from another task.
+#[derive(Clone, Default)]
+pub struct CancellationHandle {
+    state: Arc<CancellationState>,
+}
+
+impl CancellationHandle {
+    /// Creates a new handle.
+    pub fn new() -> Self {
+        CancellationHandle::default()
+    }
+
+    /// Cancels the invocation(s) that this handle has been passed into.
+    ///
+    /// Invocations that are started with this handle after it has been cancelled fail immediately.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Release);
+        self.state.notify.notify_waiters();
+    }
+
+    /// Checks if this handle has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Acquire)
+    }
+
+    async fn cancelled(&self) {
+        loop {
+            let mut notified = pin!(self.state.notify.notified());
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Limits on the number of pending RPC invocations of an [`InMemoryRpcClient`].
+///
+/// An invocation is pending from the moment the RPC Request message is about to be sent until
+/// the response has been received, the request has timed out or the invocation has been cancelled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PendingRequestLimits {
+    /// The maximum number of pending invocations of the client, or `None` for no limit.
+    pub max_pending: Option<usize>,
+    /// The maximum number of pending invocations of methods of the same service instance,
+    /// or `None` for no limit.
+    pub max_pending_per_sink: Option<usize>,
+}
+
+/// A reservation of capacity for invoking a method of a particular service instance.
+///
+/// A permit is obtained by means of [`InMemoryRpcClient::reserve`] and is consumed by
+/// [`InMemoryRpcClient::invoke_method_with_permit`]. The reserved capacity is released
+/// when the invocation has completed or when the permit is dropped without being used.
+pub struct InvocationPermit {
+    sink: UUri,
+    _total: Option<OwnedSemaphorePermit>,
+    _per_sink: Option<SinkPermit>,
+}
+
+impl InvocationPermit {
+    /// Gets the service instance that this permit has been obtained for.
+    pub fn sink(&self) -> &UUri {
+        &self.sink
+    }
+}
+
+fn service_instance(method: &UUri) -> UUri {
+    UUri {
+        resource_id: 0,
+        ..method.to_owned()
+    }
+}
+
+fn resource_exhausted_error() -> ServiceInvocationError {
+    ServiceInvocationError::ResourceExhausted(
+        "too many pending requests, try again later".to_string(),
+    )
+}
+
+// service instance -> permits for invoking its methods
+type SinkSemaphores = Arc<Mutex<HashMap<UUri, Arc<Semaphore>>>>;
+
+/// A reference to the semaphore of a service instance.
+///
+/// The semaphore is removed from the limiter when the last reference to it is dropped, i.e. when
+/// there are no more pending invocations of (or reservations for) the service instance.
+struct SinkSemaphore {
+    sink: UUri,
+    semaphore: Arc<Semaphore>,
+    semaphores: SinkSemaphores,
+}
+
+impl SinkSemaphore {
+    fn try_acquire(self) -> Result<SinkPermit, TryAcquireError> {
+        let permit = self.semaphore.clone().try_acquire_owned()?;
+        Ok(SinkPermit {
+            _permit: permit,
+            _semaphore: self,
+        })
+    }
+
+    async fn acquire(self) -> Result<SinkPermit, AcquireError> {
+        let permit = self.semaphore.clone().acquire_owned().await?;
+        Ok(SinkPermit {
+            _permit: permit,
+            _semaphore: self,
+        })
+    }
+}
+
+impl Drop for SinkSemaphore {
+    fn drop(&mut self) {
+        let Ok(mut semaphores) = self.semaphores.lock() else {
+            return;
+        };
+        // references are only handed out while holding the lock, so the semaphore cannot be
+        // picked up by another invocation if it is only referenced by the map and by this instance
+        if Arc::strong_count(&self.semaphore) == 2
+            && semaphores
+                .get(&self.sink)
+                .is_some_and(|semaphore| Arc::ptr_eq(semaphore, &self.semaphore))
+        {
+            semaphores.remove(&self.sink);
+        }
+    }
+}
+
+struct SinkPermit {
+    // needs to be dropped before the semaphore reference
+    _permit: OwnedSemaphorePermit,
+    _semaphore: SinkSemaphore,
+}
+
+#[derive(Default)]
+struct InvocationLimiter {
+    total: Option<Arc<Semaphore>>,
+    max_per_sink: Option<usize>,
+    per_sink: SinkSemaphores,
+}
+
+impl InvocationLimiter {
+    fn new(limits: PendingRequestLimits) -> Self {
+        InvocationLimiter {
+            total: limits.max_pending.map(|max| Arc::new(Semaphore::new(max))),
+            max_per_sink: limits.max_pending_per_sink,
+            per_sink: SinkSemaphores::default(),
+        }
+    }
+
+    fn sink_semaphore(&self, sink: &UUri) -> Option<SinkSemaphore> {
+        let max = self.max_per_sink?;
+        self.per_sink
+            .lock()
+            .ok()
+            .map(|mut semaphores| SinkSemaphore {
+                sink: sink.to_owned(),
+                semaphore: semaphores
+                    .entry(sink.to_owned())
+                    .or_insert_with(|| Arc::new(Semaphore::new(max)))
+                    .clone(),
+                semaphores: self.per_sink.clone(),
+            })
+    }
+
+    /// Reserves capacity for invoking a method without waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceInvocationError::ResourceExhausted`] if any of the limits has been reached.
+    fn try_acquire(&self, method: &UUri) -> Result<InvocationPermit, ServiceInvocationError> {
+        let sink = service_instance(method);
+        let total = self
+            .total
+            .clone()
+            .map(|semaphore| semaphore.try_acquire_owned())
+            .transpose()
+            .map_err(|_e| resource_exhausted_error())?;
+        let per_sink = self
+            .sink_semaphore(&sink)
+            .map(|semaphore| semaphore.try_acquire())
+            .transpose()
+            .map_err(|_e| resource_exhausted_error())?;
+        Ok(InvocationPermit {
+            sink,
+            _total: total,
+            _per_sink: per_sink,
+        })
+    }
+
+    /// Reserves capacity for invoking a method, waiting for pending invocations to complete if necessary.
+    async fn acquire(&self, method: &UUri) -> Result<InvocationPermit, ServiceInvocationError> {
+        let sink = service_instance(method);
+        // acquire the per sink permit first in order to not block invocations of other services
+        let per_sink = match self.sink_semaphore(&sink) {
+            Some(semaphore) => Some(
+                semaphore
+                    .acquire()
+                    .await
+                    .map_err(|_e| resource_exhausted_error())?,
+            ),
+            None => None,
+        };
+        let total = match self.total.clone() {
+            Some(semaphore) => Some(
+                semaphore
+                    .acquire_owned()
+                    .await
+                    .map_err(|_e| resource_exhausted_error())?,
+            ),
+            None => None,
+        };
+        Ok(InvocationPermit {
+            sink,
+            _total: total,
+            _per_sink: per_sink,
+        })
+    }
+}
+
 /// An [`RpcClient`] which keeps all information about pending requests in memory.
 ///
 /// The client requires an implementations of [`UTransport`] for sending RPC Request messages
@@ -173,10 +597,26 @@ impl UListener for ResponseListener {
 /// implementation and a response handler is created and registered with the listener.
 /// When an RPC Response message arrives from the service, the corresponding handler is being looked
 /// up and invoked.
+///
+/// The client can optionally be configured to [hedge requests](`Self::with_hedging`).
+///
+/// Invocations can be [cancelled](`Self::invoke_method_cancellable`) by the caller while waiting for
+/// the response.
+///
+/// The number of pending invocations can be [limited](`Self::with_pending_request_limits`) in order to
+/// bound the amount of memory used for keeping track of them.
 pub struct InMemoryRpcClient {
     transport: Arc<dyn UTransport>,
     uri_provider: Arc<dyn LocalUriProvider>,
     response_listener: Arc<ResponseListener>,
+    requests_sent: AtomicU64,
+    requests_timed_out: AtomicU64,
+    hedges_skipped: AtomicU64,
+    hedging_policy: Option<HedgingPolicy>,
+    notify_cancellations: bool,
+    limiter: InvocationLimiter,
+    #[cfg(feature = "compression")]
+    max_decompressed_size: usize,
 }
 
 impl InMemoryRpcClient {
@@ -197,6 +637,9 @@ impl InMemoryRpcClient {
     ) -> Result<Self, RegistrationError> {
         let response_listener = Arc::new(ResponseListener {
             pending_requests: Mutex::new(HashMap::new()),
+            responses_received: AtomicU64::new(0),
+            verify_response_source: AtomicBool::new(false),
+            responses_rejected: AtomicU64::new(0),
         });
         transport
             .register_listener(
@@ -211,27 +654,229 @@ impl InMemoryRpcClient {
             transport,
             uri_provider,
             response_listener,
+            requests_sent: AtomicU64::new(0),
+            requests_timed_out: AtomicU64::new(0),
+            hedges_skipped: AtomicU64::new(0),
+            hedging_policy: None,
+            notify_cancellations: false,
+            limiter: InvocationLimiter::default(),
+            #[cfg(feature = "compression")]
+            max_decompressed_size: super::DEFAULT_MAX_DECOMPRESSED_SIZE,
         })
     }
 
-    #[cfg(test)]
-    fn contains_pending_request(&self, reqid: &UUID) -> bool {
-        self.response_listener.contains(reqid)
+    /// Sets limits on the number of pending invocations.
+    ///
+    /// Invocations that would exceed any of the limits fail with [`ServiceInvocationError::ResourceExhausted`]
+    /// without sending an RPC Request message. Callers that prefer waiting for capacity to become available
+    /// can [reserve](`Self::reserve`) capacity before invoking a method.
+    ///
+    /// There are no limits by default.
+    pub fn with_pending_request_limits(mut self, limits: PendingRequestLimits) -> Self {
+        self.limiter = InvocationLimiter::new(limits);
+        self
     }
-}
 
-#[async_trait]
-impl RpcClient for InMemoryRpcClient {
-    async fn invoke_method(
+    /// Reserves capacity for invoking a method of a service instance.
+    ///
+    /// This function waits until pending invocations have completed if any of the
+    /// [limits](`Self::with_pending_request_limits`) have been reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method to invoke. The permit can be used for invoking any method of the
+    ///              same service instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if capacity cannot be reserved.
+    pub async fn reserve(&self, method: &UUri) -> Result<InvocationPermit, ServiceInvocationError> {
+        self.limiter.acquire(method).await
+    }
+
+    /// Invokes a method on a service using previously [reserved](`Self::reserve`) capacity.
+    ///
+    /// Apart from not being subject to the client's limits on pending invocations, this function behaves
+    /// like [`RpcClient::invoke_method`]. Hedged requests are subject to the limits, though.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceInvocationError::InvalidArgument`] if the permit has been obtained for a different
+    /// service instance. Otherwise, returns the same errors as [`RpcClient::invoke_method`].
+    pub async fn invoke_method_with_permit(
         &self,
+        permit: InvocationPermit,
         method: UUri,
         call_options: CallOptions,
         payload: Option<UPayload>,
     ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        if permit.sink() != &service_instance(&method) {
+            return Err(ServiceInvocationError::InvalidArgument(
+                "permit has been obtained for different service instance".to_string(),
+            ));
+        }
+        self.invoke(method, call_options, payload, Some(permit))
+            .await
+    }
+
+    /// Enables hedging of requests.
+    ///
+    /// Note that hedging is applied to all requests sent by this client, so it should only
+    /// be enabled for clients that are used for invoking idempotent service operations.
+    pub fn with_hedging(mut self, policy: HedgingPolicy) -> Self {
+        self.hedging_policy = Some(policy);
+        self
+    }
+
+    /// Sets the maximum number of bytes that compressed response payloads may be decompressed to.
+    ///
+    /// Invocations whose response payload exceeds the limit after decompression fail with
+    /// [`ServiceInvocationError::ResourceExhausted`]. The limit defaults to
+    /// [`DEFAULT_MAX_DECOMPRESSED_SIZE`](super::DEFAULT_MAX_DECOMPRESSED_SIZE).
+    #[cfg(feature = "compression")]
+    pub fn with_max_decompressed_size(mut self, max_size: usize) -> Self {
+        self.max_decompressed_size = max_size;
+        self
+    }
+
+    /// Enables verification of the source of received RPC Response messages.
+    ///
+    /// When enabled, the client only accepts RPC Response messages which have been sent by the method that
+    /// the corresponding request has been sent to, i.e. the response message's source must match the request
+    /// message's sink. Other response messages, e.g. spoofed or misrouted messages on a shared broker, are
+    /// discarded and counted in the client's [diagnostics](`Self::diagnostics`).
+    ///
+    /// The authority of the response message's source is not verified for requests that have been sent to
+    /// a local method, i.e. a method URI with an empty authority.
+    pub fn with_response_source_verification(self) -> Self {
+        self.response_listener
+            .verify_response_source
+            .store(true, Ordering::Relaxed);
+        self
+    }
+
+    /// Enables sending of notifications to service providers about cancelled invocations.
+    ///
+    /// See [`RPC_CANCELLATION_RESOURCE_ID`] for the format of the notifications.
+    pub fn with_cancellation_notifications(mut self) -> Self {
+        self.notify_cancellations = true;
+        self
+    }
+
+    /// Invokes a method on a service and allows the caller to cancel the invocation
+    /// before the response has arrived.
+    ///
+    /// Apart from cancellation, this function behaves like [`RpcClient::invoke_method`].
+    /// When the invocation gets cancelled, the client immediately stops waiting for the response
+    /// and forgets about the pending request, i.e. a response arriving later on is ignored.
+    /// If [enabled](`Self::with_cancellation_notifications`), the client also notifies the
+    /// service provider about the cancelled request.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The URI representing the method to invoke.
+    /// * `call_options` - Options to include in the request message.
+    /// * `payload` - The (optional) payload to include in the request message.
+    /// * `cancellation` - The handle that the caller can use for cancelling the invocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with code [`UCode::CANCELLED`] if the invocation has been cancelled.
+    /// Otherwise, returns the same errors as [`RpcClient::invoke_method`].
+    pub async fn invoke_method_cancellable(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+        cancellation: &CancellationHandle,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        if cancellation.is_cancelled() {
+            return Err(cancelled_error());
+        }
+        // the request ID is needed for notifying the service provider about the cancellation
         let message_id = call_options.message_id().unwrap_or_else(UUID::build);
+        let call_options = CallOptions {
+            message_id: Some(message_id.clone()),
+            ..call_options
+        };
+        let service = UUri {
+            resource_id: 0,
+            ..method.clone()
+        };
+
+        {
+            let mut invocation = pin!(self.invoke_method(method, call_options, payload));
+            let mut cancelled = pin!(cancellation.cancelled());
+            let outcome = poll_fn(|cx| {
+                if let Poll::Ready(result) = invocation.as_mut().poll(cx) {
+                    return Poll::Ready(Some(result));
+                }
+                cancelled.as_mut().poll(cx).map(|_| None)
+            })
+            .await;
+            if let Some(result) = outcome {
+                return result;
+            }
+            // the pending invocation is dropped at the end of this block,
+            // which also removes the pending request
+        }
+        debug!(
+            request_id = message_id.to_hyphenated_string(),
+            "invocation of service operation has been cancelled"
+        );
+        if self.notify_cancellations {
+            self.send_cancellation_notification(&message_id, service)
+                .await;
+        }
+        Err(cancelled_error())
+    }
 
+    async fn send_cancellation_notification(&self, request_id: &UUID, service: UUri) {
+        let notification = UMessageBuilder::notification(
+            self.uri_provider
+                .get_resource_uri(RPC_CANCELLATION_RESOURCE_ID),
+            service,
+        )
+        .build_with_protobuf_payload(request_id);
+        let result = match notification {
+            Ok(msg) => self.transport.send(msg).await,
+            Err(e) => Err(UStatus::fail_with_code(UCode::INTERNAL, e.to_string())),
+        };
+        if let Err(e) = result {
+            info!(
+                request_id = request_id.to_hyphenated_string(),
+                "failed to send cancellation notification: {}", e
+            );
+        }
+    }
+
+    /// Gets a snapshot of this client's state.
+    pub fn diagnostics(&self) -> RpcClientDiagnostics {
+        RpcClientDiagnostics {
+            pending_requests: self.response_listener.pending_request_ids(),
+            requests_sent: self.requests_sent.load(Ordering::Relaxed),
+            responses_received: self
+                .response_listener
+                .responses_received
+                .load(Ordering::Relaxed),
+            requests_timed_out: self.requests_timed_out.load(Ordering::Relaxed),
+            responses_rejected: self
+                .response_listener
+                .responses_rejected
+                .load(Ordering::Relaxed),
+            hedges_skipped: self.hedges_skipped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn build_request_message(
+        &self,
+        method: UUri,
+        message_id: &UUID,
+        call_options: &CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<UMessage, ServiceInvocationError> {
         let mut builder = UMessageBuilder::request(
-            method.clone(),
+            method,
             self.uri_provider.get_source_uri(),
             call_options.ttl(),
         );
@@ -239,22 +884,67 @@ impl RpcClient for InMemoryRpcClient {
         if let Some(token) = call_options.token() {
             builder.with_token(token.to_owned());
         }
+        if let Some(key) = call_options.idempotency_key() {
+            builder.with_idempotency_key(key);
+        }
+        if let Some(session_id) = call_options.session_id() {
+            builder.with_session_id(session_id);
+        }
         if let Some(priority) = call_options.priority() {
+            let priority = call_options
+                .rpc_priority_policy()
+                .apply(priority)
+                .map_err(|e| ServiceInvocationError::InvalidArgument(e.to_string()))?;
             builder.with_priority(priority);
         }
-        let rpc_request_message = build_message(&mut builder, payload)
+        #[cfg_attr(not(feature = "compression"), allow(unused_mut))]
+        let mut request_message = build_message(&mut builder, payload)
             .map_err(|e| ServiceInvocationError::InvalidArgument(e.to_string()))?;
+        #[cfg(feature = "compression")]
+        request_message
+            .attributes
+            .mut_or_insert_default()
+            .set_accepted_encodings([super::DEFLATE_ENCODING]);
+        Ok(request_message)
+    }
+
+    fn process_response_message(
+        &self,
+        #[cfg_attr(not(feature = "compression"), allow(unused_mut))] mut response: UMessage,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        #[cfg(feature = "compression")]
+        super::compression::inflate_payload(&mut response, self.max_decompressed_size)?;
+        handle_response_message(response)
+    }
+
+    async fn invoke_once(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+        permit: Option<InvocationPermit>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        // the permit is released when the invocation has completed
+        let _permit = match permit {
+            Some(permit) => permit,
+            None => self.limiter.try_acquire(&method)?,
+        };
+        let message_id = call_options.message_id().unwrap_or_else(UUID::build);
+        let sink = method.clone();
+        let rpc_request_message =
+            self.build_request_message(method, &message_id, &call_options, payload)?;
 
         let receiver = self
             .response_listener
-            .try_add_pending_request(message_id.clone())?;
-        self.transport
-            .send(rpc_request_message)
-            .await
-            .map_err(|e| {
-                self.response_listener.remove_pending_request(&message_id);
-                e
-            })?;
+            .try_add_pending_request(message_id.clone(), sink)?;
+        // makes sure that the pending request is removed when the invocation has completed
+        // or has been cancelled
+        let _pending_request = PendingRequestGuard {
+            response_listener: &self.response_listener,
+            reqid: &message_id,
+        };
+        self.transport.send(rpc_request_message).await?;
+        self.requests_sent.fetch_add(1, Ordering::Relaxed);
         debug!(
             request_id = message_id.to_hyphenated_string(),
             ttl = call_options.ttl(),
@@ -268,17 +958,16 @@ impl RpcClient for InMemoryRpcClient {
                     ttl = call_options.ttl(),
                     "invocation of service operation has timed out"
                 );
-                self.response_listener.remove_pending_request(&message_id);
+                self.requests_timed_out.fetch_add(1, Ordering::Relaxed);
                 Err(ServiceInvocationError::DeadlineExceeded)
             }
             Ok(result) => match result {
-                Ok(response_message) => handle_response_message(response_message),
+                Ok(response_message) => self.process_response_message(response_message),
                 Err(_e) => {
                     debug!(
                         request_id = message_id.to_hyphenated_string(),
                         "response listener failed to forward response message"
                     );
-                    self.response_listener.remove_pending_request(&message_id);
                     Err(ServiceInvocationError::Internal(
                         "error receiving response message".to_string(),
                     ))
@@ -286,6 +975,226 @@ impl RpcClient for InMemoryRpcClient {
             },
         }
     }
+
+    /// Invokes a method whose result is streamed back in multiple chunks.
+    ///
+    /// The service provider is expected to send its result by means of a
+    /// [`ResponseStreamWriter`](super::ResponseStreamWriter). Results of service providers
+    /// that do not support streaming are yielded as a single chunk.
+    ///
+    /// The time to live set in the call options is used as the maximum amount of time to wait for
+    /// each chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC Request message could not be sent.
+    pub async fn invoke_method_streaming(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<ResponseStream, ServiceInvocationError> {
+        let permit = self.limiter.try_acquire(&method)?;
+        let message_id = call_options.message_id().unwrap_or_else(UUID::build);
+        let sink = method.clone();
+        let rpc_request_message =
+            self.build_request_message(method, &message_id, &call_options, payload)?;
+
+        let receiver = self
+            .response_listener
+            .try_add_pending_stream(message_id.clone(), sink)?;
+        let response_listener = self.response_listener.clone();
+        let reqid = message_id.clone();
+        let stream = ResponseStream::new(
+            receiver,
+            Duration::from_millis(call_options.ttl() as u64),
+            Box::new(move || {
+                response_listener.remove_pending_request(&reqid);
+                drop(permit);
+            }),
+        );
+        #[cfg(feature = "compression")]
+        let stream = stream.with_max_decompressed_size(self.max_decompressed_size);
+        // dropping the stream removes the pending request if the request cannot be sent
+        self.transport.send(rpc_request_message).await?;
+        self.requests_sent.fetch_add(1, Ordering::Relaxed);
+        debug!(
+            request_id = message_id.to_hyphenated_string(),
+            ttl = call_options.ttl(),
+            "successfully sent RPC Request message expecting a streamed response"
+        );
+        Ok(stream)
+    }
+
+    /// Invokes a method, hedging the request if configured.
+    async fn invoke(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+        permit: Option<InvocationPermit>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        let Some(policy) = self.hedging_policy.as_ref() else {
+            return self
+                .invoke_once(method, call_options, payload, permit)
+                .await;
+        };
+        let hedge_delay_millis = u32::try_from(policy.delay.as_millis()).unwrap_or(u32::MAX);
+        if hedge_delay_millis >= call_options.ttl() {
+            // the hedged request would expire before being sent
+            return self
+                .invoke_once(method, call_options, payload, permit)
+                .await;
+        }
+
+        let hedge_method = policy.alternate_sink.as_ref().map_or_else(
+            || method.clone(),
+            |sink| UUri {
+                resource_id: method.resource_id,
+                ..sink.to_owned()
+            },
+        );
+        // the hedged request needs to use its own message ID in order to be able
+        // to correlate its response
+        let hedge_call_options = CallOptions {
+            ttl: call_options.ttl() - hedge_delay_millis,
+            message_id: None,
+            ..call_options.clone()
+        };
+        let hedge_payload = payload.clone();
+
+        let primary = self.invoke_once(method, call_options, payload, permit);
+        let hedge = async {
+            tokio::time::sleep(policy.delay).await;
+            let permit = match self.limiter.try_acquire(&hedge_method) {
+                Ok(permit) => permit,
+                Err(e) => {
+                    debug!("skipping hedged RPC Request message: {}", e);
+                    self.hedges_skipped.fetch_add(1, Ordering::Relaxed);
+                    return Err(e);
+                }
+            };
+            debug!("sending hedged RPC Request message");
+            self.invoke_once(
+                hedge_method,
+                hedge_call_options,
+                hedge_payload,
+                Some(permit),
+            )
+            .await
+        };
+        first_success(primary, hedge).await
+    }
+
+    #[cfg(test)]
+    fn contains_pending_request(&self, reqid: &UUID) -> bool {
+        self.response_listener.contains(reqid)
+    }
+}
+
+#[async_trait]
+impl RpcClient for InMemoryRpcClient {
+    async fn invoke_method(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        self.invoke(method, call_options, payload, None).await
+    }
+
+    async fn invoke_no_response(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<(), ServiceInvocationError> {
+        let message_id = call_options.message_id().unwrap_or_else(UUID::build);
+        let rpc_request_message =
+            self.build_request_message(method, &message_id, &call_options, payload)?;
+        self.transport.send(rpc_request_message).await?;
+        self.requests_sent.fetch_add(1, Ordering::Relaxed);
+        debug!(
+            request_id = message_id.to_hyphenated_string(),
+            "successfully sent RPC Request message not expecting a response"
+        );
+        Ok(())
+    }
+}
+
+fn cancelled_error() -> ServiceInvocationError {
+    ServiceInvocationError::from(UStatus::fail_with_code(
+        UCode::CANCELLED,
+        "invocation has been cancelled",
+    ))
+}
+
+/// Ensures that a pending request is removed from the response listener when dropped.
+struct PendingRequestGuard<'a> {
+    response_listener: &'a ResponseListener,
+    reqid: &'a UUID,
+}
+
+impl Drop for PendingRequestGuard<'_> {
+    fn drop(&mut self) {
+        self.response_listener.remove_pending_request(self.reqid);
+    }
+}
+
+/// Checks if an invocation that has failed with a given error might succeed when being tried again.
+fn is_retryable(error: &ServiceInvocationError) -> bool {
+    UStatus::from(error.clone()).get_code().is_retryable()
+}
+
+/// Polls two invocations concurrently.
+///
+/// # Returns
+///
+/// The result of the invocation that first completes successfully, or the primary invocation's
+/// error if both invocations fail. The primary invocation's error is returned right away if it
+/// is not [retryable](`UCode::is_retryable`). The other invocation is dropped (and thus cancelled)
+/// as soon as a result is available.
+async fn first_success<P, H>(
+    primary: P,
+    hedge: H,
+) -> Result<Option<UPayload>, ServiceInvocationError>
+where
+    P: Future<Output = Result<Option<UPayload>, ServiceInvocationError>>,
+    H: Future<Output = Result<Option<UPayload>, ServiceInvocationError>>,
+{
+    let mut primary = pin!(primary);
+    let mut hedge = pin!(hedge);
+    let mut primary_error = None;
+    let mut hedge_failed = false;
+    poll_fn(|cx| {
+        if primary_error.is_none() {
+            if let Poll::Ready(result) = primary.as_mut().poll(cx) {
+                match result {
+                    Ok(response) => return Poll::Ready(Ok(response)),
+                    Err(e) if !is_retryable(&e) => return Poll::Ready(Err(e)),
+                    Err(e) => primary_error = Some(e),
+                }
+            }
+        }
+        if !hedge_failed {
+            if let Poll::Ready(result) = hedge.as_mut().poll(cx) {
+                match result {
+                    Ok(response) => return Poll::Ready(Ok(response)),
+                    Err(e) => {
+                        debug!("hedged RPC Request has failed: {}", e);
+                        hedge_failed = true;
+                    }
+                }
+            }
+        }
+        if hedge_failed {
+            if let Some(e) = primary_error.take() {
+                return Poll::Ready(Err(e));
+            }
+        }
+        Poll::Pending
+    })
+    .await
 }
 
 #[cfg(test)]
@@ -296,9 +1205,13 @@ mod tests {
     use super::*;
 
     use protobuf::{well_known_types::wrappers::StringValue, Enum};
+    use test_case::test_case;
     use tokio::{join, sync::Notify};
 
-    use crate::{utransport::MockTransport, StaticUriProvider, UMessageBuilder, UPriority, UUri};
+    use crate::{
+        utransport::MockTransport, RpcPriorityPolicy, StaticUriProvider, UMessageBuilder,
+        UPriority, UUri,
+    };
 
     fn new_uri_provider() -> Arc<dyn LocalUriProvider> {
         Arc::new(StaticUriProvider::new("", 0x0005, 0x02))
@@ -372,6 +1285,99 @@ mod tests {
         assert!(!client.contains_pending_request(&message_id));
     }
 
+    #[tokio::test]
+    async fn test_invoke_method_rejects_low_priority() {
+        // GIVEN an RPC client
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .once()
+            .returning(|_source_filter, _sink_filter, _listener| Ok(()));
+        mock_transport.expect_do_send().never();
+        let client = InMemoryRpcClient::new(Arc::new(mock_transport), new_uri_provider())
+            .await
+            .unwrap();
+
+        // WHEN invoking a remote service operation using a priority lower than CS4
+        let call_options =
+            CallOptions::for_rpc_request(5_000, None, None, Some(UPriority::UPRIORITY_CS2));
+        let response = client
+            .invoke_method(service_method_uri(), call_options, None)
+            .await;
+
+        // THEN the invocation fails without a request having been sent
+        assert!(response.is_err_and(|e| matches!(e, ServiceInvocationError::InvalidArgument(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_upgrades_low_priority() {
+        // GIVEN an RPC client
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .once()
+            .returning(|_source_filter, _sink_filter, _listener| Ok(()));
+        // with a transport that expects a request using priority CS4
+        mock_transport
+            .expect_do_send()
+            .once()
+            .withf(|request_message| {
+                request_message.attributes.priority == UPriority::UPRIORITY_CS4.into()
+            })
+            .returning(|_request_message| {
+                Err(UStatus::fail_with_code(
+                    UCode::UNAVAILABLE,
+                    "transport not available",
+                ))
+            });
+        let client = InMemoryRpcClient::new(Arc::new(mock_transport), new_uri_provider())
+            .await
+            .unwrap();
+
+        // WHEN invoking a remote service operation using a priority lower than CS4
+        // and requesting the priority to be upgraded
+        let call_options =
+            CallOptions::for_rpc_request(5_000, None, None, Some(UPriority::UPRIORITY_CS2))
+                .with_rpc_priority_policy(RpcPriorityPolicy::Upgrade);
+        let response = client
+            .invoke_method(service_method_uri(), call_options, None)
+            .await;
+
+        // THEN the request has been sent using priority CS4
+        assert!(response.is_err_and(|e| matches!(e, ServiceInvocationError::Unavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invoke_no_response_returns_after_sending_request() {
+        // GIVEN an RPC client
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .once()
+            .returning(|_source_filter, _sink_filter, _listener| Ok(()));
+        mock_transport
+            .expect_do_send()
+            .once()
+            .withf(|request_message| request_message.is_request())
+            .returning(|_request_message| Ok(()));
+        let client = InMemoryRpcClient::new(Arc::new(mock_transport), new_uri_provider())
+            .await
+            .unwrap();
+
+        // WHEN invoking a remote service operation without waiting for a response
+        let message_id = UUID::build();
+        let call_options =
+            CallOptions::for_rpc_request(5_000, Some(message_id.clone()), None, None);
+        let result = client
+            .invoke_no_response(service_method_uri(), call_options, None)
+            .await;
+
+        // THEN the invocation succeeds without the request being tracked
+        assert!(result.is_ok());
+        assert!(!client.contains_pending_request(&message_id));
+        assert_eq!(client.diagnostics().requests_sent, 1);
+    }
+
     #[tokio::test]
     async fn test_invoke_method_succeeds() {
         let message_id = UUID::build();
@@ -476,6 +1482,95 @@ mod tests {
         assert!(!rpc_client.contains_pending_request(&message_id));
     }
 
+    #[test_case("//vehicle/A100/1/7", "//vehicle/A100/1/7", true; "for matching source")]
+    #[test_case("//vehicle/A100/1/7", "//other/A100/1/7", false; "for other authority")]
+    #[test_case("//vehicle/A100/1/7", "//vehicle/A100/1/8", false; "for other method")]
+    #[test_case("/A100/1/7", "//vehicle/A100/1/7", true; "for local method")]
+    #[test_case("/A100/1/7", "//vehicle/B100/1/7", false; "for other local entity")]
+    fn test_is_response_source_valid(request_sink: &str, response_source: &str, expected: bool) {
+        assert_eq!(
+            is_response_source_valid(
+                &UUri::try_from(request_sink).unwrap(),
+                &UUri::try_from(response_source).unwrap()
+            ),
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_rejects_response_from_other_source() {
+        let message_id = UUID::build();
+        let (captured_listener_tx, captured_listener_rx) = tokio::sync::oneshot::channel();
+        let request_sent = Arc::new(Notify::new());
+        let request_sent_clone = request_sent.clone();
+
+        // GIVEN an RPC client verifying the source of response messages
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .once()
+            .return_once(move |_source_filter, _sink_filter, listener| {
+                captured_listener_tx
+                    .send(listener)
+                    .map_err(|_e| UStatus::fail("cannot capture listener"))
+            });
+        mock_transport
+            .expect_do_send()
+            .once()
+            .returning(move |_request_message| {
+                request_sent_clone.notify_one();
+                Ok(())
+            });
+        let uri_provider = new_uri_provider();
+        let rpc_client = Arc::new(
+            InMemoryRpcClient::new(Arc::new(mock_transport), uri_provider.clone())
+                .await
+                .unwrap()
+                .with_response_source_verification(),
+        );
+
+        // WHEN invoking a remote service operation
+        let client = rpc_client.clone();
+        let call_options =
+            CallOptions::for_rpc_request(5_000, Some(message_id.clone()), None, None);
+        let response_handle = tokio::spawn(async move {
+            client
+                .invoke_method(service_method_uri(), call_options, None)
+                .await
+        });
+        let (response_listener_result, _) = join!(captured_listener_rx, request_sent.notified());
+        let response_listener = response_listener_result.unwrap();
+
+        // AND a response is received from another method
+        let spoofed_response = UMessageBuilder::response(
+            uri_provider.get_source_uri(),
+            message_id.clone(),
+            UUri {
+                resource_id: 0x2000,
+                ..service_method_uri()
+            },
+        )
+        .build()
+        .unwrap();
+        response_listener.on_receive(spoofed_response).await;
+
+        // THEN the response is discarded
+        assert!(rpc_client.contains_pending_request(&message_id));
+        assert_eq!(rpc_client.diagnostics().responses_rejected, 1);
+
+        // AND the response of the invoked method is accepted
+        let response = UMessageBuilder::response(
+            uri_provider.get_source_uri(),
+            message_id.clone(),
+            service_method_uri(),
+        )
+        .build()
+        .unwrap();
+        response_listener.on_receive(response).await;
+        assert!(response_handle.await.unwrap().is_ok());
+        assert_eq!(rpc_client.diagnostics().responses_received, 1);
+    }
+
     #[tokio::test]
     async fn test_invoke_method_fails_on_repeated_invocation() {
         let message_id = UUID::build();
@@ -559,6 +1654,59 @@ mod tests {
         assert!(in_memory_rpc_client.contains_pending_request(&message_id));
     }
 
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_invoke_method_fails_for_response_exceeding_max_decompressed_size() {
+        // GIVEN an RPC client that accepts response payloads of at most 100 bytes
+        let (captured_listener_tx, captured_listener_rx) = tokio::sync::oneshot::channel();
+        let (sent_messages_tx, mut sent_messages) = tokio::sync::mpsc::unbounded_channel();
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .once()
+            .return_once(move |_source_filter, _sink_filter, listener| {
+                captured_listener_tx
+                    .send(listener)
+                    .map_err(|_e| UStatus::fail("cannot capture listener"))
+            });
+        mock_transport
+            .expect_do_send()
+            .returning(move |request_message| {
+                sent_messages_tx
+                    .send(request_message)
+                    .map_err(|_e| UStatus::fail("cannot capture request"))
+            });
+        let client = InMemoryRpcClient::new(Arc::new(mock_transport), new_uri_provider())
+            .await
+            .unwrap()
+            .with_max_decompressed_size(100);
+        let response_listener = captured_listener_rx.await.unwrap();
+
+        // WHEN invoking a remote service operation
+        let call_options = CallOptions::for_rpc_request(5_000, None, None, None);
+        let invocation = client.invoke_method(service_method_uri(), call_options, None);
+        let service = async {
+            // which responds with a compressed payload that decompresses to more than 100 bytes
+            let request = sent_messages.recv().await.unwrap();
+            let mut response_message =
+                UMessageBuilder::response_for_request(request.attributes.as_ref().unwrap())
+                    .build_with_payload(
+                        "Hello World ".repeat(50),
+                        crate::UPayloadFormat::UPAYLOAD_FORMAT_TEXT,
+                    )
+                    .unwrap();
+            assert!(
+                crate::communication::compression::deflate_payload(&mut response_message, 0)
+                    .unwrap()
+            );
+            response_listener.on_receive(response_message).await;
+        };
+        let (response, _) = join!(invocation, service);
+
+        // THEN the invocation fails
+        assert!(response.is_err_and(|e| matches!(e, ServiceInvocationError::ResourceExhausted(_))));
+    }
+
     #[tokio::test]
     async fn test_invoke_method_fails_with_remote_error() {
         let (captured_listener_tx, captured_listener_rx) = std::sync::mpsc::channel();
@@ -631,6 +1779,350 @@ mod tests {
         // THEN the invocation times out
         assert!(response.is_err_and(|e| { matches!(e, ServiceInvocationError::DeadlineExceeded) }));
         assert!(!client.contains_pending_request(&message_id));
+        // and the timeout is reflected in the client's diagnostics
+        let diagnostics = client.diagnostics();
+        assert!(diagnostics.pending_requests.is_empty());
+        assert_eq!(diagnostics.requests_sent, 1);
+        assert_eq!(diagnostics.responses_received, 0);
+        assert_eq!(diagnostics.requests_timed_out, 1);
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_fails_if_pending_request_limit_is_reached() {
+        // GIVEN an RPC client that allows for a single pending request per service instance
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .returning(|_source_filter, _sink_filter, _listener| Ok(()));
+        mock_transport.expect_do_send().never();
+        let client = InMemoryRpcClient::new(Arc::new(mock_transport), new_uri_provider())
+            .await
+            .unwrap()
+            .with_pending_request_limits(PendingRequestLimits {
+                max_pending: None,
+                max_pending_per_sink: Some(1),
+            });
+        // and capacity having been reserved for invoking the service
+        let permit = client.reserve(&service_method_uri()).await.unwrap();
+
+        // WHEN invoking another method of the same service
+        let method = UUri {
+            resource_id: 0x2000,
+            ..service_method_uri()
+        };
+        let response = client
+            .invoke_method(
+                method.clone(),
+                CallOptions::for_rpc_request(1_000, None, None, None),
+                None,
+            )
+            .await;
+
+        // THEN the invocation fails without sending a request
+        assert!(response.is_err_and(|e| matches!(e, ServiceInvocationError::ResourceExhausted(_))));
+        // and capacity becomes available again once the permit has been released
+        drop(permit);
+        let permit = client.reserve(&method).await.unwrap();
+        // and the service instance is forgotten once no more permits are held for it
+        drop(permit);
+        assert!(client.limiter.per_sink.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_with_permit_fails_for_other_service() {
+        // GIVEN an RPC client that allows for a single pending request
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .returning(|_source_filter, _sink_filter, _listener| Ok(()));
+        mock_transport.expect_do_send().never();
+        let client = InMemoryRpcClient::new(Arc::new(mock_transport), new_uri_provider())
+            .await
+            .unwrap()
+            .with_pending_request_limits(PendingRequestLimits {
+                max_pending: Some(1),
+                max_pending_per_sink: None,
+            });
+        let permit = client.reserve(&service_method_uri()).await.unwrap();
+
+        // WHEN using the permit for invoking a method of another service
+        let other_method = UUri {
+            ue_id: 0x0002,
+            ..service_method_uri()
+        };
+        let response = client
+            .invoke_method_with_permit(
+                permit,
+                other_method,
+                CallOptions::for_rpc_request(1_000, None, None, None),
+                None,
+            )
+            .await;
+
+        // THEN the invocation fails
+        assert!(response.is_err_and(|e| matches!(e, ServiceInvocationError::InvalidArgument(_))));
+    }
+
+    async fn new_hedging_client(
+        delay: Duration,
+        alternate_sink: Option<UUri>,
+    ) -> (
+        InMemoryRpcClient,
+        Arc<dyn UListener>,
+        tokio::sync::mpsc::UnboundedReceiver<UMessage>,
+    ) {
+        let (captured_listener_tx, captured_listener_rx) = tokio::sync::oneshot::channel();
+        let (sent_messages_tx, sent_messages_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .once()
+            .return_once(move |_source_filter, _sink_filter, listener| {
+                captured_listener_tx
+                    .send(listener)
+                    .map_err(|_e| UStatus::fail("cannot capture listener"))
+            });
+        mock_transport
+            .expect_do_send()
+            .returning(move |request_message| {
+                sent_messages_tx
+                    .send(request_message)
+                    .map_err(|_e| UStatus::fail("cannot capture request"))
+            });
+        let client = InMemoryRpcClient::new(Arc::new(mock_transport), new_uri_provider())
+            .await
+            .unwrap()
+            .with_hedging(HedgingPolicy::new(delay, alternate_sink));
+        (
+            client,
+            captured_listener_rx.await.unwrap(),
+            sent_messages_rx,
+        )
+    }
+
+    fn response_for(request: &UMessage) -> UMessage {
+        let attribs = request.attributes.as_ref().unwrap();
+        UMessageBuilder::response_for_request(attribs)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_uses_response_to_hedged_request() {
+        // GIVEN an RPC client that hedges requests to an alternate service instance
+        let alternate_sink = UUri::try_from_parts("backup", 0x0001, 0x01, 0x0000).unwrap();
+        let (client, response_listener, mut sent_messages) =
+            new_hedging_client(Duration::from_millis(20), Some(alternate_sink)).await;
+
+        // WHEN invoking a remote service operation
+        let call_options = CallOptions::for_rpc_request(5_000, None, None, None);
+        let invocation = client.invoke_method(service_method_uri(), call_options, None);
+        let service = async {
+            // for which the original service instance does not respond
+            let original_request = sent_messages.recv().await.unwrap();
+            // but the alternate service instance does
+            let hedged_request = sent_messages.recv().await.unwrap();
+            response_listener
+                .on_receive(response_for(&hedged_request))
+                .await;
+            (original_request, hedged_request)
+        };
+        let (response, (original_request, hedged_request)) = join!(invocation, service);
+
+        // THEN the invocation succeeds
+        assert!(response.is_ok());
+        // and the hedged request has been sent to the alternate service instance
+        let original_attribs = original_request.attributes.as_ref().unwrap();
+        let hedged_attribs = hedged_request.attributes.as_ref().unwrap();
+        assert_eq!(original_attribs.sink.authority_name, "");
+        assert_eq!(hedged_attribs.sink.authority_name, "backup");
+        assert_eq!(
+            hedged_attribs.sink.resource_id,
+            service_method_uri().resource_id
+        );
+        assert_ne!(original_attribs.id, hedged_attribs.id);
+        // and the original request is no longer pending
+        assert!(client.diagnostics().pending_requests.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_does_not_hedge_request_answered_in_time() {
+        // GIVEN an RPC client that hedges requests
+        let (client, response_listener, mut sent_messages) =
+            new_hedging_client(Duration::from_millis(500), None).await;
+
+        // WHEN invoking a remote service operation
+        let call_options = CallOptions::for_rpc_request(5_000, None, None, None);
+        let invocation = client.invoke_method(service_method_uri(), call_options, None);
+        let service = async {
+            // which responds before the hedging delay has expired
+            let original_request = sent_messages.recv().await.unwrap();
+            response_listener
+                .on_receive(response_for(&original_request))
+                .await;
+        };
+        let (response, _) = join!(invocation, service);
+
+        // THEN the invocation succeeds
+        assert!(response.is_ok());
+        // without a hedged request having been sent
+        assert!(sent_messages.try_recv().is_err());
+        assert_eq!(client.diagnostics().requests_sent, 1);
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_fails_on_non_retryable_error_without_hedging() {
+        // GIVEN an RPC client that hedges requests
+        let (client, response_listener, mut sent_messages) =
+            new_hedging_client(Duration::from_millis(100), None).await;
+
+        // WHEN invoking a remote service operation
+        let call_options = CallOptions::for_rpc_request(5_000, None, None, None);
+        let invocation = client.invoke_method(service_method_uri(), call_options, None);
+        let service = async {
+            // which does not exist
+            let original_request = sent_messages.recv().await.unwrap();
+            let error = UStatus::fail_with_code(UCode::NOT_FOUND, "no such method");
+            let response_message = UMessageBuilder::response_for_request(
+                original_request.attributes.as_ref().unwrap(),
+            )
+            .with_comm_status(UCode::NOT_FOUND)
+            .build_with_protobuf_payload(&error)
+            .unwrap();
+            response_listener.on_receive(response_message).await;
+        };
+        let (response, _) = join!(invocation, service);
+
+        // THEN the invocation fails with the original request's error
+        assert!(response.is_err_and(|e| matches!(e, ServiceInvocationError::NotFound(_msg))));
+        // without a hedged request having been sent
+        assert!(sent_messages.try_recv().is_err());
+        assert_eq!(client.diagnostics().requests_sent, 1);
+        assert!(client.diagnostics().pending_requests.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_skips_hedged_request_if_limits_are_reached() {
+        // GIVEN an RPC client that hedges requests
+        let (client, response_listener, mut sent_messages) =
+            new_hedging_client(Duration::from_millis(20), None).await;
+        // and which allows for a single pending request only
+        let client = client.with_pending_request_limits(PendingRequestLimits {
+            max_pending: Some(1),
+            max_pending_per_sink: None,
+        });
+
+        // WHEN invoking a remote service operation
+        let call_options = CallOptions::for_rpc_request(5_000, None, None, None);
+        let invocation = client.invoke_method(service_method_uri(), call_options, None);
+        let service = async {
+            // which responds after the hedging delay has expired
+            let original_request = sent_messages.recv().await.unwrap();
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            response_listener
+                .on_receive(response_for(&original_request))
+                .await;
+        };
+        let (response, _) = join!(invocation, service);
+
+        // THEN the invocation succeeds
+        assert!(response.is_ok());
+        // without a hedged request having been sent
+        assert!(sent_messages.try_recv().is_err());
+        let diagnostics = client.diagnostics();
+        assert_eq!(diagnostics.requests_sent, 1);
+        assert_eq!(diagnostics.hedges_skipped, 1);
+    }
+
+    async fn new_capturing_client() -> (
+        InMemoryRpcClient,
+        tokio::sync::mpsc::UnboundedReceiver<UMessage>,
+    ) {
+        let (sent_messages_tx, sent_messages_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut mock_transport = MockTransport::default();
+        mock_transport
+            .expect_do_register_listener()
+            .once()
+            .returning(|_source_filter, _sink_filter, _listener| Ok(()));
+        mock_transport.expect_do_send().returning(move |message| {
+            sent_messages_tx
+                .send(message)
+                .map_err(|_e| UStatus::fail("cannot capture message"))
+        });
+        let client = InMemoryRpcClient::new(Arc::new(mock_transport), new_uri_provider())
+            .await
+            .unwrap();
+        (client, sent_messages_rx)
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_cancellable_releases_pending_request() {
+        // GIVEN an RPC client that notifies service providers about cancelled invocations
+        let (client, mut sent_messages) = new_capturing_client().await;
+        let client = client.with_cancellation_notifications();
+        let cancellation = CancellationHandle::new();
+
+        // WHEN cancelling an invocation after the request has been sent
+        let call_options = CallOptions::for_rpc_request(5_000, None, None, None);
+        let invocation = client.invoke_method_cancellable(
+            service_method_uri(),
+            call_options,
+            None,
+            &cancellation,
+        );
+        let caller = async {
+            let request = sent_messages.recv().await.unwrap();
+            assert_eq!(client.diagnostics().pending_requests.len(), 1);
+            cancellation.cancel();
+            request
+        };
+        let (response, request) = join!(invocation, caller);
+
+        // THEN the invocation fails with a CANCELLED error
+        assert!(response.is_err_and(|e| matches!(
+            e,
+            ServiceInvocationError::RpcError(status) if status.get_code() == UCode::CANCELLED
+        )));
+        // and the request is no longer pending
+        assert!(client.diagnostics().pending_requests.is_empty());
+        // and the service provider has been notified about the cancelled request
+        let notification = sent_messages.recv().await.unwrap();
+        assert!(notification.is_notification());
+        let attribs = notification.attributes.as_ref().unwrap();
+        assert_eq!(
+            attribs.source.resource_id,
+            RPC_CANCELLATION_RESOURCE_ID as u32
+        );
+        assert_eq!(attribs.sink.ue_id, service_method_uri().ue_id);
+        assert_eq!(attribs.sink.resource_id, 0);
+        let cancelled_request_id: UUID = notification.extract_protobuf().unwrap();
+        assert_eq!(
+            Some(cancelled_request_id),
+            request.attributes.id.clone().into_option()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_cancellable_fails_for_cancelled_handle() {
+        // GIVEN an RPC client
+        let (client, mut sent_messages) = new_capturing_client().await;
+        // and a cancellation handle that has already been cancelled
+        let cancellation = CancellationHandle::new();
+        cancellation.cancel();
+        assert!(cancellation.is_cancelled());
+
+        // WHEN invoking a remote service operation using the handle
+        let call_options = CallOptions::for_rpc_request(5_000, None, None, None);
+        let response = client
+            .invoke_method_cancellable(service_method_uri(), call_options, None, &cancellation)
+            .await;
+
+        // THEN the invocation fails without a request having been sent
+        assert!(response.is_err_and(|e| matches!(
+            e,
+            ServiceInvocationError::RpcError(status) if status.get_code() == UCode::CANCELLED
+        )));
+        assert!(sent_messages.try_recv().is_err());
     }
 
     #[test]
@@ -14,27 +14,511 @@
 // [impl->req~up-language-comm-api-default-impl~1]
 
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
-    communication::build_message, LocalUriProvider, UAttributes, UAttributesError,
-    UAttributesValidators, UCode, UListener, UMessage, UMessageBuilder, UStatus, UTransport, UUri,
+    communication::build_message,
+    diagnostics::{EndpointDiagnostics, LatencyBucket, RpcServerDiagnostics},
+    LocalUriProvider, UAttributes, UAttributesError, UAttributesValidators, UCode, UListener,
+    UMessage, UMessageBuilder, UStatus, UTransport, UUri, UUID,
 };
 
-use super::{RegistrationError, RequestHandler, RpcServer, ServiceInvocationError, UPayload};
+use super::{
+    IdempotencyKey, IdempotencyStore, RegistrationError, RequestHandler, RpcServer,
+    ServiceInvocationError, UPayload,
+};
+
+/// Determines what an [`ExecutionWatchdog`] does with a handler invocation that exceeds
+/// the watchdog's threshold.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WatchdogAction {
+    /// Log a warning and let the handler continue processing the request.
+    ///
+    /// If the request's TTL elapses before the handler has completed, a response indicating
+    /// `DEADLINE_EXCEEDED` is sent back to the client and the handler is still run to completion.
+    /// The handler's outcome is discarded.
+    #[default]
+    Log,
+    /// Log a warning, abort the handler invocation and send back a response indicating
+    /// `DEADLINE_EXCEEDED` to the client.
+    Abort,
+}
+
+/// Monitors the time that a [`RequestHandler`] takes for processing a request.
+///
+/// Each invocation of the handler that takes longer than the watchdog's threshold is logged
+/// and counted in the endpoint's [diagnostics](`InMemoryRpcServer::diagnostics`). Invocations are only
+/// checked against the threshold if the request's TTL exceeds the threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutionWatchdog {
+    threshold: Duration,
+    action: WatchdogAction,
+}
+
+impl ExecutionWatchdog {
+    /// Creates a new watchdog.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The maximum amount of time that a handler invocation is expected to take.
+    /// * `action` - What to do with invocations that exceed the threshold.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use up_rust::communication::{ExecutionWatchdog, WatchdogAction};
+    ///
+    /// let watchdog = ExecutionWatchdog::new(Duration::from_millis(500), WatchdogAction::Abort);
+    /// assert_eq!(watchdog.threshold(), Duration::from_millis(500));
+    /// assert_eq!(watchdog.action(), WatchdogAction::Abort);
+    /// ```
+    pub fn new(threshold: Duration, action: WatchdogAction) -> Self {
+        ExecutionWatchdog { threshold, action }
+    }
+
+    /// Gets the maximum amount of time that a handler invocation is expected to take.
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Gets the action to take for invocations that exceed the threshold.
+    pub fn action(&self) -> WatchdogAction {
+        self.action
+    }
+}
+
+/// Determines what the [orphan reaper](`InMemoryRpcServer::run_orphan_reaper`) does with
+/// requests that are still being processed after their TTL and grace period have elapsed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OrphanAction {
+    /// Log a warning and let processing of the request continue.
+    #[default]
+    Log,
+    /// Log a warning and abandon processing of the request.
+    ///
+    /// Any response that has not been sent yet is dropped.
+    Abandon,
+}
+
+/// Settings for detecting requests whose processing has neither completed nor been cancelled
+/// in time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrphanDetection {
+    grace_period: Duration,
+    action: OrphanAction,
+}
+
+impl OrphanDetection {
+    /// Creates new settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `grace_period` - The amount of time that processing of a request may take in addition
+    ///                    to the request's TTL before the request is considered orphaned.
+    /// * `action` - What to do with orphaned requests.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use up_rust::communication::{OrphanAction, OrphanDetection};
+    ///
+    /// let orphan_detection = OrphanDetection::new(Duration::from_secs(5), OrphanAction::Abandon);
+    /// assert_eq!(orphan_detection.grace_period(), Duration::from_secs(5));
+    /// assert_eq!(orphan_detection.action(), OrphanAction::Abandon);
+    /// ```
+    pub fn new(grace_period: Duration, action: OrphanAction) -> Self {
+        OrphanDetection {
+            grace_period,
+            action,
+        }
+    }
+
+    /// Gets the amount of time that processing of a request may exceed the request's TTL.
+    pub fn grace_period(&self) -> Duration {
+        self.grace_period
+    }
+
+    /// Gets the action to take for orphaned requests.
+    pub fn action(&self) -> OrphanAction {
+        self.action
+    }
+}
+
+/// Information about an endpoint whose error rate has exceeded the threshold of an [`ErrorRateAlarm`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorRateAlert {
+    /// The resource ID of the method that the endpoint handles requests for.
+    pub resource_id: u16,
+    /// The number of requests that the endpoint has processed within the alarm's window.
+    pub requests: usize,
+    /// The number of these requests that have failed.
+    pub errors: usize,
+}
+
+impl ErrorRateAlert {
+    /// Gets the ratio of failed requests to all requests within the alarm's window.
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.requests as f64
+        }
+    }
+}
+
+/// Raises an alert if the ratio of failed requests processed by an endpoint exceeds a threshold
+/// within a sliding window of time.
+///
+/// The alarm's callback is invoked once when an endpoint's error rate exceeds the threshold.
+/// It is invoked again only after the error rate has dropped to or below the threshold in the meantime.
+#[derive(Clone)]
+pub struct ErrorRateAlarm {
+    threshold: f64,
+    window: Duration,
+    min_requests: usize,
+    callback: Arc<dyn Fn(&ErrorRateAlert) + Send + Sync>,
+}
+
+impl ErrorRateAlarm {
+    /// Creates a new alarm.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The ratio of failed requests to all requests, in range `[0.0, 1.0]`, that needs to be
+    ///                 exceeded in order to raise an alert.
+    /// * `window` - The period of time to determine the error rate for.
+    /// * `callback` - The function to invoke when an alert is raised. The function is invoked on the task
+    ///                processing the request and therefore must not block.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use up_rust::communication::ErrorRateAlarm;
+    ///
+    /// let alarm = ErrorRateAlarm::new(0.2, Duration::from_secs(60), |alert| {
+    ///     eprintln!("endpoint {:#06X} fails {:.0}% of requests", alert.resource_id, alert.error_rate() * 100.0);
+    /// })
+    /// .with_min_requests(20);
+    /// assert_eq!(alarm.min_requests(), 20);
+    /// ```
+    pub fn new<F>(threshold: f64, window: Duration, callback: F) -> Self
+    where
+        F: Fn(&ErrorRateAlert) + Send + Sync + 'static,
+    {
+        ErrorRateAlarm {
+            threshold,
+            window,
+            min_requests: 1,
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Sets the minimum number of requests that need to have been processed within the window
+    /// before an alert can be raised.
+    ///
+    /// The default is 1.
+    pub fn with_min_requests(mut self, min_requests: usize) -> Self {
+        self.min_requests = min_requests;
+        self
+    }
+
+    /// Gets the ratio of failed requests that needs to be exceeded in order to raise an alert.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Gets the period of time to determine the error rate for.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Gets the minimum number of requests required for raising an alert.
+    pub fn min_requests(&self) -> usize {
+        self.min_requests
+    }
+}
+
+// the upper bounds of the buckets of the latency histograms (in ms), the last bucket is unbounded
+const LATENCY_BUCKET_BOUNDS: [u64; 8] = [5, 10, 25, 50, 100, 250, 500, 1_000];
+
+#[derive(Default)]
+struct EndpointMetrics {
+    requests: u64,
+    // error code name -> number of requests that have failed with the code
+    errors: BTreeMap<String, u64>,
+    latency_counts: [u64; LATENCY_BUCKET_BOUNDS.len() + 1],
+    // (time of completion, failed) of requests within the error rate alarm's window
+    recent_outcomes: VecDeque<(Instant, bool)>,
+    alarm_raised: bool,
+}
+
+impl EndpointMetrics {
+    fn record(
+        &mut self,
+        resource_id: u16,
+        latency: Duration,
+        error_code: Option<UCode>,
+        alarm: Option<&ErrorRateAlarm>,
+    ) -> Option<ErrorRateAlert> {
+        self.requests += 1;
+        if let Some(code) = error_code {
+            *self.errors.entry(format!("{:?}", code)).or_default() += 1;
+        }
+        let latency_millis = latency.as_millis();
+        let bucket = LATENCY_BUCKET_BOUNDS
+            .iter()
+            .position(|bound| latency_millis <= *bound as u128)
+            .unwrap_or(LATENCY_BUCKET_BOUNDS.len());
+        self.latency_counts[bucket] += 1;
+
+        let alarm = alarm?;
+        let now = Instant::now();
+        self.recent_outcomes.push_back((now, error_code.is_some()));
+        while self
+            .recent_outcomes
+            .front()
+            .is_some_and(|(completed_at, _failed)| now.duration_since(*completed_at) > alarm.window)
+        {
+            self.recent_outcomes.pop_front();
+        }
+        let alert = ErrorRateAlert {
+            resource_id,
+            requests: self.recent_outcomes.len(),
+            errors: self
+                .recent_outcomes
+                .iter()
+                .filter(|(_completed_at, failed)| *failed)
+                .count(),
+        };
+        if alert.error_rate() <= alarm.threshold {
+            self.alarm_raised = false;
+            None
+        } else if alert.requests >= alarm.min_requests && !self.alarm_raised {
+            self.alarm_raised = true;
+            Some(alert)
+        } else {
+            None
+        }
+    }
+
+    fn latency_histogram(&self) -> Vec<LatencyBucket> {
+        LATENCY_BUCKET_BOUNDS
+            .iter()
+            .copied()
+            .chain(std::iter::once(u64::MAX))
+            .zip(self.latency_counts.iter())
+            .map(|(le_millis, count)| LatencyBucket {
+                le_millis,
+                count: *count,
+            })
+            .collect()
+    }
+}
+
+struct InFlightRequest {
+    resource_id: u16,
+    received_at: Instant,
+    ttl: Duration,
+    orphaned: bool,
+    abandon: Arc<tokio::sync::Notify>,
+}
+
+// the requests that are currently being processed by any of a server's endpoints
+#[derive(Default)]
+struct InFlightRequests {
+    requests: Mutex<HashMap<UUID, InFlightRequest>>,
+    orphaned_requests: AtomicU64,
+}
+
+impl InFlightRequests {
+    fn start(&self, request_id: UUID, resource_id: u16, ttl: Duration) -> Arc<tokio::sync::Notify> {
+        let abandon = Arc::new(tokio::sync::Notify::new());
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.insert(
+                request_id,
+                InFlightRequest {
+                    resource_id,
+                    received_at: Instant::now(),
+                    ttl,
+                    orphaned: false,
+                    abandon: abandon.clone(),
+                },
+            );
+        }
+        abandon
+    }
+
+    fn finish(&self, request_id: &UUID) {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.remove(request_id);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.requests.lock().map_or(0, |requests| requests.len())
+    }
+
+    fn reap(&self, orphan_detection: &OrphanDetection) -> usize {
+        let Ok(mut requests) = self.requests.lock() else {
+            return 0;
+        };
+        let now = Instant::now();
+        let mut reaped = 0;
+        for (request_id, request) in requests.iter_mut().filter(|(_id, request)| {
+            !request.orphaned
+                && now.duration_since(request.received_at)
+                    > request.ttl + orphan_detection.grace_period
+        }) {
+            warn!(
+                id = %request_id,
+                resource_id = request.resource_id,
+                ttl = request.ttl.as_millis(),
+                age = now.duration_since(request.received_at).as_millis(),
+                action = ?orphan_detection.action,
+                "processing of request has not completed in time"
+            );
+            request.orphaned = true;
+            if orphan_detection.action == OrphanAction::Abandon {
+                request.abandon.notify_one();
+            }
+            reaped += 1;
+        }
+        self.orphaned_requests
+            .fetch_add(reaped as u64, Ordering::Relaxed);
+        reaped
+    }
+}
+
+// removes a request from the in-flight requests when its processing has completed
+// or has been cancelled by dropping the processing future
+struct InFlightGuard<'a> {
+    in_flight_requests: &'a InFlightRequests,
+    request_id: UUID,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.in_flight_requests.finish(&self.request_id);
+    }
+}
 
 struct RequestListener {
     request_handler: Arc<dyn RequestHandler>,
     transport: Arc<dyn UTransport>,
+    watchdog: Option<ExecutionWatchdog>,
+    watchdog_alerts: AtomicU64,
+    metrics: Mutex<EndpointMetrics>,
+    error_rate_alarm: Option<ErrorRateAlarm>,
+    idempotency_store: Option<Arc<dyn IdempotencyStore>>,
+    in_flight_requests: Option<Arc<InFlightRequests>>,
+    #[cfg(feature = "compression")]
+    response_compression_threshold: Option<usize>,
 }
 
 impl RequestListener {
+    fn new(
+        request_handler: Arc<dyn RequestHandler>,
+        transport: Arc<dyn UTransport>,
+        watchdog: Option<ExecutionWatchdog>,
+    ) -> Self {
+        RequestListener {
+            request_handler,
+            transport,
+            watchdog,
+            watchdog_alerts: AtomicU64::new(0),
+            metrics: Mutex::new(EndpointMetrics::default()),
+            error_rate_alarm: None,
+            idempotency_store: None,
+            in_flight_requests: None,
+            #[cfg(feature = "compression")]
+            response_compression_threshold: None,
+        }
+    }
+
+    fn with_in_flight_requests(mut self, in_flight_requests: Arc<InFlightRequests>) -> Self {
+        self.in_flight_requests = Some(in_flight_requests);
+        self
+    }
+
+    fn with_error_rate_alarm(mut self, error_rate_alarm: Option<ErrorRateAlarm>) -> Self {
+        self.error_rate_alarm = error_rate_alarm;
+        self
+    }
+
+    fn record_outcome(&self, resource_id: u16, latency: Duration, error_code: Option<UCode>) {
+        let Some(alert) = self.metrics.lock().ok().and_then(|mut metrics| {
+            metrics.record(
+                resource_id,
+                latency,
+                error_code,
+                self.error_rate_alarm.as_ref(),
+            )
+        }) else {
+            return;
+        };
+        warn!(
+            resource_id,
+            requests = alert.requests,
+            errors = alert.errors,
+            "endpoint's error rate exceeds threshold"
+        );
+        if let Some(alarm) = self.error_rate_alarm.as_ref() {
+            (alarm.callback)(&alert);
+        }
+    }
+
+    fn with_idempotency_store(
+        mut self,
+        idempotency_store: Option<Arc<dyn IdempotencyStore>>,
+    ) -> Self {
+        self.idempotency_store = idempotency_store;
+        self
+    }
+
+    #[cfg(feature = "compression")]
+    fn with_response_compression_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.response_compression_threshold = threshold;
+        self
+    }
+
+    #[cfg(feature = "compression")]
+    fn compress_response(
+        &self,
+        request_attributes: &UAttributes,
+        mut response: UMessage,
+    ) -> UMessage {
+        let Some(threshold) = self
+            .response_compression_threshold
+            .filter(|_threshold| request_attributes.accepts_encoding(super::DEFLATE_ENCODING))
+        else {
+            return response;
+        };
+        if response.attributes.get_or_default().commstatus.is_some() {
+            // error responses are consumed by clients that may not support compression
+            return response;
+        }
+        match super::compression::deflate_payload(&mut response, threshold) {
+            Ok(true) => debug!("compressed response payload"),
+            Ok(false) => {}
+            Err(e) => info!("failed to compress response payload: {}", e),
+        }
+        response
+    }
+
     async fn process_valid_request(&self, resource_id: u16, request_message: UMessage) {
+        let received_at = Instant::now();
         let transport_clone = self.transport.clone();
         let request_handler_clone = self.request_handler.clone();
 
@@ -48,6 +532,18 @@ impl RequestListener {
             .get_or_default()
             .ttl
             .unwrap_or(10_000);
+        let idempotency_key = self.idempotency_store.as_ref().and_then(|_store| {
+            let attributes = request_message.attributes.get_or_default();
+            attributes.idempotency_key().map(|key| IdempotencyKey {
+                caller: attributes.source.get_or_default().to_owned(),
+                resource_id,
+                key,
+            })
+        });
+        let stored_outcome = match (self.idempotency_store.as_ref(), idempotency_key.as_ref()) {
+            (Some(store), Some(key)) => store.get(key).await,
+            _ => None,
+        };
         let payload = request_message.payload;
         let payload_format = request_message
             .attributes
@@ -58,21 +554,77 @@ impl RequestListener {
 
         debug!(ttl = request_timeout, id = %request_id, "processing RPC request");
 
-        let invocation_result_future = request_handler_clone.handle_request(
+        let mut invocation = None;
+        let mut handler_timed_out = false;
+        let outcome = if let Some(outcome) = stored_outcome {
+            debug!(id = %request_id, "responding with outcome of previous invocation");
+            outcome
+        } else {
+            let mut handler_completed = false;
+            let invocation = invocation.insert(request_handler_clone.handle_request(
+                resource_id,
+                &request_message.attributes,
+                request_payload,
+            ));
+            let mut remaining_time = Duration::from_millis(request_timeout as u64);
+            let mut watchdog_outcome = None;
+            if let Some(watchdog) = self
+                .watchdog
+                .as_ref()
+                .filter(|watchdog| watchdog.threshold < remaining_time)
+            {
+                match tokio::time::timeout(watchdog.threshold, invocation.as_mut()).await {
+                    Ok(result) => {
+                        handler_completed = true;
+                        watchdog_outcome = Some(result);
+                    }
+                    Err(_e) => {
+                        self.watchdog_alerts.fetch_add(1, Ordering::Relaxed);
+                        warn!(
+                            id = %request_id,
+                            threshold = watchdog.threshold.as_millis(),
+                            action = ?watchdog.action,
+                            "request handler exceeds execution time threshold"
+                        );
+                        if watchdog.action == WatchdogAction::Abort {
+                            watchdog_outcome = Some(Err(ServiceInvocationError::DeadlineExceeded));
+                        }
+                    }
+                }
+                remaining_time -= watchdog.threshold;
+            }
+            let outcome = match watchdog_outcome {
+                Some(result) => result,
+                None => tokio::time::timeout(remaining_time, invocation.as_mut())
+                    .await
+                    .map(|result| {
+                        handler_completed = true;
+                        result
+                    })
+                    .map_err(|_e| {
+                        info!(ttl = request_timeout, "request handler timed out");
+                        handler_timed_out = true;
+                        ServiceInvocationError::DeadlineExceeded
+                    })
+                    .and_then(|v| v),
+            };
+            if let (true, Some(store), Some(key)) = (
+                handler_completed,
+                self.idempotency_store.as_ref(),
+                idempotency_key,
+            ) {
+                store.put(key, outcome.clone()).await;
+            }
+            outcome
+        };
+        self.record_outcome(
             resource_id,
-            &request_message.attributes,
-            request_payload,
+            received_at.elapsed(),
+            outcome
+                .as_ref()
+                .err()
+                .map(|e| UStatus::from(e.to_owned()).get_code()),
         );
-        let outcome = tokio::time::timeout(
-            Duration::from_millis(request_timeout as u64),
-            invocation_result_future,
-        )
-        .await
-        .map_err(|_e| {
-            info!(ttl = request_timeout, "request handler timed out");
-            ServiceInvocationError::DeadlineExceeded
-        })
-        .and_then(|v| v);
 
         let response = match outcome {
             Ok(response_payload) => {
@@ -88,6 +640,10 @@ impl RequestListener {
                     .build_with_protobuf_payload(&error)
             }
         };
+        #[cfg(feature = "compression")]
+        let response = response.map(|message| {
+            self.compress_response(request_message.attributes.get_or_default(), message)
+        });
 
         match response {
             Ok(response_message) => {
@@ -99,6 +655,19 @@ impl RequestListener {
                 info!("failed to create response message: {}", e);
             }
         }
+
+        if handler_timed_out
+            && self
+                .watchdog
+                .as_ref()
+                .is_some_and(|watchdog| watchdog.action == WatchdogAction::Log)
+        {
+            // let the handler complete processing of the request
+            if let Some(invocation) = invocation {
+                let _ = invocation.await;
+            }
+            debug!(id = %request_id, "request handler has completed after request has expired");
+        }
     }
 
     async fn process_invalid_request(
@@ -157,11 +726,66 @@ impl UListener for RequestListener {
             .and_then(|uri| u16::try_from(uri.resource_id).ok())
         {
             // the conversion cannot fail because request message validation has succeeded
-            self.process_valid_request(resource_id, msg).await;
+            let Some(in_flight_requests) = self.in_flight_requests.as_ref() else {
+                self.process_valid_request(resource_id, msg).await;
+                return;
+            };
+            let request_id = attributes.id.get_or_default().to_owned();
+            let ttl = Duration::from_millis(attributes.ttl.unwrap_or(10_000) as u64);
+            let abandon = in_flight_requests.start(request_id.clone(), resource_id, ttl);
+            let _guard = InFlightGuard {
+                in_flight_requests,
+                request_id: request_id.clone(),
+            };
+            if run_until_notified(self.process_valid_request(resource_id, msg), &abandon)
+                .await
+                .is_none()
+            {
+                warn!(id = %request_id, "abandoned processing of orphaned request");
+            }
         }
     }
 }
 
+// runs a future to completion unless the given notification is received before
+async fn run_until_notified<F: Future>(
+    future: F,
+    notify: &tokio::sync::Notify,
+) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut notified = pin!(notify.notified());
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        notified.as_mut().poll(cx).map(|_| None)
+    })
+    .await
+}
+
+/// The settings of an endpoint that is registered by means of [`InMemoryRpcServer::apply_config`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct EndpointConfig {
+    /// The resource identifier of the (local) method to accept requests for.
+    pub resource_id: u16,
+    /// The URI pattern defining origin addresses to accept requests from, any origin if not set.
+    pub origin_filter: Option<String>,
+    /// The name of the request handler to invoke for incoming requests.
+    pub handler: String,
+}
+
+struct RegisteredEndpoint {
+    source_filter: UUri,
+    listener: Arc<RequestListener>,
+    // set for endpoints that have been registered by means of a configuration
+    config: Option<EndpointConfig>,
+}
+
 /// An [`RpcServer`] which keeps all information about registered endpoints in memory.
 ///
 /// The server requires an implementations of [`UTransport`] for receiving RPC Request messages
@@ -174,7 +798,13 @@ impl UListener for RequestListener {
 pub struct InMemoryRpcServer {
     transport: Arc<dyn UTransport>,
     uri_provider: Arc<dyn LocalUriProvider>,
-    request_listeners: tokio::sync::Mutex<HashMap<u16, Arc<dyn UListener>>>,
+    request_listeners: tokio::sync::Mutex<HashMap<u16, RegisteredEndpoint>>,
+    idempotency_store: Option<Arc<dyn IdempotencyStore>>,
+    error_rate_alarm: Option<ErrorRateAlarm>,
+    in_flight_requests: Arc<InFlightRequests>,
+    orphan_detection: Option<OrphanDetection>,
+    #[cfg(feature = "compression")]
+    response_compression_threshold: Option<usize>,
 }
 
 impl InMemoryRpcServer {
@@ -184,7 +814,118 @@ impl InMemoryRpcServer {
             transport,
             uri_provider,
             request_listeners: tokio::sync::Mutex::new(HashMap::new()),
+            idempotency_store: None,
+            error_rate_alarm: None,
+            in_flight_requests: Arc::new(InFlightRequests::default()),
+            orphan_detection: None,
+            #[cfg(feature = "compression")]
+            response_compression_threshold: None,
+        }
+    }
+
+    /// Enables detection of orphaned requests.
+    ///
+    /// A request is considered orphaned if its processing has neither completed nor been cancelled
+    /// within the request's TTL and the given grace period. Orphaned requests are detected by
+    /// [`Self::run_orphan_reaper`] and are counted in the server's [diagnostics](`Self::diagnostics`).
+    pub fn with_orphan_detection(mut self, orphan_detection: OrphanDetection) -> Self {
+        self.orphan_detection = Some(orphan_detection);
+        self
+    }
+
+    /// Checks the requests that are currently being processed for orphaned requests.
+    ///
+    /// # Returns
+    ///
+    /// The number of requests that have been found to be orphaned since the last check.
+    /// Always returns 0 if orphan detection has not been [enabled](`Self::with_orphan_detection`).
+    pub fn reap_orphans(&self) -> usize {
+        self.orphan_detection
+            .as_ref()
+            .map_or(0, |orphan_detection| {
+                self.in_flight_requests.reap(orphan_detection)
+            })
+    }
+
+    /// Periodically checks for orphaned requests.
+    ///
+    /// The returned future never completes, unless orphan detection has not been
+    /// [enabled](`Self::with_orphan_detection`), in which case it completes immediately.
+    /// It is supposed to be spawned on the runtime that the server is being used on.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time to wait between two checks.
+    pub async fn run_orphan_reaper(&self, interval: Duration) {
+        if self.orphan_detection.is_none() {
+            debug!("orphan detection is not enabled");
+            return;
         }
+        loop {
+            tokio::time::sleep(interval).await;
+            self.reap_orphans();
+        }
+    }
+
+    /// Sets the store to use for the outcomes of requests that carry an
+    /// [idempotency key](`crate::UAttributes::idempotency_key`).
+    ///
+    /// If a request contains an idempotency key for which the store already holds an outcome,
+    /// the server responds with the stored outcome without invoking the request handler again.
+    /// Outcomes are only stored if the request handler has completed processing of the request.
+    ///
+    /// The store is used for all endpoints that are registered after this function has been invoked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use up_rust::{LocalUriProvider, UTransport};
+    /// use up_rust::communication::{InMemoryIdempotencyStore, InMemoryRpcServer};
+    ///
+    /// fn create_server(
+    ///     transport: Arc<dyn UTransport>,
+    ///     uri_provider: Arc<dyn LocalUriProvider>,
+    /// ) -> InMemoryRpcServer {
+    ///     InMemoryRpcServer::new(transport, uri_provider)
+    ///         .with_idempotency_store(Arc::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))))
+    /// }
+    /// ```
+    pub fn with_idempotency_store(mut self, idempotency_store: Arc<dyn IdempotencyStore>) -> Self {
+        self.idempotency_store = Some(idempotency_store);
+        self
+    }
+
+    /// Sets an alarm to raise if the error rate of any of the server's endpoints exceeds a threshold.
+    ///
+    /// Each endpoint's error rate is determined separately. The number of requests, the number
+    /// of errors by error code and the distribution of processing times of each endpoint are
+    /// available from the server's [diagnostics](`Self::diagnostics`), regardless of whether
+    /// an alarm has been set.
+    ///
+    /// The alarm is used for all endpoints that are registered after this function has been invoked.
+    pub fn with_error_rate_alarm(mut self, error_rate_alarm: ErrorRateAlarm) -> Self {
+        self.error_rate_alarm = Some(error_rate_alarm);
+        self
+    }
+
+    /// Enables compression of response payloads.
+    ///
+    /// The payload of a successful response is compressed using the DEFLATE algorithm, if the
+    /// request indicates that the client [accepts](`crate::UAttributes::accepted_encodings`)
+    /// the `deflate` encoding and if the payload is larger than the given threshold. The response's
+    /// [content encoding](`crate::UAttributes::content_encoding`) is set accordingly.
+    ///
+    /// The setting is used for all endpoints that are registered after this function has been invoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The number of bytes that a payload needs to exceed in order to be compressed.
+    #[cfg(feature = "compression")]
+    pub fn with_response_compression(mut self, threshold: usize) -> Self {
+        self.response_compression_threshold = Some(threshold);
+        self
     }
 
     fn validate_sink_filter(filter: &UUri) -> Result<(), RegistrationError> {
@@ -207,20 +948,13 @@ impl InMemoryRpcServer {
         Ok(())
     }
 
-    #[cfg(test)]
-    async fn contains_endpoint(&self, resource_id: u16) -> bool {
-        let listener_map = self.request_listeners.lock().await;
-        listener_map.contains_key(&resource_id)
-    }
-}
-
-#[async_trait]
-impl RpcServer for InMemoryRpcServer {
-    async fn register_endpoint(
+    async fn do_register_endpoint(
         &self,
         origin_filter: Option<&UUri>,
         resource_id: u16,
         request_handler: Arc<dyn RequestHandler>,
+        watchdog: Option<ExecutionWatchdog>,
+        config: Option<EndpointConfig>,
     ) -> Result<(), RegistrationError> {
         Self::validate_origin_filter(origin_filter)?;
         let sink_filter = self.uri_provider.get_resource_uri(resource_id);
@@ -228,21 +962,27 @@ impl RpcServer for InMemoryRpcServer {
 
         let mut listener_map = self.request_listeners.lock().await;
         if let Entry::Vacant(e) = listener_map.entry(resource_id) {
-            let listener = Arc::new(RequestListener {
-                request_handler,
-                transport: self.transport.clone(),
-            });
+            let listener = RequestListener::new(request_handler, self.transport.clone(), watchdog)
+                .with_idempotency_store(self.idempotency_store.clone())
+                .with_error_rate_alarm(self.error_rate_alarm.clone())
+                .with_in_flight_requests(self.in_flight_requests.clone());
+            #[cfg(feature = "compression")]
+            let listener =
+                listener.with_response_compression_threshold(self.response_compression_threshold);
+            let listener = Arc::new(listener);
+            let source_filter = origin_filter.map_or_else(
+                || UUri::any_with_resource_id(crate::uri::RESOURCE_ID_RESPONSE),
+                UUri::to_owned,
+            );
             self.transport
-                .register_listener(
-                    origin_filter.unwrap_or(&UUri::any_with_resource_id(
-                        crate::uri::RESOURCE_ID_RESPONSE,
-                    )),
-                    Some(&sink_filter),
-                    listener.clone(),
-                )
+                .register_listener(&source_filter, Some(&sink_filter), listener.clone())
                 .await
                 .map(|_| {
-                    e.insert(listener);
+                    e.insert(RegisteredEndpoint {
+                        source_filter,
+                        listener,
+                        config,
+                    });
                 })
                 .map_err(RegistrationError::from)
         } else {
@@ -250,6 +990,263 @@ impl RpcServer for InMemoryRpcServer {
         }
     }
 
+    /// Registers an endpoint for RPC requests whose handler invocations are monitored by a watchdog.
+    ///
+    /// Apart from the watchdog, this function behaves like [`RpcServer::register_endpoint`].
+    ///
+    /// # Arguments
+    ///
+    /// * `origin_filter` - A pattern defining origin addresses to accept requests from.
+    /// * `resource_id` - The resource identifier of the (local) method to accept requests for.
+    /// * `request_handler` - The handler to invoke for each incoming request.
+    /// * `watchdog` - The watchdog to apply to the handler's invocations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the endpoint could not be registered.
+    pub async fn register_endpoint_with_watchdog(
+        &self,
+        origin_filter: Option<&UUri>,
+        resource_id: u16,
+        request_handler: Arc<dyn RequestHandler>,
+        watchdog: ExecutionWatchdog,
+    ) -> Result<(), RegistrationError> {
+        self.do_register_endpoint(
+            origin_filter,
+            resource_id,
+            request_handler,
+            Some(watchdog),
+            None,
+        )
+        .await
+    }
+
+    /// Unregisters all endpoints that have been registered with this server.
+    ///
+    /// The server tries to unregister the listeners of all endpoints from the underlying transport,
+    /// regardless of whether unregistering any of the other endpoints' listeners fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the resource IDs of all endpoints that could not be unregistered along with the
+    /// corresponding error. These endpoints remain registered with the server.
+    pub async fn unregister_all(&self) -> Result<(), Vec<(u16, RegistrationError)>> {
+        let mut listener_map = self.request_listeners.lock().await;
+        let endpoints: Vec<(u16, RegisteredEndpoint)> = listener_map.drain().collect();
+        let mut errors = vec![];
+        for (resource_id, endpoint) in endpoints {
+            let sink_filter = self.uri_provider.get_resource_uri(resource_id);
+            if let Err(e) = self
+                .transport
+                .unregister_listener(
+                    &endpoint.source_filter,
+                    Some(&sink_filter),
+                    endpoint.listener.clone(),
+                )
+                .await
+            {
+                debug!(resource_id, "failed to unregister endpoint: {}", e);
+                errors.push((resource_id, RegistrationError::from(e)));
+                listener_map.insert(resource_id, endpoint);
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Registers and unregisters endpoints according to a configuration.
+    ///
+    /// Endpoints that are contained in the configuration but have not been registered yet are registered
+    /// using the request handlers of the given names. Endpoints that have been registered by means of a
+    /// previous configuration but are no longer contained in the configuration are unregistered.
+    /// Endpoints that have been registered using [`RpcServer::register_endpoint`] are not affected.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The endpoints that should be registered.
+    /// * `handlers` - The request handlers that the endpoints refer to, by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns the resource IDs of all endpoints that could not be (un)registered along with the
+    /// corresponding error. All other endpoints are (un)registered regardless.
+    pub async fn apply_config(
+        &self,
+        config: &[EndpointConfig],
+        handlers: &HashMap<String, Arc<dyn RequestHandler>>,
+    ) -> Result<(), Vec<(u16, RegistrationError)>> {
+        let current_config = self.export_config().await;
+        let mut errors = vec![];
+        for endpoint_config in current_config
+            .iter()
+            .filter(|endpoint_config| !config.contains(endpoint_config))
+        {
+            let resource_id = endpoint_config.resource_id;
+            if let Err(e) = self.unregister_configured_endpoint(resource_id).await {
+                debug!(resource_id, "failed to unregister endpoint: {}", e);
+                errors.push((resource_id, e));
+            }
+        }
+        for endpoint_config in config
+            .iter()
+            .filter(|endpoint_config| !current_config.contains(endpoint_config))
+        {
+            let resource_id = endpoint_config.resource_id;
+            if let Err(e) = self
+                .register_configured_endpoint(endpoint_config, handlers)
+                .await
+            {
+                debug!(resource_id, "failed to register endpoint: {}", e);
+                errors.push((resource_id, e));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Gets the configuration of the endpoints that have been registered by means of [`Self::apply_config`],
+    /// ordered by resource ID.
+    pub async fn export_config(&self) -> Vec<EndpointConfig> {
+        let listener_map = self.request_listeners.lock().await;
+        let mut config: Vec<EndpointConfig> = listener_map
+            .values()
+            .filter_map(|endpoint| endpoint.config.clone())
+            .collect();
+        config.sort_by_key(|endpoint_config| endpoint_config.resource_id);
+        config
+    }
+
+    async fn register_configured_endpoint(
+        &self,
+        config: &EndpointConfig,
+        handlers: &HashMap<String, Arc<dyn RequestHandler>>,
+    ) -> Result<(), RegistrationError> {
+        let Some(request_handler) = handlers.get(&config.handler) else {
+            return Err(RegistrationError::Unknown(UStatus::fail_with_code(
+                UCode::NOT_FOUND,
+                format!("no request handler named [{}]", config.handler),
+            )));
+        };
+        let origin_filter = config
+            .origin_filter
+            .as_deref()
+            .map(UUri::try_from)
+            .transpose()
+            .map_err(|e| RegistrationError::InvalidFilter(e.to_string()))?;
+        let resource_id = config.resource_id;
+        if self
+            .request_listeners
+            .lock()
+            .await
+            .contains_key(&resource_id)
+        {
+            // replace the previous configuration of the endpoint
+            self.unregister_configured_endpoint(resource_id).await?;
+        }
+        self.do_register_endpoint(
+            origin_filter.as_ref(),
+            resource_id,
+            request_handler.clone(),
+            None,
+            Some(config.clone()),
+        )
+        .await
+    }
+
+    async fn unregister_configured_endpoint(
+        &self,
+        resource_id: u16,
+    ) -> Result<(), RegistrationError> {
+        let mut listener_map = self.request_listeners.lock().await;
+        let Entry::Occupied(entry) = listener_map.entry(resource_id) else {
+            return Err(RegistrationError::NoSuchListener);
+        };
+        if entry.get().config.is_none() {
+            // do not touch endpoints that have been registered programmatically
+            return Err(RegistrationError::AlreadyExists);
+        }
+        let sink_filter = self.uri_provider.get_resource_uri(resource_id);
+        self.transport
+            .unregister_listener(
+                &entry.get().source_filter,
+                Some(&sink_filter),
+                entry.get().listener.clone(),
+            )
+            .await
+            .map(|_| {
+                entry.remove();
+            })
+            .map_err(RegistrationError::from)
+    }
+
+    /// Gets a snapshot of this server's state.
+    pub async fn diagnostics(&self) -> RpcServerDiagnostics {
+        let listener_map = self.request_listeners.lock().await;
+        let mut endpoints: Vec<EndpointDiagnostics> = listener_map
+            .iter()
+            .map(|(resource_id, endpoint)| {
+                let (requests, errors, latency_histogram): (u64, Vec<_>, Vec<_>) = endpoint
+                    .listener
+                    .metrics
+                    .lock()
+                    .map(|metrics| {
+                        (
+                            metrics.requests,
+                            metrics
+                                .errors
+                                .iter()
+                                .map(|(code, count)| (code.to_owned(), *count))
+                                .collect(),
+                            metrics.latency_histogram(),
+                        )
+                    })
+                    .unwrap_or_default();
+                EndpointDiagnostics {
+                    resource_id: *resource_id,
+                    origin_filter: endpoint.source_filter.to_uri(false),
+                    watchdog_alerts: endpoint.listener.watchdog_alerts.load(Ordering::Relaxed),
+                    requests,
+                    errors,
+                    latency_histogram,
+                }
+            })
+            .collect();
+        endpoints.sort_by_key(|endpoint| endpoint.resource_id);
+        RpcServerDiagnostics {
+            endpoints,
+            in_flight_requests: self.in_flight_requests.len(),
+            orphaned_requests: self
+                .in_flight_requests
+                .orphaned_requests
+                .load(Ordering::Relaxed),
+        }
+    }
+
+    #[cfg(test)]
+    async fn contains_endpoint(&self, resource_id: u16) -> bool {
+        let listener_map = self.request_listeners.lock().await;
+        listener_map.contains_key(&resource_id)
+    }
+}
+
+#[async_trait]
+impl RpcServer for InMemoryRpcServer {
+    async fn register_endpoint(
+        &self,
+        origin_filter: Option<&UUri>,
+        resource_id: u16,
+        request_handler: Arc<dyn RequestHandler>,
+    ) -> Result<(), RegistrationError> {
+        self.do_register_endpoint(origin_filter, resource_id, request_handler, None, None)
+            .await
+    }
+
     async fn unregister_endpoint(
         &self,
         origin_filter: Option<&UUri>,
@@ -262,7 +1259,7 @@ impl RpcServer for InMemoryRpcServer {
 
         let mut listener_map = self.request_listeners.lock().await;
         if let Entry::Occupied(entry) = listener_map.entry(resource_id) {
-            let listener = entry.get().to_owned();
+            let listener = entry.get().listener.to_owned();
             self.transport
                 .unregister_listener(
                     origin_filter.unwrap_or(&UUri::any_with_resource_id(
@@ -294,8 +1291,9 @@ mod tests {
     use tokio::sync::Notify;
 
     use crate::{
-        communication::rpc::MockRequestHandler, utransport::MockTransport, StaticUriProvider,
-        UAttributes, UMessageType, UPriority, UUri, UUID,
+        communication::{rpc::MockRequestHandler, InMemoryIdempotencyStore},
+        utransport::MockTransport,
+        StaticUriProvider, UAttributes, UMessageType, UPriority, UUri, UUID,
     };
 
     fn new_uri_provider() -> Arc<dyn LocalUriProvider> {
@@ -340,6 +1338,9 @@ mod tests {
         // THEN registration succeeds
         assert!(register_result.is_ok());
         assert!(rpc_server.contains_endpoint(resource_id).await);
+        let diagnostics = rpc_server.diagnostics().await;
+        assert_eq!(diagnostics.endpoints.len(), 1);
+        assert_eq!(diagnostics.endpoints[0].resource_id, resource_id);
 
         // and the handler can be unregistered again
         let unregister_result = rpc_server
@@ -426,6 +1427,110 @@ mod tests {
         assert!(result.is_err_and(|e| matches!(e, RegistrationError::NoSuchListener)));
     }
 
+    #[tokio::test]
+    async fn test_apply_config_registers_and_unregisters_endpoints() {
+        // GIVEN an RpcServer for a transport
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_register_listener()
+            .times(3)
+            .return_const(Ok(()));
+        transport
+            .expect_do_unregister_listener()
+            .once()
+            .withf(|_source_filter, sink_filter, _listener| {
+                sink_filter.is_some_and(|uri| uri.resource_id == 0x1000)
+            })
+            .return_const(Ok(()));
+        let rpc_server = InMemoryRpcServer::new(Arc::new(transport), new_uri_provider());
+        // with an endpoint that has been registered programmatically
+        assert!(rpc_server
+            .register_endpoint(None, 0x3000, Arc::new(MockRequestHandler::new()))
+            .await
+            .is_ok());
+        let handlers: HashMap<String, Arc<dyn RequestHandler>> = HashMap::from([(
+            "echo".to_string(),
+            Arc::new(MockRequestHandler::new()) as Arc<dyn RequestHandler>,
+        )]);
+        let endpoint_config = |resource_id| EndpointConfig {
+            resource_id,
+            handler: "echo".to_string(),
+            ..Default::default()
+        };
+
+        // WHEN applying a configuration containing two endpoints
+        let config = vec![endpoint_config(0x1000), endpoint_config(0x2000)];
+        assert!(rpc_server.apply_config(&config, &handlers).await.is_ok());
+        // THEN both endpoints are registered
+        assert_eq!(rpc_server.export_config().await, config);
+
+        // WHEN applying a configuration without the first endpoint
+        // and with an endpoint referring to an unknown handler
+        let config = vec![
+            endpoint_config(0x2000),
+            EndpointConfig {
+                resource_id: 0x4000,
+                handler: "unknown".to_string(),
+                ..Default::default()
+            },
+        ];
+        let result = rpc_server.apply_config(&config, &handlers).await;
+
+        // THEN the first endpoint is unregistered and the unknown handler is reported
+        assert!(result.is_err_and(|errors| errors.len() == 1 && errors[0].0 == 0x4000));
+        assert_eq!(
+            rpc_server.export_config().await,
+            vec![endpoint_config(0x2000)]
+        );
+        // and the programmatically registered endpoint is not affected
+        assert!(rpc_server.contains_endpoint(0x3000).await);
+    }
+
+    #[tokio::test]
+    async fn test_unregister_all_reports_endpoints_that_could_not_be_unregistered() {
+        // GIVEN an RpcServer for a transport
+        let request_handler = Arc::new(MockRequestHandler::new());
+        let mut transport = MockTransport::new();
+        let uri_provider = new_uri_provider();
+        transport
+            .expect_do_register_listener()
+            .times(2)
+            .return_const(Ok(()));
+        // which fails to unregister the listener for one of the endpoints
+        transport
+            .expect_do_unregister_listener()
+            .times(2)
+            .returning(|_source_filter, sink_filter, _listener| {
+                if sink_filter.is_some_and(|uri| uri.resource_id == 0x5000) {
+                    Err(UStatus::fail_with_code(UCode::UNAVAILABLE, "not connected"))
+                } else {
+                    Ok(())
+                }
+            });
+
+        let rpc_server = InMemoryRpcServer::new(Arc::new(transport), uri_provider);
+        assert!(rpc_server
+            .register_endpoint(None, 0x5000, request_handler.clone())
+            .await
+            .is_ok());
+        assert!(rpc_server
+            .register_endpoint(None, 0x6000, request_handler)
+            .await
+            .is_ok());
+
+        // WHEN unregistering all endpoints
+        let result = rpc_server.unregister_all().await;
+
+        // THEN the endpoint that could not be unregistered is reported
+        assert!(result.is_err_and(|errors| errors.len() == 1
+            && errors[0].0 == 0x5000
+            && matches!(errors[0].1, RegistrationError::Unknown(_))));
+        // and remains registered
+        assert!(rpc_server.contains_endpoint(0x5000).await);
+        // while the other endpoint has been unregistered
+        assert!(!rpc_server.contains_endpoint(0x6000).await);
+    }
+
     #[tokio::test]
     async fn test_request_listener_returns_response_for_invalid_request() {
         // GIVEN an RpcServer for a transport
@@ -489,10 +1594,8 @@ mod tests {
             ..Default::default()
         };
 
-        let request_listener = RequestListener {
-            request_handler: Arc::new(request_handler),
-            transport: Arc::new(transport),
-        };
+        let request_listener =
+            RequestListener::new(Arc::new(request_handler), Arc::new(transport), None);
         request_listener.on_receive(invalid_request_message).await;
 
         // THEN the listener sends an error message in response to the invalid request
@@ -532,10 +1635,8 @@ mod tests {
             ..Default::default()
         };
 
-        let request_listener = RequestListener {
-            request_handler: Arc::new(request_handler),
-            transport: Arc::new(transport),
-        };
+        let request_listener =
+            RequestListener::new(Arc::new(request_handler), Arc::new(transport), None);
         request_listener.on_receive(invalid_request_message).await;
 
         // THEN the listener ignores the invalid request
@@ -612,10 +1713,8 @@ mod tests {
         .build_with_protobuf_payload(&request_payload)
         .unwrap();
 
-        let request_listener = RequestListener {
-            request_handler: Arc::new(request_handler),
-            transport: Arc::new(transport),
-        };
+        let request_listener =
+            RequestListener::new(Arc::new(request_handler), Arc::new(transport), None);
         request_listener.on_receive(request_message).await;
         let result = tokio::time::timeout(Duration::from_secs(2), notify.notified()).await;
         assert!(result.is_ok());
@@ -671,15 +1770,134 @@ mod tests {
         .build()
         .unwrap();
 
-        let request_listener = RequestListener {
-            request_handler: Arc::new(request_handler),
-            transport: Arc::new(transport),
-        };
+        let request_listener =
+            RequestListener::new(Arc::new(request_handler), Arc::new(transport), None);
         request_listener.on_receive(request_message).await;
         let result = tokio::time::timeout(Duration::from_secs(2), notify.notified()).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_request_listener_replays_outcome_for_repeated_idempotency_key() {
+        // GIVEN a request listener using an idempotency store
+        let mut request_handler = MockRequestHandler::new();
+        let mut transport = MockTransport::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // and a request handler which expects to be invoked once only
+        request_handler.expect_handle_request().once().returning(
+            |_resource_id, _message_attributes, _request_payload| {
+                let response_payload = UPayload::try_from_protobuf(StringValue {
+                    value: "Hello World".to_string(),
+                    ..Default::default()
+                })
+                .unwrap();
+                Ok(Some(response_payload))
+            },
+        );
+        transport
+            .expect_do_send()
+            .times(2)
+            .returning(move |response_message| {
+                tx.send(response_message).unwrap();
+                Ok(())
+            });
+        let request_listener =
+            RequestListener::new(Arc::new(request_handler), Arc::new(transport), None)
+                .with_idempotency_store(Some(Arc::new(InMemoryIdempotencyStore::new(
+                    Duration::from_secs(60),
+                ))));
+
+        // WHEN the same request is received twice with the same idempotency key
+        for _attempt in 0..2 {
+            let request_message = UMessageBuilder::request(
+                UUri::try_from("up://localhost/A200/1/7000").unwrap(),
+                UUri::try_from("up://localhost/A100/1/0").unwrap(),
+                5_000,
+            )
+            .with_idempotency_key("4711")
+            .build()
+            .unwrap();
+            request_listener.on_receive(request_message).await;
+        }
+
+        // THEN the handler is invoked once only and both responses contain the same payload
+        for _attempt in 0..2 {
+            let response_message = rx.recv().await.unwrap();
+            let msg: StringValue = response_message.extract_protobuf().unwrap();
+            assert_eq!(msg.value, "Hello World");
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    #[test_case(true, true; "for request accepting deflate")]
+    #[test_case(false, false; "for request not accepting any encoding")]
+    #[tokio::test]
+    async fn test_request_listener_compresses_response_payload(
+        accepts_deflate: bool,
+        expect_compressed: bool,
+    ) {
+        // GIVEN a request listener compressing payloads exceeding 100 bytes
+        let mut request_handler = MockRequestHandler::new();
+        let mut transport = MockTransport::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // and a request handler returning a large payload
+        let data = "Hello World ".repeat(50);
+        let response_data = data.clone();
+        request_handler.expect_handle_request().once().returning(
+            move |_resource_id, _message_attributes, _request_payload| {
+                Ok(Some(UPayload::new(
+                    response_data.clone(),
+                    crate::UPayloadFormat::UPAYLOAD_FORMAT_TEXT,
+                )))
+            },
+        );
+        transport
+            .expect_do_send()
+            .once()
+            .returning(move |response_message| {
+                tx.send(response_message).unwrap();
+                Ok(())
+            });
+        let request_listener =
+            RequestListener::new(Arc::new(request_handler), Arc::new(transport), None)
+                .with_response_compression_threshold(Some(100));
+
+        // WHEN a request is received
+        let mut request_message = UMessageBuilder::request(
+            UUri::try_from("up://localhost/A200/1/7000").unwrap(),
+            UUri::try_from("up://localhost/A100/1/0").unwrap(),
+            5_000,
+        )
+        .build()
+        .unwrap();
+        if accepts_deflate {
+            request_message
+                .attributes
+                .mut_or_insert_default()
+                .set_accepted_encodings(["gzip", "deflate"]);
+        }
+        request_listener.on_receive(request_message).await;
+
+        // THEN the response payload is compressed only if the client accepts the encoding
+        let mut response_message = rx.recv().await.unwrap();
+        assert_eq!(
+            response_message
+                .attributes
+                .get_or_default()
+                .content_encoding()
+                .is_some(),
+            expect_compressed
+        );
+        crate::communication::compression::inflate_payload(
+            &mut response_message,
+            crate::communication::DEFAULT_MAX_DECOMPRESSED_SIZE,
+        )
+        .unwrap();
+        assert_eq!(response_message.payload.unwrap(), data.as_bytes());
+    }
+
     #[tokio::test]
     async fn test_request_listener_times_out() {
         // we need to manually implement the RequestHandler
@@ -743,12 +1961,278 @@ mod tests {
         .build()
         .expect("should have been able to create RPC Request message");
 
-        let request_listener = RequestListener {
-            request_handler: Arc::new(request_handler),
-            transport: Arc::new(transport),
-        };
+        let request_listener =
+            RequestListener::new(Arc::new(request_handler), Arc::new(transport), None);
         request_listener.on_receive(request_message).await;
         let result = tokio::time::timeout(Duration::from_secs(2), notify.notified()).await;
         assert!(result.is_ok());
     }
+
+    // a handler that takes a given amount of time for processing a request
+    // and which signals completion of the processing
+    struct SlowHandler {
+        processing_time: Duration,
+        completed: Arc<Notify>,
+    }
+
+    #[async_trait]
+    impl RequestHandler for SlowHandler {
+        async fn handle_request(
+            &self,
+            _resource_id: u16,
+            _message_attributes: &UAttributes,
+            _request_payload: Option<UPayload>,
+        ) -> Result<Option<UPayload>, ServiceInvocationError> {
+            tokio::time::sleep(self.processing_time).await;
+            self.completed.notify_one();
+            Ok(None)
+        }
+    }
+
+    fn new_response_transport(expected_code: UCode, response_sent: Arc<Notify>) -> MockTransport {
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_send()
+            .once()
+            .withf(move |response_message| {
+                response_message.is_response()
+                    && response_message
+                        .attributes
+                        .get_or_default()
+                        .commstatus
+                        .map_or(UCode::OK, |v| v.enum_value_or_default())
+                        == expected_code
+            })
+            .returning(move |_msg| {
+                response_sent.notify_one();
+                Ok(())
+            });
+        transport
+    }
+
+    fn new_request_message(ttl: u32) -> UMessage {
+        UMessageBuilder::request(
+            UUri::try_from("up://localhost/A200/1/7000").unwrap(),
+            UUri::try_from("up://localhost/A100/1/0").unwrap(),
+            ttl,
+        )
+        .build()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_logs_slow_handler_invocation() {
+        // GIVEN a request listener with a watchdog that logs invocations taking longer than 20ms
+        let handler_completed = Arc::new(Notify::new());
+        let response_sent = Arc::new(Notify::new());
+        let request_listener = RequestListener::new(
+            Arc::new(SlowHandler {
+                processing_time: Duration::from_millis(100),
+                completed: handler_completed.clone(),
+            }),
+            Arc::new(new_response_transport(UCode::OK, response_sent.clone())),
+            Some(ExecutionWatchdog::new(
+                Duration::from_millis(20),
+                WatchdogAction::Log,
+            )),
+        );
+
+        // WHEN the handler takes longer than the threshold but completes before the request expires
+        request_listener
+            .on_receive(new_request_message(5_000))
+            .await;
+
+        // THEN the handler's response is sent back to the client
+        let result = tokio::time::timeout(Duration::from_secs(2), response_sent.notified()).await;
+        assert!(result.is_ok());
+        // and the invocation has been recorded
+        assert_eq!(request_listener.watchdog_alerts.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_aborts_slow_handler_invocation() {
+        // GIVEN a request listener with a watchdog that aborts invocations taking longer than 20ms
+        let handler_completed = Arc::new(Notify::new());
+        let response_sent = Arc::new(Notify::new());
+        let request_listener = RequestListener::new(
+            Arc::new(SlowHandler {
+                processing_time: Duration::from_millis(200),
+                completed: handler_completed.clone(),
+            }),
+            Arc::new(new_response_transport(
+                UCode::DEADLINE_EXCEEDED,
+                response_sent.clone(),
+            )),
+            Some(ExecutionWatchdog::new(
+                Duration::from_millis(20),
+                WatchdogAction::Abort,
+            )),
+        );
+
+        // WHEN the handler takes longer than the threshold
+        tokio::time::timeout(
+            Duration::from_millis(150),
+            request_listener.on_receive(new_request_message(5_000)),
+        )
+        .await
+        .expect("handler should have been aborted");
+
+        // THEN a DEADLINE_EXCEEDED response is sent back to the client
+        let result = tokio::time::timeout(Duration::from_secs(2), response_sent.notified()).await;
+        assert!(result.is_ok());
+        assert_eq!(request_listener.watchdog_alerts.load(Ordering::Relaxed), 1);
+        // and the handler does not complete
+        let result =
+            tokio::time::timeout(Duration::from_millis(300), handler_completed.notified()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_responds_to_expired_request_while_handler_is_running() {
+        // GIVEN a request listener with a watchdog that only logs slow invocations
+        let handler_completed = Arc::new(Notify::new());
+        let response_sent = Arc::new(Notify::new());
+        let request_listener = Arc::new(RequestListener::new(
+            Arc::new(SlowHandler {
+                processing_time: Duration::from_millis(300),
+                completed: handler_completed.clone(),
+            }),
+            Arc::new(new_response_transport(
+                UCode::DEADLINE_EXCEEDED,
+                response_sent.clone(),
+            )),
+            Some(ExecutionWatchdog::new(
+                Duration::from_millis(20),
+                WatchdogAction::Log,
+            )),
+        ));
+
+        // WHEN the request expires before the handler has completed
+        let listener = request_listener.clone();
+        tokio::spawn(async move { listener.on_receive(new_request_message(100)).await });
+
+        // THEN a DEADLINE_EXCEEDED response is sent back to the client
+        let result = tokio::time::timeout(Duration::from_secs(2), response_sent.notified()).await;
+        assert!(result.is_ok());
+        // and the handler is still run to completion
+        let result =
+            tokio::time::timeout(Duration::from_secs(2), handler_completed.notified()).await;
+        assert!(result.is_ok());
+        assert_eq!(request_listener.watchdog_alerts.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_error_rate_alarm_is_raised_once_threshold_is_exceeded() {
+        // GIVEN a request listener for a handler that always fails
+        let mut request_handler = MockRequestHandler::new();
+        request_handler.expect_handle_request().times(3).returning(
+            |_resource_id, _attributes, _payload| {
+                Err(ServiceInvocationError::Unavailable(
+                    "backend not available".to_string(),
+                ))
+            },
+        );
+        let mut transport = MockTransport::new();
+        transport.expect_do_send().times(3).returning(|_msg| Ok(()));
+        // and an alarm that is raised if more than half of at least two requests fail
+        let alerts = Arc::new(Mutex::new(vec![]));
+        let raised_alerts = alerts.clone();
+        let alarm = ErrorRateAlarm::new(0.5, Duration::from_secs(60), move |alert| {
+            raised_alerts.lock().unwrap().push(alert.to_owned());
+        })
+        .with_min_requests(2);
+        let request_listener =
+            RequestListener::new(Arc::new(request_handler), Arc::new(transport), None)
+                .with_error_rate_alarm(Some(alarm));
+
+        // WHEN processing three requests
+        for _ in 0..3 {
+            request_listener
+                .on_receive(new_request_message(5_000))
+                .await;
+        }
+
+        // THEN the alert has been raised once, after the second request
+        let alerts = alerts.lock().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].requests, 2);
+        assert_eq!(alerts[0].errors, 2);
+        // and the errors have been counted by error code
+        let metrics = request_listener.metrics.lock().unwrap();
+        assert_eq!(metrics.requests, 3);
+        assert_eq!(metrics.errors.get("UNAVAILABLE"), Some(&3));
+        assert_eq!(
+            metrics
+                .latency_histogram()
+                .iter()
+                .map(|bucket| bucket.count)
+                .sum::<u64>(),
+            3
+        );
+    }
+
+    #[test_case(OrphanAction::Log; "logging orphans")]
+    #[test_case(OrphanAction::Abandon; "abandoning orphans")]
+    #[tokio::test]
+    async fn test_reaper_detects_orphaned_request(action: OrphanAction) {
+        // GIVEN a request listener with a watchdog that lets the handler continue after the request has expired
+        let handler_completed = Arc::new(Notify::new());
+        let response_sent = Arc::new(Notify::new());
+        let in_flight_requests = Arc::new(InFlightRequests::default());
+        let request_listener = Arc::new(
+            RequestListener::new(
+                Arc::new(SlowHandler {
+                    processing_time: Duration::from_millis(500),
+                    completed: handler_completed.clone(),
+                }),
+                Arc::new(new_response_transport(
+                    UCode::DEADLINE_EXCEEDED,
+                    response_sent.clone(),
+                )),
+                Some(ExecutionWatchdog::new(
+                    Duration::from_millis(20),
+                    WatchdogAction::Log,
+                )),
+            )
+            .with_in_flight_requests(in_flight_requests.clone()),
+        );
+        let orphan_detection = OrphanDetection::new(Duration::from_millis(50), action);
+
+        // WHEN the listener receives a request
+        let listener = request_listener.clone();
+        let processing =
+            tokio::spawn(async move { listener.on_receive(new_request_message(100)).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        // THEN the request is in flight but not orphaned
+        assert_eq!(in_flight_requests.len(), 1);
+        assert_eq!(in_flight_requests.reap(&orphan_detection), 0);
+
+        // WHEN the request's TTL and the grace period have elapsed
+        let result = tokio::time::timeout(Duration::from_secs(2), response_sent.notified()).await;
+        assert!(result.is_ok());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // THEN the request is reported as orphaned exactly once
+        assert_eq!(in_flight_requests.reap(&orphan_detection), 1);
+        assert_eq!(in_flight_requests.reap(&orphan_detection), 0);
+        assert_eq!(
+            in_flight_requests.orphaned_requests.load(Ordering::Relaxed),
+            1
+        );
+
+        let result =
+            tokio::time::timeout(Duration::from_secs(1), handler_completed.notified()).await;
+        match action {
+            OrphanAction::Log => {
+                // and the handler is run to completion
+                assert!(result.is_ok());
+            }
+            OrphanAction::Abandon => {
+                // and processing of the request is abandoned
+                assert!(result.is_err());
+            }
+        }
+        assert!(processing.await.is_ok());
+        assert_eq!(in_flight_requests.len(), 0);
+    }
 }
@@ -0,0 +1,113 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+use crate::UUri;
+
+use super::{CallOptions, RpcClient, ServiceInvocationError};
+
+/// The resource ID of the well-known method that uEntities can expose for verifying that they are
+/// reachable.
+///
+/// The method returns the request's payload (if any) as is. uEntities can expose the method by
+/// means of registering an [`EchoService`](super::EchoService).
+pub const ECHO_RESOURCE_ID: u16 = crate::wellknown::ECHO_RESOURCE_ID;
+
+/// Verifies that a uEntity can be reached by means of invoking its echo method.
+///
+/// Applications can use this function for checking if a uEntity can be reached before starting
+/// a sequence of interactions with it.
+///
+/// # Arguments
+///
+/// * `rpc_client` - The client to use for invoking the echo method.
+/// * `sink` - The uEntity to check. The resource ID is ignored.
+/// * `call_options` - The options to use for invoking the echo method.
+///
+/// # Returns
+///
+/// The amount of time it took to receive the uEntity's response.
+///
+/// # Errors
+///
+/// Returns an error if the echo method could not be invoked successfully.
+// [impl->req~up-language-comm-api~1]
+pub async fn ping(
+    rpc_client: &dyn RpcClient,
+    sink: &UUri,
+    call_options: CallOptions,
+) -> Result<Duration, ServiceInvocationError> {
+    let method = UUri {
+        resource_id: ECHO_RESOURCE_ID as u32,
+        ..sink.to_owned()
+    };
+    let start = Instant::now();
+    rpc_client
+        .invoke_method(method, call_options, None)
+        .await
+        .map(|_response| start.elapsed())
+        .map_err(|e| {
+            debug!(sink = %sink, "failed to ping uEntity: {}", e);
+            e
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::communication::MockRpcClient;
+
+    #[tokio::test]
+    async fn test_ping_invokes_echo_method() {
+        let mut rpc_client = MockRpcClient::new();
+        rpc_client
+            .expect_invoke_method()
+            .once()
+            .withf(|method, _call_options, payload| {
+                method.resource_id == ECHO_RESOURCE_ID as u32
+                    && method.ue_id == 0x1000
+                    && payload.is_none()
+            })
+            .return_const(Ok(None));
+        let sink = UUri::try_from_parts("other", 0x1000, 0x01, 0x0000).unwrap();
+
+        assert!(ping(
+            &rpc_client,
+            &sink,
+            CallOptions::for_rpc_request(1_000, None, None, None)
+        )
+        .await
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ping_fails_for_unreachable_entity() {
+        let mut rpc_client = MockRpcClient::new();
+        rpc_client
+            .expect_invoke_method()
+            .once()
+            .return_const(Err(ServiceInvocationError::DeadlineExceeded));
+        let sink = UUri::try_from_parts("other", 0x1000, 0x01, 0x0000).unwrap();
+
+        assert!(ping(
+            &rpc_client,
+            &sink,
+            CallOptions::for_rpc_request(1_000, None, None, None)
+        )
+        .await
+        .is_err_and(|e| matches!(e, ServiceInvocationError::DeadlineExceeded)));
+    }
+}
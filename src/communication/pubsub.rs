@@ -11,13 +11,18 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use std::{error::Error, fmt::Display, sync::Arc};
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use async_trait::async_trait;
 
 use crate::communication::RegistrationError;
-use crate::core::usubscription::SubscriptionStatus;
-use crate::{UListener, UStatus, UUri};
+use crate::core::usubscription::{SubscribeAttributes, SubscriptionStatus};
+use crate::{ResourceId, UListener, UStatus, UUri};
 
 use super::{CallOptions, UPayload};
 
@@ -68,6 +73,71 @@ pub trait Publisher: Send + Sync {
         call_options: CallOptions,
         payload: Option<UPayload>,
     ) -> Result<(), PubSubError>;
+
+    /// Publishes a message to a topic identified by a typed resource ID.
+    ///
+    /// This default implementation [publishes](`Self::publish`) the message to the topic's
+    /// numeric resource ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The (local) resource ID of the topic to publish to.
+    /// * `call_options` - Options to include in the published message.
+    /// * `payload` - Payload to include in the published message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be published.
+    async fn publish_to(
+        &self,
+        topic: ResourceId,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<(), PubSubError> {
+        self.publish(topic.value(), call_options, payload).await
+    }
+
+    /// Publishes multiple related messages to (possibly different) topics.
+    ///
+    /// Implementations should create all messages before sending any of them, so that none of the
+    /// messages is published if any of them is invalid. This default implementation
+    /// [publishes](`Self::publish`) the messages one after the other and can therefore only
+    /// detect invalid messages once all preceding messages have already been published.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The (local) resource IDs of the topics to publish to along with the
+    ///                payload to include in the corresponding message.
+    /// * `call_options` - Options to include in all of the published messages. The options must not
+    ///                    contain a message ID if more than one message is to be published.
+    ///
+    /// # Returns
+    ///
+    /// The outcome of publishing each message, in the same order as the given messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the messages could not be created. In this case, none
+    /// of the messages has been published.
+    async fn publish_all(
+        &self,
+        messages: Vec<(u16, UPayload)>,
+        call_options: CallOptions,
+    ) -> Result<Vec<Result<(), PubSubError>>, PubSubError> {
+        if messages.len() > 1 && call_options.message_id().is_some() {
+            return Err(PubSubError::InvalidArgument(
+                "call options for multiple messages must not contain a message ID".to_string(),
+            ));
+        }
+        let mut outcomes = Vec::with_capacity(messages.len());
+        for (resource_id, payload) in messages {
+            outcomes.push(
+                self.publish(resource_id, call_options.clone(), Some(payload))
+                    .await,
+            );
+        }
+        Ok(outcomes)
+    }
 }
 
 // [impl->req~up-language-comm-api~1]
@@ -84,6 +154,41 @@ pub trait SubscriptionChangeHandler: Send + Sync {
     fn on_subscription_change(&self, topic: UUri, new_status: SubscriptionStatus);
 }
 
+/// Hints for the USubscription service regarding the delivery of events published to a subscribed topic.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubscriptionOptions {
+    /// The minimum period of time between two consecutive events to deliver to the subscriber.
+    ///
+    /// The period is sent to the USubscription service with millisecond precision.
+    pub sample_period: Option<Duration>,
+    /// The point in time at which the subscription should expire.
+    pub expire: Option<SystemTime>,
+}
+
+impl SubscriptionOptions {
+    /// Creates options for receiving at most one event per given period of time.
+    pub fn with_sample_period(sample_period: Duration) -> Self {
+        SubscriptionOptions {
+            sample_period: Some(sample_period),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the attributes to include in a subscription request to the USubscription service.
+    pub(crate) fn to_subscribe_attributes(&self) -> Option<SubscribeAttributes> {
+        if self.sample_period.is_none() && self.expire.is_none() {
+            return None;
+        }
+        Some(SubscribeAttributes {
+            expire: self.expire.map(Into::into).into(),
+            sample_period_ms: self
+                .sample_period
+                .map(|period| u32::try_from(period.as_millis()).unwrap_or(u32::MAX)),
+            ..Default::default()
+        })
+    }
+}
+
 /// A client for subscribing to topics.
 ///
 /// Please refer to the
@@ -113,6 +218,34 @@ pub trait Subscriber: Send + Sync {
         subscription_change_handler: Option<Arc<dyn SubscriptionChangeHandler>>,
     ) -> Result<(), RegistrationError>;
 
+    /// Registers a handler to invoke for messages that have been published to a given topic,
+    /// passing hints regarding the delivery of the messages to the USubscription service.
+    ///
+    /// The USubscription service may ignore the hints. This default implementation ignores the
+    /// options altogether and delegates to [`Self::subscribe`].
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to subscribe to. The topic must not contain any wildcards.
+    /// * `handler` - The handler to invoke for each message that has been published to the topic.
+    /// * `subscription_change_handler` - A handler to invoke for any subscription state changes for
+    ///                                   the given topic.
+    /// * `options` - The hints regarding the delivery of messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener cannot be registered.
+    async fn subscribe_with_options(
+        &self,
+        topic: &UUri,
+        handler: Arc<dyn UListener>,
+        subscription_change_handler: Option<Arc<dyn SubscriptionChangeHandler>>,
+        _options: SubscriptionOptions,
+    ) -> Result<(), RegistrationError> {
+        self.subscribe(topic, handler, subscription_change_handler)
+            .await
+    }
+
     /// Unregisters a previously [registered handler](`Self::subscribe`).
     ///
     /// # Arguments
@@ -129,3 +262,35 @@ pub trait Subscriber: Send + Sync {
         handler: Arc<dyn UListener>,
     ) -> Result<(), RegistrationError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_options_without_hints_have_no_attributes() {
+        assert!(SubscriptionOptions::default()
+            .to_subscribe_attributes()
+            .is_none());
+    }
+
+    #[test]
+    fn test_subscription_options_contain_sample_period() {
+        let attributes = SubscriptionOptions::with_sample_period(Duration::from_secs(1))
+            .to_subscribe_attributes()
+            .unwrap();
+        assert_eq!(attributes.sample_period_ms, Some(1_000));
+        assert!(attributes.expire.is_none());
+    }
+
+    #[test]
+    fn test_subscription_options_contain_expiry() {
+        let options = SubscriptionOptions {
+            expire: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            ..Default::default()
+        };
+        let attributes = options.to_subscribe_attributes().unwrap();
+        assert_eq!(attributes.expire.seconds, 1_700_000_000);
+        assert!(attributes.sample_period_ms.is_none());
+    }
+}
@@ -0,0 +1,405 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+#[cfg(feature = "rpc-client")]
+use std::collections::BTreeMap;
+use std::sync::Arc;
+#[cfg(feature = "rpc-client")]
+use std::time::Duration;
+
+#[cfg(feature = "rpc-client")]
+use tokio::sync::mpsc::UnboundedReceiver;
+#[cfg(feature = "rpc-client")]
+use tracing::debug;
+
+use crate::{UAttributes, UCode, UMessage, UMessageBuilder, UStatus, UTransport};
+
+#[cfg(feature = "rpc-client")]
+use super::in_memory_rpc_client::handle_response_message;
+use super::{build_message, ServiceInvocationError, UPayload};
+
+/// Sends the result of an RPC request as a sequence of RPC Response messages.
+///
+/// Each message carries a chunk of the overall result and is marked with the chunk's
+/// [index](`crate::uattributes::CHUNK_INDEX_FIELD_NUMBER`). The last message is marked as such
+/// by means of the [last chunk marker](`crate::uattributes::LAST_CHUNK_FIELD_NUMBER`).
+/// Clients use [`InMemoryRpcClient::invoke_method_streaming`](super::InMemoryRpcClient::invoke_method_streaming)
+/// for consuming the chunks.
+///
+/// A [`RequestHandler`](super::RequestHandler) can use the writer for streaming its result
+/// before returning. The (empty) response that the [`RpcServer`](super::RpcServer) sends after
+/// the handler has returned is then ignored by the client.
+pub struct ResponseStreamWriter {
+    transport: Arc<dyn UTransport>,
+    request_attributes: UAttributes,
+    next_index: u32,
+}
+
+impl ResponseStreamWriter {
+    /// Creates a writer for responding to a request.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to send the RPC Response messages with.
+    /// * `request_attributes` - The attributes of the RPC Request message to respond to.
+    pub fn new(transport: Arc<dyn UTransport>, request_attributes: &UAttributes) -> Self {
+        ResponseStreamWriter {
+            transport,
+            request_attributes: request_attributes.to_owned(),
+            next_index: 0,
+        }
+    }
+
+    fn build_chunk(&self, payload: Option<UPayload>, last: bool) -> Result<UMessage, UStatus> {
+        let mut builder = UMessageBuilder::response_for_request(&self.request_attributes);
+        let mut message = build_message(&mut builder, payload).map_err(|e| {
+            UStatus::fail_with_code(
+                UCode::INTERNAL,
+                format!("failed to create response message: {e}"),
+            )
+        })?;
+        let attributes = message.attributes.mut_or_insert_default();
+        attributes.set_chunk_index(self.next_index);
+        attributes.set_last_chunk(last);
+        Ok(message)
+    }
+
+    /// Sends a chunk of the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC Response message could not be created or sent.
+    pub async fn send_chunk(&mut self, payload: UPayload) -> Result<(), UStatus> {
+        let message = self.build_chunk(Some(payload), false)?;
+        self.transport.send(message).await?;
+        self.next_index += 1;
+        Ok(())
+    }
+
+    /// Sends the last chunk of the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The last chunk or `None` if all chunks have already been sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC Response message could not be created or sent.
+    pub async fn finish(self, payload: Option<UPayload>) -> Result<(), UStatus> {
+        let message = self.build_chunk(payload, true)?;
+        self.transport.send(message).await
+    }
+
+    /// Aborts the stream with an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC Response message could not be created or sent.
+    pub async fn fail(self, error: ServiceInvocationError) -> Result<(), UStatus> {
+        let status = UStatus::from(error);
+        let message = UMessageBuilder::response_for_request(&self.request_attributes)
+            .with_comm_status(status.get_code())
+            .build_with_protobuf_payload(&status)
+            .map_err(|e| {
+                UStatus::fail_with_code(
+                    UCode::INTERNAL,
+                    format!("failed to create response message: {e}"),
+                )
+            })?;
+        self.transport.send(message).await
+    }
+}
+
+/// A stream of chunks of the result of an RPC request.
+///
+/// Chunks are yielded in the order of their indices, regardless of the order in which the
+/// RPC Response messages have been received. The stream ends after the last chunk has been yielded
+/// or an error has occurred.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use up_rust::{UUri, communication::{CallOptions, InMemoryRpcClient, ServiceInvocationError}};
+///
+/// async fn download(client: &InMemoryRpcClient) -> Result<usize, ServiceInvocationError> {
+///     let method = UUri::try_from("//my-vehicle/A100/1/7").unwrap();
+///     let mut stream = client
+///         .invoke_method_streaming(method, CallOptions::for_rpc_request(5_000, None, None, None), None)
+///         .await?;
+///     let mut size = 0;
+///     while let Some(chunk) = stream.next().await {
+///         size += chunk?.payload().len();
+///     }
+///     Ok(size)
+/// }
+/// ```
+#[cfg(feature = "rpc-client")]
+pub struct ResponseStream {
+    receiver: UnboundedReceiver<UMessage>,
+    chunk_timeout: Duration,
+    // chunk index -> message that has been received out of order
+    buffered_chunks: BTreeMap<u32, UMessage>,
+    next_index: u32,
+    last_index: Option<u32>,
+    on_close: Option<Box<dyn FnOnce() + Send + Sync>>,
+    #[cfg(feature = "compression")]
+    max_decompressed_size: usize,
+}
+
+#[cfg(feature = "rpc-client")]
+impl ResponseStream {
+    pub(super) fn new(
+        receiver: UnboundedReceiver<UMessage>,
+        chunk_timeout: Duration,
+        on_close: Box<dyn FnOnce() + Send + Sync>,
+    ) -> Self {
+        ResponseStream {
+            receiver,
+            chunk_timeout,
+            buffered_chunks: BTreeMap::new(),
+            next_index: 0,
+            last_index: None,
+            on_close: Some(on_close),
+            #[cfg(feature = "compression")]
+            max_decompressed_size: super::DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    pub(super) fn with_max_decompressed_size(mut self, max_size: usize) -> Self {
+        self.max_decompressed_size = max_size;
+        self
+    }
+
+    fn process_response_message(
+        &self,
+        #[cfg_attr(not(feature = "compression"), allow(unused_mut))] mut message: UMessage,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        #[cfg(feature = "compression")]
+        super::compression::inflate_payload(&mut message, self.max_decompressed_size)?;
+        handle_response_message(message)
+    }
+
+    fn close(&mut self) {
+        if let Some(on_close) = self.on_close.take() {
+            on_close();
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.on_close.is_none()
+    }
+
+    /// Waits for the next chunk of the result.
+    ///
+    /// # Returns
+    ///
+    /// The next chunk or `None` if all chunks have been yielded already.
+    ///
+    /// # Errors
+    ///
+    /// Yields [`ServiceInvocationError::DeadlineExceeded`] if the next chunk has not been received
+    /// in time, or the error that the service provider has responded with.
+    pub async fn next(&mut self) -> Option<Result<UPayload, ServiceInvocationError>> {
+        loop {
+            if let Some(message) = self.buffered_chunks.remove(&self.next_index) {
+                if self.last_index == Some(self.next_index) {
+                    self.close();
+                }
+                self.next_index += 1;
+                match self.process_response_message(message) {
+                    Ok(Some(payload)) => return Some(Ok(payload)),
+                    Ok(None) => continue,
+                    Err(e) => {
+                        self.close();
+                        return Some(Err(e));
+                    }
+                }
+            }
+            if self.is_closed() {
+                return None;
+            }
+            match tokio::time::timeout(self.chunk_timeout, self.receiver.recv()).await {
+                Err(_elapsed) => {
+                    debug!("streamed response chunk has not been received in time");
+                    self.close();
+                    return Some(Err(ServiceInvocationError::DeadlineExceeded));
+                }
+                Ok(None) => {
+                    self.close();
+                    return Some(Err(ServiceInvocationError::Internal(
+                        "response listener has been closed".to_string(),
+                    )));
+                }
+                Ok(Some(message)) => {
+                    if let Some(result) = self.accept(message) {
+                        return Some(result);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Processes a received message.
+    ///
+    /// Returns a result to yield immediately, if the message does not carry a chunk.
+    fn accept(&mut self, message: UMessage) -> Option<Result<UPayload, ServiceInvocationError>> {
+        let attributes = message.attributes.get_or_default();
+        let Some(index) = attributes.chunk_index() else {
+            let is_error = attributes
+                .commstatus
+                .is_some_and(|code| code.enum_value_or_default() != UCode::OK);
+            if !is_error && (self.next_index > 0 || !self.buffered_chunks.is_empty()) {
+                // the RpcServer's response to a request that has been handled using a ResponseStreamWriter
+                debug!("ignoring non-chunked response to streamed request");
+                return None;
+            }
+            // a service provider that does not support streaming or an error
+            self.close();
+            return self.process_response_message(message).transpose();
+        };
+        if index < self.next_index {
+            debug!(index, "ignoring duplicate response chunk");
+            return None;
+        }
+        if attributes.is_last_chunk() {
+            self.last_index = Some(index);
+        }
+        self.buffered_chunks.insert(index, message);
+        None
+    }
+
+    /// Collects all remaining chunks of the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error that occurs while waiting for the chunks.
+    pub async fn collect(mut self) -> Result<Vec<UPayload>, ServiceInvocationError> {
+        let mut chunks = vec![];
+        while let Some(chunk) = self.next().await {
+            chunks.push(chunk?);
+        }
+        Ok(chunks)
+    }
+}
+
+#[cfg(feature = "rpc-client")]
+impl Drop for ResponseStream {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(all(test, feature = "rpc-client"))]
+mod tests {
+    use super::*;
+
+    use crate::{UPayloadFormat, UUri};
+
+    fn request_attributes() -> UAttributes {
+        UMessageBuilder::request(
+            UUri::try_from("//vehicle/A100/1/7").unwrap(),
+            UUri::try_from("//vehicle/B100/1/0").unwrap(),
+            5_000,
+        )
+        .build()
+        .unwrap()
+        .attributes
+        .unwrap()
+    }
+
+    fn chunk(request_attributes: &UAttributes, index: u32, last: bool, data: &str) -> UMessage {
+        let mut message = UMessageBuilder::response_for_request(request_attributes)
+            .build_with_payload(data.to_string(), UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+            .unwrap();
+        let attributes = message.attributes.mut_or_insert_default();
+        attributes.set_chunk_index(index);
+        attributes.set_last_chunk(last);
+        message
+    }
+
+    fn texts(chunks: Vec<UPayload>) -> Vec<String> {
+        chunks
+            .into_iter()
+            .map(|chunk| String::from_utf8(chunk.payload().to_vec()).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_stream_yields_chunks_in_order() {
+        // GIVEN a stream
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let closed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let closed_clone = closed.clone();
+        let stream = ResponseStream::new(
+            rx,
+            Duration::from_secs(1),
+            Box::new(move || closed_clone.store(true, std::sync::atomic::Ordering::SeqCst)),
+        );
+        let attributes = request_attributes();
+
+        // WHEN the chunks are received out of order, including a duplicate and the server's trailing response
+        tx.send(chunk(&attributes, 1, false, "b")).unwrap();
+        tx.send(chunk(&attributes, 0, false, "a")).unwrap();
+        tx.send(chunk(&attributes, 2, true, "c")).unwrap();
+        tx.send(chunk(&attributes, 0, false, "a")).unwrap();
+        tx.send(
+            UMessageBuilder::response_for_request(&attributes)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        // THEN all chunks are yielded in order
+        assert_eq!(texts(stream.collect().await.unwrap()), vec!["a", "b", "c"]);
+        // and the pending request has been removed
+        assert!(closed.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_stream_ends_with_error_response() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut stream = ResponseStream::new(rx, Duration::from_secs(1), Box::new(|| {}));
+        let attributes = request_attributes();
+
+        tx.send(chunk(&attributes, 0, false, "a")).unwrap();
+        let status = UStatus::fail_with_code(UCode::RESOURCE_EXHAUSTED, "out of memory");
+        tx.send(
+            UMessageBuilder::response_for_request(&attributes)
+                .with_comm_status(status.get_code())
+                .build_with_protobuf_payload(&status)
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert!(stream.next().await.is_some_and(|chunk| chunk.is_ok()));
+        assert!(stream
+            .next()
+            .await
+            .is_some_and(|chunk| chunk
+                .is_err_and(|e| matches!(e, ServiceInvocationError::ResourceExhausted(_)))));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_fails_if_chunk_is_not_received_in_time() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut stream = ResponseStream::new(rx, Duration::from_millis(50), Box::new(|| {}));
+        tx.send(chunk(&request_attributes(), 1, true, "b")).unwrap();
+
+        assert!(stream.next().await.is_some_and(
+            |chunk| chunk.is_err_and(|e| matches!(e, ServiceInvocationError::DeadlineExceeded))
+        ));
+        assert!(stream.next().await.is_none());
+    }
+}
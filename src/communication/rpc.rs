@@ -18,7 +18,7 @@ use async_trait::async_trait;
 use protobuf::MessageFull;
 
 use crate::communication::RegistrationError;
-use crate::{UAttributes, UCode, UStatus, UUri};
+use crate::{ResourceId, UAttributes, UCode, UStatus, UUri};
 
 use super::{CallOptions, UPayload};
 
@@ -161,6 +161,35 @@ pub trait RpcClient: Send + Sync {
         call_options: CallOptions,
         payload: Option<UPayload>,
     ) -> Result<Option<UPayload>, ServiceInvocationError>;
+
+    /// Invokes a method on a service without waiting for a response.
+    ///
+    /// This is useful for invoking methods which semantically do not have a reply, e.g. commands
+    /// that are executed on a best effort basis. The client does not keep track of the request,
+    /// so any response sent by the service is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The URI representing the method to invoke.
+    /// * `call_options` - Options to include in the request message.
+    /// * `payload` - The (optional) payload to include in the request message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given arguments cannot be turned into a valid RPC Request message
+    /// or if the request message cannot be sent.
+    ///
+    /// The default implementation returns [`ServiceInvocationError::Unimplemented`].
+    async fn invoke_no_response(
+        &self,
+        _method: UUri,
+        _call_options: CallOptions,
+        _payload: Option<UPayload>,
+    ) -> Result<(), ServiceInvocationError> {
+        Err(ServiceInvocationError::Unimplemented(
+            "RPC client does not support invoking methods without response".to_string(),
+        ))
+    }
 }
 
 impl dyn RpcClient {
@@ -208,6 +237,92 @@ impl dyn RpcClient {
     }
 }
 
+/// Extension methods for invoking service operations using proto-generated `Message` objects.
+///
+/// The methods are available for all [`RpcClient`] implementations and take care of packing the
+/// request message into the request payload and extracting the response message from the response payload.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use protobuf::well_known_types::wrappers::StringValue;
+/// use up_rust::{UUri, communication::{CallOptions, RpcClient, RpcClientExt, ServiceInvocationError}};
+///
+/// async fn echo(client: Arc<dyn RpcClient>) -> Result<String, ServiceInvocationError> {
+///     let request = StringValue {
+///         value: "hello".to_string(),
+///         ..Default::default()
+///     };
+///     let response: StringValue = client
+///         .call(
+///             UUri::try_from("//my-vehicle/A100/1/7").unwrap(),
+///             request,
+///             CallOptions::for_rpc_request(5_000, None, None, None),
+///         )
+///         .await?;
+///     Ok(response.value)
+/// }
+/// ```
+#[async_trait]
+pub trait RpcClientExt: RpcClient {
+    /// Invokes a method on a service using and returning proto-generated `Message` objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The URI representing the method to invoke.
+    /// * `request` - The protobuf `Message` to include in the request message.
+    /// * `call_options` - Options to include in the request message.
+    ///
+    /// # Returns
+    ///
+    /// The response message returned by the service operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if invocation fails or the given arguments cannot be turned into a valid
+    /// RPC Request message. Returns [`ServiceInvocationError::NotFound`] if the service operation has not
+    /// returned any payload and [`ServiceInvocationError::InvalidArgument`] if the returned payload cannot
+    /// be deserialized into the response type.
+    async fn call<Req, Resp>(
+        &self,
+        method: UUri,
+        request: Req,
+        call_options: CallOptions,
+    ) -> Result<Resp, ServiceInvocationError>
+    where
+        Req: MessageFull,
+        Resp: MessageFull;
+}
+
+#[async_trait]
+impl<T: RpcClient + ?Sized> RpcClientExt for T {
+    async fn call<Req, Resp>(
+        &self,
+        method: UUri,
+        request: Req,
+        call_options: CallOptions,
+    ) -> Result<Resp, ServiceInvocationError>
+    where
+        Req: MessageFull,
+        Resp: MessageFull,
+    {
+        let payload = UPayload::try_from_protobuf(request)
+            .map_err(|e| ServiceInvocationError::InvalidArgument(e.to_string()))?;
+        let Some(response) = self
+            .invoke_method(method, call_options, Some(payload))
+            .await?
+        else {
+            return Err(ServiceInvocationError::NotFound(
+                "service operation has returned no payload".to_string(),
+            ));
+        };
+        response
+            .extract_protobuf::<Resp>()
+            .map_err(|e| ServiceInvocationError::InvalidArgument(e.to_string()))
+    }
+}
+
 /// A handler for processing incoming RPC requests.
 ///
 // [impl->req~up-language-comm-api~1]
@@ -289,6 +404,31 @@ pub trait RpcServer {
         resource_id: u16,
         request_handler: Arc<dyn RequestHandler>,
     ) -> Result<(), RegistrationError>;
+
+    /// Registers an endpoint for RPC requests to a method identified by a typed resource ID.
+    ///
+    /// This default implementation delegates to [`Self::register_endpoint`].
+    ///
+    /// # Arguments
+    ///
+    /// * `origin_filter` - A pattern defining origin addresses to accept requests from. If `None`, requests
+    ///                     will be accepted from all sources.
+    /// * `method` - The resource identifier of the (local) method to accept requests for.
+    /// * `request_handler` - The handler to invoke for each incoming request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listener cannot be registered or if a listener has already been registered
+    /// for the given resource ID.
+    async fn register_method(
+        &self,
+        origin_filter: Option<&UUri>,
+        method: ResourceId,
+        request_handler: Arc<dyn RequestHandler>,
+    ) -> Result<(), RegistrationError> {
+        self.register_endpoint(origin_filter, method.value(), request_handler)
+            .await
+    }
 }
 
 #[cfg(any(test, feature = "test-util"))]
@@ -336,6 +476,59 @@ mod tests {
 
     use super::*;
 
+    #[tokio::test]
+    async fn test_call_returns_response_message() {
+        let mut rpc_client = MockRpcClient::new();
+        rpc_client
+            .expect_invoke_method()
+            .once()
+            .withf(|_method, _options, payload| {
+                payload.as_ref().is_some_and(|payload| {
+                    payload
+                        .extract_protobuf::<StringValue>()
+                        .is_ok_and(|request| request.value == "hello")
+                })
+            })
+            .returning(|_method, _options, _payload| {
+                let response = StringValue {
+                    value: "world".to_string(),
+                    ..Default::default()
+                };
+                Ok(Some(UPayload::try_from_protobuf(response).unwrap()))
+            });
+        let request = StringValue {
+            value: "hello".to_string(),
+            ..Default::default()
+        };
+        let response: StringValue = rpc_client
+            .call(
+                UUri::try_from_parts("", 0x1000, 0x01, 0x0001).unwrap(),
+                request,
+                CallOptions::for_rpc_request(5_000, None, None, None),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.value, "world");
+    }
+
+    #[tokio::test]
+    async fn test_call_fails_for_missing_response_payload() {
+        let mut rpc_client = MockRpcClient::new();
+        rpc_client
+            .expect_invoke_method()
+            .once()
+            .return_const(Ok(None));
+        let client: Arc<dyn RpcClient> = Arc::new(rpc_client);
+        let result = client
+            .call::<_, StringValue>(
+                UUri::try_from_parts("", 0x1000, 0x01, 0x0001).unwrap(),
+                StringValue::new(),
+                CallOptions::for_rpc_request(5_000, None, None, None),
+            )
+            .await;
+        assert!(result.is_err_and(|e| matches!(e, ServiceInvocationError::NotFound(_))));
+    }
+
     #[tokio::test]
     async fn test_invoke_proto_method_fails_for_unexpected_return_type() {
         let mut rpc_client = MockRpcClient::new();
@@ -379,4 +572,22 @@ mod tests {
             .await;
         assert!(result.is_err_and(|e| matches!(e, ServiceInvocationError::InvalidArgument(_))));
     }
+
+    #[tokio::test]
+    async fn test_register_method_delegates_to_register_endpoint() {
+        const GET_STATUS: ResourceId = ResourceId::method(0x0007);
+        let mut rpc_server = MockRpcServerImpl::new();
+        rpc_server
+            .expect_do_register_endpoint()
+            .once()
+            .withf(|origin_filter, resource_id, _handler| {
+                origin_filter.is_none() && *resource_id == 0x0007
+            })
+            .returning(|_origin_filter, _resource_id, _handler| Ok(()));
+
+        assert!(rpc_server
+            .register_method(None, GET_STATUS, Arc::new(MockRequestHandler::new()))
+            .await
+            .is_ok());
+    }
 }
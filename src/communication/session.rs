@@ -0,0 +1,332 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use protobuf::well_known_types::{empty::Empty, wrappers::StringValue};
+use tracing::debug;
+
+use crate::{UAttributes, UUri, UUID};
+
+use super::{
+    CallOptions, RequestHandler, RpcClient, RpcClientExt, ServiceInvocationError, UPayload,
+};
+
+struct Session {
+    client: UUri,
+    last_activity: Instant,
+}
+
+/// Keeps track of the sessions that clients have opened with a (stateful) service.
+///
+/// A session expires if no request has been made in its context for the registry's idle timeout.
+/// Services usually register a [`SessionHandshakeHandler`] for letting clients open sessions and use
+/// [`SessionRegistry::verify_request`] for associating subsequent requests with a session.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use up_rust::{UAttributes, UUri};
+/// use up_rust::communication::SessionRegistry;
+///
+/// let registry = SessionRegistry::new(Duration::from_secs(60));
+/// let client = UUri::try_from("//my-vehicle/A100/1/0").unwrap();
+/// let session_id = registry.open(&client);
+///
+/// let mut request_attributes = UAttributes {
+///     source: Some(client).into(),
+///     ..Default::default()
+/// };
+/// request_attributes.set_session_id(session_id.clone());
+/// assert_eq!(registry.verify_request(&request_attributes).unwrap(), session_id);
+/// ```
+pub struct SessionRegistry {
+    idle_timeout: Duration,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl SessionRegistry {
+    /// Creates a new registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `idle_timeout` - The amount of time after which a session expires if it is not being used.
+    pub fn new(idle_timeout: Duration) -> Self {
+        SessionRegistry {
+            idle_timeout,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Opens a new session for a client.
+    ///
+    /// Expired sessions are removed from the registry as a side effect.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the new session.
+    pub fn open(&self, client: &UUri) -> String {
+        let session_id = UUID::build().to_hyphenated_string();
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|_id, session| session.last_activity.elapsed() < self.idle_timeout);
+            sessions.insert(
+                session_id.clone(),
+                Session {
+                    client: client.to_owned(),
+                    last_activity: Instant::now(),
+                },
+            );
+        }
+        debug!(session_id, client = %client.to_uri(false), "opened session");
+        session_id
+    }
+
+    /// Closes a session.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the session existed and had not expired yet.
+    pub fn close(&self, session_id: &str) -> bool {
+        self.sessions.lock().is_ok_and(|mut sessions| {
+            sessions
+                .remove(session_id)
+                .is_some_and(|session| session.last_activity.elapsed() < self.idle_timeout)
+        })
+    }
+
+    /// Gets the number of sessions that have not expired.
+    pub fn len(&self) -> usize {
+        self.sessions.lock().map_or(0, |sessions| {
+            sessions
+                .values()
+                .filter(|session| session.last_activity.elapsed() < self.idle_timeout)
+                .count()
+        })
+    }
+
+    /// Checks if there are no sessions that have not expired.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the session that a request message belongs to.
+    ///
+    /// The session's expiry is extended by the registry's idle timeout.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the session.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServiceInvocationError::FailedPrecondition`] if the request does not contain a
+    /// [session identifier](`UAttributes::session_id`), if the session does not exist or has expired,
+    /// or if the session has been opened by a different client.
+    pub fn verify_request(
+        &self,
+        request_attributes: &UAttributes,
+    ) -> Result<String, ServiceInvocationError> {
+        let Some(session_id) = request_attributes.session_id() else {
+            return Err(ServiceInvocationError::FailedPrecondition(
+                "request does not belong to a session".to_string(),
+            ));
+        };
+        let mut sessions = self.sessions.lock().map_err(|_e| {
+            ServiceInvocationError::Internal("failed to access session registry".to_string())
+        })?;
+        match sessions.get_mut(&session_id) {
+            Some(session)
+                if session.last_activity.elapsed() < self.idle_timeout
+                    && Some(&session.client) == request_attributes.source.as_ref() =>
+            {
+                session.last_activity = Instant::now();
+                Ok(session_id)
+            }
+            Some(session) if session.last_activity.elapsed() >= self.idle_timeout => {
+                sessions.remove(&session_id);
+                Err(ServiceInvocationError::FailedPrecondition(
+                    "session has expired".to_string(),
+                ))
+            }
+            _ => Err(ServiceInvocationError::FailedPrecondition(
+                "no such session".to_string(),
+            )),
+        }
+    }
+}
+
+/// A [`RequestHandler`] which opens a session for the client invoking the method.
+///
+/// The handler responds with the identifier of the new session, wrapped in a protobuf `StringValue`.
+/// Clients use [`open_session`] for invoking the handler.
+pub struct SessionHandshakeHandler {
+    registry: Arc<SessionRegistry>,
+}
+
+impl SessionHandshakeHandler {
+    /// Creates a new handler for a registry.
+    pub fn new(registry: Arc<SessionRegistry>) -> Self {
+        SessionHandshakeHandler { registry }
+    }
+}
+
+#[async_trait]
+impl RequestHandler for SessionHandshakeHandler {
+    async fn handle_request(
+        &self,
+        _resource_id: u16,
+        message_attributes: &UAttributes,
+        _request_payload: Option<UPayload>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        let Some(client) = message_attributes.source.as_ref() else {
+            return Err(ServiceInvocationError::InvalidArgument(
+                "request does not contain source address".to_string(),
+            ));
+        };
+        let session_id = StringValue {
+            value: self.registry.open(client),
+            ..Default::default()
+        };
+        UPayload::try_from_protobuf(session_id)
+            .map(Some)
+            .map_err(|e| ServiceInvocationError::Internal(e.to_string()))
+    }
+}
+
+/// Opens a session with a service by invoking the service's handshake method.
+///
+/// The returned identifier can be used for associating subsequent requests with the session
+/// by means of [`CallOptions::with_session_id`].
+///
+/// # Arguments
+///
+/// * `rpc_client` - The client to use for invoking the handshake method.
+/// * `handshake_method` - The method at which the service has registered a [`SessionHandshakeHandler`].
+/// * `call_options` - The options to use for invoking the method.
+///
+/// # Errors
+///
+/// Returns an error if the handshake method could not be invoked or did not return a session identifier.
+pub async fn open_session(
+    rpc_client: Arc<dyn RpcClient>,
+    handshake_method: UUri,
+    call_options: CallOptions,
+) -> Result<String, ServiceInvocationError> {
+    rpc_client
+        .call::<_, StringValue>(handshake_method, Empty::new(), call_options)
+        .await
+        .map(|session_id| session_id.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::communication::MockRpcClient;
+
+    fn request_attributes(client: &UUri, session_id: Option<&str>) -> UAttributes {
+        let mut attributes = UAttributes {
+            source: Some(client.to_owned()).into(),
+            ..Default::default()
+        };
+        if let Some(id) = session_id {
+            attributes.set_session_id(id);
+        }
+        attributes
+    }
+
+    #[test]
+    fn test_verify_request_fails_for_other_client() {
+        let registry = SessionRegistry::new(Duration::from_secs(10));
+        let client = UUri::try_from("//vehicle/A100/1/0").unwrap();
+        let other_client = UUri::try_from("//vehicle/A200/1/0").unwrap();
+        let session_id = registry.open(&client);
+
+        assert!(registry
+            .verify_request(&request_attributes(&other_client, Some(&session_id)))
+            .is_err_and(|e| matches!(e, ServiceInvocationError::FailedPrecondition(_))));
+        assert!(registry
+            .verify_request(&request_attributes(&client, None))
+            .is_err_and(|e| matches!(e, ServiceInvocationError::FailedPrecondition(_))));
+        assert!(registry
+            .verify_request(&request_attributes(&client, Some(&session_id)))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_sessions_expire_after_idle_timeout() {
+        // GIVEN a registry with a session
+        let registry = SessionRegistry::new(Duration::from_millis(50));
+        let client = UUri::try_from("//vehicle/A100/1/0").unwrap();
+        let session_id = registry.open(&client);
+        let attributes = request_attributes(&client, Some(&session_id));
+
+        // WHEN using the session within the idle timeout
+        std::thread::sleep(Duration::from_millis(30));
+        // THEN the session is still valid
+        assert!(registry.verify_request(&attributes).is_ok());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(registry.verify_request(&attributes).is_ok());
+        assert_eq!(registry.len(), 1);
+
+        // WHEN the session is not used for longer than the idle timeout
+        std::thread::sleep(Duration::from_millis(60));
+        // THEN the session has expired
+        assert!(registry.is_empty());
+        assert!(registry
+            .verify_request(&attributes)
+            .is_err_and(|e| matches!(e, ServiceInvocationError::FailedPrecondition(_))));
+        assert!(!registry.close(&session_id));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_opens_session() {
+        // GIVEN a service with a handshake handler
+        let registry = Arc::new(SessionRegistry::new(Duration::from_secs(10)));
+        let handler = Arc::new(SessionHandshakeHandler::new(registry.clone()));
+        let client = UUri::try_from("//vehicle/A100/1/0").unwrap();
+
+        // which responds to a handshake request from a client
+        let handshake_response = handler
+            .handle_request(0x0001, &request_attributes(&client, None), None)
+            .await
+            .unwrap();
+        // that is being delivered via an RPC client
+        let mut rpc_client = MockRpcClient::new();
+        rpc_client
+            .expect_invoke_method()
+            .once()
+            .return_const(Ok(handshake_response));
+
+        // WHEN the client opens a session
+        let session_id = open_session(
+            Arc::new(rpc_client),
+            UUri::try_from("//vehicle/B100/1/1").unwrap(),
+            CallOptions::for_rpc_request(5_000, None, None, None),
+        )
+        .await
+        .unwrap();
+
+        // THEN the session can be used by the client
+        assert_eq!(
+            registry
+                .verify_request(&request_attributes(&client, Some(&session_id)))
+                .unwrap(),
+            session_id
+        );
+    }
+}
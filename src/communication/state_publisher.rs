@@ -0,0 +1,366 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use protobuf::MessageFull;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::umessage::UMessageError;
+
+use super::{CallOptions, PubSubError, Publisher, UPayload};
+
+const PUBLICATION_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+type Encoder<T> = Box<dyn Fn(&T) -> Result<UPayload, UMessageError> + Send + Sync>;
+
+struct State<T> {
+    latest_value: Option<T>,
+    // indicates whether the latest value still needs to be published
+    pending: bool,
+    last_published: Option<Instant>,
+}
+
+/// Publishes the current value of a (typed) state to a topic.
+///
+/// The value is published whenever it changes. Optionally, the publisher
+/// * makes sure that a minimum amount of time passes between subsequent publications, so that
+///   rapidly changing values do not flood the bus. The latest value is published once the interval has elapsed.
+/// * re-publishes the current value periodically, so that consumers can detect that the producer is still alive.
+///
+/// Both options require the publisher's [timers](`Self::run_timers`) to be running.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use protobuf::well_known_types::wrappers::BoolValue;
+/// use up_rust::communication::{Publisher, StatePublisher};
+///
+/// async fn publish_door_state(publisher: Arc<dyn Publisher>) {
+///     let state_publisher = Arc::new(
+///         StatePublisher::<BoolValue>::new(publisher, 0x8001)
+///             .with_min_interval(Duration::from_millis(100))
+///             .with_keep_alive_interval(Duration::from_secs(5)),
+///     );
+///     let timers = state_publisher.clone();
+///     tokio::spawn(async move { timers.run_timers().await });
+///
+///     let open = BoolValue {
+///         value: true,
+///         ..Default::default()
+///     };
+///     // publishes the value
+///     assert!(state_publisher.update(open.clone()).await.unwrap());
+///     // does not publish the unchanged value again
+///     assert!(!state_publisher.update(open).await.unwrap());
+/// }
+/// ```
+pub struct StatePublisher<T> {
+    publisher: Arc<dyn Publisher>,
+    resource_id: u16,
+    encoder: Encoder<T>,
+    min_interval: Option<Duration>,
+    keep_alive_interval: Option<Duration>,
+    state: Mutex<State<T>>,
+    state_changed: Notify,
+}
+
+impl<T: MessageFull> StatePublisher<T> {
+    /// Creates a new publisher for a state represented by a protobuf message.
+    ///
+    /// Values are published as [`UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY`](`crate::UPayloadFormat`).
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The publisher to use for publishing the values.
+    /// * `resource_id` - The (local) resource ID of the topic to publish to.
+    pub fn new(publisher: Arc<dyn Publisher>, resource_id: u16) -> Self {
+        Self::with_encoder(publisher, resource_id, |value: &T| {
+            UPayload::try_from_protobuf(value.clone())
+        })
+    }
+}
+
+impl<T: PartialEq + Send + Sync> StatePublisher<T> {
+    /// Creates a new publisher for a state of an arbitrary type.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The publisher to use for publishing the values.
+    /// * `resource_id` - The (local) resource ID of the topic to publish to.
+    /// * `encoder` - The function to use for creating the payload representing a value.
+    pub fn with_encoder<F>(publisher: Arc<dyn Publisher>, resource_id: u16, encoder: F) -> Self
+    where
+        F: Fn(&T) -> Result<UPayload, UMessageError> + Send + Sync + 'static,
+    {
+        StatePublisher {
+            publisher,
+            resource_id,
+            encoder: Box::new(encoder),
+            min_interval: None,
+            keep_alive_interval: None,
+            state: Mutex::new(State {
+                latest_value: None,
+                pending: false,
+                last_published: None,
+            }),
+            state_changed: Notify::new(),
+        }
+    }
+
+    /// Sets the minimum amount of time between subsequent publications.
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = Some(min_interval);
+        self
+    }
+
+    /// Sets the amount of time after which the current value is re-published, if it has not changed.
+    pub fn with_keep_alive_interval(mut self, keep_alive_interval: Duration) -> Self {
+        self.keep_alive_interval = Some(keep_alive_interval);
+        self
+    }
+
+    /// Sets the current value of the state.
+    ///
+    /// The value is published immediately if it differs from the previous value and if the
+    /// minimum interval since the last publication has elapsed. Otherwise, publication of the value
+    /// is deferred until the minimum interval has elapsed.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value has been published.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value could not be published. Publication of the value is retried
+    /// by the publisher's timers.
+    pub async fn update(&self, value: T) -> Result<bool, PubSubError> {
+        let mut state = self.state.lock().await;
+        if state.latest_value.as_ref() == Some(&value) {
+            return Ok(false);
+        }
+        state.latest_value = Some(value);
+        state.pending = true;
+        let published = if self.is_min_interval_elapsed(&state) {
+            self.publish_latest_value(&mut state).await.map(|_| true)
+        } else {
+            debug!(
+                resource_id = self.resource_id,
+                "deferring publication of state"
+            );
+            Ok(false)
+        };
+        self.state_changed.notify_one();
+        published
+    }
+
+    /// Gets the current value of the state, if any.
+    pub async fn current_value(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.state.lock().await.latest_value.clone()
+    }
+
+    /// Publishes deferred values and re-publishes the current value according to the
+    /// configured intervals.
+    ///
+    /// The returned future never completes. It can be spawned on a runtime and aborted
+    /// when the state is no longer being published.
+    pub async fn run_timers(&self) {
+        loop {
+            let next_deadline = {
+                let mut state = self.state.lock().await;
+                let now = Instant::now();
+                let publication_due = (state.pending && self.is_min_interval_elapsed(&state))
+                    || self.keep_alive_deadline(&state).is_some_and(|d| d <= now);
+                if publication_due {
+                    if let Err(e) = self.publish_latest_value(&mut state).await {
+                        info!(
+                            resource_id = self.resource_id,
+                            "failed to publish state: {}", e
+                        );
+                        // do not retry immediately
+                        Some(now + PUBLICATION_RETRY_DELAY)
+                    } else {
+                        self.next_deadline(&state)
+                    }
+                } else {
+                    self.next_deadline(&state)
+                }
+            };
+            match next_deadline {
+                Some(deadline) => {
+                    let _ = tokio::time::timeout_at(deadline, self.state_changed.notified()).await;
+                }
+                None => self.state_changed.notified().await,
+            }
+        }
+    }
+
+    fn is_min_interval_elapsed(&self, state: &State<T>) -> bool {
+        match (self.min_interval, state.last_published) {
+            (Some(min_interval), Some(last_published)) => last_published.elapsed() >= min_interval,
+            _ => true,
+        }
+    }
+
+    fn keep_alive_deadline(&self, state: &State<T>) -> Option<Instant> {
+        match (self.keep_alive_interval, state.last_published) {
+            (Some(interval), Some(last_published)) => Some(last_published + interval),
+            _ => None,
+        }
+    }
+
+    fn next_deadline(&self, state: &State<T>) -> Option<Instant> {
+        let deferred_publication_deadline = if state.pending {
+            Some(
+                state
+                    .last_published
+                    .zip(self.min_interval)
+                    .map_or_else(Instant::now, |(last_published, min_interval)| {
+                        last_published + min_interval
+                    }),
+            )
+        } else {
+            None
+        };
+        match (
+            deferred_publication_deadline,
+            self.keep_alive_deadline(state),
+        ) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    async fn publish_latest_value(&self, state: &mut State<T>) -> Result<(), PubSubError> {
+        let Some(value) = state.latest_value.as_ref() else {
+            return Ok(());
+        };
+        let payload =
+            (self.encoder)(value).map_err(|e| PubSubError::InvalidArgument(e.to_string()))?;
+        self.publisher
+            .publish(
+                self.resource_id,
+                CallOptions::for_publish(None, None, None),
+                Some(payload),
+            )
+            .await?;
+        state.pending = false;
+        state.last_published = Some(Instant::now());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use protobuf::well_known_types::wrappers::UInt32Value;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingPublisher {
+        published_values: std::sync::Mutex<Vec<u32>>,
+    }
+
+    impl RecordingPublisher {
+        fn published_values(&self) -> Vec<u32> {
+            self.published_values.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl Publisher for RecordingPublisher {
+        async fn publish(
+            &self,
+            _resource_id: u16,
+            _call_options: CallOptions,
+            payload: Option<UPayload>,
+        ) -> Result<(), PubSubError> {
+            let value: UInt32Value = payload.unwrap().extract_protobuf().unwrap();
+            self.published_values.lock().unwrap().push(value.value);
+            Ok(())
+        }
+    }
+
+    fn value(value: u32) -> UInt32Value {
+        UInt32Value {
+            value,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_publishes_changed_values_only() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let state_publisher = StatePublisher::<UInt32Value>::new(publisher.clone(), 0x8001);
+
+        assert!(state_publisher.update(value(1)).await.unwrap());
+        assert!(!state_publisher.update(value(1)).await.unwrap());
+        assert!(state_publisher.update(value(2)).await.unwrap());
+
+        assert_eq!(publisher.published_values(), vec![1, 2]);
+        assert_eq!(state_publisher.current_value().await, Some(value(2)));
+    }
+
+    #[tokio::test]
+    async fn test_timers_publish_deferred_value() {
+        // GIVEN a state publisher with a minimum interval
+        let publisher = Arc::new(RecordingPublisher::default());
+        let state_publisher = Arc::new(
+            StatePublisher::<UInt32Value>::new(publisher.clone(), 0x8001)
+                .with_min_interval(Duration::from_millis(50)),
+        );
+        let timers = state_publisher.clone();
+        let timers_task = tokio::spawn(async move { timers.run_timers().await });
+
+        // WHEN the value changes multiple times within the minimum interval
+        assert!(state_publisher.update(value(1)).await.unwrap());
+        assert!(!state_publisher.update(value(2)).await.unwrap());
+        assert!(!state_publisher.update(value(3)).await.unwrap());
+
+        // THEN only the latest value is published after the interval has elapsed
+        assert_eq!(publisher.published_values(), vec![1]);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(publisher.published_values(), vec![1, 3]);
+        timers_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_timers_republish_unchanged_value() {
+        // GIVEN a state publisher with a keep-alive interval
+        let publisher = Arc::new(RecordingPublisher::default());
+        let state_publisher = Arc::new(
+            StatePublisher::<UInt32Value>::new(publisher.clone(), 0x8001)
+                .with_keep_alive_interval(Duration::from_millis(40)),
+        );
+        let timers = state_publisher.clone();
+        let timers_task = tokio::spawn(async move { timers.run_timers().await });
+
+        // WHEN the value does not change
+        assert!(state_publisher.update(value(7)).await.unwrap());
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        // THEN the value is re-published periodically
+        assert!(publisher.published_values().len() >= 3);
+        assert!(publisher.published_values().iter().all(|v| *v == 7));
+        timers_task.abort();
+    }
+}
@@ -0,0 +1,285 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use tokio::sync::watch;
+use tracing::debug;
+
+use crate::core::usubscription::{State, SubscriptionStatus};
+use crate::UUri;
+
+use super::SubscriptionChangeHandler;
+
+const DEFAULT_MAX_HISTORY_LENGTH: usize = 16;
+
+/// The state of a subscription to a topic, as tracked by a [`SubscriptionStateMachine`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SubscriptionState {
+    /// The USubscription service is in the process of (un)subscribing to the topic.
+    Pending,
+    /// Events published to the topic are being delivered.
+    Subscribed,
+    /// Events published to the topic are not being delivered.
+    Unsubscribed,
+    /// The subscription has expired.
+    Expired,
+}
+
+impl From<State> for SubscriptionState {
+    fn from(value: State) -> Self {
+        match value {
+            State::SUBSCRIBE_PENDING | State::UNSUBSCRIBE_PENDING => SubscriptionState::Pending,
+            State::SUBSCRIBED => SubscriptionState::Subscribed,
+            State::UNSUBSCRIBED => SubscriptionState::Unsubscribed,
+        }
+    }
+}
+
+/// A change of a topic's [`SubscriptionState`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubscriptionTransition {
+    /// The state before the change or `None` if the topic had not been tracked before.
+    pub from: Option<SubscriptionState>,
+    /// The state after the change.
+    pub to: SubscriptionState,
+    /// The point in time at which the change has been recorded.
+    pub at: SystemTime,
+    /// The message that has been included in the status update, if any.
+    pub message: String,
+}
+
+struct TopicState {
+    sender: watch::Sender<Option<SubscriptionState>>,
+    history: VecDeque<SubscriptionTransition>,
+}
+
+impl TopicState {
+    fn new() -> Self {
+        TopicState {
+            sender: watch::Sender::new(None),
+            history: VecDeque::new(),
+        }
+    }
+}
+
+/// A [`SubscriptionChangeHandler`] which keeps track of the state of subscriptions.
+///
+/// The same state machine can be used with multiple topics. For each topic, the state machine
+/// exposes the current state, a (bounded) history of state transitions and a
+/// [watch channel](`Self::watch`) which can be used to wait for a subscription to become active.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::UUri;
+/// use up_rust::communication::{SubscriptionChangeHandler, SubscriptionState, SubscriptionStateMachine};
+/// use up_rust::core::usubscription::{State, SubscriptionStatus};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let topic = UUri::try_from("//my-vehicle/A8000/2/8001").unwrap();
+/// let state_machine = SubscriptionStateMachine::new();
+/// let mut state_receiver = state_machine.watch(&topic);
+///
+/// // usually invoked by a Subscriber
+/// state_machine.on_subscription_change(
+///     topic.clone(),
+///     SubscriptionStatus {
+///         state: State::SUBSCRIBED.into(),
+///         ..Default::default()
+///     },
+/// );
+///
+/// let state = state_receiver
+///     .wait_for(|state| *state == Some(SubscriptionState::Subscribed))
+///     .await
+///     .unwrap();
+/// assert_eq!(*state, Some(SubscriptionState::Subscribed));
+/// assert!(state_machine.is_subscribed(&topic));
+/// # }
+/// ```
+pub struct SubscriptionStateMachine {
+    max_history_length: usize,
+    topics: Mutex<HashMap<UUri, TopicState>>,
+}
+
+impl Default for SubscriptionStateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubscriptionStateMachine {
+    /// Creates a new state machine which keeps the last 16 transitions per topic.
+    pub fn new() -> Self {
+        SubscriptionStateMachine {
+            max_history_length: DEFAULT_MAX_HISTORY_LENGTH,
+            topics: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the maximum number of transitions to keep per topic.
+    pub fn with_max_history_length(mut self, max_history_length: usize) -> Self {
+        self.max_history_length = max_history_length;
+        self
+    }
+
+    /// Gets the current state of the subscription to a topic.
+    ///
+    /// # Returns
+    ///
+    /// The state or `None` if no status has been recorded for the topic yet.
+    pub fn state(&self, topic: &UUri) -> Option<SubscriptionState> {
+        self.topics
+            .lock()
+            .ok()
+            .and_then(|topics| topics.get(topic).and_then(|t| *t.sender.borrow()))
+    }
+
+    /// Checks if events published to a topic are being delivered.
+    pub fn is_subscribed(&self, topic: &UUri) -> bool {
+        self.state(topic) == Some(SubscriptionState::Subscribed)
+    }
+
+    /// Gets the recorded state transitions of the subscription to a topic, oldest first.
+    pub fn history(&self, topic: &UUri) -> Vec<SubscriptionTransition> {
+        self.topics.lock().map_or(vec![], |topics| {
+            topics
+                .get(topic)
+                .map_or(vec![], |t| t.history.iter().cloned().collect())
+        })
+    }
+
+    /// Gets a receiver for changes of the state of the subscription to a topic.
+    ///
+    /// The receiver's value is `None` until a status has been recorded for the topic.
+    pub fn watch(&self, topic: &UUri) -> watch::Receiver<Option<SubscriptionState>> {
+        match self.topics.lock() {
+            Ok(mut topics) => topics
+                .entry(topic.to_owned())
+                .or_insert_with(TopicState::new)
+                .sender
+                .subscribe(),
+            Err(_e) => watch::Sender::new(None).subscribe(),
+        }
+    }
+
+    /// Marks the subscription to a topic as expired.
+    ///
+    /// The USubscription service does not send status updates when a subscription expires,
+    /// so applications need to record the expiration themselves.
+    pub fn mark_expired(&self, topic: &UUri) {
+        self.transition(topic, SubscriptionState::Expired, String::new());
+    }
+
+    fn transition(&self, topic: &UUri, new_state: SubscriptionState, message: String) {
+        let Ok(mut topics) = self.topics.lock() else {
+            return;
+        };
+        let topic_state = topics
+            .entry(topic.to_owned())
+            .or_insert_with(TopicState::new);
+        let old_state = *topic_state.sender.borrow();
+        if old_state == Some(new_state) {
+            return;
+        }
+        debug!(topic = %topic, from = ?old_state, to = ?new_state, "subscription state has changed");
+        if self.max_history_length > 0 {
+            if topic_state.history.len() >= self.max_history_length {
+                topic_state.history.pop_front();
+            }
+            topic_state.history.push_back(SubscriptionTransition {
+                from: old_state,
+                to: new_state,
+                at: SystemTime::now(),
+                message,
+            });
+        }
+        topic_state.sender.send_replace(Some(new_state));
+    }
+}
+
+impl SubscriptionChangeHandler for SubscriptionStateMachine {
+    fn on_subscription_change(&self, topic: UUri, new_status: SubscriptionStatus) {
+        let new_state = SubscriptionState::from(new_status.state.enum_value_or_default());
+        self.transition(&topic, new_state, new_status.message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(state: State) -> SubscriptionStatus {
+        SubscriptionStatus {
+            state: state.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_state_machine_records_transitions() {
+        // GIVEN a state machine keeping two transitions per topic
+        let state_machine = SubscriptionStateMachine::new().with_max_history_length(2);
+        let topic = UUri::try_from_parts("vehicle", 0x1000, 0x01, 0x8001).unwrap();
+        assert!(state_machine.state(&topic).is_none());
+
+        // WHEN the subscription goes through multiple states
+        state_machine.on_subscription_change(topic.clone(), status(State::SUBSCRIBE_PENDING));
+        state_machine.on_subscription_change(topic.clone(), status(State::SUBSCRIBE_PENDING));
+        state_machine.on_subscription_change(topic.clone(), status(State::SUBSCRIBED));
+        state_machine.mark_expired(&topic);
+
+        // THEN the current state reflects the last transition
+        assert_eq!(
+            state_machine.state(&topic),
+            Some(SubscriptionState::Expired)
+        );
+        assert!(!state_machine.is_subscribed(&topic));
+        // and the history contains the most recent actual transitions only
+        let history = state_machine.history(&topic);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].from, Some(SubscriptionState::Pending));
+        assert_eq!(history[0].to, SubscriptionState::Subscribed);
+        assert_eq!(history[1].from, Some(SubscriptionState::Subscribed));
+        assert_eq!(history[1].to, SubscriptionState::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_watch_receives_state_changes() {
+        // GIVEN a state machine
+        let state_machine = SubscriptionStateMachine::new();
+        let topic = UUri::try_from_parts("vehicle", 0x1000, 0x01, 0x8001).unwrap();
+        let other_topic = UUri::try_from_parts("vehicle", 0x1000, 0x01, 0x8002).unwrap();
+        // and a receiver watching a topic
+        let mut receiver = state_machine.watch(&topic);
+        assert!(receiver.borrow_and_update().is_none());
+
+        // WHEN the subscription to another topic changes
+        state_machine.on_subscription_change(other_topic, status(State::SUBSCRIBED));
+        // THEN the receiver is not notified
+        assert!(!receiver.has_changed().unwrap());
+
+        // WHEN the subscription to the watched topic changes
+        state_machine.on_subscription_change(topic, status(State::UNSUBSCRIBED));
+        // THEN the receiver is notified
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(
+            *receiver.borrow_and_update(),
+            Some(SubscriptionState::Unsubscribed)
+        );
+    }
+}
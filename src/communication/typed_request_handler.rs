@@ -0,0 +1,164 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::{future::Future, marker::PhantomData};
+
+use async_trait::async_trait;
+use protobuf::MessageFull;
+use tracing::debug;
+
+use crate::UAttributes;
+
+use super::{RequestHandler, ServiceInvocationError, UPayload};
+
+/// A [`RequestHandler`] that extracts a protobuf message of a particular type from the request
+/// payload, passes it on to a (user provided) function and packs the protobuf message returned
+/// by the function into the response payload.
+///
+/// Requests that do not contain a payload are treated like requests containing the default
+/// instance of the request message type, in line with protobuf's encoding of empty messages.
+/// Requests whose payload cannot be extracted as the expected type are rejected with
+/// [`ServiceInvocationError::InvalidArgument`].
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use protobuf::well_known_types::wrappers::{StringValue, UInt32Value};
+/// use up_rust::communication::{RequestHandler, ServiceInvocationError, TypedRequestHandler};
+///
+/// let handler: Arc<dyn RequestHandler> = Arc::new(TypedRequestHandler::new(
+///     |request: StringValue, _attributes| async move {
+///         let length = UInt32Value {
+///             value: request.value.len() as u32,
+///             ..Default::default()
+///         };
+///         Ok::<_, ServiceInvocationError>(Some(length))
+///     },
+/// ));
+/// ```
+pub struct TypedRequestHandler<Req, Resp, F> {
+    handler: F,
+    _message_types: PhantomData<fn(Req) -> Resp>,
+}
+
+impl<Req, Resp, F, Fut> TypedRequestHandler<Req, Resp, F>
+where
+    Req: MessageFull + Default,
+    Resp: MessageFull,
+    F: Fn(Req, UAttributes) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Option<Resp>, ServiceInvocationError>> + Send,
+{
+    /// Creates a new handler for a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The function to invoke with the extracted request message and the
+    ///               request message's attributes.
+    pub fn new(handler: F) -> Self {
+        TypedRequestHandler {
+            handler,
+            _message_types: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<Req, Resp, F, Fut> RequestHandler for TypedRequestHandler<Req, Resp, F>
+where
+    Req: MessageFull + Default,
+    Resp: MessageFull,
+    F: Fn(Req, UAttributes) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Option<Resp>, ServiceInvocationError>> + Send,
+{
+    async fn handle_request(
+        &self,
+        _resource_id: u16,
+        message_attributes: &UAttributes,
+        request_payload: Option<UPayload>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        let request = match request_payload {
+            Some(payload) => payload.extract_protobuf::<Req>().map_err(|e| {
+                debug!(
+                    payload_type = Req::descriptor().full_name(),
+                    "failed to extract request payload: {}", e
+                );
+                ServiceInvocationError::InvalidArgument(format!(
+                    "request payload is not a {}",
+                    Req::descriptor().full_name()
+                ))
+            })?,
+            None => Req::default(),
+        };
+        let Some(response) = (self.handler)(request, message_attributes.to_owned()).await? else {
+            return Ok(None);
+        };
+        UPayload::try_from_protobuf(response)
+            .map(Some)
+            .map_err(|e| ServiceInvocationError::Internal(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protobuf::well_known_types::wrappers::{StringValue, UInt32Value};
+
+    use super::*;
+
+    fn length_handler() -> impl RequestHandler {
+        TypedRequestHandler::new(|request: StringValue, _attributes| async move {
+            Ok(Some(UInt32Value {
+                value: request.value.len() as u32,
+                ..Default::default()
+            }))
+        })
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_packs_response_message() {
+        let request = StringValue {
+            value: "hello".to_string(),
+            ..Default::default()
+        };
+
+        let response = length_handler()
+            .handle_request(
+                0x0001,
+                &UAttributes::default(),
+                Some(UPayload::try_from_protobuf(request).unwrap()),
+            )
+            .await
+            .expect("request should have been processed successfully")
+            .expect("response should contain payload");
+
+        assert_eq!(response.extract_protobuf::<UInt32Value>().unwrap().value, 5);
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_rejects_unexpected_request_payload() {
+        let request = UInt32Value {
+            value: 5,
+            ..Default::default()
+        };
+
+        let result = length_handler()
+            .handle_request(
+                0x0001,
+                &UAttributes::default(),
+                Some(UPayload::try_from_protobuf(request).unwrap()),
+            )
+            .await;
+
+        assert!(result.is_err_and(|e| matches!(e, ServiceInvocationError::InvalidArgument(_))));
+    }
+}
@@ -23,13 +23,17 @@ use crate::{
     UStatus, UUri,
 };
 
-use super::{CallOptions, RpcClient};
+use super::{CallOptions, PriorityTimeouts, RpcClient, RpcClientExt};
+
+// the time-to-live of RPC Requests, if no table has been set
+const DEFAULT_TTL: u32 = 5_000;
 
 /// A [`UDiscovery`] client implementation for invoking operations of a local uDiscovery service.
 ///
 /// The client requires an [`RpcClient`] for performing the remote procedure calls.
 pub struct RpcClientUDiscovery {
     rpc_client: Arc<dyn RpcClient>,
+    timeouts: Option<PriorityTimeouts>,
 }
 
 impl RpcClientUDiscovery {
@@ -39,11 +43,27 @@ impl RpcClientUDiscovery {
     ///
     /// * `rpc_client` - The client to use for performing the remote procedure calls on the service.
     pub fn new(rpc_client: Arc<dyn RpcClient>) -> Self {
-        RpcClientUDiscovery { rpc_client }
+        RpcClientUDiscovery {
+            rpc_client,
+            timeouts: None,
+        }
+    }
+
+    /// Sets the table to derive the time-to-live of RPC Requests from.
+    ///
+    /// By default, RPC Requests to the uDiscovery service expire after 5 seconds.
+    pub fn with_timeouts(mut self, timeouts: PriorityTimeouts) -> Self {
+        self.timeouts = Some(timeouts);
+        self
     }
 
-    fn default_call_options() -> CallOptions {
-        CallOptions::for_rpc_request(5_000, None, None, None)
+    fn call_options(&self) -> CallOptions {
+        match self.timeouts.as_ref() {
+            Some(timeouts) => {
+                CallOptions::for_rpc_request_with_timeouts(timeouts, None, None, None)
+            }
+            None => CallOptions::for_rpc_request(DEFAULT_TTL, None, None, None),
+        }
     }
 }
 
@@ -60,10 +80,10 @@ impl UDiscovery for RpcClientUDiscovery {
             ..Default::default()
         };
         self.rpc_client
-            .invoke_proto_method::<_, FindServicesResponse>(
+            .call::<_, FindServicesResponse>(
                 udiscovery_uri(RESOURCE_ID_FIND_SERVICES),
-                Self::default_call_options(),
                 request_message,
+                self.call_options(),
             )
             .await
             .map(|response_message| {
@@ -86,10 +106,10 @@ impl UDiscovery for RpcClientUDiscovery {
             ..Default::default()
         };
         self.rpc_client
-            .invoke_proto_method::<_, GetServiceTopicsResponse>(
+            .call::<_, GetServiceTopicsResponse>(
                 udiscovery_uri(RESOURCE_ID_GET_SERVICE_TOPICS),
-                Self::default_call_options(),
                 request_message,
+                self.call_options(),
             )
             .await
             .map(|response_message| response_message.topics.to_owned())
@@ -27,13 +27,17 @@ use crate::{
     UStatus,
 };
 
-use super::{CallOptions, RpcClient};
+use super::{CallOptions, PriorityTimeouts, RpcClient, RpcClientExt};
+
+// the time-to-live of RPC Requests, if no table has been set
+const DEFAULT_TTL: u32 = 5_000;
 
 /// A [`USubscription`] client implementation for invoking operations of a local USubscription service.
 ///
 /// The client requires an [`RpcClient`] for performing the remote procedure calls.
 pub struct RpcClientUSubscription {
     rpc_client: Arc<dyn RpcClient>,
+    timeouts: Option<PriorityTimeouts>,
 }
 
 impl RpcClientUSubscription {
@@ -43,11 +47,27 @@ impl RpcClientUSubscription {
     ///
     /// * `rpc_client` - The client to use for performing the remote procedure calls on the USubscription service.
     pub fn new(rpc_client: Arc<dyn RpcClient>) -> Self {
-        RpcClientUSubscription { rpc_client }
+        RpcClientUSubscription {
+            rpc_client,
+            timeouts: None,
+        }
+    }
+
+    /// Sets the table to derive the time-to-live of RPC Requests from.
+    ///
+    /// By default, RPC Requests to the USubscription service expire after 5 seconds.
+    pub fn with_timeouts(mut self, timeouts: PriorityTimeouts) -> Self {
+        self.timeouts = Some(timeouts);
+        self
     }
 
-    fn default_call_options() -> CallOptions {
-        CallOptions::for_rpc_request(5_000, None, None, None)
+    fn call_options(&self) -> CallOptions {
+        match self.timeouts.as_ref() {
+            Some(timeouts) => {
+                CallOptions::for_rpc_request_with_timeouts(timeouts, None, None, None)
+            }
+            None => CallOptions::for_rpc_request(DEFAULT_TTL, None, None, None),
+        }
     }
 }
 
@@ -58,10 +78,10 @@ impl USubscription for RpcClientUSubscription {
         subscription_request: SubscriptionRequest,
     ) -> Result<SubscriptionResponse, UStatus> {
         self.rpc_client
-            .invoke_proto_method::<_, SubscriptionResponse>(
+            .call::<_, SubscriptionResponse>(
                 usubscription_uri(RESOURCE_ID_SUBSCRIBE),
-                Self::default_call_options(),
                 subscription_request,
+                self.call_options(),
             )
             .await
             .map_err(UStatus::from)
@@ -69,10 +89,10 @@ impl USubscription for RpcClientUSubscription {
 
     async fn unsubscribe(&self, unsubscribe_request: UnsubscribeRequest) -> Result<(), UStatus> {
         self.rpc_client
-            .invoke_proto_method::<_, UnsubscribeResponse>(
+            .call::<_, UnsubscribeResponse>(
                 usubscription_uri(RESOURCE_ID_UNSUBSCRIBE),
-                Self::default_call_options(),
                 unsubscribe_request,
+                self.call_options(),
             )
             .await
             .map(|_response| ())
@@ -84,10 +104,10 @@ impl USubscription for RpcClientUSubscription {
         fetch_subscriptions_request: FetchSubscriptionsRequest,
     ) -> Result<FetchSubscriptionsResponse, UStatus> {
         self.rpc_client
-            .invoke_proto_method::<_, FetchSubscriptionsResponse>(
+            .call::<_, FetchSubscriptionsResponse>(
                 usubscription_uri(RESOURCE_ID_FETCH_SUBSCRIPTIONS),
-                Self::default_call_options(),
                 fetch_subscriptions_request,
+                self.call_options(),
             )
             .await
             .map_err(UStatus::from)
@@ -98,10 +118,10 @@ impl USubscription for RpcClientUSubscription {
         notifications_register_request: NotificationsRequest,
     ) -> Result<(), UStatus> {
         self.rpc_client
-            .invoke_proto_method::<_, NotificationsResponse>(
+            .call::<_, NotificationsResponse>(
                 usubscription_uri(RESOURCE_ID_REGISTER_FOR_NOTIFICATIONS),
-                Self::default_call_options(),
                 notifications_register_request,
+                self.call_options(),
             )
             .await
             .map(|_response| ())
@@ -113,10 +133,10 @@ impl USubscription for RpcClientUSubscription {
         notifications_unregister_request: NotificationsRequest,
     ) -> Result<(), UStatus> {
         self.rpc_client
-            .invoke_proto_method::<_, NotificationsResponse>(
+            .call::<_, NotificationsResponse>(
                 usubscription_uri(RESOURCE_ID_UNREGISTER_FOR_NOTIFICATIONS),
-                Self::default_call_options(),
                 notifications_unregister_request,
+                self.call_options(),
             )
             .await
             .map(|_response| ())
@@ -128,10 +148,10 @@ impl USubscription for RpcClientUSubscription {
         fetch_subscribers_request: FetchSubscribersRequest,
     ) -> Result<FetchSubscribersResponse, UStatus> {
         self.rpc_client
-            .invoke_proto_method::<_, FetchSubscribersResponse>(
+            .call::<_, FetchSubscribersResponse>(
                 usubscription_uri(RESOURCE_ID_FETCH_SUBSCRIBERS),
-                Self::default_call_options(),
                 fetch_subscribers_request,
+                self.call_options(),
             )
             .await
             .map_err(UStatus::from)
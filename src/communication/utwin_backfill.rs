@@ -0,0 +1,225 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use tracing::debug;
+
+use crate::{
+    core::utwin::{
+        utwin_uri, GetLastMessagesRequest, GetLastMessagesResponse, RESOURCE_ID_GET_LAST_MESSAGES,
+    },
+    up_core_api::uri::UUriBatch,
+    UCode, UListener, UStatus, UUri,
+};
+
+use super::{CallOptions, RpcClient, RpcClientExt, ServiceInvocationError};
+
+/// The outcome of backfilling a service's state from the messages cached by uTwin.
+#[derive(Clone, Debug, Default)]
+pub struct BackfillReport {
+    /// The number of messages that have been passed to the listener.
+    pub replayed: usize,
+    /// The topics that uTwin could not provide a message for, along with the reason.
+    pub missing: Vec<(UUri, UStatus)>,
+}
+
+/// Reconstructs the state of a service from the last messages that have been published to a set of topics.
+///
+/// Services that mirror the state of other uEntities usually lose that state when being restarted.
+/// This function retrieves the last message published to each of the given topics from the
+/// local uTwin service and passes these messages to the listener that the service uses for
+/// processing the topics' messages during regular operation.
+///
+/// The function should be invoked during startup, _before_ the service registers its own endpoints,
+/// so that clients do not observe the service's (empty) state before it has been reconstructed.
+/// The service should also subscribe to the topics before invoking this function, in order to not
+/// miss any messages that are being published in the meantime. The listener should therefore be
+/// prepared to receive (outdated) messages that it has already processed.
+///
+/// # Arguments
+///
+/// * `rpc_client` - The client to use for invoking the uTwin service.
+/// * `topics` - The topics to retrieve the last messages for.
+/// * `listener` - The listener to pass the retrieved messages to.
+/// * `call_options` - The options to use for invoking the uTwin service.
+///
+/// # Returns
+///
+/// A report indicating the number of messages that have been passed to the listener and
+/// the topics for which no message could be retrieved.
+///
+/// # Errors
+///
+/// Returns an error if the uTwin service could not be invoked successfully.
+pub async fn backfill_from_utwin(
+    rpc_client: &dyn RpcClient,
+    topics: &[UUri],
+    listener: &dyn UListener,
+    call_options: CallOptions,
+) -> Result<BackfillReport, ServiceInvocationError> {
+    let mut report = BackfillReport::default();
+    if topics.is_empty() {
+        return Ok(report);
+    }
+
+    let request = GetLastMessagesRequest {
+        topics: Some(UUriBatch {
+            uris: topics.to_vec(),
+            ..Default::default()
+        })
+        .into(),
+        ..Default::default()
+    };
+    let response: GetLastMessagesResponse = rpc_client
+        .call(
+            utwin_uri(RESOURCE_ID_GET_LAST_MESSAGES),
+            request,
+            call_options,
+        )
+        .await
+        .map_err(|e| {
+            debug!("failed to retrieve last messages from uTwin: {}", e);
+            e
+        })?;
+
+    let mut unanswered = topics.to_vec();
+    for message_response in response.responses {
+        let topic = message_response.topic.unwrap_or_default();
+        unanswered.retain(|t| t != &topic);
+        let status = message_response.status.unwrap_or_default();
+        match message_response.message.into_option() {
+            Some(message) if status.is_success() => {
+                listener.on_receive(message).await;
+                report.replayed += 1;
+            }
+            Some(_) | None => {
+                debug!(topic = %topic, "uTwin has no last message for topic: {}", status);
+                report.missing.push((topic, status));
+            }
+        }
+    }
+    report.missing.extend(unanswered.into_iter().map(|topic| {
+        (
+            topic,
+            UStatus::fail_with_code(UCode::NOT_FOUND, "topic not included in uTwin's response"),
+        )
+    }));
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        communication::{MockRpcClient, UPayload},
+        core::utwin::MessageResponse,
+        utransport::MockUListener,
+        UMessageBuilder,
+    };
+
+    #[tokio::test]
+    async fn test_backfill_passes_last_messages_to_listener() {
+        let present_topic = UUri::try_from_parts("other", 0x4210, 0x01, 0x8001).unwrap();
+        let absent_topic = UUri::try_from_parts("other", 0x4210, 0x01, 0x8002).unwrap();
+        let ignored_topic = UUri::try_from_parts("other", 0x4210, 0x01, 0x8003).unwrap();
+
+        // GIVEN a uTwin service which has cached a message for one of the requested topics only
+        let last_message = UMessageBuilder::publish(present_topic.clone())
+            .build()
+            .unwrap();
+        let response = GetLastMessagesResponse {
+            responses: vec![
+                MessageResponse {
+                    topic: Some(present_topic.clone()).into(),
+                    status: Some(UStatus::ok()).into(),
+                    message: Some(last_message.clone()).into(),
+                    ..Default::default()
+                },
+                MessageResponse {
+                    topic: Some(absent_topic.clone()).into(),
+                    status: Some(UStatus::fail_with_code(UCode::NOT_FOUND, "no message")).into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let requested_topics = vec![
+            present_topic.clone(),
+            absent_topic.clone(),
+            ignored_topic.clone(),
+        ];
+        let expected_topics = requested_topics.clone();
+        let mut rpc_client = MockRpcClient::new();
+        rpc_client
+            .expect_invoke_method()
+            .once()
+            .withf(move |method, _options, payload| {
+                let request = payload
+                    .to_owned()
+                    .unwrap()
+                    .extract_protobuf::<GetLastMessagesRequest>()
+                    .unwrap();
+                method == &utwin_uri(RESOURCE_ID_GET_LAST_MESSAGES)
+                    && request.topics.uris == expected_topics
+            })
+            .returning(move |_method, _options, _payload| {
+                Ok(Some(UPayload::try_from_protobuf(response.clone()).unwrap()))
+            });
+        let mut listener = MockUListener::new();
+        listener
+            .expect_on_receive()
+            .once()
+            .withf(move |msg| msg == &last_message)
+            .return_const(());
+
+        // WHEN backfilling the state for all topics
+        let report = backfill_from_utwin(
+            &rpc_client,
+            &requested_topics,
+            &listener,
+            CallOptions::for_rpc_request(1_000, None, None, None),
+        )
+        .await
+        .expect("backfill should have succeeded");
+
+        // THEN the cached message has been passed to the listener
+        assert_eq!(report.replayed, 1);
+        // and the other topics are reported as missing
+        assert_eq!(report.missing.len(), 2);
+        assert!(report
+            .missing
+            .iter()
+            .all(|(topic, status)| status.get_code() == UCode::NOT_FOUND
+                && (topic == &absent_topic || topic == &ignored_topic)));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_fails_if_utwin_is_unavailable() {
+        let mut rpc_client = MockRpcClient::new();
+        rpc_client.expect_invoke_method().once().return_const(Err(
+            ServiceInvocationError::Unavailable("uTwin not running".to_string()),
+        ));
+        let mut listener = MockUListener::new();
+        listener.expect_on_receive().never();
+        let topic = UUri::try_from_parts("other", 0x4210, 0x01, 0x8001).unwrap();
+
+        let result = backfill_from_utwin(
+            &rpc_client,
+            &[topic],
+            &listener,
+            CallOptions::for_rpc_request(1_000, None, None, None),
+        )
+        .await;
+
+        assert!(result.is_err_and(|e| matches!(e, ServiceInvocationError::Unavailable(_))));
+    }
+}
@@ -0,0 +1,389 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, info};
+
+use crate::{core::udiscovery::UDiscovery, UListener, UStatus, UUri};
+
+use super::Subscriber;
+
+/// The changes applied to the set of topics covered by a [`WildcardSubscription`]
+/// during a [refresh](`WildcardSubscription::refresh`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TopicSetChanges {
+    /// The topics that the listener has been subscribed to, ordered by topic.
+    pub added: Vec<UUri>,
+    /// The topics that the listener has been unsubscribed from, ordered by topic.
+    pub removed: Vec<UUri>,
+}
+
+/// Subscribes a listener to all topics matching a (wildcard) topic pattern.
+///
+/// uSubscription does not support subscribing to topic patterns. The wildcard subscription
+/// therefore uses the uDiscovery service to look up the concrete topics that match the pattern
+/// and subscribes the listener to each of them. Topics that have been added to or removed from
+/// uDiscovery, e.g. because services have appeared or disappeared, are taken into account on
+/// each [refresh](`Self::refresh`).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use up_rust::{UListener, UUri};
+/// use up_rust::communication::{Subscriber, WildcardSubscription};
+/// use up_rust::core::udiscovery::UDiscovery;
+///
+/// async fn monitor_doors(
+///     udiscovery: Arc<dyn UDiscovery>,
+///     subscriber: Arc<dyn Subscriber>,
+///     listener: Arc<dyn UListener>,
+/// ) {
+///     // the door status topics of any authority
+///     let pattern = UUri::try_from("//*/D4A/1/8001").unwrap();
+///     let subscription = WildcardSubscription::new(pattern, udiscovery, subscriber, listener);
+///     subscription.keep_updated(Duration::from_secs(30)).await;
+/// }
+/// ```
+pub struct WildcardSubscription {
+    topic_pattern: UUri,
+    udiscovery: Arc<dyn UDiscovery>,
+    subscriber: Arc<dyn Subscriber>,
+    listener: Arc<dyn UListener>,
+    recursive: bool,
+    topics: tokio::sync::Mutex<HashSet<UUri>>,
+}
+
+impl WildcardSubscription {
+    /// Creates a new subscription.
+    ///
+    /// The listener is not subscribed to any topics until the subscription is being
+    /// [refreshed](`Self::refresh`).
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_pattern` - The pattern that topics need to match.
+    /// * `udiscovery` - The client to use for looking up topics.
+    /// * `subscriber` - The client to use for subscribing the listener to topics.
+    /// * `listener` - The listener to subscribe.
+    pub fn new(
+        topic_pattern: UUri,
+        udiscovery: Arc<dyn UDiscovery>,
+        subscriber: Arc<dyn Subscriber>,
+        listener: Arc<dyn UListener>,
+    ) -> Self {
+        WildcardSubscription {
+            topic_pattern,
+            udiscovery,
+            subscriber,
+            listener,
+            recursive: false,
+            topics: tokio::sync::Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Sets whether the local uDiscovery service should extend the lookup of topics
+    /// to its parent uDiscovery node.
+    ///
+    /// The lookup is not extended by default.
+    pub fn with_recursive_lookup(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Gets the topics that the listener is currently subscribed to, ordered by topic.
+    pub async fn topics(&self) -> Vec<UUri> {
+        let mut topics: Vec<UUri> = self.topics.lock().await.iter().cloned().collect();
+        topics.sort_by_key(|topic| topic.to_uri(false));
+        topics
+    }
+
+    /// Looks up the topics matching the pattern and updates the listener's subscriptions accordingly.
+    ///
+    /// Subscribing to or unsubscribing from individual topics may fail, e.g. because the
+    /// USubscription service is temporarily unavailable. Such failures are logged and the
+    /// corresponding topics are retried during the next refresh.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the topics could not be looked up. The listener's subscriptions
+    /// remain unchanged in this case.
+    // UUri's cached size is the only interior mutability and does not affect hashing
+    #[allow(clippy::mutable_key_type)]
+    pub async fn refresh(&self) -> Result<TopicSetChanges, UStatus> {
+        let discovered_topics: HashSet<UUri> = self
+            .udiscovery
+            .get_service_topics(self.topic_pattern.clone(), self.recursive)
+            .await?
+            .into_iter()
+            .filter_map(|info| info.topic.into_option())
+            .filter(|topic| {
+                topic.verify_event().is_ok()
+                    && topic.verify_no_wildcards().is_ok()
+                    && self.topic_pattern.matches(topic)
+            })
+            .collect();
+
+        let mut topics = self.topics.lock().await;
+        let mut changes = TopicSetChanges::default();
+        for topic in discovered_topics.difference(&topics) {
+            match self
+                .subscriber
+                .subscribe(topic, self.listener.clone(), None)
+                .await
+            {
+                Ok(()) => changes.added.push(topic.to_owned()),
+                Err(e) => info!(topic = %topic, "failed to subscribe to topic: {}", e),
+            }
+        }
+        for topic in topics.difference(&discovered_topics) {
+            match self
+                .subscriber
+                .unsubscribe(topic, self.listener.clone())
+                .await
+            {
+                Ok(()) => changes.removed.push(topic.to_owned()),
+                Err(e) => info!(topic = %topic, "failed to unsubscribe from topic: {}", e),
+            }
+        }
+        changes.added.iter().for_each(|topic| {
+            topics.insert(topic.to_owned());
+        });
+        changes.removed.iter().for_each(|topic| {
+            topics.remove(topic);
+        });
+        changes.added.sort_by_key(|topic| topic.to_uri(false));
+        changes.removed.sort_by_key(|topic| topic.to_uri(false));
+        debug!(
+            pattern = %self.topic_pattern,
+            added = changes.added.len(),
+            removed = changes.removed.len(),
+            "refreshed wildcard subscription"
+        );
+        Ok(changes)
+    }
+
+    /// Periodically [refreshes](`Self::refresh`) this subscription.
+    ///
+    /// The returned future never completes. It can be spawned on a runtime and aborted
+    /// when the subscription is no longer needed. Errors occurring during a refresh are logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time to wait between refreshes.
+    pub async fn keep_updated(&self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.refresh().await {
+                info!(pattern = %self.topic_pattern, "failed to look up topics: {}", e);
+            }
+        }
+    }
+
+    /// Unsubscribes the listener from all topics.
+    ///
+    /// # Errors
+    ///
+    /// Returns the topics that the listener could not be unsubscribed from along with the
+    /// corresponding error. The listener remains subscribed to these topics.
+    pub async fn cancel(&self) -> Result<(), Vec<(UUri, super::RegistrationError)>> {
+        let mut topics = self.topics.lock().await;
+        let mut errors = vec![];
+        for topic in topics.clone() {
+            match self
+                .subscriber
+                .unsubscribe(&topic, self.listener.clone())
+                .await
+            {
+                Ok(()) => {
+                    topics.remove(&topic);
+                }
+                Err(e) => errors.push((topic, e)),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::{
+        communication::{RegistrationError, SubscriptionChangeHandler},
+        core::udiscovery::{MockUDiscovery, ServiceTopicInfo},
+        utransport::MockUListener,
+        UCode,
+    };
+
+    #[derive(Default)]
+    struct RecordingSubscriber {
+        subscribed_topics: Mutex<Vec<UUri>>,
+        failing_topic: Option<UUri>,
+    }
+
+    #[async_trait]
+    impl Subscriber for RecordingSubscriber {
+        async fn subscribe(
+            &self,
+            topic_filter: &UUri,
+            _handler: Arc<dyn UListener>,
+            _subscription_change_handler: Option<Arc<dyn SubscriptionChangeHandler>>,
+        ) -> Result<(), RegistrationError> {
+            if self.failing_topic.as_ref() == Some(topic_filter) {
+                return Err(RegistrationError::Unknown(UStatus::fail_with_code(
+                    UCode::UNAVAILABLE,
+                    "USubscription service not available",
+                )));
+            }
+            self.subscribed_topics
+                .lock()
+                .unwrap()
+                .push(topic_filter.to_owned());
+            Ok(())
+        }
+
+        async fn unsubscribe(
+            &self,
+            topic: &UUri,
+            _handler: Arc<dyn UListener>,
+        ) -> Result<(), RegistrationError> {
+            self.subscribed_topics
+                .lock()
+                .unwrap()
+                .retain(|subscribed_topic| subscribed_topic != topic);
+            Ok(())
+        }
+    }
+
+    fn topic_info(uri: &str) -> ServiceTopicInfo {
+        ServiceTopicInfo {
+            topic: Some(UUri::try_from(uri).unwrap()).into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_updates_subscriptions() {
+        // GIVEN a uDiscovery service which knows about a door topic of two vehicles first
+        // and then only about the door topic of one of the vehicles
+        let mut udiscovery = MockUDiscovery::new();
+        let mut lookups = 0;
+        udiscovery
+            .expect_get_service_topics()
+            .times(2)
+            .returning(move |_pattern, _recursive| {
+                lookups += 1;
+                if lookups == 1 {
+                    Ok(vec![
+                        topic_info("//vehicle1/D4A/1/8001"),
+                        topic_info("//vehicle2/D4A/1/8001"),
+                        // not matching the pattern
+                        topic_info("//vehicle2/D4A/1/8002"),
+                    ])
+                } else {
+                    Ok(vec![topic_info("//vehicle2/D4A/1/8001")])
+                }
+            });
+        let subscriber = Arc::new(RecordingSubscriber::default());
+        let subscription = WildcardSubscription::new(
+            UUri::try_from("//*/D4A/1/8001").unwrap(),
+            Arc::new(udiscovery),
+            subscriber.clone(),
+            Arc::new(MockUListener::new()),
+        );
+
+        // WHEN refreshing the subscription
+        let changes = subscription.refresh().await.unwrap();
+        // THEN the listener is subscribed to the matching topics
+        assert_eq!(
+            changes.added,
+            vec![
+                UUri::try_from("//vehicle1/D4A/1/8001").unwrap(),
+                UUri::try_from("//vehicle2/D4A/1/8001").unwrap()
+            ]
+        );
+        assert!(changes.removed.is_empty());
+        assert_eq!(subscriber.subscribed_topics.lock().unwrap().len(), 2);
+
+        // WHEN refreshing the subscription after one of the vehicles has disappeared
+        let changes = subscription.refresh().await.unwrap();
+        // THEN the listener is unsubscribed from the vehicle's topic
+        assert!(changes.added.is_empty());
+        assert_eq!(
+            changes.removed,
+            vec![UUri::try_from("//vehicle1/D4A/1/8001").unwrap()]
+        );
+        assert_eq!(
+            subscription.topics().await,
+            vec![UUri::try_from("//vehicle2/D4A/1/8001").unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_retries_failed_subscriptions() {
+        // GIVEN a uDiscovery service which knows about two topics
+        let mut udiscovery = MockUDiscovery::new();
+        udiscovery
+            .expect_get_service_topics()
+            .returning(|_pattern, _recursive| {
+                Ok(vec![
+                    topic_info("//vehicle1/D4A/1/8001"),
+                    topic_info("//vehicle2/D4A/1/8001"),
+                ])
+            });
+        // and a subscriber which fails to subscribe to one of them
+        let subscriber = Arc::new(RecordingSubscriber {
+            failing_topic: Some(UUri::try_from("//vehicle2/D4A/1/8001").unwrap()),
+            ..Default::default()
+        });
+        let subscription = WildcardSubscription::new(
+            UUri::try_from("//*/D4A/1/8001").unwrap(),
+            Arc::new(udiscovery),
+            subscriber.clone(),
+            Arc::new(MockUListener::new()),
+        );
+
+        // WHEN refreshing the subscription
+        let changes = subscription.refresh().await.unwrap();
+
+        // THEN only the topic that could be subscribed to is added
+        assert_eq!(
+            changes.added,
+            vec![UUri::try_from("//vehicle1/D4A/1/8001").unwrap()]
+        );
+        // and the other topic is retried during the next refresh
+        let changes = subscription.refresh().await.unwrap();
+        assert!(changes.removed.is_empty());
+        assert!(changes.added.is_empty());
+
+        // WHEN canceling the subscription
+        assert!(subscription.cancel().await.is_ok());
+        // THEN the listener is unsubscribed from all topics
+        assert!(subscription.topics().await.is_empty());
+        assert!(subscriber.subscribed_topics.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides means to check if a [`UMessage`] can be processed by uEntities that implement an older
+version of the uProtocol specification.
+
+Gateways that bridge between uEntities implementing different versions of the specification
+can use the [`check_compatibility`] function to determine if a received message uses any attributes
+or attribute values that have been introduced in a version newer than the one implemented by
+the message's recipient. Based on the outcome, the gateway can then deliberately decide to
+reject the message or to remove the offending information before forwarding the message.
+
+Changes of the wire format itself can be detected by comparing the encoding of a set of
+[`WireFixture`]s against binary snapshots that have been created with an earlier version of this library.
+*/
+
+mod wire;
+pub use wire::{WireFixture, WireMismatch};
+
+use std::fmt::Display;
+
+use crate::{UMessage, UPayloadFormat};
+
+/// The versions of the uProtocol specification that messages can be checked against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpecVersion {
+    V1_5_7,
+    V1_5_8,
+    V1_6_0,
+}
+
+impl Display for SpecVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecVersion::V1_5_7 => f.write_str("1.5.7"),
+            SpecVersion::V1_5_8 => f.write_str("1.5.8"),
+            SpecVersion::V1_6_0 => f.write_str("1.6.0"),
+        }
+    }
+}
+
+/// An attribute or attribute value that is not supported by a particular version of the specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompatibilityIssue {
+    /// The name of the message attribute.
+    pub attribute: &'static str,
+    /// The version of the specification in which the attribute (value) has been introduced,
+    /// or `None` if the value is unknown to all versions supported by this library.
+    pub introduced_in: Option<SpecVersion>,
+}
+
+impl Display for CompatibilityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.introduced_in {
+            Some(version) => write!(
+                f,
+                "attribute [{}] requires spec version {}",
+                self.attribute, version
+            ),
+            None => write!(f, "attribute [{}] has unknown value", self.attribute),
+        }
+    }
+}
+
+/// The outcome of checking a message's compatibility with a particular version of the specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompatibilityReport {
+    target: SpecVersion,
+    issues: Vec<CompatibilityIssue>,
+}
+
+impl CompatibilityReport {
+    /// Gets the version of the specification that the message has been checked against.
+    pub fn target(&self) -> SpecVersion {
+        self.target
+    }
+
+    /// Checks if the message is compatible with the target version.
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Gets the attributes that are not supported by the target version.
+    pub fn issues(&self) -> &[CompatibilityIssue] {
+        &self.issues
+    }
+}
+
+/// Checks if a message can be processed by a uEntity implementing a particular version of the specification.
+///
+/// The following attributes (values) are considered:
+///
+/// | Attribute        | Value                          | Introduced in |
+/// | ---------------- | ------------------------------ | ------------- |
+/// | `traceparent`    | any                            | 1.5.8         |
+/// | `payload_format` | any other than `UNSPECIFIED`   | 1.5.8         |
+/// | `payload_format` | `SHM`                          | 1.6.0         |
+///
+/// Additionally, enum values that are unknown to this library are reported for
+/// the `type`, `priority`, `commstatus` and `payload_format` attributes.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{compat::{check_compatibility, SpecVersion}, UMessageBuilder, UPayloadFormat, UUri};
+///
+/// let topic = UUri::try_from("//my-vehicle/D45/23/A001").unwrap();
+/// let message = UMessageBuilder::publish(topic)
+///     .with_traceparent("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01")
+///     .build_with_payload("data", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+///     .unwrap();
+///
+/// assert!(check_compatibility(&message, SpecVersion::V1_6_0).is_compatible());
+/// let report = check_compatibility(&message, SpecVersion::V1_5_7);
+/// assert!(!report.is_compatible());
+/// assert_eq!(report.issues().len(), 2);
+/// ```
+pub fn check_compatibility(message: &UMessage, target: SpecVersion) -> CompatibilityReport {
+    let mut issues = vec![];
+    let mut check = |attribute: &'static str, introduced_in: Option<SpecVersion>| {
+        if introduced_in.map_or(true, |version| version > target) {
+            issues.push(CompatibilityIssue {
+                attribute,
+                introduced_in,
+            });
+        }
+    };
+
+    if let Some(attributes) = message.attributes.as_ref() {
+        if attributes.type_.enum_value().is_err() {
+            check("type", None);
+        }
+        if attributes.priority.enum_value().is_err() {
+            check("priority", None);
+        }
+        if attributes
+            .commstatus
+            .is_some_and(|status| status.enum_value().is_err())
+        {
+            check("commstatus", None);
+        }
+        if attributes.traceparent.is_some() {
+            check("traceparent", Some(SpecVersion::V1_5_8));
+        }
+        match attributes.payload_format.enum_value() {
+            Ok(UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED) => {}
+            Ok(UPayloadFormat::UPAYLOAD_FORMAT_SHM) => {
+                check("payload_format", Some(SpecVersion::V1_6_0))
+            }
+            Ok(_) => check("payload_format", Some(SpecVersion::V1_5_8)),
+            Err(_) => check("payload_format", None),
+        }
+    }
+
+    CompatibilityReport { target, issues }
+}
+
+#[cfg(test)]
+mod tests {
+    use protobuf::EnumOrUnknown;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{UMessageBuilder, UUri};
+
+    fn publish_message(payload_format: UPayloadFormat) -> UMessage {
+        let topic = UUri::try_from_parts("my-vehicle", 0xD45, 0x23, 0xA001).unwrap();
+        UMessageBuilder::publish(topic)
+            .build_with_payload("data", payload_format)
+            .unwrap()
+    }
+
+    #[test_case(UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED, SpecVersion::V1_5_7, true; "unspecified format with 1.5.7")]
+    #[test_case(UPayloadFormat::UPAYLOAD_FORMAT_JSON, SpecVersion::V1_5_7, false; "JSON format with 1.5.7")]
+    #[test_case(UPayloadFormat::UPAYLOAD_FORMAT_JSON, SpecVersion::V1_5_8, true; "JSON format with 1.5.8")]
+    #[test_case(UPayloadFormat::UPAYLOAD_FORMAT_SHM, SpecVersion::V1_5_8, false; "SHM format with 1.5.8")]
+    #[test_case(UPayloadFormat::UPAYLOAD_FORMAT_SHM, SpecVersion::V1_6_0, true; "SHM format with 1.6.0")]
+    fn test_check_compatibility_of_payload_format(
+        payload_format: UPayloadFormat,
+        target: SpecVersion,
+        expected_compatible: bool,
+    ) {
+        let report = check_compatibility(&publish_message(payload_format), target);
+        assert_eq!(report.target(), target);
+        assert_eq!(report.is_compatible(), expected_compatible);
+    }
+
+    #[test]
+    fn test_check_compatibility_reports_unknown_enum_values() {
+        let mut message = publish_message(UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED);
+        message.attributes.as_mut().unwrap().priority = EnumOrUnknown::from_i32(42);
+
+        let report = check_compatibility(&message, SpecVersion::V1_6_0);
+        assert_eq!(
+            report.issues(),
+            &[CompatibilityIssue {
+                attribute: "priority",
+                introduced_in: None
+            }]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::fmt::Display;
+
+use protobuf::Message;
+
+use crate::{UCode, UMessage, UMessageBuilder, UPayloadFormat, UPriority, UUri, UUID};
+
+/// A representative uProtocol object with a well-known wire format.
+///
+/// The fixtures cover the URI format and all message types, including some of the non-standard
+/// attributes supported by this library. Their protobuf encoding is compared against binary snapshots
+/// in order to detect changes of the wire format, e.g. due to updated code generators or changed field
+/// numbers, which would break communication with uEntities built from an earlier version of this library.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::compat::WireFixture;
+///
+/// // snapshots are usually read from files that have been created using an earlier version
+/// let snapshot = WireFixture::Uri.encode();
+/// assert!(WireFixture::Uri.verify(&snapshot).is_ok());
+/// assert!(WireFixture::Uri.verify(&snapshot[1..]).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WireFixture {
+    /// A URI with all fields set.
+    Uri,
+    /// A Publish message carrying an event time.
+    PublishMessage,
+    /// A Notification message.
+    NotificationMessage,
+    /// An RPC Request message carrying an idempotency key.
+    RequestMessage,
+    /// An RPC Response message carrying an error status and a chunk index.
+    ResponseMessage,
+}
+
+fn fixture_message_id() -> UUID {
+    UUID {
+        msb: 0x018e_8c8a_1b00_7123,
+        lsb: 0x8000_0000_0000_0001,
+        ..Default::default()
+    }
+}
+
+fn fixture_request_id() -> UUID {
+    UUID {
+        msb: 0x018e_8c8a_1a00_7456,
+        lsb: 0x8000_0000_0000_0002,
+        ..Default::default()
+    }
+}
+
+fn uri(uri: &str) -> UUri {
+    UUri::try_from(uri).expect("fixture URI is valid")
+}
+
+impl WireFixture {
+    /// All fixtures.
+    pub const ALL: [WireFixture; 5] = [
+        WireFixture::Uri,
+        WireFixture::PublishMessage,
+        WireFixture::NotificationMessage,
+        WireFixture::RequestMessage,
+        WireFixture::ResponseMessage,
+    ];
+
+    /// Gets the fixture's name, which is suitable for use as a file name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WireFixture::Uri => "uri",
+            WireFixture::PublishMessage => "publish-message",
+            WireFixture::NotificationMessage => "notification-message",
+            WireFixture::RequestMessage => "request-message",
+            WireFixture::ResponseMessage => "response-message",
+        }
+    }
+
+    fn message(&self) -> Option<UMessage> {
+        let message = match self {
+            WireFixture::Uri => return None,
+            WireFixture::PublishMessage => {
+                let mut message = UMessageBuilder::publish(uri("//vehicle/A100/1/8001"))
+                    .with_message_id(fixture_message_id())
+                    .with_ttl(10_000)
+                    .build_with_payload("on", UPayloadFormat::UPAYLOAD_FORMAT_TEXT);
+                if let Ok(message) = message.as_mut() {
+                    message
+                        .attributes
+                        .mut_or_insert_default()
+                        .set_event_time(1_706_520_652_000);
+                }
+                message
+            }
+            WireFixture::NotificationMessage => UMessageBuilder::notification(
+                uri("//vehicle/A100/1/8001"),
+                uri("//vehicle/B100/1/0"),
+            )
+            .with_message_id(fixture_message_id())
+            .with_priority(UPriority::UPRIORITY_CS2)
+            .build(),
+            WireFixture::RequestMessage => {
+                UMessageBuilder::request(uri("//cloud/C200/2/7"), uri("//vehicle/A100/1/0"), 5_000)
+                    .with_message_id(fixture_request_id())
+                    .with_token("my-token")
+                    .with_idempotency_key("order-42")
+                    .build_with_payload(vec![0x08, 0x2a], UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF)
+            }
+            WireFixture::ResponseMessage => {
+                let mut message = UMessageBuilder::response(
+                    uri("//vehicle/A100/1/0"),
+                    fixture_request_id(),
+                    uri("//cloud/C200/2/7"),
+                )
+                .with_message_id(fixture_message_id())
+                .with_comm_status(UCode::NOT_FOUND)
+                .build();
+                if let Ok(message) = message.as_mut() {
+                    message
+                        .attributes
+                        .mut_or_insert_default()
+                        .set_chunk_index(3);
+                }
+                message
+            }
+        };
+        Some(message.expect("fixture message is valid"))
+    }
+
+    /// Encodes the fixture to its protobuf wire format.
+    pub fn encode(&self) -> Vec<u8> {
+        let bytes = match self.message() {
+            Some(message) => message.write_to_bytes(),
+            None => uri("//vehicle/1A100/2/8001").write_to_bytes(),
+        };
+        bytes.expect("fixture can be encoded")
+    }
+
+    /// Verifies that the fixture's current wire format matches a snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first difference between the snapshot and the current encoding.
+    pub fn verify(&self, snapshot: &[u8]) -> Result<(), WireMismatch> {
+        let actual = self.encode();
+        if actual == snapshot {
+            return Ok(());
+        }
+        let offset = actual
+            .iter()
+            .zip(snapshot)
+            .position(|(actual, expected)| actual != expected)
+            .unwrap_or_else(|| actual.len().min(snapshot.len()));
+        Err(WireMismatch {
+            fixture: *self,
+            offset,
+            expected_len: snapshot.len(),
+            actual: actual.to_owned(),
+        })
+    }
+}
+
+/// A difference between the current wire format of a [`WireFixture`] and its snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WireMismatch {
+    /// The fixture whose wire format has changed.
+    pub fixture: WireFixture,
+    /// The offset of the first byte that differs.
+    pub offset: usize,
+    /// The length of the snapshot.
+    pub expected_len: usize,
+    /// The current encoding of the fixture.
+    pub actual: Vec<u8>,
+}
+
+impl Display for WireMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "wire format of [{}] differs from snapshot at byte offset {} (expected {} bytes, got {} bytes): ",
+            self.fixture.name(),
+            self.offset,
+            self.expected_len,
+            self.actual.len()
+        )?;
+        self.actual
+            .iter()
+            .try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl std::error::Error for WireMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_are_encoded_deterministically() {
+        for fixture in WireFixture::ALL {
+            assert_eq!(fixture.encode(), fixture.encode(), "{}", fixture.name());
+        }
+    }
+
+    #[test]
+    fn test_verify_reports_first_difference() {
+        let mut snapshot = WireFixture::RequestMessage.encode();
+        snapshot[5] ^= 0xff;
+        let mismatch = WireFixture::RequestMessage.verify(&snapshot).unwrap_err();
+        assert_eq!(mismatch.offset, 5);
+        assert_eq!(mismatch.expected_len, mismatch.actual.len());
+        assert!(mismatch.to_string().starts_with(
+            "wire format of [request-message] differs from snapshot at byte offset 5"
+        ));
+
+        let mismatch = WireFixture::RequestMessage
+            .verify(&snapshot[..3])
+            .unwrap_err();
+        assert_eq!(mismatch.offset, 3);
+    }
+}
@@ -11,6 +11,9 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+mod pagination;
+pub use pagination::{Page, PageCursor, PageFuture, Paginator};
+
 #[cfg(feature = "usubscription")]
 pub mod usubscription;
 
@@ -0,0 +1,281 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::{UCode, UStatus};
+
+/// A position within a result set that is split into multiple pages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PageCursor {
+    /// The index of the first item to fetch.
+    Offset(u32),
+    /// An opaque token that has been handed out by the service along with the previous page.
+    Token(String),
+}
+
+/// A single page of items as returned by a core service operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page<T> {
+    items: Vec<T>,
+    next: Option<PageCursor>,
+}
+
+impl<T> Page<T> {
+    /// Creates a new page.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items contained in the page.
+    /// * `next` - The position of the next page, or `None` if this is the last page.
+    pub fn new(items: Vec<T>, next: Option<PageCursor>) -> Self {
+        Page { items, next }
+    }
+
+    /// Creates a new page of an operation using offset based pagination.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items contained in the page.
+    /// * `offset` - The offset that the page has been requested with.
+    /// * `has_more` - `true` if there are more items after the ones contained in this page.
+    pub fn with_offset(items: Vec<T>, offset: Option<u32>, has_more: bool) -> Self {
+        let next = if has_more {
+            let count = u32::try_from(items.len()).unwrap_or(u32::MAX);
+            Some(PageCursor::Offset(
+                offset.unwrap_or_default().saturating_add(count),
+            ))
+        } else {
+            None
+        };
+        Page { items, next }
+    }
+
+    /// Gets the items contained in this page.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Gets the position of the next page.
+    pub fn next(&self) -> Option<&PageCursor> {
+        self.next.as_ref()
+    }
+}
+
+/// The future returned by the function that a [`Paginator`] uses for fetching pages.
+pub type PageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<Page<T>, UStatus>> + Send + 'a>>;
+
+/// Iterates over the items of a result set that a core service returns in multiple pages.
+///
+/// The paginator fetches the next page on demand, i.e. only once all items of the previous page
+/// have been consumed, so callers never need to keep track of offsets or tokens themselves.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::core::{Page, PageCursor, Paginator};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let items = vec![1, 2, 3, 4, 5];
+/// let mut paginator = Paginator::new(move |cursor: Option<PageCursor>| {
+///     let items = items.clone();
+///     Box::pin(async move {
+///         let offset = match cursor {
+///             Some(PageCursor::Offset(offset)) => Some(offset),
+///             _ => None,
+///         };
+///         let start = offset.unwrap_or_default() as usize;
+///         let end = (start + 2).min(items.len());
+///         Ok(Page::with_offset(items[start..end].to_vec(), offset, end < items.len()))
+///     })
+/// });
+///
+/// assert_eq!(paginator.try_collect().await.unwrap(), vec![1, 2, 3, 4, 5]);
+/// # }
+/// ```
+pub struct Paginator<'a, T> {
+    fetch_page: Box<dyn FnMut(Option<PageCursor>) -> PageFuture<'a, T> + Send + 'a>,
+    items: VecDeque<T>,
+    // the position of the next page to fetch, `None` once the last page has been fetched
+    next: Option<Option<PageCursor>>,
+}
+
+impl<'a, T> Paginator<'a, T> {
+    /// Creates a new paginator.
+    ///
+    /// # Arguments
+    ///
+    /// * `fetch_page` - The function to invoke for fetching a page. The function is invoked with
+    ///                  `None` for fetching the first page and with the position returned along
+    ///                  with the previous page for fetching subsequent pages.
+    pub fn new<F>(fetch_page: F) -> Self
+    where
+        F: FnMut(Option<PageCursor>) -> PageFuture<'a, T> + Send + 'a,
+    {
+        Paginator {
+            fetch_page: Box::new(fetch_page),
+            items: VecDeque::new(),
+            next: Some(None),
+        }
+    }
+
+    /// Gets the next item, fetching the next page if necessary.
+    ///
+    /// # Returns
+    ///
+    /// `None` if all items have been consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the next page could not be fetched. The page will be fetched again
+    /// when this function is invoked the next time. Returns an error with [`UCode::INTERNAL`]
+    /// if the service has returned an empty page that refers to itself as the next page.
+    pub async fn next(&mut self) -> Option<Result<T, UStatus>> {
+        loop {
+            if let Some(item) = self.items.pop_front() {
+                return Some(Ok(item));
+            }
+            let cursor = self.next.clone()?;
+            match (self.fetch_page)(cursor.clone()).await {
+                Ok(page) => {
+                    if page.items.is_empty() && page.next.is_some() && page.next == cursor {
+                        self.next = None;
+                        return Some(Err(UStatus::fail_with_code(
+                            UCode::INTERNAL,
+                            "service returned empty page referring to itself",
+                        )));
+                    }
+                    self.items.extend(page.items);
+                    self.next = page.next.map(Some);
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    /// Gets all remaining items.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the pages could not be fetched.
+    pub async fn try_collect(&mut self) -> Result<Vec<T>, UStatus> {
+        let mut items = vec![];
+        while let Some(item) = self.next().await {
+            items.push(item?);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[tokio::test]
+    async fn test_paginator_fetches_pages_on_demand() {
+        // GIVEN a service returning tokens for subsequent pages
+        let fetch_count = Arc::new(AtomicUsize::new(0));
+        let counter = fetch_count.clone();
+        let mut paginator = Paginator::new(move |cursor| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                match cursor {
+                    None => Ok(Page::new(
+                        vec!["a", "b"],
+                        Some(PageCursor::Token("second".to_string())),
+                    )),
+                    Some(PageCursor::Token(token)) if token == "second" => {
+                        Ok(Page::new(vec!["c"], None))
+                    }
+                    _ => Err(UStatus::fail_with_code(
+                        UCode::INVALID_ARGUMENT,
+                        "bad cursor",
+                    )),
+                }
+            })
+        });
+
+        // WHEN consuming the items of the first page
+        assert_eq!(paginator.next().await.unwrap().unwrap(), "a");
+        assert_eq!(paginator.next().await.unwrap().unwrap(), "b");
+        // THEN only the first page has been fetched
+        assert_eq!(fetch_count.load(Ordering::SeqCst), 1);
+
+        // WHEN consuming the remaining items
+        assert_eq!(paginator.try_collect().await.unwrap(), vec!["c"]);
+        // THEN the second page has been fetched
+        assert_eq!(fetch_count.load(Ordering::SeqCst), 2);
+        // and no more items are returned
+        assert!(paginator.next().await.is_none());
+        assert_eq!(fetch_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_paginator_retries_failed_page() {
+        // GIVEN a service that fails to return the second page once
+        let fetch_count = Arc::new(AtomicUsize::new(0));
+        let counter = fetch_count.clone();
+        let mut paginator = Paginator::new(move |cursor| {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                match cursor {
+                    None => Ok(Page::with_offset(vec![1, 2], None, true)),
+                    Some(PageCursor::Offset(2)) if attempt == 1 => {
+                        Err(UStatus::fail_with_code(UCode::UNAVAILABLE, "not available"))
+                    }
+                    Some(PageCursor::Offset(2)) => Ok(Page::with_offset(vec![3], Some(2), false)),
+                    _ => Err(UStatus::fail_with_code(
+                        UCode::INVALID_ARGUMENT,
+                        "bad cursor",
+                    )),
+                }
+            })
+        });
+
+        // WHEN collecting all items
+        // THEN the first attempt fails
+        assert!(paginator
+            .try_collect()
+            .await
+            .is_err_and(|e| e.get_code() == UCode::UNAVAILABLE));
+        // and the second attempt continues with the page that could not be fetched
+        assert_eq!(paginator.try_collect().await.unwrap(), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn test_paginator_detects_empty_page_referring_to_itself() {
+        let mut paginator: Paginator<u8> = Paginator::new(move |cursor| {
+            Box::pin(async move {
+                let offset = match cursor {
+                    Some(PageCursor::Offset(offset)) => Some(offset),
+                    _ => None,
+                };
+                Ok(Page::with_offset(vec![], offset, true))
+            })
+        });
+
+        // the first (empty) page refers to offset 0 which differs from the initial cursor
+        let result = paginator.try_collect().await;
+
+        assert!(result.is_err_and(|e| e.get_code() == UCode::INTERNAL));
+        assert!(paginator.next().await.is_none());
+    }
+}
@@ -22,7 +22,7 @@ pub use crate::up_core_api::udiscovery::{
 use crate::{UStatus, UUri};
 
 /// The uEntity (type) identifier of the uDiscovery service.
-pub const UDISCOVERY_TYPE_ID: u32 = 0x0000_0001;
+pub const UDISCOVERY_TYPE_ID: u32 = crate::wellknown::UDISCOVERY_TYPE_ID;
 /// The (latest) major version of the uDiscovery service.
 pub const UDISCOVERY_VERSION_MAJOR: u8 = 0x03;
 /// The resource identifier of uDiscovery's _find services_ operation.
@@ -24,7 +24,8 @@ pub use crate::up_core_api::usubscription::{
     UnsubscribeRequest, UnsubscribeResponse, Update,
 };
 
-use crate::{UStatus, UUri};
+use crate::core::{Page, PageCursor, Paginator};
+use crate::{UCode, UStatus, UUri};
 
 impl Hash for SubscriberInfo {
     /// Creates a hash value based on the URI property.
@@ -115,7 +116,7 @@ impl SubscriptionResponse {
 }
 
 /// The uEntity (type) identifier of the uSubscription service.
-pub const USUBSCRIPTION_TYPE_ID: u32 = 0x0000_0000;
+pub const USUBSCRIPTION_TYPE_ID: u32 = crate::wellknown::USUBSCRIPTION_TYPE_ID;
 /// The (latest) major version of the uSubscription service.
 pub const USUBSCRIPTION_VERSION_MAJOR: u8 = 0x03;
 /// The resource identifier of uSubscription's _subscribe_ operation.
@@ -154,6 +155,76 @@ pub fn usubscription_uri(resource_id: u16) -> UUri {
     .unwrap()
 }
 
+fn offset_of(cursor: Option<PageCursor>) -> Result<Option<u32>, UStatus> {
+    match cursor {
+        None => Ok(None),
+        Some(PageCursor::Offset(offset)) => Ok(Some(offset)),
+        Some(PageCursor::Token(_)) => Err(UStatus::fail_with_code(
+            UCode::INTERNAL,
+            "uSubscription service does not support token based pagination",
+        )),
+    }
+}
+
+/// Creates a paginator for the subscriptions matching a request to the uSubscription service.
+///
+/// The paginator invokes [`USubscription::fetch_subscriptions`] repeatedly, with increasing
+/// offsets, until the service indicates that there are no more records.
+///
+/// # Arguments
+///
+/// * `usubscription` - The client to use for invoking the uSubscription service.
+/// * `request` - The request identifying the subscriptions to fetch. The request's offset
+///               is used as the starting point.
+pub fn paginate_subscriptions(
+    usubscription: &dyn USubscription,
+    request: FetchSubscriptionsRequest,
+) -> Paginator<'_, Subscription> {
+    let first_offset = request.offset;
+    Paginator::new(move |cursor| {
+        let mut request = request.clone();
+        Box::pin(async move {
+            let offset = offset_of(cursor)?.or(first_offset);
+            request.offset = offset;
+            let response = usubscription.fetch_subscriptions(request).await?;
+            Ok(Page::with_offset(
+                response.subscriptions,
+                offset,
+                response.has_more_records.unwrap_or(false),
+            ))
+        })
+    })
+}
+
+/// Creates a paginator for the subscribers of a topic.
+///
+/// The paginator invokes [`USubscription::fetch_subscribers`] repeatedly, with increasing
+/// offsets, until the service indicates that there are no more records.
+///
+/// # Arguments
+///
+/// * `usubscription` - The client to use for invoking the uSubscription service.
+/// * `request` - The request identifying the topic. The request's offset is used as the starting point.
+pub fn paginate_subscribers(
+    usubscription: &dyn USubscription,
+    request: FetchSubscribersRequest,
+) -> Paginator<'_, SubscriberInfo> {
+    let first_offset = request.offset;
+    Paginator::new(move |cursor| {
+        let mut request = request.clone();
+        Box::pin(async move {
+            let offset = offset_of(cursor)?.or(first_offset);
+            request.offset = offset;
+            let response = usubscription.fetch_subscribers(request).await?;
+            Ok(Page::with_offset(
+                response.subscribers,
+                offset,
+                response.has_more_records.unwrap_or(false),
+            ))
+        })
+    })
+}
+
 /// The uProtocol Application Layer client interface to the uSubscription service.
 ///
 /// Please refer to the [uSubscription service specification](https://github.com/eclipse-uprotocol/up-spec/blob/main/up-l3/usubscription/v3/README.adoc)
@@ -244,3 +315,65 @@ pub trait USubscription: Send + Sync {
         fetch_subscribers_request: FetchSubscribersRequest,
     ) -> Result<FetchSubscribersResponse, UStatus>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_paginate_subscribers_fetches_all_pages() {
+        // GIVEN a uSubscription service which returns subscribers in pages of two
+        let subscribers: Vec<SubscriberInfo> = (0x9a00..0x9a05)
+            .map(|resource_id| SubscriberInfo {
+                uri: Some(UUri::try_from_parts("", 0x1000, 0x01, resource_id).unwrap()).into(),
+                ..Default::default()
+            })
+            .collect();
+        let all_subscribers = subscribers.clone();
+        let mut usubscription = MockUSubscription::new();
+        usubscription
+            .expect_fetch_subscribers()
+            .times(3)
+            .returning(move |request| {
+                let start = request.offset.unwrap_or_default() as usize;
+                let end = (start + 2).min(all_subscribers.len());
+                Ok(FetchSubscribersResponse {
+                    subscribers: all_subscribers[start..end].to_vec(),
+                    has_more_records: Some(end < all_subscribers.len()),
+                    ..Default::default()
+                })
+            });
+
+        // WHEN fetching all subscribers of a topic
+        let request = FetchSubscribersRequest {
+            topic: Some(UUri::try_from_parts("", 0x2000, 0x01, 0x8001).unwrap()).into(),
+            ..Default::default()
+        };
+        let result = paginate_subscribers(&usubscription, request)
+            .try_collect()
+            .await;
+
+        // THEN all subscribers are returned
+        assert_eq!(result.unwrap(), subscribers);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_subscriptions_stops_on_error() {
+        let mut usubscription = MockUSubscription::new();
+        usubscription
+            .expect_fetch_subscriptions()
+            .once()
+            .return_const(Err(UStatus::fail_with_code(
+                UCode::UNAVAILABLE,
+                "not available",
+            )));
+
+        let mut paginator =
+            paginate_subscriptions(&usubscription, FetchSubscriptionsRequest::default());
+
+        assert!(paginator
+            .next()
+            .await
+            .is_some_and(|result| result.is_err_and(|e| e.get_code() == UCode::UNAVAILABLE)));
+    }
+}
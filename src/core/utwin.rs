@@ -11,4 +11,29 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-pub use crate::up_core_api::utwin::{GetLastMessagesResponse, MessageResponse};
+use crate::UUri;
+
+pub use crate::up_core_api::utwin::{
+    GetLastMessagesRequest, GetLastMessagesResponse, MessageResponse,
+};
+
+/// The uEntity (type) identifier of the uTwin service.
+pub const UTWIN_TYPE_ID: u32 = crate::wellknown::UTWIN_TYPE_ID;
+/// The (latest) major version of the uTwin service.
+pub const UTWIN_VERSION_MAJOR: u8 = 0x02;
+/// The resource identifier of uTwin's _get last messages_ operation.
+pub const RESOURCE_ID_GET_LAST_MESSAGES: u16 = 0x0001;
+
+/// Gets a UUri referring to one of the local uTwin service's resources.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::core::utwin;
+///
+/// let uuri = utwin::utwin_uri(utwin::RESOURCE_ID_GET_LAST_MESSAGES);
+/// assert_eq!(uuri.resource_id, 0x0001);
+/// ```
+pub fn utwin_uri(resource_id: u16) -> UUri {
+    UUri::try_from_parts("", UTWIN_TYPE_ID, UTWIN_VERSION_MAJOR, resource_id).unwrap()
+}
@@ -0,0 +1,190 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides types representing snapshots of the internal state of the components of the
+communication stack.
+
+The snapshots can be retrieved by means of the `diagnostics` functions of the
+corresponding components and can be combined into a [`StackDiagnostics`] in order to
+create a dump of the overall state, e.g. when processing a corresponding RPC request or
+when the process receives a signal. All URIs are represented by their string serialization.
+
+If the `serde` feature is enabled, all types implement `serde::Serialize`.
+*/
+
+/// A snapshot of the state of an RPC client.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RpcClientDiagnostics {
+    /// The IDs of all requests for which the client is waiting for a response.
+    pub pending_requests: Vec<String>,
+    /// The number of RPC Request messages that have been sent successfully.
+    pub requests_sent: u64,
+    /// The number of RPC Response messages that have been received for pending requests.
+    pub responses_received: u64,
+    /// The number of requests for which no response has been received in time.
+    pub requests_timed_out: u64,
+    /// The number of RPC Response messages that have been discarded because their source did not match
+    /// the method that the request had been sent to.
+    pub responses_rejected: u64,
+    /// The number of hedged requests that have not been sent because the client's pending request
+    /// limits had been reached.
+    pub hedges_skipped: u64,
+}
+
+/// A snapshot of an RPC endpoint that is registered with an RPC server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EndpointDiagnostics {
+    /// The resource ID of the method that the endpoint handles requests for.
+    pub resource_id: u16,
+    /// The pattern that the sources of requests need to match.
+    pub origin_filter: String,
+    /// The number of handler invocations that have exceeded the threshold of the endpoint's
+    /// execution watchdog.
+    pub watchdog_alerts: u64,
+    /// The number of requests that the endpoint has processed.
+    pub requests: u64,
+    /// The number of requests that have failed, by the name of the error code, ordered by name.
+    pub errors: Vec<(String, u64)>,
+    /// The distribution of the times it took to process requests.
+    pub latency_histogram: Vec<LatencyBucket>,
+}
+
+/// A bucket of a histogram of processing times.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LatencyBucket {
+    /// The (inclusive) upper bound of the processing times counted in this bucket, in milliseconds.
+    /// The last bucket of a histogram has an upper bound of `u64::MAX`.
+    pub le_millis: u64,
+    /// The number of requests whose processing time falls into this bucket but not into the previous one.
+    pub count: u64,
+}
+
+/// A snapshot of the state of an RPC server.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RpcServerDiagnostics {
+    /// The registered endpoints, ordered by resource ID.
+    pub endpoints: Vec<EndpointDiagnostics>,
+    /// The number of requests that are currently being processed.
+    pub in_flight_requests: usize,
+    /// The number of requests that have been found to be orphaned by the server's orphan reaper.
+    pub orphaned_requests: u64,
+}
+
+/// A snapshot of the state of a subscriber.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SubscriberDiagnostics {
+    /// The topic filters that listeners have been subscribed to, along with the number of
+    /// listeners subscribed to each of them.
+    pub subscriptions: Vec<(String, usize)>,
+    /// The topics that subscription change handlers are registered for.
+    pub subscription_change_handlers: Vec<String>,
+}
+
+/// A snapshot of a listener that is registered with a transport.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ListenerDiagnostics {
+    pub source_filter: String,
+    pub sink_filter: Option<String>,
+}
+
+/// A snapshot of the counters that a gap detector maintains for a topic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TopicGapDiagnostics {
+    /// The topic.
+    pub topic: String,
+    /// The number of events that have been received.
+    pub messages_received: u64,
+    /// The estimated number of events that have been lost.
+    pub estimated_messages_lost: u64,
+}
+
+/// A snapshot of the state of a transport.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TransportDiagnostics {
+    /// The registered listeners.
+    pub listeners: Vec<ListenerDiagnostics>,
+    /// The number of messages that have been sent via the transport.
+    pub messages_sent: u64,
+}
+
+/// A snapshot of the background tasks of a component.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TaskDiagnostics {
+    /// The names of the tasks that are currently running, in alphabetical order.
+    pub running_tasks: Vec<String>,
+}
+
+/// A snapshot of the overall state of a uEntity's communication stack.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::diagnostics::{RpcServerDiagnostics, StackDiagnostics, TransportDiagnostics};
+///
+/// let diagnostics = StackDiagnostics::default()
+///     .with_rpc_server(RpcServerDiagnostics::default())
+///     .with_transport(TransportDiagnostics::default());
+/// assert!(diagnostics.rpc_server.is_some());
+/// assert!(diagnostics.rpc_client.is_none());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StackDiagnostics {
+    pub rpc_client: Option<RpcClientDiagnostics>,
+    pub rpc_server: Option<RpcServerDiagnostics>,
+    pub subscriber: Option<SubscriberDiagnostics>,
+    pub tasks: Option<TaskDiagnostics>,
+    pub transport: Option<TransportDiagnostics>,
+}
+
+impl StackDiagnostics {
+    /// Sets the state of the RPC client.
+    pub fn with_rpc_client(mut self, diagnostics: RpcClientDiagnostics) -> Self {
+        self.rpc_client = Some(diagnostics);
+        self
+    }
+
+    /// Sets the state of the RPC server.
+    pub fn with_rpc_server(mut self, diagnostics: RpcServerDiagnostics) -> Self {
+        self.rpc_server = Some(diagnostics);
+        self
+    }
+
+    /// Sets the state of the subscriber.
+    pub fn with_subscriber(mut self, diagnostics: SubscriberDiagnostics) -> Self {
+        self.subscriber = Some(diagnostics);
+        self
+    }
+
+    /// Sets the state of the background tasks.
+    pub fn with_tasks(mut self, diagnostics: TaskDiagnostics) -> Self {
+        self.tasks = Some(diagnostics);
+        self
+    }
+
+    /// Sets the state of the transport.
+    pub fn with_transport(mut self, diagnostics: TransportDiagnostics) -> Self {
+        self.transport = Some(diagnostics);
+        self
+    }
+}
@@ -0,0 +1,402 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a UListener decorator for skipping duplicates of messages that have already been processed,
+even across restarts.
+
+Transports and helpers like the [`RedeliveryCoordinator`](crate::redelivery::RedeliveryCoordinator)
+may deliver the same message more than once. Consumers that apply side effects which must not be
+repeated, e.g. unlocking a door or charging a customer, can wrap their listener in an
+[`ExactlyOnceListener`], which keeps a ledger of the IDs of the messages that have been processed
+in a [`KvStore`]. When using a persistent store like the [`FileKvStore`](crate::kv_store::FileKvStore),
+messages that have been processed before a restart are skipped after the restart as well.
+
+A message is only recorded as processed once the wrapped listener has returned. A message whose
+processing has been interrupted, e.g. by a crash, is therefore processed again when it is redelivered.
+*/
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::{kv_store::KvStore, time_source, UListener, UMessage, UStatus};
+
+/// The prefix of the keys that processed message IDs are stored under by default.
+pub const DEFAULT_KEY_PREFIX: &str = "exactly-once/";
+
+fn now_millis() -> u64 {
+    time_source::now().map_or(0, |now| now.as_millis() as u64)
+}
+
+/// A [`UListener`] that passes on each message to another listener once only.
+///
+/// The ID of each message is recorded in the ledger _after_ the wrapped listener has processed the
+/// message. Duplicates that arrive while the message is being processed or after it has been
+/// processed are skipped. If the process terminates while the wrapped listener is processing a
+/// message, the message's ID has not been recorded yet, so a redelivered copy of the message is
+/// processed again after the restart. Messages are not passed on if the ledger cannot be read,
+/// e.g. because the store is not accessible. Messages without an ID are ignored.
+///
+/// Entries are removed from the ledger once their time-to-live has passed. The TTL should
+/// therefore exceed the time span during which duplicates of a message may arrive, e.g. the
+/// maximum redelivery period of the sender. Expired entries are pruned periodically while
+/// messages are being received, and can also be [pruned explicitly](`Self::prune`).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::{
+///     atomic::{AtomicUsize, Ordering},
+///     Arc,
+/// };
+/// use std::time::Duration;
+/// use up_rust::{
+///     exactly_once::ExactlyOnceListener, kv_store::InMemoryKvStore, UListener, UMessage,
+///     UMessageBuilder, UUri,
+/// };
+///
+/// #[derive(Default)]
+/// struct DoorUnlocker {
+///     unlocked: AtomicUsize,
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl UListener for DoorUnlocker {
+///     async fn on_receive(&self, _msg: UMessage) {
+///         self.unlocked.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let door_unlocker = Arc::new(DoorUnlocker::default());
+/// let listener = ExactlyOnceListener::new(
+///     door_unlocker.clone(),
+///     Arc::new(InMemoryKvStore::new()),
+///     Duration::from_secs(3600),
+/// );
+///
+/// let request = UMessageBuilder::publish(UUri::try_from("//vehicle/D100/1/8001").unwrap())
+///     .build()
+///     .unwrap();
+/// listener.on_receive(request.clone()).await;
+/// // the duplicate is not passed on to the door unlocker
+/// listener.on_receive(request).await;
+/// assert_eq!(door_unlocker.unlocked.load(Ordering::SeqCst), 1);
+/// # }
+/// ```
+pub struct ExactlyOnceListener {
+    listener: Arc<dyn UListener>,
+    store: Arc<dyn KvStore>,
+    key_prefix: String,
+    ttl: Duration,
+    prune_interval: Duration,
+    // serializes checking and recording of message IDs
+    ledger_lock: Mutex<LedgerState>,
+}
+
+struct LedgerState {
+    last_pruned: u64,
+    // the IDs of the messages that are currently being processed
+    in_progress: HashSet<String>,
+}
+
+impl ExactlyOnceListener {
+    /// Creates a new listener.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to pass on messages to.
+    /// * `store` - The store to keep the ledger of processed message IDs in.
+    /// * `ttl` - The time after which a processed message ID is removed from the ledger.
+    pub fn new(listener: Arc<dyn UListener>, store: Arc<dyn KvStore>, ttl: Duration) -> Self {
+        ExactlyOnceListener {
+            listener,
+            store,
+            key_prefix: DEFAULT_KEY_PREFIX.to_string(),
+            ttl,
+            prune_interval: ttl,
+            ledger_lock: Mutex::new(LedgerState {
+                last_pruned: now_millis(),
+                in_progress: HashSet::new(),
+            }),
+        }
+    }
+
+    /// Sets the prefix of the keys that processed message IDs are stored under.
+    ///
+    /// Listeners sharing the same store need to use distinct prefixes.
+    pub fn with_key_prefix<T: Into<String>>(mut self, key_prefix: T) -> Self {
+        self.key_prefix = key_prefix.into();
+        self
+    }
+
+    /// Sets the interval at which expired entries are pruned from the ledger.
+    ///
+    /// The default interval is the TTL of the entries.
+    pub fn with_prune_interval(mut self, prune_interval: Duration) -> Self {
+        self.prune_interval = prune_interval;
+        self
+    }
+
+    /// Checks if a message with a given ID has already been processed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ledger cannot be accessed.
+    pub async fn is_processed(&self, message_id: &str) -> Result<bool, UStatus> {
+        let key = format!("{}{}", self.key_prefix, message_id);
+        self.store
+            .get(&key)
+            .await
+            .map(|expiry| expiry.is_some_and(|expiry| !Self::is_expired(&expiry, now_millis())))
+    }
+
+    /// Removes all expired entries from the ledger.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries that have been removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ledger cannot be accessed.
+    pub async fn prune(&self) -> Result<usize, UStatus> {
+        let mut state = self.ledger_lock.lock().await;
+        self.prune_expired(&mut state.last_pruned).await
+    }
+
+    fn is_expired(expiry: &[u8], now: u64) -> bool {
+        // entries with malformed values are considered expired so that they get pruned
+        <[u8; 8]>::try_from(expiry).map_or(true, |expiry| u64::from_be_bytes(expiry) <= now)
+    }
+
+    async fn prune_expired(&self, last_pruned: &mut u64) -> Result<usize, UStatus> {
+        let now = now_millis();
+        let mut removed = 0;
+        for key in self.store.keys(&self.key_prefix).await? {
+            if let Some(expiry) = self.store.get(&key).await? {
+                if Self::is_expired(&expiry, now) && self.store.delete(&key).await? {
+                    removed += 1;
+                }
+            }
+        }
+        *last_pruned = now;
+        debug!(removed, "pruned expired entries from message ID ledger");
+        Ok(removed)
+    }
+
+    /// Marks a message as being processed.
+    ///
+    /// Returns `false` if the message is already being processed or has already been recorded.
+    async fn begin(&self, message_id: &str) -> Result<bool, UStatus> {
+        let mut state = self.ledger_lock.lock().await;
+        let now = now_millis();
+        if now.saturating_sub(state.last_pruned) >= self.prune_interval.as_millis() as u64 {
+            if let Err(e) = self.prune_expired(&mut state.last_pruned).await {
+                debug!("failed to prune message ID ledger: {}", e);
+            }
+        }
+        if state.in_progress.contains(message_id) {
+            return Ok(false);
+        }
+        let key = format!("{}{}", self.key_prefix, message_id);
+        if let Some(expiry) = self.store.get(&key).await? {
+            if !Self::is_expired(&expiry, now) {
+                return Ok(false);
+            }
+        }
+        state.in_progress.insert(message_id.to_string());
+        Ok(true)
+    }
+
+    /// Records the ID of a message that has been processed in the ledger.
+    async fn commit(&self, message_id: &str) -> Result<(), UStatus> {
+        let mut state = self.ledger_lock.lock().await;
+        state.in_progress.remove(message_id);
+        let key = format!("{}{}", self.key_prefix, message_id);
+        let expiry = now_millis().saturating_add(self.ttl.as_millis() as u64);
+        self.store.put(&key, expiry.to_be_bytes().to_vec()).await
+    }
+}
+
+#[async_trait]
+impl UListener for ExactlyOnceListener {
+    async fn on_receive(&self, msg: UMessage) {
+        let Some(message_id) = msg
+            .attributes
+            .id
+            .as_ref()
+            .map(|id| id.to_hyphenated_string())
+        else {
+            debug!("ignoring message without ID");
+            return;
+        };
+        match self.begin(&message_id).await {
+            Ok(true) => {
+                self.listener.on_receive(msg).await;
+                if let Err(e) = self.commit(&message_id).await {
+                    warn!(
+                        id = %message_id,
+                        "failed to record ID of processed message: {}", e
+                    );
+                }
+            }
+            Ok(false) => {
+                debug!(id = %message_id, "skipping message that has already been processed");
+            }
+            Err(e) => {
+                warn!(
+                    id = %message_id,
+                    "failed to read message ID ledger, skipping message: {}", e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kv_store::{InMemoryKvStore, MockKvStore},
+        utransport::MockUListener,
+        UCode, UMessageBuilder, UUri,
+    };
+
+    fn new_message() -> UMessage {
+        UMessageBuilder::publish(UUri::try_from("//vehicle/D100/1/8001").unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_are_skipped_across_listener_instances() {
+        // GIVEN a store that is shared by subsequent listener instances, e.g. a persistent store
+        let store = Arc::new(InMemoryKvStore::new());
+        let message = new_message();
+        let mut listener = MockUListener::new();
+        listener.expect_on_receive().once().return_const(());
+        let exactly_once =
+            ExactlyOnceListener::new(Arc::new(listener), store.clone(), Duration::from_secs(60));
+
+        // WHEN the same message is received twice
+        exactly_once.on_receive(message.clone()).await;
+        exactly_once.on_receive(message.clone()).await;
+        // and once more after a restart
+        let mut listener_after_restart = MockUListener::new();
+        listener_after_restart.expect_on_receive().never();
+        let exactly_once = ExactlyOnceListener::new(
+            Arc::new(listener_after_restart),
+            store,
+            Duration::from_secs(60),
+        );
+        exactly_once.on_receive(message.clone()).await;
+
+        // THEN the message has been passed on only once
+        // (verified by the mocks' expectations)
+        let message_id = message
+            .attributes
+            .id
+            .get_or_default()
+            .to_hyphenated_string();
+        assert!(exactly_once.is_processed(&message_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_expired_entries() {
+        let mut listener = MockUListener::new();
+        listener.expect_on_receive().times(2).return_const(());
+        let exactly_once = ExactlyOnceListener::new(
+            Arc::new(listener),
+            Arc::new(InMemoryKvStore::new()),
+            Duration::ZERO,
+        );
+        let message = new_message();
+
+        exactly_once.on_receive(message.clone()).await;
+        assert_eq!(exactly_once.prune().await.unwrap(), 1);
+
+        // a message whose entry has expired is processed again
+        exactly_once.on_receive(message).await;
+    }
+
+    // A listener that checks if the message being processed has already been recorded.
+    struct LedgerInspector {
+        store: Arc<InMemoryKvStore>,
+        recorded_while_processing: std::sync::Mutex<Option<bool>>,
+    }
+
+    #[async_trait]
+    impl UListener for LedgerInspector {
+        async fn on_receive(&self, _msg: UMessage) {
+            let recorded = !self
+                .store
+                .keys(DEFAULT_KEY_PREFIX)
+                .await
+                .unwrap()
+                .is_empty();
+            *self.recorded_while_processing.lock().unwrap() = Some(recorded);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_id_is_recorded_after_processing() {
+        // GIVEN a listener that inspects the ledger while processing a message
+        let store = Arc::new(InMemoryKvStore::new());
+        let inspector = Arc::new(LedgerInspector {
+            store: store.clone(),
+            recorded_while_processing: std::sync::Mutex::new(None),
+        });
+        let exactly_once =
+            ExactlyOnceListener::new(inspector.clone(), store.clone(), Duration::from_secs(60));
+
+        // WHEN a message is received
+        let message = new_message();
+        exactly_once.on_receive(message.clone()).await;
+
+        // THEN the message's ID has not been recorded before the message has been processed
+        assert_eq!(
+            *inspector.recorded_while_processing.lock().unwrap(),
+            Some(false)
+        );
+        // but has been recorded afterwards
+        let message_id = message
+            .attributes
+            .id
+            .get_or_default()
+            .to_hyphenated_string();
+        assert!(exactly_once.is_processed(&message_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_message_is_skipped_if_ledger_cannot_be_read() {
+        let mut store = MockKvStore::new();
+        store.expect_get().returning(|_key| {
+            Err(UStatus::fail_with_code(
+                UCode::UNAVAILABLE,
+                "disk not mounted",
+            ))
+        });
+        store.expect_put().never();
+        let mut listener = MockUListener::new();
+        listener.expect_on_receive().never();
+        let exactly_once =
+            ExactlyOnceListener::new(Arc::new(listener), Arc::new(store), Duration::from_secs(60));
+
+        exactly_once.on_receive(new_message()).await;
+    }
+}
@@ -0,0 +1,998 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a C ABI for embedding up-rust into C/C++ applications.
+
+The functions in this module operate on opaque handles to [`UUri`]s, [`UMessage`]s and
+[`LocalTransport`]s. Handles that are returned to the caller via an _out_ parameter are owned
+by the caller and must be released using the corresponding `up_*_free` function. Strings returned
+by the functions must be released using [`up_string_free`], byte buffers using [`up_bytes_free`].
+
+Functions that can fail return a status code, which is the numeric value of a [`UCode`].
+`0` (`UCode::OK`) indicates success.
+
+The local transport is run on an internal Tokio runtime. Callbacks that have been registered for
+receiving messages are invoked on one of the runtime's threads, while the thread that has sent the
+message waits for the dispatching to complete. Callbacks may invoke the functions of this module,
+e.g. for sending a reply. The message passed into a callback is only valid for the duration of the
+callback's invocation.
+
+Panics are caught at the boundary of each function, which then returns `INTERNAL`, `NULL` or `0`.
+*/
+
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use protobuf::{Enum, Message};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tracing::error;
+
+use crate::{
+    internal_runtime::runtime, local_transport::LocalTransport, UAttributesValidators, UCode,
+    UListener, UMessage, UMessageBuilder, UPayloadFormat, UTransport, UUri,
+};
+
+/// The type of function that is invoked for messages received by the local transport.
+///
+/// The function is invoked with the received message and the user data that has been
+/// passed in when registering the callback.
+pub type UpMessageCallback = unsafe extern "C" fn(message: *const UMessage, user_data: *mut c_void);
+
+// Runs a future on the internal runtime and waits for its completion.
+//
+// The future is spawned on the runtime, so that callbacks are always invoked on one of the
+// runtime's threads. If the calling thread is a runtime thread itself, e.g. because a callback
+// sends a reply, the thread is put into blocking mode first, because a runtime thread must not
+// be blocked otherwise.
+fn run_on_runtime<F>(future: F) -> Result<F::Output, UCode>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let on_runtime_thread = match Handle::try_current() {
+        Err(_) => false,
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => true,
+        // the threads of a single-threaded runtime cannot be put into blocking mode
+        Ok(_) => return Err(UCode::FAILED_PRECONDITION),
+    };
+    let task = runtime().spawn(future);
+    let result = if on_runtime_thread {
+        tokio::task::block_in_place(|| runtime().handle().block_on(task))
+    } else {
+        runtime().block_on(task)
+    };
+    result.map_err(|_| UCode::INTERNAL)
+}
+
+// Invokes a function, preventing panics from unwinding across the FFI boundary.
+fn catch_panic<R, F: FnOnce() -> R>(on_panic: R, f: F) -> R {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        error!("caught panic in FFI function");
+        on_panic
+    })
+}
+
+fn status(code: UCode) -> i32 {
+    code.value()
+}
+
+unsafe fn str_from_ptr<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+fn into_c_string(value: String) -> *mut c_char {
+    CString::new(value).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+unsafe fn payload_from_ptr(payload: *const u8, payload_len: usize) -> Option<Vec<u8>> {
+    if payload.is_null() {
+        (payload_len == 0).then(Vec::new)
+    } else {
+        Some(std::slice::from_raw_parts(payload, payload_len).to_vec())
+    }
+}
+
+unsafe fn build_message(
+    builder: &mut UMessageBuilder,
+    payload: *const u8,
+    payload_len: usize,
+    payload_format: i32,
+    out: *mut *mut UMessage,
+) -> i32 {
+    if out.is_null() {
+        return status(UCode::INVALID_ARGUMENT);
+    }
+    let Some(payload) = payload_from_ptr(payload, payload_len) else {
+        return status(UCode::INVALID_ARGUMENT);
+    };
+    let result = if payload.is_empty() {
+        builder.build()
+    } else {
+        let Some(format) = UPayloadFormat::from_i32(payload_format) else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        builder.build_with_payload(payload, format)
+    };
+    match result {
+        Ok(message) => {
+            *out = Box::into_raw(Box::new(message));
+            status(UCode::OK)
+        }
+        Err(_) => status(UCode::INVALID_ARGUMENT),
+    }
+}
+
+/// Releases a string that has been returned by one of the functions of this module.
+///
+/// # Safety
+///
+/// `value` must be `NULL` or a string returned by this module which has not been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn up_string_free(value: *mut c_char) {
+    catch_panic((), || {
+        if !value.is_null() {
+            drop(CString::from_raw(value));
+        }
+    })
+}
+
+/// Releases a byte buffer that has been returned by one of the functions of this module.
+///
+/// # Safety
+///
+/// `buf` and `len` must have been returned by this module and the buffer must not have been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn up_bytes_free(buf: *mut u8, len: usize) {
+    catch_panic((), || {
+        if !buf.is_null() {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(buf, len)));
+        }
+    })
+}
+
+/// Creates a URI from its string representation.
+///
+/// # Returns
+///
+/// `OK` and the URI in `out`, or `INVALID_ARGUMENT` if the string is not a valid uProtocol URI.
+///
+/// # Safety
+///
+/// `uri` must be a valid, NUL terminated string and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_parse(uri: *const c_char, out: *mut *mut UUri) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let Some(uri) = str_from_ptr(uri) else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        if out.is_null() {
+            return status(UCode::INVALID_ARGUMENT);
+        }
+        match UUri::from_str(uri) {
+            Ok(uri) => {
+                *out = Box::into_raw(Box::new(uri));
+                status(UCode::OK)
+            }
+            Err(_) => status(UCode::INVALID_ARGUMENT),
+        }
+    })
+}
+
+/// Creates a URI from its components.
+///
+/// # Returns
+///
+/// `OK` and the URI in `out`, or `INVALID_ARGUMENT` if the components do not form a valid uProtocol URI.
+///
+/// # Safety
+///
+/// `authority` must be a valid, NUL terminated string and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_new(
+    authority: *const c_char,
+    entity_id: u32,
+    entity_version: u8,
+    resource_id: u16,
+    out: *mut *mut UUri,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let Some(authority) = str_from_ptr(authority) else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        if out.is_null() {
+            return status(UCode::INVALID_ARGUMENT);
+        }
+        match UUri::try_from_parts(authority, entity_id, entity_version, resource_id) {
+            Ok(uri) => {
+                *out = Box::into_raw(Box::new(uri));
+                status(UCode::OK)
+            }
+            Err(_) => status(UCode::INVALID_ARGUMENT),
+        }
+    })
+}
+
+/// Gets the string representation of a URI.
+///
+/// The returned string must be released using [`up_string_free`].
+///
+/// # Safety
+///
+/// `uri` must be `NULL` or a valid URI handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_to_string(uri: *const UUri) -> *mut c_char {
+    catch_panic(std::ptr::null_mut(), || {
+        uri.as_ref()
+            .map_or(std::ptr::null_mut(), |uri| into_c_string(uri.to_uri(false)))
+    })
+}
+
+/// Gets the authority name of a URI.
+///
+/// The returned string must be released using [`up_string_free`].
+///
+/// # Safety
+///
+/// `uri` must be `NULL` or a valid URI handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_get_authority(uri: *const UUri) -> *mut c_char {
+    catch_panic(std::ptr::null_mut(), || {
+        uri.as_ref().map_or(std::ptr::null_mut(), |uri| {
+            into_c_string(uri.authority_name.clone())
+        })
+    })
+}
+
+/// Gets the uEntity ID of a URI.
+///
+/// # Safety
+///
+/// `uri` must be a valid URI handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_get_entity_id(uri: *const UUri) -> u32 {
+    catch_panic(0, || uri.as_ref().map_or(0, |uri| uri.ue_id))
+}
+
+/// Gets the uEntity major version of a URI.
+///
+/// # Safety
+///
+/// `uri` must be a valid URI handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_get_entity_version(uri: *const UUri) -> u8 {
+    catch_panic(0, || {
+        uri.as_ref().map_or(0, |uri| uri.uentity_major_version())
+    })
+}
+
+/// Gets the resource ID of a URI.
+///
+/// # Safety
+///
+/// `uri` must be a valid URI handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_get_resource_id(uri: *const UUri) -> u16 {
+    catch_panic(0, || uri.as_ref().map_or(0, |uri| uri.resource_id()))
+}
+
+/// Releases a URI.
+///
+/// # Safety
+///
+/// `uri` must be `NULL` or a URI handle that is owned by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_free(uri: *mut UUri) {
+    catch_panic((), || {
+        if !uri.is_null() {
+            drop(Box::from_raw(uri));
+        }
+    })
+}
+
+/// Creates a Publish message.
+///
+/// # Arguments
+///
+/// * `topic` - The topic to publish to.
+/// * `payload` - The message's payload or `NULL` if the message has no payload.
+/// * `payload_len` - The length of the payload.
+/// * `payload_format` - The format of the payload (a `UPayloadFormat` value).
+/// * `out` - The location to store the created message at.
+///
+/// # Returns
+///
+/// `OK` and the message in `out`, or `INVALID_ARGUMENT` if no valid message can be created from the arguments.
+///
+/// # Safety
+///
+/// `topic` must be a valid URI handle, `payload` must point to at least `payload_len` bytes and
+/// `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_build_publish(
+    topic: *const UUri,
+    payload: *const u8,
+    payload_len: usize,
+    payload_format: i32,
+    out: *mut *mut UMessage,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let Some(topic) = topic.as_ref() else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        build_message(
+            &mut UMessageBuilder::publish(topic.to_owned()),
+            payload,
+            payload_len,
+            payload_format,
+            out,
+        )
+    })
+}
+
+/// Creates a Notification message.
+///
+/// # Arguments
+///
+/// * `origin` - The resource that the notification originates from.
+/// * `destination` - The uEntity that the notification is sent to.
+/// * `payload` - The message's payload or `NULL` if the message has no payload.
+/// * `payload_len` - The length of the payload.
+/// * `payload_format` - The format of the payload (a `UPayloadFormat` value).
+/// * `out` - The location to store the created message at.
+///
+/// # Returns
+///
+/// `OK` and the message in `out`, or `INVALID_ARGUMENT` if no valid message can be created from the arguments.
+///
+/// # Safety
+///
+/// `origin` and `destination` must be valid URI handles, `payload` must point to at least `payload_len` bytes and
+/// `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_build_notification(
+    origin: *const UUri,
+    destination: *const UUri,
+    payload: *const u8,
+    payload_len: usize,
+    payload_format: i32,
+    out: *mut *mut UMessage,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let (Some(origin), Some(destination)) = (origin.as_ref(), destination.as_ref()) else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        build_message(
+            &mut UMessageBuilder::notification(origin.to_owned(), destination.to_owned()),
+            payload,
+            payload_len,
+            payload_format,
+            out,
+        )
+    })
+}
+
+/// Serializes a message to its protobuf representation.
+///
+/// The buffer returned in `buf` must be released using [`up_bytes_free`].
+///
+/// # Safety
+///
+/// `message` must be a valid message handle, `buf` and `len` must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_serialize(
+    message: *const UMessage,
+    buf: *mut *mut u8,
+    len: *mut usize,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let Some(message) = message.as_ref() else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        if buf.is_null() || len.is_null() {
+            return status(UCode::INVALID_ARGUMENT);
+        }
+        match message.write_to_bytes() {
+            Ok(bytes) => {
+                let bytes = bytes.into_boxed_slice();
+                *len = bytes.len();
+                *buf = Box::into_raw(bytes).cast();
+                status(UCode::OK)
+            }
+            Err(_) => status(UCode::INTERNAL),
+        }
+    })
+}
+
+/// Parses a message from its protobuf representation.
+///
+/// # Returns
+///
+/// `OK` and the message in `out`, or `INVALID_ARGUMENT` if the buffer does not contain a valid uProtocol message.
+///
+/// # Safety
+///
+/// `buf` must point to at least `len` bytes and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_parse(
+    buf: *const u8,
+    len: usize,
+    out: *mut *mut UMessage,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let Some(bytes) = payload_from_ptr(buf, len) else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        if out.is_null() {
+            return status(UCode::INVALID_ARGUMENT);
+        }
+        let Ok(message) = UMessage::parse_from_bytes(&bytes) else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        let Some(attributes) = message.attributes.as_ref() else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        if UAttributesValidators::get_validator_for_attributes(attributes)
+            .validate(attributes)
+            .is_err()
+        {
+            return status(UCode::INVALID_ARGUMENT);
+        }
+        *out = Box::into_raw(Box::new(message));
+        status(UCode::OK)
+    })
+}
+
+/// Gets the type of a message (a `UMessageType` value).
+///
+/// # Safety
+///
+/// `message` must be a valid message handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_get_type(message: *const UMessage) -> i32 {
+    catch_panic(0, || {
+        message
+            .as_ref()
+            .and_then(|message| message.attributes.as_ref())
+            .map_or(0, |attributes| attributes.type_.value())
+    })
+}
+
+/// Gets the source address of a message.
+///
+/// The returned URI is owned by the caller and must be released using [`up_uri_free`].
+///
+/// # Safety
+///
+/// `message` must be a valid message handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_get_source(message: *const UMessage) -> *mut UUri {
+    catch_panic(std::ptr::null_mut(), || {
+        message
+            .as_ref()
+            .and_then(|message| message.attributes.as_ref())
+            .and_then(|attributes| attributes.source.as_ref())
+            .map_or(std::ptr::null_mut(), |uri| {
+                Box::into_raw(Box::new(uri.to_owned()))
+            })
+    })
+}
+
+/// Gets the sink address of a message.
+///
+/// The returned URI is owned by the caller and must be released using [`up_uri_free`].
+///
+/// # Returns
+///
+/// The sink or `NULL` if the message has no sink.
+///
+/// # Safety
+///
+/// `message` must be a valid message handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_get_sink(message: *const UMessage) -> *mut UUri {
+    catch_panic(std::ptr::null_mut(), || {
+        message
+            .as_ref()
+            .and_then(|message| message.attributes.as_ref())
+            .and_then(|attributes| attributes.sink.as_ref())
+            .map_or(std::ptr::null_mut(), |uri| {
+                Box::into_raw(Box::new(uri.to_owned()))
+            })
+    })
+}
+
+/// Gets the payload of a message.
+///
+/// The payload remains owned by the message, i.e. it is only valid as long as the message is.
+///
+/// # Returns
+///
+/// `OK` and the payload in `data` and `len`, or `NOT_FOUND` if the message has no payload.
+///
+/// # Safety
+///
+/// `message` must be a valid message handle, `data` and `len` must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_get_payload(
+    message: *const UMessage,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let Some(message) = message.as_ref() else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        if data.is_null() || len.is_null() {
+            return status(UCode::INVALID_ARGUMENT);
+        }
+        match message.payload.as_ref() {
+            Some(payload) => {
+                *data = payload.as_ptr();
+                *len = payload.len();
+                status(UCode::OK)
+            }
+            None => status(UCode::NOT_FOUND),
+        }
+    })
+}
+
+/// Gets the format of a message's payload (a `UPayloadFormat` value).
+///
+/// # Safety
+///
+/// `message` must be a valid message handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_get_payload_format(message: *const UMessage) -> i32 {
+    catch_panic(0, || {
+        message
+            .as_ref()
+            .and_then(|message| message.attributes.as_ref())
+            .map_or(
+                UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED.value(),
+                |attributes| attributes.payload_format.value(),
+            )
+    })
+}
+
+/// Releases a message.
+///
+/// # Safety
+///
+/// `message` must be `NULL` or a message handle that is owned by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn up_message_free(message: *mut UMessage) {
+    catch_panic((), || {
+        if !message.is_null() {
+            drop(Box::from_raw(message));
+        }
+    })
+}
+
+// forwards received messages to a C callback
+struct CallbackListener {
+    callback: UpMessageCallback,
+    user_data: *mut c_void,
+}
+
+// the caller is responsible for the user data being usable from any thread
+unsafe impl Send for CallbackListener {}
+unsafe impl Sync for CallbackListener {}
+
+#[async_trait]
+impl UListener for CallbackListener {
+    async fn on_receive(&self, msg: UMessage) {
+        unsafe { (self.callback)(&msg, self.user_data) }
+    }
+}
+
+struct RegisteredCallback {
+    source_filter: UUri,
+    sink_filter: Option<UUri>,
+    listener: Arc<dyn UListener>,
+}
+
+/// An opaque handle to a [`LocalTransport`].
+pub struct UpLocalTransport {
+    transport: Arc<LocalTransport>,
+    next_listener_id: AtomicU64,
+    listeners: Mutex<HashMap<u64, RegisteredCallback>>,
+}
+
+/// Creates a new local transport.
+///
+/// The returned handle must be released using [`up_local_transport_free`].
+#[no_mangle]
+pub extern "C" fn up_local_transport_new() -> *mut UpLocalTransport {
+    catch_panic(std::ptr::null_mut(), || {
+        Box::into_raw(Box::new(UpLocalTransport {
+            transport: Arc::new(LocalTransport::default()),
+            next_listener_id: AtomicU64::new(1),
+            listeners: Mutex::new(HashMap::new()),
+        }))
+    })
+}
+
+/// Sends a message via a local transport.
+///
+/// The message is dispatched to the matching callbacks before this function returns.
+///
+/// # Returns
+///
+/// `OK`, the error code returned by the transport, or `FAILED_PRECONDITION` if invoked from
+/// a thread of a single-threaded Tokio runtime.
+///
+/// # Safety
+///
+/// `transport` must be a valid transport handle and `message` must be a valid message handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_local_transport_send(
+    transport: *const UpLocalTransport,
+    message: *const UMessage,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let (Some(transport), Some(message)) = (transport.as_ref(), message.as_ref()) else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        let local_transport = transport.transport.clone();
+        let message = message.to_owned();
+        match run_on_runtime(async move { local_transport.send(message).await }) {
+            Ok(Ok(())) => status(UCode::OK),
+            Ok(Err(e)) => e.get_code().value(),
+            Err(code) => status(code),
+        }
+    })
+}
+
+/// Registers a callback for messages matching given source and sink filters.
+///
+/// # Arguments
+///
+/// * `transport` - The transport to register the callback with.
+/// * `source_filter` - The pattern that the messages' source address must match.
+/// * `sink_filter` - The pattern that the messages' sink address must match or `NULL` for
+///                   matching messages without a sink (i.e. Publish messages).
+/// * `callback` - The function to invoke for each matching message.
+/// * `user_data` - Data to pass into the callback, must be safe to use from any thread.
+/// * `listener_id` - The location to store the identifier of the registration at.
+///
+/// # Safety
+///
+/// `transport` must be a valid transport handle, `source_filter` must be a valid URI handle,
+/// `sink_filter` must be `NULL` or a valid URI handle and `listener_id` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn up_local_transport_register_listener(
+    transport: *const UpLocalTransport,
+    source_filter: *const UUri,
+    sink_filter: *const UUri,
+    callback: Option<UpMessageCallback>,
+    user_data: *mut c_void,
+    listener_id: *mut u64,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let (Some(transport), Some(source_filter), Some(callback)) =
+            (transport.as_ref(), source_filter.as_ref(), callback)
+        else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        if listener_id.is_null() {
+            return status(UCode::INVALID_ARGUMENT);
+        }
+        let registration = RegisteredCallback {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.as_ref().cloned(),
+            listener: Arc::new(CallbackListener {
+                callback,
+                user_data,
+            }),
+        };
+        let local_transport = transport.transport.clone();
+        let source_filter = registration.source_filter.clone();
+        let sink_filter = registration.sink_filter.clone();
+        let listener = registration.listener.clone();
+        match run_on_runtime(async move {
+            local_transport
+                .register_listener(&source_filter, sink_filter.as_ref(), listener)
+                .await
+        }) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return e.get_code().value(),
+            Err(code) => return status(code),
+        }
+        let id = transport.next_listener_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut listeners) = transport.listeners.lock() {
+            listeners.insert(id, registration);
+        }
+        *listener_id = id;
+        status(UCode::OK)
+    })
+}
+
+/// Unregisters a callback.
+///
+/// # Returns
+///
+/// `OK` or `NOT_FOUND` if no callback has been registered using the given identifier.
+///
+/// # Safety
+///
+/// `transport` must be a valid transport handle.
+#[no_mangle]
+pub unsafe extern "C" fn up_local_transport_unregister_listener(
+    transport: *const UpLocalTransport,
+    listener_id: u64,
+) -> i32 {
+    catch_panic(status(UCode::INTERNAL), || {
+        let Some(transport) = transport.as_ref() else {
+            return status(UCode::INVALID_ARGUMENT);
+        };
+        let Some(registration) = transport
+            .listeners
+            .lock()
+            .ok()
+            .and_then(|mut listeners| listeners.remove(&listener_id))
+        else {
+            return status(UCode::NOT_FOUND);
+        };
+        let local_transport = transport.transport.clone();
+        match run_on_runtime(async move {
+            local_transport
+                .unregister_listener(
+                    &registration.source_filter,
+                    registration.sink_filter.as_ref(),
+                    registration.listener,
+                )
+                .await
+        }) {
+            Ok(Ok(())) => status(UCode::OK),
+            Ok(Err(e)) => e.get_code().value(),
+            Err(code) => status(code),
+        }
+    })
+}
+
+/// Releases a local transport.
+///
+/// # Safety
+///
+/// `transport` must be `NULL` or a transport handle that is owned by the caller.
+#[no_mangle]
+pub unsafe extern "C" fn up_local_transport_free(transport: *mut UpLocalTransport) {
+    catch_panic((), || {
+        if !transport.is_null() {
+            drop(Box::from_raw(transport));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicI32, AtomicUsize};
+
+    unsafe extern "C" fn count_messages(message: *const UMessage, user_data: *mut c_void) {
+        assert!(up_message_get_type(message) > 0);
+        (*(user_data as *const AtomicUsize)).fetch_add(1, Ordering::SeqCst);
+    }
+
+    struct Replier {
+        transport: *const UpLocalTransport,
+        reply: *const UMessage,
+        reply_status: AtomicI32,
+    }
+
+    unsafe extern "C" fn send_reply(_message: *const UMessage, user_data: *mut c_void) {
+        let replier = &*(user_data as *const Replier);
+        let status = up_local_transport_send(replier.transport, replier.reply);
+        replier.reply_status.store(status, Ordering::SeqCst);
+    }
+
+    unsafe fn new_topic(resource_id: u16) -> *mut UUri {
+        let mut topic = std::ptr::null_mut();
+        let authority = CString::new("my-vehicle").unwrap();
+        assert_eq!(
+            up_uri_new(authority.as_ptr(), 0xA8000, 0x02, resource_id, &mut topic),
+            0
+        );
+        topic
+    }
+
+    unsafe fn new_message(topic: *const UUri) -> *mut UMessage {
+        let mut message = std::ptr::null_mut();
+        assert_eq!(
+            up_message_build_publish(topic, std::ptr::null(), 0, 0, &mut message),
+            0
+        );
+        message
+    }
+
+    unsafe fn register(
+        transport: *const UpLocalTransport,
+        topic: *const UUri,
+        callback: UpMessageCallback,
+        user_data: *const c_void,
+    ) -> u64 {
+        let mut listener_id = 0;
+        assert_eq!(
+            up_local_transport_register_listener(
+                transport,
+                topic,
+                std::ptr::null(),
+                Some(callback),
+                user_data as *mut c_void,
+                &mut listener_id,
+            ),
+            0
+        );
+        listener_id
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        unsafe {
+            let mut topic = std::ptr::null_mut();
+            let uri = CString::new("//my-vehicle/A8000/2/8A50").unwrap();
+            assert_eq!(up_uri_parse(uri.as_ptr(), &mut topic), 0);
+            assert_eq!(up_uri_get_entity_id(topic), 0xA8000);
+
+            let payload = b"locked";
+            let mut message = std::ptr::null_mut();
+            assert_eq!(
+                up_message_build_publish(
+                    topic,
+                    payload.as_ptr(),
+                    payload.len(),
+                    UPayloadFormat::UPAYLOAD_FORMAT_TEXT.value(),
+                    &mut message
+                ),
+                0
+            );
+
+            let mut buf = std::ptr::null_mut();
+            let mut len = 0;
+            assert_eq!(up_message_serialize(message, &mut buf, &mut len), 0);
+            let mut parsed = std::ptr::null_mut();
+            assert_eq!(up_message_parse(buf, len, &mut parsed), 0);
+            assert_eq!(*parsed, *message);
+
+            let source = up_message_get_source(parsed);
+            let source_str = up_uri_to_string(source);
+            assert_eq!(
+                CStr::from_ptr(source_str).to_str().unwrap(),
+                "//my-vehicle/A8000/2/8A50"
+            );
+
+            up_string_free(source_str);
+            up_uri_free(source);
+            up_message_free(parsed);
+            up_bytes_free(buf, len);
+            up_message_free(message);
+            up_uri_free(topic);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_buffer() {
+        let mut message = std::ptr::null_mut();
+        let buf = [0xffu8, 0x01, 0x02];
+        unsafe {
+            assert_eq!(
+                up_message_parse(buf.as_ptr(), buf.len(), &mut message),
+                UCode::INVALID_ARGUMENT.value()
+            );
+        }
+        assert!(message.is_null());
+    }
+
+    #[test]
+    fn test_local_transport_dispatches_to_callback() {
+        let received = AtomicUsize::new(0);
+        unsafe {
+            let transport = up_local_transport_new();
+            let mut topic = std::ptr::null_mut();
+            let authority = CString::new("my-vehicle").unwrap();
+            assert_eq!(
+                up_uri_new(authority.as_ptr(), 0xA8000, 0x02, 0x8A50, &mut topic),
+                0
+            );
+            let mut listener_id = 0;
+            assert_eq!(
+                up_local_transport_register_listener(
+                    transport,
+                    topic,
+                    std::ptr::null(),
+                    Some(count_messages),
+                    &received as *const AtomicUsize as *mut c_void,
+                    &mut listener_id,
+                ),
+                0
+            );
+
+            let mut message = std::ptr::null_mut();
+            assert_eq!(
+                up_message_build_publish(topic, std::ptr::null(), 0, 0, &mut message),
+                0
+            );
+            assert_eq!(up_local_transport_send(transport, message), 0);
+            assert_eq!(received.load(Ordering::SeqCst), 1);
+
+            assert_eq!(
+                up_local_transport_unregister_listener(transport, listener_id),
+                0
+            );
+            assert_eq!(up_local_transport_send(transport, message), 0);
+            assert_eq!(received.load(Ordering::SeqCst), 1);
+            assert_eq!(
+                up_local_transport_unregister_listener(transport, listener_id),
+                UCode::NOT_FOUND.value()
+            );
+
+            up_message_free(message);
+            up_uri_free(topic);
+            up_local_transport_free(transport);
+        }
+    }
+
+    #[test]
+    fn test_callback_can_send_reply() {
+        unsafe {
+            // GIVEN a callback which sends a reply for each received request
+            let transport = up_local_transport_new();
+            let request_topic = new_topic(0x8A50);
+            let reply_topic = new_topic(0x8A51);
+            let request = new_message(request_topic);
+            let reply = new_message(reply_topic);
+            let replier = Replier {
+                transport,
+                reply,
+                reply_status: AtomicI32::new(-1),
+            };
+            register(
+                transport,
+                request_topic,
+                send_reply,
+                &replier as *const Replier as *const c_void,
+            );
+            let received_replies = AtomicUsize::new(0);
+            register(
+                transport,
+                reply_topic,
+                count_messages,
+                &received_replies as *const AtomicUsize as *const c_void,
+            );
+
+            // WHEN sending a request
+            assert_eq!(up_local_transport_send(transport, request), 0);
+
+            // THEN the reply has been sent and dispatched from within the callback
+            assert_eq!(replier.reply_status.load(Ordering::SeqCst), 0);
+            assert_eq!(received_replies.load(Ordering::SeqCst), 1);
+
+            up_message_free(reply);
+            up_message_free(request);
+            up_uri_free(reply_topic);
+            up_uri_free(request_topic);
+            up_local_transport_free(transport);
+        }
+    }
+}
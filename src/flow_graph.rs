@@ -0,0 +1,356 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides means to visualize the communication topology of a running system.
+
+A [`MessageFlowGraph`] keeps track of the uEntities that have been observed to exchange messages.
+Each edge of the graph connects the uEntity that has sent a message (the source) with the uEntity that
+the message has been sent to (the sink) and counts the number of messages of a particular type that have
+been observed. Published messages do not have a sink, so edges representing published messages
+lead from the publisher to the topic that the messages have been published to.
+
+The graph can be fed with messages by means of [`MessageFlowGraph::observe`], e.g. from within
+audit or metrics hooks, or it can be registered as a [`UListener`] with a transport directly.
+The collected data can then be exported to [Graphviz DOT](https://graphviz.org/doc/info/lang.html)
+or JSON (with feature `json`).
+*/
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use protobuf::Enum;
+
+use crate::{UListener, UMessage, UMessageType, UUri};
+
+/// A vertex of a [`MessageFlowGraph`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FlowNode {
+    /// A uEntity.
+    Entity {
+        /// The authority that the uEntity runs on.
+        authority: String,
+        /// The uEntity's identifier.
+        ue_id: u32,
+    },
+    /// A topic that messages have been published to.
+    Topic(String),
+}
+
+impl FlowNode {
+    fn entity(uri: &UUri) -> Self {
+        FlowNode::Entity {
+            authority: uri.authority_name.to_owned(),
+            ue_id: uri.ue_id,
+        }
+    }
+}
+
+impl Display for FlowNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlowNode::Entity { authority, ue_id } => write!(f, "//{}/{:X}", authority, ue_id),
+            FlowNode::Topic(topic) => f.write_str(topic),
+        }
+    }
+}
+
+/// A directed edge of a [`MessageFlowGraph`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FlowEdge {
+    /// The sender of the messages.
+    pub source: FlowNode,
+    /// The receiver of the messages.
+    pub sink: FlowNode,
+    /// The type of the messages.
+    pub message_type: UMessageType,
+}
+
+impl Ord for FlowEdge {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.source, &self.sink, self.message_type.value()).cmp(&(
+            &other.source,
+            &other.sink,
+            other.message_type.value(),
+        ))
+    }
+}
+
+impl PartialOrd for FlowEdge {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The counters of a [`FlowEdge`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FlowCounters {
+    /// The number of messages that have been observed.
+    pub messages: u64,
+    /// The total size of the payload of the messages in bytes.
+    pub payload_bytes: u64,
+}
+
+/// An in-memory graph of observed message flows between uEntities.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{flow_graph::MessageFlowGraph, UMessageBuilder, UUri};
+///
+/// let graph = MessageFlowGraph::new();
+/// let request = UMessageBuilder::request(
+///     UUri::try_from("//vehicle/A100/1/1").unwrap(),
+///     UUri::try_from("//cloud/B200/1/0").unwrap(),
+///     5_000,
+/// )
+/// .build()
+/// .unwrap();
+/// graph.observe(&request);
+///
+/// assert_eq!(graph.edges().len(), 1);
+/// assert!(graph
+///     .to_dot()
+///     .contains(r#""//cloud/B200" -> "//vehicle/A100" [label="request (1)"];"#));
+/// ```
+#[derive(Debug, Default)]
+pub struct MessageFlowGraph {
+    edges: Mutex<BTreeMap<FlowEdge, FlowCounters>>,
+}
+
+impl MessageFlowGraph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a message.
+    ///
+    /// Messages without a type or source are ignored.
+    pub fn observe(&self, message: &UMessage) {
+        let Some(attributes) = message.attributes.as_ref() else {
+            return;
+        };
+        let (Ok(message_type), Some(source)) =
+            (attributes.type_.enum_value(), attributes.source.as_ref())
+        else {
+            return;
+        };
+        let edge = match (message_type, attributes.sink.as_ref()) {
+            (UMessageType::UMESSAGE_TYPE_UNSPECIFIED, _) => return,
+            (UMessageType::UMESSAGE_TYPE_PUBLISH, _) => FlowEdge {
+                source: FlowNode::entity(source),
+                sink: FlowNode::Topic(source.to_uri(false)),
+                message_type,
+            },
+            (_, Some(sink)) => FlowEdge {
+                source: FlowNode::entity(source),
+                sink: FlowNode::entity(sink),
+                message_type,
+            },
+            (_, None) => return,
+        };
+        let payload_bytes = message.payload.as_ref().map_or(0, |p| p.len() as u64);
+        if let Ok(mut edges) = self.edges.lock() {
+            let counters = edges.entry(edge).or_default();
+            counters.messages += 1;
+            counters.payload_bytes += payload_bytes;
+        }
+    }
+
+    /// Removes all edges from the graph.
+    pub fn clear(&self) {
+        if let Ok(mut edges) = self.edges.lock() {
+            edges.clear();
+        }
+    }
+
+    /// Gets a snapshot of the graph's edges and their counters, ordered by source, sink and message type.
+    pub fn edges(&self) -> Vec<(FlowEdge, FlowCounters)> {
+        self.edges.lock().map_or(vec![], |edges| {
+            edges
+                .iter()
+                .map(|(edge, counters)| (edge.to_owned(), counters.to_owned()))
+                .collect()
+        })
+    }
+
+    /// Exports the graph in Graphviz DOT format.
+    ///
+    /// Edges are labeled with the message type and the number of observed messages.
+    /// Topics are rendered as boxes.
+    pub fn to_dot(&self) -> String {
+        let edges = self.edges();
+        let mut dot = String::from("digraph uprotocol {\n");
+        let topics: std::collections::BTreeSet<&FlowNode> = edges
+            .iter()
+            .map(|(edge, _)| &edge.sink)
+            .filter(|node| matches!(node, FlowNode::Topic(_)))
+            .collect();
+        for topic in topics {
+            let _ = writeln!(dot, "  \"{}\" [shape=box];", topic);
+        }
+        for (edge, counters) in &edges {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{} ({})\"];",
+                edge.source,
+                edge.sink,
+                type_label(edge.message_type),
+                counters.messages
+            );
+        }
+        dot.push('}');
+        dot
+    }
+
+    /// Exports the graph as a JSON document.
+    ///
+    /// The document contains an array of edges, each having `source`, `sink`, `type`,
+    /// `messages` and `payloadBytes` properties.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let edges: Vec<serde_json::Value> = self
+            .edges()
+            .into_iter()
+            .map(|(edge, counters)| {
+                serde_json::json!({
+                    "source": edge.source.to_string(),
+                    "sink": edge.sink.to_string(),
+                    "type": type_label(edge.message_type),
+                    "messages": counters.messages,
+                    "payloadBytes": counters.payload_bytes,
+                })
+            })
+            .collect();
+        serde_json::json!({ "edges": edges }).to_string()
+    }
+}
+
+fn type_label(message_type: UMessageType) -> &'static str {
+    match message_type {
+        UMessageType::UMESSAGE_TYPE_PUBLISH => "publish",
+        UMessageType::UMESSAGE_TYPE_NOTIFICATION => "notification",
+        UMessageType::UMESSAGE_TYPE_REQUEST => "request",
+        UMessageType::UMESSAGE_TYPE_RESPONSE => "response",
+        UMessageType::UMESSAGE_TYPE_UNSPECIFIED => "unspecified",
+    }
+}
+
+#[async_trait]
+impl UListener for MessageFlowGraph {
+    async fn on_receive(&self, msg: UMessage) {
+        self.observe(&msg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{UMessageBuilder, UPayloadFormat};
+
+    fn uri(uri: &str) -> UUri {
+        UUri::try_from(uri).unwrap()
+    }
+
+    #[test]
+    fn test_observe_counts_messages_per_edge() {
+        let graph = MessageFlowGraph::new();
+        let topic = uri("//vehicle/A100/1/8001");
+        for _ in 0..3 {
+            graph.observe(
+                &UMessageBuilder::publish(topic.clone())
+                    .build_with_payload("on", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+                    .unwrap(),
+            );
+        }
+        let request =
+            UMessageBuilder::request(uri("//cloud/B200/1/7"), uri("//vehicle/A100/1/0"), 1_000)
+                .build()
+                .unwrap();
+        let response = UMessageBuilder::response_for_request(request.attributes.as_ref().unwrap())
+            .build()
+            .unwrap();
+        graph.observe(&request);
+        graph.observe(&response);
+
+        let edges = graph.edges();
+        assert_eq!(edges.len(), 3);
+        let (publish_edge, publish_counters) = edges
+            .iter()
+            .find(|(edge, _)| edge.message_type == UMessageType::UMESSAGE_TYPE_PUBLISH)
+            .unwrap();
+        assert_eq!(publish_edge.sink, FlowNode::Topic(topic.to_uri(false)));
+        assert_eq!(
+            publish_counters,
+            &FlowCounters {
+                messages: 3,
+                payload_bytes: 6
+            }
+        );
+        let (response_edge, _) = edges
+            .iter()
+            .find(|(edge, _)| edge.message_type == UMessageType::UMESSAGE_TYPE_RESPONSE)
+            .unwrap();
+        assert_eq!(
+            response_edge.source,
+            FlowNode::Entity {
+                authority: "cloud".to_string(),
+                ue_id: 0xB200
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_dot_renders_topics_as_boxes() {
+        let graph = MessageFlowGraph::new();
+        graph.observe(
+            &UMessageBuilder::publish(uri("//vehicle/A100/1/8001"))
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(
+            graph.to_dot(),
+            "digraph uprotocol {\n  \"//vehicle/A100/1/8001\" [shape=box];\n  \"//vehicle/A100\" -> \"//vehicle/A100/1/8001\" [label=\"publish (1)\"];\n}"
+        );
+        graph.clear();
+        assert!(graph.edges().is_empty());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_to_json_contains_counters() {
+        let graph = MessageFlowGraph::new();
+        graph.observe(
+            &UMessageBuilder::notification(uri("//vehicle/A100/1/8001"), uri("//vehicle/B100/1/0"))
+                .build()
+                .unwrap(),
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"edges": [{
+                "source": "//vehicle/A100",
+                "sink": "//vehicle/B100",
+                "type": "notification",
+                "messages": 1,
+                "payloadBytes": 0
+            }]})
+        );
+    }
+}
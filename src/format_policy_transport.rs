@@ -0,0 +1,430 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a UTransport decorator which restricts the payload formats of the messages being exchanged.
+
+Deployments may require that only well known payload formats are used on certain transports,
+e.g. only protobuf encoded payloads on a safety relevant bus. Wrapping the transport in a
+[`FormatPolicyTransport`] makes sure that messages with other payload formats are neither sent
+nor passed on to listeners.
+*/
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::{
+    utransport::send_checked_batch, ComparableListener, UCode, UListener, UMessage, UPayloadFormat,
+    UStatus, UTransport, UUri,
+};
+
+#[derive(Eq, PartialEq, Hash)]
+struct Registration {
+    source_filter: UUri,
+    sink_filter: Option<UUri>,
+    listener: ComparableListener,
+}
+
+struct FormatPolicy {
+    allowed_formats: Vec<UPayloadFormat>,
+    outgoing_violations: AtomicU64,
+    incoming_violations: AtomicU64,
+}
+
+impl FormatPolicy {
+    /// Checks if a message's payload format is allowed.
+    ///
+    /// Messages without payload are always allowed.
+    fn is_allowed(&self, message: &UMessage) -> bool {
+        if message.payload.is_none() {
+            return true;
+        }
+        let payload_format = message
+            .attributes
+            .as_ref()
+            .map_or(UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED, |attribs| {
+                attribs.payload_format.enum_value_or_default()
+            });
+        self.allowed_formats.contains(&payload_format)
+    }
+
+    fn check_outgoing(&self, message: &UMessage) -> Result<(), UStatus> {
+        if self.is_allowed(message) {
+            Ok(())
+        } else {
+            self.outgoing_violations.fetch_add(1, Ordering::Relaxed);
+            Err(UStatus::fail_with_code(
+                UCode::INVALID_ARGUMENT,
+                "payload format is not allowed on this transport",
+            ))
+        }
+    }
+
+    fn check_incoming(&self, message: &UMessage) -> Result<(), UStatus> {
+        if self.is_allowed(message) {
+            Ok(())
+        } else {
+            self.incoming_violations.fetch_add(1, Ordering::Relaxed);
+            Err(UStatus::fail_with_code(
+                UCode::INVALID_ARGUMENT,
+                "received message with payload format that is not allowed on this transport",
+            ))
+        }
+    }
+}
+
+/// A [`UListener`] which drops messages with a payload format that is not allowed.
+struct FormatFilter {
+    policy: Arc<FormatPolicy>,
+    listener: Arc<dyn UListener>,
+}
+
+#[async_trait]
+impl UListener for FormatFilter {
+    async fn on_receive(&self, msg: UMessage) {
+        if let Err(e) = self.policy.check_incoming(&msg) {
+            debug!("dropping message: {}", e.get_message());
+            return;
+        }
+        self.listener.on_receive(msg).await;
+    }
+}
+
+/// A transport that only exchanges messages having one of a given set of payload formats.
+///
+/// Sending a message with another payload format fails with [`UCode::INVALID_ARGUMENT`].
+/// Received messages with another payload format are not passed on to registered listeners and
+/// cause [`UTransport::receive`] to fail with [`UCode::INVALID_ARGUMENT`].
+/// Messages without a payload are not subject to the policy.
+///
+/// The number of rejected messages can be retrieved for monitoring purposes.
+pub struct FormatPolicyTransport {
+    transport: Arc<dyn UTransport>,
+    policy: Arc<FormatPolicy>,
+    // registered listener -> filter registered with underlying transport
+    registrations: Mutex<HashMap<Registration, Arc<dyn UListener>>>,
+}
+
+impl FormatPolicyTransport {
+    /// Creates a new transport for a given underlying transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to delegate to.
+    /// * `allowed_formats` - The payload formats that messages may have.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use up_rust::{
+    ///     format_policy_transport::FormatPolicyTransport, local_transport::LocalTransport,
+    ///     UPayloadFormat,
+    /// };
+    ///
+    /// let transport = FormatPolicyTransport::new(
+    ///     Arc::new(LocalTransport::default()),
+    ///     &[UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF],
+    /// );
+    /// assert_eq!(transport.outgoing_violations(), 0);
+    /// ```
+    pub fn new(transport: Arc<dyn UTransport>, allowed_formats: &[UPayloadFormat]) -> Self {
+        FormatPolicyTransport {
+            transport,
+            policy: Arc::new(FormatPolicy {
+                allowed_formats: allowed_formats.to_vec(),
+                outgoing_violations: AtomicU64::new(0),
+                incoming_violations: AtomicU64::new(0),
+            }),
+            registrations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Gets the number of messages that could not be sent because of their payload format.
+    pub fn outgoing_violations(&self) -> u64 {
+        self.policy.outgoing_violations.load(Ordering::Relaxed)
+    }
+
+    /// Gets the number of received messages that have been dropped because of their payload format.
+    pub fn incoming_violations(&self) -> u64 {
+        self.policy.incoming_violations.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl UTransport for FormatPolicyTransport {
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        self.policy.check_outgoing(&message)?;
+        self.transport.send(message).await
+    }
+
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        send_checked_batch(self.transport.as_ref(), messages, |message| {
+            self.policy.check_outgoing(&message).map(|_| message)
+        })
+        .await
+    }
+
+    async fn receive(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Result<UMessage, UStatus> {
+        let message = self.transport.receive(source_filter, sink_filter).await?;
+        self.policy.check_incoming(&message)?;
+        Ok(message)
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let registration = Registration {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.cloned(),
+            listener: ComparableListener::new(listener.clone()),
+        };
+        let filter = self
+            .registrations
+            .lock()
+            .ok()
+            .and_then(|registrations| registrations.get(&registration).cloned())
+            .unwrap_or_else(|| {
+                Arc::new(FormatFilter {
+                    policy: self.policy.clone(),
+                    listener,
+                })
+            });
+        self.transport
+            .register_listener(source_filter, sink_filter, filter.clone())
+            .await?;
+        if let Ok(mut registrations) = self.registrations.lock() {
+            registrations.insert(registration, filter);
+        }
+        Ok(())
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let registration = Registration {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.cloned(),
+            listener: ComparableListener::new(listener),
+        };
+        let Some(filter) = self
+            .registrations
+            .lock()
+            .ok()
+            .and_then(|registrations| registrations.get(&registration).cloned())
+        else {
+            return Err(UStatus::fail_with_code(
+                UCode::NOT_FOUND,
+                "no such listener registered",
+            ));
+        };
+        self.transport
+            .unregister_listener(source_filter, sink_filter, filter)
+            .await?;
+        if let Ok(mut registrations) = self.registrations.lock() {
+            registrations.remove(&registration);
+        }
+        Ok(())
+    }
+
+    async fn probe(&self, peer_authority: &str) -> Result<(), UStatus> {
+        self.transport.probe(peer_authority).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utransport::{MockTransport, MockUListener},
+        UMessageBuilder,
+    };
+
+    fn publish_message(format: UPayloadFormat) -> UMessage {
+        let topic = UUri::try_from_parts("my-vehicle", 0x1000, 0x01, 0xA100).unwrap();
+        UMessageBuilder::publish(topic)
+            .build_with_payload("payload", format)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_for_disallowed_payload_format() {
+        // GIVEN a transport that only allows protobuf payloads
+        let mut underlying_transport = MockTransport::new();
+        underlying_transport
+            .expect_do_send()
+            .once()
+            .return_const(Ok(()));
+        let transport = FormatPolicyTransport::new(
+            Arc::new(underlying_transport),
+            &[UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF],
+        );
+
+        // WHEN sending a message with a JSON payload
+        let result = transport
+            .send(publish_message(UPayloadFormat::UPAYLOAD_FORMAT_JSON))
+            .await;
+
+        // THEN the attempt fails with INVALID_ARGUMENT
+        assert!(result.is_err_and(|e| e.get_code() == UCode::INVALID_ARGUMENT));
+        assert_eq!(transport.outgoing_violations(), 1);
+
+        // AND a message with a protobuf payload is sent
+        assert!(transport
+            .send(publish_message(UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF))
+            .await
+            .is_ok());
+        assert_eq!(transport.outgoing_violations(), 1);
+    }
+
+    // A transport that records the messages of the batches that it has been asked to send.
+    #[derive(Default)]
+    struct BatchingTransport {
+        batches: Mutex<Vec<Vec<UMessage>>>,
+    }
+
+    #[async_trait]
+    impl UTransport for BatchingTransport {
+        async fn send(&self, _message: UMessage) -> Result<(), UStatus> {
+            Err(UStatus::fail_with_code(
+                UCode::INTERNAL,
+                "messages should have been sent as a batch",
+            ))
+        }
+
+        async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+            let outcomes = messages.iter().map(|_msg| Ok(())).collect();
+            self.batches.lock().unwrap().push(messages);
+            outcomes
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_batch_forwards_allowed_messages_only() {
+        // GIVEN a transport that only allows protobuf payloads
+        let underlying_transport = Arc::new(BatchingTransport::default());
+        let transport = FormatPolicyTransport::new(
+            underlying_transport.clone(),
+            &[UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF],
+        );
+
+        // WHEN sending a batch of messages with protobuf and JSON payloads
+        let first = publish_message(UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF);
+        let second = publish_message(UPayloadFormat::UPAYLOAD_FORMAT_JSON);
+        let third = publish_message(UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF);
+        let outcomes = transport
+            .send_batch(vec![first.clone(), second, third.clone()])
+            .await;
+
+        // THEN the messages with protobuf payloads are passed on to the underlying transport's
+        // send_batch function
+        assert_eq!(
+            *underlying_transport.batches.lock().unwrap(),
+            vec![vec![first, third]]
+        );
+        // and the outcomes are reported in the order of the messages
+        assert_eq!(outcomes.len(), 3);
+        assert!(outcomes[0].is_ok());
+        assert!(outcomes[1]
+            .as_ref()
+            .is_err_and(|e| e.get_code() == UCode::INVALID_ARGUMENT));
+        assert!(outcomes[2].is_ok());
+        assert_eq!(transport.outgoing_violations(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_listener_is_not_invoked_for_disallowed_payload_format() {
+        let captured_listener = Arc::new(Mutex::new(None));
+        let captured_listener_clone = captured_listener.clone();
+
+        // GIVEN a transport that only allows protobuf payloads
+        let mut underlying_transport = MockTransport::new();
+        underlying_transport
+            .expect_do_register_listener()
+            .once()
+            .returning(move |_source_filter, _sink_filter, listener| {
+                *captured_listener_clone.lock().unwrap() = Some(listener);
+                Ok(())
+            });
+        let captured_listener_clone = captured_listener.clone();
+        underlying_transport
+            .expect_do_unregister_listener()
+            .once()
+            .returning(move |_source_filter, _sink_filter, listener| {
+                // THEN the filter that had been registered is unregistered again
+                assert!(Arc::ptr_eq(
+                    captured_listener_clone.lock().unwrap().as_ref().unwrap(),
+                    &listener
+                ));
+                Ok(())
+            });
+        let transport = FormatPolicyTransport::new(
+            Arc::new(underlying_transport),
+            &[UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF],
+        );
+
+        // and a listener that expects to be invoked once only
+        let mut listener = MockUListener::new();
+        listener
+            .expect_on_receive()
+            .once()
+            .withf(|msg| {
+                msg.attributes
+                    .get_or_default()
+                    .payload_format
+                    .enum_value_or_default()
+                    == UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF
+            })
+            .return_const(());
+        let listener: Arc<dyn UListener> = Arc::new(listener);
+        let source_filter = UUri::any();
+        assert!(transport
+            .register_listener(&source_filter, None, listener.clone())
+            .await
+            .is_ok());
+
+        // WHEN the underlying transport dispatches messages with different payload formats
+        let registered_listener = captured_listener.lock().unwrap().clone().unwrap();
+        registered_listener
+            .on_receive(publish_message(UPayloadFormat::UPAYLOAD_FORMAT_TEXT))
+            .await;
+        registered_listener
+            .on_receive(publish_message(UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF))
+            .await;
+
+        // THEN the listener is only invoked for the message with the protobuf payload
+        assert_eq!(transport.incoming_violations(), 1);
+        assert!(transport
+            .unregister_listener(&source_filter, None, listener)
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,290 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides means to estimate the number of events that have been lost on their way from
+a publisher to a subscriber.
+
+uProtocol messages do not contain sequence numbers. However, each message ID contains the point
+in time at which the message has been created. For topics that events are published to
+periodically, a [`GapDetector`] learns the typical interval between subsequent events and
+counts intervals that are significantly longer than usual as gaps, estimating the number of
+events that would have been published during the gap.
+*/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::{diagnostics::TopicGapDiagnostics, UListener, UMessage, UUri};
+
+const DEFAULT_TOLERANCE: f64 = 2.0;
+const MIN_INTERVAL_SAMPLES: u64 = 3;
+// weight of the latest interval in the moving average
+const SMOOTHING_FACTOR: f64 = 0.2;
+
+/// A hook for exporting the counters of a [`GapDetector`] to a metrics system.
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+pub trait GapMetrics: Send + Sync {
+    /// Invoked for each message that has been received for a topic.
+    fn on_message(&self, topic: &UUri);
+
+    /// Invoked for each gap that has been detected for a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic.
+    /// * `estimated_missing` - The estimated number of events that have been lost.
+    fn on_gap(&self, topic: &UUri, estimated_missing: u64);
+}
+
+#[derive(Default)]
+struct TopicStats {
+    latest_time: Option<u64>,
+    mean_interval: Option<f64>,
+    interval_samples: u64,
+    messages_received: u64,
+    estimated_messages_lost: u64,
+}
+
+impl TopicStats {
+    /// Records the creation time of a received message.
+    ///
+    /// # Returns
+    ///
+    /// The estimated number of messages that have been lost since the previously
+    /// received message.
+    fn record(&mut self, time: u64, tolerance: f64) -> u64 {
+        self.messages_received += 1;
+        let Some(latest_time) = self.latest_time.filter(|latest| *latest < time) else {
+            // first or out-of-order message, nothing to learn from
+            self.latest_time = self.latest_time.max(Some(time));
+            return 0;
+        };
+        self.latest_time = Some(time);
+        let interval = (time - latest_time) as f64;
+
+        if let Some(mean) = self
+            .mean_interval
+            .filter(|mean| self.interval_samples >= MIN_INTERVAL_SAMPLES && *mean > 0.0)
+        {
+            if interval > tolerance * mean {
+                // do not include the gap in the average
+                let missing = ((interval / mean).round() as u64).saturating_sub(1).max(1);
+                self.estimated_messages_lost += missing;
+                return missing;
+            }
+        }
+        self.mean_interval = Some(
+            self.mean_interval
+                .map_or(interval, |mean| mean + SMOOTHING_FACTOR * (interval - mean)),
+        );
+        self.interval_samples += 1;
+        0
+    }
+}
+
+/// A [`UListener`] that estimates the number of lost events per topic before forwarding
+/// received messages to another listener.
+///
+/// The detector considers the interval between the creation times of two subsequent events
+/// published to the same topic a gap, if the interval exceeds the average interval by a
+/// [configurable factor](`Self::with_tolerance`). The number of lost events is then estimated
+/// from the length of the gap. Topics that events are published to irregularly will produce
+/// false positives.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use up_rust::{gap_detector::GapDetector, UListener, UMessage};
+///
+/// struct MyListener;
+///
+/// #[async_trait::async_trait]
+/// impl UListener for MyListener {
+///     async fn on_receive(&self, _msg: UMessage) {}
+/// }
+///
+/// let listener: Arc<dyn UListener> = Arc::new(GapDetector::new(Arc::new(MyListener)));
+/// ```
+pub struct GapDetector {
+    listener: Arc<dyn UListener>,
+    metrics: Option<Arc<dyn GapMetrics>>,
+    tolerance: f64,
+    topics: Mutex<HashMap<UUri, TopicStats>>,
+}
+
+impl GapDetector {
+    /// Creates a new detector.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to forward received messages to.
+    pub fn new(listener: Arc<dyn UListener>) -> Self {
+        GapDetector {
+            listener,
+            metrics: None,
+            tolerance: DEFAULT_TOLERANCE,
+            topics: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the hook to report counters to.
+    pub fn with_metrics(mut self, metrics: Arc<dyn GapMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Sets the factor by which an interval needs to exceed the average interval
+    /// in order to be considered a gap.
+    ///
+    /// The default tolerance is 2.0.
+    ///
+    /// # Panics
+    ///
+    /// if the tolerance is not greater than 1.0.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        assert!(tolerance > 1.0, "tolerance must be greater than 1.0");
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Records the reception of a message.
+    ///
+    /// Messages without a valid uProtocol UUID or without a source are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The estimated number of messages that have been lost since the previous message
+    /// published to the same topic has been received.
+    pub fn observe(&self, message: &UMessage) -> u64 {
+        let Some(attributes) = message.attributes.as_ref() else {
+            return 0;
+        };
+        let (Some(topic), Some(time)) = (
+            attributes.source.as_ref(),
+            attributes.id.as_ref().and_then(|id| id.get_time()),
+        ) else {
+            return 0;
+        };
+        let missing = match self.topics.lock() {
+            Ok(mut topics) => topics
+                .entry(topic.to_owned())
+                .or_default()
+                .record(time, self.tolerance),
+            Err(_e) => return 0,
+        };
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.on_message(topic);
+            if missing > 0 {
+                metrics.on_gap(topic, missing);
+            }
+        }
+        if missing > 0 {
+            debug!(topic = %topic, estimated_missing = missing, "detected gap in events");
+        }
+        missing
+    }
+
+    /// Gets a snapshot of the counters of all topics, ordered by topic.
+    pub fn diagnostics(&self) -> Vec<TopicGapDiagnostics> {
+        let Ok(topics) = self.topics.lock() else {
+            return vec![];
+        };
+        let mut diagnostics: Vec<TopicGapDiagnostics> = topics
+            .iter()
+            .map(|(topic, stats)| TopicGapDiagnostics {
+                topic: topic.to_uri(false),
+                messages_received: stats.messages_received,
+                estimated_messages_lost: stats.estimated_messages_lost,
+            })
+            .collect();
+        diagnostics.sort_by(|a, b| a.topic.cmp(&b.topic));
+        diagnostics
+    }
+}
+
+#[async_trait]
+impl UListener for GapDetector {
+    async fn on_receive(&self, msg: UMessage) {
+        self.observe(&msg);
+        self.listener.on_receive(msg).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{utransport::MockUListener, UMessageBuilder, UUID};
+
+    fn event_created_at(topic: &UUri, time: u64) -> UMessage {
+        UMessageBuilder::publish(topic.to_owned())
+            .with_message_id(UUID {
+                // ver = 0b0111
+                msb: (time << 16) | 0x7000,
+                // variant = 0b10
+                lsb: 0x8000000000000000,
+                ..Default::default()
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_detector_estimates_lost_messages() {
+        // GIVEN a detector with a metrics hook
+        let topic = UUri::try_from_parts("vehicle", 0x1000, 0x01, 0x8001).unwrap();
+        let mut metrics = MockGapMetrics::new();
+        metrics.expect_on_message().times(7).return_const(());
+        metrics
+            .expect_on_gap()
+            .once()
+            .withf(|_topic, estimated_missing| *estimated_missing == 2)
+            .return_const(());
+        let detector =
+            GapDetector::new(Arc::new(MockUListener::new())).with_metrics(Arc::new(metrics));
+
+        // WHEN receiving events that have been published every 100ms
+        for time in [1_000, 1_100, 1_200, 1_300, 1_400] {
+            assert_eq!(detector.observe(&event_created_at(&topic, time)), 0);
+        }
+        // and missing two events
+        assert_eq!(detector.observe(&event_created_at(&topic, 1_700)), 2);
+        // THEN an out-of-order event is not counted as a gap
+        assert_eq!(detector.observe(&event_created_at(&topic, 1_650)), 0);
+
+        // and the counters reflect the estimated loss
+        assert_eq!(
+            detector.diagnostics(),
+            vec![TopicGapDiagnostics {
+                topic: topic.to_uri(false),
+                messages_received: 7,
+                estimated_messages_lost: 2,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detector_forwards_messages() {
+        let topic = UUri::try_from_parts("vehicle", 0x1000, 0x01, 0x8001).unwrap();
+        let mut listener = MockUListener::new();
+        listener.expect_on_receive().once().return_const(());
+        let detector = GapDetector::new(Arc::new(listener));
+
+        detector.on_receive(event_created_at(&topic, 1_000)).await;
+        assert_eq!(detector.diagnostics()[0].messages_received, 1);
+    }
+}
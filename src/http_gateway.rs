@@ -0,0 +1,396 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides support for forwarding uProtocol notifications to off-board HTTP endpoints (webhooks).
+
+A [`WebhookNotifier`] can be registered with any [`UTransport`](crate::UTransport) like any other
+[`UListener`]. It maps each Notification that it receives to a [`CloudEvent`] using the
+[Protobuf Event Format](https://github.com/cloudevents/spec/blob/v1.0.2/cloudevents/formats/protobuf-format.md)
+and POSTs the event to all of its [`WebhookEndpoint`]s. Failed requests are retried according to the
+[`RedeliveryPolicy`](crate::redelivery::RedeliveryPolicy) of the notifier's [`RedeliveryCoordinator`].
+
+This crate does not depend on a particular HTTP client library. Instead, the HTTP requests are
+sent by means of an [`HttpSender`], which can easily be implemented on top of the HTTP client
+that an application already uses. In the same way, requests can be signed by means of a
+[`RequestSigner`], e.g. using an HMAC over the request body.
+*/
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use protobuf::Message;
+use tracing::{debug, warn};
+
+use crate::{
+    redelivery::{AcknowledgingHandler, RedeliveryCoordinator},
+    task_tracker::TaskTracker,
+    CloudEvent, UCode, UListener, UMessage, UStatus, UUri, UriRewriter,
+    CONTENT_TYPE_CLOUDEVENTS_PROTOBUF,
+};
+
+/// The name of the HTTP header that carries the media type of the request body.
+pub const CONTENT_TYPE_HEADER: &str = "content-type";
+
+/// Sends HTTP requests on behalf of a [`WebhookNotifier`].
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+#[async_trait]
+pub trait HttpSender: Send + Sync {
+    /// Sends an HTTP POST request.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to send the request to.
+    /// * `headers` - The (name, value) pairs to include as HTTP headers.
+    /// * `body` - The request body.
+    ///
+    /// # Returns
+    ///
+    /// The HTTP status code of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request could not be sent or no response has been received.
+    async fn post(
+        &self,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Bytes,
+    ) -> Result<u16, UStatus>;
+}
+
+/// Signs the HTTP requests sent to a [`WebhookEndpoint`].
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+pub trait RequestSigner: Send + Sync {
+    /// Creates the signature for a request body.
+    ///
+    /// # Returns
+    ///
+    /// The (name, value) pair of the HTTP header that carries the signature.
+    fn sign(&self, body: &[u8]) -> (String, String);
+}
+
+/// An HTTP endpoint that notifications are forwarded to.
+#[derive(Clone)]
+pub struct WebhookEndpoint {
+    url: String,
+    headers: Vec<(String, String)>,
+    signer: Option<Arc<dyn RequestSigner>>,
+}
+
+impl WebhookEndpoint {
+    /// Creates a new endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to POST the CloudEvents to.
+    pub fn new<T: Into<String>>(url: T) -> Self {
+        WebhookEndpoint {
+            url: url.into(),
+            headers: vec![],
+            signer: None,
+        }
+    }
+
+    /// Adds an HTTP header to include in all requests sent to this endpoint.
+    pub fn with_header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the signer to use for signing the requests sent to this endpoint.
+    pub fn with_signer(mut self, signer: Arc<dyn RequestSigner>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Gets the URL of this endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+// POSTs an event to a single endpoint, the (re-)delivered message is ignored because
+// the event has already been created from it
+struct EndpointDelivery {
+    sender: Arc<dyn HttpSender>,
+    endpoint: WebhookEndpoint,
+    body: Bytes,
+}
+
+#[async_trait]
+impl AcknowledgingHandler for EndpointDelivery {
+    async fn handle_message(&self, _msg: UMessage) -> Result<(), UStatus> {
+        let mut headers = vec![(
+            CONTENT_TYPE_HEADER.to_string(),
+            CONTENT_TYPE_CLOUDEVENTS_PROTOBUF.to_string(),
+        )];
+        headers.extend(self.endpoint.headers.iter().cloned());
+        if let Some(signer) = self.endpoint.signer.as_ref() {
+            headers.push(signer.sign(&self.body));
+        }
+        match self
+            .sender
+            .post(&self.endpoint.url, headers, self.body.clone())
+            .await?
+        {
+            200..=299 => Ok(()),
+            status => Err(UStatus::fail_with_code(
+                UCode::UNAVAILABLE,
+                format!("endpoint responded with HTTP status {status}"),
+            )),
+        }
+    }
+}
+
+/// A [`UListener`] that forwards Notifications to HTTP endpoints as CloudEvents.
+///
+/// Each notification is POSTed to the endpoints on separate tasks, i.e. [`UListener::on_receive`]
+/// returns immediately. Messages of other types are ignored.
+pub struct WebhookNotifier {
+    sender: Arc<dyn HttpSender>,
+    endpoints: Vec<WebhookEndpoint>,
+    source_filter: Option<UUri>,
+    uri_rewriter: UriRewriter,
+    coordinator: Arc<RedeliveryCoordinator>,
+    tasks: TaskTracker,
+}
+
+impl WebhookNotifier {
+    /// Creates a new notifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - The sender to use for POSTing events to the endpoints.
+    /// * `endpoints` - The endpoints to forward notifications to.
+    /// * `coordinator` - The coordinator to use for retrying failed requests. Notifications that could
+    ///   not be forwarded to an endpoint are handed over to the coordinator's dead letter listener.
+    pub fn new(
+        sender: Arc<dyn HttpSender>,
+        endpoints: Vec<WebhookEndpoint>,
+        coordinator: Arc<RedeliveryCoordinator>,
+    ) -> Self {
+        WebhookNotifier {
+            sender,
+            endpoints,
+            source_filter: None,
+            uri_rewriter: UriRewriter::default(),
+            coordinator,
+            tasks: TaskTracker::new("webhook-notifier"),
+        }
+    }
+
+    /// Sets a pattern that the source of notifications must match in order to be forwarded.
+    ///
+    /// This is useful if the notifier has been registered with a transport using a broader filter,
+    /// e.g. for sharing the registration with other listeners.
+    pub fn with_source_filter(mut self, source_filter: UUri) -> Self {
+        self.source_filter = Some(source_filter);
+        self
+    }
+
+    /// Sets the rewriter to apply to the source and sink addresses of notifications.
+    ///
+    /// The addresses are rewritten after the source filter has been applied, i.e. the
+    /// CloudEvents POSTed to the endpoints contain the rewritten addresses.
+    pub fn with_uri_rewriter(mut self, uri_rewriter: UriRewriter) -> Self {
+        self.uri_rewriter = uri_rewriter;
+        self
+    }
+
+    /// Gets the tracker of the tasks forwarding notifications to the endpoints.
+    pub fn task_tracker(&self) -> &TaskTracker {
+        &self.tasks
+    }
+}
+
+#[async_trait]
+impl UListener for WebhookNotifier {
+    async fn on_receive(&self, msg: UMessage) {
+        if !msg.is_notification() {
+            debug!("ignoring message that is not a notification");
+            return;
+        }
+        if let Some(filter) = self.source_filter.as_ref() {
+            if !msg
+                .attributes
+                .source
+                .as_ref()
+                .is_some_and(|source| filter.matches(source))
+            {
+                return;
+            }
+        }
+        let mut msg = msg;
+        self.uri_rewriter.rewrite_message(&mut msg);
+        let body = match CloudEvent::try_from(msg.clone())
+            .map_err(|e| e.to_string())
+            .and_then(|event| event.write_to_bytes().map_err(|e| e.to_string()))
+        {
+            Ok(bytes) => Bytes::from(bytes),
+            Err(e) => {
+                warn!("failed to map notification to CloudEvent: {}", e);
+                return;
+            }
+        };
+        for endpoint in &self.endpoints {
+            let delivery = EndpointDelivery {
+                sender: self.sender.clone(),
+                endpoint: endpoint.clone(),
+                body: body.clone(),
+            };
+            let coordinator = self.coordinator.clone();
+            let msg = msg.clone();
+            self.tasks.spawn(endpoint.url.clone(), async move {
+                coordinator.deliver(&delivery, msg).await
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use mockall::Sequence;
+
+    use crate::{redelivery::RedeliveryPolicy, UMessageBuilder};
+
+    fn new_coordinator() -> Arc<RedeliveryCoordinator> {
+        Arc::new(RedeliveryCoordinator::new(
+            RedeliveryPolicy::new(2, Duration::from_millis(1), Duration::from_millis(5)),
+            None,
+        ))
+    }
+
+    fn new_notification(source: &str) -> UMessage {
+        UMessageBuilder::notification(
+            UUri::try_from(source).unwrap(),
+            UUri::try_from("//cloud/A100/1/0").unwrap(),
+        )
+        .build()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_notification_is_posted_as_signed_cloudevent() {
+        // GIVEN an endpoint that requires signed requests
+        let mut signer = MockRequestSigner::new();
+        signer
+            .expect_sign()
+            .once()
+            .returning(|_body| ("x-signature".to_string(), "abc".to_string()));
+        let endpoint = WebhookEndpoint::new("https://cloud.example.com/events")
+            .with_header("authorization", "Bearer token")
+            .with_signer(Arc::new(signer));
+        let notification = new_notification("//vehicle/B100/1/8001");
+        let expected_id = notification.attributes.id.clone().unwrap();
+        let mut sender = MockHttpSender::new();
+        sender
+            .expect_post()
+            .once()
+            .withf(move |url, headers, body| {
+                let event = CloudEvent::parse_from_bytes(body).unwrap();
+                url == "https://cloud.example.com/events"
+                    && headers.contains(&(
+                        CONTENT_TYPE_HEADER.to_string(),
+                        CONTENT_TYPE_CLOUDEVENTS_PROTOBUF.to_string(),
+                    ))
+                    && headers.contains(&("authorization".to_string(), "Bearer token".to_string()))
+                    && headers.contains(&("x-signature".to_string(), "abc".to_string()))
+                    && event.id == expected_id.to_hyphenated_string()
+            })
+            .return_const(Ok(202));
+        let notifier = WebhookNotifier::new(Arc::new(sender), vec![endpoint], new_coordinator());
+
+        // WHEN the notifier receives a notification
+        notifier.on_receive(notification).await;
+        notifier.task_tracker().wait().await;
+
+        // THEN the notification has been POSTed to the endpoint
+        // (verified by the mock's expectations)
+    }
+
+    #[tokio::test]
+    async fn test_failed_request_is_retried() {
+        // GIVEN an endpoint that is temporarily unavailable
+        let mut sender = MockHttpSender::new();
+        let mut seq = Sequence::new();
+        sender
+            .expect_post()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(503));
+        sender
+            .expect_post()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_url, _headers, _body| {
+                Err(UStatus::fail_with_code(
+                    UCode::UNAVAILABLE,
+                    "connection refused",
+                ))
+            });
+        sender
+            .expect_post()
+            .once()
+            .in_sequence(&mut seq)
+            .return_const(Ok(200));
+        let notifier = WebhookNotifier::new(
+            Arc::new(sender),
+            vec![WebhookEndpoint::new("https://cloud.example.com/events")],
+            new_coordinator(),
+        );
+
+        // WHEN the notifier receives a notification
+        notifier
+            .on_receive(new_notification("//vehicle/B100/1/8001"))
+            .await;
+        notifier.task_tracker().wait().await;
+
+        // THEN the request has been retried until it succeeded
+        // (verified by the mock's expectations)
+    }
+
+    #[tokio::test]
+    async fn test_messages_not_matching_filter_are_ignored() {
+        // GIVEN a notifier that forwards notifications from a particular entity only
+        let mut sender = MockHttpSender::new();
+        sender.expect_post().never();
+        let notifier = WebhookNotifier::new(
+            Arc::new(sender),
+            vec![WebhookEndpoint::new("https://cloud.example.com/events")],
+            new_coordinator(),
+        )
+        .with_source_filter(UUri::try_from("//vehicle/B100/1/FFFF").unwrap());
+
+        // WHEN the notifier receives a notification from another entity
+        notifier
+            .on_receive(new_notification("//vehicle/C100/1/8001"))
+            .await;
+        // and a published message
+        notifier
+            .on_receive(
+                UMessageBuilder::publish(UUri::try_from("//vehicle/B100/1/8001").unwrap())
+                    .build()
+                    .unwrap(),
+            )
+            .await;
+
+        // THEN no requests are sent
+        assert!(notifier.task_tracker().running_tasks().is_empty());
+    }
+}
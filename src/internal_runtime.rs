@@ -0,0 +1,36 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::OnceLock;
+
+use tokio::runtime::Runtime;
+
+/// Gets the Tokio runtime that the synchronous APIs use for running asynchronous operations.
+///
+/// The runtime is created on first use and is shared by the `blocking` facade and the `ffi` C ABI,
+/// so that a process using both only runs a single internal runtime.
+///
+/// # Panics
+///
+/// if the runtime cannot be created.
+pub(crate) fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("up-rust-runtime")
+            .enable_time()
+            .build()
+            .expect("failed to create internal runtime")
+    })
+}
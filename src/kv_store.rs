@@ -0,0 +1,429 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a minimal storage abstraction for helpers that need to keep state, e.g. across restarts.
+
+Helpers use the [`KvStore`] trait for accessing their state, so that applications can choose
+between the implementations provided by this module or plug in their own, e.g. based on a database
+that is already being used by the application.
+
+* [`InMemoryKvStore`] keeps all entries in memory.
+* [`FileKvStore`] keeps all entries in memory as well but also appends all changes to a log file,
+  from which the entries are restored when the store is re-opened.
+*/
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::{UCode, UStatus};
+
+/// A store for arbitrary binary values, identified by string keys.
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+#[async_trait]
+pub trait KvStore: Send + Sync {
+    /// Gets the value stored for a key.
+    ///
+    /// # Returns
+    ///
+    /// The value or `None` if no value is stored for the given key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be accessed.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, UStatus>;
+
+    /// Stores a value for a key, replacing any existing value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value could not be stored.
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<(), UStatus>;
+
+    /// Removes the value stored for a key.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a value has been removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value could not be removed.
+    async fn delete(&self, key: &str) -> Result<bool, UStatus>;
+
+    /// Gets all keys starting with a prefix, in lexicographical order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be accessed.
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>, UStatus>;
+}
+
+fn lock_error() -> UStatus {
+    UStatus::fail_with_code(UCode::INTERNAL, "failed to acquire lock for store")
+}
+
+fn keys_with_prefix(entries: &BTreeMap<String, Vec<u8>>, prefix: &str) -> Vec<String> {
+    entries
+        .range(prefix.to_string()..)
+        .take_while(|(key, _)| key.starts_with(prefix))
+        .map(|(key, _)| key.to_owned())
+        .collect()
+}
+
+/// A [`KvStore`] which keeps all entries in memory.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::kv_store::{InMemoryKvStore, KvStore};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let store = InMemoryKvStore::new();
+/// store.put("subscriptions/8001", vec![0x01]).await.unwrap();
+/// assert_eq!(store.get("subscriptions/8001").await.unwrap(), Some(vec![0x01]));
+/// assert_eq!(store.keys("subscriptions/").await.unwrap(), vec!["subscriptions/8001"]);
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct InMemoryKvStore {
+    entries: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl InMemoryKvStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl KvStore for InMemoryKvStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, UStatus> {
+        let entries = self.entries.lock().map_err(|_e| lock_error())?;
+        Ok(entries.get(key).cloned())
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<(), UStatus> {
+        let mut entries = self.entries.lock().map_err(|_e| lock_error())?;
+        entries.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool, UStatus> {
+        let mut entries = self.entries.lock().map_err(|_e| lock_error())?;
+        Ok(entries.remove(key).is_some())
+    }
+
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>, UStatus> {
+        let entries = self.entries.lock().map_err(|_e| lock_error())?;
+        Ok(keys_with_prefix(&entries, prefix))
+    }
+}
+
+const RECORD_PUT: u8 = 1;
+const RECORD_DELETE: u8 = 2;
+// the minimum number of obsolete records before the log gets compacted
+const MIN_OBSOLETE_RECORDS_FOR_COMPACTION: usize = 64;
+
+fn io_error(context: &str, path: &Path, error: std::io::Error) -> UStatus {
+    UStatus::fail_with_code(
+        UCode::UNAVAILABLE,
+        format!("{} {}: {}", context, path.display(), error),
+    )
+}
+
+fn encode_record(kind: u8, key: &str, value: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(9 + key.len() + value.len());
+    record.push(kind);
+    record.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    record.extend_from_slice(key.as_bytes());
+    record.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    record.extend_from_slice(value);
+    record
+}
+
+/// Reads the records contained in a log.
+///
+/// Returns the entries, the number of obsolete records and the length of the valid part of the log.
+fn replay(log: &[u8]) -> (BTreeMap<String, Vec<u8>>, usize, usize) {
+    fn read_chunk<'a>(log: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+        let len_bytes = log.get(*pos..*pos + 4)?;
+        let len = u32::from_be_bytes(len_bytes.try_into().ok()?) as usize;
+        let chunk = log.get(*pos + 4..*pos + 4 + len)?;
+        *pos += 4 + len;
+        Some(chunk)
+    }
+
+    let mut entries = BTreeMap::new();
+    let mut obsolete_records = 0;
+    let mut valid_len = 0;
+    loop {
+        let mut pos = valid_len;
+        let Some(kind) = log.get(pos).copied() else {
+            break;
+        };
+        pos += 1;
+        let Some(key) =
+            read_chunk(log, &mut pos).and_then(|key| String::from_utf8(key.to_vec()).ok())
+        else {
+            break;
+        };
+        let Some(value) = read_chunk(log, &mut pos) else {
+            break;
+        };
+        let replaced = match kind {
+            RECORD_PUT => entries.insert(key, value.to_vec()).is_some(),
+            RECORD_DELETE => {
+                // the delete record itself is obsolete as well
+                obsolete_records += 1;
+                entries.remove(&key).is_some()
+            }
+            _ => break,
+        };
+        if replaced {
+            obsolete_records += 1;
+        }
+        valid_len = pos;
+    }
+    (entries, obsolete_records, valid_len)
+}
+
+struct LogState {
+    file: File,
+    entries: BTreeMap<String, Vec<u8>>,
+    obsolete_records: usize,
+}
+
+/// A [`KvStore`] which persists its entries to a log file.
+///
+/// Each change is appended to the log file and synced to disk before the operation completes.
+/// The log is compacted automatically once it contains more obsolete than current records.
+/// Records that have been written partially, e.g. due to a power loss, are discarded when
+/// the store is opened.
+///
+/// All entries are also kept in memory, so this store is intended for moderate amounts of data
+/// like the state of stateful helpers.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::kv_store::{FileKvStore, KvStore};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let path = std::env::temp_dir().join(format!("up-rust-kv-doc-{}.log", std::process::id()));
+/// # let _ = std::fs::remove_file(&path);
+/// let store = FileKvStore::open(&path).unwrap();
+/// store.put("last-seen", b"42".to_vec()).await.unwrap();
+/// drop(store);
+///
+/// let store = FileKvStore::open(&path).unwrap();
+/// assert_eq!(store.get("last-seen").await.unwrap(), Some(b"42".to_vec()));
+/// # let _ = std::fs::remove_file(&path);
+/// # }
+/// ```
+pub struct FileKvStore {
+    path: PathBuf,
+    state: Mutex<LogState>,
+}
+
+impl FileKvStore {
+    /// Opens a store, restoring its entries from the given log file.
+    ///
+    /// The file is created if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or created.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, UStatus> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)
+            .map_err(|e| io_error("cannot open store", &path, e))?;
+        let mut log = vec![];
+        file.read_to_end(&mut log)
+            .map_err(|e| io_error("cannot read store", &path, e))?;
+        let (entries, obsolete_records, valid_len) = replay(&log);
+        if valid_len < log.len() {
+            info!(
+                path = %path.display(),
+                discarded_bytes = log.len() - valid_len,
+                "discarding incomplete record at end of store"
+            );
+            file.set_len(valid_len as u64)
+                .map_err(|e| io_error("cannot truncate store", &path, e))?;
+        }
+        Ok(FileKvStore {
+            path,
+            state: Mutex::new(LogState {
+                file,
+                entries,
+                obsolete_records,
+            }),
+        })
+    }
+
+    fn append(&self, state: &mut LogState, record: &[u8]) -> Result<(), UStatus> {
+        state
+            .file
+            .write_all(record)
+            .and_then(|_| state.file.sync_data())
+            .map_err(|e| io_error("cannot write to store", &self.path, e))
+    }
+
+    fn compact_if_necessary(&self, state: &mut LogState) -> Result<(), UStatus> {
+        if state.obsolete_records < MIN_OBSOLETE_RECORDS_FOR_COMPACTION
+            || state.obsolete_records <= state.entries.len()
+        {
+            return Ok(());
+        }
+        let temp_path = self.path.with_extension("compacting");
+        let log: Vec<u8> = state
+            .entries
+            .iter()
+            .flat_map(|(key, value)| encode_record(RECORD_PUT, key, value))
+            .collect();
+        std::fs::write(&temp_path, log)
+            .and_then(|_| std::fs::rename(&temp_path, &self.path))
+            .map_err(|e| io_error("cannot compact store", &self.path, e))?;
+        state.file = OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| io_error("cannot open store", &self.path, e))?;
+        state.obsolete_records = 0;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KvStore for FileKvStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, UStatus> {
+        let state = self.state.lock().map_err(|_e| lock_error())?;
+        Ok(state.entries.get(key).cloned())
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>) -> Result<(), UStatus> {
+        let mut state = self.state.lock().map_err(|_e| lock_error())?;
+        self.append(&mut state, &encode_record(RECORD_PUT, key, &value))?;
+        if state.entries.insert(key.to_string(), value).is_some() {
+            state.obsolete_records += 1;
+        }
+        self.compact_if_necessary(&mut state)
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool, UStatus> {
+        let mut state = self.state.lock().map_err(|_e| lock_error())?;
+        if !state.entries.contains_key(key) {
+            return Ok(false);
+        }
+        self.append(&mut state, &encode_record(RECORD_DELETE, key, &[]))?;
+        state.entries.remove(key);
+        state.obsolete_records += 2;
+        self.compact_if_necessary(&mut state)?;
+        Ok(true)
+    }
+
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>, UStatus> {
+        let state = self.state.lock().map_err(|_e| lock_error())?;
+        Ok(keys_with_prefix(&state.entries, prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("up-rust-kv-{}-{}.log", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_deletes_entries() {
+        let store = InMemoryKvStore::new();
+        store.put("a/1", vec![1]).await.unwrap();
+        store.put("a/2", vec![2]).await.unwrap();
+        store.put("b/1", vec![3]).await.unwrap();
+
+        assert!(store.delete("a/1").await.unwrap());
+        assert!(!store.delete("a/1").await.unwrap());
+        assert_eq!(store.keys("a/").await.unwrap(), vec!["a/2"]);
+        assert!(store.get("a/1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_store_restores_entries() {
+        // GIVEN a store containing some entries
+        let path = temp_path("restore");
+        let store = FileKvStore::open(&path).unwrap();
+        store.put("a", b"first".to_vec()).await.unwrap();
+        store.put("a", b"second".to_vec()).await.unwrap();
+        store.put("b", b"other".to_vec()).await.unwrap();
+        assert!(store.delete("b").await.unwrap());
+        drop(store);
+
+        // WHEN a record is written partially before re-opening the store
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&encode_record(RECORD_PUT, "c", b"lost")[..5])
+            .unwrap();
+        drop(file);
+        let store = FileKvStore::open(&path).unwrap();
+
+        // THEN the completely written changes have been restored
+        assert_eq!(store.get("a").await.unwrap(), Some(b"second".to_vec()));
+        assert_eq!(store.keys("").await.unwrap(), vec!["a"]);
+        // and the store can be written to again
+        store.put("c", b"new".to_vec()).await.unwrap();
+        drop(store);
+        let store = FileKvStore::open(&path).unwrap();
+        assert_eq!(store.get("c").await.unwrap(), Some(b"new".to_vec()));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_file_store_compacts_log() {
+        let path = temp_path("compact");
+        let store = FileKvStore::open(&path).unwrap();
+        for i in 0..=MIN_OBSOLETE_RECORDS_FOR_COMPACTION {
+            store.put("counter", vec![i as u8]).await.unwrap();
+        }
+        // only the current value remains in the log
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len() as usize,
+            encode_record(RECORD_PUT, "counter", &[0]).len()
+        );
+        store.put("other", vec![0]).await.unwrap();
+        drop(store);
+
+        let store = FileKvStore::open(&path).unwrap();
+        assert_eq!(
+            store.get("counter").await.unwrap(),
+            Some(vec![MIN_OBSOLETE_RECORDS_FOR_COMPACTION as u8])
+        );
+        assert_eq!(store.keys("").await.unwrap(), vec!["counter", "other"]);
+        let _ = std::fs::remove_file(&path);
+    }
+}
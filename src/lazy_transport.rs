@@ -0,0 +1,372 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a UTransport which defers creating the underlying transport until it is first used.
+
+This allows applications to start up before the infrastructure that their transport
+depends on (e.g. a message broker) is reachable, without implementing bespoke reconnect logic.
+*/
+
+use std::{collections::HashSet, future::Future, sync::Arc};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::{ComparableListener, UCode, UListener, UMessage, UStatus, UTransport, UUri};
+
+#[derive(Eq, PartialEq, Hash)]
+struct Registration {
+    source_filter: UUri,
+    sink_filter: Option<UUri>,
+    listener: ComparableListener,
+}
+
+/// A transport that uses a factory for creating its underlying transport on first use.
+///
+/// If the underlying transport fails an operation with [`UCode::UNAVAILABLE`], the transport
+/// is considered broken and is discarded. A new transport is then created using the factory
+/// when the next operation is invoked. All listeners that have been registered successfully
+/// are registered with the newly created transport again before the operation is performed.
+pub struct LazyTransport<F> {
+    factory: F,
+    transport: Mutex<Option<Arc<dyn UTransport>>>,
+    registrations: Mutex<HashSet<Registration>>,
+}
+
+impl<F, Fut> LazyTransport<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Arc<dyn UTransport>, UStatus>> + Send,
+{
+    /// Creates a new transport.
+    ///
+    /// Note that the factory is not invoked by this function.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory` - The function to use for (re-)creating the underlying transport.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use up_rust::{lazy_transport::LazyTransport, local_transport::LocalTransport, UTransport};
+    ///
+    /// let transport = LazyTransport::new(|| async {
+    ///     Ok(Arc::new(LocalTransport::default()) as Arc<dyn UTransport>)
+    /// });
+    /// ```
+    pub fn new(factory: F) -> Self {
+        LazyTransport {
+            factory,
+            transport: Mutex::new(None),
+            registrations: Mutex::new(HashSet::new()),
+        }
+    }
+
+    async fn get_transport(&self) -> Result<Arc<dyn UTransport>, UStatus> {
+        let mut current_transport = self.transport.lock().await;
+        if let Some(transport) = current_transport.as_ref() {
+            return Ok(transport.clone());
+        }
+
+        debug!("creating underlying transport");
+        let transport = (self.factory)().await?;
+        for registration in self.registrations.lock().await.iter() {
+            transport
+                .register_listener(
+                    &registration.source_filter,
+                    registration.sink_filter.as_ref(),
+                    registration.listener.into_inner(),
+                )
+                .await?;
+        }
+        *current_transport = Some(transport.clone());
+        Ok(transport)
+    }
+
+    async fn check_result<T>(
+        &self,
+        transport: &Arc<dyn UTransport>,
+        result: Result<T, UStatus>,
+    ) -> Result<T, UStatus> {
+        if let Err(status) = &result {
+            if status.get_code() == UCode::UNAVAILABLE {
+                let mut current_transport = self.transport.lock().await;
+                if current_transport
+                    .as_ref()
+                    .is_some_and(|t| Arc::ptr_eq(t, transport))
+                {
+                    info!("discarding underlying transport: {}", status.get_message());
+                    *current_transport = None;
+                }
+            }
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<F, Fut> UTransport for LazyTransport<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Arc<dyn UTransport>, UStatus>> + Send,
+{
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        let transport = self.get_transport().await?;
+        let result = transport.send(message).await;
+        self.check_result(&transport, result).await
+    }
+
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        let transport = match self.get_transport().await {
+            Ok(transport) => transport,
+            Err(e) => return vec![Err(e); messages.len()],
+        };
+        let outcomes = transport.send_batch(messages).await;
+        if let Some(status) = outcomes.iter().find_map(|outcome| {
+            outcome
+                .as_ref()
+                .err()
+                .filter(|status| status.get_code() == UCode::UNAVAILABLE)
+        }) {
+            let _ = self
+                .check_result::<()>(&transport, Err(status.clone()))
+                .await;
+        }
+        outcomes
+    }
+
+    async fn receive(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Result<UMessage, UStatus> {
+        let transport = self.get_transport().await?;
+        let result = transport.receive(source_filter, sink_filter).await;
+        self.check_result(&transport, result).await
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let transport = self.get_transport().await?;
+        let result = transport
+            .register_listener(source_filter, sink_filter, listener.clone())
+            .await;
+        self.check_result(&transport, result).await?;
+        self.registrations.lock().await.insert(Registration {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.cloned(),
+            listener: ComparableListener::new(listener),
+        });
+        Ok(())
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let registration = Registration {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.cloned(),
+            listener: ComparableListener::new(listener.clone()),
+        };
+        if !self.registrations.lock().await.remove(&registration) {
+            return Err(UStatus::fail_with_code(
+                UCode::NOT_FOUND,
+                "no such listener registered",
+            ));
+        }
+        let Some(transport) = self.transport.lock().await.clone() else {
+            // the listener will not be registered with the next transport being created
+            return Ok(());
+        };
+        let result = transport
+            .unregister_listener(source_filter, sink_filter, listener)
+            .await;
+        self.check_result(&transport, result).await
+    }
+
+    async fn probe(&self, peer_authority: &str) -> Result<(), UStatus> {
+        let transport = self.get_transport().await?;
+        let result = transport.probe(peer_authority).await;
+        self.check_result(&transport, result).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{
+        utransport::{MockTransport, MockUListener},
+        UMessageBuilder,
+    };
+
+    fn publish_message() -> UMessage {
+        let topic = UUri::try_from_parts("my-vehicle", 0x1000, 0x01, 0xA100).unwrap();
+        UMessageBuilder::publish(topic).build().unwrap()
+    }
+
+    fn connected_transport(expected_sends: usize) -> Arc<dyn UTransport> {
+        let mut transport = MockTransport::new();
+        transport
+            .expect_do_send()
+            .times(expected_sends)
+            .returning(|_msg| Ok(()));
+        Arc::new(transport)
+    }
+
+    #[tokio::test]
+    async fn test_transport_is_created_on_first_use_only() {
+        // GIVEN a lazy transport
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let factory_invocations = invocations.clone();
+        let transport = LazyTransport::new(move || {
+            factory_invocations.fetch_add(1, Ordering::SeqCst);
+            async { Ok(connected_transport(2)) }
+        });
+        assert_eq!(invocations.load(Ordering::SeqCst), 0);
+
+        // WHEN sending two messages
+        assert!(transport.send(publish_message()).await.is_ok());
+        assert!(transport.send(publish_message()).await.is_ok());
+
+        // THEN the underlying transport has been created once only
+        assert_eq!(invocations.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_if_transport_cannot_be_created() {
+        // GIVEN a lazy transport whose factory fails
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let factory_invocations = invocations.clone();
+        let transport = LazyTransport::new(move || {
+            let attempt = factory_invocations.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    Err(UStatus::fail_with_code(
+                        UCode::UNAVAILABLE,
+                        "broker not reachable",
+                    ))
+                } else {
+                    Ok(connected_transport(1))
+                }
+            }
+        });
+
+        // WHEN sending a message
+        // THEN the attempt fails with the factory's error
+        assert!(transport
+            .send(publish_message())
+            .await
+            .is_err_and(|e| e.get_code() == UCode::UNAVAILABLE));
+        // and creating the transport is attempted again on next use
+        assert!(transport.send(publish_message()).await.is_ok());
+        assert_eq!(invocations.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_transport_is_recreated_after_fatal_error() {
+        // GIVEN a lazy transport whose first underlying transport has lost its connection
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let factory_invocations = invocations.clone();
+        let transport = LazyTransport::new(move || {
+            let attempt = factory_invocations.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let mut transport = MockTransport::new();
+                if attempt == 0 {
+                    transport
+                        .expect_do_register_listener()
+                        .once()
+                        .returning(|_source, _sink, _listener| Ok(()));
+                    transport.expect_do_send().once().returning(|_msg| {
+                        Err(UStatus::fail_with_code(
+                            UCode::UNAVAILABLE,
+                            "connection lost",
+                        ))
+                    });
+                } else {
+                    // the listener is expected to be registered again
+                    transport
+                        .expect_do_register_listener()
+                        .once()
+                        .returning(|_source, _sink, _listener| Ok(()));
+                    transport.expect_do_send().once().returning(|_msg| Ok(()));
+                }
+                Ok(Arc::new(transport) as Arc<dyn UTransport>)
+            }
+        });
+        let source_filter = UUri::try_from_parts("*", 0xFFFF_FFFF, 0xFF, 0xFFFF).unwrap();
+        assert!(transport
+            .register_listener(&source_filter, None, Arc::new(MockUListener::new()))
+            .await
+            .is_ok());
+
+        // WHEN sending a message fails with UNAVAILABLE
+        assert!(transport
+            .send(publish_message())
+            .await
+            .is_err_and(|e| e.get_code() == UCode::UNAVAILABLE));
+
+        // THEN the next message is sent using a newly created transport
+        assert!(transport.send(publish_message()).await.is_ok());
+        assert_eq!(invocations.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_transport_is_kept_after_non_fatal_error() {
+        // GIVEN a lazy transport whose underlying transport rejects a message
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let factory_invocations = invocations.clone();
+        let transport = LazyTransport::new(move || {
+            factory_invocations.fetch_add(1, Ordering::SeqCst);
+            async {
+                let mut transport = MockTransport::new();
+                transport.expect_do_send().times(2).returning(|_msg| {
+                    Err(UStatus::fail_with_code(
+                        UCode::INVALID_ARGUMENT,
+                        "invalid message",
+                    ))
+                });
+                Ok(Arc::new(transport) as Arc<dyn UTransport>)
+            }
+        });
+
+        // WHEN sending messages fails with a non-fatal error
+        assert!(transport.send(publish_message()).await.is_err());
+        assert!(transport.send(publish_message()).await.is_err());
+
+        // THEN the underlying transport is not re-created
+        assert_eq!(invocations.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unregister_listener_fails_for_unknown_listener() {
+        let transport = LazyTransport::new(|| async { Ok(connected_transport(0)) });
+        let source_filter = UUri::try_from_parts("*", 0xFFFF_FFFF, 0xFF, 0xFFFF).unwrap();
+        assert!(transport
+            .unregister_listener(&source_filter, None, Arc::new(MockUListener::new()))
+            .await
+            .is_err_and(|e| e.get_code() == UCode::NOT_FOUND));
+    }
+}
@@ -23,34 +23,107 @@ This crate can be used to
 
 ## Library contents
 
+* `blocking` module, providing a synchronous facade for the Communication Layer API
+* `cli` module, providing entry points for command line tools that compose, validate and explain messages and URIs
 * `communication` module, which defines uProtocol's Communication Layer API for publishing and subscribing to topics and invoking RPC methods.
   It also contains a default implementation employing the Transport Layer API.
-* `uattributes` module, with uProtocol message attribute types and validators
+* `compat` module, for checking if messages can be processed by uEntities implementing older versions of the uProtocol specification
+* `diagnostics` module, with types representing snapshots of the state of the communication stack's components
+* `ffi` module, providing a C ABI for core types and the local transport
+* `flow_graph` module, for visualizing the communication topology of a running system based on observed messages
+* `qos` module, providing a configurable mapping of message priorities to the QoS parameters of common transport protocols
+* `routing` module, providing a rules engine for forwarding messages between transports
+* `uattributes` module, with uProtocol message attribute types and validators, including standalone functions for checking individual attributes
+* `uentity` module, which defines the identity of a uEntity and serves as the single source for creating its URIs
 * `umessage` module, which defines the uProtocol core message type and provides related convenience functionality
 * `upayload` module, which defines payload representation for uProtocol messages
 * `uri` module, providing convenience wrappers for creation and validation of uProtocol-style resource identifiers
+* `wellknown` module, defining the entity and resource identifiers reserved by the uProtocol specification
+* `time_source` module, for configuring the (synchronized) clock that the timestamps of UUIDs are taken from
 * `ustatus` module, which provices uProtocol types for representing status and status codes
-* `utransport` module, as an interface contract between uProtocol and specific transport protocol implementations
+* `utransport` module, as an interface contract between uProtocol and specific transport protocol implementations,
+  including a common means for providing transports with the credentials and TLS configuration for connecting to their infrastructure
+  and a pipeline for verifying, decompressing and decoding received messages
 * `uuid` module, which generates and validates UUIDs as per the uProtocol specification
 
 For user convenience, all of these modules export their types on up_rust top-level, except for (future) optional features.
 
 ## Features
 
+* `avro` enables support for creating and extracting [Apache Avro](https://avro.apache.org/) encoded payloads,
+  based on a given Avro schema. Implies `communication`.
+
+* `blocking` enables a synchronous facade for the Communication Layer API which runs the (asynchronous) default
+  implementations on an internal runtime. This allows code bases that are not based on `async`/`await` to adopt
+  uProtocol. Implies `communication`.
+
 * `cloudevents` enables support for mapping UMessages to/from CloudEvents using Protobuf Format according to the
   [uProtocol specification](https://github.com/eclipse-uprotocol/up-spec/blob/v1.6.0-alpha.4/up-l1/cloudevents.adoc).
 
+* `compression` enables DEFLATE compression of RPC response payloads by the Communication Layer API's
+  default implementations, if the client accepts compressed payloads. Implies `communication`.
+
 * `communication` enables support for the [Communication Layer API](https://github.com/eclipse-uprotocol/up-spec/blob/v1.6.0-alpha.4/up-l2/api.adoc) and its
   default implementation on top of the [Transport Layer API](https://github.com/eclipse-uprotocol/up-spec/blob/v1.6.0-alpha.4/up-l1/README.adoc).
-  Enabled by default.
+  Enabled by default. This feature is an umbrella for the following features, which can be enabled individually
+  in order to only include the parts of the Communication Layer API that a uEntity actually uses:
+  * `notification` enables the `Notifier` API and its default implementation.
+  * `pubsub` enables the `Publisher` and `Subscriber` APIs and their default implementations. Implies `notification`
+    and `usubscription`. The default `InMemorySubscriber` can only be created from a transport if `rpc-client`
+    is enabled as well.
+  * `rpc-client` enables the default `RpcClient` implementation, which correlates RPC Request and Response messages.
+  * `rpc-server` enables the default `RpcServer` implementation and the `EchoService`.
+
+  The `RpcClient` and `RpcServer` traits are available if any of these features is enabled.
+* `defmt` implements [defmt](https://defmt.ferrous-systems.com/)'s `Format` trait for `UUri`, `UUID`, `UStatus` and
+  the crate's error types, which allows embedded uEntities to log these types efficiently, e.g. via RTT.
+* `ffi` enables a C ABI for building and parsing UMessages and UUris and for running the local, in-memory UTransport,
+  which allows embedding up-rust into C/C++ applications. Implies `util`.
+* `http-gateway` enables forwarding of Notifications to HTTP endpoints (webhooks) as CloudEvents, including retries
+  and signing of requests. The HTTP client to use is provided by the application. Implies `cloudevents` and `util`.
+* `json` enables converting UMessage payloads to/from JSON by means of `UMessage::transcode`.
 * `udiscovery` enables support for types required to interact with [uDiscovery service](https://raw.githubusercontent.com/eclipse-uprotocol/up-spec/v1.6.0-alpha.4/up-l3/udiscovery/v3/README.adoc)
-  implementations.
+  implementations. In combination with the `communication` feature, it also provides a means to subscribe to all topics matching
+  a wildcard pattern.
 * `usubscription` enables support for types required to interact with [uSubscription service](https://raw.githubusercontent.com/eclipse-uprotocol/up-spec/v1.6.0-alpha.4/up-l3/usubscription/v3/README.adoc)
   implementations. Enabled by default.
 * `utwin` enables support for types required to interact with [uTwin service](https://raw.githubusercontent.com/eclipse-uprotocol/up-spec/v1.6.0-alpha.4/up-l3/utwin/v3/README.adoc)
-  implementations.
+  implementations. Together with the `communication` feature, it also enables a helper for stateful services to
+  reconstruct their state from the messages cached by uTwin during startup.
+* `serde` enables serialization of the diagnostics snapshot types using [serde](https://serde.rs/).
+  It also enables loading routing rules from any format supported by serde and (de-)serializing
+  [`UUri`]s from/to their URI string representation.
 * `test-util` provides some useful mock implementations for testing. In particular, provides mock implementations of UTransport and Communication Layer API traits which make implementing unit tests a lot easier.
-* `util` provides some useful helper structs. In particular, provides a local, in-memory UTransport for exchanging messages within a single process. This transport is also used by the examples illustrating usage of the Communication Layer API.
+  In combination with the `util` feature, it also provides a UTransport exchanging messages via channels, which allows
+  inspecting the messages exchanged between two communication stacks. If the `communication` feature is enabled as well,
+  it provides a test bed for end-to-end testing of publish/subscribe interactions between uEntities running in the same process.
+* `util` provides some useful helper structs and UTransport/UListener decorators:
+  * `local_transport` provides a local, in-memory UTransport for exchanging messages within a single process.
+    This transport is also used by the examples illustrating usage of the Communication Layer API.
+  * `redelivery` provides a listener for consuming messages with _at-least-once_ semantics, redelivering
+    unacknowledged messages.
+  * `exactly_once` provides a listener which keeps a ledger of processed message IDs in a `kv_store`, which allows
+    consumers to skip duplicates of messages that have already been processed, even across restarts.
+  * `kv_store` provides a key-value store abstraction with an in-memory and a file based implementation.
+  * `timeout_transport` bounds the time that sending a message may take.
+  * `format_policy_transport` restricts the payload formats of exchanged messages to an allow-list.
+  * `lazy_transport` creates its underlying transport lazily on first use, re-creating it after the connection
+    has been lost.
+  * `persistent_send_queue` spools outgoing messages to disk while the underlying transport is unavailable.
+  * `sampling` passes a deterministically sampled fraction of the messages being sent to an observer.
+  * `logged` logs failed operations of UTransport and the Communication Layer API traits consistently.
+  * `PooledListener` processes received messages on a bounded pool of workers instead of the transport's receive task.
+  * `ExecutorGroups` process received messages on named executor groups, backed by dedicated threads or separate
+    Tokio runtimes, so that bulk processing cannot starve latency-critical listeners.
+  * `task_tracker` manages the background tasks spawned by these helpers.
+  * `scaffold` provides building blocks for transport implementations, for keeping track of registered listeners
+    and dispatching incoming messages to them.
+  * `capture` records messages to and replays them from capture files.
+  * `gap_detector` estimates the number of events lost per topic from the creation times of received events.
+  * `rpc_over_pubsub` emulates RPC interactions by means of request and reply topics on top of transports that
+    only support publish/subscribe.
+  * `loadgen` sends configurable publish and RPC traffic via any UTransport, measuring throughput and latency.
+  * `uuid_audit` detects duplicate message IDs and message IDs violating their source's creation time order.
 
 ## References
 
@@ -62,40 +135,147 @@ For user convenience, all of these modules export their types on up_rust top-lev
 #[cfg(feature = "cloudevents")]
 mod cloudevents;
 #[cfg(feature = "cloudevents")]
-pub use cloudevents::{CloudEvent, CONTENT_TYPE_CLOUDEVENTS_PROTOBUF};
+pub use cloudevents::{CloudEvent, CloudEventValidators, CONTENT_TYPE_CLOUDEVENTS_PROTOBUF};
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg(feature = "util")]
+pub mod capture;
 
-#[cfg(feature = "communication")]
+#[cfg(any(
+    feature = "notification",
+    feature = "pubsub",
+    feature = "rpc-client",
+    feature = "rpc-server"
+))]
 pub mod communication;
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(any(feature = "blocking", feature = "ffi"))]
+mod internal_runtime;
+
+pub mod flow_graph;
+
+#[cfg(feature = "util")]
+pub mod exactly_once;
+
+#[cfg(feature = "util")]
+pub mod format_policy_transport;
+
+#[cfg(feature = "util")]
+pub mod gap_detector;
+
+#[cfg(feature = "http-gateway")]
+pub mod http_gateway;
+
+#[cfg(feature = "util")]
+pub mod kv_store;
+
+#[cfg(feature = "util")]
+pub mod lazy_transport;
+
+#[cfg(feature = "util")]
+pub mod loadgen;
+
 #[cfg(feature = "util")]
 pub mod local_transport;
 
-mod uattributes;
+#[cfg(feature = "util")]
+pub mod logged;
+
+#[cfg(feature = "util")]
+pub mod persistent_send_queue;
+
+#[cfg(feature = "util")]
+pub mod redelivery;
+
+#[cfg(feature = "util")]
+pub mod rpc_over_pubsub;
+
+#[cfg(feature = "util")]
+pub mod sampling;
+
+#[cfg(feature = "util")]
+pub mod task_tracker;
+
+#[cfg(feature = "util")]
+pub mod timeout_transport;
+
+pub mod cli;
+
+pub mod compat;
+
+pub mod diagnostics;
+
+pub mod qos;
+
+pub mod routing;
+
+pub mod time_source;
+
+pub mod uattributes;
 pub use uattributes::{
-    NotificationValidator, PublishValidator, RequestValidator, ResponseValidator, UAttributes,
-    UAttributesError, UAttributesValidator, UAttributesValidators, UMessageType, UPayloadFormat,
-    UPriority,
+    NotificationValidator, PublishValidator, RequestValidator, ResponseValidator,
+    RpcPriorityPolicy, UAttributes, UAttributesError, UAttributesValidator, UAttributesValidators,
+    UMessageType, UPayloadFormat, UPriority,
 };
 
+mod uentity;
+pub use uentity::{UEntityIdentity, UEntityIdentityBuilder};
+
 mod umessage;
-pub use umessage::{UMessage, UMessageBuilder, UMessageError};
+#[cfg(feature = "json")]
+pub use umessage::JsonCodec;
+pub use umessage::{
+    CanonicalHashOptions, CodecRegistry, PayloadCodec, ProtobufCodec, UMessage, UMessageBuilder,
+    UMessageError, UriPolicy,
+};
 
 mod uri;
-pub use uri::{UUri, UUriError};
+pub use uri::{
+    wellknown, AuthorityName, ResourceId, TopicPolicy, UUri, UUriBuilder, UUriError, UUriIndex,
+    UeId, UriLint, UriLintFinding, UriLintKind, UriRewriteRule, UriRewriter,
+};
 
 mod ustatus;
-pub use ustatus::{UCode, UStatus};
+pub use ustatus::{UCode, UCodeCategory, UStatus};
 
 mod utransport;
+#[cfg(feature = "util")]
+pub use utransport::scaffold;
+pub use utransport::{
+    verify_filter_criteria, ComparableListener, Credentials, CredentialsProvider, DecodingPipeline,
+    EnvCredentialsProvider, FileCredentialsProvider, ListenerPipeline, LocalUriProvider,
+    MessageVerifier, PipelineListener, StaticUriProvider, UListener, UTransport,
+};
+pub use utransport::{ClientAuth, PemSource, TlsConfig, TlsRotationWatcher};
+#[cfg(feature = "util")]
 pub use utransport::{
-    ComparableListener, LocalUriProvider, StaticUriProvider, UListener, UTransport,
+    ExecutorGroup, ExecutorGroups, IsolatedListener, PoolOverflowPolicy, PooledListener,
 };
 #[cfg(feature = "test-util")]
-pub use utransport::{MockLocalUriProvider, MockTransport, MockUListener};
+pub use utransport::{
+    MockCredentialsProvider, MockLocalUriProvider, MockMessageVerifier, MockTransport,
+    MockUListener,
+};
+
+#[cfg(all(feature = "test-util", feature = "util"))]
+pub mod channel_transport;
+#[cfg(all(feature = "test-util", feature = "util", feature = "communication"))]
+pub mod test_bed;
+
+mod typed_listener;
+pub use typed_listener::TypedListener;
 
 mod uuid;
 pub use uuid::UUID;
 
+#[cfg(feature = "util")]
+pub mod uuid_audit;
+
 // protoc-generated stubs, see build.rs
 mod up_core_api {
     include!(concat!(env!("OUT_DIR"), "/uprotocol/mod.rs"));
@@ -0,0 +1,574 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a synthetic load generator for benchmarking and soak-testing transport implementations.
+
+A [`LoadGenerator`] sends messages according to a set of [`TrafficPattern`]s via any
+[`UTransport`] and measures the time it takes until each message (or the response to an RPC
+Request) is received back via the same transport. Running the same patterns against different
+transport implementations allows comparing them uniformly.
+
+```rust
+use std::{sync::Arc, time::Duration};
+use up_rust::{
+    loadgen::{LoadGenerator, TrafficPattern},
+    local_transport::LocalTransport,
+    UPriority, UUri,
+};
+
+# #[tokio::main(flavor = "current_thread")]
+# async fn main() {
+let generator = LoadGenerator::new(
+    Arc::new(LocalTransport::default()),
+    UUri::try_from("//loadgen/1000/1/0").unwrap(),
+)
+.with_pattern(
+    TrafficPattern::publish(500)
+        .with_payload_size(1024)
+        .with_priority(UPriority::UPRIORITY_CS2)
+        .with_fan_out(4),
+);
+
+let report = generator.run(Duration::from_millis(100)).await.unwrap();
+println!("sent {} messages", report.patterns[0].sent);
+# }
+```
+*/
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tracing::debug;
+
+use crate::{
+    wellknown, UListener, UMessage, UMessageBuilder, UPayloadFormat, UPriority, UStatus,
+    UTransport, UUri, UUID,
+};
+
+const DEFAULT_RPC_TTL: u32 = 1_000;
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+const FIRST_TOPIC_ID: u32 = 0x8000;
+const FIRST_METHOD_ID: u32 = 0x0001;
+
+#[derive(Clone, Debug, PartialEq)]
+enum TrafficKind {
+    Publish,
+    Rpc { target: UUri, ttl: u32 },
+}
+
+/// A stream of messages that a [`LoadGenerator`] sends at a constant rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrafficPattern {
+    kind: TrafficKind,
+    messages_per_second: u32,
+    payload_size: usize,
+    priority: Option<UPriority>,
+    fan_out: u16,
+}
+
+impl TrafficPattern {
+    fn new(kind: TrafficKind, messages_per_second: u32) -> Self {
+        TrafficPattern {
+            kind,
+            messages_per_second,
+            payload_size: 0,
+            priority: None,
+            fan_out: 1,
+        }
+    }
+
+    /// Creates a pattern of events being published to topics of the load generator's uEntity.
+    ///
+    /// The latency of an event is the time from sending the event until it is received by a
+    /// listener that the generator registers for its own topics.
+    pub fn publish(messages_per_second: u32) -> Self {
+        TrafficPattern::new(TrafficKind::Publish, messages_per_second)
+    }
+
+    /// Creates a pattern of RPC Requests being sent to the methods of a uEntity.
+    ///
+    /// The latency of a request is the time from sending the request until the response is received.
+    /// The uEntity needs to expose methods with resource IDs `1` up to the pattern's fan-out, e.g. by
+    /// means of an RPC server that is connected to the same transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The uEntity to invoke. The resource ID is ignored.
+    /// * `messages_per_second` - The number of requests to send per second.
+    pub fn rpc(target: UUri, messages_per_second: u32) -> Self {
+        TrafficPattern::new(
+            TrafficKind::Rpc {
+                target,
+                ttl: DEFAULT_RPC_TTL,
+            },
+            messages_per_second,
+        )
+    }
+
+    /// Sets the size of the messages' payload in bytes.
+    ///
+    /// Messages are sent without payload by default.
+    pub fn with_payload_size(mut self, payload_size: usize) -> Self {
+        self.payload_size = payload_size;
+        self
+    }
+
+    /// Sets the priority of the messages.
+    ///
+    /// Messages are sent with the default priority of their type by default.
+    pub fn with_priority(mut self, priority: UPriority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Sets the number of distinct topics or methods that the messages are distributed over.
+    ///
+    /// Messages are sent to a single topic or method by default.
+    pub fn with_fan_out(mut self, fan_out: u16) -> Self {
+        self.fan_out = fan_out.max(1);
+        self
+    }
+
+    /// Sets the time-to-live (in milliseconds) of RPC Requests.
+    ///
+    /// Has no effect on publish patterns. The default TTL is 1000ms.
+    pub fn with_ttl(mut self, ttl: u32) -> Self {
+        if let TrafficKind::Rpc { ttl: rpc_ttl, .. } = &mut self.kind {
+            *rpc_ttl = ttl;
+        }
+        self
+    }
+
+    fn build_message(&self, source: &UUri, sequence_no: u64) -> Result<UMessage, String> {
+        let index = (sequence_no % self.fan_out as u64) as u32;
+        let mut builder = match &self.kind {
+            TrafficKind::Publish => UMessageBuilder::publish(UUri {
+                resource_id: FIRST_TOPIC_ID + index,
+                ..source.to_owned()
+            }),
+            TrafficKind::Rpc { target, ttl } => UMessageBuilder::request(
+                UUri {
+                    resource_id: FIRST_METHOD_ID + index,
+                    ..target.to_owned()
+                },
+                UUri {
+                    resource_id: wellknown::RESOURCE_ID_RESPONSE as u32,
+                    ..source.to_owned()
+                },
+                *ttl,
+            ),
+        };
+        if let Some(priority) = self.priority {
+            builder.with_priority(priority);
+        }
+        if self.payload_size == 0 {
+            builder.build()
+        } else {
+            builder.build_with_payload(
+                Bytes::from(vec![0xA5; self.payload_size]),
+                UPayloadFormat::UPAYLOAD_FORMAT_RAW,
+            )
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    fn listener_filters(&self, source: &UUri) -> (UUri, Option<UUri>) {
+        match &self.kind {
+            TrafficKind::Publish => (
+                UUri {
+                    resource_id: wellknown::WILDCARD_RESOURCE_ID as u32,
+                    ..source.to_owned()
+                },
+                None,
+            ),
+            TrafficKind::Rpc { target, .. } => (
+                UUri {
+                    resource_id: wellknown::WILDCARD_RESOURCE_ID as u32,
+                    ..target.to_owned()
+                },
+                Some(UUri {
+                    resource_id: wellknown::RESOURCE_ID_RESPONSE as u32,
+                    ..source.to_owned()
+                }),
+            ),
+        }
+    }
+}
+
+/// The results of running a single [`TrafficPattern`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PatternReport {
+    /// The number of messages that have been sent successfully.
+    pub sent: u64,
+    /// The number of messages that could not be sent.
+    pub send_failures: u64,
+    /// The number of messages (or responses) that have been received.
+    pub received: u64,
+    /// The total number of payload bytes that have been sent successfully.
+    pub payload_bytes_sent: u64,
+    /// The latencies of the received messages, sorted in ascending order.
+    pub latencies: Vec<Duration>,
+}
+
+impl PatternReport {
+    /// Gets the number of messages that have been sent but not received.
+    pub fn lost(&self) -> u64 {
+        self.sent.saturating_sub(self.received)
+    }
+
+    /// Gets a latency percentile.
+    ///
+    /// # Arguments
+    ///
+    /// * `percentile` - The percentile, e.g. `99.0`. Values are clamped to the range `[0.0, 100.0]`.
+    ///
+    /// # Returns
+    ///
+    /// The latency or `None` if no message has been received.
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * self.latencies.len() as f64).ceil();
+        let index = (rank as usize).clamp(1, self.latencies.len()) - 1;
+        self.latencies.get(index).copied()
+    }
+}
+
+/// The results of a [`LoadGenerator`] run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadReport {
+    /// The results of each pattern, in the order in which the patterns have been added.
+    pub patterns: Vec<PatternReport>,
+    /// The amount of time that messages have been sent for.
+    pub elapsed: Duration,
+}
+
+impl LoadReport {
+    /// Gets the number of messages per second that have been received, across all patterns.
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.patterns.iter().map(|p| p.received).sum::<u64>() as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+#[derive(Default)]
+struct PatternStats {
+    in_flight: HashMap<UUID, Instant>,
+    report: PatternReport,
+}
+
+// measures the latency of the messages (or responses) received for a pattern
+struct LatencyListener {
+    stats: Arc<Mutex<PatternStats>>,
+}
+
+#[async_trait]
+impl UListener for LatencyListener {
+    async fn on_receive(&self, msg: UMessage) {
+        let received_at = Instant::now();
+        let Some(attributes) = msg.attributes.as_ref() else {
+            return;
+        };
+        let Some(id) = (if msg.is_response() {
+            attributes.reqid.as_ref()
+        } else {
+            attributes.id.as_ref()
+        }) else {
+            return;
+        };
+        if let Ok(mut stats) = self.stats.lock() {
+            if let Some(sent_at) = stats.in_flight.remove(id) {
+                stats.report.received += 1;
+                stats.report.latencies.push(received_at - sent_at);
+            }
+        }
+    }
+}
+
+struct ListenerRegistration {
+    source_filter: UUri,
+    sink_filter: Option<UUri>,
+    listener: Arc<dyn UListener>,
+    stats: Arc<Mutex<PatternStats>>,
+}
+
+/// Generates traffic via a transport according to a set of [`TrafficPattern`]s.
+///
+/// See the [module documentation](`crate::loadgen`) for an example.
+pub struct LoadGenerator {
+    transport: Arc<dyn UTransport>,
+    source: UUri,
+    patterns: Vec<TrafficPattern>,
+    drain_timeout: Duration,
+}
+
+impl LoadGenerator {
+    /// Creates a new generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to send the messages via.
+    /// * `source` - The URI of the uEntity that the messages originate from. The resource ID is ignored.
+    pub fn new(transport: Arc<dyn UTransport>, source: UUri) -> Self {
+        LoadGenerator {
+            transport,
+            source,
+            patterns: vec![],
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+        }
+    }
+
+    /// Adds a traffic pattern.
+    ///
+    /// All patterns are run concurrently.
+    pub fn with_pattern(mut self, pattern: TrafficPattern) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    /// Sets the maximum amount of time to wait for outstanding messages after sending has stopped.
+    ///
+    /// The default timeout is 500ms.
+    pub fn with_drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = drain_timeout;
+        self
+    }
+
+    async fn send_pattern(
+        transport: Arc<dyn UTransport>,
+        source: UUri,
+        pattern: TrafficPattern,
+        stats: Arc<Mutex<PatternStats>>,
+        duration: Duration,
+    ) {
+        if pattern.messages_per_second == 0 {
+            return;
+        }
+        let period = Duration::from_secs(1) / pattern.messages_per_second;
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let deadline = tokio::time::Instant::now() + duration;
+        let mut sequence_no = 0_u64;
+        loop {
+            if interval.tick().await >= deadline {
+                break;
+            }
+            let message = match pattern.build_message(&source, sequence_no) {
+                Ok(message) => message,
+                Err(e) => {
+                    debug!("failed to create message: {}", e);
+                    if let Ok(mut stats) = stats.lock() {
+                        stats.report.send_failures += 1;
+                    }
+                    continue;
+                }
+            };
+            sequence_no += 1;
+            let id = message.attributes.id.get_or_default().to_owned();
+            if let Ok(mut stats) = stats.lock() {
+                stats.in_flight.insert(id.clone(), Instant::now());
+            }
+            let result = transport.send(message).await;
+            if let Ok(mut stats) = stats.lock() {
+                if result.is_ok() {
+                    stats.report.sent += 1;
+                    stats.report.payload_bytes_sent += pattern.payload_size as u64;
+                } else {
+                    stats.in_flight.remove(&id);
+                    stats.report.send_failures += 1;
+                }
+            }
+        }
+    }
+
+    /// Sends messages according to the patterns for a given amount of time.
+    ///
+    /// After sending has stopped, the generator waits for outstanding messages to be received,
+    /// for at most the drain timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listeners for measuring the messages' latency cannot be registered
+    /// with the transport.
+    pub async fn run(&self, duration: Duration) -> Result<LoadReport, UStatus> {
+        let mut registrations = Vec::with_capacity(self.patterns.len());
+        for pattern in &self.patterns {
+            let stats = Arc::new(Mutex::new(PatternStats::default()));
+            let listener: Arc<dyn UListener> = Arc::new(LatencyListener {
+                stats: stats.clone(),
+            });
+            let (source_filter, sink_filter) = pattern.listener_filters(&self.source);
+            if let Err(e) = self
+                .transport
+                .register_listener(&source_filter, sink_filter.as_ref(), listener.clone())
+                .await
+            {
+                self.unregister_listeners(&registrations).await;
+                return Err(e);
+            }
+            registrations.push(ListenerRegistration {
+                source_filter,
+                sink_filter,
+                listener,
+                stats,
+            });
+        }
+
+        let start = Instant::now();
+        let senders = self
+            .patterns
+            .iter()
+            .zip(registrations.iter())
+            .map(|(pattern, registration)| {
+                tokio::spawn(Self::send_pattern(
+                    self.transport.clone(),
+                    self.source.clone(),
+                    pattern.clone(),
+                    registration.stats.clone(),
+                    duration,
+                ))
+            })
+            .collect::<Vec<_>>();
+        for sender in senders {
+            if let Err(e) = sender.await {
+                debug!("failed to send traffic pattern: {}", e);
+            }
+        }
+        let elapsed = start.elapsed();
+
+        let drain_deadline = Instant::now() + self.drain_timeout;
+        while Instant::now() < drain_deadline
+            && registrations.iter().any(|registration| {
+                registration
+                    .stats
+                    .lock()
+                    .is_ok_and(|stats| !stats.in_flight.is_empty())
+            })
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        self.unregister_listeners(&registrations).await;
+
+        let patterns = registrations
+            .iter()
+            .map(|registration| {
+                let mut report = registration
+                    .stats
+                    .lock()
+                    .map(|stats| stats.report.clone())
+                    .unwrap_or_default();
+                report.latencies.sort();
+                report
+            })
+            .collect();
+        Ok(LoadReport { patterns, elapsed })
+    }
+
+    async fn unregister_listeners(&self, registrations: &[ListenerRegistration]) {
+        for registration in registrations {
+            if let Err(e) = self
+                .transport
+                .unregister_listener(
+                    &registration.source_filter,
+                    registration.sink_filter.as_ref(),
+                    registration.listener.clone(),
+                )
+                .await
+            {
+                debug!("failed to unregister listener: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local_transport::LocalTransport;
+
+    #[test]
+    fn test_latency_percentile() {
+        let report = PatternReport {
+            latencies: (1..=100).map(Duration::from_millis).collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            report.latency_percentile(50.0),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(
+            report.latency_percentile(99.0),
+            Some(Duration::from_millis(99))
+        );
+        assert_eq!(
+            report.latency_percentile(100.0),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(PatternReport::default().latency_percentile(50.0), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_measures_latency_of_published_events() {
+        // GIVEN a generator publishing events to multiple topics via a local transport
+        let generator = LoadGenerator::new(
+            Arc::new(LocalTransport::default()),
+            UUri::try_from("//loadgen/1000/1/0").unwrap(),
+        )
+        .with_pattern(
+            TrafficPattern::publish(200)
+                .with_payload_size(64)
+                .with_fan_out(3),
+        );
+
+        // WHEN running the generator
+        let report = generator.run(Duration::from_millis(50)).await.unwrap();
+
+        // THEN all events have been received
+        let pattern_report = &report.patterns[0];
+        assert!(pattern_report.sent > 0);
+        assert_eq!(pattern_report.send_failures, 0);
+        assert_eq!(pattern_report.lost(), 0);
+        assert_eq!(pattern_report.payload_bytes_sent, pattern_report.sent * 64);
+        assert!(pattern_report.latency_percentile(99.0).is_some());
+        assert!(report.throughput() > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_run_counts_unanswered_requests_as_lost() {
+        let generator = LoadGenerator::new(
+            Arc::new(LocalTransport::default()),
+            UUri::try_from("//loadgen/1000/1/0").unwrap(),
+        )
+        .with_pattern(TrafficPattern::rpc(
+            UUri::try_from("//loadgen/2000/1/0").unwrap(),
+            100,
+        ))
+        .with_drain_timeout(Duration::from_millis(20));
+
+        let report = generator.run(Duration::from_millis(30)).await.unwrap();
+
+        let pattern_report = &report.patterns[0];
+        assert!(pattern_report.sent > 0);
+        assert_eq!(pattern_report.received, 0);
+        assert_eq!(pattern_report.lost(), pattern_report.sent);
+    }
+}
@@ -16,51 +16,16 @@ Provides a local UTransport which can be used for connecting uEntities running i
 process.
 */
 
-use std::{collections::HashSet, sync::Arc};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
-use tokio::sync::RwLock;
-
-use crate::{ComparableListener, UListener, UMessage, UStatus, UTransport, UUri};
-
-#[derive(Eq, PartialEq, Hash)]
-struct RegisteredListener {
-    source_filter: UUri,
-    sink_filter: Option<UUri>,
-    listener: ComparableListener,
-}
-
-impl RegisteredListener {
-    fn matches(&self, source: &UUri, sink: Option<&UUri>) -> bool {
-        if !self.source_filter.matches(source) {
-            return false;
-        }
-
-        if let Some(pattern) = &self.sink_filter {
-            sink.map_or(false, |candidate_sink| pattern.matches(candidate_sink))
-        } else {
-            sink.is_none()
-        }
-    }
-    fn matches_msg(&self, msg: &UMessage) -> bool {
-        if let Some(source) = msg
-            .attributes
-            .as_ref()
-            .and_then(|attribs| attribs.source.as_ref())
-        {
-            self.matches(
-                source,
-                msg.attributes
-                    .as_ref()
-                    .and_then(|attribs| attribs.sink.as_ref()),
-            )
-        } else {
-            false
-        }
-    }
-    async fn on_receive(&self, msg: UMessage) {
-        self.listener.on_receive(msg).await
-    }
-}
+use crate::{
+    diagnostics::{ListenerDiagnostics, TransportDiagnostics},
+    utransport::scaffold::ListenerRegistry,
+    UListener, UMessage, UStatus, UTransport, UUri,
+};
 
 /// A [`UTransport`] that can be used to exchange messages within a single process.
 ///
@@ -68,16 +33,36 @@ impl RegisteredListener {
 /// match the message's source and sink filters.
 #[derive(Default)]
 pub struct LocalTransport {
-    listeners: RwLock<HashSet<RegisteredListener>>,
+    listeners: ListenerRegistry,
+    messages_sent: AtomicU64,
 }
 
 impl LocalTransport {
-    async fn dispatch(&self, message: UMessage) {
-        let listeners = self.listeners.read().await;
-        for listener in listeners.iter() {
-            if listener.matches_msg(&message) {
-                listener.on_receive(message.clone()).await;
-            }
+    /// Unregisters all listeners that have been registered with this transport.
+    ///
+    /// # Returns
+    ///
+    /// The number of listeners that have been unregistered.
+    pub async fn clear_listeners(&self) -> usize {
+        self.listeners.clear().await
+    }
+
+    /// Gets a snapshot of this transport's state.
+    pub async fn diagnostics(&self) -> TransportDiagnostics {
+        let mut listeners: Vec<ListenerDiagnostics> = self
+            .listeners
+            .filters()
+            .await
+            .iter()
+            .map(|(source_filter, sink_filter)| ListenerDiagnostics {
+                source_filter: source_filter.to_uri(false),
+                sink_filter: sink_filter.as_ref().map(|uri| uri.to_uri(false)),
+            })
+            .collect();
+        listeners.sort();
+        TransportDiagnostics {
+            listeners,
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
         }
     }
 }
@@ -85,7 +70,8 @@ impl LocalTransport {
 #[async_trait::async_trait]
 impl UTransport for LocalTransport {
     async fn send(&self, message: UMessage) -> Result<(), UStatus> {
-        self.dispatch(message).await;
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.listeners.dispatch(message).await;
         Ok(())
     }
 
@@ -95,21 +81,9 @@ impl UTransport for LocalTransport {
         sink_filter: Option<&UUri>,
         listener: Arc<dyn UListener>,
     ) -> Result<(), UStatus> {
-        let registered_listener = RegisteredListener {
-            source_filter: source_filter.to_owned(),
-            sink_filter: sink_filter.map(|u| u.to_owned()),
-            listener: ComparableListener::new(listener),
-        };
-        let mut listeners = self.listeners.write().await;
-        if listeners.contains(&registered_listener) {
-            Err(UStatus::fail_with_code(
-                crate::UCode::ALREADY_EXISTS,
-                "listener already registered for filters",
-            ))
-        } else {
-            listeners.insert(registered_listener);
-            Ok(())
-        }
+        self.listeners
+            .register(source_filter, sink_filter, listener)
+            .await
     }
 
     async fn unregister_listener(
@@ -118,20 +92,14 @@ impl UTransport for LocalTransport {
         sink_filter: Option<&UUri>,
         listener: Arc<dyn UListener>,
     ) -> Result<(), UStatus> {
-        let registered_listener = RegisteredListener {
-            source_filter: source_filter.to_owned(),
-            sink_filter: sink_filter.map(|u| u.to_owned()),
-            listener: ComparableListener::new(listener),
-        };
-        let mut listeners = self.listeners.write().await;
-        if listeners.remove(&registered_listener) {
-            Ok(())
-        } else {
-            Err(UStatus::fail_with_code(
-                crate::UCode::NOT_FOUND,
-                "no such listener registered for filters",
-            ))
-        }
+        self.listeners
+            .unregister(source_filter, sink_filter, listener)
+            .await
+    }
+
+    async fn probe(&self, _peer_authority: &str) -> Result<(), UStatus> {
+        // all uEntities using this transport run in the same process
+        Ok(())
     }
 }
 
@@ -140,6 +108,12 @@ mod tests {
     use super::*;
     use crate::{utransport::MockUListener, LocalUriProvider, StaticUriProvider, UMessageBuilder};
 
+    #[tokio::test]
+    async fn test_probe_succeeds_for_any_authority() {
+        let transport = LocalTransport::default();
+        assert!(transport.probe("other").await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_send_dispatches_to_matching_listener() {
         const RESOURCE_ID: u16 = 0xa1b3;
@@ -223,4 +197,71 @@ mod tests {
             )
             .await;
     }
+
+    #[tokio::test]
+    async fn test_diagnostics_contains_registered_listeners() {
+        let uri_provider = StaticUriProvider::new("my-vehicle", 0x100d, 0x02);
+        let transport = LocalTransport::default();
+        transport
+            .register_listener(
+                &UUri::any(),
+                Some(&uri_provider.get_source_uri()),
+                Arc::new(MockUListener::new()),
+            )
+            .await
+            .unwrap();
+        let _ = transport
+            .send(
+                UMessageBuilder::publish(uri_provider.get_resource_uri(0xa1b3))
+                    .build()
+                    .unwrap(),
+            )
+            .await;
+
+        let diagnostics = transport.diagnostics().await;
+        assert_eq!(diagnostics.messages_sent, 1);
+        assert_eq!(
+            diagnostics.listeners,
+            vec![ListenerDiagnostics {
+                source_filter: UUri::any().to_uri(false),
+                sink_filter: Some(uri_provider.get_source_uri().to_uri(false)),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clear_listeners_unregisters_all_listeners() {
+        const RESOURCE_ID: u16 = 0xa1b3;
+        let mut listener = MockUListener::new();
+        listener.expect_on_receive().never().return_const(());
+        let listener_ref = Arc::new(listener);
+        let uri_provider = StaticUriProvider::new("my-vehicle", 0x100d, 0x02);
+        let transport = LocalTransport::default();
+
+        transport
+            .register_listener(
+                &uri_provider.get_resource_uri(RESOURCE_ID),
+                None,
+                listener_ref.clone(),
+            )
+            .await
+            .unwrap();
+        transport
+            .register_listener(&UUri::any(), None, listener_ref.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(transport.clear_listeners().await, 2);
+        let _ = transport
+            .send(
+                UMessageBuilder::publish(uri_provider.get_resource_uri(RESOURCE_ID))
+                    .build()
+                    .unwrap(),
+            )
+            .await;
+        assert!(transport
+            .unregister_listener(&UUri::any(), None, listener_ref)
+            .await
+            .is_err());
+    }
 }
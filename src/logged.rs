@@ -0,0 +1,432 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a decorator which logs the failures of the operations of the wrapped object.
+
+Wrapping a [`UTransport`] or (with the corresponding features enabled) an [`RpcClient`],
+[`Publisher`] or [`Notifier`] in a [`Logged`] decorator makes sure that all
+failed invocations are logged consistently, including the operation's target, the error's code
+and message and the time it took for the operation to fail. Successful invocations are not logged.
+
+```rust
+use std::sync::Arc;
+use tracing::Level;
+use up_rust::{local_transport::LocalTransport, logged::Logged, UTransport};
+
+let transport: Arc<dyn UTransport> =
+    Arc::new(Logged::new(Arc::new(LocalTransport::default())).with_level(Level::ERROR));
+```
+*/
+
+use std::{
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use tracing::{event, Level};
+
+use crate::{UCode, UListener, UMessage, UStatus, UTransport, UUri};
+
+#[cfg(any(feature = "notification", feature = "pubsub", feature = "rpc-client"))]
+use crate::communication::{CallOptions, UPayload};
+#[cfg(feature = "notification")]
+use crate::communication::{NotificationError, Notifier, RegistrationError};
+#[cfg(feature = "pubsub")]
+use crate::communication::{PubSubError, Publisher};
+#[cfg(feature = "rpc-client")]
+use crate::communication::{RpcClient, ServiceInvocationError};
+
+/// An error that can be logged along with its status code.
+trait LoggableError: Display {
+    fn code(&self) -> UCode;
+}
+
+impl LoggableError for UStatus {
+    fn code(&self) -> UCode {
+        self.get_code()
+    }
+}
+
+#[cfg(feature = "rpc-client")]
+impl LoggableError for ServiceInvocationError {
+    fn code(&self) -> UCode {
+        UStatus::from(self.to_owned()).get_code()
+    }
+}
+
+#[cfg(feature = "pubsub")]
+impl LoggableError for PubSubError {
+    fn code(&self) -> UCode {
+        match self {
+            PubSubError::InvalidArgument(_) => UCode::INVALID_ARGUMENT,
+            PubSubError::PublishError(status) => status.get_code(),
+        }
+    }
+}
+
+#[cfg(feature = "notification")]
+impl LoggableError for NotificationError {
+    fn code(&self) -> UCode {
+        match self {
+            NotificationError::InvalidArgument(_) => UCode::INVALID_ARGUMENT,
+            NotificationError::NotifyError(status) => status.get_code(),
+        }
+    }
+}
+
+#[cfg(feature = "notification")]
+impl LoggableError for RegistrationError {
+    fn code(&self) -> UCode {
+        match self {
+            RegistrationError::AlreadyExists => UCode::ALREADY_EXISTS,
+            RegistrationError::MaxListenersExceeded => UCode::RESOURCE_EXHAUSTED,
+            RegistrationError::NoSuchListener => UCode::NOT_FOUND,
+            RegistrationError::PushDeliveryMethodNotSupported => UCode::UNIMPLEMENTED,
+            RegistrationError::InvalidFilter(_) => UCode::INVALID_ARGUMENT,
+            RegistrationError::Unknown(status) => status.get_code(),
+        }
+    }
+}
+
+/// Awaits the outcome of an operation of the wrapped object and logs it if it has failed.
+///
+/// The target expression is only evaluated if the operation has failed.
+macro_rules! logged {
+    ($self:ident, $operation:literal, $target:expr, $invocation:expr) => {{
+        let start = Instant::now();
+        let result = $invocation.await;
+        if let Err(e) = &result {
+            $self.log_failure($operation, &$target, e, start.elapsed());
+        }
+        result
+    }};
+}
+
+/// A decorator which logs failed invocations of the wrapped object's operations.
+///
+/// All operations are delegated to the wrapped object as is, i.e. errors are returned to the
+/// caller unaltered after they have been logged.
+pub struct Logged<T: ?Sized> {
+    inner: Arc<T>,
+    level: Level,
+}
+
+impl<T: ?Sized> Logged<T> {
+    /// Creates a new decorator.
+    ///
+    /// Failures are logged at [`Level::WARN`] by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The object to delegate to.
+    pub fn new(inner: Arc<T>) -> Self {
+        Logged {
+            inner,
+            level: Level::WARN,
+        }
+    }
+
+    /// Sets the level to log failures at.
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Gets the wrapped object.
+    pub fn inner(&self) -> &Arc<T> {
+        &self.inner
+    }
+
+    fn log_failure<E: LoggableError>(
+        &self,
+        operation: &str,
+        target: &dyn Display,
+        error: &E,
+        duration: Duration,
+    ) {
+        // the level of an event needs to be known at compile time
+        macro_rules! log_at {
+            ($level:expr) => {
+                event!(
+                    $level,
+                    operation,
+                    sink = %target,
+                    code = ?error.code(),
+                    duration_ms = duration.as_millis() as u64,
+                    "operation failed: {}",
+                    error
+                )
+            };
+        }
+        match self.level {
+            Level::ERROR => log_at!(Level::ERROR),
+            Level::WARN => log_at!(Level::WARN),
+            Level::INFO => log_at!(Level::INFO),
+            Level::DEBUG => log_at!(Level::DEBUG),
+            _ => log_at!(Level::TRACE),
+        }
+    }
+}
+
+fn message_target(message: &UMessage) -> String {
+    message
+        .attributes
+        .as_ref()
+        .and_then(|attribs| attribs.sink.as_ref().or(attribs.source.as_ref()))
+        .map_or_else(|| "unknown".to_string(), |uri| uri.to_uri(false))
+}
+
+#[async_trait]
+impl<T: UTransport + ?Sized> UTransport for Logged<T> {
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        let target = message_target(&message);
+        logged!(self, "send", target, self.inner.send(message))
+    }
+
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        let targets: Vec<String> = messages.iter().map(message_target).collect();
+        let start = Instant::now();
+        let outcomes = self.inner.send_batch(messages).await;
+        for (target, outcome) in targets.iter().zip(outcomes.iter()) {
+            if let Err(e) = outcome {
+                self.log_failure("send_batch", target, e, start.elapsed());
+            }
+        }
+        outcomes
+    }
+
+    async fn receive(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Result<UMessage, UStatus> {
+        logged!(
+            self,
+            "receive",
+            source_filter.to_uri(false),
+            self.inner.receive(source_filter, sink_filter)
+        )
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        logged!(
+            self,
+            "register_listener",
+            source_filter.to_uri(false),
+            self.inner
+                .register_listener(source_filter, sink_filter, listener)
+        )
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        logged!(
+            self,
+            "unregister_listener",
+            source_filter.to_uri(false),
+            self.inner
+                .unregister_listener(source_filter, sink_filter, listener)
+        )
+    }
+
+    async fn probe(&self, peer_authority: &str) -> Result<(), UStatus> {
+        logged!(
+            self,
+            "probe",
+            peer_authority,
+            self.inner.probe(peer_authority)
+        )
+    }
+}
+
+#[cfg(feature = "rpc-client")]
+#[async_trait]
+impl<T: RpcClient + ?Sized> RpcClient for Logged<T> {
+    async fn invoke_method(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<Option<UPayload>, ServiceInvocationError> {
+        let target = method.to_uri(false);
+        logged!(
+            self,
+            "invoke_method",
+            target,
+            self.inner.invoke_method(method, call_options, payload)
+        )
+    }
+
+    async fn invoke_no_response(
+        &self,
+        method: UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<(), ServiceInvocationError> {
+        let target = method.to_uri(false);
+        logged!(
+            self,
+            "invoke_no_response",
+            target,
+            self.inner.invoke_no_response(method, call_options, payload)
+        )
+    }
+}
+
+#[cfg(feature = "pubsub")]
+#[async_trait]
+impl<T: Publisher + ?Sized> Publisher for Logged<T> {
+    async fn publish(
+        &self,
+        resource_id: u16,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<(), PubSubError> {
+        let target = format!("resource {resource_id:#06X}");
+        logged!(
+            self,
+            "publish",
+            target,
+            self.inner.publish(resource_id, call_options, payload)
+        )
+    }
+
+    async fn publish_all(
+        &self,
+        messages: Vec<(u16, UPayload)>,
+        call_options: CallOptions,
+    ) -> Result<Vec<Result<(), PubSubError>>, PubSubError> {
+        let targets: Vec<String> = messages
+            .iter()
+            .map(|(resource_id, _payload)| format!("resource {resource_id:#06X}"))
+            .collect();
+        let start = Instant::now();
+        let outcomes = logged!(
+            self,
+            "publish_all",
+            targets.join(", "),
+            self.inner.publish_all(messages, call_options)
+        )?;
+        for (target, outcome) in targets.iter().zip(outcomes.iter()) {
+            if let Err(e) = outcome {
+                self.log_failure("publish_all", target, e, start.elapsed());
+            }
+        }
+        Ok(outcomes)
+    }
+}
+
+#[cfg(feature = "notification")]
+#[async_trait]
+impl<T: Notifier + ?Sized> Notifier for Logged<T> {
+    async fn notify(
+        &self,
+        resource_id: u16,
+        destination: &UUri,
+        call_options: CallOptions,
+        payload: Option<UPayload>,
+    ) -> Result<(), NotificationError> {
+        logged!(
+            self,
+            "notify",
+            destination.to_uri(false),
+            self.inner
+                .notify(resource_id, destination, call_options, payload)
+        )
+    }
+
+    async fn start_listening(
+        &self,
+        topic: &UUri,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), RegistrationError> {
+        logged!(
+            self,
+            "start_listening",
+            topic.to_uri(false),
+            self.inner.start_listening(topic, listener)
+        )
+    }
+
+    async fn stop_listening(
+        &self,
+        topic: &UUri,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), RegistrationError> {
+        logged!(
+            self,
+            "stop_listening",
+            topic.to_uri(false),
+            self.inner.stop_listening(topic, listener)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{utransport::MockTransport, UMessageBuilder};
+
+    #[tokio::test]
+    async fn test_send_returns_error_of_wrapped_transport() {
+        let mut underlying_transport = MockTransport::new();
+        underlying_transport
+            .expect_do_send()
+            .once()
+            .return_const(Err(UStatus::fail_with_code(
+                UCode::UNAVAILABLE,
+                "not connected",
+            )));
+        let transport = Logged::new(Arc::new(underlying_transport)).with_level(Level::ERROR);
+        let message = UMessageBuilder::publish(UUri::try_from("//my-vehicle/1000/1/8001").unwrap())
+            .build()
+            .unwrap();
+
+        let result = transport.send(message).await;
+
+        assert!(result.is_err_and(|e| e.get_code() == UCode::UNAVAILABLE));
+    }
+
+    #[cfg(feature = "rpc-client")]
+    #[tokio::test]
+    async fn test_invoke_method_returns_error_of_wrapped_client() {
+        let mut rpc_client = crate::communication::MockRpcClient::new();
+        rpc_client
+            .expect_invoke_method()
+            .once()
+            .return_const(Err(ServiceInvocationError::DeadlineExceeded));
+        let client = Logged::new(Arc::new(rpc_client));
+
+        let result = client
+            .invoke_method(
+                UUri::try_from("//my-vehicle/1000/1/1").unwrap(),
+                CallOptions::for_rpc_request(1_000, None, None, None),
+                None,
+            )
+            .await;
+
+        assert!(result.is_err_and(|e| matches!(e, ServiceInvocationError::DeadlineExceeded)));
+    }
+}
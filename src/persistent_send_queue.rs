@@ -0,0 +1,507 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a UTransport decorator which stores outgoing messages on disk while the underlying
+transport is unavailable and forwards them once it has recovered.
+
+Devices with intermittent connectivity, e.g. telematics units, often cannot reach the
+infrastructure that the transport connects to. Wrapping the transport in a [`PersistentSendQueue`]
+allows uEntities to keep sending messages during such periods without losing them, even if the
+process gets restarted in the meantime.
+*/
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use protobuf::Message;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::{
+    task_tracker::TaskTracker, uattributes::validate_not_expired, UCode, UListener, UMessage,
+    UStatus, UTransport, UUri,
+};
+
+const SPOOL_FILE_EXTENSION: &str = "msg";
+const TEMP_FILE_EXTENSION: &str = "tmp";
+
+/// Runs a blocking file system operation without blocking the runtime's worker thread.
+async fn run_blocking<T, F>(f: F) -> std::io::Result<T>
+where
+    F: FnOnce() -> std::io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+struct Spool {
+    next_sequence_number: u64,
+    files: VecDeque<PathBuf>,
+}
+
+/// A transport that spools outgoing messages to disk if the underlying transport
+/// fails sending them with [`UCode::UNAVAILABLE`].
+///
+/// Spooled messages are forwarded to the underlying transport in the order in which they have
+/// been sent, before any message that is sent after the transport has recovered. Messages that
+/// have expired while being spooled are discarded. Spooled messages are forwarded
+///
+/// * on each invocation of [`UTransport::send`] and [`UTransport::send_batch`],
+/// * on each invocation of [`PersistentSendQueue::flush`] and
+/// * periodically, if [enabled](`PersistentSendQueue::start_flushing`).
+///
+/// Each spooled message is stored in a separate file in the spool directory. Messages that have
+/// been spooled by a previous instance using the same directory are picked up on creation. The files
+/// are read and written on Tokio's pool of threads for blocking operations.
+///
+/// Sending messages is serialized in order to maintain the messages' order. The messages of a batch
+/// that the underlying transport fails to send with [`UCode::UNAVAILABLE`] are spooled, i.e. they
+/// may get forwarded after other messages of the same batch. All other operations
+/// are delegated to the underlying transport as is.
+pub struct PersistentSendQueue {
+    transport: Arc<dyn UTransport>,
+    spool_dir: PathBuf,
+    spool: Mutex<Spool>,
+    max_spooled_messages: usize,
+    tasks: TaskTracker,
+}
+
+impl PersistentSendQueue {
+    /// Creates a new queue for a given underlying transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to delegate to.
+    /// * `spool_dir` - The directory to store messages in. The directory is created if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the spool directory cannot be created or read, or if the temporary files
+    /// of messages that have not been spooled completely cannot be removed from it.
+    pub fn new<P: AsRef<Path>>(
+        transport: Arc<dyn UTransport>,
+        spool_dir: P,
+    ) -> std::io::Result<Self> {
+        let spool_dir = spool_dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&spool_dir)?;
+        let mut spooled = vec![];
+        for entry in std::fs::read_dir(&spool_dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|ext| ext == TEMP_FILE_EXTENSION)
+            {
+                // left behind by a previous instance that has crashed while spooling a message
+                debug!("removing incompletely spooled message {}", path.display());
+                std::fs::remove_file(&path)?;
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext == SPOOL_FILE_EXTENSION)
+            {
+                if let Some(sequence_number) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u64>().ok())
+                {
+                    spooled.push((sequence_number, path));
+                }
+            }
+        }
+        spooled.sort();
+        if !spooled.is_empty() {
+            info!(
+                "found {} spooled message(s) in {}",
+                spooled.len(),
+                spool_dir.display()
+            );
+        }
+        let next_sequence_number = spooled.last().map_or(0, |(seq, _path)| seq + 1);
+        Ok(PersistentSendQueue {
+            transport,
+            spool_dir,
+            spool: Mutex::new(Spool {
+                next_sequence_number,
+                files: spooled.into_iter().map(|(_seq, path)| path).collect(),
+            }),
+            max_spooled_messages: usize::MAX,
+            tasks: TaskTracker::new("persistent-send-queue"),
+        })
+    }
+
+    /// Sets the maximum number of messages to spool.
+    ///
+    /// Sending a message fails with [`UCode::RESOURCE_EXHAUSTED`] if the message would need to be
+    /// spooled but the maximum number of messages has already been spooled.
+    pub fn with_max_spooled_messages(mut self, max_spooled_messages: usize) -> Self {
+        self.max_spooled_messages = max_spooled_messages;
+        self
+    }
+
+    /// Gets the number of messages that are currently spooled.
+    pub async fn spooled_messages(&self) -> usize {
+        self.spool.lock().await.files.len()
+    }
+
+    /// Gets the tracker of the task that periodically flushes the spool.
+    pub fn task_tracker(&self) -> &TaskTracker {
+        &self.tasks
+    }
+
+    /// Forwards spooled messages to the underlying transport.
+    ///
+    /// Stops at the first message that the transport fails to send with [`UCode::UNAVAILABLE`].
+    ///
+    /// # Returns
+    ///
+    /// The number of messages that have been forwarded.
+    pub async fn flush(&self) -> usize {
+        let mut spool = self.spool.lock().await;
+        self.do_flush(&mut spool).await
+    }
+
+    /// Starts periodically forwarding spooled messages to the underlying transport.
+    ///
+    /// The task stops once the queue has been dropped.
+    ///
+    /// # Panics
+    ///
+    /// if not called from within the context of a Tokio runtime.
+    pub fn start_flushing(self: &Arc<Self>, interval: Duration) {
+        let queue: Weak<Self> = Arc::downgrade(self);
+        self.tasks.spawn("flusher", async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(queue) = queue.upgrade() else {
+                    break;
+                };
+                queue.flush().await;
+            }
+        });
+    }
+
+    async fn do_flush(&self, spool: &mut Spool) -> usize {
+        let mut forwarded = 0;
+        while let Some(path) = spool.files.front().cloned() {
+            let message = match run_blocking(move || std::fs::read(path))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| UMessage::parse_from_bytes(&bytes).map_err(|e| e.to_string()))
+            {
+                Ok(message) => message,
+                Err(e) => {
+                    info!("discarding unreadable spooled message: {}", e);
+                    Self::remove_first(spool).await;
+                    continue;
+                }
+            };
+            if message
+                .attributes
+                .as_ref()
+                .is_some_and(|attribs| validate_not_expired(attribs).is_err())
+            {
+                debug!("discarding expired spooled message");
+                Self::remove_first(spool).await;
+                continue;
+            }
+            match self.transport.send(message).await {
+                Ok(()) => forwarded += 1,
+                Err(e) if e.get_code() == UCode::UNAVAILABLE => break,
+                Err(e) => info!("discarding spooled message rejected by transport: {}", e),
+            }
+            Self::remove_first(spool).await;
+        }
+        if forwarded > 0 {
+            debug!("forwarded {} spooled message(s)", forwarded);
+        }
+        forwarded
+    }
+
+    async fn remove_first(spool: &mut Spool) {
+        if let Some(path) = spool.files.pop_front() {
+            let file = path.clone();
+            if let Err(e) = run_blocking(move || std::fs::remove_file(file)).await {
+                info!("failed to remove spool file {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    async fn spool_message(&self, spool: &mut Spool, message: &UMessage) -> Result<(), UStatus> {
+        if spool.files.len() >= self.max_spooled_messages {
+            return Err(UStatus::fail_with_code(
+                UCode::RESOURCE_EXHAUSTED,
+                "transport is unavailable and maximum number of spooled messages has been reached",
+            ));
+        }
+        let bytes = message
+            .write_to_bytes()
+            .map_err(|e| UStatus::fail_with_code(UCode::INVALID_ARGUMENT, e.to_string()))?;
+        let file_name = format!("{:020}", spool.next_sequence_number);
+        let temp_path = self
+            .spool_dir
+            .join(&file_name)
+            .with_extension(TEMP_FILE_EXTENSION);
+        let path = self
+            .spool_dir
+            .join(&file_name)
+            .with_extension(SPOOL_FILE_EXTENSION);
+        let file = path.clone();
+        // the message is written to a temporary file first so that a crash
+        // does not leave a partially written message in the spool
+        run_blocking(move || {
+            std::fs::write(&temp_path, bytes).and_then(|_| std::fs::rename(&temp_path, file))
+        })
+        .await
+        .map_err(|e| {
+            UStatus::fail_with_code(UCode::INTERNAL, format!("failed to spool message: {}", e))
+        })?;
+        spool.next_sequence_number += 1;
+        spool.files.push_back(path);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UTransport for PersistentSendQueue {
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        let mut spool = self.spool.lock().await;
+        if !spool.files.is_empty() {
+            self.do_flush(&mut spool).await;
+        }
+        if spool.files.is_empty() {
+            match self.transport.send(message.clone()).await {
+                Err(e) if e.get_code() == UCode::UNAVAILABLE => {
+                    debug!("underlying transport is unavailable, spooling message");
+                }
+                result => return result,
+            }
+        }
+        self.spool_message(&mut spool, &message).await
+    }
+
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        let mut spool = self.spool.lock().await;
+        if !spool.files.is_empty() {
+            self.do_flush(&mut spool).await;
+        }
+        let outcomes = if spool.files.is_empty() {
+            self.transport.send_batch(messages.clone()).await
+        } else {
+            vec![]
+        };
+        let mut results = Vec::with_capacity(messages.len());
+        let mut outcomes = outcomes.into_iter();
+        for message in &messages {
+            let result = match outcomes.next() {
+                Some(Err(e)) if e.get_code() == UCode::UNAVAILABLE => {
+                    debug!("underlying transport is unavailable, spooling message");
+                    self.spool_message(&mut spool, message).await
+                }
+                Some(outcome) => outcome,
+                // the spool could not be flushed completely, so the message needs to be
+                // spooled as well in order to maintain the order of messages
+                None => self.spool_message(&mut spool, message).await,
+            };
+            results.push(result);
+        }
+        results
+    }
+
+    async fn receive(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Result<UMessage, UStatus> {
+        self.transport.receive(source_filter, sink_filter).await
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.transport
+            .register_listener(source_filter, sink_filter, listener)
+            .await
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.transport
+            .unregister_listener(source_filter, sink_filter, listener)
+            .await
+    }
+
+    async fn probe(&self, peer_authority: &str) -> Result<(), UStatus> {
+        self.transport.probe(peer_authority).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use crate::UMessageBuilder;
+
+    #[derive(Default)]
+    struct IntermittentTransport {
+        unavailable: AtomicBool,
+        sent: std::sync::Mutex<Vec<UMessage>>,
+    }
+
+    #[async_trait]
+    impl UTransport for IntermittentTransport {
+        async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+            if self.unavailable.load(Ordering::SeqCst) {
+                return Err(UStatus::fail_with_code(UCode::UNAVAILABLE, "offline"));
+            }
+            self.sent.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    fn spool_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("up-rust-spool-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn publish_message(resource_id: u16, ttl: Option<u32>) -> UMessage {
+        let topic = UUri::try_from_parts("my-vehicle", 0x1000, 0x01, resource_id).unwrap();
+        let mut builder = UMessageBuilder::publish(topic);
+        if let Some(ttl) = ttl {
+            builder.with_ttl(ttl);
+        }
+        builder.build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_spooled_messages_are_forwarded_in_order_after_recovery() {
+        // GIVEN a queue for a transport that is unavailable
+        let transport = Arc::new(IntermittentTransport::default());
+        transport.unavailable.store(true, Ordering::SeqCst);
+        let queue = PersistentSendQueue::new(transport.clone(), spool_dir("order")).unwrap();
+
+        // WHEN sending messages while the transport is unavailable
+        let first = publish_message(0x8001, None);
+        let second = publish_message(0x8002, None);
+        assert!(queue.send(first.clone()).await.is_ok());
+        assert!(queue.send(second.clone()).await.is_ok());
+
+        // THEN the messages are spooled
+        assert_eq!(queue.spooled_messages().await, 2);
+        assert!(transport.sent.lock().unwrap().is_empty());
+
+        // and sending a message after the transport has recovered forwards the spooled messages first
+        transport.unavailable.store(false, Ordering::SeqCst);
+        let third = publish_message(0x8003, None);
+        assert!(queue.send(third.clone()).await.is_ok());
+        assert_eq!(*transport.sent.lock().unwrap(), vec![first, second, third]);
+        assert_eq!(queue.spooled_messages().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_send_batch_spools_messages_while_transport_is_unavailable() {
+        // GIVEN a queue for a transport that is unavailable
+        let transport = Arc::new(IntermittentTransport::default());
+        transport.unavailable.store(true, Ordering::SeqCst);
+        let queue = PersistentSendQueue::new(transport.clone(), spool_dir("batch")).unwrap();
+
+        // WHEN sending a batch of messages
+        let first = publish_message(0x8001, None);
+        let second = publish_message(0x8002, None);
+        let outcomes = queue.send_batch(vec![first.clone(), second.clone()]).await;
+
+        // THEN the messages are spooled
+        assert!(outcomes.iter().all(|outcome| outcome.is_ok()));
+        assert_eq!(queue.spooled_messages().await, 2);
+
+        // and are forwarded before the messages of the next batch after the transport has recovered
+        transport.unavailable.store(false, Ordering::SeqCst);
+        let third = publish_message(0x8003, None);
+        let outcomes = queue.send_batch(vec![third.clone()]).await;
+        assert!(outcomes.iter().all(|outcome| outcome.is_ok()));
+        assert_eq!(*transport.sent.lock().unwrap(), vec![first, second, third]);
+        assert_eq!(queue.spooled_messages().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_spooled_messages_survive_restart() {
+        let dir = spool_dir("restart");
+        let transport = Arc::new(IntermittentTransport::default());
+        transport.unavailable.store(true, Ordering::SeqCst);
+        let message = publish_message(0x8001, None);
+        {
+            let queue = PersistentSendQueue::new(transport.clone(), &dir).unwrap();
+            assert!(queue.send(message.clone()).await.is_ok());
+        }
+
+        transport.unavailable.store(false, Ordering::SeqCst);
+        let queue = PersistentSendQueue::new(transport.clone(), &dir).unwrap();
+        assert_eq!(queue.spooled_messages().await, 1);
+        assert_eq!(queue.flush().await, 1);
+        assert_eq!(*transport.sent.lock().unwrap(), vec![message]);
+    }
+
+    #[tokio::test]
+    async fn test_incompletely_spooled_messages_are_removed_on_startup() {
+        // GIVEN a spool directory containing a message which has not been written completely
+        let dir = spool_dir("incomplete");
+        std::fs::create_dir_all(&dir).unwrap();
+        let temp_file = dir
+            .join(format!("{:020}", 0))
+            .with_extension(TEMP_FILE_EXTENSION);
+        std::fs::write(&temp_file, b"partial").unwrap();
+
+        // WHEN creating a queue for the directory
+        let transport = Arc::new(IntermittentTransport::default());
+        let queue = PersistentSendQueue::new(transport.clone(), &dir).unwrap();
+
+        // THEN the temporary file has been removed
+        assert!(!temp_file.exists());
+        assert_eq!(queue.spooled_messages().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_expired_messages_are_discarded() {
+        let transport = Arc::new(IntermittentTransport::default());
+        transport.unavailable.store(true, Ordering::SeqCst);
+        let queue = PersistentSendQueue::new(transport.clone(), spool_dir("expiry"))
+            .unwrap()
+            .with_max_spooled_messages(1);
+        assert!(queue.send(publish_message(0x8001, Some(20))).await.is_ok());
+        assert!(queue
+            .send(publish_message(0x8002, None))
+            .await
+            .is_err_and(|e| e.get_code() == UCode::RESOURCE_EXHAUSTED));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        transport.unavailable.store(false, Ordering::SeqCst);
+        assert_eq!(queue.flush().await, 0);
+        assert_eq!(queue.spooled_messages().await, 0);
+        assert!(transport.sent.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,303 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a configurable mapping of [`UPriority`] values to the quality of service parameters
+of the protocols that uProtocol transports are commonly implemented on.
+
+Transport implementations can use a [`QosMapper`] for determining the QoS parameters to
+use for sending a message, and for determining the priority of a message received via
+the underlying protocol. Integrators may adapt the mapping to the needs of a particular
+deployment without having to patch the transport implementations.
+*/
+
+use std::collections::HashMap;
+
+use protobuf::Enum;
+
+use crate::UPriority;
+
+/// The MQTT quality of service levels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MqttQos {
+    /// QoS 0, the message is delivered at most once and may get lost.
+    AtMostOnce = 0,
+    /// QoS 1, the message is delivered at least once and may get duplicated.
+    AtLeastOnce = 1,
+    /// QoS 2, the message is delivered exactly once.
+    ExactlyOnce = 2,
+}
+
+/// The DDS quality of service policies that are relevant for message priorities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DdsQos {
+    /// The value of the `TRANSPORT_PRIORITY` policy.
+    pub transport_priority: i32,
+    /// Indicates whether the `RELIABILITY` policy's kind is `RELIABLE` (or `BEST_EFFORT` otherwise).
+    pub reliable: bool,
+}
+
+/// The SOME/IP transport parameters that are relevant for message priorities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SomeIpQos {
+    /// Indicates whether the message is sent via a reliable (TCP) or unreliable (UDP) connection.
+    pub reliable: bool,
+}
+
+/// The QoS parameters to use for messages of a particular priority.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QosProfile {
+    /// The QoS level to use when publishing via MQTT.
+    pub mqtt: MqttQos,
+    /// The DDS policies to use for writing samples.
+    pub dds: DdsQos,
+    /// The SOME/IP connection to use.
+    pub someip: SomeIpQos,
+}
+
+/// A mapping of [`UPriority`] values to [`QosProfile`]s.
+///
+/// The [default mapping](`QosMapper::default`) uses
+/// * MQTT QoS 0 (_at most once_) for priorities `CS0` and `CS1`, and QoS 1 (_at least once_) for all others,
+/// * the priority's class number (0 - 6) as the DDS transport priority,
+/// * best effort DDS reliability and unreliable SOME/IP connections for priorities `CS0` and `CS1`, and
+///   reliable DDS and SOME/IP communication for all others.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QosMapper {
+    profiles: HashMap<UPriority, QosProfile>,
+}
+
+impl Default for QosMapper {
+    fn default() -> Self {
+        let profiles = UPriority::VALUES
+            .iter()
+            .filter(|priority| **priority != UPriority::UPRIORITY_UNSPECIFIED)
+            .map(|priority| {
+                let class = priority.value() - UPriority::UPRIORITY_CS0.value();
+                let reliable = priority.value() >= UPriority::UPRIORITY_CS2.value();
+                let profile = QosProfile {
+                    mqtt: if reliable {
+                        MqttQos::AtLeastOnce
+                    } else {
+                        MqttQos::AtMostOnce
+                    },
+                    dds: DdsQos {
+                        transport_priority: class,
+                        reliable,
+                    },
+                    someip: SomeIpQos { reliable },
+                };
+                (*priority, profile)
+            })
+            .collect();
+        QosMapper { profiles }
+    }
+}
+
+impl QosMapper {
+    /// Sets the QoS parameters to use for a priority.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{qos::{MqttQos, QosMapper}, UPriority};
+    ///
+    /// let default_profile = QosMapper::default().qos_for_priority(UPriority::UPRIORITY_CS6);
+    /// let mapper = QosMapper::default().with_profile(
+    ///     UPriority::UPRIORITY_CS6,
+    ///     up_rust::qos::QosProfile {
+    ///         mqtt: MqttQos::ExactlyOnce,
+    ///         ..default_profile
+    ///     },
+    /// );
+    /// assert_eq!(mapper.qos_for_priority(UPriority::UPRIORITY_CS6).mqtt, MqttQos::ExactlyOnce);
+    /// ```
+    pub fn with_profile(mut self, priority: UPriority, profile: QosProfile) -> Self {
+        self.profiles
+            .insert(Self::effective_priority(priority), profile);
+        self
+    }
+
+    /// Gets the QoS parameters to use for a priority.
+    ///
+    /// [`UPriority::UPRIORITY_UNSPECIFIED`] is mapped like [`UPriority::UPRIORITY_CS1`],
+    /// which is the default priority defined by the uProtocol specification.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{qos::{MqttQos, QosMapper}, UPriority};
+    ///
+    /// let mapper = QosMapper::default();
+    /// assert_eq!(mapper.qos_for_priority(UPriority::UPRIORITY_CS1).mqtt, MqttQos::AtMostOnce);
+    /// assert_eq!(mapper.qos_for_priority(UPriority::UPRIORITY_CS4).mqtt, MqttQos::AtLeastOnce);
+    /// ```
+    pub fn qos_for_priority(&self, priority: UPriority) -> QosProfile {
+        // the default mapping contains profiles for all priorities and
+        // profiles can only be replaced but not removed
+        self.profiles[&Self::effective_priority(priority)]
+    }
+
+    /// Gets the priority that a message received via MQTT with a given QoS level is considered to have.
+    ///
+    /// # Returns
+    ///
+    /// The lowest priority that is mapped to the given QoS level or `None`
+    /// if no priority is mapped to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{qos::{MqttQos, QosMapper}, UPriority};
+    ///
+    /// let mapper = QosMapper::default();
+    /// assert_eq!(mapper.priority_for_mqtt_qos(MqttQos::AtLeastOnce), Some(UPriority::UPRIORITY_CS2));
+    /// assert_eq!(mapper.priority_for_mqtt_qos(MqttQos::ExactlyOnce), None);
+    /// ```
+    pub fn priority_for_mqtt_qos(&self, qos: MqttQos) -> Option<UPriority> {
+        self.lowest_priority_matching(|profile| profile.mqtt == qos)
+    }
+
+    /// Gets the priority that a message received via DDS with a given transport priority is considered to have.
+    ///
+    /// # Returns
+    ///
+    /// The lowest priority that is mapped to the given transport priority or `None`
+    /// if no priority is mapped to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{qos::QosMapper, UPriority};
+    ///
+    /// let mapper = QosMapper::default();
+    /// assert_eq!(mapper.priority_for_dds_transport_priority(5), Some(UPriority::UPRIORITY_CS5));
+    /// assert_eq!(mapper.priority_for_dds_transport_priority(100), None);
+    /// ```
+    pub fn priority_for_dds_transport_priority(
+        &self,
+        transport_priority: i32,
+    ) -> Option<UPriority> {
+        self.lowest_priority_matching(|profile| {
+            profile.dds.transport_priority == transport_priority
+        })
+    }
+
+    /// Gets the priority that a message received via a SOME/IP connection is considered to have.
+    ///
+    /// # Returns
+    ///
+    /// The lowest priority that is mapped to the given type of connection or `None`
+    /// if no priority is mapped to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{qos::{QosMapper, SomeIpQos}, UPriority};
+    ///
+    /// let mapper = QosMapper::default();
+    /// assert_eq!(
+    ///     mapper.priority_for_someip_qos(SomeIpQos { reliable: false }),
+    ///     Some(UPriority::UPRIORITY_CS0)
+    /// );
+    /// ```
+    pub fn priority_for_someip_qos(&self, qos: SomeIpQos) -> Option<UPriority> {
+        self.lowest_priority_matching(|profile| profile.someip == qos)
+    }
+
+    fn effective_priority(priority: UPriority) -> UPriority {
+        if priority == UPriority::UPRIORITY_UNSPECIFIED {
+            UPriority::UPRIORITY_CS1
+        } else {
+            priority
+        }
+    }
+
+    fn lowest_priority_matching<P>(&self, predicate: P) -> Option<UPriority>
+    where
+        P: Fn(&QosProfile) -> bool,
+    {
+        self.profiles
+            .iter()
+            .filter(|(_priority, profile)| predicate(profile))
+            .map(|(priority, _profile)| *priority)
+            .min_by_key(|priority| priority.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_mapping_covers_all_priorities() {
+        let mapper = QosMapper::default();
+        UPriority::VALUES.iter().for_each(|priority| {
+            let profile = mapper.qos_for_priority(*priority);
+            let reliable = priority.value() >= UPriority::UPRIORITY_CS2.value();
+            assert_eq!(profile.dds.reliable, reliable);
+            assert_eq!(profile.someip.reliable, reliable);
+        });
+    }
+
+    #[test]
+    fn test_default_mapping_round_trips_dds_transport_priority() {
+        let mapper = QosMapper::default();
+        UPriority::VALUES
+            .iter()
+            .filter(|priority| **priority != UPriority::UPRIORITY_UNSPECIFIED)
+            .for_each(|priority| {
+                let profile = mapper.qos_for_priority(*priority);
+                assert_eq!(
+                    mapper.priority_for_dds_transport_priority(profile.dds.transport_priority),
+                    Some(*priority)
+                );
+            });
+    }
+
+    #[test]
+    fn test_unspecified_priority_is_mapped_like_cs1() {
+        let mapper = QosMapper::default();
+        assert_eq!(
+            mapper.qos_for_priority(UPriority::UPRIORITY_UNSPECIFIED),
+            mapper.qos_for_priority(UPriority::UPRIORITY_CS1)
+        );
+    }
+
+    #[test]
+    fn test_with_profile_replaces_mapping() {
+        let custom_profile = QosProfile {
+            mqtt: MqttQos::ExactlyOnce,
+            dds: DdsQos {
+                transport_priority: 100,
+                reliable: true,
+            },
+            someip: SomeIpQos { reliable: true },
+        };
+        let mapper = QosMapper::default().with_profile(UPriority::UPRIORITY_CS6, custom_profile);
+
+        assert_eq!(
+            mapper.qos_for_priority(UPriority::UPRIORITY_CS6),
+            custom_profile
+        );
+        assert_eq!(
+            mapper.priority_for_mqtt_qos(MqttQos::ExactlyOnce),
+            Some(UPriority::UPRIORITY_CS6)
+        );
+        assert_eq!(
+            mapper.priority_for_dds_transport_priority(100),
+            Some(UPriority::UPRIORITY_CS6)
+        );
+        assert_eq!(mapper.priority_for_dds_transport_priority(6), None);
+    }
+}
@@ -0,0 +1,368 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides support for processing messages with _at-least-once_ semantics on top of
+transports that deliver messages in a fire-and-forget manner only.
+
+An [`AckingListener`] can be registered with any [`UTransport`](crate::UTransport) like any other
+[`UListener`]. It hands over each received message to an [`AcknowledgingHandler`], which acknowledges
+the message by returning `Ok` or rejects it by returning an error. Rejected messages are handed over
+to the handler again, according to the [`RedeliveryPolicy`] used by the listener's
+[`RedeliveryCoordinator`]. Messages that have not been acknowledged after the maximum number of
+redeliveries, or which have expired in the meantime, are _dead-lettered_.
+*/
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tracing::{debug, info};
+
+use crate::{task_tracker::TaskTracker, UAttributesValidators, UListener, UMessage, UStatus};
+
+/// A handler for processing messages that need to be acknowledged explicitly.
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+#[async_trait]
+pub trait AcknowledgingHandler: Send + Sync {
+    /// Processes a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to process.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be processed. The message will then be
+    /// redelivered according to the [`RedeliveryPolicy`] in use.
+    async fn handle_message(&self, msg: UMessage) -> Result<(), UStatus>;
+}
+
+/// Determines how often and when unacknowledged messages are being redelivered.
+///
+/// The delay between two consecutive delivery attempts starts with the initial backoff and
+/// doubles with each redelivery, until it reaches the maximum backoff.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedeliveryPolicy {
+    max_redeliveries: u16,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RedeliveryPolicy {
+    /// Creates a policy that redelivers messages up to three times, starting with a backoff of 100ms.
+    fn default() -> Self {
+        RedeliveryPolicy {
+            max_redeliveries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RedeliveryPolicy {
+    /// Creates a new policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_redeliveries` - The maximum number of times that an unacknowledged message is redelivered.
+    /// * `initial_backoff` - The delay before the first redelivery.
+    /// * `max_backoff` - The upper limit for the delay between two consecutive delivery attempts.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use up_rust::redelivery::RedeliveryPolicy;
+    ///
+    /// let policy = RedeliveryPolicy::new(5, Duration::from_millis(50), Duration::from_secs(1));
+    /// assert_eq!(policy.max_redeliveries(), 5);
+    /// assert_eq!(policy.backoff(1), Duration::from_millis(50));
+    /// assert_eq!(policy.backoff(3), Duration::from_millis(200));
+    /// assert_eq!(policy.backoff(10), Duration::from_secs(1));
+    /// ```
+    pub fn new(max_redeliveries: u16, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        RedeliveryPolicy {
+            max_redeliveries,
+            initial_backoff,
+            max_backoff,
+        }
+    }
+
+    /// Gets the maximum number of times that an unacknowledged message is redelivered.
+    pub fn max_redeliveries(&self) -> u16 {
+        self.max_redeliveries
+    }
+
+    /// Gets the delay to wait for before redelivering a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `redelivery` - The (1-based) number of the redelivery attempt.
+    pub fn backoff(&self, redelivery: u16) -> Duration {
+        let exponent = u32::from(redelivery.saturating_sub(1)).min(31);
+        self.initial_backoff
+            .checked_mul(1_u32 << exponent)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+/// Delivers messages to [`AcknowledgingHandler`]s until they get acknowledged.
+///
+/// Messages which have not been acknowledged after the maximum number of redeliveries
+/// or which have expired according to their time-to-live are dead-lettered, i.e. handed
+/// over to the (optional) dead letter listener.
+pub struct RedeliveryCoordinator {
+    policy: RedeliveryPolicy,
+    dead_letter_listener: Option<Arc<dyn UListener>>,
+}
+
+impl RedeliveryCoordinator {
+    /// Creates a new coordinator.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy to use for redelivering unacknowledged messages.
+    /// * `dead_letter_listener` - The listener to hand over messages to that could not be delivered,
+    ///   or `None` if such messages should simply be dropped.
+    pub fn new(policy: RedeliveryPolicy, dead_letter_listener: Option<Arc<dyn UListener>>) -> Self {
+        RedeliveryCoordinator {
+            policy,
+            dead_letter_listener,
+        }
+    }
+
+    /// Delivers a message to a handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to deliver the message to.
+    /// * `msg` - The message to deliver.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the message has been acknowledged by the handler,
+    /// `false` if the message has been dead-lettered.
+    pub async fn deliver(&self, handler: &dyn AcknowledgingHandler, msg: UMessage) -> bool {
+        let mut redelivery = 0_u16;
+        loop {
+            match handler.handle_message(msg.clone()).await {
+                Ok(_) => return true,
+                Err(e) => {
+                    debug!(redelivery, "handler failed to process message: {}", e);
+                }
+            }
+            if redelivery >= self.policy.max_redeliveries {
+                info!(
+                    redeliveries = redelivery,
+                    "message has not been acknowledged, dead-lettering message"
+                );
+                break;
+            }
+            redelivery += 1;
+            tokio::time::sleep(self.policy.backoff(redelivery)).await;
+            if Self::is_expired(&msg) {
+                info!("message has expired, dead-lettering message");
+                break;
+            }
+        }
+        if let Some(listener) = self.dead_letter_listener.as_ref() {
+            listener.on_receive(msg).await;
+        }
+        false
+    }
+
+    fn is_expired(msg: &UMessage) -> bool {
+        msg.attributes.as_ref().map_or(false, |attribs| {
+            UAttributesValidators::get_validator_for_attributes(attribs)
+                .is_expired(attribs)
+                .is_err()
+        })
+    }
+}
+
+/// A [`UListener`] that processes messages with _at-least-once_ semantics.
+///
+/// Each message received by the listener is delivered to an [`AcknowledgingHandler`] by means of a
+/// [`RedeliveryCoordinator`] on a separate task, i.e. [`UListener::on_receive`] returns immediately.
+pub struct AckingListener {
+    handler: Arc<dyn AcknowledgingHandler>,
+    coordinator: Arc<RedeliveryCoordinator>,
+    tasks: TaskTracker,
+}
+
+impl AckingListener {
+    /// Creates a new listener.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to deliver received messages to.
+    /// * `coordinator` - The coordinator to use for (re-)delivering messages to the handler.
+    pub fn new(
+        handler: Arc<dyn AcknowledgingHandler>,
+        coordinator: Arc<RedeliveryCoordinator>,
+    ) -> Self {
+        AckingListener {
+            handler,
+            coordinator,
+            tasks: TaskTracker::new("acking-listener"),
+        }
+    }
+
+    /// Gets the tracker of the tasks delivering messages to the handler.
+    pub fn task_tracker(&self) -> &TaskTracker {
+        &self.tasks
+    }
+}
+
+#[async_trait]
+impl UListener for AckingListener {
+    async fn on_receive(&self, msg: UMessage) {
+        let handler = self.handler.clone();
+        let coordinator = self.coordinator.clone();
+        self.tasks.spawn("delivery", async move {
+            coordinator.deliver(handler.as_ref(), msg).await
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mockall::Sequence;
+    use tokio::sync::Notify;
+
+    use crate::{utransport::MockUListener, UCode, UMessageBuilder, UUri};
+
+    fn new_policy(max_redeliveries: u16) -> RedeliveryPolicy {
+        RedeliveryPolicy::new(
+            max_redeliveries,
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+        )
+    }
+
+    fn new_message() -> UMessage {
+        UMessageBuilder::publish(UUri::try_from("//my-vehicle/A100/1/A1B3").unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy =
+            RedeliveryPolicy::new(u16::MAX, Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u16::MAX), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_deliver_succeeds_after_redelivery() {
+        // GIVEN a handler that acknowledges a message on the third attempt only
+        let mut handler = MockAcknowledgingHandler::new();
+        let mut seq = Sequence::new();
+        handler
+            .expect_handle_message()
+            .times(2)
+            .in_sequence(&mut seq)
+            .returning(|_msg| Err(UStatus::fail_with_code(UCode::UNAVAILABLE, "busy")));
+        handler
+            .expect_handle_message()
+            .once()
+            .in_sequence(&mut seq)
+            .returning(|_msg| Ok(()));
+        let mut dead_letter_listener = MockUListener::new();
+        dead_letter_listener.expect_on_receive().never();
+
+        let coordinator =
+            RedeliveryCoordinator::new(new_policy(3), Some(Arc::new(dead_letter_listener)));
+
+        // WHEN delivering a message
+        // THEN the message gets acknowledged
+        assert!(coordinator.deliver(&handler, new_message()).await);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_dead_letters_unacknowledged_message() {
+        // GIVEN a handler that never acknowledges a message
+        let mut handler = MockAcknowledgingHandler::new();
+        handler
+            .expect_handle_message()
+            .times(3)
+            .returning(|_msg| Err(UStatus::fail_with_code(UCode::UNAVAILABLE, "busy")));
+        let mut dead_letter_listener = MockUListener::new();
+        dead_letter_listener
+            .expect_on_receive()
+            .once()
+            .return_const(());
+
+        let coordinator =
+            RedeliveryCoordinator::new(new_policy(2), Some(Arc::new(dead_letter_listener)));
+
+        // WHEN delivering a message
+        // THEN the message gets dead-lettered after two redeliveries
+        assert!(!coordinator.deliver(&handler, new_message()).await);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_dead_letters_expired_message() {
+        // GIVEN a handler that never acknowledges a message
+        let mut handler = MockAcknowledgingHandler::new();
+        handler
+            .expect_handle_message()
+            .once()
+            .returning(|_msg| Err(UStatus::fail_with_code(UCode::UNAVAILABLE, "busy")));
+        let mut dead_letter_listener = MockUListener::new();
+        dead_letter_listener
+            .expect_on_receive()
+            .once()
+            .return_const(());
+
+        let policy = RedeliveryPolicy::new(5, Duration::from_millis(50), Duration::from_secs(1));
+        let coordinator = RedeliveryCoordinator::new(policy, Some(Arc::new(dead_letter_listener)));
+
+        // WHEN delivering a message that expires before it can be redelivered
+        let msg = UMessageBuilder::publish(UUri::try_from("//my-vehicle/A100/1/A1B3").unwrap())
+            .with_ttl(10)
+            .build()
+            .unwrap();
+
+        // THEN the message gets dead-lettered without being redelivered
+        assert!(!coordinator.deliver(&handler, msg).await);
+    }
+
+    #[tokio::test]
+    async fn test_acking_listener_delivers_message_to_handler() {
+        let notify = Arc::new(Notify::new());
+        let notify_clone = notify.clone();
+        let mut handler = MockAcknowledgingHandler::new();
+        handler
+            .expect_handle_message()
+            .once()
+            .returning(move |_msg| {
+                notify_clone.notify_one();
+                Ok(())
+            });
+
+        let listener = AckingListener::new(
+            Arc::new(handler),
+            Arc::new(RedeliveryCoordinator::new(new_policy(1), None)),
+        );
+        listener.on_receive(new_message()).await;
+
+        let result = tokio::time::timeout(Duration::from_secs(2), notify.notified()).await;
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,527 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a rules engine for components that forward messages between transports,
+e.g. a streamer connecting the in-vehicle network to a cloud backend.
+
+A [`RoutingRules`] instance consists of an ordered list of routes. Each route matches messages
+based on their source and sink addresses and, optionally, on their type, priority and payload format.
+The first route that matches a message determines the name of the route (which a forwarder can map
+to an egress transport) and the transformations to apply to the message before forwarding it.
+
+If the addresses of uEntities differ on either side of the forwarder, a [`UriRewriter`] can be
+[set](`RoutingRules::with_uri_rewriter`) for translating the source and sink addresses of routed messages.
+
+The rules are created from a [`RoutingConfig`], which can be loaded from any format supported
+by [serde](https://serde.rs/) if the `serde` feature is enabled.
+
+```rust
+use up_rust::routing::{RouteConfig, RoutingConfig, RoutingRules, TransformationConfig};
+use up_rust::{UMessageBuilder, UPriority, UUri};
+
+let config = RoutingConfig {
+    routes: vec![RouteConfig {
+        name: "cloud".to_string(),
+        source: Some("//vehicle/FFFF/FF/FFFF".to_string()),
+        message_types: vec!["up-pub.v1".to_string()],
+        transformations: vec![
+            TransformationConfig::BumpPriority { min: "CS3".to_string() },
+            TransformationConfig::StripPayload,
+        ],
+        ..Default::default()
+    }],
+};
+let rules = RoutingRules::try_from(config).unwrap();
+
+let event = UMessageBuilder::publish(UUri::try_from("//vehicle/D45/1/8001").unwrap())
+    .build_with_payload("open", up_rust::UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+    .unwrap();
+let routed = rules.route(event).unwrap();
+assert_eq!(routed.route, "cloud");
+assert_eq!(routed.message.attributes.priority.enum_value_or_default(), UPriority::UPRIORITY_CS3);
+assert!(routed.message.payload.is_none());
+```
+*/
+
+use protobuf::Enum;
+
+use crate::{
+    UAttributesError, UMessage, UMessageType, UPayloadFormat, UPriority, UUri, UriRewriter,
+};
+
+/// An error indicating that a [`RoutingConfig`] contains invalid settings.
+#[derive(Debug)]
+pub struct RoutingConfigError {
+    route: String,
+    message: String,
+}
+
+impl RoutingConfigError {
+    fn new<T: Into<String>>(route: &str, message: T) -> Self {
+        RoutingConfigError {
+            route: route.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Gets the name of the route that contains the invalid setting.
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+}
+
+impl std::fmt::Display for RoutingConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid route [{}]: {}", self.route, self.message)
+    }
+}
+
+impl std::error::Error for RoutingConfigError {}
+
+/// A transformation to apply to messages matching a route.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum TransformationConfig {
+    /// Replaces the authority of the message's sink address.
+    RewriteSinkAuthority {
+        /// The new authority.
+        authority: String,
+    },
+    /// Raises the message's priority to a minimum priority.
+    BumpPriority {
+        /// The priority code of the minimum priority, e.g. `CS4`.
+        min: String,
+    },
+    /// Removes the message's payload.
+    StripPayload,
+}
+
+/// The settings of a route.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct RouteConfig {
+    /// The name of the route.
+    pub name: String,
+    /// The URI pattern that the message's source address must match, any source if not set.
+    pub source: Option<String>,
+    /// The URI pattern that the message's sink address must match, any sink (including none) if not set.
+    pub sink: Option<String>,
+    /// The CloudEvent type names of the message types to match, e.g. `up-pub.v1`, all types if empty.
+    pub message_types: Vec<String>,
+    /// The codes of the priorities to match, e.g. `CS4`, all priorities if empty.
+    pub priorities: Vec<String>,
+    /// The media types of the payload formats to match, e.g. `application/json`, all formats if empty.
+    pub payload_formats: Vec<String>,
+    /// The transformations to apply to matching messages, in order.
+    pub transformations: Vec<TransformationConfig>,
+}
+
+/// The settings of a [`RoutingRules`] instance.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct RoutingConfig {
+    /// The routes, in the order in which they are evaluated.
+    pub routes: Vec<RouteConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Transformation {
+    RewriteSinkAuthority(String),
+    BumpPriority(UPriority),
+    StripPayload,
+}
+
+impl Transformation {
+    fn apply(&self, message: &mut UMessage) {
+        match self {
+            Transformation::RewriteSinkAuthority(authority) => {
+                if let Some(sink) = message.attributes.mut_or_insert_default().sink.as_mut() {
+                    sink.authority_name = authority.to_owned();
+                }
+            }
+            Transformation::BumpPriority(min_priority) => {
+                let attributes = message.attributes.mut_or_insert_default();
+                if attributes.priority.enum_value_or_default().value() < min_priority.value() {
+                    attributes.priority = (*min_priority).into();
+                }
+            }
+            Transformation::StripPayload => {
+                message.payload = None;
+                message.attributes.mut_or_insert_default().payload_format =
+                    UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED.into();
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Route {
+    config: RouteConfig,
+    name: String,
+    source: Option<UUri>,
+    sink: Option<UUri>,
+    message_types: Vec<UMessageType>,
+    priorities: Vec<UPriority>,
+    payload_formats: Vec<UPayloadFormat>,
+    transformations: Vec<Transformation>,
+}
+
+impl Route {
+    fn matches(&self, message: &UMessage) -> bool {
+        let Some(attributes) = message.attributes.as_ref() else {
+            return false;
+        };
+        let matches_uri = |pattern: Option<&UUri>, uri: Option<&UUri>| match (pattern, uri) {
+            (None, _) => true,
+            (Some(pattern), Some(uri)) => pattern.matches(uri),
+            (Some(_pattern), None) => false,
+        };
+        matches_uri(self.source.as_ref(), attributes.source.as_ref())
+            && matches_uri(self.sink.as_ref(), attributes.sink.as_ref())
+            && (self.message_types.is_empty()
+                || self
+                    .message_types
+                    .contains(&attributes.type_.enum_value_or_default()))
+            && (self.priorities.is_empty()
+                || self
+                    .priorities
+                    .contains(&attributes.priority.enum_value_or_default()))
+            && (self.payload_formats.is_empty()
+                || self
+                    .payload_formats
+                    .contains(&attributes.payload_format.enum_value_or_default()))
+    }
+}
+
+impl TryFrom<RouteConfig> for Route {
+    type Error = RoutingConfigError;
+
+    fn try_from(config: RouteConfig) -> Result<Self, Self::Error> {
+        let route_config = config.clone();
+        let name = config.name;
+        let parse_uri = |pattern: Option<String>| {
+            pattern
+                .map(|p| {
+                    UUri::try_from(p.as_str())
+                        .map_err(|e| RoutingConfigError::new(&name, e.to_string()))
+                })
+                .transpose()
+        };
+        let to_config_error = |e: UAttributesError| RoutingConfigError::new(&name, e.to_string());
+        let source = parse_uri(config.source)?;
+        let sink = parse_uri(config.sink)?;
+        let message_types = config
+            .message_types
+            .into_iter()
+            .map(|t| UMessageType::try_from_cloudevent_type(t).map_err(to_config_error))
+            .collect::<Result<Vec<_>, _>>()?;
+        let priorities = config
+            .priorities
+            .into_iter()
+            .map(|p| UPriority::try_from_priority_code(p).map_err(to_config_error))
+            .collect::<Result<Vec<_>, _>>()?;
+        let payload_formats = config
+            .payload_formats
+            .iter()
+            .map(|f| {
+                UPayloadFormat::from_media_type(f)
+                    .map_err(|e| RoutingConfigError::new(&name, e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let transformations = config
+            .transformations
+            .into_iter()
+            .map(|t| match t {
+                TransformationConfig::RewriteSinkAuthority { authority } => {
+                    Ok(Transformation::RewriteSinkAuthority(authority))
+                }
+                TransformationConfig::BumpPriority { min } => {
+                    UPriority::try_from_priority_code(min)
+                        .map(Transformation::BumpPriority)
+                        .map_err(to_config_error)
+                }
+                TransformationConfig::StripPayload => Ok(Transformation::StripPayload),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Route {
+            config: route_config,
+            name,
+            source,
+            sink,
+            message_types,
+            priorities,
+            payload_formats,
+            transformations,
+        })
+    }
+}
+
+/// A message that matches one of the routes of a [`RoutingRules`] instance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoutedMessage {
+    /// The name of the matching route.
+    pub route: String,
+    /// The message after the route's transformations have been applied.
+    pub message: UMessage,
+}
+
+/// An ordered list of routes for forwarding messages.
+///
+/// See the [module documentation](`crate::routing`) for an example.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RoutingRules {
+    routes: Vec<Route>,
+    uri_rewriter: UriRewriter,
+}
+
+impl TryFrom<RoutingConfig> for RoutingRules {
+    type Error = RoutingConfigError;
+
+    fn try_from(config: RoutingConfig) -> Result<Self, Self::Error> {
+        config
+            .routes
+            .into_iter()
+            .map(Route::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|routes| RoutingRules {
+                routes,
+                uri_rewriter: UriRewriter::default(),
+            })
+    }
+}
+
+impl RoutingRules {
+    /// Sets the rewriter to apply to the source and sink addresses of routed messages.
+    ///
+    /// Routes are matched against the original addresses. The addresses are rewritten before
+    /// the matching route's transformations are applied.
+    pub fn with_uri_rewriter(mut self, uri_rewriter: UriRewriter) -> Self {
+        self.uri_rewriter = uri_rewriter;
+        self
+    }
+
+    /// Replaces all routes with the routes of a configuration.
+    ///
+    /// The URI rewriter remains unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the routes is invalid. The existing routes remain unchanged in this case.
+    pub fn apply_config(&mut self, config: RoutingConfig) -> Result<(), RoutingConfigError> {
+        self.routes = RoutingRules::try_from(config)?.routes;
+        Ok(())
+    }
+
+    /// Gets the configuration that this instance has been created from.
+    pub fn export_config(&self) -> RoutingConfig {
+        RoutingConfig {
+            routes: self
+                .routes
+                .iter()
+                .map(|route| route.config.clone())
+                .collect(),
+        }
+    }
+
+    /// Finds the first route matching a message and applies the route's transformations.
+    ///
+    /// # Returns
+    ///
+    /// The transformed message along with the name of the route, or `None` if no route matches.
+    pub fn route(&self, message: UMessage) -> Option<RoutedMessage> {
+        let route = self.routes.iter().find(|route| route.matches(&message))?;
+        let mut message = message;
+        self.uri_rewriter.rewrite_message(&mut message);
+        route
+            .transformations
+            .iter()
+            .for_each(|transformation| transformation.apply(&mut message));
+        Some(RoutedMessage {
+            route: route.name.to_owned(),
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::UMessageBuilder;
+
+    fn notification() -> UMessage {
+        UMessageBuilder::notification(
+            UUri::try_from("//vehicle/D45/1/8001").unwrap(),
+            UUri::try_from("//vehicle/D46/1/0").unwrap(),
+        )
+        .with_priority(UPriority::UPRIORITY_CS2)
+        .build_with_payload("{}", UPayloadFormat::UPAYLOAD_FORMAT_JSON)
+        .unwrap()
+    }
+
+    #[test_case(RouteConfig::default(), true; "empty route")]
+    #[test_case(RouteConfig { sink: Some("//*/D46/1/0".to_string()), ..Default::default() }, true; "matching sink")]
+    #[test_case(RouteConfig { sink: Some("//*/D47/1/0".to_string()), ..Default::default() }, false; "non-matching sink")]
+    #[test_case(RouteConfig { message_types: vec!["up-pub.v1".to_string()], ..Default::default() }, false; "non-matching message type")]
+    #[test_case(RouteConfig { priorities: vec!["CS1".to_string(), "CS2".to_string()], ..Default::default() }, true; "matching priority")]
+    #[test_case(RouteConfig { payload_formats: vec!["application/x-protobuf".to_string()], ..Default::default() }, false; "non-matching payload format")]
+    fn test_route_matches(route: RouteConfig, expected_match: bool) {
+        let rules = RoutingRules::try_from(RoutingConfig {
+            routes: vec![route],
+        })
+        .unwrap();
+        assert_eq!(rules.route(notification()).is_some(), expected_match);
+    }
+
+    #[test]
+    fn test_route_applies_transformations_of_first_matching_route() {
+        let rules = RoutingRules::try_from(RoutingConfig {
+            routes: vec![
+                RouteConfig {
+                    name: "requests".to_string(),
+                    message_types: vec!["up-req.v1".to_string()],
+                    ..Default::default()
+                },
+                RouteConfig {
+                    name: "notifications".to_string(),
+                    transformations: vec![
+                        TransformationConfig::RewriteSinkAuthority {
+                            authority: "backend".to_string(),
+                        },
+                        TransformationConfig::BumpPriority {
+                            min: "CS4".to_string(),
+                        },
+                    ],
+                    ..Default::default()
+                },
+            ],
+        })
+        .unwrap();
+
+        let routed = rules.route(notification()).unwrap();
+        assert_eq!(routed.route, "notifications");
+        let attributes = routed.message.attributes.get_or_default();
+        assert_eq!(attributes.sink.get_or_default().authority_name, "backend");
+        assert_eq!(
+            attributes.priority.enum_value_or_default(),
+            UPriority::UPRIORITY_CS4
+        );
+        assert!(routed.message.payload.is_some());
+    }
+
+    #[test_case(RouteConfig { source: Some("//vehicle/D45".to_string()), ..Default::default() }; "invalid source pattern")]
+    #[test_case(RouteConfig { message_types: vec!["publish".to_string()], ..Default::default() }; "unknown message type")]
+    #[test_case(RouteConfig { transformations: vec![TransformationConfig::BumpPriority { min: "CS9".to_string() }], ..Default::default() }; "unknown priority")]
+    fn test_try_from_config_fails_for_invalid_route(route: RouteConfig) {
+        let config = RoutingConfig {
+            routes: vec![RouteConfig {
+                name: "invalid".to_string(),
+                ..route
+            }],
+        };
+        assert!(RoutingRules::try_from(config).is_err_and(|e| e.route() == "invalid"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_can_be_deserialized() {
+        let document = r#"{
+            "routes": [{
+                "name": "cloud",
+                "source": "//vehicle/FFFF/FF/FFFF",
+                "priorities": ["CS2"],
+                "transformations": [
+                    { "type": "rewrite_sink_authority", "authority": "backend" },
+                    { "type": "strip_payload" }
+                ]
+            }]
+        }"#;
+        let config: RoutingConfig = serde_json::from_str(document).unwrap();
+        assert_eq!(
+            config.routes[0].transformations,
+            vec![
+                TransformationConfig::RewriteSinkAuthority {
+                    authority: "backend".to_string()
+                },
+                TransformationConfig::StripPayload
+            ]
+        );
+        let rules = RoutingRules::try_from(config.clone()).unwrap();
+        let routed = rules.route(notification()).unwrap();
+        assert!(routed.message.payload.is_none());
+
+        // and the exported configuration can be serialized again
+        let exported = serde_json::to_value(rules.export_config()).unwrap();
+        assert_eq!(exported, serde_json::to_value(config).unwrap());
+    }
+
+    #[test]
+    fn test_route_rewrites_addresses_after_matching() {
+        let rules = RoutingRules::try_from(RoutingConfig {
+            routes: vec![RouteConfig {
+                name: "cloud".to_string(),
+                source: Some("//vehicle/D45/1/FFFF".to_string()),
+                ..Default::default()
+            }],
+        })
+        .unwrap()
+        .with_uri_rewriter(
+            UriRewriter::default().with_rule(
+                crate::UriRewriteRule::new(UUri::try_from("//vehicle/FFFF/FF/FFFF").unwrap())
+                    .with_authority(crate::AuthorityName::try_from("vin-1234").unwrap()),
+            ),
+        );
+
+        let routed = rules.route(notification()).unwrap();
+        let attributes = routed.message.attributes.get_or_default();
+        assert_eq!(
+            attributes.source.get_or_default().authority_name,
+            "vin-1234"
+        );
+        assert_eq!(attributes.sink.get_or_default().authority_name, "vin-1234");
+    }
+
+    #[test]
+    fn test_apply_config_keeps_routes_for_invalid_config() {
+        let config = RoutingConfig {
+            routes: vec![RouteConfig {
+                name: "all".to_string(),
+                ..Default::default()
+            }],
+        };
+        let mut rules = RoutingRules::try_from(config.clone()).unwrap();
+
+        let invalid_config = RoutingConfig {
+            routes: vec![RouteConfig {
+                priorities: vec!["CS9".to_string()],
+                ..Default::default()
+            }],
+        };
+        assert!(rules.apply_config(invalid_config).is_err());
+        assert_eq!(rules.export_config(), config);
+    }
+}
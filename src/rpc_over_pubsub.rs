@@ -0,0 +1,382 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a UTransport adapter which emulates request/reply interactions by means of publish/subscribe.
+
+Some transports are built on top of brokers that only support routing of messages based on
+topics, e.g. plain MQTT brokers without any uProtocol specific routing. The [`RpcOverPubSub`]
+adapter allows using the Communication Layer API's RPC default implementations on top of such
+transports by wrapping RPC Request and Response messages in Publish messages, using the
+following convention:
+
+* RPC Request messages are published to the _request topic_ of the service being invoked, which has
+  the service's entity ID and version and resource ID [`RPC_REQUEST_TOPIC_ID`].
+* RPC Response messages are published to the _reply topic_ of the client that has invoked the service,
+  which has the client's entity ID and version and resource ID [`RPC_REPLY_TOPIC_ID`].
+
+The original message is contained in the Publish message's payload, encoded as protobuf. Both sides of
+an interaction need to use the adapter.
+*/
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use protobuf::Message;
+use tracing::debug;
+
+use crate::{
+    utransport::send_checked_batch, ComparableListener, UCode, UListener, UMessage,
+    UMessageBuilder, UPayloadFormat, UStatus, UTransport, UUri,
+};
+
+/// The resource ID of the topic that RPC Request messages for a service are published to.
+pub const RPC_REQUEST_TOPIC_ID: u16 = 0xFFFD;
+/// The resource ID of the topic that RPC Response messages for a client are published to.
+pub const RPC_REPLY_TOPIC_ID: u16 = 0xFFFC;
+
+fn topic_for(entity: &UUri, resource_id: u16) -> UUri {
+    UUri {
+        resource_id: resource_id as u32,
+        ..entity.to_owned()
+    }
+}
+
+/// Determines the topic that messages matching a sink filter are published to.
+///
+/// Returns `None` if the sink filter does not refer to RPC Request or Response messages.
+fn topic_for_sink_filter(sink_filter: Option<&UUri>) -> Option<UUri> {
+    let sink_filter = sink_filter?;
+    if sink_filter.is_rpc_method() {
+        Some(topic_for(sink_filter, RPC_REQUEST_TOPIC_ID))
+    } else if sink_filter.is_rpc_response() {
+        Some(topic_for(sink_filter, RPC_REPLY_TOPIC_ID))
+    } else {
+        None
+    }
+}
+
+/// Wraps a message in a Publish message for a given topic.
+fn wrap(message: &UMessage, topic: UUri) -> Result<UMessage, UStatus> {
+    let attribs = message.attributes.get_or_default();
+    let payload = message.write_to_bytes().map_err(|e| {
+        UStatus::fail_with_code(UCode::INTERNAL, format!("failed to encode message: {e}"))
+    })?;
+    let mut builder = UMessageBuilder::publish(topic);
+    builder.with_priority(attribs.priority.enum_value_or_default());
+    if let Some(ttl) = attribs.ttl {
+        builder.with_ttl(ttl);
+    }
+    builder
+        .build_with_payload(payload, UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF)
+        .map_err(|e| UStatus::fail_with_code(UCode::INVALID_ARGUMENT, e.to_string()))
+}
+
+/// Gets the message to send via the underlying transport for a message being sent.
+///
+/// RPC Request and Response messages are wrapped in a message published to the sink's request
+/// or reply topic, all other messages are sent as is.
+fn to_published_message(message: UMessage) -> Result<UMessage, UStatus> {
+    let topic_id = if message.is_request() {
+        RPC_REQUEST_TOPIC_ID
+    } else if message.is_response() {
+        RPC_REPLY_TOPIC_ID
+    } else {
+        return Ok(message);
+    };
+    let Some(sink) = message.attributes.get_or_default().sink.as_ref() else {
+        return Err(UStatus::fail_with_code(
+            UCode::INVALID_ARGUMENT,
+            "message has no sink",
+        ));
+    };
+    wrap(&message, topic_for(sink, topic_id))
+}
+
+#[derive(Eq, PartialEq, Hash)]
+struct Registration {
+    source_filter: UUri,
+    sink_filter: Option<UUri>,
+    listener: ComparableListener,
+}
+
+/// A [`UListener`] which extracts wrapped messages from Publish messages.
+struct Unwrapper {
+    source_filter: UUri,
+    sink_filter: UUri,
+    listener: Arc<dyn UListener>,
+}
+
+#[async_trait]
+impl UListener for Unwrapper {
+    async fn on_receive(&self, msg: UMessage) {
+        let Some(payload) = msg.payload.as_ref() else {
+            debug!("ignoring published message without wrapped message");
+            return;
+        };
+        let wrapped_message = match UMessage::parse_from_bytes(payload) {
+            Ok(message) => message,
+            Err(e) => {
+                debug!(
+                    "ignoring published message with invalid wrapped message: {}",
+                    e
+                );
+                return;
+            }
+        };
+        let attribs = wrapped_message.attributes.get_or_default();
+        if self.source_filter.matches(attribs.source.get_or_default())
+            && self.sink_filter.matches(attribs.sink.get_or_default())
+        {
+            self.listener.on_receive(wrapped_message).await;
+        }
+    }
+}
+
+// the topic filter and unwrapper that have been registered with the underlying transport
+type RegisteredListener = (UUri, Arc<dyn UListener>);
+
+/// A transport that sends RPC Request and Response messages via topics.
+///
+/// Listeners for RPC Request messages and for RPC Response messages are registered for the
+/// corresponding request or reply topic with the underlying transport. All other messages and
+/// listeners are passed on to the underlying transport as is.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use up_rust::{local_transport::LocalTransport, rpc_over_pubsub::RpcOverPubSub, UTransport};
+///
+/// // the transport can be used with the Communication Layer API's RpcClient and RpcServer
+/// // default implementations
+/// let transport: Arc<dyn UTransport> =
+///     Arc::new(RpcOverPubSub::new(Arc::new(LocalTransport::default())));
+/// ```
+pub struct RpcOverPubSub {
+    transport: Arc<dyn UTransport>,
+    // registered listener -> topic filter and unwrapper registered with underlying transport
+    registrations: Mutex<HashMap<Registration, RegisteredListener>>,
+}
+
+impl RpcOverPubSub {
+    /// Creates a new adapter for a given underlying transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The (publish/subscribe only) transport to delegate to.
+    pub fn new(transport: Arc<dyn UTransport>) -> Self {
+        RpcOverPubSub {
+            transport,
+            registrations: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl UTransport for RpcOverPubSub {
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        let published_message = to_published_message(message)?;
+        self.transport.send(published_message).await
+    }
+
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        send_checked_batch(self.transport.as_ref(), messages, to_published_message).await
+    }
+
+    async fn receive(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Result<UMessage, UStatus> {
+        self.transport.receive(source_filter, sink_filter).await
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let (Some(topic_filter), Some(sink_filter)) =
+            (topic_for_sink_filter(sink_filter), sink_filter)
+        else {
+            return self
+                .transport
+                .register_listener(source_filter, sink_filter, listener)
+                .await;
+        };
+        let registration = Registration {
+            source_filter: source_filter.to_owned(),
+            sink_filter: Some(sink_filter.to_owned()),
+            listener: ComparableListener::new(listener.clone()),
+        };
+        let unwrapper = self
+            .registrations
+            .lock()
+            .ok()
+            .and_then(|registrations| {
+                registrations
+                    .get(&registration)
+                    .map(|(_topic_filter, unwrapper)| unwrapper.clone())
+            })
+            .unwrap_or_else(|| {
+                Arc::new(Unwrapper {
+                    source_filter: source_filter.to_owned(),
+                    sink_filter: sink_filter.to_owned(),
+                    listener,
+                })
+            });
+        self.transport
+            .register_listener(&topic_filter, None, unwrapper.clone())
+            .await?;
+        if let Ok(mut registrations) = self.registrations.lock() {
+            registrations.insert(registration, (topic_filter, unwrapper));
+        }
+        Ok(())
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        if topic_for_sink_filter(sink_filter).is_none() {
+            return self
+                .transport
+                .unregister_listener(source_filter, sink_filter, listener)
+                .await;
+        }
+        let registration = Registration {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.cloned(),
+            listener: ComparableListener::new(listener),
+        };
+        let Some((topic_filter, unwrapper)) = self
+            .registrations
+            .lock()
+            .ok()
+            .and_then(|registrations| registrations.get(&registration).cloned())
+        else {
+            return Err(UStatus::fail_with_code(
+                UCode::NOT_FOUND,
+                "no such listener registered",
+            ));
+        };
+        self.transport
+            .unregister_listener(&topic_filter, None, unwrapper)
+            .await?;
+        if let Ok(mut registrations) = self.registrations.lock() {
+            registrations.remove(&registration);
+        }
+        Ok(())
+    }
+
+    async fn probe(&self, peer_authority: &str) -> Result<(), UStatus> {
+        self.transport.probe(peer_authority).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utransport::{MockTransport, MockUListener};
+
+    fn request_message() -> UMessage {
+        let method = UUri::try_from("//other/1000/1/7").unwrap();
+        let reply_to = UUri::try_from("//my-vehicle/A34B/1/0").unwrap();
+        UMessageBuilder::request(method, reply_to, 5_000)
+            .build_with_payload("hello", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send_publishes_request_to_request_topic() {
+        // GIVEN an adapter for a publish/subscribe transport
+        let mut underlying_transport = MockTransport::new();
+        underlying_transport
+            .expect_do_send()
+            .once()
+            .withf(|msg| {
+                // THEN the request is published to the service's request topic
+                let topic = msg.attributes.get_or_default().source.get_or_default();
+                msg.is_publish()
+                    && topic.authority_name == "other"
+                    && topic.ue_id == 0x1000
+                    && topic.resource_id == RPC_REQUEST_TOPIC_ID as u32
+                    && UMessage::parse_from_bytes(msg.payload.as_ref().unwrap())
+                        .is_ok_and(|wrapped| wrapped.is_request())
+            })
+            .return_const(Ok(()));
+        let transport = RpcOverPubSub::new(Arc::new(underlying_transport));
+
+        // WHEN sending an RPC Request message
+        assert!(transport.send(request_message()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_listener_receives_unwrapped_request() {
+        let captured_listener = Arc::new(Mutex::new(None));
+        let captured_listener_clone = captured_listener.clone();
+
+        // GIVEN an adapter for a publish/subscribe transport
+        let mut underlying_transport = MockTransport::new();
+        underlying_transport
+            .expect_do_register_listener()
+            .once()
+            .withf(|source_filter, sink_filter, _listener| {
+                // THEN the listener is registered for the service's request topic
+                source_filter.resource_id == RPC_REQUEST_TOPIC_ID as u32
+                    && source_filter.ue_id == 0x1000
+                    && sink_filter.is_none()
+            })
+            .returning(move |_source_filter, _sink_filter, listener| {
+                *captured_listener_clone.lock().unwrap() = Some(listener);
+                Ok(())
+            });
+        let transport = RpcOverPubSub::new(Arc::new(underlying_transport));
+
+        // and a listener for requests to a method of the service
+        let mut listener = MockUListener::new();
+        listener
+            .expect_on_receive()
+            .once()
+            .withf(|msg| msg.is_request())
+            .return_const(());
+        assert!(transport
+            .register_listener(
+                &UUri::any(),
+                Some(&UUri::try_from("//other/1000/1/7").unwrap()),
+                Arc::new(listener),
+            )
+            .await
+            .is_ok());
+
+        // WHEN the underlying transport dispatches a request published to the request topic
+        let request = request_message();
+        let topic = topic_for(
+            request.attributes.sink.get_or_default(),
+            RPC_REQUEST_TOPIC_ID,
+        );
+        let registered_listener = captured_listener.lock().unwrap().clone().unwrap();
+        registered_listener
+            .on_receive(wrap(&request, topic).unwrap())
+            .await;
+
+        // THEN the listener is invoked with the unwrapped request
+    }
+}
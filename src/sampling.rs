@@ -0,0 +1,298 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides means for tapping a fraction of the messages being sent via a transport.
+
+Gateways often need to forward telemetry about the traffic that passes through them without
+forwarding every single message of high-rate topics. The [`Sampler`] decides whether a message
+should be sampled based on the message's ID only, so that all gateways sampling at the same rate
+select the same messages. The [`SamplingTransport`] uses a sampler for passing a fraction of the
+messages being sent to an observer.
+*/
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+
+use crate::{UListener, UMessage, UStatus, UTransport, UUri, UUID};
+
+/// Decides whether a message should be sampled, based on the message's ID.
+///
+/// The decision is deterministic, i.e. the same message is either sampled or not sampled by all
+/// samplers using the same rate.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{sampling::Sampler, UUID};
+///
+/// let sampler = Sampler::new(0.25);
+/// let message_id = UUID::build();
+/// assert_eq!(sampler.is_sampled(&message_id), Sampler::new(0.25).is_sampled(&message_id));
+/// assert!(Sampler::new(1.0).is_sampled(&message_id));
+/// assert!(!Sampler::new(0.0).is_sampled(&message_id));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sampler {
+    threshold: u64,
+    always: bool,
+}
+
+impl Sampler {
+    /// Creates a new sampler.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - The fraction of messages to sample. Values are clamped to the range `[0.0, 1.0]`.
+    pub fn new(rate: f64) -> Self {
+        let rate = if rate.is_nan() {
+            0.0
+        } else {
+            rate.clamp(0.0, 1.0)
+        };
+        Sampler {
+            threshold: (rate * u64::MAX as f64) as u64,
+            always: rate >= 1.0,
+        }
+    }
+
+    /// Checks if the message with a given ID should be sampled.
+    pub fn is_sampled(&self, message_id: &UUID) -> bool {
+        self.always || mix(message_id.msb ^ message_id.lsb.rotate_left(32)) < self.threshold
+    }
+
+    /// Checks if a message should be sampled.
+    ///
+    /// Messages without an ID are never sampled.
+    pub fn is_message_sampled(&self, message: &UMessage) -> bool {
+        message
+            .attributes
+            .as_ref()
+            .and_then(|attribs| attribs.id.as_ref())
+            .is_some_and(|id| self.is_sampled(id))
+    }
+}
+
+/// Distributes the bits of a value evenly (finalizer of the SplitMix64 generator).
+fn mix(value: u64) -> u64 {
+    let mut z = value;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A transport that passes a fraction of the messages being sent to an observer.
+///
+/// Only messages whose source matches the configured pattern are considered. The observer is
+/// invoked after the message has been sent successfully via the underlying transport, on the
+/// task sending the message. Observers should therefore return quickly.
+///
+/// All other operations are delegated to the underlying transport as is.
+pub struct SamplingTransport {
+    transport: Arc<dyn UTransport>,
+    observer: Arc<dyn UListener>,
+    source_filter: UUri,
+    sampler: Sampler,
+    messages_sampled: AtomicU64,
+}
+
+impl SamplingTransport {
+    /// Creates a new transport for a given underlying transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to delegate to.
+    /// * `observer` - The listener to pass the sampled messages to.
+    /// * `source_filter` - The pattern that the source of messages to sample needs to match.
+    /// * `sampler` - The sampler to use for selecting messages.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use up_rust::{
+    ///     local_transport::LocalTransport,
+    ///     sampling::{Sampler, SamplingTransport},
+    ///     UListener, UMessage, UUri,
+    /// };
+    ///
+    /// struct Backhaul;
+    /// #[async_trait::async_trait]
+    /// impl UListener for Backhaul {
+    ///     async fn on_receive(&self, _msg: UMessage) {}
+    /// }
+    ///
+    /// let transport = SamplingTransport::new(
+    ///     Arc::new(LocalTransport::default()),
+    ///     Arc::new(Backhaul),
+    ///     UUri::try_from("//*/FFFF/FF/FFFF").unwrap(),
+    ///     Sampler::new(0.01),
+    /// );
+    /// assert_eq!(transport.messages_sampled(), 0);
+    /// ```
+    pub fn new(
+        transport: Arc<dyn UTransport>,
+        observer: Arc<dyn UListener>,
+        source_filter: UUri,
+        sampler: Sampler,
+    ) -> Self {
+        SamplingTransport {
+            transport,
+            observer,
+            source_filter,
+            sampler,
+            messages_sampled: AtomicU64::new(0),
+        }
+    }
+
+    /// Gets the number of messages that have been passed to the observer.
+    pub fn messages_sampled(&self) -> u64 {
+        self.messages_sampled.load(Ordering::Relaxed)
+    }
+
+    fn is_sampled(&self, message: &UMessage) -> bool {
+        message
+            .attributes
+            .as_ref()
+            .and_then(|attribs| attribs.source.as_ref())
+            .is_some_and(|source| self.source_filter.matches(source))
+            && self.sampler.is_message_sampled(message)
+    }
+}
+
+#[async_trait]
+impl UTransport for SamplingTransport {
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        let sample = self.is_sampled(&message).then(|| message.clone());
+        self.transport.send(message).await?;
+        if let Some(sampled_message) = sample {
+            self.messages_sampled.fetch_add(1, Ordering::Relaxed);
+            self.observer.on_receive(sampled_message).await;
+        }
+        Ok(())
+    }
+
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        let samples: Vec<Option<UMessage>> = messages
+            .iter()
+            .map(|message| self.is_sampled(message).then(|| message.clone()))
+            .collect();
+        let outcomes = self.transport.send_batch(messages).await;
+        for (sample, outcome) in samples.into_iter().zip(outcomes.iter()) {
+            if let (Some(sampled_message), Ok(())) = (sample, outcome) {
+                self.messages_sampled.fetch_add(1, Ordering::Relaxed);
+                self.observer.on_receive(sampled_message).await;
+            }
+        }
+        outcomes
+    }
+
+    async fn receive(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Result<UMessage, UStatus> {
+        self.transport.receive(source_filter, sink_filter).await
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.transport
+            .register_listener(source_filter, sink_filter, listener)
+            .await
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.transport
+            .unregister_listener(source_filter, sink_filter, listener)
+            .await
+    }
+
+    async fn probe(&self, peer_authority: &str) -> Result<(), UStatus> {
+        self.transport.probe(peer_authority).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        utransport::{MockTransport, MockUListener},
+        UMessageBuilder,
+    };
+
+    #[test]
+    fn test_sampler_selects_configured_fraction() {
+        let sampler = Sampler::new(0.1);
+        let sampled = (0..10_000)
+            .filter(|_| sampler.is_sampled(&UUID::build()))
+            .count();
+        assert!(
+            (800..1_200).contains(&sampled),
+            "sampled {sampled} messages"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_passes_matching_sampled_messages_to_observer() {
+        // GIVEN a transport sampling all messages published by entity 0x1000
+        let mut underlying_transport = MockTransport::new();
+        underlying_transport
+            .expect_do_send()
+            .times(2)
+            .return_const(Ok(()));
+        let mut observer = MockUListener::new();
+        observer
+            .expect_on_receive()
+            .once()
+            .withf(|msg| {
+                msg.attributes
+                    .get_or_default()
+                    .source
+                    .get_or_default()
+                    .ue_id
+                    == 0x1000
+            })
+            .return_const(());
+        let transport = SamplingTransport::new(
+            Arc::new(underlying_transport),
+            Arc::new(observer),
+            UUri::try_from("//*/1000/FF/FFFF").unwrap(),
+            Sampler::new(1.0),
+        );
+
+        // WHEN sending messages of different entities
+        for topic in ["//my-vehicle/1000/1/8001", "//my-vehicle/2000/1/8001"] {
+            let message = UMessageBuilder::publish(UUri::try_from(topic).unwrap())
+                .build()
+                .unwrap();
+            assert!(transport.send(message).await.is_ok());
+        }
+
+        // THEN only the matching message has been passed to the observer
+        assert_eq!(transport.messages_sampled(), 1);
+    }
+}
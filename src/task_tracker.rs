@@ -0,0 +1,324 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides means to keep track of the background tasks spawned by a component.
+
+Components that need to run work in the background, e.g. for dispatching messages, spawn their
+tasks by means of a [`TaskTracker`] instead of calling `tokio::spawn` directly. This allows the
+component's owner to find out which tasks are (still) running and to stop all of the tasks when
+shutting down. Trackers can be organized in a hierarchy, so that shutting down a tracker
+also shuts down the tasks of all of its children.
+*/
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+use tokio::task::{AbortHandle, JoinHandle};
+use tracing::debug;
+
+use crate::diagnostics::TaskDiagnostics;
+
+struct TrackedTask {
+    name: String,
+    abort_handle: AbortHandle,
+}
+
+struct TrackerState {
+    name: String,
+    closed: AtomicBool,
+    next_task_id: AtomicU64,
+    tasks: Mutex<HashMap<u64, TrackedTask>>,
+    children: Mutex<Vec<TaskTracker>>,
+    task_finished: Notify,
+}
+
+// removes a task from its tracker when the task's future is dropped,
+// i.e. when the task has completed or has been aborted
+struct TaskGuard {
+    state: Arc<TrackerState>,
+    task_id: u64,
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        if let Ok(mut tasks) = self.state.tasks.lock() {
+            tasks.remove(&self.task_id);
+        }
+        self.state.task_finished.notify_waiters();
+    }
+}
+
+/// Spawns and keeps track of named background tasks.
+///
+/// A tracker is a cheap handle to shared state, i.e. clones of a tracker refer to the same set of tasks.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::task_tracker::TaskTracker;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let tracker = TaskTracker::new("my-service");
+/// let dispatchers = tracker.child("dispatchers");
+/// dispatchers.spawn("dispatcher-1", std::future::pending::<()>());
+/// assert_eq!(tracker.running_tasks(), vec!["my-service/dispatchers/dispatcher-1"]);
+///
+/// tracker.shutdown().await;
+/// assert!(tracker.running_tasks().is_empty());
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TaskTracker {
+    state: Arc<TrackerState>,
+}
+
+impl TaskTracker {
+    /// Creates a new tracker.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tracker, which is used as the prefix of the names of its tasks.
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        TaskTracker {
+            state: Arc::new(TrackerState {
+                name: name.into(),
+                closed: AtomicBool::new(false),
+                next_task_id: AtomicU64::new(0),
+                tasks: Mutex::new(HashMap::new()),
+                children: Mutex::new(vec![]),
+                task_finished: Notify::new(),
+            }),
+        }
+    }
+
+    /// Creates a tracker whose tasks are considered to be part of this tracker.
+    ///
+    /// Closing or shutting down this tracker also closes or shuts down the child tracker.
+    /// A child created for a closed tracker is closed already.
+    pub fn child<T: Into<String>>(&self, name: T) -> TaskTracker {
+        let child = TaskTracker::new(name);
+        if self.is_closed() {
+            child.close();
+        }
+        if let Ok(mut children) = self.state.children.lock() {
+            children.push(child.clone());
+        }
+        child
+    }
+
+    /// Gets the name of this tracker.
+    pub fn name(&self) -> &str {
+        &self.state.name
+    }
+
+    /// Spawns a new task on the current Tokio runtime.
+    ///
+    /// The task is tracked until it has completed or has been aborted. Tasks spawned after
+    /// the tracker has been closed are aborted immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the task.
+    /// * `future` - The work to perform.
+    ///
+    /// # Panics
+    ///
+    /// if not called from within the context of a Tokio runtime.
+    pub fn spawn<N, F>(&self, name: N, future: F) -> JoinHandle<F::Output>
+    where
+        N: Into<String>,
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let name = name.into();
+        let task_id = self.state.next_task_id.fetch_add(1, Ordering::Relaxed);
+        let guard = TaskGuard {
+            state: self.state.clone(),
+            task_id,
+        };
+        let handle = {
+            // the task is registered before it gets the chance to complete
+            let mut tasks = self
+                .state
+                .tasks
+                .lock()
+                .expect("lock on tracked tasks is poisoned");
+            let handle = tokio::spawn(async move {
+                let _guard = guard;
+                future.await
+            });
+            tasks.insert(
+                task_id,
+                TrackedTask {
+                    name,
+                    abort_handle: handle.abort_handle(),
+                },
+            );
+            handle
+        };
+        if self.is_closed() {
+            debug!(
+                tracker = self.name(),
+                "tracker has been closed, aborting task"
+            );
+            handle.abort();
+        }
+        handle
+    }
+
+    /// Checks if this tracker has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.state.closed.load(Ordering::Acquire)
+    }
+
+    /// Closes this tracker and all of its children.
+    ///
+    /// Tasks that are already running are not affected.
+    pub fn close(&self) {
+        self.state.closed.store(true, Ordering::Release);
+        self.children().iter().for_each(TaskTracker::close);
+    }
+
+    /// Aborts all tasks of this tracker and its children.
+    pub fn abort_all(&self) {
+        // abort handles are collected first because aborting a task may drop
+        // its future (and thus its guard) immediately
+        let abort_handles: Vec<AbortHandle> = self.state.tasks.lock().map_or(vec![], |tasks| {
+            tasks
+                .values()
+                .map(|task| task.abort_handle.clone())
+                .collect()
+        });
+        abort_handles.iter().for_each(AbortHandle::abort);
+        self.children().iter().for_each(TaskTracker::abort_all);
+    }
+
+    /// Waits until all tasks of this tracker and its children have completed or have been aborted.
+    pub fn wait(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            loop {
+                let mut task_finished = pin!(self.state.task_finished.notified());
+                task_finished.as_mut().enable();
+                if self
+                    .state
+                    .tasks
+                    .lock()
+                    .map_or(true, |tasks| tasks.is_empty())
+                {
+                    break;
+                }
+                task_finished.await;
+            }
+            for child in self.children() {
+                child.wait().await;
+            }
+        })
+    }
+
+    /// Closes this tracker, aborts all of its (and its children's) tasks and waits for them to
+    /// have stopped.
+    pub async fn shutdown(&self) {
+        self.close();
+        self.abort_all();
+        self.wait().await;
+        debug!(tracker = self.name(), "all tasks have stopped");
+    }
+
+    /// Gets the names of all running tasks of this tracker and its children.
+    ///
+    /// Each name is prefixed with the names of the trackers that the task belongs to,
+    /// separated by `/`.
+    pub fn running_tasks(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.state.tasks.lock().map_or(vec![], |tasks| {
+            tasks
+                .values()
+                .map(|task| format!("{}/{}", self.name(), task.name))
+                .collect()
+        });
+        for child in self.children() {
+            names.extend(
+                child
+                    .running_tasks()
+                    .into_iter()
+                    .map(|name| format!("{}/{}", self.name(), name)),
+            );
+        }
+        names.sort();
+        names
+    }
+
+    /// Gets a snapshot of the tasks of this tracker and its children.
+    pub fn diagnostics(&self) -> TaskDiagnostics {
+        TaskDiagnostics {
+            running_tasks: self.running_tasks(),
+        }
+    }
+
+    fn children(&self) -> Vec<TaskTracker> {
+        self.state
+            .children
+            .lock()
+            .map_or(vec![], |children| children.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_completed_tasks_are_no_longer_tracked() {
+        let tracker = TaskTracker::new("test");
+        let handle = tracker.spawn("short-lived", async { 42 });
+        assert_eq!(handle.await.unwrap(), 42);
+        tokio::time::timeout(Duration::from_secs(1), tracker.wait())
+            .await
+            .expect("tracker should not contain any tasks");
+        assert!(tracker.running_tasks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_tasks_of_children() {
+        // GIVEN a tracker with a child tracker, both running a long-lived task
+        let tracker = TaskTracker::new("parent");
+        let child = tracker.child("child");
+        let parent_task = tracker.spawn("parent-task", std::future::pending::<()>());
+        let child_task = child.spawn("child-task", std::future::pending::<()>());
+        assert_eq!(
+            tracker.diagnostics().running_tasks,
+            vec!["parent/child/child-task", "parent/parent-task"]
+        );
+
+        // WHEN shutting down the parent tracker
+        tokio::time::timeout(Duration::from_secs(1), tracker.shutdown())
+            .await
+            .expect("shutdown should complete");
+
+        // THEN all tasks have been aborted
+        assert!(parent_task.await.is_err_and(|e| e.is_cancelled()));
+        assert!(child_task.await.is_err_and(|e| e.is_cancelled()));
+        assert!(tracker.running_tasks().is_empty());
+        // and the child tracker does not accept any new tasks
+        assert!(child.is_closed());
+        let late_task = child.spawn("late-task", std::future::pending::<()>());
+        assert!(late_task.await.is_err_and(|e| e.is_cancelled()));
+    }
+}
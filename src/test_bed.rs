@@ -0,0 +1,454 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a fixture for end-to-end testing of publish/subscribe interactions between
+uEntities running in the same process.
+
+A [`TestBed`] wires up a [`LocalTransport`], an in-process USubscription service and
+a [`SimplePublisher`] and [`InMemorySubscriber`] for each of a given number of simulated uEntities.
+*/
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    communication::{InMemorySubscriber, RegistrationError, SimpleNotifier, SimplePublisher},
+    core::usubscription::{
+        FetchSubscribersRequest, FetchSubscribersResponse, FetchSubscriptionsRequest,
+        FetchSubscriptionsResponse, NotificationsRequest, Request, State, SubscriberInfo,
+        Subscription, SubscriptionRequest, SubscriptionResponse, SubscriptionStatus, USubscription,
+        UnsubscribeRequest,
+    },
+    local_transport::LocalTransport,
+    LocalUriProvider, StaticUriProvider, UCode, UStatus, UUri,
+};
+
+type SubscriptionTable = Arc<RwLock<HashMap<UUri, HashSet<UUri>>>>;
+
+fn read_table(
+    table: &SubscriptionTable,
+) -> Result<std::sync::RwLockReadGuard<'_, HashMap<UUri, HashSet<UUri>>>, UStatus> {
+    table
+        .read()
+        .map_err(|_e| UStatus::fail_with_code(UCode::INTERNAL, "failed to read subscriptions"))
+}
+
+fn write_table(
+    table: &SubscriptionTable,
+) -> Result<std::sync::RwLockWriteGuard<'_, HashMap<UUri, HashSet<UUri>>>, UStatus> {
+    table
+        .write()
+        .map_err(|_e| UStatus::fail_with_code(UCode::INTERNAL, "failed to update subscriptions"))
+}
+
+fn subscriber_info(uri: &UUri) -> SubscriberInfo {
+    SubscriberInfo {
+        uri: Some(uri.to_owned()).into(),
+        ..Default::default()
+    }
+}
+
+fn subscribed_status() -> SubscriptionStatus {
+    SubscriptionStatus {
+        state: State::SUBSCRIBED.into(),
+        ..Default::default()
+    }
+}
+
+/// An in-process USubscription service which keeps track of subscriptions in memory.
+///
+/// Each instance acts on behalf of a particular subscriber while sharing the subscription
+/// table with all other instances that have been created from the same instance by means of
+/// [`InMemoryUSubscription::for_subscriber`]. All subscription requests are granted immediately.
+///
+/// Note that the service neither sends subscription change notifications nor does it enforce
+/// any access control.
+pub struct InMemoryUSubscription {
+    subscriber: UUri,
+    subscriptions: SubscriptionTable,
+}
+
+impl InMemoryUSubscription {
+    /// Creates a new service with an empty subscription table.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscriber` - The URI of the uEntity that the service acts on behalf of.
+    pub fn new(subscriber: UUri) -> Self {
+        InMemoryUSubscription {
+            subscriber,
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Creates a new service for another subscriber, sharing this service's subscription table.
+    pub fn for_subscriber(&self, subscriber: UUri) -> Self {
+        InMemoryUSubscription {
+            subscriber,
+            subscriptions: self.subscriptions.clone(),
+        }
+    }
+
+    /// Gets the URIs of all uEntities that are currently subscribed to a topic.
+    pub fn subscribers(&self, topic: &UUri) -> Vec<UUri> {
+        read_table(&self.subscriptions).map_or(vec![], |table| {
+            table
+                .get(topic)
+                .map_or(vec![], |subscribers| subscribers.iter().cloned().collect())
+        })
+    }
+}
+
+#[async_trait]
+impl USubscription for InMemoryUSubscription {
+    async fn subscribe(
+        &self,
+        subscription_request: SubscriptionRequest,
+    ) -> Result<SubscriptionResponse, UStatus> {
+        let Some(topic) = subscription_request.topic.into_option() else {
+            return Err(UStatus::fail_with_code(
+                UCode::INVALID_ARGUMENT,
+                "request does not contain topic",
+            ));
+        };
+        write_table(&self.subscriptions)?
+            .entry(topic.clone())
+            .or_default()
+            .insert(self.subscriber.clone());
+        Ok(SubscriptionResponse {
+            topic: Some(topic).into(),
+            status: Some(subscribed_status()).into(),
+            ..Default::default()
+        })
+    }
+
+    async fn unsubscribe(&self, unsubscribe_request: UnsubscribeRequest) -> Result<(), UStatus> {
+        let mut table = write_table(&self.subscriptions)?;
+        match unsubscribe_request.topic.as_ref() {
+            Some(topic) => {
+                if let Some(subscribers) = table.get_mut(topic) {
+                    subscribers.remove(&self.subscriber);
+                }
+            }
+            None => {
+                // no topic given, remove all of the subscriber's subscriptions
+                table.values_mut().for_each(|subscribers| {
+                    subscribers.remove(&self.subscriber);
+                });
+            }
+        }
+        table.retain(|_topic, subscribers| !subscribers.is_empty());
+        Ok(())
+    }
+
+    async fn fetch_subscriptions(
+        &self,
+        fetch_subscriptions_request: FetchSubscriptionsRequest,
+    ) -> Result<FetchSubscriptionsResponse, UStatus> {
+        let table = read_table(&self.subscriptions)?;
+        let matches = |topic: &UUri, subscriber: &UUri| match &fetch_subscriptions_request.request {
+            Some(Request::Topic(requested_topic)) => requested_topic == topic,
+            Some(Request::Subscriber(info)) => info.uri.as_ref() == Some(subscriber),
+            _ => false,
+        };
+        let subscriptions = table
+            .iter()
+            .flat_map(|(topic, subscribers)| {
+                subscribers
+                    .iter()
+                    .map(move |subscriber| (topic, subscriber))
+            })
+            .filter(|(topic, subscriber)| matches(topic, subscriber))
+            .map(|(topic, subscriber)| Subscription {
+                topic: Some(topic.to_owned()).into(),
+                subscriber: Some(subscriber_info(subscriber)).into(),
+                status: Some(subscribed_status()).into(),
+                ..Default::default()
+            })
+            .collect();
+        Ok(FetchSubscriptionsResponse {
+            subscriptions,
+            has_more_records: Some(false),
+            ..Default::default()
+        })
+    }
+
+    async fn register_for_notifications(
+        &self,
+        _notifications_register_request: NotificationsRequest,
+    ) -> Result<(), UStatus> {
+        Ok(())
+    }
+
+    async fn unregister_for_notifications(
+        &self,
+        _notifications_unregister_request: NotificationsRequest,
+    ) -> Result<(), UStatus> {
+        Ok(())
+    }
+
+    async fn fetch_subscribers(
+        &self,
+        fetch_subscribers_request: FetchSubscribersRequest,
+    ) -> Result<FetchSubscribersResponse, UStatus> {
+        let subscribers = fetch_subscribers_request
+            .topic
+            .as_ref()
+            .map_or(vec![], |topic| self.subscribers(topic))
+            .iter()
+            .map(subscriber_info)
+            .collect();
+        Ok(FetchSubscribersResponse {
+            subscribers,
+            has_more_records: Some(false),
+            ..Default::default()
+        })
+    }
+}
+
+/// A simulated uEntity that is part of a [`TestBed`].
+pub struct TestEntity {
+    uri_provider: Arc<StaticUriProvider>,
+    publisher: Arc<SimplePublisher>,
+    subscriber: Arc<InMemorySubscriber>,
+}
+
+impl TestEntity {
+    /// Gets the provider of the uEntity's URIs.
+    pub fn uri_provider(&self) -> Arc<StaticUriProvider> {
+        self.uri_provider.clone()
+    }
+
+    /// Gets the publisher to use for publishing events on behalf of the uEntity.
+    pub fn publisher(&self) -> Arc<SimplePublisher> {
+        self.publisher.clone()
+    }
+
+    /// Gets the subscriber to use for subscribing to topics on behalf of the uEntity.
+    pub fn subscriber(&self) -> Arc<InMemorySubscriber> {
+        self.subscriber.clone()
+    }
+}
+
+/// A set of uEntities which are connected to each other by means of a [`LocalTransport`]
+/// and which share an [`InMemoryUSubscription`] service.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use up_rust::{
+///     communication::{CallOptions, Publisher, Subscriber},
+///     test_bed::TestBed,
+///     LocalUriProvider, MockUListener,
+/// };
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let test_bed = TestBed::new(2).await.unwrap();
+/// let topic = test_bed.entity(0).uri_provider().get_resource_uri(0xA100);
+///
+/// let mut listener = MockUListener::new();
+/// listener.expect_on_receive().once().return_const(());
+/// test_bed.entity(1).subscriber().subscribe(&topic, Arc::new(listener), None).await.unwrap();
+///
+/// test_bed
+///     .entity(0)
+///     .publisher()
+///     .publish(0xA100, CallOptions::for_publish(None, None, None), None)
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub struct TestBed {
+    transport: Arc<LocalTransport>,
+    usubscription: InMemoryUSubscription,
+    entities: Vec<TestEntity>,
+}
+
+impl TestBed {
+    /// The authority name used in the URIs of all simulated uEntities.
+    pub const AUTHORITY: &'static str = "test-bed";
+    /// The entity ID of the first simulated uEntity. Subsequent uEntities use consecutive IDs.
+    pub const FIRST_ENTITY_ID: u32 = 0x1000;
+
+    /// Creates a new test bed.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_count` - The number of uEntities to simulate. The URIs of the uEntities use
+    ///   authority [`Self::AUTHORITY`], major version 1 and entity IDs starting at [`Self::FIRST_ENTITY_ID`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the subscribers cannot be created.
+    pub async fn new(entity_count: usize) -> Result<Self, RegistrationError> {
+        let transport = Arc::new(LocalTransport::default());
+        let usubscription = InMemoryUSubscription::new(UUri::default());
+        let mut entities = Vec::with_capacity(entity_count);
+        for index in 0..entity_count {
+            let uri_provider = Arc::new(StaticUriProvider::new(
+                Self::AUTHORITY,
+                Self::FIRST_ENTITY_ID + index as u32,
+                0x01,
+            ));
+            let publisher = Arc::new(SimplePublisher::new(
+                transport.clone(),
+                uri_provider.clone(),
+            ));
+            let notifier = Arc::new(SimpleNotifier::new(transport.clone(), uri_provider.clone()));
+            let subscriber = InMemorySubscriber::for_clients(
+                transport.clone(),
+                uri_provider.clone(),
+                Arc::new(usubscription.for_subscriber(uri_provider.get_source_uri())),
+                notifier,
+            )
+            .await
+            .map(Arc::new)?;
+            entities.push(TestEntity {
+                uri_provider,
+                publisher,
+                subscriber,
+            });
+        }
+        Ok(TestBed {
+            transport,
+            usubscription,
+            entities,
+        })
+    }
+
+    /// Gets the transport that all uEntities use for exchanging messages.
+    pub fn transport(&self) -> Arc<LocalTransport> {
+        self.transport.clone()
+    }
+
+    /// Gets the USubscription service that keeps track of all uEntities' subscriptions.
+    pub fn usubscription(&self) -> &InMemoryUSubscription {
+        &self.usubscription
+    }
+
+    /// Gets all simulated uEntities.
+    pub fn entities(&self) -> &[TestEntity] {
+        &self.entities
+    }
+
+    /// Gets a simulated uEntity.
+    ///
+    /// # Panics
+    ///
+    /// if `index` is not smaller than the number of simulated uEntities.
+    pub fn entity(&self, index: usize) -> &TestEntity {
+        &self.entities[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protobuf::well_known_types::wrappers::StringValue;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        communication::{CallOptions, Publisher, Subscriber, UPayload},
+        TypedListener, UListener,
+    };
+
+    #[tokio::test]
+    async fn test_published_event_is_delivered_to_subscribed_entities_only() {
+        // GIVEN a test bed with three uEntities
+        let test_bed = TestBed::new(3).await.unwrap();
+        assert_eq!(test_bed.entities().len(), 3);
+        let topic = test_bed.entity(0).uri_provider().get_resource_uri(0xA100);
+
+        // and a listener of the second uEntity being subscribed to a topic of the first uEntity
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let listener: Arc<dyn UListener> =
+            Arc::new(TypedListener::new(move |value: StringValue, _msg| {
+                let tx = tx.clone();
+                async move {
+                    tx.send(value.value).unwrap();
+                }
+            }));
+        test_bed
+            .entity(1)
+            .subscriber()
+            .subscribe(&topic, listener, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            test_bed.usubscription().subscribers(&topic),
+            vec![test_bed.entity(1).uri_provider().get_source_uri()]
+        );
+
+        // WHEN the first uEntity publishes an event to the topic
+        let payload = UPayload::try_from_protobuf(StringValue {
+            value: "hello".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        test_bed
+            .entity(0)
+            .publisher()
+            .publish(
+                0xA100,
+                CallOptions::for_publish(None, None, None),
+                Some(payload),
+            )
+            .await
+            .unwrap();
+
+        // THEN the event is delivered to the subscribed listener
+        assert_eq!(rx.recv().await.unwrap(), "hello");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_subscriptions_by_subscriber() {
+        let usubscription = InMemoryUSubscription::new(UUri::default());
+        let subscriber = UUri::try_from_parts(TestBed::AUTHORITY, 0x1000, 0x01, 0x0000).unwrap();
+        let client = usubscription.for_subscriber(subscriber.clone());
+        let topic = UUri::try_from_parts(TestBed::AUTHORITY, 0x2000, 0x01, 0xA100).unwrap();
+        client
+            .subscribe(SubscriptionRequest {
+                topic: Some(topic.clone()).into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let response = usubscription
+            .fetch_subscriptions(FetchSubscriptionsRequest {
+                request: Some(Request::Subscriber(subscriber_info(&subscriber))),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.subscriptions.len(), 1);
+        assert_eq!(response.subscriptions[0].topic.as_ref(), Some(&topic));
+
+        client
+            .unsubscribe(UnsubscribeRequest {
+                topic: Some(topic.clone()).into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(usubscription.subscribers(&topic).is_empty());
+    }
+}
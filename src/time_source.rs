@@ -0,0 +1,167 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides the source of the timestamps contained in [`UUID`](crate::UUID)s.
+
+By default, the creation time of a message is taken from the system clock. In vehicles,
+ECUs often share a synchronized time domain (e.g. by means of the Precision Time Protocol)
+which is independent of the (possibly unsynchronized) system clock. Setting a [`TimeSource`]
+that reads the synchronized time makes sure that the creation times of messages originating
+from different ECUs are comparable, which is crucial for checking if messages have expired.
+
+```rust
+use std::{sync::Arc, time::Duration};
+use up_rust::{time_source::{self, HybridTimeSource}, UUID};
+
+let time_source = Arc::new(HybridTimeSource::default());
+// the time provided by the time synchronization daemon
+time_source.synchronize(Duration::from_millis(1_700_000_000_000));
+time_source::set_time_source(time_source);
+
+let uuid = UUID::build();
+assert!(uuid.get_time().is_some_and(|millis| millis >= 1_700_000_000_000));
+# time_source::set_time_source(Arc::new(time_source::SystemTimeSource));
+```
+*/
+
+use std::{
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// A source of the current (wall clock) time.
+pub trait TimeSource: Send + Sync {
+    /// Gets the current time.
+    ///
+    /// # Returns
+    ///
+    /// The time that has elapsed since UNIX Epoch or `None` if the current time cannot be determined.
+    fn now(&self) -> Option<Duration>;
+}
+
+/// A time source reading the system clock.
+///
+/// This is the time source being used by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemTimeSource;
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Option<Duration> {
+        SystemTime::UNIX_EPOCH.elapsed().ok()
+    }
+}
+
+/// A time source which extrapolates a synchronized time by means of the monotonic clock.
+///
+/// Time synchronization protocols like PTP provide the synchronized time only at discrete points
+/// in time. This time source keeps track of the most recent synchronized time that it has been
+/// [provided with](`Self::synchronize`) and adds the time that has elapsed since then according to
+/// the (monotonic) [`Instant`] clock. Until the first synchronization, the system clock is used.
+///
+/// The time returned by this source never goes backwards, even if a synchronization moves the
+/// time into the past. Instead, the time does not advance until the synchronized time has caught up.
+#[derive(Debug, Default)]
+pub struct HybridTimeSource {
+    reference: RwLock<Option<(Duration, Instant)>>,
+    last: Mutex<Duration>,
+}
+
+impl HybridTimeSource {
+    /// Sets the synchronized time.
+    ///
+    /// # Arguments
+    ///
+    /// * `synchronized_time` - The time that has elapsed since UNIX Epoch according to the synchronized clock.
+    pub fn synchronize(&self, synchronized_time: Duration) {
+        if let Ok(mut reference) = self.reference.write() {
+            *reference = Some((synchronized_time, Instant::now()));
+        }
+    }
+
+    /// Checks if this source has been provided with the synchronized time.
+    pub fn is_synchronized(&self) -> bool {
+        self.reference
+            .read()
+            .is_ok_and(|reference| reference.is_some())
+    }
+}
+
+impl TimeSource for HybridTimeSource {
+    fn now(&self) -> Option<Duration> {
+        let current = match self.reference.read().ok().and_then(|r| *r) {
+            Some((synchronized_time, synchronized_at)) => {
+                synchronized_time.checked_add(synchronized_at.elapsed())
+            }
+            None => SystemTimeSource.now(),
+        }?;
+        let mut last = self.last.lock().ok()?;
+        *last = current.max(*last);
+        Some(*last)
+    }
+}
+
+static TIME_SOURCE: RwLock<Option<Arc<dyn TimeSource>>> = RwLock::new(None);
+
+/// Sets the time source to use for creating UUIDs and for checking if messages have expired.
+///
+/// The time source should be set once during startup, before any messages are being created.
+pub fn set_time_source(time_source: Arc<dyn TimeSource>) {
+    if let Ok(mut current) = TIME_SOURCE.write() {
+        *current = Some(time_source);
+    }
+}
+
+/// Gets the current time from the [configured](`set_time_source`) time source.
+///
+/// # Returns
+///
+/// The time that has elapsed since UNIX Epoch or `None` if the current time cannot be determined.
+pub fn now() -> Option<Duration> {
+    match TIME_SOURCE.read().ok().as_deref() {
+        Some(Some(time_source)) => time_source.now(),
+        _ => SystemTimeSource.now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hybrid_time_source_extrapolates_synchronized_time() {
+        let time_source = HybridTimeSource::default();
+        assert!(!time_source.is_synchronized());
+
+        time_source.synchronize(Duration::from_secs(1_000));
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert!(time_source.is_synchronized());
+        let now = time_source.now().unwrap();
+        assert!(now >= Duration::from_millis(1_000_005));
+        assert!(now < Duration::from_secs(1_010));
+    }
+
+    #[test]
+    fn test_hybrid_time_source_does_not_go_backwards() {
+        let time_source = HybridTimeSource::default();
+        time_source.synchronize(Duration::from_secs(2_000));
+        let before = time_source.now().unwrap();
+
+        // WHEN the synchronized time moves into the past
+        time_source.synchronize(Duration::from_secs(1_000));
+
+        // THEN the time source's time does not move backwards
+        assert!(time_source.now().unwrap() >= before);
+    }
+}
@@ -0,0 +1,245 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a UTransport decorator which bounds the time that sending a message may take.
+
+Some transport implementations return futures from [`UTransport::send`] which may stall
+indefinitely, e.g. while the connection to a message broker is being re-established.
+Wrapping such a transport in a [`TimeoutTransport`] protects callers from hanging forever.
+*/
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::{UCode, UListener, UMessage, UStatus, UTransport, UUri};
+
+/// A transport that fails sending a message with [`UCode::DEADLINE_EXCEEDED`]
+/// if the underlying transport does not complete sending it within a given amount of time.
+///
+/// The time limit applies to [`UTransport::send_batch`] as a whole, i.e. all messages of the batch
+/// fail with [`UCode::DEADLINE_EXCEEDED`] if the underlying transport does not complete sending the
+/// batch in time.
+///
+/// All other operations are delegated to the underlying transport as is.
+pub struct TimeoutTransport {
+    transport: Arc<dyn UTransport>,
+    send_timeout: Duration,
+}
+
+impl TimeoutTransport {
+    /// Creates a new transport for a given underlying transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to delegate to.
+    /// * `send_timeout` - The maximum amount of time that sending a message may take.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::{sync::Arc, time::Duration};
+    /// use up_rust::{local_transport::LocalTransport, timeout_transport::TimeoutTransport};
+    ///
+    /// let transport = TimeoutTransport::new(
+    ///     Arc::new(LocalTransport::default()),
+    ///     Duration::from_secs(2),
+    /// );
+    /// assert_eq!(transport.send_timeout(), Duration::from_secs(2));
+    /// ```
+    pub fn new(transport: Arc<dyn UTransport>, send_timeout: Duration) -> Self {
+        TimeoutTransport {
+            transport,
+            send_timeout,
+        }
+    }
+
+    /// Gets the maximum amount of time that sending a message may take.
+    pub fn send_timeout(&self) -> Duration {
+        self.send_timeout
+    }
+}
+
+fn deadline_exceeded() -> UStatus {
+    UStatus::fail_with_code(UCode::DEADLINE_EXCEEDED, "sending message timed out")
+}
+
+#[async_trait]
+impl UTransport for TimeoutTransport {
+    async fn send(&self, message: UMessage) -> Result<(), UStatus> {
+        match tokio::time::timeout(self.send_timeout, self.transport.send(message)).await {
+            Ok(result) => result,
+            Err(_elapsed) => {
+                debug!(
+                    "underlying transport did not complete sending message within {:?}",
+                    self.send_timeout
+                );
+                Err(deadline_exceeded())
+            }
+        }
+    }
+
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        let count = messages.len();
+        match tokio::time::timeout(self.send_timeout, self.transport.send_batch(messages)).await {
+            Ok(outcomes) => outcomes,
+            Err(_elapsed) => {
+                debug!(
+                    "underlying transport did not complete sending {} message(s) within {:?}",
+                    count, self.send_timeout
+                );
+                vec![Err(deadline_exceeded()); count]
+            }
+        }
+    }
+
+    async fn receive(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Result<UMessage, UStatus> {
+        self.transport.receive(source_filter, sink_filter).await
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.transport
+            .register_listener(source_filter, sink_filter, listener)
+            .await
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.transport
+            .unregister_listener(source_filter, sink_filter, listener)
+            .await
+    }
+
+    async fn probe(&self, peer_authority: &str) -> Result<(), UStatus> {
+        self.transport.probe(peer_authority).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{utransport::MockTransport, UMessageBuilder, UUri};
+
+    struct StallingTransport {}
+
+    #[async_trait]
+    impl UTransport for StallingTransport {
+        async fn send(&self, _message: UMessage) -> Result<(), UStatus> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        }
+    }
+
+    // A transport that records the sizes of the batches that it has been asked to send.
+    #[derive(Default)]
+    struct BatchingTransport {
+        batches: std::sync::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl UTransport for BatchingTransport {
+        async fn send(&self, _message: UMessage) -> Result<(), UStatus> {
+            Err(UStatus::fail_with_code(
+                UCode::INTERNAL,
+                "messages should have been sent as a batch",
+            ))
+        }
+
+        async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+            self.batches.lock().unwrap().push(messages.len());
+            messages.iter().map(|_msg| Ok(())).collect()
+        }
+    }
+
+    fn publish_message() -> UMessage {
+        let topic = UUri::try_from_parts("my-vehicle", 0x1000, 0x01, 0xA100).unwrap();
+        UMessageBuilder::publish(topic).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_with_deadline_exceeded_for_stalling_transport() {
+        // GIVEN a transport whose send operation never completes
+        let transport =
+            TimeoutTransport::new(Arc::new(StallingTransport {}), Duration::from_millis(50));
+
+        // WHEN sending a message
+        let result = transport.send(publish_message()).await;
+
+        // THEN the attempt fails with DEADLINE_EXCEEDED
+        assert!(result.is_err_and(|e| e.get_code() == UCode::DEADLINE_EXCEEDED));
+    }
+
+    #[tokio::test]
+    async fn test_send_returns_result_of_underlying_transport() {
+        // GIVEN a transport that fails sending messages
+        let mut underlying_transport = MockTransport::new();
+        underlying_transport
+            .expect_do_send()
+            .once()
+            .returning(|_msg| Err(UStatus::fail_with_code(UCode::UNAVAILABLE, "not connected")));
+        let transport =
+            TimeoutTransport::new(Arc::new(underlying_transport), Duration::from_secs(5));
+
+        // WHEN sending a message
+        let result = transport.send(publish_message()).await;
+
+        // THEN the error returned by the underlying transport is passed on to the caller
+        assert!(result.is_err_and(|e| e.get_code() == UCode::UNAVAILABLE));
+    }
+
+    #[tokio::test]
+    async fn test_send_batch_forwards_batch_to_underlying_transport() {
+        // GIVEN a transport that supports sending messages in a batch
+        let underlying_transport = Arc::new(BatchingTransport::default());
+        let transport = TimeoutTransport::new(underlying_transport.clone(), Duration::from_secs(5));
+
+        // WHEN sending a batch of messages
+        let outcomes = transport
+            .send_batch(vec![publish_message(), publish_message()])
+            .await;
+
+        // THEN the batch is passed on to the underlying transport's send_batch function
+        assert!(outcomes.iter().all(|outcome| outcome.is_ok()));
+        assert_eq!(*underlying_transport.batches.lock().unwrap(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_send_batch_fails_with_deadline_exceeded_for_stalling_transport() {
+        let transport =
+            TimeoutTransport::new(Arc::new(StallingTransport {}), Duration::from_millis(50));
+
+        let outcomes = transport
+            .send_batch(vec![publish_message(), publish_message()])
+            .await;
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes
+            .into_iter()
+            .all(|outcome| outcome.is_err_and(|e| e.get_code() == UCode::DEADLINE_EXCEEDED)));
+    }
+}
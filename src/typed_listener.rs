@@ -0,0 +1,151 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::{future::Future, marker::PhantomData, sync::Arc};
+
+use async_trait::async_trait;
+use protobuf::MessageFull;
+use tracing::debug;
+
+use crate::{UListener, UMessage};
+
+/// A [`UListener`] that extracts a protobuf message of a particular type from the payload of
+/// received messages and passes it on to a (user provided) function.
+///
+/// Messages whose payload cannot be extracted as the expected type are either dropped
+/// or passed on to a _dead letter_ listener, if configured.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use protobuf::well_known_types::wrappers::StringValue;
+/// use up_rust::{TypedListener, UListener, UMessage};
+///
+/// let listener: Arc<dyn UListener> = Arc::new(TypedListener::new(
+///     |value: StringValue, _msg: UMessage| async move {
+///         println!("received: {}", value.value);
+///     },
+/// ));
+/// ```
+pub struct TypedListener<T, F> {
+    handler: F,
+    dead_letter_listener: Option<Arc<dyn UListener>>,
+    _payload_type: PhantomData<fn() -> T>,
+}
+
+impl<T, F, Fut> TypedListener<T, F>
+where
+    T: MessageFull + Default,
+    F: Fn(T, UMessage) -> Fut + Send + Sync,
+    Fut: Future<Output = ()> + Send,
+{
+    /// Creates a new listener for a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The function to invoke with the extracted payload and the original message.
+    pub fn new(handler: F) -> Self {
+        TypedListener {
+            handler,
+            dead_letter_listener: None,
+            _payload_type: PhantomData,
+        }
+    }
+
+    /// Sets the listener to forward messages to whose payload cannot be extracted.
+    pub fn with_dead_letter_listener(mut self, listener: Arc<dyn UListener>) -> Self {
+        self.dead_letter_listener = Some(listener);
+        self
+    }
+}
+
+#[async_trait]
+impl<T, F, Fut> UListener for TypedListener<T, F>
+where
+    T: MessageFull + Default,
+    F: Fn(T, UMessage) -> Fut + Send + Sync,
+    Fut: Future<Output = ()> + Send,
+{
+    async fn on_receive(&self, msg: UMessage) {
+        match msg.extract_protobuf::<T>() {
+            Ok(payload) => (self.handler)(payload, msg).await,
+            Err(e) => {
+                debug!(
+                    payload_type = T::descriptor().full_name(),
+                    "failed to extract payload from message: {}", e
+                );
+                if let Some(listener) = self.dead_letter_listener.as_ref() {
+                    listener.on_receive(msg).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protobuf::well_known_types::wrappers::{StringValue, UInt32Value};
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{utransport::MockUListener, UMessageBuilder, UUri};
+
+    fn message_with_payload<M: MessageFull>(payload: &M) -> UMessage {
+        let topic = UUri::try_from_parts("my-vehicle", 0x1000, 0x01, 0xA100).unwrap();
+        UMessageBuilder::publish(topic)
+            .build_with_wrapped_protobuf_payload(payload)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_on_receive_invokes_handler_with_extracted_payload() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut dead_letter_listener = MockUListener::new();
+        dead_letter_listener.expect_on_receive().never();
+        let listener = TypedListener::new(move |value: StringValue, _msg| {
+            let tx = tx.clone();
+            async move {
+                tx.send(value.value).unwrap();
+            }
+        })
+        .with_dead_letter_listener(Arc::new(dead_letter_listener));
+
+        let payload = StringValue {
+            value: "hello".to_string(),
+            ..Default::default()
+        };
+        listener.on_receive(message_with_payload(&payload)).await;
+
+        assert_eq!(rx.recv().await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_on_receive_forwards_message_with_unexpected_payload_to_dead_letter_listener() {
+        let mut dead_letter_listener = MockUListener::new();
+        dead_letter_listener
+            .expect_on_receive()
+            .once()
+            .return_const(());
+        let listener = TypedListener::new(|_value: StringValue, _msg| async {
+            panic!("handler should not have been invoked");
+        })
+        .with_dead_letter_listener(Arc::new(dead_letter_listener));
+
+        let payload = UInt32Value {
+            value: 5,
+            ..Default::default()
+        };
+        listener.on_receive(message_with_payload(&payload)).await;
+    }
+}
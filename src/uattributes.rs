@@ -11,15 +11,106 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+/*!
+Provides the uProtocol message attribute types along with means to validate them.
+
+The [`UAttributesValidators`] can be used for checking all attributes of a message against the
+rules defined for the message's type. In addition, the individual checks performed by the
+validators are available as standalone functions. Transports can use these functions for
+validating only those attributes that they actually map to the underlying protocol.
+
+The [`TraceParent`] type supports parsing and generating the W3C Trace Context identifiers
+conveyed in a message's `traceparent` attribute. The [`http`] module maps attributes to and from
+HTTP headers, e.g. for REST facades.
+
+```rust
+use up_rust::{uattributes::{validate_request_ttl, validate_rpc_priority}, UAttributes, UPriority};
+
+let attributes = UAttributes {
+    priority: UPriority::UPRIORITY_CS4.into(),
+    ttl: Some(5_000),
+    ..Default::default()
+};
+assert!(validate_request_ttl(&attributes).is_ok());
+assert!(validate_rpc_priority(&attributes).is_ok());
+```
+*/
+
+pub mod http;
+mod tracecontext;
 mod uattributesvalidator;
 mod upayloadformat;
 mod upriority;
 
+pub use tracecontext::{validate_tracestate, TraceParent};
 pub use uattributesvalidator::*;
 pub use upriority::*;
 
 pub use crate::up_core_api::uattributes::*;
 
+/// The number of the (non-standard) protobuf field that carries a request's idempotency key.
+///
+/// The uProtocol specification does not (yet) define an attribute for idempotency keys. The key is
+/// therefore conveyed as an unknown field of [`UAttributes`], which is retained when (de-)serializing
+/// the attributes and which is ignored by uEntities that do not support idempotency keys.
+pub const IDEMPOTENCY_KEY_FIELD_NUMBER: u32 = 1001;
+
+/// The number of the (non-standard) protobuf field that carries the point in time at which the event
+/// described by a message has occurred.
+///
+/// The [message ID](`UAttributes::id`) contains the point in time at which the message has been created,
+/// which may differ significantly from the point in time at which e.g. a sensor value has been measured.
+/// Like the [idempotency key](`IDEMPOTENCY_KEY_FIELD_NUMBER`), the event time is conveyed as an unknown
+/// field of [`UAttributes`], containing the number of milliseconds since UNIX epoch as a varint.
+pub const EVENT_TIME_FIELD_NUMBER: u32 = 1002;
+
+/// The number of the (non-standard) protobuf field that carries the encodings that the sender of an
+/// RPC Request message is able to decode response payloads from.
+///
+/// The encoding names are conveyed as a single comma separated string in an unknown field of
+/// [`UAttributes`], e.g. `deflate`.
+pub const ACCEPTED_ENCODINGS_FIELD_NUMBER: u32 = 1003;
+
+/// The number of the (non-standard) protobuf field that carries the name of the encoding that has been
+/// applied to a message's payload.
+///
+/// The [payload format](`UAttributes::payload_format`) describes the format of the payload after it has
+/// been decoded. Messages without this field carry an unencoded payload. The encoding is retained when
+/// mapping a message to a CloudEvent (`contentencoding` extension) or to [HTTP headers](`http`)
+/// (`content-encoding` header).
+pub const CONTENT_ENCODING_FIELD_NUMBER: u32 = 1004;
+
+/// The number of the (non-standard) protobuf field that carries the identifier of the session that a
+/// message belongs to.
+///
+/// Sessions allow stateful services to associate multiple requests of the same client with each other.
+/// The identifier is handed out by the service and is conveyed as a string in an unknown field of
+/// [`UAttributes`].
+pub const SESSION_ID_FIELD_NUMBER: u32 = 1005;
+
+/// The number of the (non-standard) protobuf field that carries the index of a chunk of a streamed
+/// RPC response.
+///
+/// Service providers may respond to a single RPC Request with a sequence of RPC Response messages,
+/// each carrying a chunk of the overall result. The zero based index of a chunk is conveyed as a varint
+/// in an unknown field of [`UAttributes`] and allows the client to restore the original order.
+pub const CHUNK_INDEX_FIELD_NUMBER: u32 = 1006;
+
+/// The number of the (non-standard) protobuf field that marks the last chunk of a streamed RPC response.
+///
+/// The marker is conveyed as a varint with value `1` in an unknown field of [`UAttributes`].
+/// See [`CHUNK_INDEX_FIELD_NUMBER`].
+pub const LAST_CHUNK_FIELD_NUMBER: u32 = 1007;
+
+/// The number of the (non-standard) protobuf field that carries the ID of a prior message that a
+/// message relates to.
+///
+/// The correlation ID establishes a causal link between messages outside of the request/response
+/// pattern, e.g. a notification about an alarm having been cleared which refers to the message that has
+/// raised the alarm. The ID is conveyed as a protobuf encoded [`UUID`](crate::UUID) in an unknown field
+/// of [`UAttributes`].
+pub const CORRELATION_ID_FIELD_NUMBER: u32 = 1008;
+
 #[derive(Debug)]
 pub enum UAttributesError {
     ValidationError(String),
@@ -53,6 +144,16 @@ impl std::fmt::Display for UAttributesError {
 
 impl std::error::Error for UAttributesError {}
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for UAttributesError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Self::ValidationError(e) => defmt::write!(f, "Validation failure: {=str}", e.as_str()),
+            Self::ParsingError(e) => defmt::write!(f, "Parsing error: {=str}", e.as_str()),
+        }
+    }
+}
+
 impl UAttributes {
     /// Checks if these are the attributes for a Publish message.
     ///
@@ -129,4 +230,312 @@ impl UAttributes {
             .enum_value()
             .map_or(false, |v| v == UMessageType::UMESSAGE_TYPE_NOTIFICATION)
     }
+
+    /// Gets the idempotency key of an RPC Request message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UAttributes;
+    ///
+    /// let mut attribs = UAttributes::default();
+    /// assert!(attribs.idempotency_key().is_none());
+    /// attribs.set_idempotency_key("unlock-door-4711");
+    /// assert_eq!(attribs.idempotency_key(), Some("unlock-door-4711".to_string()));
+    /// ```
+    pub fn idempotency_key(&self) -> Option<String> {
+        match self
+            .special_fields
+            .unknown_fields()
+            .get(IDEMPOTENCY_KEY_FIELD_NUMBER)
+        {
+            Some(protobuf::UnknownValueRef::LengthDelimited(bytes)) => {
+                String::from_utf8(bytes.to_vec()).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Sets the idempotency key of an RPC Request message.
+    ///
+    /// See [`IDEMPOTENCY_KEY_FIELD_NUMBER`] for details regarding the representation of the key.
+    pub fn set_idempotency_key<T: Into<String>>(&mut self, key: T) {
+        let unknown_fields = self.special_fields.mut_unknown_fields();
+        unknown_fields.remove(IDEMPOTENCY_KEY_FIELD_NUMBER);
+        unknown_fields.add_length_delimited(IDEMPOTENCY_KEY_FIELD_NUMBER, key.into().into_bytes());
+    }
+
+    /// Gets the point in time at which the event described by the message has occurred.
+    ///
+    /// # Returns
+    ///
+    /// The number of milliseconds since UNIX epoch or `None` if the attributes do not contain an event time.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UAttributes;
+    ///
+    /// let mut attribs = UAttributes::default();
+    /// assert!(attribs.event_time().is_none());
+    /// attribs.set_event_time(1_706_520_652_000);
+    /// assert_eq!(attribs.event_time(), Some(1_706_520_652_000));
+    /// ```
+    pub fn event_time(&self) -> Option<u64> {
+        match self
+            .special_fields
+            .unknown_fields()
+            .get(EVENT_TIME_FIELD_NUMBER)
+        {
+            Some(protobuf::UnknownValueRef::Varint(millis)) => Some(millis),
+            _ => None,
+        }
+    }
+
+    /// Sets the point in time at which the event described by the message has occurred.
+    ///
+    /// See [`EVENT_TIME_FIELD_NUMBER`] for details regarding the representation of the time.
+    ///
+    /// # Arguments
+    ///
+    /// * `millis_since_epoch` - The number of milliseconds since UNIX epoch.
+    pub fn set_event_time(&mut self, millis_since_epoch: u64) {
+        let unknown_fields = self.special_fields.mut_unknown_fields();
+        unknown_fields.remove(EVENT_TIME_FIELD_NUMBER);
+        unknown_fields.add_varint(EVENT_TIME_FIELD_NUMBER, millis_since_epoch);
+    }
+
+    /// Gets the encodings that the sender of an RPC Request message accepts for the response payload.
+    ///
+    /// # Returns
+    ///
+    /// The encoding names, which is empty if the attributes do not contain any accepted encodings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UAttributes;
+    ///
+    /// let mut attribs = UAttributes::default();
+    /// assert!(attribs.accepted_encodings().is_empty());
+    /// attribs.set_accepted_encodings(["deflate", "gzip"]);
+    /// assert_eq!(attribs.accepted_encodings(), vec!["deflate", "gzip"]);
+    /// ```
+    pub fn accepted_encodings(&self) -> Vec<String> {
+        match self
+            .special_fields
+            .unknown_fields()
+            .get(ACCEPTED_ENCODINGS_FIELD_NUMBER)
+        {
+            Some(protobuf::UnknownValueRef::LengthDelimited(bytes)) => {
+                String::from_utf8_lossy(bytes)
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|encoding| !encoding.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Checks if the sender of an RPC Request message accepts a given encoding for the response payload.
+    pub fn accepts_encoding(&self, encoding: &str) -> bool {
+        self.accepted_encodings()
+            .iter()
+            .any(|accepted| accepted.eq_ignore_ascii_case(encoding))
+    }
+
+    /// Sets the encodings that the sender of an RPC Request message accepts for the response payload.
+    ///
+    /// See [`ACCEPTED_ENCODINGS_FIELD_NUMBER`] for details regarding the representation of the encodings.
+    pub fn set_accepted_encodings<I, T>(&mut self, encodings: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let value = encodings
+            .into_iter()
+            .map(|encoding| encoding.as_ref().to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let unknown_fields = self.special_fields.mut_unknown_fields();
+        unknown_fields.remove(ACCEPTED_ENCODINGS_FIELD_NUMBER);
+        unknown_fields.add_length_delimited(ACCEPTED_ENCODINGS_FIELD_NUMBER, value.into_bytes());
+    }
+
+    /// Gets the name of the encoding that has been applied to the message's payload.
+    ///
+    /// # Returns
+    ///
+    /// The encoding name or `None` if the payload is not encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UAttributes;
+    ///
+    /// let mut attribs = UAttributes::default();
+    /// assert!(attribs.content_encoding().is_none());
+    /// attribs.set_content_encoding("deflate");
+    /// assert_eq!(attribs.content_encoding(), Some("deflate".to_string()));
+    /// ```
+    pub fn content_encoding(&self) -> Option<String> {
+        match self
+            .special_fields
+            .unknown_fields()
+            .get(CONTENT_ENCODING_FIELD_NUMBER)
+        {
+            Some(protobuf::UnknownValueRef::LengthDelimited(bytes)) => {
+                String::from_utf8(bytes.to_vec()).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Sets the name of the encoding that has been applied to the message's payload.
+    ///
+    /// See [`CONTENT_ENCODING_FIELD_NUMBER`] for details regarding the representation of the encoding.
+    pub fn set_content_encoding<T: Into<String>>(&mut self, encoding: T) {
+        let unknown_fields = self.special_fields.mut_unknown_fields();
+        unknown_fields.remove(CONTENT_ENCODING_FIELD_NUMBER);
+        unknown_fields
+            .add_length_delimited(CONTENT_ENCODING_FIELD_NUMBER, encoding.into().into_bytes());
+    }
+
+    /// Gets the identifier of the session that the message belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UAttributes;
+    ///
+    /// let mut attribs = UAttributes::default();
+    /// assert!(attribs.session_id().is_none());
+    /// attribs.set_session_id("f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+    /// assert_eq!(attribs.session_id(), Some("f81d4fae-7dec-11d0-a765-00a0c91e6bf6".to_string()));
+    /// ```
+    pub fn session_id(&self) -> Option<String> {
+        match self
+            .special_fields
+            .unknown_fields()
+            .get(SESSION_ID_FIELD_NUMBER)
+        {
+            Some(protobuf::UnknownValueRef::LengthDelimited(bytes)) => {
+                String::from_utf8(bytes.to_vec()).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Sets the identifier of the session that the message belongs to.
+    ///
+    /// See [`SESSION_ID_FIELD_NUMBER`] for details regarding the representation of the identifier.
+    pub fn set_session_id<T: Into<String>>(&mut self, session_id: T) {
+        let unknown_fields = self.special_fields.mut_unknown_fields();
+        unknown_fields.remove(SESSION_ID_FIELD_NUMBER);
+        unknown_fields
+            .add_length_delimited(SESSION_ID_FIELD_NUMBER, session_id.into().into_bytes());
+    }
+
+    /// Gets the index of the chunk of a streamed RPC response that the message carries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UAttributes;
+    ///
+    /// let mut attribs = UAttributes::default();
+    /// assert!(attribs.chunk_index().is_none());
+    /// attribs.set_chunk_index(3);
+    /// assert_eq!(attribs.chunk_index(), Some(3));
+    /// ```
+    pub fn chunk_index(&self) -> Option<u32> {
+        match self
+            .special_fields
+            .unknown_fields()
+            .get(CHUNK_INDEX_FIELD_NUMBER)
+        {
+            Some(protobuf::UnknownValueRef::Varint(index)) => u32::try_from(index).ok(),
+            _ => None,
+        }
+    }
+
+    /// Sets the index of the chunk of a streamed RPC response that the message carries.
+    ///
+    /// See [`CHUNK_INDEX_FIELD_NUMBER`] for details.
+    pub fn set_chunk_index(&mut self, index: u32) {
+        let unknown_fields = self.special_fields.mut_unknown_fields();
+        unknown_fields.remove(CHUNK_INDEX_FIELD_NUMBER);
+        unknown_fields.add_varint(CHUNK_INDEX_FIELD_NUMBER, index.into());
+    }
+
+    /// Checks if the message carries the last chunk of a streamed RPC response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UAttributes;
+    ///
+    /// let mut attribs = UAttributes::default();
+    /// assert!(!attribs.is_last_chunk());
+    /// attribs.set_last_chunk(true);
+    /// assert!(attribs.is_last_chunk());
+    /// ```
+    pub fn is_last_chunk(&self) -> bool {
+        matches!(
+            self.special_fields
+                .unknown_fields()
+                .get(LAST_CHUNK_FIELD_NUMBER),
+            Some(protobuf::UnknownValueRef::Varint(1))
+        )
+    }
+
+    /// Marks the message as carrying the last chunk of a streamed RPC response.
+    ///
+    /// See [`LAST_CHUNK_FIELD_NUMBER`] for details.
+    pub fn set_last_chunk(&mut self, last: bool) {
+        let unknown_fields = self.special_fields.mut_unknown_fields();
+        unknown_fields.remove(LAST_CHUNK_FIELD_NUMBER);
+        if last {
+            unknown_fields.add_varint(LAST_CHUNK_FIELD_NUMBER, 1);
+        }
+    }
+
+    /// Gets the ID of the prior message that the message relates to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UAttributes, UUID};
+    ///
+    /// let mut attribs = UAttributes::default();
+    /// assert!(attribs.correlation_id().is_none());
+    /// let alarm_id = UUID::build();
+    /// attribs.set_correlation_id(&alarm_id);
+    /// assert_eq!(attribs.correlation_id(), Some(alarm_id));
+    /// ```
+    pub fn correlation_id(&self) -> Option<crate::UUID> {
+        match self
+            .special_fields
+            .unknown_fields()
+            .get(CORRELATION_ID_FIELD_NUMBER)
+        {
+            Some(protobuf::UnknownValueRef::LengthDelimited(bytes)) => {
+                <crate::UUID as protobuf::Message>::parse_from_bytes(bytes).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Sets the ID of the prior message that the message relates to.
+    ///
+    /// See [`CORRELATION_ID_FIELD_NUMBER`] for details regarding the representation of the ID.
+    pub fn set_correlation_id(&mut self, correlation_id: &crate::UUID) {
+        let unknown_fields = self.special_fields.mut_unknown_fields();
+        unknown_fields.remove(CORRELATION_ID_FIELD_NUMBER);
+        if let Ok(bytes) = protobuf::Message::write_to_bytes(correlation_id) {
+            unknown_fields.add_length_delimited(CORRELATION_ID_FIELD_NUMBER, bytes);
+        }
+    }
 }
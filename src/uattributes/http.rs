@@ -0,0 +1,246 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides a mapping of message attributes to and from HTTP headers.
+
+REST facades that expose uEntities via plain HTTP requests can use this mapping for conveying
+a message's attributes in HTTP headers, without having to map messages to CloudEvents. The
+following attributes are mapped:
+
+| Attribute                          | HTTP header             | Header value                                    |
+| ---------------------------------- | ----------------------- | ----------------------------------------------- |
+| [`UAttributes::id`]                | `x-uprotocol-id`        | The UUID in hyphenated form                     |
+| [`UAttributes::source`]            | `x-uprotocol-source`    | The URI in string form                          |
+| [`UAttributes::sink`]              | `x-uprotocol-sink`      | The URI in string form                          |
+| [`UAttributes::priority`]          | `x-uprotocol-priority`  | The priority code, e.g. `CS4`                   |
+| [`UAttributes::ttl`]               | `x-uprotocol-ttl`       | The number of milliseconds in decimal notation  |
+| [`UAttributes::token`]             | `x-uprotocol-token`     | The token as is                                 |
+| [`UAttributes::traceparent`]       | `traceparent`           | The W3C Trace Context identifier as is          |
+| [`UAttributes::content_encoding`]  | `content-encoding`      | The encoding name, e.g. `deflate`               |
+
+Header names are matched case-insensitively. All other attributes, e.g. the message type,
+need to be derived from the HTTP request by the facade.
+
+```rust
+use up_rust::{uattributes::http, UAttributes, UPriority, UUri};
+
+let attributes = UAttributes {
+    source: Some(UUri::try_from("//vehicle/A100/1/0").unwrap()).into(),
+    priority: UPriority::UPRIORITY_CS4.into(),
+    ttl: Some(5_000),
+    ..Default::default()
+};
+let headers = http::to_http_headers(&attributes);
+assert!(headers.contains(&("x-uprotocol-priority".to_string(), "CS4".to_string())));
+
+let parsed = http::from_http_headers(headers.iter().map(|(n, v)| (n.as_str(), v.as_str()))).unwrap();
+assert_eq!(parsed, attributes);
+```
+*/
+
+use crate::{UAttributes, UAttributesError, UPriority, UUri, UUID};
+
+use super::TraceParent;
+
+/// The name of the HTTP header that carries the message ID.
+pub const HEADER_ID: &str = "x-uprotocol-id";
+/// The name of the HTTP header that carries the message's source address.
+pub const HEADER_SOURCE: &str = "x-uprotocol-source";
+/// The name of the HTTP header that carries the message's sink address.
+pub const HEADER_SINK: &str = "x-uprotocol-sink";
+/// The name of the HTTP header that carries the message's priority.
+pub const HEADER_PRIORITY: &str = "x-uprotocol-priority";
+/// The name of the HTTP header that carries the message's time-to-live.
+pub const HEADER_TTL: &str = "x-uprotocol-ttl";
+/// The name of the HTTP header that carries the message's access token.
+pub const HEADER_TOKEN: &str = "x-uprotocol-token";
+/// The name of the HTTP header that carries the message's W3C Trace Context identifier.
+pub const HEADER_TRACEPARENT: &str = "traceparent";
+/// The name of the HTTP header that carries the encoding that has been applied to the message's payload.
+pub const HEADER_CONTENT_ENCODING: &str = "content-encoding";
+
+const MAPPED_HEADERS: [&str; 8] = [
+    HEADER_ID,
+    HEADER_SOURCE,
+    HEADER_SINK,
+    HEADER_PRIORITY,
+    HEADER_TTL,
+    HEADER_TOKEN,
+    HEADER_TRACEPARENT,
+    HEADER_CONTENT_ENCODING,
+];
+
+/// Maps message attributes to HTTP headers.
+///
+/// # Returns
+///
+/// The (name, value) pairs of the HTTP headers for all [mapped](`crate::uattributes::http`)
+/// attributes that are set.
+pub fn to_http_headers(attributes: &UAttributes) -> Vec<(String, String)> {
+    let mut headers = vec![];
+    let mut add_header = |name: &str, value: String| headers.push((name.to_string(), value));
+    if let Some(id) = attributes.id.as_ref() {
+        add_header(HEADER_ID, id.to_hyphenated_string());
+    }
+    if let Some(source) = attributes.source.as_ref() {
+        add_header(HEADER_SOURCE, String::from(source));
+    }
+    if let Some(sink) = attributes.sink.as_ref() {
+        add_header(HEADER_SINK, String::from(sink));
+    }
+    let priority = attributes.priority.enum_value_or_default();
+    if priority != UPriority::UPRIORITY_UNSPECIFIED {
+        add_header(HEADER_PRIORITY, priority.to_priority_code());
+    }
+    if let Some(ttl) = attributes.ttl {
+        add_header(HEADER_TTL, ttl.to_string());
+    }
+    if let Some(token) = attributes.token.as_ref() {
+        add_header(HEADER_TOKEN, token.to_owned());
+    }
+    if let Some(traceparent) = attributes.traceparent.as_ref() {
+        add_header(HEADER_TRACEPARENT, traceparent.to_owned());
+    }
+    if let Some(encoding) = attributes.content_encoding() {
+        add_header(HEADER_CONTENT_ENCODING, encoding);
+    }
+    headers
+}
+
+fn parse_uri(name: &str, value: &str) -> Result<UUri, UAttributesError> {
+    UUri::try_from(value)
+        .map_err(|e| UAttributesError::parsing_error(format!("invalid {name} header: {e}")))
+}
+
+/// Creates message attributes from HTTP headers.
+///
+/// Headers that are not [mapped](`crate::uattributes::http`) to attributes are ignored.
+///
+/// # Arguments
+///
+/// * `headers` - The (name, value) pairs of the HTTP headers.
+///
+/// # Errors
+///
+/// Returns an error if any of the mapped headers has an invalid value or occurs more than once.
+/// The attributes are not [validated](`crate::UAttributesValidators`) against the rules of any
+/// particular message type.
+pub fn from_http_headers<'a, I>(headers: I) -> Result<UAttributes, UAttributesError>
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let mut attributes = UAttributes::default();
+    let mut seen = [false; MAPPED_HEADERS.len()];
+    for (name, value) in headers {
+        let Some(index) = MAPPED_HEADERS
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name))
+        else {
+            continue;
+        };
+        let header = MAPPED_HEADERS[index];
+        if std::mem::replace(&mut seen[index], true) {
+            return Err(UAttributesError::parsing_error(format!(
+                "duplicate {header} header"
+            )));
+        }
+        let value = value.trim();
+        match header {
+            HEADER_ID => {
+                let id = value.parse::<UUID>().map_err(|e| {
+                    UAttributesError::parsing_error(format!("invalid {header} header: {e}"))
+                })?;
+                attributes.id = Some(id).into();
+            }
+            HEADER_SOURCE => attributes.source = Some(parse_uri(header, value)?).into(),
+            HEADER_SINK => attributes.sink = Some(parse_uri(header, value)?).into(),
+            HEADER_PRIORITY => {
+                attributes.priority = UPriority::try_from_priority_code(value)?.into();
+            }
+            HEADER_TTL => {
+                let ttl = value.parse::<u32>().map_err(|e| {
+                    UAttributesError::parsing_error(format!("invalid {header} header: {e}"))
+                })?;
+                attributes.ttl = Some(ttl);
+            }
+            HEADER_TOKEN => attributes.token = Some(value.to_string()),
+            HEADER_TRACEPARENT => {
+                value.parse::<TraceParent>().map_err(|e| {
+                    UAttributesError::parsing_error(format!("invalid {header} header: {e}"))
+                })?;
+                attributes.traceparent = Some(value.to_string());
+            }
+            HEADER_CONTENT_ENCODING => attributes.set_content_encoding(value),
+            _ => {}
+        }
+    }
+    Ok(attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_mapping_is_bidirectional() {
+        let mut attributes = UAttributes {
+            id: Some(UUID::build()).into(),
+            source: Some(UUri::try_from("//vehicle/A100/1/0").unwrap()).into(),
+            sink: Some(UUri::try_from("//vehicle/B100/1/1").unwrap()).into(),
+            priority: UPriority::UPRIORITY_CS5.into(),
+            ttl: Some(3_000),
+            token: Some("secret".to_string()),
+            traceparent: Some(
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+            ),
+            ..Default::default()
+        };
+        attributes.set_content_encoding("deflate");
+
+        let headers = to_http_headers(&attributes);
+        assert_eq!(headers.len(), 8);
+        let parsed =
+            from_http_headers(headers.iter().map(|(n, v)| (n.as_str(), v.as_str()))).unwrap();
+
+        assert_eq!(parsed, attributes);
+    }
+
+    #[test]
+    fn test_from_http_headers_ignores_case_and_unmapped_headers() {
+        let attributes = from_http_headers([
+            ("Content-Type", "application/json"),
+            ("X-UProtocol-Priority", "CS2"),
+            ("X-UProtocol-TTL", " 100 "),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            attributes.priority.enum_value_or_default(),
+            UPriority::UPRIORITY_CS2
+        );
+        assert_eq!(attributes.ttl, Some(100));
+        assert!(attributes.source.is_none());
+    }
+
+    #[test_case(&[("x-uprotocol-ttl", "-1")]; "negative ttl")]
+    #[test_case(&[("x-uprotocol-priority", "CS9")]; "unknown priority")]
+    #[test_case(&[("x-uprotocol-id", "not-a-uuid")]; "invalid id")]
+    #[test_case(&[("x-uprotocol-sink", "//vehicle/B100")]; "invalid sink")]
+    #[test_case(&[("traceparent", "00-abc")]; "invalid traceparent")]
+    #[test_case(&[("x-uprotocol-token", "a"), ("X-UProtocol-Token", "b")]; "duplicate header")]
+    fn test_from_http_headers_fails_for_invalid_header(headers: &[(&str, &str)]) {
+        assert!(from_http_headers(headers.iter().copied()).is_err());
+    }
+}
@@ -0,0 +1,384 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use rand::RngCore;
+
+use crate::UAttributesError;
+
+const TRACE_FLAG_SAMPLED: u8 = 0x01;
+const MAX_TRACESTATE_MEMBERS: usize = 32;
+const MAX_TRACESTATE_LENGTH: usize = 512;
+
+/// A W3C Trace Context `traceparent` header value.
+///
+/// Please refer to the [W3C Trace Context specification](https://www.w3.org/TR/trace-context/#traceparent-header)
+/// for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::uattributes::TraceParent;
+///
+/// let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+///     .parse::<TraceParent>()
+///     .unwrap();
+/// assert!(traceparent.is_sampled());
+///
+/// // the context to propagate to downstream operations
+/// let child = traceparent.child();
+/// assert_eq!(child.trace_id(), traceparent.trace_id());
+/// assert_ne!(child.parent_id(), traceparent.parent_id());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceParent {
+    trace_id: [u8; 16],
+    parent_id: [u8; 8],
+    trace_flags: u8,
+}
+
+impl TraceParent {
+    /// Creates a new trace context.
+    ///
+    /// # Arguments
+    ///
+    /// * `trace_id` - The ID of the whole trace.
+    /// * `parent_id` - The ID of the operation that has been invoked by the caller.
+    /// * `trace_flags` - The tracing flags.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the IDs consists of zero bytes only.
+    pub fn new(
+        trace_id: [u8; 16],
+        parent_id: [u8; 8],
+        trace_flags: u8,
+    ) -> Result<Self, UAttributesError> {
+        if trace_id.iter().all(|b| *b == 0) {
+            return Err(UAttributesError::validation_error(
+                "trace-id must not be all zeros",
+            ));
+        }
+        if parent_id.iter().all(|b| *b == 0) {
+            return Err(UAttributesError::validation_error(
+                "parent-id must not be all zeros",
+            ));
+        }
+        Ok(TraceParent {
+            trace_id,
+            parent_id,
+            trace_flags,
+        })
+    }
+
+    /// Creates a trace context for a new trace using random IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampled` - `true` if the caller may have recorded trace data.
+    pub fn generate(sampled: bool) -> Self {
+        let mut trace_id = [0_u8; 16];
+        while trace_id.iter().all(|b| *b == 0) {
+            rand::thread_rng().fill_bytes(&mut trace_id);
+        }
+        TraceParent {
+            trace_id,
+            parent_id: random_parent_id(),
+            trace_flags: if sampled { TRACE_FLAG_SAMPLED } else { 0 },
+        }
+    }
+
+    /// Creates a trace context for an operation that is part of the same trace as this context,
+    /// using a random parent ID.
+    pub fn child(&self) -> Self {
+        TraceParent {
+            parent_id: random_parent_id(),
+            ..*self
+        }
+    }
+
+    /// Gets the ID of the whole trace.
+    pub fn trace_id(&self) -> [u8; 16] {
+        self.trace_id
+    }
+
+    /// Gets the ID of the operation that has been invoked by the caller.
+    pub fn parent_id(&self) -> [u8; 8] {
+        self.parent_id
+    }
+
+    /// Gets the tracing flags.
+    pub fn trace_flags(&self) -> u8 {
+        self.trace_flags
+    }
+
+    /// Checks if the caller may have recorded trace data.
+    pub fn is_sampled(&self) -> bool {
+        self.trace_flags & TRACE_FLAG_SAMPLED == TRACE_FLAG_SAMPLED
+    }
+}
+
+fn random_parent_id() -> [u8; 8] {
+    let mut parent_id = [0_u8; 8];
+    while parent_id.iter().all(|b| *b == 0) {
+        rand::thread_rng().fill_bytes(&mut parent_id);
+    }
+    parent_id
+}
+
+fn parse_hex<const N: usize>(value: &str, field: &str) -> Result<[u8; N], UAttributesError> {
+    if value.len() != 2 * N
+        || !value
+            .bytes()
+            .all(|c| c.is_ascii_digit() || (b'a'..=b'f').contains(&c))
+    {
+        return Err(UAttributesError::parsing_error(format!(
+            "{} must consist of {} lowercase hex digits",
+            field,
+            2 * N
+        )));
+    }
+    let mut bytes = [0_u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        // cannot fail because all characters are hex digits
+        *byte = u8::from_str_radix(&value[2 * i..2 * i + 2], 16).unwrap_or_default();
+    }
+    Ok(bytes)
+}
+
+impl FromStr for TraceParent {
+    type Err = UAttributesError;
+
+    /// Parses a `traceparent` header value.
+    ///
+    /// Values using a version other than `00` are accepted as long as their first four fields
+    /// can be parsed, as required by the W3C Trace Context specification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a valid `traceparent`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut fields = value.splitn(5, '-');
+        let (Some(version), Some(trace_id), Some(parent_id), Some(trace_flags)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(UAttributesError::parsing_error(
+                "traceparent must consist of version, trace-id, parent-id and trace-flags",
+            ));
+        };
+        let [version] = parse_hex::<1>(version, "version")?;
+        if version == 0xff {
+            return Err(UAttributesError::parsing_error("version ff is invalid"));
+        }
+        if version == 0x00 && fields.next().is_some() {
+            return Err(UAttributesError::parsing_error(
+                "traceparent of version 00 must not contain additional fields",
+            ));
+        }
+        let [trace_flags] = parse_hex::<1>(trace_flags, "trace-flags")?;
+        TraceParent::new(
+            parse_hex(trace_id, "trace-id")?,
+            parse_hex(parent_id, "parent-id")?,
+            trace_flags,
+        )
+        .map_err(|e| UAttributesError::parsing_error(e.to_string()))
+    }
+}
+
+impl Display for TraceParent {
+    /// Formats this context as a `traceparent` header value of version `00`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("00-")?;
+        self.trace_id
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))?;
+        f.write_str("-")?;
+        self.parent_id
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))?;
+        write!(f, "-{:02x}", self.trace_flags)
+    }
+}
+
+impl From<TraceParent> for String {
+    fn from(value: TraceParent) -> Self {
+        value.to_string()
+    }
+}
+
+impl From<&TraceParent> for String {
+    fn from(value: &TraceParent) -> Self {
+        value.to_string()
+    }
+}
+
+fn is_valid_tracestate_key(key: &str) -> bool {
+    let is_key_char = |c: u8| {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, b'_' | b'-' | b'*' | b'/')
+    };
+    let starts_valid = |s: &str| {
+        s.bytes()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    };
+    match key.split_once('@') {
+        // multi-tenant key
+        Some((tenant, system)) => {
+            (1..=241).contains(&tenant.len())
+                && (1..=14).contains(&system.len())
+                && starts_valid(tenant)
+                && system
+                    .bytes()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_lowercase())
+                && tenant.bytes().all(is_key_char)
+                && system.bytes().all(is_key_char)
+        }
+        None => {
+            (1..=256).contains(&key.len())
+                && key.bytes().next().is_some_and(|c| c.is_ascii_lowercase())
+                && key.bytes().all(is_key_char)
+        }
+    }
+}
+
+fn is_valid_tracestate_value(value: &str) -> bool {
+    (1..=256).contains(&value.len())
+        && value
+            .bytes()
+            .all(|c| (0x20..=0x7e).contains(&c) && c != b',' && c != b'=')
+        && !value.ends_with(' ')
+}
+
+/// Verifies that a string is a valid W3C Trace Context `tracestate` header value.
+///
+/// Please refer to the [W3C Trace Context specification](https://www.w3.org/TR/trace-context/#tracestate-header)
+/// for details.
+///
+/// # Errors
+///
+/// Returns an error if the value is longer than 512 characters, contains more than 32 list members,
+/// contains a list member which is not a valid key-value pair or contains the same key more than once.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::uattributes::validate_tracestate;
+///
+/// assert!(validate_tracestate("rojo=00f067aa0ba902b7,congo=t61rcWkgMzE").is_ok());
+/// assert!(validate_tracestate("rojo=1,rojo=2").is_err());
+/// ```
+pub fn validate_tracestate(tracestate: &str) -> Result<(), UAttributesError> {
+    if tracestate.len() > MAX_TRACESTATE_LENGTH {
+        return Err(UAttributesError::validation_error(format!(
+            "tracestate must not be longer than {} characters",
+            MAX_TRACESTATE_LENGTH
+        )));
+    }
+    let mut keys = Vec::new();
+    for member in tracestate
+        .split(',')
+        .map(|member| member.trim_matches([' ', '\t']))
+        // empty list members are allowed
+        .filter(|member| !member.is_empty())
+    {
+        let Some((key, _value)) = member.split_once('=').filter(|(key, value)| {
+            is_valid_tracestate_key(key) && is_valid_tracestate_value(value)
+        }) else {
+            return Err(UAttributesError::validation_error(format!(
+                "invalid tracestate list member: {}",
+                member
+            )));
+        };
+        if keys.contains(&key) {
+            return Err(UAttributesError::validation_error(format!(
+                "tracestate contains duplicate key: {}",
+                key
+            )));
+        }
+        keys.push(key);
+    }
+    if keys.len() > MAX_TRACESTATE_MEMBERS {
+        return Err(UAttributesError::validation_error(format!(
+            "tracestate must not contain more than {} list members",
+            MAX_TRACESTATE_MEMBERS
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", true; "for sampled trace")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00", false; "for unsampled trace")]
+    #[test_case("cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-what-the-future-holds", true; "for future version with additional fields")]
+    fn test_parse_succeeds(traceparent: &str, expected_sampled: bool) {
+        let result = traceparent.parse::<TraceParent>();
+        assert!(result
+            .as_ref()
+            .is_ok_and(|tp| tp.is_sampled() == expected_sampled
+                && tp.parent_id() == [0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7]));
+    }
+
+    #[test_case(""; "for empty string")]
+    #[test_case("ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"; "for version ff")]
+    #[test_case("00-00000000000000000000000000000000-00f067aa0ba902b7-01"; "for all zeros trace-id")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01"; "for all zeros parent-id")]
+    #[test_case("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01"; "for uppercase hex digits")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e47-00f067aa0ba902b7-01"; "for short trace-id")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-1"; "for short trace-flags")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra"; "for version 00 with additional fields")]
+    #[test_case("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7"; "for missing trace-flags")]
+    fn test_parse_fails(traceparent: &str) {
+        assert!(traceparent
+            .parse::<TraceParent>()
+            .is_err_and(|e| matches!(e, UAttributesError::ParsingError(_))));
+    }
+
+    #[test]
+    fn test_generated_traceparent_can_be_parsed() {
+        let traceparent = TraceParent::generate(true);
+        assert!(traceparent.is_sampled());
+        assert_eq!(
+            traceparent.to_string().parse::<TraceParent>().unwrap(),
+            traceparent
+        );
+        assert!(!TraceParent::generate(false).is_sampled());
+    }
+
+    #[test_case("rojo=00f067aa0ba902b7", true; "for single member")]
+    #[test_case("rojo=00f067aa0ba902b7 , , congo=t61rcWkgMzE", true; "for empty members and whitespace")]
+    #[test_case("tenant@vendor=value", true; "for multi-tenant key")]
+    #[test_case("Rojo=1", false; "for uppercase key")]
+    #[test_case("rojo", false; "for member without value")]
+    #[test_case("rojo=a=b", false; "for value containing equals sign")]
+    #[test_case("rojo=1,rojo=2", false; "for duplicate keys")]
+    fn test_validate_tracestate(tracestate: &str, expected_valid: bool) {
+        assert_eq!(validate_tracestate(tracestate).is_ok(), expected_valid);
+    }
+
+    #[test]
+    fn test_validate_tracestate_fails_for_too_many_members() {
+        let tracestate = (0..33)
+            .map(|i| format!("k{}=v", i))
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(validate_tracestate(&tracestate).is_err());
+    }
+}
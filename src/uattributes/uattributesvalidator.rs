@@ -11,7 +11,7 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use std::time::SystemTime;
+use std::time::Duration;
 
 use protobuf::Enum;
 
@@ -35,24 +35,29 @@ pub trait UAttributesValidator: Send {
     /// Returns an error if the attributes are not consistent with the rules specified for the message type.
     fn validate(&self, attributes: &UAttributes) -> Result<(), UAttributesError>;
 
+    /// Checks if a given set of attributes complies with the rules specified for
+    /// the type of message they describe and does not rely on any lenient behavior.
+    ///
+    /// This helps implementers of uEntities to verify that they comply with the uProtocol specification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the following checks fail for the given attributes:
+    ///
+    /// * [`UAttributesValidator::validate`]
+    /// * [`validate_strict_spec`]
+    fn validate_strict(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
+        self.validate(attributes)?;
+        validate_strict_spec(attributes)
+    }
+
     /// Verifies that this validator is appropriate for a set of attributes.
     ///
     /// # Errors
     ///
     /// Returns an error if [`UAttributes::type_`] does not match the type returned by [`UAttributesValidator::message_type`].
     fn validate_type(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        let expected_type = self.message_type();
-        match attributes.type_.enum_value() {
-            Ok(mt) if mt == expected_type => Ok(()),
-            Ok(mt) => Err(UAttributesError::validation_error(format!(
-                "Wrong Message Type [{}]",
-                mt.to_cloudevent_type()
-            ))),
-            Err(unknown_code) => Err(UAttributesError::validation_error(format!(
-                "Unknown Message Type code [{}]",
-                unknown_code
-            ))),
-        }
+        validate_message_type(attributes, self.message_type())
     }
 
     /// Verifies that a set of attributes contains a valid message ID.
@@ -61,17 +66,7 @@ pub trait UAttributesValidator: Send {
     ///
     /// Returns an error if [`UAttributes::id`] does not contain a [valid uProtocol UUID](`UUID::is_uprotocol_uuid`).
     fn validate_id(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if attributes
-            .id
-            .as_ref()
-            .map_or(false, |id| id.is_uprotocol_uuid())
-        {
-            Ok(())
-        } else {
-            Err(UAttributesError::validation_error(
-                "Attributes must contain valid uProtocol UUID in id property",
-            ))
-        }
+        validate_id(attributes)
     }
 
     /// Returns the type of message that this validator can be used with.
@@ -83,29 +78,9 @@ pub trait UAttributesValidator: Send {
     ///
     /// Returns an error if [`UAttributes::ttl`] (time-to-live) contains a value greater than 0, but
     /// * the message has expired according to the timestamp extracted from [`UAttributes::id`] and the time-to-live value, or
-    /// * the current system time cannot be determined.
+    /// * the current time cannot be determined by the [configured time source](`crate::time_source`).
     fn is_expired(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        let ttl = match attributes.ttl {
-            Some(t) if t > 0 => u64::from(t),
-            _ => return Ok(()),
-        };
-
-        if let Some(time) = attributes.id.as_ref().and_then(UUID::get_time) {
-            let delta = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-                Ok(duration) => {
-                    if let Ok(duration) = u64::try_from(duration.as_millis()) {
-                        duration - time
-                    } else {
-                        return Err(UAttributesError::validation_error("Invalid duration"));
-                    }
-                }
-                Err(e) => return Err(UAttributesError::validation_error(e.to_string())),
-            };
-            if delta >= ttl {
-                return Err(UAttributesError::validation_error("Payload is expired"));
-            }
-        }
-        Ok(())
+        validate_not_expired(attributes)
     }
 
     /// Verifies that a set of attributes contains a valid source URI.
@@ -119,6 +94,25 @@ pub trait UAttributesValidator: Send {
     fn validate_sink(&self, attributes: &UAttributes) -> Result<(), UAttributesError>;
 }
 
+/// Verifies that a set of attributes contains a valid W3C Trace Context identifier, if any.
+///
+/// # Errors
+///
+/// If [`UAttributes::traceparent`] contains a value that is not a valid [`TraceParent`](`super::TraceParent`).
+pub fn validate_traceparent(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    attributes
+        .traceparent
+        .as_deref()
+        .map_or(Ok(()), |traceparent| {
+            traceparent
+                .parse::<super::TraceParent>()
+                .map(|_| ())
+                .map_err(|e| {
+                    UAttributesError::validation_error(format!("Invalid traceparent: {}", e))
+                })
+        })
+}
+
 /// Verifies that a set of attributes contains a priority that is appropriate for an RPC request message.
 ///
 /// # Errors
@@ -145,6 +139,369 @@ pub fn validate_rpc_priority(attributes: &UAttributes) -> Result<(), UAttributes
         })
 }
 
+/// Verifies that a set of attributes describe a particular type of message.
+///
+/// # Errors
+///
+/// Returns an error if [`UAttributes::type_`] does not match the given type.
+pub fn validate_message_type(
+    attributes: &UAttributes,
+    expected_type: UMessageType,
+) -> Result<(), UAttributesError> {
+    match attributes.type_.enum_value() {
+        Ok(mt) if mt == expected_type => Ok(()),
+        Ok(mt) => Err(UAttributesError::validation_error(format!(
+            "Wrong Message Type [{}]",
+            mt.to_cloudevent_type()
+        ))),
+        Err(unknown_code) => Err(UAttributesError::validation_error(format!(
+            "Unknown Message Type code [{}]",
+            unknown_code
+        ))),
+    }
+}
+
+/// Verifies that a set of attributes contains a valid message ID.
+///
+/// # Errors
+///
+/// Returns an error if [`UAttributes::id`] does not contain a [valid uProtocol UUID](`UUID::is_uprotocol_uuid`).
+pub fn validate_id(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if attributes
+        .id
+        .as_ref()
+        .map_or(false, |id| id.is_uprotocol_uuid())
+    {
+        Ok(())
+    } else {
+        Err(UAttributesError::validation_error(
+            "Attributes must contain valid uProtocol UUID in id property",
+        ))
+    }
+}
+
+/// Checks if the message that is described by a set of attributes should be considered expired.
+///
+/// # Errors
+///
+/// Returns an error if [`UAttributes::ttl`] (time-to-live) contains a value greater than 0, but
+/// * the message has expired according to the timestamp extracted from [`UAttributes::id`] and the time-to-live value, or
+/// * the current time cannot be determined by the [configured time source](`crate::time_source`).
+pub fn validate_not_expired(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    let ttl = match attributes.ttl {
+        Some(t) if t > 0 => u64::from(t),
+        _ => return Ok(()),
+    };
+
+    if let Some(time) = attributes.id.as_ref().and_then(UUID::get_time) {
+        let delta = match crate::time_source::now() {
+            Some(duration) => {
+                if let Ok(duration) = u64::try_from(duration.as_millis()) {
+                    duration.saturating_sub(time)
+                } else {
+                    return Err(UAttributesError::validation_error("Invalid duration"));
+                }
+            }
+            None => {
+                return Err(UAttributesError::validation_error(
+                    "current time cannot be determined",
+                ))
+            }
+        };
+        if delta >= ttl {
+            return Err(UAttributesError::validation_error("Payload is expired"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks if the event time contained in a set of attributes is plausible.
+///
+/// Attributes that do not contain an [event time](`UAttributes::event_time`) are considered valid.
+///
+/// # Arguments
+///
+/// * `attributes` - The attributes to check.
+/// * `max_skew` - The maximum amount of time that the event time may deviate from the message's
+///                creation time. The deviation includes the time that has passed between the
+///                occurrence of the event and the creation of the message as well as any
+///                difference between the clocks used for determining these points in time.
+///
+/// # Errors
+///
+/// Returns an error if the attributes contain an event time but
+/// * [`UAttributes::id`] does not contain a valid uProtocol UUID, or
+/// * the event time deviates from the creation time contained in the message ID by more than `max_skew`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use up_rust::{UAttributes, UUID};
+/// use up_rust::uattributes::validate_event_time;
+///
+/// let id = UUID::build();
+/// let created_at = id.get_time().unwrap();
+/// let mut attribs = UAttributes {
+///     id: Some(id).into(),
+///     ..Default::default()
+/// };
+/// attribs.set_event_time(created_at - 200);
+/// assert!(validate_event_time(&attribs, Duration::from_millis(500)).is_ok());
+/// assert!(validate_event_time(&attribs, Duration::from_millis(100)).is_err());
+/// ```
+pub fn validate_event_time(
+    attributes: &UAttributes,
+    max_skew: Duration,
+) -> Result<(), UAttributesError> {
+    let Some(event_time) = attributes.event_time() else {
+        return Ok(());
+    };
+    let Some(creation_time) = attributes.id.as_ref().and_then(UUID::get_time) else {
+        return Err(UAttributesError::validation_error(
+            "Attributes containing an event time must contain valid uProtocol UUID in id property",
+        ));
+    };
+    let skew = creation_time.abs_diff(event_time);
+    if u128::from(skew) > max_skew.as_millis() {
+        return Err(UAttributesError::validation_error(format!(
+            "Event time deviates from message creation time by {} ms",
+            skew
+        )));
+    }
+    Ok(())
+}
+
+/// Verifies that attributes for a publish message contain a valid source URI.
+///
+/// # Errors
+///
+/// Returns an error
+///
+/// * if the attributes do not contain a source URI, or
+/// * if the source URI contains any wildcards, or
+/// * if the source URI has a resource ID of 0.
+pub fn validate_publish_source(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Some(source) = attributes.source.as_ref() {
+        source
+            .verify_event()
+            .map_err(|e| UAttributesError::validation_error(format!("Invalid source URI: {}", e)))
+    } else {
+        Err(UAttributesError::validation_error(
+            "Attributes for a publish message must contain a source URI",
+        ))
+    }
+}
+
+/// Verifies that attributes for a publish message do not contain a sink URI.
+///
+/// # Errors
+///
+/// If the [`UAttributes::sink`] property contains any URI, an error is returned.
+pub fn validate_publish_sink(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if attributes.sink.as_ref().is_some() {
+        Err(UAttributesError::validation_error(
+            "Attributes for a publish message must not contain a sink URI",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Verifies that attributes for a notification message contain a source URI.
+///
+/// # Errors
+///
+/// Returns an error
+///
+/// * if the attributes do not contain a source URI, or
+/// * if the source URI is an RPC response URI, or
+/// * if the source URI contains any wildcards.
+pub fn validate_notification_source(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Some(source) = attributes.source.as_ref() {
+        if source.is_rpc_response() {
+            Err(UAttributesError::validation_error(
+                "Origin must not be an RPC response URI",
+            ))
+        } else {
+            source.verify_no_wildcards().map_err(|e| {
+                UAttributesError::validation_error(format!("Invalid source URI: {}", e))
+            })
+        }
+    } else {
+        Err(UAttributesError::validation_error(
+            "Attributes must contain a source URI",
+        ))
+    }
+}
+
+/// Verifies that attributes for a notification message contain a sink URI.
+///
+/// # Errors
+///
+/// Returns an error
+///
+/// * if the attributes do not contain a sink URI, or
+/// * if the sink URI's resource ID is != 0, or
+/// * if the sink URI contains any wildcards.
+pub fn validate_notification_sink(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Some(sink) = attributes.sink.as_ref() {
+        if !sink.is_notification_destination() {
+            Err(UAttributesError::validation_error(
+                "Destination's resource ID must be 0",
+            ))
+        } else {
+            sink.verify_no_wildcards()
+                .map_err(|e| UAttributesError::validation_error(format!("Invalid sink URI: {}", e)))
+        }
+    } else {
+        Err(UAttributesError::validation_error(
+            "Attributes for a notification message must contain a sink URI",
+        ))
+    }
+}
+
+/// Verifies that a set of attributes representing an RPC request contain a valid time-to-live.
+///
+/// # Errors
+///
+/// Returns an error if [`UAttributes::ttl`] (time-to-live) is empty or contains a value less than 1.
+pub fn validate_request_ttl(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    match attributes.ttl {
+        Some(ttl) if ttl > 0 => Ok(()),
+        Some(invalid_ttl) => Err(UAttributesError::validation_error(format!(
+            "RPC request message's TTL must be a positive integer [{invalid_ttl}]"
+        ))),
+        None => Err(UAttributesError::validation_error(
+            "RPC request message must contain a TTL",
+        )),
+    }
+}
+
+/// Verifies that attributes for a message representing an RPC request contain a reply-to-address.
+///
+/// # Errors
+///
+/// Returns an error if the [`UAttributes::source`] property does not contain a valid reply-to-address according to
+/// [`UUri::verify_rpc_response`].
+pub fn validate_request_source(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Some(source) = attributes.source.as_ref() {
+        UUri::verify_rpc_response(source)
+            .map_err(|e| UAttributesError::validation_error(format!("Invalid source URI: {}", e)))
+    } else {
+        Err(UAttributesError::validation_error("Attributes for a request message must contain a reply-to address in the source property"))
+    }
+}
+
+/// Verifies that attributes for a message representing an RPC request indicate the method to invoke.
+///
+/// # Errors
+///
+/// Returns an error if the [`UAttributes::sink`] property does not contain a URI representing a method according to
+/// [`UUri::verify_rpc_method`].
+pub fn validate_request_sink(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Some(sink) = attributes.sink.as_ref() {
+        UUri::verify_rpc_method(sink)
+            .map_err(|e| UAttributesError::validation_error(format!("Invalid sink URI: {}", e)))
+    } else {
+        Err(UAttributesError::validation_error(
+            "Attributes for a request message must contain a method-to-invoke in the sink property",
+        ))
+    }
+}
+
+/// Verifies that attributes for a message representing an RPC response indicate the method that has
+/// been invoked.
+///
+/// # Errors
+///
+/// Returns an error if the [`UAttributes::source`] property does not contain a URI representing a method according to
+/// [`UUri::verify_rpc_method`].
+pub fn validate_response_source(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Some(source) = attributes.source.as_ref() {
+        UUri::verify_rpc_method(source)
+            .map_err(|e| UAttributesError::validation_error(format!("Invalid source URI: {}", e)))
+    } else {
+        Err(UAttributesError::validation_error("Missing Source"))
+    }
+}
+
+/// Verifies that attributes for a message representing an RPC response contain a valid
+/// reply-to-address.
+///
+/// # Errors
+///
+/// Returns an error if the [`UAttributes::sink`] property does not contain a valid reply-to-address according to
+/// [`UUri::verify_rpc_response`].
+pub fn validate_response_sink(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Some(sink) = &attributes.sink.as_ref() {
+        UUri::verify_rpc_response(sink)
+            .map_err(|e| UAttributesError::validation_error(format!("Invalid sink URI: {}", e)))
+    } else {
+        Err(UAttributesError::validation_error("Missing Sink"))
+    }
+}
+
+/// Verifies that the attributes of an RPC response contain a valid request ID.
+///
+/// # Errors
+///
+/// Returns an error if [`UAttributes::reqid`] is empty or contains a value which is not
+/// a [valid uProtocol UUID](`UUID::is_uprotocol_uuid`).
+pub fn validate_response_reqid(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if !attributes
+        .reqid
+        .as_ref()
+        .map_or(false, |id| id.is_uprotocol_uuid())
+    {
+        Err(UAttributesError::validation_error(
+            "Request ID is not a valid uProtocol UUID",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Verifies that a set of attributes contains a valid communication status.
+///
+/// # Errors
+///
+/// Returns an error if [`UAttributes::commstatus`] does not contain a value that is a `UCode`.
+pub fn validate_commstatus(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Some(status) = attributes.commstatus {
+        if let Err(e) = status.enum_value() {
+            return Err(UAttributesError::validation_error(format!(
+                "Invalid Communication Status code: {e}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Verifies that a set of attributes complies with the rules that are only enforced by
+/// [`UAttributesValidator::validate_strict`].
+///
+/// # Errors
+///
+/// Returns an error if
+/// * [`UAttributes::priority`] or [`UAttributes::payload_format`] contain an unknown value, or
+/// * [`UAttributes::traceparent`] does not contain a valid W3C trace context.
+pub fn validate_strict_spec(attributes: &UAttributes) -> Result<(), UAttributesError> {
+    if let Err(unknown_code) = attributes.priority.enum_value() {
+        return Err(UAttributesError::validation_error(format!(
+            "Unknown Priority code [{}]",
+            unknown_code
+        )));
+    }
+    if let Err(unknown_code) = attributes.payload_format.enum_value() {
+        return Err(UAttributesError::validation_error(format!(
+            "Unknown Payload Format code [{}]",
+            unknown_code
+        )));
+    }
+    validate_traceparent(attributes)
+}
+
 /// Enum that hold the implementations of uattributesValidator according to type.
 pub enum UAttributesValidators {
     Publish,
@@ -286,15 +643,7 @@ impl UAttributesValidator for PublishValidator {
     /// * if the source URI contains any wildcards, or
     /// * if the source URI has a resource ID of 0.
     fn validate_source(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if let Some(source) = attributes.source.as_ref() {
-            source.verify_event().map_err(|e| {
-                UAttributesError::validation_error(format!("Invalid source URI: {}", e))
-            })
-        } else {
-            Err(UAttributesError::validation_error(
-                "Attributes for a publish message must contain a source URI",
-            ))
-        }
+        validate_publish_source(attributes)
     }
 
     /// Verifies that attributes for a publish message do not contain a sink URI.
@@ -303,13 +652,7 @@ impl UAttributesValidator for PublishValidator {
     ///
     /// If the [`UAttributes::sink`] property contains any URI, an error is returned.
     fn validate_sink(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if attributes.sink.as_ref().is_some() {
-            Err(UAttributesError::validation_error(
-                "Attributes for a publish message must not contain a sink URI",
-            ))
-        } else {
-            Ok(())
-        }
+        validate_publish_sink(attributes)
     }
 }
 
@@ -362,21 +705,7 @@ impl UAttributesValidator for NotificationValidator {
     /// * if the source URI is an RPC response URI, or
     /// * if the source URI contains any wildcards.
     fn validate_source(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if let Some(source) = attributes.source.as_ref() {
-            if source.is_rpc_response() {
-                Err(UAttributesError::validation_error(
-                    "Origin must not be an RPC response URI",
-                ))
-            } else {
-                source.verify_no_wildcards().map_err(|e| {
-                    UAttributesError::validation_error(format!("Invalid source URI: {}", e))
-                })
-            }
-        } else {
-            Err(UAttributesError::validation_error(
-                "Attributes must contain a source URI",
-            ))
-        }
+        validate_notification_source(attributes)
     }
 
     /// Verifies that attributes for a notification message contain a sink URI.
@@ -389,21 +718,7 @@ impl UAttributesValidator for NotificationValidator {
     /// * if the sink URI's resource ID is != 0, or
     /// * if the sink URI contains any wildcards.
     fn validate_sink(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if let Some(sink) = attributes.sink.as_ref() {
-            if !sink.is_notification_destination() {
-                Err(UAttributesError::validation_error(
-                    "Destination's resource ID must be 0",
-                ))
-            } else {
-                sink.verify_no_wildcards().map_err(|e| {
-                    UAttributesError::validation_error(format!("Invalid sink URI: {}", e))
-                })
-            }
-        } else {
-            Err(UAttributesError::validation_error(
-                "Attributes for a notification message must contain a sink URI",
-            ))
-        }
+        validate_notification_sink(attributes)
     }
 }
 
@@ -417,15 +732,7 @@ impl RequestValidator {
     ///
     /// Returns an error if [`UAttributes::ttl`] (time-to-live) is empty or contains a value less than 1.
     pub fn validate_ttl(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        match attributes.ttl {
-            Some(ttl) if ttl > 0 => Ok(()),
-            Some(invalid_ttl) => Err(UAttributesError::validation_error(format!(
-                "RPC request message's TTL must be a positive integer [{invalid_ttl}]"
-            ))),
-            None => Err(UAttributesError::validation_error(
-                "RPC request message must contain a TTL",
-            )),
-        }
+        validate_request_ttl(attributes)
     }
 }
 
@@ -476,13 +783,7 @@ impl UAttributesValidator for RequestValidator {
     /// Returns an error if the [`UAttributes::source`] property does not contain a valid reply-to-address according to
     /// [`UUri::verify_rpc_response`].
     fn validate_source(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if let Some(source) = attributes.source.as_ref() {
-            UUri::verify_rpc_response(source).map_err(|e| {
-                UAttributesError::validation_error(format!("Invalid source URI: {}", e))
-            })
-        } else {
-            Err(UAttributesError::validation_error("Attributes for a request message must contain a reply-to address in the source property"))
-        }
+        validate_request_source(attributes)
     }
 
     /// Verifies that attributes for a message representing an RPC request indicate the method to invoke.
@@ -492,12 +793,7 @@ impl UAttributesValidator for RequestValidator {
     /// Returns an erro if the [`UAttributes::sink`] property does not contain a URI representing a method according to
     /// [`UUri::verify_rpc_method`].
     fn validate_sink(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if let Some(sink) = attributes.sink.as_ref() {
-            UUri::verify_rpc_method(sink)
-                .map_err(|e| UAttributesError::validation_error(format!("Invalid sink URI: {}", e)))
-        } else {
-            Err(UAttributesError::validation_error("Attributes for a request message must contain a method-to-invoke in the sink property"))
-        }
+        validate_request_sink(attributes)
     }
 }
 
@@ -512,17 +808,7 @@ impl ResponseValidator {
     /// Returns an error if [`UAttributes::reqid`] is empty or contains a value which is not
     /// a [valid uProtocol UUID](`UUID::is_uprotocol_uuid`).
     pub fn validate_reqid(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if !attributes
-            .reqid
-            .as_ref()
-            .map_or(false, |id| id.is_uprotocol_uuid())
-        {
-            Err(UAttributesError::validation_error(
-                "Request ID is not a valid uProtocol UUID",
-            ))
-        } else {
-            Ok(())
-        }
+        validate_response_reqid(attributes)
     }
 
     /// Verifies that a set of attributes contains a valid communication status.
@@ -531,19 +817,7 @@ impl ResponseValidator {
     ///
     /// Returns an error if [`UAttributes::commstatus`] does not contain a value that is a `UCode`.
     pub fn validate_commstatus(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if let Some(status) = attributes.commstatus {
-            match status.enum_value() {
-                Ok(_) => {
-                    return Ok(());
-                }
-                Err(e) => {
-                    return Err(UAttributesError::validation_error(format!(
-                        "Invalid Communication Status code: {e}"
-                    )));
-                }
-            }
-        }
-        Ok(())
+        validate_commstatus(attributes)
     }
 }
 
@@ -597,13 +871,7 @@ impl UAttributesValidator for ResponseValidator {
     /// Returns an error if the [`UAttributes::source`] property does not contain a URI representing a method according to
     /// [`UUri::verify_rpc_method`].
     fn validate_source(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if let Some(source) = attributes.source.as_ref() {
-            UUri::verify_rpc_method(source).map_err(|e| {
-                UAttributesError::validation_error(format!("Invalid source URI: {}", e))
-            })
-        } else {
-            Err(UAttributesError::validation_error("Missing Source"))
-        }
+        validate_response_source(attributes)
     }
 
     /// Verifies that attributes for a message representing an RPC response contain a valid
@@ -614,12 +882,7 @@ impl UAttributesValidator for ResponseValidator {
     /// Returns an error if the [`UAttributes::sink`] property does not contain a valid reply-to-address according to
     /// [`UUri::verify_rpc_response`].
     fn validate_sink(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
-        if let Some(sink) = &attributes.sink.as_ref() {
-            UUri::verify_rpc_response(sink)
-                .map_err(|e| UAttributesError::validation_error(format!("Invalid sink URI: {}", e)))
-        } else {
-            Err(UAttributesError::validation_error("Missing Sink"))
-        }
+        validate_response_sink(attributes)
     }
 }
 
@@ -627,7 +890,7 @@ impl UAttributesValidator for ResponseValidator {
 mod tests {
     use std::{
         ops::Sub,
-        time::{Duration, UNIX_EPOCH},
+        time::{Duration, SystemTime, UNIX_EPOCH},
     };
 
     use protobuf::EnumOrUnknown;
@@ -650,6 +913,28 @@ mod tests {
         )
     }
 
+    #[test_case(None, None, false; "for missing addresses")]
+    #[test_case(Some(reply_to_address()), Some(method_to_invoke()), true; "for valid addresses")]
+    #[test_case(Some(method_to_invoke()), Some(method_to_invoke()), false; "for invalid source")]
+    #[test_case(Some(reply_to_address()), Some(reply_to_address()), false; "for invalid sink")]
+    fn test_standalone_request_address_checks(
+        source: Option<UUri>,
+        sink: Option<UUri>,
+        expected_valid: bool,
+    ) {
+        // the standalone checks do not depend on any other attributes being set
+        let attributes = UAttributes {
+            source: source.into(),
+            sink: sink.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_request_source(&attributes).is_ok()
+                && validate_request_sink(&attributes).is_ok(),
+            expected_valid
+        );
+    }
+
     #[test]
     fn test_validate_type_fails_for_unknown_type_code() {
         let attributes = UAttributes {
@@ -674,6 +959,30 @@ mod tests {
             .is_err());
     }
 
+    #[test_case(UAttributes { priority: EnumOrUnknown::from_i32(42), ..Default::default() }; "for unknown priority")]
+    #[test_case(UAttributes { payload_format: EnumOrUnknown::from_i32(42), ..Default::default() }; "for unknown payload format")]
+    #[test_case(UAttributes { traceparent: Some("not-a-traceparent".to_string()), ..Default::default() }; "for invalid traceparent")]
+    fn test_validate_strict_spec_fails_for_lenient_values(attributes: UAttributes) {
+        assert!(validate_strict_spec(&attributes).is_err());
+    }
+
+    #[test]
+    fn test_validate_strict_fails_for_unknown_priority() {
+        // GIVEN a valid publish message's attributes containing an unknown priority
+        let attributes = UAttributes {
+            type_: UMessageType::UMESSAGE_TYPE_PUBLISH.into(),
+            id: Some(UUID::build()).into(),
+            source: Some(UUri::try_from_parts("vin", 0x0000_5a6b, 0x01, 0x8001).unwrap()).into(),
+            priority: EnumOrUnknown::from_i32(42),
+            ..Default::default()
+        };
+        let validator = UAttributesValidators::Publish.validator();
+
+        // THEN the attributes are accepted by the lenient validation only
+        assert!(validator.validate(&attributes).is_ok());
+        assert!(validator.validate_strict(&attributes).is_err());
+    }
+
     #[test_case(UMessageType::UMESSAGE_TYPE_UNSPECIFIED, UMessageType::UMESSAGE_TYPE_PUBLISH; "succeeds for Unspecified message")]
     #[test_case(UMessageType::UMESSAGE_TYPE_PUBLISH, UMessageType::UMESSAGE_TYPE_PUBLISH; "succeeds for Publish message")]
     #[test_case(UMessageType::UMESSAGE_TYPE_NOTIFICATION, UMessageType::UMESSAGE_TYPE_NOTIFICATION; "succeeds for Notification message")]
@@ -734,6 +1043,26 @@ mod tests {
         assert!(validator.is_expired(&attributes).is_err() == should_be_expired);
     }
 
+    #[test_case(None, None, true; "for attributes without event time")]
+    #[test_case(None, Some(1_000), false; "for event time without message ID")]
+    #[test_case(Some(build_n_ms_in_past(0)), Some(1_000), false; "for event time far in the past")]
+    #[test_case(Some(UUID::build_for_timestamp(Duration::from_millis(10_000))), Some(9_800), true; "for event time shortly before creation time")]
+    #[test_case(Some(UUID::build_for_timestamp(Duration::from_millis(10_000))), Some(10_300), true; "for event time shortly after creation time")]
+    #[test_case(Some(UUID::build_for_timestamp(Duration::from_millis(10_000))), Some(10_600), false; "for event time long after creation time")]
+    fn test_validate_event_time(id: Option<UUID>, event_time: Option<u64>, expected_valid: bool) {
+        let mut attributes = UAttributes {
+            id: id.into(),
+            ..Default::default()
+        };
+        if let Some(time) = event_time {
+            attributes.set_event_time(time);
+        }
+        assert_eq!(
+            validate_event_time(&attributes, Duration::from_millis(500)).is_ok(),
+            expected_valid
+        );
+    }
+
     #[test_case(Some(UUID::build()), Some(publish_topic()), None, None, true; "succeeds for topic only")]
     #[test_case(Some(UUID::build()), Some(publish_topic()), Some(destination()), None, false; "fails for message containing destination")]
     #[test_case(Some(UUID::build()), Some(publish_topic()), None, Some(100), true; "succeeds for valid attributes")]
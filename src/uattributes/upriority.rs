@@ -11,7 +11,8 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use protobuf::EnumFull;
+use protobuf::{Enum, EnumFull};
+use tracing::warn;
 
 use crate::uattributes::UAttributesError;
 pub use crate::up_core_api::uattributes::UPriority;
@@ -80,3 +81,64 @@ impl UPriority {
             .ok_or_else(|| UAttributesError::parsing_error(format!("unknown priority [{}]", prio)))
     }
 }
+
+/// Determines how to handle priorities that are lower than the minimum priority
+/// required for RPC Request and Response messages.
+///
+/// The uProtocol specification requires RPC messages to have a priority of at least
+/// [`UPriority::UPRIORITY_CS4`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RpcPriorityPolicy {
+    /// Priorities lower than CS4 are rejected.
+    #[default]
+    Reject,
+    /// Priorities lower than CS4 are replaced with CS4 and a warning is logged.
+    Upgrade,
+}
+
+impl RpcPriorityPolicy {
+    /// Applies this policy to a priority to be used for an RPC message.
+    ///
+    /// # Returns
+    ///
+    /// The given priority if it is at least CS4, or CS4 if the given priority is lower and
+    /// this policy is [`RpcPriorityPolicy::Upgrade`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given priority is lower than CS4 and this policy is [`RpcPriorityPolicy::Reject`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{RpcPriorityPolicy, UPriority};
+    ///
+    /// assert_eq!(
+    ///     RpcPriorityPolicy::Upgrade.apply(UPriority::UPRIORITY_CS2).unwrap(),
+    ///     UPriority::UPRIORITY_CS4
+    /// );
+    /// assert_eq!(
+    ///     RpcPriorityPolicy::Reject.apply(UPriority::UPRIORITY_CS5).unwrap(),
+    ///     UPriority::UPRIORITY_CS5
+    /// );
+    /// assert!(RpcPriorityPolicy::Reject.apply(UPriority::UPRIORITY_CS2).is_err());
+    /// ```
+    pub fn apply(&self, priority: UPriority) -> Result<UPriority, UAttributesError> {
+        if priority.value() >= UPriority::UPRIORITY_CS4.value() {
+            return Ok(priority);
+        }
+        match self {
+            RpcPriorityPolicy::Reject => Err(UAttributesError::validation_error(format!(
+                "RPC message must have a priority of at least CS4 [{}]",
+                priority.to_priority_code()
+            ))),
+            RpcPriorityPolicy::Upgrade => {
+                warn!(
+                    "upgrading priority of RPC message from {} to CS4",
+                    priority.to_priority_code()
+                );
+                Ok(UPriority::UPRIORITY_CS4)
+            }
+        }
+    }
+}
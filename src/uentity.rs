@@ -0,0 +1,373 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{LocalUriProvider, StaticUriProvider, UUri, UUriError};
+
+const CONFIG_KEY_AUTHORITY: &str = "authority";
+const CONFIG_KEY_UE_ID: &str = "ue_id";
+const CONFIG_KEY_INSTANCE: &str = "instance";
+const CONFIG_KEY_VERSION: &str = "version";
+
+/// The identity of a uEntity, consisting of its authority, type ID, instance ID and major version.
+///
+/// An identity can be used wherever a [`LocalUriProvider`] is required, e.g. when creating the
+/// default implementations of the Communication Layer API.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{LocalUriProvider, UEntityIdentity};
+///
+/// let identity = UEntityIdentity::builder(0x4210)
+///     .with_authority("my-vehicle")
+///     .with_instance(0x0003)
+///     .with_version(0x05)
+///     .build()
+///     .unwrap();
+/// assert_eq!(identity.get_source_uri().to_uri(false), "//my-vehicle/34210/5/0");
+/// assert_eq!("//my-vehicle/34210/5/0".parse::<UEntityIdentity>().unwrap(), identity);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UEntityIdentity {
+    authority: String,
+    ue_id: u16,
+    instance: u16,
+    version: u8,
+}
+
+impl UEntityIdentity {
+    /// Creates a new identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `authority` - The name of the authority that the uEntity runs on.
+    /// * `ue_id` - The uEntity's type identifier.
+    /// * `instance` - The uEntity's instance identifier.
+    /// * `version` - The uEntity's major version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the authority is invalid or if any of the properties contain
+    /// a wildcard value.
+    pub fn new<T: Into<String>>(
+        authority: T,
+        ue_id: u16,
+        instance: u16,
+        version: u8,
+    ) -> Result<Self, UUriError> {
+        let identity = UEntityIdentity {
+            authority: authority.into(),
+            ue_id,
+            instance,
+            version,
+        };
+        UUri::try_from_parts(&identity.authority, identity.entity_id(), version, 0)
+            .and_then(|uri| uri.verify_no_wildcards())?;
+        Ok(identity)
+    }
+
+    /// Creates a builder for an identity.
+    ///
+    /// The builder uses the local authority, instance 0 and major version 1, unless set explicitly.
+    ///
+    /// # Arguments
+    ///
+    /// * `ue_id` - The uEntity's type identifier.
+    pub fn builder(ue_id: u16) -> UEntityIdentityBuilder {
+        UEntityIdentityBuilder {
+            authority: String::new(),
+            ue_id,
+            instance: 0,
+            version: 0x01,
+        }
+    }
+
+    /// Gets the name of the authority that the uEntity runs on.
+    pub fn authority(&self) -> &str {
+        &self.authority
+    }
+
+    /// Gets the uEntity's type identifier.
+    pub fn ue_id(&self) -> u16 {
+        self.ue_id
+    }
+
+    /// Gets the uEntity's instance identifier.
+    pub fn instance(&self) -> u16 {
+        self.instance
+    }
+
+    /// Gets the uEntity's major version.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Gets the uEntity's identifier as used in [`UUri::ue_id`], i.e. the instance
+    /// identifier in the upper and the type identifier in the lower 16 bits.
+    pub fn entity_id(&self) -> u32 {
+        (self.instance as u32) << 16 | self.ue_id as u32
+    }
+}
+
+impl LocalUriProvider for UEntityIdentity {
+    fn get_authority(&self) -> String {
+        self.authority.clone()
+    }
+
+    fn get_resource_uri(&self, resource_id: u16) -> UUri {
+        UUri {
+            authority_name: self.authority.clone(),
+            ue_id: self.entity_id(),
+            ue_version_major: self.version as u32,
+            resource_id: resource_id as u32,
+            ..Default::default()
+        }
+    }
+
+    fn get_source_uri(&self) -> UUri {
+        self.get_resource_uri(0x0000)
+    }
+}
+
+impl Display for UEntityIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.get_source_uri().to_uri(false))
+    }
+}
+
+impl TryFrom<&UUri> for UEntityIdentity {
+    type Error = UUriError;
+
+    /// Creates an identity from a URI's authority, entity ID and version.
+    ///
+    /// The URI's resource ID is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URI's major version is not a `u8` or if the URI contains wildcards.
+    fn try_from(uri: &UUri) -> Result<Self, Self::Error> {
+        let version = u8::try_from(uri.ue_version_major)
+            .map_err(|_e| UUriError::validation_error("major version must be a u8"))?;
+        UEntityIdentity::new(
+            uri.authority_name.clone(),
+            uri.uentity_type_id(),
+            uri.uentity_instance_id(),
+            version,
+        )
+    }
+}
+
+impl FromStr for UEntityIdentity {
+    type Err = UUriError;
+
+    /// Creates an identity from a URI string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not a valid URI or if the URI contains wildcards.
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let uri = UUri::from_str(uri)?;
+        UEntityIdentity::try_from(&uri)
+    }
+}
+
+fn parse_number<T>(config: &HashMap<String, String>, key: &str) -> Result<Option<T>, UUriError>
+where
+    T: TryFrom<u32>,
+{
+    let Some(value) = config.get(key).map(|v| v.trim()) else {
+        return Ok(None);
+    };
+    let number = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => value.parse::<u32>(),
+    };
+    number
+        .ok()
+        .and_then(|n| T::try_from(n).ok())
+        .map(Some)
+        .ok_or_else(|| UUriError::validation_error(format!("invalid value for {}", key)))
+}
+
+impl TryFrom<&HashMap<String, String>> for UEntityIdentity {
+    type Error = UUriError;
+
+    /// Creates an identity from configuration properties.
+    ///
+    /// The properties `authority`, `ue_id`, `instance` and `version` are used, of which
+    /// only `ue_id` is mandatory. Numbers may be given in decimal or (`0x` prefixed)
+    /// hexadecimal notation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `ue_id` property is missing or if any of the properties
+    /// contain an invalid value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use up_rust::UEntityIdentity;
+    ///
+    /// let config = HashMap::from([
+    ///     ("authority".to_string(), "my-vehicle".to_string()),
+    ///     ("ue_id".to_string(), "0x4210".to_string()),
+    ///     ("version".to_string(), "5".to_string()),
+    /// ]);
+    /// let identity = UEntityIdentity::try_from(&config).unwrap();
+    /// assert_eq!(identity.ue_id(), 0x4210);
+    /// assert_eq!(identity.instance(), 0);
+    /// assert_eq!(identity.version(), 5);
+    /// ```
+    fn try_from(config: &HashMap<String, String>) -> Result<Self, Self::Error> {
+        let Some(ue_id) = parse_number::<u16>(config, CONFIG_KEY_UE_ID)? else {
+            return Err(UUriError::validation_error(format!(
+                "missing property {}",
+                CONFIG_KEY_UE_ID
+            )));
+        };
+        let mut builder = UEntityIdentity::builder(ue_id);
+        if let Some(authority) = config.get(CONFIG_KEY_AUTHORITY) {
+            builder.with_authority(authority.trim());
+        }
+        if let Some(instance) = parse_number::<u16>(config, CONFIG_KEY_INSTANCE)? {
+            builder.with_instance(instance);
+        }
+        if let Some(version) = parse_number::<u8>(config, CONFIG_KEY_VERSION)? {
+            builder.with_version(version);
+        }
+        builder.build()
+    }
+}
+
+impl From<UEntityIdentity> for StaticUriProvider {
+    fn from(identity: UEntityIdentity) -> Self {
+        StaticUriProvider::new(
+            identity.authority.clone(),
+            identity.entity_id(),
+            identity.version,
+        )
+    }
+}
+
+#[cfg(feature = "usubscription")]
+impl From<&UEntityIdentity> for crate::core::usubscription::SubscriberInfo {
+    /// Creates subscriber information for a uEntity.
+    fn from(identity: &UEntityIdentity) -> Self {
+        crate::core::usubscription::SubscriberInfo {
+            uri: Some(identity.get_source_uri()).into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A builder for [`UEntityIdentity`]s.
+pub struct UEntityIdentityBuilder {
+    authority: String,
+    ue_id: u16,
+    instance: u16,
+    version: u8,
+}
+
+impl UEntityIdentityBuilder {
+    /// Sets the name of the authority that the uEntity runs on.
+    pub fn with_authority<T: Into<String>>(&mut self, authority: T) -> &mut UEntityIdentityBuilder {
+        self.authority = authority.into();
+        self
+    }
+
+    /// Sets the uEntity's instance identifier.
+    pub fn with_instance(&mut self, instance: u16) -> &mut UEntityIdentityBuilder {
+        self.instance = instance;
+        self
+    }
+
+    /// Sets the uEntity's major version.
+    pub fn with_version(&mut self, version: u8) -> &mut UEntityIdentityBuilder {
+        self.version = version;
+        self
+    }
+
+    /// Creates the identity based on the builder's state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the authority is invalid or if any of the properties contain
+    /// a wildcard value.
+    pub fn build(&self) -> Result<UEntityIdentity, UUriError> {
+        UEntityIdentity::new(
+            self.authority.clone(),
+            self.ue_id,
+            self.instance,
+            self.version,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case("//my-vehicle/34210/5/0", "my-vehicle", 0x4210, 0x0003, 0x05; "for URI with authority")]
+    #[test_case("/4210/1/8001", "", 0x4210, 0x0000, 0x01; "for local URI with resource ID")]
+    fn test_from_str_succeeds(uri: &str, authority: &str, ue_id: u16, instance: u16, version: u8) {
+        let identity = uri.parse::<UEntityIdentity>().unwrap();
+        assert_eq!(identity.authority(), authority);
+        assert_eq!(identity.ue_id(), ue_id);
+        assert_eq!(identity.instance(), instance);
+        assert_eq!(identity.version(), version);
+    }
+
+    #[test_case("//*/4210/1/0"; "for wildcard authority")]
+    #[test_case("//my-vehicle/FFFF/1/0"; "for wildcard entity type")]
+    #[test_case("//my-vehicle/4210/FF/0"; "for wildcard version")]
+    #[test_case("invalid uri"; "for invalid URI")]
+    fn test_from_str_fails(uri: &str) {
+        assert!(uri.parse::<UEntityIdentity>().is_err());
+    }
+
+    #[test_case(&[("ue_id", "16912")], true; "for decimal ue_id only")]
+    #[test_case(&[("ue_id", "0x4210"), ("instance", "0X2"), ("authority", "vin")], true; "for hex values")]
+    #[test_case(&[("authority", "vin")], false; "for missing ue_id")]
+    #[test_case(&[("ue_id", "0x14210")], false; "for ue_id out of range")]
+    #[test_case(&[("ue_id", "0x4210"), ("version", "256")], false; "for version out of range")]
+    #[test_case(&[("ue_id", "0x4210"), ("authority", "vin:1234")], false; "for invalid authority")]
+    fn test_try_from_config(properties: &[(&str, &str)], expected_valid: bool) {
+        let config: HashMap<String, String> = properties
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(UEntityIdentity::try_from(&config).is_ok(), expected_valid);
+    }
+
+    #[test]
+    fn test_identity_creates_same_uris_as_static_provider() {
+        let identity = UEntityIdentity::new("my-vehicle", 0x4210, 0x0003, 0x05).unwrap();
+        let static_provider = StaticUriProvider::from(identity.clone());
+        assert_eq!(identity.get_source_uri(), static_provider.get_source_uri());
+        assert_eq!(
+            identity.get_resource_uri(0x8001),
+            static_provider.get_resource_uri(0x8001)
+        );
+        assert_eq!(identity.to_string(), "//my-vehicle/34210/5/0");
+    }
+}
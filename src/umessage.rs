@@ -11,13 +11,21 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+mod canonical_hash;
+mod transcoding;
 mod umessagebuilder;
 mod umessagetype;
+mod uri_policy;
 
 use bytes::Bytes;
 use protobuf::{well_known_types::any::Any, Message, MessageFull};
 
+pub use canonical_hash::CanonicalHashOptions;
+#[cfg(feature = "json")]
+pub use transcoding::JsonCodec;
+pub use transcoding::{CodecRegistry, PayloadCodec, ProtobufCodec};
 pub use umessagebuilder::*;
+pub use uri_policy::UriPolicy;
 
 pub use crate::up_core_api::umessage::UMessage;
 
@@ -47,6 +55,25 @@ impl std::fmt::Display for UMessageError {
 
 impl std::error::Error for UMessageError {}
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for UMessageError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Self::AttributesValidationError(e) => defmt::write!(
+                f,
+                "Builder state is not consistent with message type: {}",
+                e
+            ),
+            Self::DataSerializationError(e) => defmt::write!(
+                f,
+                "Failed to serialize payload: {}",
+                defmt::Display2Format(e)
+            ),
+            Self::PayloadError(e) => defmt::write!(f, "UMessage payload error: {=str}", e.as_str()),
+        }
+    }
+}
+
 impl From<UAttributesError> for UMessageError {
     fn from(value: UAttributesError) -> Self {
         Self::AttributesValidationError(value)
@@ -0,0 +1,280 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use protobuf::UnknownValueRef;
+
+use crate::uattributes::EVENT_TIME_FIELD_NUMBER;
+use crate::{UMessage, UUri, UUID};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Determines which parts of a message are taken into account by [`UMessage::canonical_hash`].
+///
+/// By default, the message ID and the event time are excluded, because they usually differ between
+/// messages that carry the same information, e.g. when a client retries a request.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CanonicalHashOptions {
+    include_message_id: bool,
+    include_event_time: bool,
+}
+
+impl CanonicalHashOptions {
+    /// Creates options excluding the message ID and the event time.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the message ID should be taken into account.
+    pub fn with_message_id(mut self, include: bool) -> Self {
+        self.include_message_id = include;
+        self
+    }
+
+    /// Sets whether the [event time](`crate::UAttributes::event_time`) should be taken into account.
+    pub fn with_event_time(mut self, include: bool) -> Self {
+        self.include_event_time = include;
+        self
+    }
+}
+
+// writes a representation of a message that does not depend on the order of the
+// message's unknown fields, which is unspecified
+#[derive(Default)]
+struct CanonicalWriter {
+    buf: Vec<u8>,
+}
+
+impl CanonicalWriter {
+    fn field(&mut self, tag: u8, data: &[u8]) {
+        self.buf.push(tag);
+        self.buf
+            .extend_from_slice(&(data.len() as u64).to_be_bytes());
+        self.buf.extend_from_slice(data);
+    }
+
+    fn number(&mut self, tag: u8, value: u64) {
+        self.field(tag, &value.to_be_bytes());
+    }
+
+    fn optional_number(&mut self, tag: u8, value: Option<u64>) {
+        if let Some(v) = value {
+            self.number(tag, v);
+        }
+    }
+
+    fn optional_string(&mut self, tag: u8, value: Option<&String>) {
+        if let Some(v) = value {
+            self.field(tag, v.as_bytes());
+        }
+    }
+
+    fn uri(&mut self, tag: u8, uri: Option<&UUri>) {
+        if let Some(uri) = uri {
+            let mut data = Vec::with_capacity(uri.authority_name.len() + 16);
+            data.extend_from_slice(&uri.ue_id.to_be_bytes());
+            data.extend_from_slice(&uri.ue_version_major.to_be_bytes());
+            data.extend_from_slice(&uri.resource_id.to_be_bytes());
+            data.extend_from_slice(uri.authority_name.as_bytes());
+            self.field(tag, &data);
+        }
+    }
+
+    fn uuid(&mut self, tag: u8, uuid: Option<&UUID>) {
+        if let Some(uuid) = uuid {
+            let mut data = [0u8; 16];
+            data[..8].copy_from_slice(&uuid.msb.to_be_bytes());
+            data[8..].copy_from_slice(&uuid.lsb.to_be_bytes());
+            self.field(tag, &data);
+        }
+    }
+}
+
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+impl UMessage {
+    fn canonical_bytes(&self, options: &CanonicalHashOptions) -> Vec<u8> {
+        let mut writer = CanonicalWriter::default();
+        if let Some(attributes) = self.attributes.as_ref() {
+            if options.include_message_id {
+                writer.uuid(1, attributes.id.as_ref());
+            }
+            writer.number(2, attributes.type_.value() as u64);
+            writer.uri(3, attributes.source.as_ref());
+            writer.uri(4, attributes.sink.as_ref());
+            writer.number(5, attributes.priority.value() as u64);
+            writer.optional_number(6, attributes.ttl.map(u64::from));
+            writer.optional_number(7, attributes.permission_level.map(u64::from));
+            writer.optional_number(8, attributes.commstatus.map(|v| v.value() as u64));
+            writer.uuid(9, attributes.reqid.as_ref());
+            writer.optional_string(10, attributes.token.as_ref());
+            writer.optional_string(11, attributes.traceparent.as_ref());
+            writer.number(12, attributes.payload_format.value() as u64);
+
+            let mut unknown_fields: Vec<(u32, Vec<u8>)> = attributes
+                .special_fields
+                .unknown_fields()
+                .iter()
+                .filter(|(number, _value)| {
+                    options.include_event_time || *number != EVENT_TIME_FIELD_NUMBER
+                })
+                .map(|(number, value)| {
+                    let data = match value {
+                        UnknownValueRef::Fixed32(v) => v.to_be_bytes().to_vec(),
+                        UnknownValueRef::Fixed64(v) => v.to_be_bytes().to_vec(),
+                        UnknownValueRef::Varint(v) => v.to_be_bytes().to_vec(),
+                        UnknownValueRef::LengthDelimited(v) => v.to_vec(),
+                    };
+                    (number, data)
+                })
+                .collect();
+            unknown_fields.sort();
+            for (number, data) in unknown_fields {
+                writer.number(13, u64::from(number));
+                writer.field(14, &data);
+            }
+        }
+        if let Some(payload) = self.payload.as_ref() {
+            writer.field(15, payload);
+        }
+        writer.buf
+    }
+
+    /// Computes a hash value over this message's attributes and payload.
+    ///
+    /// The hash value is stable across processes and platforms, so it can be used as a key for
+    /// de-duplication windows, idempotency stores or replay detection. Messages that are
+    /// [canonically equal](`Self::canonical_eq`) have the same hash value.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Determines which parts of the message to include.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{CanonicalHashOptions, UMessageBuilder, UUri};
+    ///
+    /// let topic = UUri::try_from("//my-vehicle/4210/1/B24D").unwrap();
+    /// let first = UMessageBuilder::publish(topic.clone()).build().unwrap();
+    /// let second = UMessageBuilder::publish(topic).build().unwrap();
+    ///
+    /// // the messages only differ in their IDs
+    /// let options = CanonicalHashOptions::default();
+    /// assert_eq!(first.canonical_hash(&options), second.canonical_hash(&options));
+    ///
+    /// let options = CanonicalHashOptions::default().with_message_id(true);
+    /// assert_ne!(first.canonical_hash(&options), second.canonical_hash(&options));
+    /// ```
+    pub fn canonical_hash(&self, options: &CanonicalHashOptions) -> u64 {
+        fnv1a(&self.canonical_bytes(options))
+    }
+
+    /// Checks if this message carries the same information as another message.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The message to compare to.
+    /// * `options` - Determines which parts of the messages to compare.
+    pub fn canonical_eq(&self, other: &UMessage, options: &CanonicalHashOptions) -> bool {
+        self.canonical_bytes(options) == other.canonical_bytes(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{UMessageBuilder, UPayloadFormat};
+
+    fn new_request() -> UMessage {
+        UMessageBuilder::request(
+            UUri::try_from("//vehicle/A200/1/7000").unwrap(),
+            UUri::try_from("//cloud/A100/1/0").unwrap(),
+            5_000,
+        )
+        .with_idempotency_key("unlock-4711")
+        .with_event_time(1_706_520_652_000)
+        .build_with_payload("unlock", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_canonical_hash_ignores_order_of_unknown_fields() {
+        let message = new_request();
+        let mut reordered = message.clone();
+        let attributes = reordered.attributes.mut_or_insert_default();
+        // re-adding the fields in reverse order
+        attributes.special_fields.mut_unknown_fields().clear();
+        attributes.set_event_time(1_706_520_652_000);
+        attributes.set_idempotency_key("unlock-4711");
+
+        let options = CanonicalHashOptions::new().with_event_time(true);
+        assert_eq!(
+            message.canonical_hash(&options),
+            reordered.canonical_hash(&options)
+        );
+        assert!(message.canonical_eq(&reordered, &options));
+    }
+
+    #[test]
+    fn test_canonical_hash_excludes_id_and_event_time_by_default() {
+        let message = new_request();
+        let mut retry = new_request();
+        retry
+            .attributes
+            .mut_or_insert_default()
+            .set_event_time(1_706_520_653_000);
+        assert_ne!(message.attributes.id, retry.attributes.id);
+
+        let options = CanonicalHashOptions::default();
+        assert!(message.canonical_eq(&retry, &options));
+        assert_eq!(
+            message.canonical_hash(&options),
+            retry.canonical_hash(&options)
+        );
+
+        let options = CanonicalHashOptions::default().with_event_time(true);
+        assert!(!message.canonical_eq(&retry, &options));
+        let options = CanonicalHashOptions::default().with_message_id(true);
+        assert!(!message.canonical_eq(&retry, &options));
+    }
+
+    #[test]
+    fn test_canonical_hash_includes_payload_and_attributes() {
+        let message = new_request();
+
+        let mut other_payload = message.clone();
+        other_payload.payload = Some("lock".into());
+        let mut other_key = message.clone();
+        other_key
+            .attributes
+            .mut_or_insert_default()
+            .set_idempotency_key("unlock-4712");
+        let mut other_ttl = message.clone();
+        other_ttl.attributes.mut_or_insert_default().ttl = Some(6_000);
+
+        let options = CanonicalHashOptions::default();
+        for other in [other_payload, other_key, other_ttl] {
+            assert!(!message.canonical_eq(&other, &options));
+            assert_ne!(
+                message.canonical_hash(&options),
+                other.canonical_hash(&options)
+            );
+        }
+    }
+}
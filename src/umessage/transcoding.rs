@@ -0,0 +1,625 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use protobuf::reflect::{
+    MessageDescriptor, ReflectValueBox, ReflectValueRef, RuntimeFieldType, RuntimeType,
+};
+use protobuf::well_known_types::any::Any;
+use protobuf::well_known_types::struct_::{value::Kind, ListValue, Struct, Value};
+use protobuf::{Message, MessageDyn, MessageFull};
+
+use crate::{UMessage, UMessageError, UPayloadFormat};
+
+/// A codec for converting payload data of a particular format from and to a format-neutral representation.
+///
+/// The format-neutral representation is a protobuf [`Value`], which supports the same kinds of
+/// data as JSON.
+pub trait PayloadCodec: Send + Sync {
+    /// Decodes payload data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be decoded.
+    fn decode(&self, data: &Bytes) -> Result<Value, UMessageError>;
+
+    /// Encodes a value into payload data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be encoded.
+    fn encode(&self, value: &Value) -> Result<Bytes, UMessageError>;
+}
+
+/// A set of [`PayloadCodec`]s, indexed by the payload format that they support.
+///
+/// The codecs for binary formats like protobuf need to know the type of data contained in a
+/// payload. A registry is therefore usually created per message type, e.g. per topic or method.
+///
+/// # Examples
+///
+/// ```rust
+/// use protobuf::well_known_types::wrappers::StringValue;
+/// use up_rust::{CodecRegistry, UMessageBuilder, UPayloadFormat, UUri};
+///
+/// let registry = CodecRegistry::for_protobuf_type::<StringValue>();
+/// let topic = UUri::try_from("//vehicle/D4A/1/8001").unwrap();
+/// let message = UMessageBuilder::publish(topic)
+///     .build_with_protobuf_payload(&StringValue {
+///         value: "hello".to_string(),
+///         ..Default::default()
+///     })
+///     .unwrap();
+///
+/// let transcoded = message
+///     .transcode(UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY, &registry)
+///     .unwrap();
+/// assert_eq!(
+///     transcoded.attributes.payload_format.enum_value_or_default(),
+///     UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY
+/// );
+/// assert_eq!(transcoded.attributes.id, message.attributes.id);
+/// ```
+#[derive(Clone, Default)]
+pub struct CodecRegistry {
+    codecs: HashMap<UPayloadFormat, Arc<dyn PayloadCodec>>,
+}
+
+impl CodecRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry containing codecs for a protobuf message type.
+    ///
+    /// The registry contains codecs for payload formats `UPAYLOAD_FORMAT_PROTOBUF`,
+    /// `UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY` and, if the `json` feature is enabled,
+    /// `UPAYLOAD_FORMAT_JSON`.
+    pub fn for_protobuf_type<T: MessageFull>() -> Self {
+        let registry = Self::new()
+            .with_codec(
+                UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF,
+                ProtobufCodec::for_type::<T>(),
+            )
+            .with_codec(
+                UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY,
+                ProtobufCodec::for_type::<T>().wrapped_in_any(),
+            );
+        #[cfg(feature = "json")]
+        let registry = registry.with_codec(UPayloadFormat::UPAYLOAD_FORMAT_JSON, JsonCodec);
+        registry
+    }
+
+    /// Adds a codec for a payload format, replacing any existing codec for the format.
+    pub fn with_codec<C: PayloadCodec + 'static>(
+        mut self,
+        payload_format: UPayloadFormat,
+        codec: C,
+    ) -> Self {
+        self.codecs.insert(payload_format, Arc::new(codec));
+        self
+    }
+
+    /// Gets the codec for a payload format.
+    ///
+    /// `UPAYLOAD_FORMAT_UNSPECIFIED` is interpreted as `UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY`
+    /// according to the uProtocol specification.
+    pub fn get(&self, payload_format: UPayloadFormat) -> Option<Arc<dyn PayloadCodec>> {
+        let payload_format = match payload_format {
+            UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED => {
+                UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY
+            }
+            other => other,
+        };
+        self.codecs.get(&payload_format).cloned()
+    }
+
+    fn codec(
+        &self,
+        payload_format: UPayloadFormat,
+    ) -> Result<Arc<dyn PayloadCodec>, UMessageError> {
+        self.get(payload_format).ok_or_else(|| {
+            UMessageError::PayloadError(format!(
+                "no codec registered for payload format {}",
+                payload_format.to_media_type().unwrap_or_default()
+            ))
+        })
+    }
+}
+
+/// A codec for protobuf encoded payloads of a particular message type.
+///
+/// Messages are represented as [`Struct`]s containing an entry for each field that is set,
+/// using the field's name as the key. Enum values are represented by their names and
+/// 64 bit integers by numbers. Fields of type `bytes` are not supported.
+#[derive(Clone)]
+pub struct ProtobufCodec {
+    descriptor: MessageDescriptor,
+    wrapped_in_any: bool,
+}
+
+impl ProtobufCodec {
+    /// Creates a codec for a message type.
+    pub fn for_type<T: MessageFull>() -> Self {
+        ProtobufCodec {
+            descriptor: T::descriptor(),
+            wrapped_in_any: false,
+        }
+    }
+
+    /// Makes this codec expect and produce messages that are wrapped in an [`Any`].
+    pub fn wrapped_in_any(mut self) -> Self {
+        self.wrapped_in_any = true;
+        self
+    }
+}
+
+impl PayloadCodec for ProtobufCodec {
+    fn decode(&self, data: &Bytes) -> Result<Value, UMessageError> {
+        let message = if self.wrapped_in_any {
+            let any =
+                Any::parse_from_tokio_bytes(data).map_err(UMessageError::DataSerializationError)?;
+            any.unpack_dyn(&self.descriptor)
+                .map_err(UMessageError::DataSerializationError)?
+                .ok_or_else(|| {
+                    UMessageError::PayloadError(
+                        "cannot decode payload, message type mismatch".to_string(),
+                    )
+                })?
+        } else {
+            self.descriptor
+                .parse_from_bytes(data)
+                .map_err(UMessageError::DataSerializationError)?
+        };
+        message_to_value(&*message)
+    }
+
+    fn encode(&self, value: &Value) -> Result<Bytes, UMessageError> {
+        let message = value_to_message(&self.descriptor, value)?;
+        let data = if self.wrapped_in_any {
+            Any::pack_dyn(&*message)
+                .and_then(|any| any.write_to_bytes())
+                .map_err(UMessageError::DataSerializationError)?
+        } else {
+            message
+                .write_to_bytes_dyn()
+                .map_err(UMessageError::DataSerializationError)?
+        };
+        Ok(Bytes::from(data))
+    }
+}
+
+/// A codec for JSON encoded payloads.
+#[cfg(feature = "json")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonCodec;
+
+#[cfg(feature = "json")]
+impl PayloadCodec for JsonCodec {
+    fn decode(&self, data: &Bytes) -> Result<Value, UMessageError> {
+        serde_json::from_slice::<serde_json::Value>(data)
+            .map(json_to_value)
+            .map_err(|e| UMessageError::PayloadError(format!("invalid JSON: {}", e)))
+    }
+
+    fn encode(&self, value: &Value) -> Result<Bytes, UMessageError> {
+        serde_json::to_vec(&value_to_json(value))
+            .map(Bytes::from)
+            .map_err(|e| UMessageError::PayloadError(format!("cannot encode JSON: {}", e)))
+    }
+}
+
+#[cfg(feature = "json")]
+fn json_to_value(json: serde_json::Value) -> Value {
+    let kind = match json {
+        serde_json::Value::Null => Kind::NullValue(Default::default()),
+        serde_json::Value::Bool(b) => Kind::BoolValue(b),
+        serde_json::Value::Number(n) => Kind::NumberValue(n.as_f64().unwrap_or_default()),
+        serde_json::Value::String(s) => Kind::StringValue(s),
+        serde_json::Value::Array(items) => Kind::ListValue(ListValue {
+            values: items.into_iter().map(json_to_value).collect(),
+            ..Default::default()
+        }),
+        serde_json::Value::Object(entries) => Kind::StructValue(Struct {
+            fields: entries
+                .into_iter()
+                .map(|(key, value)| (key, json_to_value(value)))
+                .collect(),
+            ..Default::default()
+        }),
+    };
+    Value {
+        kind: Some(kind),
+        ..Default::default()
+    }
+}
+
+#[cfg(feature = "json")]
+fn value_to_json(value: &Value) -> serde_json::Value {
+    match &value.kind {
+        Some(Kind::BoolValue(b)) => serde_json::Value::Bool(*b),
+        Some(Kind::NumberValue(n)) => serde_json::Number::from_f64(*n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Some(Kind::StringValue(s)) => serde_json::Value::String(s.to_owned()),
+        Some(Kind::ListValue(list)) => {
+            serde_json::Value::Array(list.values.iter().map(value_to_json).collect())
+        }
+        Some(Kind::StructValue(s)) => serde_json::Value::Object(
+            s.fields
+                .iter()
+                .map(|(key, value)| (key.to_owned(), value_to_json(value)))
+                .collect(),
+        ),
+        _ => serde_json::Value::Null,
+    }
+}
+
+fn value_of(kind: Kind) -> Value {
+    Value {
+        kind: Some(kind),
+        ..Default::default()
+    }
+}
+
+fn message_to_value(message: &dyn MessageDyn) -> Result<Value, UMessageError> {
+    let mut fields = HashMap::new();
+    for field in message.descriptor_dyn().fields() {
+        let value = match field.runtime_field_type() {
+            RuntimeFieldType::Singular(_) => match field.get_singular(message) {
+                Some(value) => reflect_value_to_value(value)?,
+                None => continue,
+            },
+            RuntimeFieldType::Repeated(_) => {
+                let repeated = field.get_repeated(message);
+                if repeated.is_empty() {
+                    continue;
+                }
+                let values = repeated
+                    .into_iter()
+                    .map(reflect_value_to_value)
+                    .collect::<Result<Vec<Value>, UMessageError>>()?;
+                value_of(Kind::ListValue(ListValue {
+                    values,
+                    ..Default::default()
+                }))
+            }
+            RuntimeFieldType::Map(_, _) => {
+                let map = field.get_map(message);
+                if map.is_empty() {
+                    continue;
+                }
+                let mut entries = HashMap::new();
+                for (key, value) in &map {
+                    entries.insert(key.to_string(), reflect_value_to_value(value)?);
+                }
+                value_of(Kind::StructValue(Struct {
+                    fields: entries,
+                    ..Default::default()
+                }))
+            }
+        };
+        fields.insert(field.name().to_string(), value);
+    }
+    Ok(value_of(Kind::StructValue(Struct {
+        fields,
+        ..Default::default()
+    })))
+}
+
+fn reflect_value_to_value(value: ReflectValueRef) -> Result<Value, UMessageError> {
+    let kind = match value {
+        ReflectValueRef::U32(v) => Kind::NumberValue(v.into()),
+        ReflectValueRef::U64(v) => Kind::NumberValue(v as f64),
+        ReflectValueRef::I32(v) => Kind::NumberValue(v.into()),
+        ReflectValueRef::I64(v) => Kind::NumberValue(v as f64),
+        ReflectValueRef::F32(v) => Kind::NumberValue(v.into()),
+        ReflectValueRef::F64(v) => Kind::NumberValue(v),
+        ReflectValueRef::Bool(v) => Kind::BoolValue(v),
+        ReflectValueRef::String(v) => Kind::StringValue(v.to_string()),
+        ReflectValueRef::Bytes(_) => {
+            return Err(UMessageError::PayloadError(
+                "fields of type bytes are not supported".to_string(),
+            ))
+        }
+        ReflectValueRef::Enum(descriptor, number) => match descriptor.value_by_number(number) {
+            Some(enum_value) => Kind::StringValue(enum_value.name().to_string()),
+            None => Kind::NumberValue(number.into()),
+        },
+        ReflectValueRef::Message(message) => return message_to_value(&*message),
+    };
+    Ok(value_of(kind))
+}
+
+fn value_to_message(
+    descriptor: &MessageDescriptor,
+    value: &Value,
+) -> Result<Box<dyn MessageDyn>, UMessageError> {
+    let Some(Kind::StructValue(entries)) = &value.kind else {
+        return Err(UMessageError::PayloadError(format!(
+            "{} must be represented by an object",
+            descriptor.full_name()
+        )));
+    };
+    let mut message = descriptor.new_instance();
+    for (name, value) in &entries.fields {
+        let field = descriptor.field_by_name_or_json_name(name).ok_or_else(|| {
+            UMessageError::PayloadError(format!(
+                "{} has no field named [{}]",
+                descriptor.full_name(),
+                name
+            ))
+        })?;
+        match field.runtime_field_type() {
+            RuntimeFieldType::Singular(runtime_type) => field
+                .set_singular_field(&mut *message, value_to_reflect_value(&runtime_type, value)?),
+            RuntimeFieldType::Repeated(runtime_type) => {
+                let Some(Kind::ListValue(list)) = &value.kind else {
+                    return Err(UMessageError::PayloadError(format!(
+                        "field [{}] must be represented by an array",
+                        name
+                    )));
+                };
+                let mut repeated = field.mut_repeated(&mut *message);
+                for item in &list.values {
+                    repeated.push(value_to_reflect_value(&runtime_type, item)?);
+                }
+            }
+            RuntimeFieldType::Map(key_type, value_type) => {
+                let Some(Kind::StructValue(map_entries)) = &value.kind else {
+                    return Err(UMessageError::PayloadError(format!(
+                        "field [{}] must be represented by an object",
+                        name
+                    )));
+                };
+                let mut map = field.mut_map(&mut *message);
+                for (key, item) in &map_entries.fields {
+                    map.insert(
+                        string_to_reflect_value(&key_type, key)?,
+                        value_to_reflect_value(&value_type, item)?,
+                    );
+                }
+            }
+        }
+    }
+    Ok(message)
+}
+
+fn value_to_reflect_value(
+    runtime_type: &RuntimeType,
+    value: &Value,
+) -> Result<ReflectValueBox, UMessageError> {
+    let type_mismatch = || {
+        UMessageError::PayloadError(format!(
+            "value {:?} cannot be converted to {}",
+            value.kind, runtime_type
+        ))
+    };
+    match (runtime_type, &value.kind) {
+        (RuntimeType::Message(descriptor), _) => {
+            value_to_message(descriptor, value).map(ReflectValueBox::Message)
+        }
+        (RuntimeType::Bool, Some(Kind::BoolValue(v))) => Ok(ReflectValueBox::Bool(*v)),
+        (RuntimeType::String, Some(Kind::StringValue(v))) => {
+            Ok(ReflectValueBox::String(v.to_owned()))
+        }
+        (RuntimeType::Enum(descriptor), Some(Kind::StringValue(v))) => descriptor
+            .value_by_name(v)
+            .map(|enum_value| ReflectValueBox::Enum(descriptor.clone(), enum_value.value()))
+            .ok_or_else(type_mismatch),
+        (RuntimeType::Enum(descriptor), Some(Kind::NumberValue(v))) => {
+            Ok(ReflectValueBox::Enum(descriptor.clone(), *v as i32))
+        }
+        (RuntimeType::F64, Some(Kind::NumberValue(v))) => Ok(ReflectValueBox::F64(*v)),
+        (RuntimeType::F32, Some(Kind::NumberValue(v))) => Ok(ReflectValueBox::F32(*v as f32)),
+        (_, Some(Kind::NumberValue(v))) if v.fract() == 0.0 => match runtime_type {
+            RuntimeType::I32 => i32::try_from(*v as i64).map(ReflectValueBox::I32).ok(),
+            RuntimeType::I64 => Some(ReflectValueBox::I64(*v as i64)),
+            RuntimeType::U32 => u32::try_from(*v as i64).map(ReflectValueBox::U32).ok(),
+            RuntimeType::U64 if *v >= 0.0 => Some(ReflectValueBox::U64(*v as u64)),
+            _ => None,
+        }
+        .ok_or_else(type_mismatch),
+        _ => Err(type_mismatch()),
+    }
+}
+
+fn string_to_reflect_value(
+    runtime_type: &RuntimeType,
+    key: &str,
+) -> Result<ReflectValueBox, UMessageError> {
+    let invalid_key = || UMessageError::PayloadError(format!("invalid map key [{}]", key));
+    match runtime_type {
+        RuntimeType::String => Ok(ReflectValueBox::String(key.to_string())),
+        RuntimeType::Bool => key
+            .parse()
+            .map(ReflectValueBox::Bool)
+            .map_err(|_| invalid_key()),
+        RuntimeType::I32 => key
+            .parse()
+            .map(ReflectValueBox::I32)
+            .map_err(|_| invalid_key()),
+        RuntimeType::I64 => key
+            .parse()
+            .map(ReflectValueBox::I64)
+            .map_err(|_| invalid_key()),
+        RuntimeType::U32 => key
+            .parse()
+            .map(ReflectValueBox::U32)
+            .map_err(|_| invalid_key()),
+        RuntimeType::U64 => key
+            .parse()
+            .map(ReflectValueBox::U64)
+            .map_err(|_| invalid_key()),
+        _ => Err(invalid_key()),
+    }
+}
+
+impl UMessage {
+    /// Converts this message's payload into another format.
+    ///
+    /// The payload is decoded using the registry's codec for the message's current payload format
+    /// and then encoded using the codec for the target format. All attributes, except for the
+    /// payload format, are preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_format` - The format to convert the payload to.
+    /// * `registry` - The codecs to use for decoding and encoding the payload.
+    ///
+    /// # Returns
+    ///
+    /// A copy of this message with the converted payload. Messages without a payload or which
+    /// already have the target format are copied as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload is compressed, if the registry does not contain a codec for
+    /// one of the formats or if the payload cannot be converted.
+    pub fn transcode(
+        &self,
+        target_format: UPayloadFormat,
+        registry: &CodecRegistry,
+    ) -> Result<UMessage, UMessageError> {
+        let source_format = self.attributes.payload_format.enum_value_or_default();
+        let Some(payload) = self.payload.as_ref() else {
+            return Ok(self.clone());
+        };
+        if source_format == target_format {
+            return Ok(self.clone());
+        }
+        if let Some(encoding) = self.attributes.content_encoding() {
+            return Err(UMessageError::PayloadError(format!(
+                "cannot transcode payload with content encoding [{}]",
+                encoding
+            )));
+        }
+        let value = registry.codec(source_format)?.decode(payload)?;
+        let data = registry.codec(target_format)?.encode(&value)?;
+
+        let mut transcoded = self.clone();
+        transcoded.payload = Some(data);
+        transcoded.attributes.mut_or_insert_default().payload_format = target_format.into();
+        Ok(transcoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protobuf::well_known_types::wrappers::StringValue;
+
+    use crate::{UAttributes, UCode, UMessageBuilder, UStatus, UUri};
+
+    use super::*;
+
+    fn status_message() -> UMessage {
+        let topic = UUri::try_from("//vehicle/D4A/1/8001").unwrap();
+        UMessageBuilder::publish(topic)
+            .with_ttl(5000)
+            .build_with_protobuf_payload(&UStatus::fail_with_code(
+                UCode::NOT_FOUND,
+                "no such thing",
+            ))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_transcode_preserves_attributes() {
+        // GIVEN a message with a protobuf payload
+        let message = status_message();
+        let registry = CodecRegistry::for_protobuf_type::<UStatus>();
+
+        // WHEN transcoding the payload to protobuf wrapped in Any and back again
+        let transcoded = message
+            .transcode(
+                UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY,
+                &registry,
+            )
+            .unwrap();
+        let roundtrip = transcoded
+            .transcode(UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF, &registry)
+            .unwrap();
+
+        // THEN the payload format has been updated while all other attributes are preserved
+        let expected_attributes = UAttributes {
+            payload_format: UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY.into(),
+            ..message.attributes.get_or_default().to_owned()
+        };
+        assert_eq!(transcoded.attributes.get_or_default(), &expected_attributes);
+        // and the payload still contains the same data
+        assert_eq!(
+            transcoded.extract_protobuf::<UStatus>().unwrap(),
+            message.extract_protobuf::<UStatus>().unwrap()
+        );
+        assert_eq!(roundtrip, message);
+    }
+
+    #[test]
+    fn test_transcode_fails_for_missing_codec() {
+        let message = status_message();
+        let registry = CodecRegistry::for_protobuf_type::<UStatus>();
+
+        let result = message.transcode(UPayloadFormat::UPAYLOAD_FORMAT_SOMEIP, &registry);
+
+        assert!(result.is_err_and(|e| matches!(e, UMessageError::PayloadError(_))));
+    }
+
+    #[test]
+    fn test_transcode_fails_for_message_type_mismatch() {
+        let message = status_message()
+            .transcode(
+                UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF_WRAPPED_IN_ANY,
+                &CodecRegistry::for_protobuf_type::<UStatus>(),
+            )
+            .unwrap();
+        let registry = CodecRegistry::for_protobuf_type::<StringValue>();
+
+        let result = message.transcode(UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF, &registry);
+
+        assert!(result.is_err_and(|e| matches!(e, UMessageError::PayloadError(_))));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_transcode_converts_protobuf_to_json() {
+        // GIVEN a message with a protobuf payload
+        let message = status_message();
+        let registry = CodecRegistry::for_protobuf_type::<UStatus>();
+
+        // WHEN transcoding the payload to JSON
+        let transcoded = message
+            .transcode(UPayloadFormat::UPAYLOAD_FORMAT_JSON, &registry)
+            .unwrap();
+
+        // THEN the payload contains the fields that have been set
+        let json: serde_json::Value =
+            serde_json::from_slice(transcoded.payload.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"code": "NOT_FOUND", "message": "no such thing"})
+        );
+
+        // and the JSON payload can be transcoded back to protobuf
+        let roundtrip = transcoded
+            .transcode(UPayloadFormat::UPAYLOAD_FORMAT_PROTOBUF, &registry)
+            .unwrap();
+        assert_eq!(
+            roundtrip.extract_protobuf::<UStatus>().unwrap(),
+            message.extract_protobuf::<UStatus>().unwrap()
+        );
+    }
+}
@@ -12,12 +12,13 @@
  ********************************************************************************/
 
 use bytes::Bytes;
-use protobuf::{well_known_types::any::Any, Enum, EnumOrUnknown, Message, MessageFull};
+use protobuf::{well_known_types::any::Any, EnumOrUnknown, Message, MessageFull};
 
-use crate::uattributes::NotificationValidator;
+use crate::uattributes::{validate_traceparent, NotificationValidator};
 use crate::{
-    PublishValidator, RequestValidator, ResponseValidator, UAttributes, UAttributesValidator,
-    UCode, UMessage, UMessageError, UMessageType, UPayloadFormat, UPriority, UUri, UUID,
+    PublishValidator, RequestValidator, ResponseValidator, RpcPriorityPolicy, TopicPolicy,
+    UAttributes, UAttributesError, UAttributesValidator, UCode, UMessage, UMessageError,
+    UMessageType, UPayloadFormat, UPriority, UUri, UriPolicy, UUID,
 };
 
 const PRIORITY_DEFAULT: UPriority = UPriority::UPRIORITY_CS1;
@@ -28,18 +29,25 @@ const PRIORITY_DEFAULT: UPriority = UPriority::UPRIORITY_CS1;
 /// and/or to invoke service operations provided by other entities.
 pub struct UMessageBuilder {
     comm_status: Option<EnumOrUnknown<UCode>>,
+    correlation_id: Option<UUID>,
+    event_time: Option<u64>,
+    idempotency_key: Option<String>,
     message_id: Option<UUID>,
     message_type: UMessageType,
     payload: Option<Bytes>,
     payload_format: UPayloadFormat,
     permission_level: Option<u32>,
     priority: UPriority,
+    rpc_priority_policy: RpcPriorityPolicy,
     request_id: Option<UUID>,
+    session_id: Option<String>,
     sink: Option<UUri>,
     source: Option<UUri>,
     token: Option<String>,
+    topic_policy: TopicPolicy,
     traceparent: Option<String>,
     ttl: Option<u32>,
+    uri_policy: Option<UriPolicy>,
     validator: Box<dyn UAttributesValidator>,
 }
 
@@ -47,18 +55,25 @@ impl Default for UMessageBuilder {
     fn default() -> Self {
         UMessageBuilder {
             comm_status: None,
+            correlation_id: None,
+            event_time: None,
+            idempotency_key: None,
             message_id: None,
             message_type: UMessageType::UMESSAGE_TYPE_UNSPECIFIED,
             payload: None,
             payload_format: UPayloadFormat::UPAYLOAD_FORMAT_UNSPECIFIED,
             permission_level: None,
             priority: UPriority::UPRIORITY_UNSPECIFIED,
+            rpc_priority_policy: RpcPriorityPolicy::default(),
             request_id: None,
+            session_id: None,
             sink: None,
             source: None,
             token: None,
+            topic_policy: TopicPolicy::default(),
             traceparent: None,
             ttl: None,
+            uri_policy: None,
             validator: Box::new(PublishValidator),
         }
     }
@@ -134,6 +149,53 @@ impl UMessageBuilder {
         }
     }
 
+    /// Gets a builder for creating *notification* messages that relate to a prior message.
+    ///
+    /// The notification is sent to the uEntity that the prior message originates from and carries
+    /// the prior message's ID as [correlation ID](`crate::uattributes::CORRELATION_ID_FIELD_NUMBER`).
+    /// This allows establishing causal links between messages in workflows that do not employ RPC,
+    /// e.g. notifying the originator of an alarm about the alarm having been cleared.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The component that the notification originates from.
+    /// * `related_message` - The message that the notification relates to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UMessageBuilder, UMessageType, UPayloadFormat, UUri};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let alarm_origin = UUri::try_from("//my-vehicle/4210/5/F20B")?;
+    /// let alarm_handler = UUri::try_from("//my-cloud/CCDD/2/0")?;
+    /// let alarm = UMessageBuilder::notification(alarm_origin, alarm_handler)
+    ///                    .build_with_payload("engine overheated", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)?;
+    ///
+    /// let reply_origin = UUri::try_from("//my-cloud/CCDD/2/8001")?;
+    /// let alarm_cleared = UMessageBuilder::notification_for(reply_origin, &alarm)
+    ///                    .build_with_payload("alarm cleared", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)?;
+    /// assert_eq!(alarm_cleared.attributes.type_, UMessageType::UMESSAGE_TYPE_NOTIFICATION.into());
+    /// assert_eq!(alarm_cleared.attributes.sink, Some(UUri::try_from("//my-vehicle/4210/5/0")?).into());
+    /// assert_eq!(alarm_cleared.attributes.correlation_id(), alarm.attributes.id.clone().into_option());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn notification_for(origin: UUri, related_message: &UMessage) -> UMessageBuilder {
+        let related_attributes = related_message.attributes.get_or_default();
+        UMessageBuilder {
+            validator: Box::new(NotificationValidator),
+            message_type: UMessageType::UMESSAGE_TYPE_NOTIFICATION,
+            source: Some(origin),
+            sink: related_attributes.source.as_ref().map(|source| UUri {
+                resource_id: 0,
+                ..source.to_owned()
+            }),
+            correlation_id: related_attributes.id.as_ref().cloned(),
+            ..Default::default()
+        }
+    }
+
     /// Gets a builder for creating RPC *request* messages.
     ///
     /// A request message is used to invoke a service's method with some input data, expecting
@@ -331,6 +393,32 @@ impl UMessageBuilder {
         self
     }
 
+    /// Sets the policy to apply when setting a priority lower than CS4 for an RPC message.
+    ///
+    /// The default policy is [`RpcPriorityPolicy::Reject`]. Note that the policy only affects
+    /// subsequent invocations of [`Self::with_priority`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{RpcPriorityPolicy, UMessageBuilder, UPriority, UUri};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let method_to_invoke = UUri::try_from("//my-vehicle/4D123/2/6FA3")?;
+    /// let reply_to_address = UUri::try_from("//my-cloud/BA4C/1/0")?;
+    /// let message = UMessageBuilder::request(method_to_invoke, reply_to_address, 5000)
+    ///                   .with_rpc_priority_policy(RpcPriorityPolicy::Upgrade)
+    ///                   .with_priority(UPriority::UPRIORITY_CS2)
+    ///                   .build()?;
+    /// assert_eq!(message.attributes.priority, UPriority::UPRIORITY_CS4.into());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_rpc_priority_policy(&mut self, policy: RpcPriorityPolicy) -> &mut UMessageBuilder {
+        self.rpc_priority_policy = policy;
+        self
+    }
+
     /// Sets the message's priority.
     ///
     /// If not set explicitly, the default priority as defined in the
@@ -347,7 +435,8 @@ impl UMessageBuilder {
     ///
     /// # Panics
     ///
-    /// if the builder is used for creating an RPC message but the given priority is less than CS4.
+    /// if the builder is used for creating an RPC message but the given priority is less than CS4
+    /// and the builder's [`RpcPriorityPolicy`] is [`RpcPriorityPolicy::Reject`].
     ///
     /// # Examples
     ///
@@ -367,7 +456,11 @@ impl UMessageBuilder {
         if self.message_type == UMessageType::UMESSAGE_TYPE_REQUEST
             || self.message_type == UMessageType::UMESSAGE_TYPE_RESPONSE
         {
-            assert!(priority.value() >= UPriority::UPRIORITY_CS4.value())
+            self.priority = self
+                .rpc_priority_policy
+                .apply(priority)
+                .unwrap_or_else(|e| panic!("{}", e));
+            return self;
         }
         if priority != PRIORITY_DEFAULT {
             // only set priority explicitly if it differs from the default priority
@@ -449,6 +542,186 @@ impl UMessageBuilder {
         self
     }
 
+    /// Sets the key that identifies repeated invocations of the same (state changing) operation.
+    ///
+    /// Services supporting idempotency keys process the first request having a particular key only
+    /// and respond to all subsequent requests having the same key with the outcome of the first invocation.
+    /// This allows clients to safely retry requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key.
+    ///
+    /// # Returns
+    ///
+    /// The builder.
+    ///
+    /// # Panics
+    ///
+    /// * if the message is not an RPC request message
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UMessageBuilder, UPayloadFormat, UUri};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let method_to_invoke = UUri::try_from("//my-vehicle/4210/5/64AB")?;
+    /// let reply_to_address = UUri::try_from("//my-cloud/BA4C/1/0")?;
+    /// let message = UMessageBuilder::request(method_to_invoke, reply_to_address, 5000)
+    ///                     .with_idempotency_key("unlock-4711")
+    ///                     .build_with_payload("unlock", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)?;
+    /// assert_eq!(message.attributes.idempotency_key(), Some("unlock-4711".to_string()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_idempotency_key<T: Into<String>>(&mut self, key: T) -> &mut UMessageBuilder {
+        assert!(self.message_type == UMessageType::UMESSAGE_TYPE_REQUEST);
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Sets the point in time at which the event described by the message has occurred.
+    ///
+    /// The event time is distinct from the point in time at which the message is created, which is
+    /// contained in the message ID. See [`crate::uattributes::EVENT_TIME_FIELD_NUMBER`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `millis_since_epoch` - The number of milliseconds since UNIX epoch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UMessageBuilder, UUri};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let topic = UUri::try_from("//my-vehicle/4210/1/B24D")?;
+    /// let message = UMessageBuilder::publish(topic)
+    ///                    .with_event_time(1_706_520_652_000)
+    ///                    .build()?;
+    /// assert_eq!(message.attributes.event_time(), Some(1_706_520_652_000));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_event_time(&mut self, millis_since_epoch: u64) -> &mut UMessageBuilder {
+        self.event_time = Some(millis_since_epoch);
+        self
+    }
+
+    /// Sets the identifier of the session that the message belongs to.
+    ///
+    /// See [`crate::uattributes::SESSION_ID_FIELD_NUMBER`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UMessageBuilder, UUri};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let method_to_invoke = UUri::try_from("//my-vehicle/4210/5/64AB")?;
+    /// let reply_to_address = UUri::try_from("//my-cloud/BA4C/1/0")?;
+    /// let message = UMessageBuilder::request(method_to_invoke, reply_to_address, 5000)
+    ///                     .with_session_id("diag-session-1")
+    ///                     .build()?;
+    /// assert_eq!(message.attributes.session_id(), Some("diag-session-1".to_string()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_session_id<T: Into<String>>(&mut self, session_id: T) -> &mut UMessageBuilder {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Sets the ID of a prior message that the message relates to.
+    ///
+    /// See [`crate::uattributes::CORRELATION_ID_FIELD_NUMBER`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UMessageBuilder, UUID, UUri};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let topic = UUri::try_from("//my-vehicle/4210/1/B24D")?;
+    /// let alarm_id = UUID::build();
+    /// let message = UMessageBuilder::publish(topic)
+    ///                     .with_correlation_id(alarm_id.clone())
+    ///                     .build()?;
+    /// assert_eq!(message.attributes.correlation_id(), Some(alarm_id));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_correlation_id(&mut self, correlation_id: UUID) -> &mut UMessageBuilder {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Sets deployment specific rules that the message's addresses need to comply with.
+    ///
+    /// The policy is checked when the message is being built, in addition to the rules
+    /// defined by the uProtocol specification.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UMessageBuilder, UMessageType, UriPolicy, UUri};
+    ///
+    /// let topic = UUri::try_from("/4210/1/B24D").unwrap();
+    /// let result = UMessageBuilder::publish(topic)
+    ///     .with_uri_policy(UriPolicy::new().require_authority_for(UMessageType::UMESSAGE_TYPE_PUBLISH))
+    ///     .build();
+    /// assert!(result.is_err());
+    /// ```
+    pub fn with_uri_policy(&mut self, policy: UriPolicy) -> &mut UMessageBuilder {
+        self.uri_policy = Some(policy);
+        self
+    }
+
+    /// Sets the policy to use for checking the resource ID of the topic that a
+    /// *publish* message is published to.
+    ///
+    /// The default policy is [`TopicPolicy::Strict`]. Other policies are intended for
+    /// platform components only. The policy is ignored for other types of messages.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{TopicPolicy, UMessageBuilder, UUri};
+    ///
+    /// let topic = UUri::try_from("//my-vehicle/4210/1/100").unwrap();
+    /// assert!(UMessageBuilder::publish(topic.clone()).build().is_err());
+    /// assert!(UMessageBuilder::publish(topic)
+    ///     .with_topic_policy(TopicPolicy::Platform)
+    ///     .build()
+    ///     .is_ok());
+    /// ```
+    pub fn with_topic_policy(&mut self, policy: TopicPolicy) -> &mut UMessageBuilder {
+        self.topic_policy = policy;
+        self
+    }
+
+    // the publish validator always applies the strict topic policy
+    fn validate_attributes(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
+        if self.message_type != UMessageType::UMESSAGE_TYPE_PUBLISH
+            || self.topic_policy == TopicPolicy::Strict
+        {
+            return self.validator.validate(attributes);
+        }
+        PublishValidator
+            .validate_type(attributes)
+            .and_then(|_| PublishValidator.validate_id(attributes))
+            .and_then(|_| PublishValidator.validate_sink(attributes))
+            .and_then(|_| match attributes.source.as_ref() {
+                Some(topic) => self.topic_policy.verify_topic(topic).map_err(|e| {
+                    UAttributesError::validation_error(format!("Invalid source URI: {}", e))
+                }),
+                None => Err(UAttributesError::validation_error(
+                    "Attributes for a publish message must contain a source URI",
+                )),
+            })
+    }
+
     /// Sets the message's permission level.
     ///
     /// # Arguments
@@ -525,6 +798,9 @@ impl UMessageBuilder {
 
     /// Sets the identifier of the W3C Trace Context to convey in the message.
     ///
+    /// The identifier is validated when the message is being built. A [`TraceParent`](`crate::uattributes::TraceParent`)
+    /// can be used for creating valid identifiers.
+    ///
     /// # Arguments
     ///
     /// * `traceparent` - The identifier.
@@ -545,8 +821,15 @@ impl UMessageBuilder {
     ///                    .with_traceparent(traceparent)
     ///                    .build_with_payload("closed", UPayloadFormat::UPAYLOAD_FORMAT_TEXT)?;
     /// assert_eq!(message.attributes.traceparent, Some(traceparent.to_string()));
+    ///
+    /// // invalid identifiers are rejected
+    /// assert!(UMessageBuilder::publish(topic)
+    ///                    .with_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01")
+    ///                    .build()
+    ///                    .is_err());
     /// # Ok(())
     /// # }
+    /// ```
     pub fn with_traceparent<T: Into<String>>(&mut self, traceparent: T) -> &mut UMessageBuilder {
         self.traceparent = Some(traceparent.into());
         self
@@ -610,7 +893,7 @@ impl UMessageBuilder {
             .message_id
             .clone()
             .map_or_else(|| Some(UUID::build()), Some);
-        let attributes = UAttributes {
+        let mut attributes = UAttributes {
             commstatus: self.comm_status,
             id: message_id.into(),
             payload_format: self.payload_format.into(),
@@ -625,8 +908,25 @@ impl UMessageBuilder {
             type_: self.message_type.into(),
             ..Default::default()
         };
-        self.validator
-            .validate(&attributes)
+        if let Some(key) = self.idempotency_key.as_ref() {
+            attributes.set_idempotency_key(key.to_owned());
+        }
+        if let Some(event_time) = self.event_time {
+            attributes.set_event_time(event_time);
+        }
+        if let Some(session_id) = self.session_id.as_ref() {
+            attributes.set_session_id(session_id.to_owned());
+        }
+        if let Some(correlation_id) = self.correlation_id.as_ref() {
+            attributes.set_correlation_id(correlation_id);
+        }
+        self.validate_attributes(&attributes)
+            .and_then(|_| validate_traceparent(&attributes))
+            .and_then(|_| {
+                self.uri_policy
+                    .as_ref()
+                    .map_or(Ok(()), |policy| policy.validate(&attributes))
+            })
             .map_err(UMessageError::from)
             .map(|_| UMessage {
                 attributes: Some(attributes).into(),
@@ -856,6 +1156,32 @@ mod tests {
         }
     }
 
+    #[test]
+    #[should_panic]
+    fn test_with_priority_panics_for_low_rpc_priority() {
+        let method_to_invoke = UUri::try_from(METHOD_TO_INVOKE)
+            .expect("should have been able to create destination UUri");
+        let reply_to_address = UUri::try_from(REPLY_TO_ADDRESS)
+            .expect("should have been able to create reply-to UUri");
+        UMessageBuilder::request(method_to_invoke, reply_to_address, 5000)
+            .with_priority(UPriority::UPRIORITY_CS3);
+    }
+
+    #[test]
+    fn test_with_priority_upgrades_low_rpc_priority() {
+        let request_id = UUID::build();
+        let method_to_invoke = UUri::try_from(METHOD_TO_INVOKE)
+            .expect("should have been able to create destination UUri");
+        let reply_to_address = UUri::try_from(REPLY_TO_ADDRESS)
+            .expect("should have been able to create reply-to UUri");
+        let message = UMessageBuilder::response(reply_to_address, request_id, method_to_invoke)
+            .with_rpc_priority_policy(RpcPriorityPolicy::Upgrade)
+            .with_priority(UPriority::UPRIORITY_CS1)
+            .build()
+            .expect("should have been able to create message");
+        assert_eq!(message.attributes.priority, UPriority::UPRIORITY_CS4.into());
+    }
+
     #[test]
     fn test_build_supports_repeated_invocation() {
         let topic = UUri::try_from(TOPIC).expect("should have been able to create UUri");
@@ -960,6 +1286,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_notification_for_published_event() {
+        let event_id = UUID::build();
+        let topic = UUri::try_from(TOPIC).expect("should have been able to create UUri");
+        let event = UMessageBuilder::publish(topic)
+            .with_message_id(event_id.clone())
+            .build()
+            .expect("should have been able to create message");
+        let origin = UUri::try_from("//my-cloud/9CB3/1/8001")
+            .expect("should have been able to create origin UUri");
+        let message = UMessageBuilder::notification_for(origin.clone(), &event)
+            .build()
+            .expect("should have been able to create message");
+        assert_eq!(message.attributes.correlation_id(), Some(event_id));
+        assert_eq!(
+            message.attributes.sink,
+            Some(UUri::try_from("//my-vehicle/4210/1/0").unwrap()).into()
+        );
+        assert_eq!(message.attributes.source, Some(origin).into());
+        assert!(message.attributes.reqid.is_none());
+    }
+
     #[test]
     fn test_build_retains_all_response_attributes() {
         let message_id = UUID::build();
@@ -0,0 +1,219 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::ops::RangeInclusive;
+
+use crate::{UAttributes, UAttributesError, UMessageType, UUri};
+
+fn has_wildcard(uri: &UUri) -> bool {
+    uri.has_wildcard_authority()
+        || uri.has_wildcard_entity_type()
+        || uri.has_wildcard_entity_instance()
+        || uri.has_wildcard_version()
+        || uri.has_wildcard_resource_id()
+}
+
+/// Deployment specific rules for the addresses contained in messages.
+///
+/// The rules are applied in addition to the rules defined by the uProtocol specification
+/// when a message is being [built](`crate::UMessageBuilder::build`).
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{UMessageBuilder, UMessageType, UriPolicy, UUri};
+///
+/// let policy = UriPolicy::new()
+///     .forbid_wildcard_source()
+///     .require_authority_for(UMessageType::UMESSAGE_TYPE_PUBLISH)
+///     .restrict_resource_ids(UMessageType::UMESSAGE_TYPE_PUBLISH, 0x8000..=0x8FFF);
+///
+/// let topic = UUri::try_from("//my-vehicle/4210/1/B24D").unwrap();
+/// assert!(UMessageBuilder::publish(topic)
+///     .with_uri_policy(policy.clone())
+///     .build()
+///     .is_err());
+///
+/// let topic = UUri::try_from("//my-vehicle/4210/1/8001").unwrap();
+/// assert!(UMessageBuilder::publish(topic)
+///     .with_uri_policy(policy)
+///     .build()
+///     .is_ok());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UriPolicy {
+    forbid_wildcard_source: bool,
+    authority_required_for: Vec<UMessageType>,
+    resource_id_ranges: Vec<(UMessageType, RangeInclusive<u16>)>,
+}
+
+impl UriPolicy {
+    /// Creates a policy that does not impose any additional rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects messages whose source address contains a wildcard in any of its components.
+    pub fn forbid_wildcard_source(mut self) -> Self {
+        self.forbid_wildcard_source = true;
+        self
+    }
+
+    /// Rejects messages of a given type whose source or sink address does not contain an authority.
+    ///
+    /// Wildcard authorities are not accepted either.
+    pub fn require_authority_for(mut self, message_type: UMessageType) -> Self {
+        if !self.authority_required_for.contains(&message_type) {
+            self.authority_required_for.push(message_type);
+        }
+        self
+    }
+
+    /// Rejects messages of a given type whose addressed resource has an ID outside of a range.
+    ///
+    /// The addressed resource is the method being invoked for RPC Request messages and
+    /// the resource contained in the source address for all other message types.
+    /// Restricting the same message type multiple times replaces the previous range.
+    pub fn restrict_resource_ids(
+        mut self,
+        message_type: UMessageType,
+        range: RangeInclusive<u16>,
+    ) -> Self {
+        self.resource_id_ranges
+            .retain(|(restricted_type, _range)| *restricted_type != message_type);
+        self.resource_id_ranges.push((message_type, range));
+        self
+    }
+
+    /// Checks if a set of attributes complies with this policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first rule that the attributes violate.
+    pub fn validate(&self, attributes: &UAttributes) -> Result<(), UAttributesError> {
+        let message_type = attributes.type_.enum_value_or_default();
+        let source = attributes.source.as_ref();
+        let sink = attributes.sink.as_ref();
+
+        if self.forbid_wildcard_source && source.is_some_and(has_wildcard) {
+            return Err(UAttributesError::validation_error(
+                "Source address must not contain wildcards",
+            ));
+        }
+
+        if self.authority_required_for.contains(&message_type) {
+            for uri in [source, sink].into_iter().flatten() {
+                if uri.has_empty_authority() || uri.has_wildcard_authority() {
+                    return Err(UAttributesError::validation_error(format!(
+                        "Address [{}] must contain an authority",
+                        uri.to_uri(true)
+                    )));
+                }
+            }
+        }
+
+        let addressed_resource = if message_type == UMessageType::UMESSAGE_TYPE_REQUEST {
+            sink
+        } else {
+            source
+        };
+        if let (Some((_type, range)), Some(uri)) = (
+            self.resource_id_ranges
+                .iter()
+                .find(|(restricted_type, _range)| *restricted_type == message_type),
+            addressed_resource,
+        ) {
+            let resource_id = uri.resource_id;
+            if !u16::try_from(resource_id).is_ok_and(|id| range.contains(&id)) {
+                return Err(UAttributesError::validation_error(format!(
+                    "Resource ID [{:#06X}] must be in range [{:#06X}, {:#06X}]",
+                    resource_id,
+                    range.start(),
+                    range.end()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::UMessageBuilder;
+
+    fn attributes_of(builder: &mut UMessageBuilder) -> UAttributes {
+        builder.build().unwrap().attributes.unwrap()
+    }
+
+    #[test_case(
+        UriPolicy::new(),
+        UMessageBuilder::publish(UUri::try_from("/D45/1/A001").unwrap()),
+        true;
+        "empty policy")]
+    #[test_case(
+        UriPolicy::new().require_authority_for(UMessageType::UMESSAGE_TYPE_PUBLISH),
+        UMessageBuilder::publish(UUri::try_from("/D45/1/A001").unwrap()),
+        false;
+        "publish without authority")]
+    #[test_case(
+        UriPolicy::new().require_authority_for(UMessageType::UMESSAGE_TYPE_PUBLISH),
+        UMessageBuilder::notification(
+            UUri::try_from("/D45/1/A001").unwrap(),
+            UUri::try_from("/D46/1/0").unwrap()),
+        true;
+        "notification without authority")]
+    #[test_case(
+        UriPolicy::new().require_authority_for(UMessageType::UMESSAGE_TYPE_REQUEST),
+        UMessageBuilder::request(
+            UUri::try_from("/D45/1/1").unwrap(),
+            UUri::try_from("//vehicle/D46/1/0").unwrap(),
+            5_000),
+        false;
+        "request to method without authority")]
+    #[test_case(
+        UriPolicy::new().restrict_resource_ids(UMessageType::UMESSAGE_TYPE_REQUEST, 0x0001..=0x00FF),
+        UMessageBuilder::request(
+            UUri::try_from("//vehicle/D45/1/100").unwrap(),
+            UUri::try_from("//vehicle/D46/1/0").unwrap(),
+            5_000),
+        false;
+        "request to method outside of range")]
+    #[test_case(
+        UriPolicy::new().restrict_resource_ids(UMessageType::UMESSAGE_TYPE_REQUEST, 0x0001..=0x00FF),
+        UMessageBuilder::request(
+            UUri::try_from("//vehicle/D45/1/FF").unwrap(),
+            UUri::try_from("//vehicle/D46/1/0").unwrap(),
+            5_000),
+        true;
+        "request to method inside of range")]
+    fn test_validate(policy: UriPolicy, mut builder: UMessageBuilder, expected_valid: bool) {
+        let attributes = attributes_of(&mut builder);
+        assert_eq!(policy.validate(&attributes).is_ok(), expected_valid);
+    }
+
+    #[test]
+    fn test_validate_rejects_wildcard_source() {
+        let policy = UriPolicy::new().forbid_wildcard_source();
+        let attributes = UAttributes {
+            type_: UMessageType::UMESSAGE_TYPE_PUBLISH.into(),
+            source: Some(UUri::try_from("//*/D45/1/A001").unwrap()).into(),
+            ..Default::default()
+        };
+        assert!(policy
+            .validate(&attributes)
+            .is_err_and(|e| matches!(e, UAttributesError::ValidationError(_msg))));
+    }
+}
@@ -21,14 +21,28 @@ use uriparse::{Authority, URIReference};
 
 pub use crate::up_core_api::uri::UUri;
 
-pub(crate) const WILDCARD_AUTHORITY: &str = "*";
-pub(crate) const WILDCARD_ENTITY_INSTANCE: u32 = 0xFFFF_0000;
-pub(crate) const WILDCARD_ENTITY_TYPE: u32 = 0x0000_FFFF;
-pub(crate) const WILDCARD_ENTITY_VERSION: u32 = 0x0000_00FF;
-pub(crate) const WILDCARD_RESOURCE_ID: u32 = 0x0000_FFFF;
-
-pub(crate) const RESOURCE_ID_RESPONSE: u32 = 0;
-pub(crate) const RESOURCE_ID_MIN_EVENT: u32 = 0x8000;
+mod builder;
+mod ids;
+mod index;
+mod rewriter;
+mod topic_policy;
+mod urilint;
+pub mod wellknown;
+pub use builder::UUriBuilder;
+pub use ids::{AuthorityName, ResourceId, UeId};
+pub use index::UUriIndex;
+pub use rewriter::{UriRewriteRule, UriRewriter};
+pub use topic_policy::TopicPolicy;
+pub use urilint::{UriLint, UriLintFinding, UriLintKind};
+
+pub(crate) const WILDCARD_AUTHORITY: &str = wellknown::WILDCARD_AUTHORITY;
+pub(crate) const WILDCARD_ENTITY_INSTANCE: u32 = wellknown::WILDCARD_ENTITY_INSTANCE_ID;
+pub(crate) const WILDCARD_ENTITY_TYPE: u32 = wellknown::WILDCARD_ENTITY_TYPE_ID;
+pub(crate) const WILDCARD_ENTITY_VERSION: u32 = wellknown::WILDCARD_VERSION as u32;
+pub(crate) const WILDCARD_RESOURCE_ID: u32 = wellknown::WILDCARD_RESOURCE_ID as u32;
+
+pub(crate) const RESOURCE_ID_RESPONSE: u32 = wellknown::RESOURCE_ID_RESPONSE as u32;
+pub(crate) const RESOURCE_ID_MIN_EVENT: u32 = *wellknown::TOPIC_IDS.start() as u32;
 
 #[derive(Debug)]
 pub enum UUriError {
@@ -63,6 +77,18 @@ impl std::fmt::Display for UUriError {
 
 impl std::error::Error for UUriError {}
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for UUriError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Self::SerializationError(e) => {
+                defmt::write!(f, "Serialization error: {=str}", e.as_str())
+            }
+            Self::ValidationError(e) => defmt::write!(f, "Validation error: {=str}", e.as_str()),
+        }
+    }
+}
+
 // [impl->req~uri-serialization~1]
 impl From<&UUri> for String {
     /// Serializes a uProtocol URI to a URI string.
@@ -106,6 +132,8 @@ impl FromStr for UUri {
     /// are converted to lowercase, no bytes that are in the unreserved character set remain percent-encoded,
     /// and all alphabetical characters in percent-encodings are converted to uppercase.
     ///
+    /// Use [`UUri::from_str_strict`] for also rejecting URIs whose path does not use the canonical form.
+    ///
     /// # Arguments
     ///
     /// * `uri` - The `String` to be converted into a `UUri`.
@@ -138,74 +166,7 @@ impl FromStr for UUri {
     // [impl->dsn~uri-path-mapping~1]
     // [impl->req~uri-serialization~1]
     fn from_str(uri: &str) -> Result<Self, Self::Err> {
-        if uri.is_empty() {
-            return Err(UUriError::serialization_error("URI is empty"));
-        }
-        let parsed_uri = URIReference::try_from(uri)
-            .map_err(|e| UUriError::serialization_error(e.to_string()))?;
-
-        if let Some(scheme) = parsed_uri.scheme() {
-            if scheme.ne("up") {
-                return Err(UUriError::serialization_error(
-                    "uProtocol URI must use 'up' scheme",
-                ));
-            }
-        }
-        if parsed_uri.has_query() {
-            return Err(UUriError::serialization_error(
-                "uProtocol URI must not contain query",
-            ));
-        }
-        if parsed_uri.has_fragment() {
-            return Err(UUriError::serialization_error(
-                "uProtocol URI must not contain fragment",
-            ));
-        }
-        let authority_name = parsed_uri
-            .authority()
-            .map_or(Ok(String::default()), Self::verify_parsed_authority)?;
-
-        let path_segments = parsed_uri.path().segments();
-        if path_segments.len() != 3 {
-            return Err(UUriError::serialization_error(
-                "uProtocol URI must contain entity ID, entity version and resource ID",
-            ));
-        }
-        let entity = path_segments[0].as_str();
-        if entity.is_empty() {
-            return Err(UUriError::serialization_error(
-                "URI must contain non-empty entity ID",
-            ));
-        }
-        let ue_id = u32::from_str_radix(entity, 16).map_err(|e| {
-            UUriError::serialization_error(format!("Cannot parse entity ID: {}", e))
-        })?;
-        let version = path_segments[1].as_str();
-        if version.is_empty() {
-            return Err(UUriError::serialization_error(
-                "URI must contain non-empty entity version",
-            ));
-        }
-        let ue_version_major = u8::from_str_radix(version, 16).map_err(|e| {
-            UUriError::serialization_error(format!("Cannot parse entity version: {}", e))
-        })?;
-        let resource = path_segments[2].as_str();
-        if resource.is_empty() {
-            return Err(UUriError::serialization_error(
-                "URI must contain non-empty resource ID",
-            ));
-        }
-        let resource_id = u16::from_str_radix(resource, 16).map_err(|e| {
-            UUriError::serialization_error(format!("Cannot parse resource ID: {}", e))
-        })?;
-
-        Ok(UUri {
-            authority_name,
-            ue_id,
-            ue_version_major: ue_version_major as u32,
-            resource_id: resource_id as u32,
-            ..Default::default()
-        })
+        UUri::parse(uri, false)
     }
 }
 
@@ -279,6 +240,60 @@ impl TryFrom<&str> for UUri {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for UUri {
+    /// Formats a uProtocol URI like [`UUri::to_uri`] without including the uProtocol scheme.
+    fn format(&self, f: defmt::Formatter) {
+        if !self.authority_name.is_empty() {
+            defmt::write!(f, "//{=str}", self.authority_name.as_str());
+        }
+        defmt::write!(
+            f,
+            "/{=u32:X}/{=u32:X}/{=u32:X}",
+            self.ue_id,
+            self.ue_version_major,
+            self.resource_id
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for UUri {
+    /// Serializes a uProtocol URI to its URI string representation.
+    ///
+    /// The string is the output of [`UUri::to_uri`] without including the uProtocol scheme.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_uri(false))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for UUri {
+    /// Deserializes a uProtocol URI from its URI string representation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not a valid uProtocol URI.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UUri;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Config {
+    ///     topic: UUri,
+    /// }
+    ///
+    /// let config: Config = serde_json::from_str(r#"{ "topic": "//vehicle/A100/1/8001" }"#).unwrap();
+    /// assert_eq!(config.topic.resource_id, 0x8001);
+    /// ```
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let uri = String::deserialize(deserializer)?;
+        UUri::from_str(&uri).map_err(serde::de::Error::custom)
+    }
+}
+
 impl Hash for UUri {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.authority_name.hash(state);
@@ -337,6 +352,112 @@ impl UUri {
         output
     }
 
+    /// Attempts to parse a `String` into a `UUri`, rejecting URIs that do not use the canonical form.
+    ///
+    /// In addition to the rules applied by [`UUri::from_str`], the URI's path must be absolute and all
+    /// of its segments must be encoded as upper case hex without leading zeros. This helps implementers
+    /// of uEntities to verify that they comply with the uProtocol specification.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`UUriError::SerializationError`] if the string is not a valid uProtocol URI
+    /// in canonical form.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use up_rust::UUri;
+    ///
+    /// assert!(UUri::from_str_strict("//vin/800A/2/1A50").is_ok());
+    /// assert!(UUri::from_str("//vin/800a/02/1a50").is_ok());
+    /// assert!(UUri::from_str_strict("//vin/800a/02/1a50").is_err());
+    /// ```
+    pub fn from_str_strict(uri: &str) -> Result<UUri, UUriError> {
+        UUri::parse(uri, true)
+    }
+
+    fn parse(uri: &str, strict: bool) -> Result<UUri, UUriError> {
+        if uri.is_empty() {
+            return Err(UUriError::serialization_error("URI is empty"));
+        }
+        let parsed_uri = URIReference::try_from(uri)
+            .map_err(|e| UUriError::serialization_error(e.to_string()))?;
+
+        if let Some(scheme) = parsed_uri.scheme() {
+            if scheme.ne("up") {
+                return Err(UUriError::serialization_error(
+                    "uProtocol URI must use 'up' scheme",
+                ));
+            }
+        }
+        if parsed_uri.has_query() {
+            return Err(UUriError::serialization_error(
+                "uProtocol URI must not contain query",
+            ));
+        }
+        if parsed_uri.has_fragment() {
+            return Err(UUriError::serialization_error(
+                "uProtocol URI must not contain fragment",
+            ));
+        }
+        let authority_name = parsed_uri
+            .authority()
+            .map_or(Ok(String::default()), Self::verify_parsed_authority)?;
+
+        let path_segments = parsed_uri.path().segments();
+        if path_segments.len() != 3 {
+            return Err(UUriError::serialization_error(
+                "uProtocol URI must contain entity ID, entity version and resource ID",
+            ));
+        }
+        let entity = path_segments[0].as_str();
+        if entity.is_empty() {
+            return Err(UUriError::serialization_error(
+                "URI must contain non-empty entity ID",
+            ));
+        }
+        let ue_id = u32::from_str_radix(entity, 16).map_err(|e| {
+            UUriError::serialization_error(format!("Cannot parse entity ID: {}", e))
+        })?;
+        let version = path_segments[1].as_str();
+        if version.is_empty() {
+            return Err(UUriError::serialization_error(
+                "URI must contain non-empty entity version",
+            ));
+        }
+        let ue_version_major = u8::from_str_radix(version, 16).map_err(|e| {
+            UUriError::serialization_error(format!("Cannot parse entity version: {}", e))
+        })?;
+        let resource = path_segments[2].as_str();
+        if resource.is_empty() {
+            return Err(UUriError::serialization_error(
+                "URI must contain non-empty resource ID",
+            ));
+        }
+        let resource_id = u16::from_str_radix(resource, 16).map_err(|e| {
+            UUriError::serialization_error(format!("Cannot parse resource ID: {}", e))
+        })?;
+        if strict {
+            Self::verify_canonical_path(
+                parsed_uri.path().is_absolute(),
+                [
+                    (entity, ue_id),
+                    (version, ue_version_major as u32),
+                    (resource, resource_id as u32),
+                ],
+            )?;
+        }
+
+        Ok(UUri {
+            authority_name,
+            ue_id,
+            ue_version_major: ue_version_major as u32,
+            resource_id: resource_id as u32,
+            ..Default::default()
+        })
+    }
+
     /// Creates a new UUri from its parts.
     ///
     /// # Errors
@@ -368,6 +489,34 @@ impl UUri {
         })
     }
 
+    /// Creates a new UUri from its (already validated) parts.
+    ///
+    /// In contrast to [`Self::try_from_parts`], the numeric parts cannot be accidentally swapped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{AuthorityName, ResourceId, UUri, UeId};
+    ///
+    /// let authority = AuthorityName::try_from("vin").unwrap();
+    /// let uri = UUri::from_typed_parts(&authority, UeId::new(0x5a6b), 0x01, ResourceId::method(0x0001));
+    /// assert_eq!(uri, UUri::try_from_parts("vin", 0x0000_5a6b, 0x01, 0x0001).unwrap());
+    /// ```
+    pub fn from_typed_parts(
+        authority: &AuthorityName,
+        entity_id: UeId,
+        entity_version: u8,
+        resource_id: ResourceId,
+    ) -> Self {
+        UUri {
+            authority_name: authority.to_string(),
+            ue_id: entity_id.value(),
+            ue_version_major: entity_version as u32,
+            resource_id: resource_id.value() as u32,
+            ..Default::default()
+        }
+    }
+
     /// Gets a URI that consists of wildcards only and therefore matches any URI.
     pub fn any() -> Self {
         Self::any_with_resource_id(WILDCARD_RESOURCE_ID)
@@ -485,6 +634,28 @@ impl UUri {
         }
     }
 
+    /// Verifies that a URI's path uses the canonical form, i.e. that it is absolute and that
+    /// all segments are encoded as upper case hex without leading zeros.
+    fn verify_canonical_path(
+        is_absolute: bool,
+        segments: [(&str, u32); 3],
+    ) -> Result<(), UUriError> {
+        if !is_absolute {
+            return Err(UUriError::serialization_error(
+                "uProtocol URI must contain absolute path",
+            ));
+        }
+        for (segment, value) in segments {
+            if segment != format!("{:X}", value) {
+                return Err(UUriError::serialization_error(format!(
+                    "path segment [{}] must be encoded as upper case hex without leading zeros",
+                    segment
+                )));
+            }
+        }
+        Ok(())
+    }
+
     fn verify_major_version(major_version: u32) -> Result<u8, UUriError> {
         u8::try_from(major_version).map_err(|_e| {
             UUriError::ValidationError(
@@ -907,14 +1078,7 @@ impl UUri {
     /// assert!(uri.verify_event().is_err());
     /// ```
     pub fn verify_event(&self) -> Result<(), UUriError> {
-        if !self.is_event() {
-            Err(UUriError::validation_error(format!(
-                "Resource ID must be >= {:#X}",
-                RESOURCE_ID_MIN_EVENT
-            )))
-        } else {
-            self.verify_no_wildcards()
-        }
+        TopicPolicy::Strict.verify_topic(self)
     }
 
     fn matches_authority(&self, candidate: &UUri) -> bool {
@@ -1045,6 +1209,22 @@ mod tests {
         assert!(parsing_result.is_err());
     }
 
+    #[test_case("/8000/01/2"; "for version with leading zero")]
+    #[test_case("/8000/1/a13"; "for lower case resource ID")]
+    #[test_case("8000/1/2"; "for relative path")]
+    fn test_from_str_strict_fails_for_non_canonical_form(string: &str) {
+        assert!(UUri::from_str(string).is_ok());
+        assert!(UUri::from_str_strict(string).is_err());
+    }
+
+    #[test]
+    fn test_from_str_strict_succeeds_for_canonical_form() {
+        assert_eq!(
+            UUri::from_str_strict("//vin/800A/2/1A50").unwrap(),
+            UUri::from_str("//vin/800a/02/1a50").unwrap()
+        );
+    }
+
     // [utest->req~uri-serialization~1]
     // [utest->dsn~uri-scheme~1]
     // [utest->dsn~uri-host-only~2]
@@ -1197,4 +1377,21 @@ mod tests {
             UUri::try_from(candidate).expect("should have been able to create candidate UUri");
         assert!(!pattern_uri.matches(&candidate_uri));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_uses_uri_string_representation() {
+        let uri = UUri::try_from("//vehicle/A100/1/8001").unwrap();
+
+        let json = serde_json::to_value(&uri).unwrap();
+        assert_eq!(json, serde_json::json!("//vehicle/A100/1/8001"));
+        assert_eq!(serde_json::from_value::<UUri>(json).unwrap(), uri);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test_case(serde_json::json!("//vehicle/A100/1/8001/extra"); "for invalid URI string")]
+    #[test_case(serde_json::json!({"authority_name": "vehicle"}); "for field struct")]
+    fn test_deserialize_fails(json: serde_json::Value) {
+        assert!(serde_json::from_value::<UUri>(json).is_err());
+    }
 }
@@ -0,0 +1,233 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use super::WILDCARD_AUTHORITY;
+use crate::{UUri, UUriError};
+
+/// A builder for creating [`UUri`]s from their parts.
+///
+/// In contrast to creating a `UUri` from a struct literal, the builder checks that all parts
+/// have been set and that their values are within the ranges defined by the uProtocol
+/// specification when the URI is being built.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{UUri, UUriBuilder};
+///
+/// let uri = UUriBuilder::new()
+///     .authority("my-vehicle")
+///     .entity_type(0xA100)
+///     .entity_instance(0x0002)
+///     .version(0x01)
+///     .resource_id(0x8001)
+///     .build()
+///     .unwrap();
+/// assert_eq!(uri, UUri::try_from("//my-vehicle/2A100/1/8001").unwrap());
+///
+/// // the resource ID is not a 16 bit integer
+/// assert!(UUriBuilder::new()
+///     .entity_id(0xA100)
+///     .version(0x01)
+///     .resource_id(0x1_8001)
+///     .build()
+///     .is_err());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UUriBuilder {
+    authority: String,
+    entity_id: Option<u32>,
+    entity_type: Option<u32>,
+    entity_instance: Option<u32>,
+    version: Option<u32>,
+    resource_id: Option<u32>,
+}
+
+impl UUriBuilder {
+    /// Creates a new builder for a URI with an empty (local) authority.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the authority name.
+    pub fn authority<T: Into<String>>(mut self, authority: T) -> Self {
+        self.authority = authority.into();
+        self
+    }
+
+    /// Sets the authority name to the wildcard.
+    pub fn any_authority(self) -> Self {
+        self.authority(WILDCARD_AUTHORITY)
+    }
+
+    /// Sets the uEntity identifier, consisting of the entity instance ID in the most significant
+    /// 16 bits and the entity type ID in the least significant 16 bits.
+    ///
+    /// Use either this method or [`Self::entity_type`] and [`Self::entity_instance`].
+    pub fn entity_id(mut self, entity_id: u32) -> Self {
+        self.entity_id = Some(entity_id);
+        self
+    }
+
+    /// Sets the uEntity type identifier, which must be a 16 bit unsigned integer.
+    pub fn entity_type(mut self, entity_type: u32) -> Self {
+        self.entity_type = Some(entity_type);
+        self
+    }
+
+    /// Sets the uEntity instance identifier, which must be a 16 bit unsigned integer.
+    ///
+    /// The default instance is 0.
+    pub fn entity_instance(mut self, entity_instance: u32) -> Self {
+        self.entity_instance = Some(entity_instance);
+        self
+    }
+
+    /// Sets the uEntity major version, which must be an 8 bit unsigned integer.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Sets the resource identifier, which must be a 16 bit unsigned integer.
+    pub fn resource_id(mut self, resource_id: u32) -> Self {
+        self.resource_id = Some(resource_id);
+        self
+    }
+
+    fn check_range(name: &str, value: u32, max: u32, bits: u8) -> Result<u32, UUriError> {
+        if value <= max {
+            Ok(value)
+        } else {
+            Err(UUriError::validation_error(format!(
+                "{name} [{value:#X}] must be a {bits} bit unsigned integer"
+            )))
+        }
+    }
+
+    fn build_uri(&self) -> Result<UUri, UUriError> {
+        let authority_name = if self.authority.is_empty() || self.authority == WILDCARD_AUTHORITY {
+            self.authority.clone()
+        } else {
+            UUri::verify_authority(&self.authority)?
+        };
+        let ue_id = match (self.entity_id, self.entity_type) {
+            (Some(_), Some(_)) => {
+                return Err(UUriError::validation_error(
+                    "entity ID and entity type ID must not be set both",
+                ));
+            }
+            (Some(_), None) if self.entity_instance.is_some() => {
+                return Err(UUriError::validation_error(
+                    "entity ID and entity instance ID must not be set both",
+                ));
+            }
+            (Some(entity_id), None) => entity_id,
+            (None, Some(entity_type)) => {
+                let entity_type = Self::check_range("entity type ID", entity_type, 0xFFFF, 16)?;
+                let entity_instance = Self::check_range(
+                    "entity instance ID",
+                    self.entity_instance.unwrap_or_default(),
+                    0xFFFF,
+                    16,
+                )?;
+                (entity_instance << 16) | entity_type
+            }
+            (None, None) => {
+                return Err(UUriError::validation_error(
+                    "entity ID or entity type ID must be set",
+                ));
+            }
+        };
+        let version = self
+            .version
+            .ok_or_else(|| UUriError::validation_error("entity version must be set"))?;
+        let resource_id = self
+            .resource_id
+            .ok_or_else(|| UUriError::validation_error("resource ID must be set"))?;
+        Ok(UUri {
+            authority_name,
+            ue_id,
+            ue_version_major: Self::check_range("entity version", version, 0xFF, 8)?,
+            resource_id: Self::check_range("resource ID", resource_id, 0xFFFF, 16)?,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a URI that refers to a specific resource.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`UUriError::ValidationError`] if any of the parts has not been set, is out of
+    /// range or is set to its wildcard value.
+    pub fn build(&self) -> Result<UUri, UUriError> {
+        let uri = self.build_uri()?;
+        uri.verify_no_wildcards()?;
+        Ok(uri)
+    }
+
+    /// Creates a URI pattern, which may contain wildcards, e.g. for registering a listener.
+    ///
+    /// Use the constants defined in [`wellknown`](crate::wellknown), e.g.
+    /// [`WILDCARD_RESOURCE_ID`](crate::wellknown::WILDCARD_RESOURCE_ID), for setting the numeric
+    /// parts to their wildcard values.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`UUriError::ValidationError`] if any of the parts has not been set or is out of
+    /// range.
+    pub fn build_pattern(&self) -> Result<UUri, UUriError> {
+        self.build_uri()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wellknown;
+    use test_case::test_case;
+
+    #[test]
+    fn test_build_pattern_succeeds_for_wildcards() {
+        let builder = UUriBuilder::new()
+            .any_authority()
+            .entity_type(wellknown::WILDCARD_ENTITY_TYPE_ID)
+            .entity_instance(0x0001)
+            .version(wellknown::WILDCARD_VERSION as u32)
+            .resource_id(wellknown::WILDCARD_RESOURCE_ID as u32);
+
+        assert!(builder
+            .build()
+            .is_err_and(|e| e.to_string().contains("wildcard")));
+        assert_eq!(
+            builder.build_pattern().unwrap(),
+            UUri::try_from("//*/1FFFF/FF/FFFF").unwrap()
+        );
+    }
+
+    #[test_case(UUriBuilder::new().version(1).resource_id(1); "for missing entity ID")]
+    #[test_case(UUriBuilder::new().entity_id(1).resource_id(1); "for missing version")]
+    #[test_case(UUriBuilder::new().entity_id(1).version(1); "for missing resource ID")]
+    #[test_case(UUriBuilder::new().entity_id(1).entity_type(1).version(1).resource_id(1); "for entity ID and type ID")]
+    #[test_case(UUriBuilder::new().entity_id(1).entity_instance(1).version(1).resource_id(1); "for entity ID and instance ID")]
+    #[test_case(UUriBuilder::new().entity_type(0x1_0000).version(1).resource_id(1); "for entity type ID out of range")]
+    #[test_case(UUriBuilder::new().entity_type(1).entity_instance(0x1_0000).version(1).resource_id(1); "for entity instance ID out of range")]
+    #[test_case(UUriBuilder::new().entity_id(1).version(0x100).resource_id(1); "for version out of range")]
+    #[test_case(UUriBuilder::new().entity_id(1).version(1).resource_id(0x1_0000); "for resource ID out of range")]
+    #[test_case(UUriBuilder::new().authority("vehicle:1234").entity_id(1).version(1).resource_id(1); "for authority with port")]
+    fn test_build_pattern_fails(builder: UUriBuilder) {
+        assert!(builder
+            .build_pattern()
+            .is_err_and(|e| matches!(e, UUriError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,275 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::fmt::Display;
+
+use super::wellknown;
+use crate::{UUri, UUriError};
+
+/// The identifier of a resource (method or topic) of a uEntity.
+///
+/// Using this type instead of a plain `u16` prevents resource identifiers from being
+/// confused with other numeric parts of a [`UUri`], e.g. when calling [`UUri::from_typed_parts`].
+///
+/// # Examples
+///
+/// Resource identifiers can be defined as constants, which are validated at compile time:
+///
+/// ```rust
+/// use up_rust::ResourceId;
+///
+/// const DOOR_STATUS: ResourceId = ResourceId::topic(0x8001);
+/// const OPEN_DOOR: ResourceId = ResourceId::method(0x0001);
+///
+/// assert!(DOOR_STATUS.is_topic());
+/// assert!(OPEN_DOOR.is_rpc_method());
+/// assert!(ResourceId::try_from(0x1_0000_u32).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceId(u16);
+
+impl ResourceId {
+    /// Creates a resource identifier.
+    pub const fn new(id: u16) -> Self {
+        ResourceId(id)
+    }
+
+    /// Creates the identifier of a topic.
+    ///
+    /// # Panics
+    ///
+    /// if the given identifier is not in the [range of topic identifiers](`wellknown::TOPIC_IDS`).
+    /// If used for defining a constant, this results in a compilation error.
+    pub const fn topic(id: u16) -> Self {
+        assert!(wellknown::is_topic_id(id), "not a topic ID");
+        ResourceId(id)
+    }
+
+    /// Creates the identifier of an RPC method.
+    ///
+    /// # Panics
+    ///
+    /// if the given identifier is not in the [range of method identifiers](`wellknown::RPC_METHOD_IDS`).
+    /// If used for defining a constant, this results in a compilation error.
+    pub const fn method(id: u16) -> Self {
+        assert!(wellknown::is_rpc_method_id(id), "not an RPC method ID");
+        ResourceId(id)
+    }
+
+    /// Gets the numeric value of this identifier.
+    pub const fn value(&self) -> u16 {
+        self.0
+    }
+
+    /// Checks if this identifier refers to a topic.
+    pub const fn is_topic(&self) -> bool {
+        wellknown::is_topic_id(self.0)
+    }
+
+    /// Checks if this identifier refers to an RPC method.
+    pub const fn is_rpc_method(&self) -> bool {
+        wellknown::is_rpc_method_id(self.0)
+    }
+}
+
+impl From<u16> for ResourceId {
+    fn from(value: u16) -> Self {
+        ResourceId(value)
+    }
+}
+
+impl TryFrom<u32> for ResourceId {
+    type Error = UUriError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        u16::try_from(value)
+            .map(ResourceId)
+            .map_err(|_e| UUriError::validation_error("Resource ID must not exceed 0xFFFF"))
+    }
+}
+
+impl From<ResourceId> for u16 {
+    fn from(value: ResourceId) -> Self {
+        value.0
+    }
+}
+
+impl From<ResourceId> for u32 {
+    fn from(value: ResourceId) -> Self {
+        value.0 as u32
+    }
+}
+
+impl Display for ResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:X}", self.0)
+    }
+}
+
+/// The identifier of a uEntity, consisting of the entity's type and instance identifiers.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::UeId;
+///
+/// const HVAC: UeId = UeId::from_parts(0x0001, 0xA100);
+///
+/// assert_eq!(HVAC.value(), 0x0001_A100);
+/// assert_eq!(HVAC.type_id(), 0xA100);
+/// assert_eq!(HVAC.instance_id(), 0x0001);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UeId(u32);
+
+impl UeId {
+    /// Creates a uEntity identifier.
+    pub const fn new(id: u32) -> Self {
+        UeId(id)
+    }
+
+    /// Creates a uEntity identifier from its instance and type identifiers.
+    pub const fn from_parts(instance_id: u16, type_id: u16) -> Self {
+        UeId(((instance_id as u32) << 16) | type_id as u32)
+    }
+
+    /// Gets the numeric value of this identifier.
+    pub const fn value(&self) -> u32 {
+        self.0
+    }
+
+    /// Gets the uEntity's type identifier.
+    pub const fn type_id(&self) -> u16 {
+        (self.0 & wellknown::WILDCARD_ENTITY_TYPE_ID) as u16
+    }
+
+    /// Gets the uEntity's instance identifier.
+    pub const fn instance_id(&self) -> u16 {
+        ((self.0 & wellknown::WILDCARD_ENTITY_INSTANCE_ID) >> 16) as u16
+    }
+}
+
+impl From<u32> for UeId {
+    fn from(value: u32) -> Self {
+        UeId(value)
+    }
+}
+
+impl From<UeId> for u32 {
+    fn from(value: UeId) -> Self {
+        value.0
+    }
+}
+
+impl Display for UeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:X}", self.0)
+    }
+}
+
+/// The name of a uProtocol authority, i.e. the device or vehicle that a uEntity runs on.
+///
+/// Instances can only be created from names that comply with the UUri specification.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::AuthorityName;
+///
+/// assert!(AuthorityName::try_from("my-vehicle").is_ok());
+/// assert!(AuthorityName::try_from("my-vehicle:1234").is_err());
+/// assert!(AuthorityName::local().is_local());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AuthorityName(String);
+
+impl AuthorityName {
+    /// Gets the (empty) name representing the local authority.
+    pub const fn local() -> Self {
+        AuthorityName(String::new())
+    }
+
+    /// Checks if this is the (empty) name representing the local authority.
+    pub fn is_local(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Gets this name as a string slice.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl TryFrom<&str> for AuthorityName {
+    type Error = UUriError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        UUri::verify_authority(value).map(AuthorityName)
+    }
+}
+
+impl TryFrom<String> for AuthorityName {
+    type Error = UUriError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        AuthorityName::try_from(value.as_str())
+    }
+}
+
+impl From<AuthorityName> for String {
+    fn from(value: AuthorityName) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<str> for AuthorityName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for AuthorityName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_id_try_from_u32_rejects_out_of_range_values() {
+        assert_eq!(
+            ResourceId::try_from(0xFFFF_u32).map(u16::from).unwrap(),
+            0xFFFF
+        );
+        assert!(ResourceId::try_from(0x1_0000_u32).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_resource_id_topic_rejects_method_id() {
+        let _ = ResourceId::topic(0x7FFF);
+    }
+
+    #[test]
+    fn test_authority_name_try_from_rejects_invalid_names() {
+        assert!(AuthorityName::try_from("user:pwd@my-vehicle").is_err());
+        assert!(AuthorityName::try_from("a".repeat(129)).is_err());
+        assert_eq!(
+            AuthorityName::try_from("my-vehicle").unwrap().as_str(),
+            "my-vehicle"
+        );
+    }
+}
@@ -0,0 +1,328 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+use crate::UUri;
+
+trait IsEmpty {
+    fn is_empty(&self) -> bool;
+}
+
+/// A level of the trie, holding the sub-tries for specific keys and for the wildcard.
+#[derive(Debug)]
+struct Node<K, C> {
+    exact: HashMap<K, C>,
+    wildcard: Option<C>,
+}
+
+impl<K, C> Default for Node<K, C> {
+    fn default() -> Self {
+        Node {
+            exact: HashMap::new(),
+            wildcard: None,
+        }
+    }
+}
+
+impl<K, C> IsEmpty for Node<K, C> {
+    fn is_empty(&self) -> bool {
+        self.exact.is_empty() && self.wildcard.is_none()
+    }
+}
+
+impl<T> IsEmpty for Vec<T> {
+    fn is_empty(&self) -> bool {
+        Vec::is_empty(self)
+    }
+}
+
+impl<K: Hash + Eq, C: Default + IsEmpty> Node<K, C> {
+    fn child_mut(&mut self, key: Option<K>) -> &mut C {
+        match key {
+            Some(key) => self.exact.entry(key).or_default(),
+            None => self.wildcard.get_or_insert_with(C::default),
+        }
+    }
+
+    /// Applies a function to the sub-trie for a key, removing the sub-trie if it ends up empty.
+    fn update_child<R, F: FnOnce(&mut C) -> R>(&mut self, key: Option<&K>, f: F) -> Option<R> {
+        match key {
+            Some(key) => {
+                let child = self.exact.get_mut(key)?;
+                let result = f(child);
+                if child.is_empty() {
+                    self.exact.remove(key);
+                }
+                Some(result)
+            }
+            None => {
+                let child = self.wildcard.as_mut()?;
+                let result = f(child);
+                if child.is_empty() {
+                    self.wildcard = None;
+                }
+                Some(result)
+            }
+        }
+    }
+
+    /// Gets the sub-tries that are relevant for a specific key.
+    fn candidates<'a, Q>(&'a self, key: &Q) -> impl Iterator<Item = &'a C>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.exact.get(key).into_iter().chain(self.wildcard.iter())
+    }
+}
+
+#[derive(Debug)]
+struct Entry<T> {
+    filter: UUri,
+    value: T,
+}
+
+type ResourceNode<T> = Node<u16, Vec<Entry<T>>>;
+type VersionNode<T> = Node<u8, ResourceNode<T>>;
+type InstanceNode<T> = Node<u16, VersionNode<T>>;
+type TypeNode<T> = Node<u16, InstanceNode<T>>;
+type AuthorityNode<T> = Node<String, TypeNode<T>>;
+
+/// The keys of a filter URI for each level of the trie, `None` representing the wildcard.
+struct FilterKeys {
+    authority: Option<String>,
+    entity_type: Option<u16>,
+    entity_instance: Option<u16>,
+    version: Option<u8>,
+    resource_id: Option<u16>,
+}
+
+impl From<&UUri> for FilterKeys {
+    fn from(filter: &UUri) -> Self {
+        FilterKeys {
+            authority: (!filter.has_wildcard_authority()).then(|| filter.authority_name.clone()),
+            entity_type: (!filter.has_wildcard_entity_type()).then(|| filter.uentity_type_id()),
+            entity_instance: (!filter.has_wildcard_entity_instance())
+                .then(|| filter.uentity_instance_id()),
+            version: (!filter.has_wildcard_version()).then(|| filter.uentity_major_version()),
+            resource_id: (!filter.has_wildcard_resource_id()).then_some(filter.resource_id as u16),
+        }
+    }
+}
+
+/// An index of values that are associated with URI patterns, e.g. the listeners registered
+/// for source and sink filters.
+///
+/// The patterns are stored in a trie that is keyed by authority name, uEntity type ID, uEntity
+/// instance ID, major version and resource ID, in that order. Each level of the trie holds the
+/// sub-tries for specific IDs and for the level's wildcard. Looking up the patterns that match
+/// a given URI therefore only visits the (at most two) relevant sub-tries on each level instead of
+/// checking each pattern individually.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{UUri, UUriIndex};
+///
+/// let mut index = UUriIndex::new();
+/// index.insert(UUri::try_from("//vehicle/A100/1/FFFF").unwrap(), "all topics of A100");
+/// index.insert(UUri::try_from("//*/A100/1/8001").unwrap(), "topic 8001 of A100 on any vehicle");
+/// index.insert(UUri::try_from("//vehicle/B100/1/8001").unwrap(), "topic 8001 of B100");
+///
+/// let topic = UUri::try_from("//vehicle/A100/1/8001").unwrap();
+/// let mut matches: Vec<_> = index.find_matches(&topic).map(|(_filter, value)| *value).collect();
+/// matches.sort();
+/// assert_eq!(matches, vec!["all topics of A100", "topic 8001 of A100 on any vehicle"]);
+/// ```
+#[derive(Debug)]
+pub struct UUriIndex<T> {
+    root: AuthorityNode<T>,
+    len: usize,
+}
+
+impl<T> Default for UUriIndex<T> {
+    fn default() -> Self {
+        UUriIndex {
+            root: Node::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> UUriIndex<T> {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the number of values in this index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if this index contains any values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a value for a URI pattern.
+    ///
+    /// Multiple values can be added for the same pattern.
+    pub fn insert(&mut self, filter: UUri, value: T) {
+        let keys = FilterKeys::from(&filter);
+        self.root
+            .child_mut(keys.authority)
+            .child_mut(keys.entity_type)
+            .child_mut(keys.entity_instance)
+            .child_mut(keys.version)
+            .child_mut(keys.resource_id)
+            .push(Entry { filter, value });
+        self.len += 1;
+    }
+
+    /// Removes the values for a URI pattern that satisfy a predicate.
+    ///
+    /// # Returns
+    ///
+    /// The removed values.
+    pub fn remove_if<F>(&mut self, filter: &UUri, mut predicate: F) -> Vec<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let keys = FilterKeys::from(filter);
+        let removed = self
+            .root
+            .update_child(keys.authority.as_ref(), |types| {
+                types.update_child(keys.entity_type.as_ref(), |instances| {
+                    instances.update_child(keys.entity_instance.as_ref(), |versions| {
+                        versions.update_child(keys.version.as_ref(), |resources| {
+                            resources.update_child(keys.resource_id.as_ref(), |entries| {
+                                let (removed, retained): (Vec<_>, Vec<_>) = std::mem::take(entries)
+                                    .into_iter()
+                                    .partition(|entry| predicate(&entry.value));
+                                *entries = retained;
+                                removed
+                            })
+                        })
+                    })
+                })
+            })
+            .flatten()
+            .flatten()
+            .flatten()
+            .flatten()
+            .unwrap_or_default();
+        self.len -= removed.len();
+        removed.into_iter().map(|entry| entry.value).collect()
+    }
+
+    /// Removes all values for a URI pattern.
+    ///
+    /// # Returns
+    ///
+    /// The removed values.
+    pub fn remove(&mut self, filter: &UUri) -> Vec<T> {
+        self.remove_if(filter, |_value| true)
+    }
+
+    /// Finds the values for all patterns that match a URI.
+    ///
+    /// A pattern matches the URI if [`UUri::matches`] returns `true` for the URI.
+    ///
+    /// # Returns
+    ///
+    /// The matching patterns along with their values, in no particular order.
+    pub fn find_matches<'a>(&'a self, uri: &UUri) -> impl Iterator<Item = (&'a UUri, &'a T)> {
+        let authority = uri.authority_name.clone();
+        let entity_type = uri.uentity_type_id();
+        let entity_instance = uri.uentity_instance_id();
+        let version = uri.uentity_major_version();
+        let resource_id = uri.resource_id as u16;
+        self.root
+            .candidates(authority.as_str())
+            .flat_map(move |types| types.candidates(&entity_type))
+            .flat_map(move |instances| instances.candidates(&entity_instance))
+            .flat_map(move |versions| versions.candidates(&version))
+            .flat_map(move |resources| resources.candidates(&resource_id))
+            .flat_map(|entries| entries.iter())
+            .map(|entry| (&entry.filter, &entry.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const FILTERS: [&str; 8] = [
+        "//vehicle/A100/1/8001",
+        "//vehicle/A100/1/FFFF",
+        "//vehicle/A100/FF/8001",
+        "//vehicle/FFFFA100/1/8001",
+        "//vehicle/FFFF/1/8001",
+        "//*/A100/1/8001",
+        "//*/FFFFFFFF/FF/FFFF",
+        "//cloud/A100/1/8001",
+    ];
+
+    fn new_index() -> UUriIndex<usize> {
+        let mut index = UUriIndex::new();
+        for (idx, filter) in FILTERS.iter().enumerate() {
+            index.insert(UUri::try_from(*filter).unwrap(), idx);
+        }
+        index
+    }
+
+    #[test_case("//vehicle/A100/1/8001"; "exact match")]
+    #[test_case("//vehicle/2A100/1/8001"; "other instance")]
+    #[test_case("//vehicle/A100/2/8001"; "other version")]
+    #[test_case("//vehicle/A100/1/9001"; "other resource")]
+    #[test_case("//vehicle/B100/1/8001"; "other type")]
+    #[test_case("//cloud/A100/1/8001"; "other authority")]
+    #[test_case("/A100/1/8001"; "local authority")]
+    fn test_find_matches_is_consistent_with_uri_matching(uri: &str) {
+        let index = new_index();
+        let uri = UUri::try_from(uri).unwrap();
+
+        let mut matches: Vec<usize> = index.find_matches(&uri).map(|(_, idx)| *idx).collect();
+        matches.sort_unstable();
+
+        let expected: Vec<usize> = FILTERS
+            .iter()
+            .enumerate()
+            .filter(|(_, filter)| UUri::try_from(**filter).unwrap().matches(&uri))
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(matches, expected);
+    }
+
+    #[test]
+    fn test_remove_prunes_empty_nodes() {
+        let mut index = new_index();
+        let filter = UUri::try_from("//vehicle/A100/1/FFFF").unwrap();
+        index.insert(filter.clone(), 42);
+        assert_eq!(index.len(), FILTERS.len() + 1);
+
+        assert_eq!(index.remove_if(&filter, |value| *value == 42), vec![42]);
+        assert_eq!(index.remove(&filter), vec![1]);
+        assert!(index.remove(&filter).is_empty());
+        assert_eq!(index.len(), FILTERS.len() - 1);
+
+        for filter in FILTERS {
+            index.remove(&UUri::try_from(filter).unwrap());
+        }
+        assert!(index.is_empty());
+        assert!(IsEmpty::is_empty(&index.root));
+    }
+}
@@ -0,0 +1,262 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::{AuthorityName, UMessage, UUri, UUriError, UeId};
+
+/// A rule for translating the addresses of uEntities that match a pattern.
+///
+/// See [`UriRewriter`] for an example.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UriRewriteRule {
+    pattern: UUri,
+    authority: Option<AuthorityName>,
+    ue_id: Option<UeId>,
+}
+
+impl UriRewriteRule {
+    /// Creates a new rule that leaves matching URIs unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern that URIs need to match in order to be rewritten.
+    pub fn new(pattern: UUri) -> Self {
+        UriRewriteRule {
+            pattern,
+            authority: None,
+            ue_id: None,
+        }
+    }
+
+    /// Sets the authority to replace the authority of matching URIs with.
+    pub fn with_authority(mut self, authority: AuthorityName) -> Self {
+        self.authority = Some(authority);
+        self
+    }
+
+    /// Sets the uEntity identifier to replace the uEntity identifier of matching URIs with.
+    pub fn with_ue_id(mut self, ue_id: UeId) -> Self {
+        self.ue_id = Some(ue_id);
+        self
+    }
+
+    /// Gets the pattern that URIs need to match in order to be rewritten.
+    pub fn pattern(&self) -> &UUri {
+        &self.pattern
+    }
+
+    fn apply(&self, uri: &UUri) -> Option<UUri> {
+        if !self.pattern.matches(uri) {
+            return None;
+        }
+        let mut rewritten = uri.to_owned();
+        if let Some(authority) = self.authority.as_ref() {
+            rewritten.authority_name = authority.to_string();
+        }
+        if let Some(ue_id) = self.ue_id {
+            rewritten.ue_id = ue_id.value();
+        }
+        Some(rewritten)
+    }
+
+    /// Creates the rule for translating addresses in the opposite direction.
+    ///
+    /// The inverse rule matches the URIs that this rule produces and restores the authority and/or
+    /// uEntity identifier of the original URIs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parts of the pattern that this rule replaces contain wildcards,
+    /// because the original values cannot be restored in this case.
+    pub fn inverse(&self) -> Result<UriRewriteRule, UUriError> {
+        let mut inverse = UriRewriteRule::new(self.pattern.clone());
+        if let Some(authority) = self.authority.as_ref() {
+            if self.pattern.has_wildcard_authority() {
+                return Err(UUriError::validation_error(
+                    "Cannot invert rewriting of wildcard authority",
+                ));
+            }
+            inverse.pattern.authority_name = authority.to_string();
+            inverse.authority = Some(AuthorityName::try_from(
+                self.pattern.authority_name.as_str(),
+            )?);
+        }
+        if let Some(ue_id) = self.ue_id {
+            if self.pattern.has_wildcard_entity_type()
+                || self.pattern.has_wildcard_entity_instance()
+            {
+                return Err(UUriError::validation_error(
+                    "Cannot invert rewriting of wildcard uEntity ID",
+                ));
+            }
+            inverse.pattern.ue_id = ue_id.value();
+            inverse.ue_id = Some(UeId::new(self.pattern.ue_id));
+        }
+        Ok(inverse)
+    }
+}
+
+/// Translates the addresses of uEntities by means of an ordered list of [rules](`UriRewriteRule`).
+///
+/// Components that connect different uProtocol networks, e.g. a bridge between an in-vehicle
+/// network and a cloud backend, may need to map the authority and/or uEntity identifier of a
+/// uEntity to different values on either side of the bridge, similar to network address
+/// translation. A rewriter is applied to both the source and the sink address of each message
+/// being forwarded, using the first rule that matches the respective address. Addresses that do
+/// not match any rule remain unchanged.
+///
+/// The rewriter for the opposite direction can be created using [`UriRewriter::inverse`].
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{AuthorityName, UMessageBuilder, UUri, UriRewriteRule, UriRewriter};
+///
+/// // vehicle-internal services appear under the VIN in the backend
+/// let outbound = UriRewriter::default().with_rule(
+///     UriRewriteRule::new(UUri::try_from("//vehicle/FFFFFFFF/FF/FFFF").unwrap())
+///         .with_authority(AuthorityName::try_from("vin-1234").unwrap()),
+/// );
+/// let inbound = outbound.inverse().unwrap();
+///
+/// let mut message = UMessageBuilder::publish(UUri::try_from("//vehicle/A100/1/8001").unwrap())
+///     .build()
+///     .unwrap();
+/// outbound.rewrite_message(&mut message);
+/// assert_eq!(message.attributes.source.authority_name, "vin-1234");
+/// inbound.rewrite_message(&mut message);
+/// assert_eq!(message.attributes.source.authority_name, "vehicle");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UriRewriter {
+    rules: Vec<UriRewriteRule>,
+}
+
+impl UriRewriter {
+    /// Adds a rule.
+    ///
+    /// Rules are evaluated in the order in which they have been added.
+    pub fn with_rule(mut self, rule: UriRewriteRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Gets the rules, in the order in which they are evaluated.
+    pub fn rules(&self) -> &[UriRewriteRule] {
+        &self.rules
+    }
+
+    /// Rewrites a URI using the first matching rule.
+    ///
+    /// # Returns
+    ///
+    /// The rewritten URI or `None` if no rule matches the URI.
+    pub fn rewrite_uri(&self, uri: &UUri) -> Option<UUri> {
+        self.rules.iter().find_map(|rule| rule.apply(uri))
+    }
+
+    /// Rewrites the source and sink addresses of a message.
+    pub fn rewrite_message(&self, message: &mut UMessage) {
+        if self.rules.is_empty() {
+            return;
+        }
+        let Some(attributes) = message.attributes.as_mut() else {
+            return;
+        };
+        for uri in [attributes.source.as_mut(), attributes.sink.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(rewritten) = self.rewrite_uri(uri) {
+                *uri = rewritten;
+            }
+        }
+    }
+
+    /// Creates the rewriter for translating addresses in the opposite direction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the rules cannot be [inverted](`UriRewriteRule::inverse`).
+    pub fn inverse(&self) -> Result<UriRewriter, UUriError> {
+        self.rules
+            .iter()
+            .map(UriRewriteRule::inverse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|rules| UriRewriter { rules })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UMessageBuilder;
+
+    #[test]
+    fn test_rewrite_message_uses_first_matching_rule_for_source_and_sink() {
+        let rewriter = UriRewriter::default()
+            .with_rule(
+                UriRewriteRule::new(UUri::try_from("//vehicle/A100/1/FFFF").unwrap())
+                    .with_authority(AuthorityName::try_from("gateway").unwrap())
+                    .with_ue_id(UeId::new(0x0002_A100)),
+            )
+            .with_rule(
+                UriRewriteRule::new(UUri::try_from("//vehicle/FFFFFFFF/FF/FFFF").unwrap())
+                    .with_authority(AuthorityName::try_from("vin-1234").unwrap()),
+            );
+        let mut message = UMessageBuilder::notification(
+            UUri::try_from("//vehicle/A100/1/8001").unwrap(),
+            UUri::try_from("//vehicle/B200/1/0").unwrap(),
+        )
+        .build()
+        .unwrap();
+
+        rewriter.rewrite_message(&mut message);
+
+        let attributes = message.attributes.get_or_default();
+        assert_eq!(
+            attributes.source.get_or_default(),
+            &UUri::try_from("//gateway/2A100/1/8001").unwrap()
+        );
+        assert_eq!(
+            attributes.sink.get_or_default(),
+            &UUri::try_from("//vin-1234/B200/1/0").unwrap()
+        );
+        assert!(rewriter
+            .rewrite_uri(&UUri::try_from("//other/A100/1/8001").unwrap())
+            .is_none());
+    }
+
+    #[test]
+    fn test_inverse_restores_original_uri() {
+        let rewriter = UriRewriter::default().with_rule(
+            UriRewriteRule::new(UUri::try_from("//vehicle/A100/1/FFFF").unwrap())
+                .with_authority(AuthorityName::try_from("gateway").unwrap())
+                .with_ue_id(UeId::new(0x0002_A100)),
+        );
+        let original = UUri::try_from("//vehicle/A100/1/1").unwrap();
+
+        let rewritten = rewriter.rewrite_uri(&original).unwrap();
+        let restored = rewriter.inverse().unwrap().rewrite_uri(&rewritten);
+
+        assert_eq!(restored, Some(original));
+    }
+
+    #[test]
+    fn test_inverse_fails_for_rewritten_wildcards() {
+        let rewriter = UriRewriter::default().with_rule(
+            UriRewriteRule::new(UUri::try_from("//*/A100/1/FFFF").unwrap())
+                .with_authority(AuthorityName::try_from("gateway").unwrap()),
+        );
+        assert!(rewriter.inverse().is_err());
+    }
+}
@@ -0,0 +1,121 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use super::{RESOURCE_ID_MIN_EVENT, RESOURCE_ID_RESPONSE};
+use crate::{UUri, UUriError};
+
+/// Determines which resource IDs may be used for topics that events are published to.
+///
+/// This is the single place where the resource IDs of topics are checked. It is used by
+/// [`UMessageBuilder::publish`](crate::UMessageBuilder::publish), the
+/// [`PublishValidator`](crate::PublishValidator) and the Communication Layer API's default
+/// `Publisher` and `Subscriber` implementations.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{TopicPolicy, UUri};
+///
+/// let topic = UUri::try_from("//my-vehicle/A100/1/7000").unwrap();
+/// assert!(TopicPolicy::Strict.verify_topic(&topic).is_err());
+/// assert!(TopicPolicy::Platform.verify_topic(&topic).is_ok());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TopicPolicy {
+    /// Topics must have a resource ID in range `[0x8000, 0xFFFE]`, as required by the
+    /// uProtocol specification.
+    #[default]
+    Strict,
+    /// Topics may have any resource ID except for `0`, which is reserved for RPC response addresses.
+    ///
+    /// This bypass is intended for platform components only, e.g. bridges or streamers that need to
+    /// relay events published by uEntities that have been implemented against earlier versions of
+    /// the specification. Regular uEntities should always use [`TopicPolicy::Strict`].
+    Platform,
+}
+
+impl TopicPolicy {
+    fn is_allowed_resource_id(&self, resource_id: u32) -> bool {
+        match self {
+            TopicPolicy::Strict => resource_id >= RESOURCE_ID_MIN_EVENT,
+            TopicPolicy::Platform => resource_id != RESOURCE_ID_RESPONSE,
+        }
+    }
+
+    fn resource_id_error(&self) -> UUriError {
+        match self {
+            TopicPolicy::Strict => UUriError::validation_error(format!(
+                "Resource ID must be >= {:#X}",
+                RESOURCE_ID_MIN_EVENT
+            )),
+            TopicPolicy::Platform => UUriError::validation_error("Resource ID must not be 0"),
+        }
+    }
+
+    /// Verifies that a URI can be used as the topic to publish an event to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URI contains any wildcards or if its resource ID is not allowed
+    /// by this policy.
+    pub fn verify_topic(&self, topic: &UUri) -> Result<(), UUriError> {
+        if !self.is_allowed_resource_id(topic.resource_id) {
+            return Err(self.resource_id_error());
+        }
+        topic.verify_no_wildcards()
+    }
+
+    /// Verifies that a URI pattern can be used for subscribing to topics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern's resource ID is neither the wildcard resource ID
+    /// nor allowed by this policy.
+    pub fn verify_topic_filter(&self, topic_filter: &UUri) -> Result<(), UUriError> {
+        if topic_filter.has_wildcard_resource_id()
+            || self.is_allowed_resource_id(topic_filter.resource_id)
+        {
+            Ok(())
+        } else {
+            Err(self.resource_id_error())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("//vehicle/A100/1/8000", TopicPolicy::Strict, true; "for topic")]
+    #[test_case("//vehicle/A100/1/7FFF", TopicPolicy::Strict, false; "for RPC method")]
+    #[test_case("//vehicle/A100/1/7FFF", TopicPolicy::Platform, true; "for RPC method using platform policy")]
+    #[test_case("//vehicle/A100/1/0", TopicPolicy::Platform, false; "for response address using platform policy")]
+    #[test_case("//*/A100/1/8000", TopicPolicy::Platform, false; "for wildcard authority")]
+    fn test_verify_topic(topic: &str, policy: TopicPolicy, expected_ok: bool) {
+        let topic = UUri::try_from(topic).unwrap();
+        assert_eq!(policy.verify_topic(&topic).is_ok(), expected_ok);
+    }
+
+    #[test_case("//*/A100/1/8000", TopicPolicy::Strict, true; "for wildcard authority")]
+    #[test_case("//vehicle/A100/1/FFFF", TopicPolicy::Strict, true; "for wildcard resource")]
+    #[test_case("//vehicle/A100/1/1", TopicPolicy::Strict, false; "for RPC method")]
+    #[test_case("//vehicle/A100/1/1", TopicPolicy::Platform, true; "for RPC method using platform policy")]
+    fn test_verify_topic_filter(topic_filter: &str, policy: TopicPolicy, expected_ok: bool) {
+        let topic_filter = UUri::try_from(topic_filter).unwrap();
+        assert_eq!(
+            policy.verify_topic_filter(&topic_filter).is_ok(),
+            expected_ok
+        );
+    }
+}
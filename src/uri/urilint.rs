@@ -0,0 +1,273 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::fmt::Display;
+
+use crate::{wellknown, UUri};
+
+/// The kinds of common mistakes that [`UriLint`] detects in URI patterns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UriLintKind {
+    /// The pattern matches any authority but refers to a specific resource.
+    ///
+    /// Resource IDs are defined by a service's (local) interface, so a pattern like this usually
+    /// only makes sense if the same service is deployed to all vehicles/devices. Often, the wildcard
+    /// authority has been used by accident instead of the empty (local) authority.
+    WildcardAuthorityWithSpecificResource,
+    /// The topic to subscribe to has a resource ID that is outside of the range reserved for topics.
+    ResourceIdOutsideTopicRange,
+    /// The pattern refers to an RPC method although it is used for matching published messages.
+    RpcMethodAsTopic,
+    /// The URI refers to a resource ID that is [reserved](`crate::wellknown::is_reserved_resource_id`)
+    /// for a well-known RPC method or topic.
+    ReservedResourceId,
+}
+
+/// A potential problem with a URI pattern, as detected by [`UriLint`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UriLintFinding {
+    kind: UriLintKind,
+    uri: String,
+    message: String,
+}
+
+impl UriLintFinding {
+    fn new<T: Into<String>>(kind: UriLintKind, uri: &UUri, message: T) -> Self {
+        UriLintFinding {
+            kind,
+            uri: uri.to_uri(false),
+            message: message.into(),
+        }
+    }
+
+    /// Gets the kind of problem that has been found.
+    pub fn kind(&self) -> UriLintKind {
+        self.kind
+    }
+
+    /// Gets the URI pattern that the problem has been found in.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Gets a description of the problem, including a hint on how to fix it.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for UriLintFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.uri, self.message)
+    }
+}
+
+/// Inspects URI patterns for common mistakes.
+///
+/// The patterns reported by the lint are not invalid as such, i.e. they might still be used
+/// intentionally. However, they usually indicate that a filter or topic will not match the
+/// messages that the developer expects it to match.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{UUri, UriLint, UriLintKind};
+///
+/// let topic = UUri::try_from("//*/D4A/1/8001").unwrap();
+/// let findings = UriLint::default().check_subscription_topic(&topic);
+/// assert_eq!(findings.len(), 1);
+/// assert_eq!(
+///     findings[0].kind(),
+///     UriLintKind::WildcardAuthorityWithSpecificResource
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UriLint {
+    strict: bool,
+}
+
+impl UriLint {
+    /// Creates a new lint which reports findings as warnings only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether findings should be treated as errors.
+    ///
+    /// This is relevant for functions like [`crate::verify_filter_criteria`] which integrate the lint.
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Checks if findings should be treated as errors.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Inspects the filter patterns used for registering a listener with a transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_filter` - The _source_ address pattern.
+    /// * `sink_filter` - The _sink_ address pattern, or `None` if the listener is supposed to
+    ///                   receive published messages.
+    ///
+    /// # Returns
+    ///
+    /// The problems that have been found, if any.
+    pub fn check_filter_criteria(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+    ) -> Vec<UriLintFinding> {
+        let mut findings = vec![];
+        check_wildcard_authority(source_filter, &mut findings);
+        if let Some(sink_filter) = sink_filter {
+            check_wildcard_authority(sink_filter, &mut findings);
+        } else if source_filter.is_rpc_method() {
+            findings.push(UriLintFinding::new(
+                UriLintKind::RpcMethodAsTopic,
+                source_filter,
+                format!(
+                    "resource ID {:#06X} refers to an RPC method but no sink filter has been given, \
+                    use a resource ID from the topic range or specify a sink filter",
+                    source_filter.resource_id
+                ),
+            ));
+        }
+        findings
+    }
+
+    /// Inspects the topic pattern used for subscribing to published messages.
+    ///
+    /// # Returns
+    ///
+    /// The problems that have been found, if any.
+    pub fn check_subscription_topic(&self, topic: &UUri) -> Vec<UriLintFinding> {
+        let mut findings = vec![];
+        check_wildcard_authority(topic, &mut findings);
+        if topic.is_rpc_method() {
+            findings.push(UriLintFinding::new(
+                UriLintKind::RpcMethodAsTopic,
+                topic,
+                format!(
+                    "resource ID {:#06X} refers to an RPC method, RPC methods cannot be subscribed to",
+                    topic.resource_id
+                ),
+            ));
+        } else if !topic.has_wildcard_resource_id() && !topic.is_event() {
+            findings.push(UriLintFinding::new(
+                UriLintKind::ResourceIdOutsideTopicRange,
+                topic,
+                format!(
+                    "resource ID {:#06X} is outside of the topic range [0x8000, 0xFFFE]",
+                    topic.resource_id
+                ),
+            ));
+        } else {
+            check_reserved_resource_id(topic, &mut findings);
+        }
+        findings
+    }
+
+    /// Inspects the URI of an RPC method that a service is about to expose.
+    ///
+    /// # Returns
+    ///
+    /// The problems that have been found, if any.
+    pub fn check_rpc_method(&self, method: &UUri) -> Vec<UriLintFinding> {
+        let mut findings = vec![];
+        check_reserved_resource_id(method, &mut findings);
+        findings
+    }
+}
+
+fn check_reserved_resource_id(uri: &UUri, findings: &mut Vec<UriLintFinding>) {
+    if u16::try_from(uri.resource_id).is_ok_and(wellknown::is_reserved_resource_id) {
+        findings.push(UriLintFinding::new(
+            UriLintKind::ReservedResourceId,
+            uri,
+            format!(
+                "resource ID {:#06X} is reserved for a well-known RPC method or topic, \
+                use another resource ID for application specific resources",
+                uri.resource_id
+            ),
+        ));
+    }
+}
+
+fn check_wildcard_authority(pattern: &UUri, findings: &mut Vec<UriLintFinding>) {
+    if pattern.has_wildcard_authority() && !pattern.has_wildcard_resource_id() {
+        findings.push(UriLintFinding::new(
+            UriLintKind::WildcardAuthorityWithSpecificResource,
+            pattern,
+            "pattern matches any authority but a specific resource, \
+            use the empty authority for matching local resources only",
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("//*/D4A/1/8001", None, &[UriLintKind::WildcardAuthorityWithSpecificResource]; "for wildcard authority with specific topic")]
+    #[test_case("//*/D4A/1/FFFF", None, &[]; "for wildcard authority with wildcard resource")]
+    #[test_case("/D4A/1/8001", None, &[]; "for local topic")]
+    #[test_case("/D4A/1/1", None, &[UriLintKind::RpcMethodAsTopic]; "for RPC method without sink filter")]
+    #[test_case("//vehicle/D4A/1/0", Some("//*/1A/1/1"), &[UriLintKind::WildcardAuthorityWithSpecificResource]; "for sink filter with wildcard authority")]
+    #[test_case("//vehicle/D4A/1/0", Some("//other/1A/1/1"), &[]; "for RPC request filter")]
+    fn test_check_filter_criteria(
+        source_filter: &str,
+        sink_filter: Option<&str>,
+        expected_kinds: &[UriLintKind],
+    ) {
+        let source_filter = UUri::try_from(source_filter).unwrap();
+        let sink_filter = sink_filter.map(|uri| UUri::try_from(uri).unwrap());
+
+        let findings = UriLint::new().check_filter_criteria(&source_filter, sink_filter.as_ref());
+
+        let kinds: Vec<UriLintKind> = findings.iter().map(UriLintFinding::kind).collect();
+        assert_eq!(kinds, expected_kinds);
+    }
+
+    #[test_case("/D4A/1/8001", &[]; "for topic")]
+    #[test_case("/D4A/1/FFFF", &[]; "for wildcard resource")]
+    #[test_case("/D4A/1/0", &[UriLintKind::ResourceIdOutsideTopicRange]; "for resource ID 0")]
+    #[test_case("/D4A/1/7FFF", &[UriLintKind::RpcMethodAsTopic]; "for RPC method")]
+    #[test_case("//*/D4A/1/1", &[UriLintKind::WildcardAuthorityWithSpecificResource, UriLintKind::RpcMethodAsTopic]; "for RPC method of any authority")]
+    #[test_case("/D4A/1/FFFE", &[UriLintKind::ReservedResourceId]; "for reserved topic")]
+    fn test_check_subscription_topic(topic: &str, expected_kinds: &[UriLintKind]) {
+        let topic = UUri::try_from(topic).unwrap();
+
+        let findings = UriLint::new().check_subscription_topic(&topic);
+
+        let kinds: Vec<UriLintKind> = findings.iter().map(UriLintFinding::kind).collect();
+        assert_eq!(kinds, expected_kinds);
+        assert!(findings.iter().all(|f| f.uri() == topic.to_uri(false)));
+    }
+
+    #[test_case("/D4A/1/1", &[]; "for application specific method")]
+    #[test_case("/D4A/1/7FFF", &[UriLintKind::ReservedResourceId]; "for echo method")]
+    #[test_case("/D4A/1/7FFE", &[UriLintKind::ReservedResourceId]; "for capture control method")]
+    fn test_check_rpc_method(method: &str, expected_kinds: &[UriLintKind]) {
+        let method = UUri::try_from(method).unwrap();
+
+        let findings = UriLint::new().check_rpc_method(&method);
+
+        let kinds: Vec<UriLintKind> = findings.iter().map(UriLintFinding::kind).collect();
+        assert_eq!(kinds, expected_kinds);
+    }
+}
@@ -0,0 +1,138 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Identifiers that are reserved by the uProtocol specification and for the well-known RPC methods and
+topics provided by this library.
+
+Applications should use these constants and predicates instead of raw numbers when creating
+or inspecting [`UUri`](crate::UUri)s.
+
+```rust
+use up_rust::{wellknown, UUri};
+
+let topic = UUri::try_from_parts("my-vehicle", 0x4210, 0x01, 0x8001).unwrap();
+assert!(wellknown::is_topic_id(topic.resource_id()));
+assert!(!wellknown::is_rpc_method_id(topic.resource_id()));
+```
+*/
+
+use std::ops::RangeInclusive;
+
+/// The resource ID of the address that RPC Response messages are sent to.
+pub const RESOURCE_ID_RESPONSE: u16 = 0x0000;
+/// The resource ID of the address that Notification messages are sent to.
+pub const RESOURCE_ID_NOTIFICATION_DESTINATION: u16 = 0x0000;
+/// The range of resource IDs reserved for RPC methods.
+pub const RPC_METHOD_IDS: RangeInclusive<u16> = 0x0001..=0x7FFF;
+/// The range of resource IDs reserved for topics, i.e. the origins of Publish and Notification messages.
+pub const TOPIC_IDS: RangeInclusive<u16> = 0x8000..=0xFFFE;
+/// The resource ID which matches any resource.
+pub const WILDCARD_RESOURCE_ID: u16 = 0xFFFF;
+
+/// The authority name which matches any authority.
+pub const WILDCARD_AUTHORITY: &str = "*";
+/// The entity type ID which matches any type of entity.
+pub const WILDCARD_ENTITY_TYPE_ID: u32 = 0x0000_FFFF;
+/// The entity instance ID which matches any instance of an entity.
+pub const WILDCARD_ENTITY_INSTANCE_ID: u32 = 0xFFFF_0000;
+/// The major version which matches any version of an entity.
+pub const WILDCARD_VERSION: u8 = 0xFF;
+
+/// The resource ID of the method that uEntities can expose for verifying that they are reachable.
+///
+/// The ID is reserved, i.e. it must not be used for an application specific RPC method.
+pub const ECHO_RESOURCE_ID: u16 = 0x7FFF;
+/// The resource ID of the method for controlling the capturing of messages at runtime.
+///
+/// The ID is reserved, i.e. it must not be used for an application specific RPC method.
+pub const CAPTURE_CONTROL_RESOURCE_ID: u16 = 0x7FFE;
+/// The resource ID of the topic that notifications about cancelled RPC invocations originate from.
+///
+/// The ID is reserved, i.e. it must not be used for an application specific topic.
+pub const RPC_CANCELLATION_RESOURCE_ID: u16 = 0xFFFE;
+
+/// The uEntity (type) identifier of the uSubscription service.
+pub const USUBSCRIPTION_TYPE_ID: u32 = 0x0000_0000;
+/// The uEntity (type) identifier of the uDiscovery service.
+pub const UDISCOVERY_TYPE_ID: u32 = 0x0000_0001;
+/// The uEntity (type) identifier of the uTwin service.
+pub const UTWIN_TYPE_ID: u32 = 0x0000_001A;
+
+/// Checks if a resource ID refers to an RPC method.
+///
+/// This includes the [reserved](`is_reserved_resource_id`) IDs of well-known methods like
+/// [`ECHO_RESOURCE_ID`].
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::wellknown::is_rpc_method_id;
+///
+/// assert!(is_rpc_method_id(0x0001));
+/// assert!(is_rpc_method_id(0x7FFF));
+/// assert!(!is_rpc_method_id(0x0000));
+/// assert!(!is_rpc_method_id(0x8000));
+/// ```
+pub const fn is_rpc_method_id(resource_id: u16) -> bool {
+    resource_id >= *RPC_METHOD_IDS.start() && resource_id <= *RPC_METHOD_IDS.end()
+}
+
+/// Checks if a resource ID refers to a topic.
+///
+/// This includes the [reserved](`is_reserved_resource_id`) IDs of well-known topics like
+/// [`RPC_CANCELLATION_RESOURCE_ID`].
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::wellknown::is_topic_id;
+///
+/// assert!(is_topic_id(0x8000));
+/// assert!(is_topic_id(0xFFFE));
+/// assert!(!is_topic_id(0x7FFF));
+/// assert!(!is_topic_id(0xFFFF));
+/// ```
+pub const fn is_topic_id(resource_id: u16) -> bool {
+    resource_id >= *TOPIC_IDS.start() && resource_id <= *TOPIC_IDS.end()
+}
+
+/// Checks if a resource ID is reserved for a well-known RPC method or topic.
+///
+/// Applications should not use reserved IDs for their own RPC methods and topics.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::wellknown::{is_reserved_resource_id, is_rpc_method_id, ECHO_RESOURCE_ID};
+///
+/// assert!(is_reserved_resource_id(ECHO_RESOURCE_ID));
+/// assert!(is_rpc_method_id(ECHO_RESOURCE_ID));
+/// assert!(!is_reserved_resource_id(0x0001));
+/// assert!(!is_reserved_resource_id(0x8000));
+/// ```
+pub const fn is_reserved_resource_id(resource_id: u16) -> bool {
+    resource_id == ECHO_RESOURCE_ID
+        || resource_id == CAPTURE_CONTROL_RESOURCE_ID
+        || resource_id == RPC_CANCELLATION_RESOURCE_ID
+}
+
+/// Checks if a resource ID refers to the address that RPC Response messages are sent to.
+pub const fn is_rpc_response_id(resource_id: u16) -> bool {
+    resource_id == RESOURCE_ID_RESPONSE
+}
+
+/// Checks if a resource ID refers to the address that Notification messages are sent to.
+pub const fn is_notification_destination_id(resource_id: u16) -> bool {
+    resource_id == RESOURCE_ID_NOTIFICATION_DESTINATION
+}
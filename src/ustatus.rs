@@ -16,6 +16,129 @@ use std::error::Error;
 pub use crate::up_core_api::ucode::UCode;
 pub use crate::up_core_api::ustatus::UStatus;
 
+/// The category that a [`UCode`] belongs to.
+///
+/// The categories follow the mapping of status codes to HTTP status codes as defined by
+/// [Google's API design guide](https://cloud.google.com/apis/design/errors#handling_errors),
+/// i.e. codes that map to a `4xx` HTTP status represent client errors whereas codes that
+/// map to a `5xx` HTTP status represent server errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UCodeCategory {
+    /// The operation has completed successfully.
+    Success,
+    /// The operation has failed due to a problem with the request or the caller's state.
+    ClientError,
+    /// The operation has failed due to a problem on the side of the service or infrastructure.
+    ServerError,
+}
+
+impl UCodeCategory {
+    /// Gets a name for this category that can be used as a label, e.g. in metrics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UCodeCategory;
+    ///
+    /// assert_eq!(UCodeCategory::ClientError.as_str(), "client_error");
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UCodeCategory::Success => "success",
+            UCodeCategory::ClientError => "client_error",
+            UCodeCategory::ServerError => "server_error",
+        }
+    }
+}
+
+impl UCode {
+    /// Gets the category that this code belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{UCode, UCodeCategory};
+    ///
+    /// assert_eq!(UCode::OK.category(), UCodeCategory::Success);
+    /// assert_eq!(UCode::NOT_FOUND.category(), UCodeCategory::ClientError);
+    /// assert_eq!(UCode::UNAVAILABLE.category(), UCodeCategory::ServerError);
+    /// ```
+    pub fn category(&self) -> UCodeCategory {
+        match self {
+            UCode::OK => UCodeCategory::Success,
+            UCode::CANCELLED
+            | UCode::INVALID_ARGUMENT
+            | UCode::NOT_FOUND
+            | UCode::ALREADY_EXISTS
+            | UCode::PERMISSION_DENIED
+            | UCode::RESOURCE_EXHAUSTED
+            | UCode::FAILED_PRECONDITION
+            | UCode::ABORTED
+            | UCode::OUT_OF_RANGE
+            | UCode::UNAUTHENTICATED => UCodeCategory::ClientError,
+            UCode::UNKNOWN
+            | UCode::DEADLINE_EXCEEDED
+            | UCode::UNIMPLEMENTED
+            | UCode::INTERNAL
+            | UCode::UNAVAILABLE
+            | UCode::DATA_LOSS => UCodeCategory::ServerError,
+        }
+    }
+
+    /// Checks if this code represents a client error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UCode;
+    ///
+    /// assert!(UCode::INVALID_ARGUMENT.is_client_error());
+    /// assert!(!UCode::INTERNAL.is_client_error());
+    /// ```
+    pub fn is_client_error(&self) -> bool {
+        self.category() == UCodeCategory::ClientError
+    }
+
+    /// Checks if this code represents a server error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UCode;
+    ///
+    /// assert!(UCode::INTERNAL.is_server_error());
+    /// assert!(!UCode::INVALID_ARGUMENT.is_server_error());
+    /// ```
+    pub fn is_server_error(&self) -> bool {
+        self.category() == UCodeCategory::ServerError
+    }
+
+    /// Checks if an operation that has failed with this code may succeed if it is retried
+    /// without modification.
+    ///
+    /// This is the case for transient conditions only, i.e. for
+    /// [`UCode::UNAVAILABLE`], [`UCode::DEADLINE_EXCEEDED`], [`UCode::RESOURCE_EXHAUSTED`]
+    /// and [`UCode::ABORTED`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::UCode;
+    ///
+    /// assert!(UCode::UNAVAILABLE.is_retryable());
+    /// assert!(!UCode::PERMISSION_DENIED.is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            UCode::UNAVAILABLE
+                | UCode::DEADLINE_EXCEEDED
+                | UCode::RESOURCE_EXHAUSTED
+                | UCode::ABORTED
+        )
+    }
+}
+
 impl UStatus {
     /// Creates a status representing a success.
     ///
@@ -154,6 +277,18 @@ impl UStatus {
 
 impl Error for UStatus {}
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for UStatus {
+    /// Formats this status' error code as its numeric value, followed by the message (if any).
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "UStatus {{ code: {=i32}", self.code.value());
+        if let Some(message) = self.message.as_ref() {
+            defmt::write!(f, ", message: {=str}", message.as_str());
+        }
+        defmt::write!(f, " }}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,4 +324,42 @@ mod tests {
             assert_eq!(ustatus.is_success(), *code == UCode::OK);
         });
     }
+
+    #[test]
+    fn test_every_code_has_exactly_one_category() {
+        UCode::VALUES.iter().for_each(|code| {
+            let categories = [
+                *code == UCode::OK,
+                code.is_client_error(),
+                code.is_server_error(),
+            ];
+            assert_eq!(
+                categories.iter().filter(|v| **v).count(),
+                1,
+                "code {:?} must belong to exactly one category",
+                code
+            );
+        });
+    }
+
+    #[test_case::test_case(UCode::OK, false; "for OK")]
+    #[test_case::test_case(UCode::CANCELLED, false; "for CANCELLED")]
+    #[test_case::test_case(UCode::UNKNOWN, false; "for UNKNOWN")]
+    #[test_case::test_case(UCode::INVALID_ARGUMENT, false; "for INVALID_ARGUMENT")]
+    #[test_case::test_case(UCode::DEADLINE_EXCEEDED, true; "for DEADLINE_EXCEEDED")]
+    #[test_case::test_case(UCode::NOT_FOUND, false; "for NOT_FOUND")]
+    #[test_case::test_case(UCode::ALREADY_EXISTS, false; "for ALREADY_EXISTS")]
+    #[test_case::test_case(UCode::PERMISSION_DENIED, false; "for PERMISSION_DENIED")]
+    #[test_case::test_case(UCode::RESOURCE_EXHAUSTED, true; "for RESOURCE_EXHAUSTED")]
+    #[test_case::test_case(UCode::FAILED_PRECONDITION, false; "for FAILED_PRECONDITION")]
+    #[test_case::test_case(UCode::ABORTED, true; "for ABORTED")]
+    #[test_case::test_case(UCode::OUT_OF_RANGE, false; "for OUT_OF_RANGE")]
+    #[test_case::test_case(UCode::UNIMPLEMENTED, false; "for UNIMPLEMENTED")]
+    #[test_case::test_case(UCode::INTERNAL, false; "for INTERNAL")]
+    #[test_case::test_case(UCode::UNAVAILABLE, true; "for UNAVAILABLE")]
+    #[test_case::test_case(UCode::DATA_LOSS, false; "for DATA_LOSS")]
+    #[test_case::test_case(UCode::UNAUTHENTICATED, false; "for UNAUTHENTICATED")]
+    fn test_is_retryable(code: UCode, expected_result: bool) {
+        assert_eq!(code.is_retryable(), expected_result);
+    }
 }
@@ -18,8 +18,34 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-
-use crate::{UCode, UMessage, UStatus, UUri};
+use tracing::warn;
+
+use crate::{UCode, UMessage, UStatus, UUri, UriLint};
+
+mod credentials;
+#[cfg(feature = "util")]
+mod executor_group;
+mod listener_pipeline;
+#[cfg(feature = "util")]
+mod pooled_listener;
+#[cfg(feature = "util")]
+pub mod scaffold;
+mod tls;
+#[cfg(feature = "test-util")]
+pub use credentials::MockCredentialsProvider;
+pub use credentials::{
+    Credentials, CredentialsProvider, EnvCredentialsProvider, FileCredentialsProvider,
+};
+#[cfg(feature = "util")]
+pub use executor_group::{ExecutorGroup, ExecutorGroups, IsolatedListener};
+#[cfg(feature = "test-util")]
+pub use listener_pipeline::MockMessageVerifier;
+pub use listener_pipeline::{
+    DecodingPipeline, ListenerPipeline, MessageVerifier, PipelineListener,
+};
+#[cfg(feature = "util")]
+pub use pooled_listener::{PoolOverflowPolicy, PooledListener};
+pub use tls::{ClientAuth, PemSource, TlsConfig, TlsRotationWatcher};
 
 /// A factory for URIs representing this uEntity's resources.
 ///
@@ -140,6 +166,112 @@ impl TryFrom<&UUri> for StaticUriProvider {
     }
 }
 
+/// Declares functions for creating the URIs of topics that a uEntity publishes to.
+///
+/// Each declaration binds a resource ID to a function which uses a [`LocalUriProvider`]
+/// for creating the topic's URI. This helps to keep resource IDs in a single place
+/// instead of scattering raw literals across application code.
+///
+/// The resource IDs are checked at compile time to be in the range reserved for topics,
+/// i.e. `[0x8000, 0xFFFE]`.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{topics, LocalUriProvider, StaticUriProvider};
+///
+/// const RESOURCE_ID_POSITION: u16 = 0x8001;
+///
+/// topics! {
+///     /// The topic that the current position is published to.
+///     pub fn position_topic = RESOURCE_ID_POSITION;
+///     fn speed_topic = 0x8002;
+/// }
+///
+/// let uri_provider = StaticUriProvider::new("my-vehicle", 0x4210, 0x01);
+/// assert_eq!(position_topic(&uri_provider).resource_id, 0x8001);
+/// assert!(speed_topic(&uri_provider).is_event());
+/// ```
+///
+/// Resource IDs outside of the topic range are rejected:
+///
+/// ```compile_fail
+/// use up_rust::topics;
+///
+/// topics! {
+///     fn not_a_topic = 0x0001;
+/// }
+/// ```
+#[macro_export]
+macro_rules! topics {
+    ($($(#[$meta:meta])* $vis:vis fn $name:ident = $resource_id:expr;)*) => {
+        $(
+            $(#[$meta])*
+            $vis fn $name<P: $crate::LocalUriProvider + ?Sized>(uri_provider: &P) -> $crate::UUri {
+                const RESOURCE_ID: u16 = $resource_id;
+                const _: () = assert!(
+                    $crate::wellknown::is_topic_id(RESOURCE_ID),
+                    "topic resource ID must be in range [0x8000, 0xFFFE]"
+                );
+                uri_provider.get_resource_uri(RESOURCE_ID)
+            }
+        )*
+    };
+}
+
+/// Declares functions for creating the URIs of RPC methods that a uEntity exposes.
+///
+/// Each declaration binds a resource ID to a function which uses a [`LocalUriProvider`]
+/// for creating the method's URI. This helps to keep resource IDs in a single place
+/// instead of scattering raw literals across application code.
+///
+/// The resource IDs are checked at compile time to be in the range reserved for methods,
+/// i.e. `[0x0001, 0x7FFF]`.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{methods, LocalUriProvider, StaticUriProvider};
+///
+/// const RESOURCE_ID_SET_SPEED: u16 = 0x0001;
+///
+/// methods! {
+///     /// The method for setting the vehicle's speed.
+///     pub fn set_speed_method = RESOURCE_ID_SET_SPEED;
+///     fn get_speed_method = 0x0002;
+/// }
+///
+/// let uri_provider = StaticUriProvider::new("my-vehicle", 0x4210, 0x01);
+/// assert_eq!(set_speed_method(&uri_provider).resource_id, 0x0001);
+/// assert!(get_speed_method(&uri_provider).is_rpc_method());
+/// ```
+///
+/// Resource IDs outside of the method range are rejected:
+///
+/// ```compile_fail
+/// use up_rust::methods;
+///
+/// methods! {
+///     fn not_a_method = 0x8000;
+/// }
+/// ```
+#[macro_export]
+macro_rules! methods {
+    ($($(#[$meta:meta])* $vis:vis fn $name:ident = $resource_id:expr;)*) => {
+        $(
+            $(#[$meta])*
+            $vis fn $name<P: $crate::LocalUriProvider + ?Sized>(uri_provider: &P) -> $crate::UUri {
+                const RESOURCE_ID: u16 = $resource_id;
+                const _: () = assert!(
+                    $crate::wellknown::is_rpc_method_id(RESOURCE_ID),
+                    "method resource ID must be in range [0x0001, 0x7FFF]"
+                );
+                uri_provider.get_resource_uri(RESOURCE_ID)
+            }
+        )*
+    };
+}
+
 /// A handler for processing uProtocol messages.
 ///
 /// Implementations contain the details for what should occur when a message is received.
@@ -188,6 +320,27 @@ pub trait UTransport: Send + Sync {
     /// Returns an error if the message could not be sent.
     async fn send(&self, message: UMessage) -> Result<(), UStatus>;
 
+    /// Sends multiple messages using this transport's message exchange mechanism.
+    ///
+    /// Transports that support sending multiple messages in a single operation should
+    /// override this function. This default implementation [sends](`UTransport::send`) the
+    /// messages one after the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The messages to send.
+    ///
+    /// # Returns
+    ///
+    /// The outcome of sending each message, in the same order as the given messages.
+    async fn send_batch(&self, messages: Vec<UMessage>) -> Vec<Result<(), UStatus>> {
+        let mut outcomes = Vec::with_capacity(messages.len());
+        for message in messages {
+            outcomes.push(self.send(message).await);
+        }
+        outcomes
+    }
+
     /// Receives a message from the transport.
     ///
     /// This default implementation returns an error with [`UCode::UNIMPLEMENTED`].
@@ -269,6 +422,28 @@ pub trait UTransport: Send + Sync {
             "not implemented",
         ))
     }
+
+    /// Checks if uEntities of a given authority can be reached via this transport.
+    ///
+    /// Transports should override this function if they can determine the reachability of an authority
+    /// without involving any uEntity, e.g. by checking the connection to the peer or a message broker.
+    /// Use [`ping`](crate::communication::ping) for checking the reachability of a particular uEntity.
+    ///
+    /// This default implementation returns an error with [`UCode::UNIMPLEMENTED`].
+    ///
+    /// # Arguments
+    ///
+    /// * `peer_authority` - The name of the authority to check.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the authority cannot be reached or if the transport cannot determine reachability.
+    async fn probe(&self, _peer_authority: &str) -> Result<(), UStatus> {
+        Err(UStatus::fail_with_code(
+            UCode::UNIMPLEMENTED,
+            "not implemented",
+        ))
+    }
 }
 
 #[cfg(any(test, feature = "test-util"))]
@@ -310,6 +485,122 @@ impl UTransport for MockTransport {
     }
 }
 
+/// Sends the messages that pass a check using a transport's [`UTransport::send_batch`] function.
+///
+/// Used by transport decorators for applying their per-message policy to a batch of messages.
+///
+/// # Arguments
+///
+/// * `transport` - The transport to send the messages with.
+/// * `messages` - The messages to send.
+/// * `check` - The function to apply to each message. The message returned by the function is sent
+///   instead of the original one. Messages that the function returns an error for are not sent.
+///
+/// # Returns
+///
+/// The outcome of sending each message, in the same order as the given messages.
+#[cfg(feature = "util")]
+pub(crate) async fn send_checked_batch<F>(
+    transport: &dyn UTransport,
+    messages: Vec<UMessage>,
+    mut check: F,
+) -> Vec<Result<(), UStatus>>
+where
+    F: FnMut(UMessage) -> Result<UMessage, UStatus>,
+{
+    let mut outcomes = Vec::with_capacity(messages.len());
+    let mut accepted_messages = Vec::with_capacity(messages.len());
+    for message in messages {
+        match check(message) {
+            Ok(message) => {
+                accepted_messages.push(message);
+                outcomes.push(Ok(()));
+            }
+            Err(e) => outcomes.push(Err(e)),
+        }
+    }
+    if accepted_messages.is_empty() {
+        return outcomes;
+    }
+    let mut sent = transport.send_batch(accepted_messages).await.into_iter();
+    for outcome in outcomes.iter_mut().filter(|outcome| outcome.is_ok()) {
+        *outcome = sent.next().unwrap_or_else(|| {
+            Err(UStatus::fail_with_code(
+                UCode::INTERNAL,
+                "transport did not report outcome of sending message",
+            ))
+        });
+    }
+    outcomes
+}
+
+/// Verifies that a combination of filter patterns can be used for registering a listener.
+///
+/// # Arguments
+///
+/// * `source_filter` - The _source_ address pattern.
+/// * `sink_filter` - The _sink_ address pattern, or `None` for matching published messages.
+/// * `lint` - The lint to inspect the patterns with for common mistakes, if any.
+///            Findings are logged as warnings unless the lint is in strict mode.
+///
+/// # Errors
+///
+/// Returns an error with [`UCode::INVALID_ARGUMENT`] if the patterns cannot match any valid message
+/// or if the lint is in strict mode and has found any problems.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{verify_filter_criteria, UUri, UriLint};
+///
+/// let source_filter = UUri::try_from("//*/D4A/1/8001").unwrap();
+/// assert!(verify_filter_criteria(&source_filter, None, None).is_ok());
+/// assert!(verify_filter_criteria(&source_filter, None, Some(&UriLint::new())).is_ok());
+///
+/// let strict = UriLint::new().with_strict_mode(true);
+/// assert!(verify_filter_criteria(&source_filter, None, Some(&strict)).is_err());
+/// ```
+pub fn verify_filter_criteria(
+    source_filter: &UUri,
+    sink_filter: Option<&UUri>,
+    lint: Option<&UriLint>,
+) -> Result<(), UStatus> {
+    if let Some(sink_filter) = sink_filter {
+        if source_filter.is_notification_destination() && sink_filter.is_notification_destination()
+        {
+            return Err(UStatus::fail_with_code(
+                UCode::INVALID_ARGUMENT,
+                "source and sink filters must not both have resource ID 0",
+            ));
+        }
+        if sink_filter.is_rpc_method()
+            && !source_filter.has_wildcard_resource_id()
+            && !source_filter.is_notification_destination()
+        {
+            return Err(UStatus::fail_with_code(
+                UCode::INVALID_ARGUMENT,
+                "source filter must either have the wildcard resource ID or resource ID 0, if sink filter matches RPC method resource ID",
+            ));
+        }
+    }
+    let Some(lint) = lint else {
+        return Ok(());
+    };
+    let findings = lint.check_filter_criteria(source_filter, sink_filter);
+    if findings.is_empty() {
+        return Ok(());
+    }
+    if lint.is_strict() {
+        let messages: Vec<String> = findings.iter().map(|f| f.to_string()).collect();
+        return Err(UStatus::fail_with_code(
+            UCode::INVALID_ARGUMENT,
+            messages.join("; "),
+        ));
+    }
+    findings.iter().for_each(|f| warn!("{}", f));
+    Ok(())
+}
+
 /// A wrapper type that allows comparing [`UListener`]s to each other.
 ///
 /// # Note
@@ -388,7 +679,7 @@ impl Debug for ComparableListener {
 
 #[cfg(test)]
 mod tests {
-    use crate::{ComparableListener, UListener, UMessage};
+    use crate::{ComparableListener, UListener, UMessage, UMessageBuilder};
     use std::{
         hash::{DefaultHasher, Hash, Hasher},
         ops::Deref,
@@ -397,6 +688,63 @@ mod tests {
 
     use super::*;
 
+    crate::topics! {
+        fn test_topic = 0x9A00;
+    }
+
+    crate::methods! {
+        fn test_method = 0x0A01;
+    }
+
+    #[test_case::test_case("//vehicle/D4A/1/0", Some("//other/1A/1/0"); "for source and sink with resource ID 0")]
+    #[test_case::test_case("//vehicle/D4A/1/8001", Some("//other/1A/1/1"); "for topic as source of RPC request")]
+    fn test_verify_filter_criteria_fails(source_filter: &str, sink_filter: Option<&str>) {
+        let source_filter = UUri::try_from(source_filter).unwrap();
+        let sink_filter = sink_filter.map(|uri| UUri::try_from(uri).unwrap());
+
+        let result = verify_filter_criteria(&source_filter, sink_filter.as_ref(), None);
+
+        assert!(result.is_err_and(|e| e.get_code() == UCode::INVALID_ARGUMENT));
+    }
+
+    #[test]
+    fn test_verify_filter_criteria_applies_lint() {
+        // GIVEN a source filter that refers to an RPC method
+        let source_filter = UUri::try_from("/D4A/1/1").unwrap();
+
+        // WHEN verifying the filter without a lint or with a non-strict lint
+        // THEN verification succeeds
+        assert!(verify_filter_criteria(&source_filter, None, None).is_ok());
+        assert!(verify_filter_criteria(&source_filter, None, Some(&UriLint::new())).is_ok());
+
+        // WHEN verifying the filter with a strict lint
+        let lint = UriLint::new().with_strict_mode(true);
+        // THEN verification fails
+        assert!(verify_filter_criteria(&source_filter, None, Some(&lint))
+            .is_err_and(|e| e.get_code() == UCode::INVALID_ARGUMENT));
+    }
+
+    #[test]
+    fn test_topics_macro_creates_topic_uri() {
+        let provider = StaticUriProvider::new("my-vehicle", 0x4210, 0x05);
+        let topic = test_topic(&provider);
+        assert_eq!(topic.authority_name, "my-vehicle");
+        assert_eq!(topic.ue_id, 0x4210);
+        assert_eq!(topic.ue_version_major, 0x05);
+        assert_eq!(topic.resource_id, 0x9A00);
+    }
+
+    #[test]
+    fn test_methods_macro_creates_method_uri() {
+        let provider: Box<dyn LocalUriProvider> =
+            Box::new(StaticUriProvider::new("my-vehicle", 0x4210, 0x05));
+        let method = test_method(provider.as_ref());
+        assert_eq!(method.authority_name, "my-vehicle");
+        assert_eq!(method.ue_id, 0x4210);
+        assert_eq!(method.ue_version_major, 0x05);
+        assert_eq!(method.resource_id, 0x0A01);
+    }
+
     #[test]
     fn test_static_uri_provider_get_source() {
         let provider = StaticUriProvider::new("my-vehicle", 0x4210, 0x05);
@@ -517,6 +865,41 @@ mod tests {
             .unregister_listener(&UUri::any(), None, listener)
             .await
             .is_err_and(|e| e.get_code() == UCode::UNIMPLEMENTED));
+        assert!(transport
+            .probe("other")
+            .await
+            .is_err_and(|e| e.get_code() == UCode::UNIMPLEMENTED));
+    }
+
+    #[tokio::test]
+    async fn test_send_batch_default_implementation_sends_all_messages() {
+        let mut transport = MockTransport::new();
+        let mut sequence = mockall::Sequence::new();
+        transport
+            .expect_do_send()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(Err(UStatus::fail_with_code(
+                UCode::UNAVAILABLE,
+                "unavailable",
+            )));
+        transport
+            .expect_do_send()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(Ok(()));
+
+        let topic = UUri::try_from_parts("", 0x1000, 0x01, 0x8000).unwrap();
+        let messages = vec![
+            UMessageBuilder::publish(topic.clone()).build().unwrap(),
+            UMessageBuilder::publish(topic).build().unwrap(),
+        ];
+        let outcomes = transport.send_batch(messages).await;
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes[0]
+            .as_ref()
+            .is_err_and(|e| e.get_code() == UCode::UNAVAILABLE));
+        assert!(outcomes[1].is_ok());
     }
 
     #[test]
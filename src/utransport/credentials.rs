@@ -0,0 +1,344 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+
+use crate::{UCode, UStatus};
+
+const KEY_USERNAME: &str = "username";
+const KEY_PASSWORD: &str = "password";
+const KEY_TOKEN: &str = "token";
+const KEY_CERT_PATH: &str = "cert_path";
+const KEY_KEY_PATH: &str = "key_path";
+const KEY_CA_PATH: &str = "ca_path";
+
+/// Credentials for authenticating to the infrastructure used by a transport,
+/// e.g. a message broker.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Credentials {
+    /// A username and password.
+    UsernamePassword {
+        /// The name of the user to authenticate as.
+        username: String,
+        /// The user's password.
+        password: String,
+    },
+    /// A client certificate to use for mutual TLS authentication.
+    Certificate {
+        /// The path to the PEM file containing the client certificate (chain).
+        cert_path: PathBuf,
+        /// The path to the PEM file containing the client certificate's private key.
+        key_path: PathBuf,
+        /// The path to the PEM file containing the trust anchors to use for verifying
+        /// the server's certificate, or `None` if the system's default trust anchors should be used.
+        ca_path: Option<PathBuf>,
+    },
+    /// A (bearer) token.
+    Token(String),
+}
+
+impl Debug for Credentials {
+    // makes sure that secrets do not end up in log files
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Credentials::UsernamePassword { username, .. } => f
+                .debug_struct("UsernamePassword")
+                .field("username", username)
+                .field("password", &"***")
+                .finish(),
+            Credentials::Certificate {
+                cert_path,
+                key_path,
+                ca_path,
+            } => f
+                .debug_struct("Certificate")
+                .field("cert_path", cert_path)
+                .field("key_path", key_path)
+                .field("ca_path", ca_path)
+                .finish(),
+            Credentials::Token(_) => f.debug_tuple("Token").field(&"***").finish(),
+        }
+    }
+}
+
+impl Credentials {
+    /// Creates credentials from a set of named properties.
+    ///
+    /// A token takes precedence over a client certificate, which takes precedence over
+    /// a username and password.
+    fn from_properties<F>(lookup: F) -> Result<Self, UStatus>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(token) = lookup(KEY_TOKEN) {
+            return Ok(Credentials::Token(token));
+        }
+        match (lookup(KEY_CERT_PATH), lookup(KEY_KEY_PATH)) {
+            (Some(cert_path), Some(key_path)) => {
+                return Ok(Credentials::Certificate {
+                    cert_path: cert_path.into(),
+                    key_path: key_path.into(),
+                    ca_path: lookup(KEY_CA_PATH).map(PathBuf::from),
+                })
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(UStatus::fail_with_code(
+                    UCode::INVALID_ARGUMENT,
+                    "client certificate requires both certificate and private key path",
+                ))
+            }
+            (None, None) => {}
+        }
+        match (lookup(KEY_USERNAME), lookup(KEY_PASSWORD)) {
+            (Some(username), Some(password)) => {
+                Ok(Credentials::UsernamePassword { username, password })
+            }
+            (Some(_), None) => Err(UStatus::fail_with_code(
+                UCode::INVALID_ARGUMENT,
+                "username requires password",
+            )),
+            _ => Err(UStatus::fail_with_code(
+                UCode::NOT_FOUND,
+                "no credentials configured",
+            )),
+        }
+    }
+}
+
+/// A source of credentials that a transport uses for authenticating to its infrastructure.
+///
+/// Transports should invoke [`CredentialsProvider::get_credentials`] each time they (re-)connect
+/// to the infrastructure instead of caching the credentials. This allows implementations to
+/// support rotation of credentials.
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+pub trait CredentialsProvider: Send + Sync {
+    /// Gets the credentials to use for authentication.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the credentials are not available.
+    fn get_credentials(&self) -> Result<Credentials, UStatus>;
+}
+
+/// A provider that reads credentials from environment variables.
+///
+/// The variables' names consist of a prefix followed by an underscore and one of
+/// `USERNAME`, `PASSWORD`, `TOKEN`, `CERT_PATH`, `KEY_PATH` or `CA_PATH`.
+/// The variables are read on each invocation of [`CredentialsProvider::get_credentials`].
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{Credentials, CredentialsProvider, EnvCredentialsProvider};
+///
+/// std::env::set_var("MY_BROKER_TOKEN", "secret");
+/// let provider = EnvCredentialsProvider::new("MY_BROKER");
+/// assert_eq!(
+///     provider.get_credentials().unwrap(),
+///     Credentials::Token("secret".to_string())
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct EnvCredentialsProvider {
+    prefix: String,
+}
+
+impl EnvCredentialsProvider {
+    /// Creates a new provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix of the environment variables' names.
+    pub fn new<T: Into<String>>(prefix: T) -> Self {
+        EnvCredentialsProvider {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl CredentialsProvider for EnvCredentialsProvider {
+    fn get_credentials(&self) -> Result<Credentials, UStatus> {
+        Credentials::from_properties(|key| {
+            std::env::var(format!("{}_{}", self.prefix, key.to_ascii_uppercase())).ok()
+        })
+    }
+}
+
+/// A provider that reads credentials from a file.
+///
+/// The file contains one `key=value` pair per line, using the keys `username`, `password`, `token`,
+/// `cert_path`, `key_path` or `ca_path`. Empty lines and lines starting with `#` are ignored.
+/// Relative certificate and key paths are resolved against the directory containing the file.
+///
+/// The file is read on each invocation of [`CredentialsProvider::get_credentials`], so credentials
+/// can be rotated by replacing the file's content.
+#[derive(Clone, Debug)]
+pub struct FileCredentialsProvider {
+    path: PathBuf,
+}
+
+impl FileCredentialsProvider {
+    /// Creates a new provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read the credentials from.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        FileCredentialsProvider {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    fn resolve(&self, path: &str) -> String {
+        match self.path.parent() {
+            Some(dir) if Path::new(path).is_relative() => dir.join(path).display().to_string(),
+            _ => path.to_string(),
+        }
+    }
+}
+
+impl CredentialsProvider for FileCredentialsProvider {
+    fn get_credentials(&self) -> Result<Credentials, UStatus> {
+        let content = std::fs::read_to_string(&self.path).map_err(|e| {
+            UStatus::fail_with_code(
+                UCode::UNAVAILABLE,
+                format!("cannot read credentials file: {}", e),
+            )
+        })?;
+        let mut properties = HashMap::new();
+        for line in content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(UStatus::fail_with_code(
+                    UCode::INVALID_ARGUMENT,
+                    "credentials file contains line that is not a key-value pair",
+                ));
+            };
+            let key = key.trim();
+            let value = value.trim();
+            let value = match key {
+                KEY_CERT_PATH | KEY_KEY_PATH | KEY_CA_PATH => self.resolve(value),
+                _ => value.to_string(),
+            };
+            properties.insert(key.to_string(), value);
+        }
+        Credentials::from_properties(|key| properties.get(key).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    fn write_credentials_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("up-rust-credentials-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_debug_output_does_not_contain_secrets() {
+        let credentials = Credentials::UsernamePassword {
+            username: "user".to_string(),
+            password: "secret".to_string(),
+        };
+        assert!(!format!("{:?}", credentials).contains("secret"));
+        assert!(!format!("{:?}", Credentials::Token("secret".to_string())).contains("secret"));
+    }
+
+    #[test_case(&[("token", "t"), ("username", "u"), ("password", "p")], Some(Credentials::Token("t".to_string())); "token takes precedence")]
+    #[test_case(&[("username", "u"), ("password", "p")], Some(Credentials::UsernamePassword { username: "u".to_string(), password: "p".to_string() }); "for username and password")]
+    #[test_case(&[("cert_path", "c"), ("key_path", "k")], Some(Credentials::Certificate { cert_path: "c".into(), key_path: "k".into(), ca_path: None }); "for client certificate")]
+    #[test_case(&[("cert_path", "c")], None; "for certificate without key")]
+    #[test_case(&[("username", "u")], None; "for username without password")]
+    #[test_case(&[], None; "for no properties")]
+    fn test_from_properties(properties: &[(&str, &str)], expected: Option<Credentials>) {
+        let result = Credentials::from_properties(|key| {
+            properties
+                .iter()
+                .find(|(k, _v)| *k == key)
+                .map(|(_k, v)| v.to_string())
+        });
+        assert_eq!(result.ok(), expected);
+    }
+
+    #[test]
+    fn test_env_provider_reads_variables() {
+        std::env::set_var("UP_RUST_TEST_ENV_PROVIDER_USERNAME", "user");
+        std::env::set_var("UP_RUST_TEST_ENV_PROVIDER_PASSWORD", "pwd");
+        let provider = EnvCredentialsProvider::new("UP_RUST_TEST_ENV_PROVIDER");
+        assert_eq!(
+            provider.get_credentials().unwrap(),
+            Credentials::UsernamePassword {
+                username: "user".to_string(),
+                password: "pwd".to_string()
+            }
+        );
+        assert!(EnvCredentialsProvider::new("UP_RUST_TEST_UNDEFINED")
+            .get_credentials()
+            .is_err_and(|e| e.get_code() == UCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_file_provider_resolves_relative_paths() {
+        let path = write_credentials_file(
+            "mtls.properties",
+            "# client certificate\ncert_path = client.pem\nkey_path=/etc/keys/client.key\n\n",
+        );
+        let provider = FileCredentialsProvider::new(&path);
+        assert_eq!(
+            provider.get_credentials().unwrap(),
+            Credentials::Certificate {
+                cert_path: path.parent().unwrap().join("client.pem"),
+                key_path: "/etc/keys/client.key".into(),
+                ca_path: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_file_provider_picks_up_rotated_credentials() {
+        let path = write_credentials_file("token.properties", "token=first");
+        let provider = FileCredentialsProvider::new(&path);
+        assert_eq!(
+            provider.get_credentials().unwrap(),
+            Credentials::Token("first".to_string())
+        );
+
+        std::fs::write(&path, "token=second").unwrap();
+        assert_eq!(
+            provider.get_credentials().unwrap(),
+            Credentials::Token("second".to_string())
+        );
+    }
+
+    #[test]
+    fn test_file_provider_fails_for_invalid_file() {
+        let path = write_credentials_file("invalid.properties", "token");
+        assert!(FileCredentialsProvider::new(path)
+            .get_credentials()
+            .is_err_and(|e| e.get_code() == UCode::INVALID_ARGUMENT));
+        assert!(FileCredentialsProvider::new("/non-existing/credentials")
+            .get_credentials()
+            .is_err_and(|e| e.get_code() == UCode::UNAVAILABLE));
+    }
+}
@@ -0,0 +1,306 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::{io, thread};
+
+use async_trait::async_trait;
+use tokio::runtime::{Builder, Handle};
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use crate::{UCode, UListener, UMessage, UStatus};
+
+/// A named set of threads that [`IsolatedListener`]s process received messages on.
+///
+/// Each group is backed by its own Tokio runtime, either one that runs on a thread dedicated to
+/// the group or an existing runtime that is managed by the application, e.g. a multi-threaded
+/// runtime for bulk processing. Listeners in different groups therefore never compete for the
+/// same worker threads, so a group of long running or CPU intensive listeners cannot delay the
+/// processing of messages by the listeners of another group.
+#[derive(Debug)]
+pub struct ExecutorGroup {
+    name: String,
+    handle: Handle,
+    // stops the group's dedicated thread, if any, when the group is dropped
+    _shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl ExecutorGroup {
+    /// Creates a group that runs on a new, dedicated thread.
+    ///
+    /// The thread is named `up-executor-<name>` and keeps running until the group is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the thread or its runtime cannot be created.
+    pub fn dedicated_thread<T: Into<String>>(name: T) -> io::Result<Self> {
+        let name = name.into();
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        let handle = runtime.handle().clone();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let group_name = name.clone();
+        thread::Builder::new()
+            .name(format!("up-executor-{name}"))
+            .spawn(move || {
+                // the receiver completes when the group has been dropped
+                let _ = runtime.block_on(shutdown_rx);
+                debug!(
+                    group = group_name.as_str(),
+                    "executor group has been dropped, stopping thread"
+                );
+            })?;
+        Ok(ExecutorGroup {
+            name,
+            handle,
+            _shutdown: Some(shutdown_tx),
+        })
+    }
+
+    /// Creates a group that runs on an existing runtime.
+    ///
+    /// The runtime needs to be kept alive by the application for as long as the group is in use.
+    /// Messages that are received after the runtime has been shut down are discarded.
+    pub fn with_runtime<T: Into<String>>(name: T, handle: Handle) -> Self {
+        ExecutorGroup {
+            name: name.into(),
+            handle,
+            _shutdown: None,
+        }
+    }
+
+    /// Gets the name of this group.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A [`UListener`] that processes received messages on the threads of an [`ExecutorGroup`].
+///
+/// `on_receive` returns as soon as the message has been handed over to the group's runtime.
+/// Messages are processed concurrently, i.e. the wrapped listener may see messages in a different
+/// order than they have been received in. Listeners that need to be protected from an excessive
+/// number of pending messages can additionally be wrapped in a
+/// [`PooledListener`](crate::PooledListener).
+///
+/// The same `Arc` needs to be used for unregistering the listener again.
+pub struct IsolatedListener {
+    listener: Arc<dyn UListener>,
+    group: Arc<ExecutorGroup>,
+}
+
+impl IsolatedListener {
+    /// Creates a new listener.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to pass on received messages to.
+    /// * `group` - The group to process the messages on. The group is kept alive as long as
+    ///   the listener exists.
+    pub fn new(listener: Arc<dyn UListener>, group: Arc<ExecutorGroup>) -> Self {
+        IsolatedListener { listener, group }
+    }
+
+    /// Gets the group that this listener processes messages on.
+    pub fn group(&self) -> &ExecutorGroup {
+        &self.group
+    }
+}
+
+#[async_trait]
+impl UListener for IsolatedListener {
+    async fn on_receive(&self, msg: UMessage) {
+        let listener = self.listener.clone();
+        self.group.handle.spawn(async move {
+            listener.on_receive(msg).await;
+        });
+    }
+}
+
+/// A registry of [`ExecutorGroup`]s that listeners can be assigned to by name.
+///
+/// Applications typically set up the groups once during startup, e.g. based on configuration,
+/// and then assign each listener to one of the groups before registering it with a transport or
+/// the Communication Layer API, e.g. when subscribing to a topic.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use up_rust::{
+///     local_transport::LocalTransport, ExecutorGroup, ExecutorGroups, UListener, UMessage,
+///     UTransport, UUri,
+/// };
+///
+/// struct BrakeMonitor;
+///
+/// #[async_trait::async_trait]
+/// impl UListener for BrakeMonitor {
+///     async fn on_receive(&self, _msg: UMessage) {}
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let groups = ExecutorGroups::new()
+///     .with_group(ExecutorGroup::dedicated_thread("safety")?)
+///     .with_group(ExecutorGroup::dedicated_thread("bulk")?);
+///
+/// let transport = LocalTransport::default();
+/// let topic = UUri::try_from("//my-vehicle/D45/1/A001")?;
+/// let listener = groups.isolate("safety", Arc::new(BrakeMonitor))?;
+/// transport.register_listener(&topic, None, listener).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct ExecutorGroups {
+    groups: HashMap<String, Arc<ExecutorGroup>>,
+}
+
+impl ExecutorGroups {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a group, replacing any existing group of the same name.
+    ///
+    /// Listeners that have been assigned to a replaced group keep using that group.
+    pub fn with_group(mut self, group: ExecutorGroup) -> Self {
+        self.groups.insert(group.name.clone(), Arc::new(group));
+        self
+    }
+
+    /// Gets a group by name.
+    pub fn get(&self, name: &str) -> Option<Arc<ExecutorGroup>> {
+        self.groups.get(name).cloned()
+    }
+
+    /// Assigns a listener to a group.
+    ///
+    /// # Returns
+    ///
+    /// An [`IsolatedListener`] that processes the messages on the group's threads.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`UCode::NOT_FOUND`] if no group with the given name exists.
+    pub fn isolate(
+        &self,
+        group_name: &str,
+        listener: Arc<dyn UListener>,
+    ) -> Result<Arc<dyn UListener>, UStatus> {
+        let group = self.get(group_name).ok_or_else(|| {
+            UStatus::fail_with_code(
+                UCode::NOT_FOUND,
+                format!("no such executor group: {group_name}"),
+            )
+        })?;
+        Ok(Arc::new(IsolatedListener::new(listener, group)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{mpsc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    use crate::{UMessageBuilder, UUri};
+
+    // A listener that reports the name of the thread that it has been invoked on.
+    struct ThreadReporter {
+        threads: UnboundedSender<Option<String>>,
+    }
+
+    #[async_trait]
+    impl UListener for ThreadReporter {
+        async fn on_receive(&self, _msg: UMessage) {
+            let _ = self
+                .threads
+                .send(thread::current().name().map(str::to_string));
+        }
+    }
+
+    // A listener that blocks its thread until it is released.
+    struct BlockingListener {
+        release: Mutex<mpsc::Receiver<()>>,
+    }
+
+    #[async_trait]
+    impl UListener for BlockingListener {
+        async fn on_receive(&self, _msg: UMessage) {
+            let _ = self.release.lock().unwrap().recv();
+        }
+    }
+
+    fn new_message() -> UMessage {
+        UMessageBuilder::publish(UUri::try_from("//vehicle/D45/1/A001").unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_blocked_group_does_not_delay_other_groups() {
+        // GIVEN a listener in the bulk group that blocks its thread
+        let groups = ExecutorGroups::new()
+            .with_group(ExecutorGroup::dedicated_thread("safety").unwrap())
+            .with_group(ExecutorGroup::dedicated_thread("bulk").unwrap());
+        let (release_tx, release_rx) = mpsc::channel();
+        let bulk_listener = groups
+            .isolate(
+                "bulk",
+                Arc::new(BlockingListener {
+                    release: Mutex::new(release_rx),
+                }),
+            )
+            .unwrap();
+        let (threads_tx, mut threads_rx) = unbounded_channel();
+        let safety_listener = groups
+            .isolate(
+                "safety",
+                Arc::new(ThreadReporter {
+                    threads: threads_tx,
+                }),
+            )
+            .unwrap();
+
+        // WHEN both listeners receive a message
+        bulk_listener.on_receive(new_message()).await;
+        safety_listener.on_receive(new_message()).await;
+
+        // THEN the listener in the safety group processes the message on its group's thread
+        let thread = tokio::time::timeout(Duration::from_secs(2), threads_rx.recv())
+            .await
+            .expect("safety listener should not have been blocked by bulk listener");
+        assert_eq!(thread, Some(Some("up-executor-safety".to_string())));
+        release_tx.send(()).unwrap();
+    }
+
+    #[test]
+    fn test_isolate_fails_for_unknown_group() {
+        let groups = ExecutorGroups::new();
+        let (threads_tx, _threads_rx) = unbounded_channel();
+        assert!(groups
+            .isolate(
+                "safety",
+                Arc::new(ThreadReporter {
+                    threads: threads_tx
+                })
+            )
+            .is_err_and(|e| e.get_code() == UCode::NOT_FOUND));
+    }
+}
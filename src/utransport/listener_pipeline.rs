@@ -0,0 +1,312 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use protobuf::MessageFull;
+use tracing::debug;
+
+use crate::{TypedListener, UListener, UMessage, UStatus};
+
+/// Verifies the authenticity of received messages, e.g. by means of checking a signature
+/// conveyed in the message's attributes.
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+pub trait MessageVerifier: Send + Sync {
+    /// Verifies a message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message's authenticity cannot be established.
+    fn verify(&self, message: &UMessage) -> Result<(), UStatus>;
+}
+
+#[derive(Clone)]
+enum Stage {
+    Verify(Arc<dyn MessageVerifier>),
+    #[cfg(feature = "compression")]
+    Decompress {
+        max_size: usize,
+    },
+}
+
+impl Stage {
+    #[cfg_attr(not(feature = "compression"), allow(unused_mut))]
+    fn apply(&self, mut message: UMessage) -> Result<UMessage, UStatus> {
+        match self {
+            Stage::Verify(verifier) => verifier.verify(&message).map(|_| message),
+            #[cfg(feature = "compression")]
+            Stage::Decompress { max_size } => {
+                crate::communication::compression::inflate_payload(&mut message, *max_size)
+                    .map(|_| message)
+            }
+        }
+    }
+}
+
+/// A declarative description of the processing steps to apply to received messages
+/// before they are passed on to a [`UListener`].
+///
+/// The steps are applied in the order in which they have been added to the pipeline.
+/// Messages that fail any of the steps are dropped or passed on to a
+/// [rejection listener](`Self::with_rejection_listener`), if configured.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use protobuf::well_known_types::wrappers::StringValue;
+/// use up_rust::{ListenerPipeline, MessageVerifier, UListener, UMessage, UStatus};
+///
+/// struct AcceptAll;
+///
+/// impl MessageVerifier for AcceptAll {
+///     fn verify(&self, _message: &UMessage) -> Result<(), UStatus> {
+///         Ok(())
+///     }
+/// }
+///
+/// let listener: Arc<dyn UListener> = Arc::new(
+///     ListenerPipeline::new()
+///         .verify(Arc::new(AcceptAll))
+///         .decode::<StringValue>()
+///         .handle(|value: StringValue, _msg: UMessage| async move {
+///             println!("received: {}", value.value);
+///         }),
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct ListenerPipeline {
+    stages: Vec<Stage>,
+    rejection_listener: Option<Arc<dyn UListener>>,
+}
+
+impl ListenerPipeline {
+    /// Creates an empty pipeline which passes on all messages unaltered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a step which verifies the authenticity of messages.
+    pub fn verify(mut self, verifier: Arc<dyn MessageVerifier>) -> Self {
+        self.stages.push(Stage::Verify(verifier));
+        self
+    }
+
+    /// Adds a step which decompresses payloads according to the messages'
+    /// [content encoding](`crate::UAttributes::content_encoding`).
+    ///
+    /// Messages without content encoding are passed on unaltered. Messages whose payload exceeds
+    /// `max_size` bytes after decompression are rejected, see
+    /// [`DEFAULT_MAX_DECOMPRESSED_SIZE`](crate::communication::DEFAULT_MAX_DECOMPRESSED_SIZE)
+    /// for a reasonable limit.
+    #[cfg(feature = "compression")]
+    pub fn decompress(mut self, max_size: usize) -> Self {
+        self.stages.push(Stage::Decompress { max_size });
+        self
+    }
+
+    /// Sets the listener to pass on messages to that have been rejected by any of the steps.
+    pub fn with_rejection_listener(mut self, listener: Arc<dyn UListener>) -> Self {
+        self.rejection_listener = Some(listener);
+        self
+    }
+
+    /// Adds a final step which extracts a protobuf message of a particular type from the payload.
+    ///
+    /// Messages whose payload cannot be extracted are passed on to the rejection listener, if configured.
+    pub fn decode<T: MessageFull + Default>(self) -> DecodingPipeline<T> {
+        DecodingPipeline {
+            pipeline: self,
+            _payload_type: PhantomData,
+        }
+    }
+
+    /// Creates a listener which applies this pipeline's steps to received messages
+    /// before passing them on to another listener.
+    pub fn into_listener(self, listener: Arc<dyn UListener>) -> PipelineListener {
+        PipelineListener {
+            stages: self.stages,
+            rejection_listener: self.rejection_listener,
+            listener,
+        }
+    }
+}
+
+/// A [`ListenerPipeline`] which ends with extracting a protobuf message of type `T`
+/// from the payload of received messages.
+pub struct DecodingPipeline<T> {
+    pipeline: ListenerPipeline,
+    _payload_type: PhantomData<fn() -> T>,
+}
+
+impl<T: MessageFull + Default> DecodingPipeline<T> {
+    /// Creates a listener which applies the pipeline's steps to received messages
+    /// before passing the extracted payload on to a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The function to invoke with the extracted payload and the (processed) message.
+    pub fn handle<F, Fut>(self, handler: F) -> PipelineListener
+    where
+        F: Fn(T, UMessage) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut typed_listener = TypedListener::new(handler);
+        if let Some(rejection_listener) = self.pipeline.rejection_listener.as_ref() {
+            typed_listener = typed_listener.with_dead_letter_listener(rejection_listener.clone());
+        }
+        self.pipeline.into_listener(Arc::new(typed_listener))
+    }
+}
+
+/// A [`UListener`] created from a [`ListenerPipeline`].
+pub struct PipelineListener {
+    stages: Vec<Stage>,
+    rejection_listener: Option<Arc<dyn UListener>>,
+    listener: Arc<dyn UListener>,
+}
+
+#[async_trait]
+impl UListener for PipelineListener {
+    async fn on_receive(&self, msg: UMessage) {
+        let mut message = msg;
+        for stage in &self.stages {
+            let original = self.rejection_listener.as_ref().map(|_| message.clone());
+            match stage.apply(message) {
+                Ok(processed) => message = processed,
+                Err(e) => {
+                    debug!("rejecting received message: {}", e);
+                    if let (Some(listener), Some(original)) =
+                        (self.rejection_listener.as_ref(), original)
+                    {
+                        listener.on_receive(original).await;
+                    }
+                    return;
+                }
+            }
+        }
+        self.listener.on_receive(message).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protobuf::well_known_types::wrappers::StringValue;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{utransport::MockUListener, UCode, UMessageBuilder, UUri};
+
+    fn message_with_payload(value: &str) -> UMessage {
+        let topic = UUri::try_from_parts("my-vehicle", 0x1000, 0x01, 0xA100).unwrap();
+        UMessageBuilder::publish(topic)
+            .build_with_wrapped_protobuf_payload(&StringValue {
+                value: value.to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_passes_verified_messages_to_handler() {
+        // GIVEN a pipeline rejecting messages with a particular payload
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut verifier = MockMessageVerifier::new();
+        verifier.expect_verify().returning(|msg| {
+            if msg.extract_protobuf::<StringValue>().unwrap().value == "forged" {
+                Err(UStatus::fail_with_code(
+                    UCode::UNAUTHENTICATED,
+                    "invalid signature",
+                ))
+            } else {
+                Ok(())
+            }
+        });
+        let mut rejection_listener = MockUListener::new();
+        rejection_listener
+            .expect_on_receive()
+            .once()
+            .return_const(());
+        let listener = ListenerPipeline::new()
+            .verify(Arc::new(verifier))
+            .with_rejection_listener(Arc::new(rejection_listener))
+            .decode::<StringValue>()
+            .handle(move |value: StringValue, _msg| {
+                let tx = tx.clone();
+                async move {
+                    tx.send(value.value).unwrap();
+                }
+            });
+
+        // WHEN receiving a forged and a genuine message
+        listener.on_receive(message_with_payload("forged")).await;
+        listener.on_receive(message_with_payload("genuine")).await;
+
+        // THEN only the genuine message's payload is passed on to the handler
+        assert_eq!(rx.recv().await.unwrap(), "genuine");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_pipeline_decompresses_payload() {
+        // GIVEN a pipeline decompressing payloads
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let value = "Hello World ".repeat(50);
+        let listener = ListenerPipeline::new()
+            .decompress(crate::communication::DEFAULT_MAX_DECOMPRESSED_SIZE)
+            .decode::<StringValue>()
+            .handle(move |value: StringValue, _msg| {
+                let tx = tx.clone();
+                async move {
+                    tx.send(value.value).unwrap();
+                }
+            });
+
+        // WHEN receiving a message with a compressed payload
+        let mut message = message_with_payload(&value);
+        assert!(crate::communication::compression::deflate_payload(&mut message, 0).unwrap());
+        listener.on_receive(message).await;
+
+        // THEN the handler is invoked with the original payload
+        assert_eq!(rx.recv().await.unwrap(), value);
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_pipeline_rejects_payload_exceeding_max_decompressed_size() {
+        // GIVEN a pipeline decompressing payloads to at most 100 bytes
+        let mut handler = MockUListener::new();
+        handler.expect_on_receive().never();
+        let mut rejection_listener = MockUListener::new();
+        rejection_listener
+            .expect_on_receive()
+            .once()
+            .return_const(());
+        let listener = ListenerPipeline::new()
+            .decompress(100)
+            .with_rejection_listener(Arc::new(rejection_listener))
+            .into_listener(Arc::new(handler));
+
+        // WHEN receiving a message with a payload that decompresses to more than 100 bytes
+        let mut message = message_with_payload(&"Hello World ".repeat(50));
+        assert!(crate::communication::compression::deflate_payload(&mut message, 0).unwrap());
+        listener.on_receive(message).await;
+
+        // THEN the message is passed on to the rejection listener only
+        // (verified by the mocks' expectations)
+    }
+}
@@ -0,0 +1,402 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::VecDeque;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+use crate::{task_tracker::TaskTracker, UListener, UMessage};
+
+/// Determines what a [`PooledListener`] does with a received message if its queue is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PoolOverflowPolicy {
+    /// Wait for a worker to take a message from the queue.
+    ///
+    /// This blocks the transport's receive task until space becomes available but does not
+    /// lose any messages.
+    #[default]
+    Block,
+    /// Discard the received message.
+    DropNewest,
+    /// Discard the message that has been waiting in the queue for the longest time
+    /// and enqueue the received message.
+    DropOldest,
+}
+
+struct MessageQueue {
+    messages: Mutex<VecDeque<UMessage>>,
+    capacity: usize,
+    message_available: Notify,
+    space_available: Notify,
+    closed: AtomicBool,
+}
+
+impl MessageQueue {
+    fn pop(&self) -> Option<UMessage> {
+        let msg = self
+            .messages
+            .lock()
+            .expect("lock on message queue is poisoned")
+            .pop_front();
+        if msg.is_some() {
+            self.space_available.notify_one();
+        }
+        msg
+    }
+}
+
+/// A [`UListener`] that hands over received messages to a pool of workers which
+/// forward the messages to another listener.
+///
+/// Transports usually invoke [`UListener::on_receive`] on the task that receives messages
+/// from the underlying protocol. A listener that takes a long time to process a message
+/// therefore delays the delivery of all subsequent messages. Wrapping such a listener in
+/// a `PooledListener` makes `on_receive` return as soon as the message has been put into a
+/// bounded queue, from which a fixed number of workers take the messages for processing.
+/// What happens if the queue is full is determined by the listener's [`PoolOverflowPolicy`].
+///
+/// The workers keep running until the listener is dropped and all queued messages have been processed.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use up_rust::{
+///     local_transport::LocalTransport, PoolOverflowPolicy, PooledListener, UListener, UMessage,
+///     UTransport, UUri,
+/// };
+///
+/// struct SlowListener;
+///
+/// #[async_trait::async_trait]
+/// impl UListener for SlowListener {
+///     async fn on_receive(&self, _msg: UMessage) {
+///         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let transport = LocalTransport::default();
+/// let listener = PooledListener::new(Arc::new(SlowListener), 4, 100)
+///     .with_overflow_policy(PoolOverflowPolicy::DropOldest);
+/// let topic = UUri::try_from("//my-vehicle/D45/1/A001").unwrap();
+/// assert!(transport
+///     .register_listener(&topic, None, Arc::new(listener))
+///     .await
+///     .is_ok());
+/// # }
+/// ```
+pub struct PooledListener {
+    queue: Arc<MessageQueue>,
+    tasks: TaskTracker,
+    overflow_policy: PoolOverflowPolicy,
+    dropped_messages: AtomicU64,
+}
+
+impl PooledListener {
+    /// Creates a new listener using the [`PoolOverflowPolicy::Block`] policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `delegate` - The listener to forward received messages to.
+    /// * `pool_size` - The number of workers that concurrently forward messages to the delegate.
+    /// * `queue_capacity` - The maximum number of messages waiting for a worker.
+    ///
+    /// # Panics
+    ///
+    /// * if the pool size or the queue capacity is 0.
+    /// * if not called from within the context of a Tokio runtime.
+    pub fn new(delegate: Arc<dyn UListener>, pool_size: usize, queue_capacity: usize) -> Self {
+        assert!(pool_size > 0, "pool size must be greater than 0");
+        assert!(queue_capacity > 0, "queue capacity must be greater than 0");
+
+        let queue = Arc::new(MessageQueue {
+            messages: Mutex::new(VecDeque::with_capacity(queue_capacity)),
+            capacity: queue_capacity,
+            message_available: Notify::new(),
+            space_available: Notify::new(),
+            closed: AtomicBool::new(false),
+        });
+        let tasks = TaskTracker::new("pooled-listener");
+        for worker in 0..pool_size {
+            let queue = queue.clone();
+            let delegate = delegate.clone();
+            tasks.spawn(format!("worker-{}", worker), async move {
+                loop {
+                    let mut message_available = pin!(queue.message_available.notified());
+                    message_available.as_mut().enable();
+                    if let Some(msg) = queue.pop() {
+                        delegate.on_receive(msg).await;
+                        continue;
+                    }
+                    if queue.closed.load(Ordering::Acquire) {
+                        break;
+                    }
+                    message_available.await;
+                }
+                debug!(worker, "listener has been dropped, stopping worker");
+            });
+        }
+        PooledListener {
+            queue,
+            tasks,
+            overflow_policy: PoolOverflowPolicy::default(),
+            dropped_messages: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets the policy to apply to received messages if the queue is full.
+    pub fn with_overflow_policy(mut self, overflow_policy: PoolOverflowPolicy) -> Self {
+        self.overflow_policy = overflow_policy;
+        self
+    }
+
+    /// Gets the tracker of the worker tasks.
+    pub fn task_tracker(&self) -> &TaskTracker {
+        &self.tasks
+    }
+
+    /// Gets the number of messages that have been discarded because the queue was full.
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped_messages.load(Ordering::Relaxed)
+    }
+
+    fn record_dropped_message(&self) {
+        self.dropped_messages.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            overflow_policy = ?self.overflow_policy,
+            "queue is full, discarding message"
+        );
+    }
+}
+
+impl Drop for PooledListener {
+    fn drop(&mut self) {
+        self.queue.closed.store(true, Ordering::Release);
+        self.queue.message_available.notify_waiters();
+    }
+}
+
+#[async_trait]
+impl UListener for PooledListener {
+    async fn on_receive(&self, msg: UMessage) {
+        loop {
+            let mut space_available = pin!(self.queue.space_available.notified());
+            space_available.as_mut().enable();
+            {
+                let mut messages = self
+                    .queue
+                    .messages
+                    .lock()
+                    .expect("lock on message queue is poisoned");
+                if messages.len() >= self.queue.capacity {
+                    match self.overflow_policy {
+                        PoolOverflowPolicy::Block => {}
+                        PoolOverflowPolicy::DropNewest => {
+                            drop(messages);
+                            self.record_dropped_message();
+                            return;
+                        }
+                        PoolOverflowPolicy::DropOldest => {
+                            messages.pop_front();
+                            messages.push_back(msg);
+                            drop(messages);
+                            self.record_dropped_message();
+                            self.queue.message_available.notify_one();
+                            return;
+                        }
+                    }
+                } else {
+                    messages.push_back(msg);
+                    drop(messages);
+                    self.queue.message_available.notify_one();
+                    return;
+                }
+            }
+            space_available.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+    use tokio::sync::Semaphore;
+
+    use crate::{UMessageBuilder, UUri};
+
+    // A listener that records the IDs of received messages and which waits for
+    // a permit before returning from on_receive.
+    struct GatedListener {
+        gate: Arc<Semaphore>,
+        received: Mutex<Vec<u16>>,
+        receipt: Notify,
+    }
+
+    impl GatedListener {
+        fn new(initial_permits: usize) -> Self {
+            GatedListener {
+                gate: Arc::new(Semaphore::new(initial_permits)),
+                received: Mutex::new(vec![]),
+                receipt: Notify::new(),
+            }
+        }
+
+        fn received(&self) -> Vec<u16> {
+            self.received.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl UListener for GatedListener {
+        async fn on_receive(&self, msg: UMessage) {
+            let resource_id = msg
+                .attributes
+                .source
+                .as_ref()
+                .map_or(0, |source| source.resource_id as u16);
+            self.received.lock().unwrap().push(resource_id);
+            self.receipt.notify_one();
+            self.gate.acquire().await.unwrap().forget();
+        }
+    }
+
+    fn new_message(resource_id: u16) -> UMessage {
+        UMessageBuilder::publish(
+            UUri::try_from_parts("my-vehicle", 0xA100, 0x01, resource_id).unwrap(),
+        )
+        .build()
+        .unwrap()
+    }
+
+    async fn wait_for_received(listener: &GatedListener, expected: &[u16]) {
+        let result = tokio::time::timeout(Duration::from_secs(2), async {
+            while listener.received() != expected {
+                listener.receipt.notified().await;
+            }
+        })
+        .await;
+        assert!(
+            result.is_ok(),
+            "expected {:?} but received {:?}",
+            expected,
+            listener.received()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_on_receive_does_not_wait_for_delegate() {
+        // GIVEN a pooled listener delegating to a listener that blocks until released
+        let delegate = Arc::new(GatedListener::new(0));
+        let listener = PooledListener::new(delegate.clone(), 2, 10);
+
+        // WHEN receiving three messages
+        for resource_id in 0x8001..=0x8003 {
+            tokio::time::timeout(
+                Duration::from_secs(1),
+                listener.on_receive(new_message(resource_id)),
+            )
+            .await
+            .expect("on_receive should not be blocked by delegate");
+        }
+
+        // THEN two messages are processed concurrently
+        wait_for_received(&delegate, &[0x8001, 0x8002]).await;
+        // and the third message is processed once a worker becomes available
+        delegate.gate.add_permits(3);
+        wait_for_received(&delegate, &[0x8001, 0x8002, 0x8003]).await;
+        assert_eq!(listener.dropped_messages(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest_discards_received_message() {
+        // GIVEN a pooled listener with a single worker and queue slot
+        let delegate = Arc::new(GatedListener::new(0));
+        let listener = PooledListener::new(delegate.clone(), 1, 1)
+            .with_overflow_policy(PoolOverflowPolicy::DropNewest);
+        listener.on_receive(new_message(0x8001)).await;
+        wait_for_received(&delegate, &[0x8001]).await;
+        listener.on_receive(new_message(0x8002)).await;
+
+        // WHEN receiving another message while the queue is full
+        listener.on_receive(new_message(0x8003)).await;
+
+        // THEN the newly received message is discarded
+        assert_eq!(listener.dropped_messages(), 1);
+        delegate.gate.add_permits(3);
+        wait_for_received(&delegate, &[0x8001, 0x8002]).await;
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_discards_queued_message() {
+        // GIVEN a pooled listener with a single worker and queue slot
+        let delegate = Arc::new(GatedListener::new(0));
+        let listener = PooledListener::new(delegate.clone(), 1, 1)
+            .with_overflow_policy(PoolOverflowPolicy::DropOldest);
+        listener.on_receive(new_message(0x8001)).await;
+        wait_for_received(&delegate, &[0x8001]).await;
+        listener.on_receive(new_message(0x8002)).await;
+
+        // WHEN receiving another message while the queue is full
+        listener.on_receive(new_message(0x8003)).await;
+
+        // THEN the queued message is discarded
+        assert_eq!(listener.dropped_messages(), 1);
+        delegate.gate.add_permits(3);
+        wait_for_received(&delegate, &[0x8001, 0x8003]).await;
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_space_in_queue() {
+        // GIVEN a pooled listener with a single worker and queue slot
+        let delegate = Arc::new(GatedListener::new(0));
+        let listener = Arc::new(PooledListener::new(delegate.clone(), 1, 1));
+        listener.on_receive(new_message(0x8001)).await;
+        wait_for_received(&delegate, &[0x8001]).await;
+        listener.on_receive(new_message(0x8002)).await;
+
+        // WHEN receiving another message while the queue is full
+        let blocked_listener = listener.clone();
+        let blocked_receive =
+            tokio::spawn(async move { blocked_listener.on_receive(new_message(0x8003)).await });
+
+        // THEN on_receive does not return before a worker has taken a message from the queue
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!blocked_receive.is_finished());
+        delegate.gate.add_permits(3);
+        assert!(blocked_receive.await.is_ok());
+        wait_for_received(&delegate, &[0x8001, 0x8002, 0x8003]).await;
+        assert_eq!(listener.dropped_messages(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_workers_process_queued_messages_after_drop() {
+        let delegate = Arc::new(GatedListener::new(0));
+        let listener = PooledListener::new(delegate.clone(), 1, 5);
+        for resource_id in 0x8001..=0x8003 {
+            listener.on_receive(new_message(resource_id)).await;
+        }
+
+        drop(listener);
+        delegate.gate.add_permits(3);
+        wait_for_received(&delegate, &[0x8001, 0x8002, 0x8003]).await;
+    }
+}
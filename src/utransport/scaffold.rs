@@ -0,0 +1,474 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides building blocks for implementing [`UTransport`](crate::UTransport)s.
+
+Most transports need to keep track of the listeners that have been registered for
+source and sink filters, need to find the listeners matching an incoming message and need to
+dispatch incoming messages to these listeners. The [`ListenerRegistry`] and [`spawn_dispatch_loop`]
+implement these aspects in a way that is consistent with the
+[uProtocol Transport Layer specification](https://github.com/eclipse-uprotocol/up-spec/blob/v1.6.0-alpha.4/up-l1/README.adoc),
+so that transport implementations can focus on the specifics of the underlying messaging technology.
+
+# Examples
+
+```rust
+use std::sync::Arc;
+use async_trait::async_trait;
+use up_rust::{UListener, UMessage, UStatus, UTransport, UUri};
+use up_rust::scaffold::{self, ListenerRegistry};
+
+struct MyTransport {
+    listeners: ListenerRegistry,
+}
+
+#[async_trait]
+impl UTransport for MyTransport {
+    async fn send(&self, _message: UMessage) -> Result<(), UStatus> {
+        Err(scaffold::unavailable("not connected"))
+    }
+
+    async fn register_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.listeners.register(source_filter, sink_filter, listener).await
+    }
+
+    async fn unregister_listener(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        self.listeners.unregister(source_filter, sink_filter, listener).await
+    }
+}
+```
+*/
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::{
+    task_tracker::TaskTracker, ComparableListener, UCode, UListener, UMessage, UStatus, UUri,
+};
+
+/// Creates an error indicating that a listener has already been registered.
+pub fn already_exists<T: Into<String>>(message: T) -> UStatus {
+    UStatus::fail_with_code(UCode::ALREADY_EXISTS, message)
+}
+
+/// Creates an error indicating that a listener or resource does not exist.
+pub fn not_found<T: Into<String>>(message: T) -> UStatus {
+    UStatus::fail_with_code(UCode::NOT_FOUND, message)
+}
+
+/// Creates an error indicating that the maximum number of listeners has been reached.
+pub fn resource_exhausted<T: Into<String>>(message: T) -> UStatus {
+    UStatus::fail_with_code(UCode::RESOURCE_EXHAUSTED, message)
+}
+
+/// Creates an error indicating that the underlying messaging infrastructure cannot be reached.
+pub fn unavailable<T: Into<String>>(message: T) -> UStatus {
+    UStatus::fail_with_code(UCode::UNAVAILABLE, message)
+}
+
+/// Creates an error indicating that a function is not supported by the transport.
+pub fn unimplemented<T: Into<String>>(message: T) -> UStatus {
+    UStatus::fail_with_code(UCode::UNIMPLEMENTED, message)
+}
+
+/// Creates an error indicating that a given argument is invalid.
+pub fn invalid_argument<T: Into<String>>(message: T) -> UStatus {
+    UStatus::fail_with_code(UCode::INVALID_ARGUMENT, message)
+}
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct RegisteredListener {
+    source_filter: UUri,
+    sink_filter: Option<UUri>,
+    listener: ComparableListener,
+}
+
+impl RegisteredListener {
+    fn matches(&self, source: &UUri, sink: Option<&UUri>) -> bool {
+        if !self.source_filter.matches(source) {
+            return false;
+        }
+
+        if let Some(pattern) = &self.sink_filter {
+            sink.map_or(false, |candidate_sink| pattern.matches(candidate_sink))
+        } else {
+            sink.is_none()
+        }
+    }
+
+    fn matches_msg(&self, msg: &UMessage) -> bool {
+        let Some(attributes) = msg.attributes.as_ref() else {
+            return false;
+        };
+        attributes
+            .source
+            .as_ref()
+            .is_some_and(|source| self.matches(source, attributes.sink.as_ref()))
+    }
+}
+
+type ListenerSet = HashSet<RegisteredListener>;
+
+/// Keeps track of the listeners that have been registered for source and sink filters.
+///
+/// Filters may contain wildcards as defined by [`UUri::matches`].
+///
+/// The registry keeps the registered listeners in an immutable snapshot which is replaced
+/// with an updated copy whenever a listener is (un)registered. Looking up the listeners
+/// for a message therefore only needs to acquire a reference to the current snapshot. This requires
+/// a read lock which is held just for cloning the reference, i.e. a lookup never waits for other
+/// messages being dispatched and at most waits for a concurrent (un)registration to swap in its
+/// updated snapshot, but not for it to copy and modify the set of listeners.
+/// This favors processes which dispatch many messages over processes which frequently
+/// (un)register listeners.
+#[derive(Default)]
+pub struct ListenerRegistry {
+    max_listeners: Option<usize>,
+    // serializes modifications of the snapshot
+    update_lock: Mutex<()>,
+    snapshot: RwLock<Arc<ListenerSet>>,
+}
+
+impl ListenerRegistry {
+    /// Creates a new registry without a limit on the number of listeners.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of listeners that can be registered.
+    ///
+    /// Registering more listeners fails with [`UCode::RESOURCE_EXHAUSTED`].
+    pub fn with_max_listeners(mut self, max_listeners: usize) -> Self {
+        self.max_listeners = Some(max_listeners);
+        self
+    }
+
+    fn current_snapshot(&self) -> Arc<ListenerSet> {
+        // the lock is only held for cloning the Arc
+        self.snapshot
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replaces the current snapshot with a modified copy.
+    // the listeners' filters are never modified while contained in the set
+    #[allow(clippy::mutable_key_type)]
+    fn update<R, F>(&self, modify: F) -> R
+    where
+        F: FnOnce(&mut ListenerSet) -> R,
+    {
+        let _guard = self
+            .update_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut listeners = ListenerSet::clone(&self.current_snapshot());
+        let result = modify(&mut listeners);
+        *self
+            .snapshot
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Arc::new(listeners);
+        result
+    }
+
+    /// Registers a listener for a source and sink filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`UCode::ALREADY_EXISTS`] if the listener has already been registered
+    /// for the same filters, or [`UCode::RESOURCE_EXHAUSTED`] if the maximum number of listeners
+    /// has been reached.
+    pub async fn register(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let registered_listener = RegisteredListener {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.map(|u| u.to_owned()),
+            listener: ComparableListener::new(listener),
+        };
+        self.update(|listeners| {
+            if listeners.contains(&registered_listener) {
+                return Err(already_exists("listener already registered for filters"));
+            }
+            if self
+                .max_listeners
+                .is_some_and(|max_listeners| listeners.len() >= max_listeners)
+            {
+                return Err(resource_exhausted("maximum number of listeners reached"));
+            }
+            listeners.insert(registered_listener);
+            Ok(())
+        })
+    }
+
+    /// Unregisters a listener that has been registered for a source and sink filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`UCode::NOT_FOUND`] if the listener has not been registered
+    /// for the given filters.
+    pub async fn unregister(
+        &self,
+        source_filter: &UUri,
+        sink_filter: Option<&UUri>,
+        listener: Arc<dyn UListener>,
+    ) -> Result<(), UStatus> {
+        let registered_listener = RegisteredListener {
+            source_filter: source_filter.to_owned(),
+            sink_filter: sink_filter.map(|u| u.to_owned()),
+            listener: ComparableListener::new(listener),
+        };
+        if self.update(|listeners| listeners.remove(&registered_listener)) {
+            Ok(())
+        } else {
+            Err(not_found("no such listener registered for filters"))
+        }
+    }
+
+    /// Unregisters all listeners.
+    ///
+    /// # Returns
+    ///
+    /// The number of listeners that have been unregistered.
+    pub async fn clear(&self) -> usize {
+        self.update(|listeners| {
+            let count = listeners.len();
+            listeners.clear();
+            count
+        })
+    }
+
+    /// Gets the number of registered listeners.
+    pub async fn len(&self) -> usize {
+        self.current_snapshot().len()
+    }
+
+    /// Checks if no listeners are registered.
+    pub async fn is_empty(&self) -> bool {
+        self.current_snapshot().is_empty()
+    }
+
+    /// Gets the source and sink filters of all registered listeners.
+    pub async fn filters(&self) -> Vec<(UUri, Option<UUri>)> {
+        self.current_snapshot()
+            .iter()
+            .map(|listener| {
+                (
+                    listener.source_filter.to_owned(),
+                    listener.sink_filter.to_owned(),
+                )
+            })
+            .collect()
+    }
+
+    /// Gets all listeners whose filters match a message's source and sink.
+    pub async fn matching_listeners(&self, message: &UMessage) -> Vec<Arc<dyn UListener>> {
+        self.current_snapshot()
+            .iter()
+            .filter(|listener| listener.matches_msg(message))
+            .map(|listener| listener.listener.into_inner())
+            .collect()
+    }
+
+    /// Dispatches a message to all listeners whose filters match the message's source and sink.
+    ///
+    /// The listeners are invoked one after the other on the current task. Listeners may
+    /// (un)register listeners while being invoked. Such changes do not affect the dispatching
+    /// of the current message.
+    ///
+    /// # Returns
+    ///
+    /// The number of listeners that the message has been dispatched to.
+    pub async fn dispatch(&self, message: UMessage) -> usize {
+        let listeners = self.matching_listeners(&message).await;
+        for listener in &listeners {
+            listener.on_receive(message.clone()).await;
+        }
+        listeners.len()
+    }
+}
+
+/// Spawns a task that dispatches all messages received from a channel to the matching listeners.
+///
+/// Transports that receive messages from the underlying messaging infrastructure on a
+/// dedicated thread or task can forward the messages to the channel.
+/// The task runs until all senders of the channel have been dropped.
+///
+/// # Arguments
+///
+/// * `task_tracker` - The tracker to spawn the task on.
+/// * `registry` - The registry containing the listeners to dispatch messages to.
+/// * `incoming` - The channel to receive messages from.
+///
+/// # Panics
+///
+/// if not called from within the context of a Tokio runtime.
+pub fn spawn_dispatch_loop(
+    task_tracker: &TaskTracker,
+    registry: Arc<ListenerRegistry>,
+    mut incoming: UnboundedReceiver<UMessage>,
+) -> JoinHandle<()> {
+    task_tracker.spawn("dispatcher", async move {
+        while let Some(message) = incoming.recv().await {
+            registry.dispatch(message).await;
+        }
+        debug!("incoming channel has been closed, stopping dispatcher");
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{utransport::MockUListener, UMessageBuilder};
+
+    fn topic() -> UUri {
+        UUri::try_from_parts("vehicle", 0x1000, 0x01, 0x8001).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_registry_enforces_listener_limit() {
+        // GIVEN a registry for at most one listener
+        let registry = ListenerRegistry::new().with_max_listeners(1);
+        let listener = Arc::new(MockUListener::new());
+        registry
+            .register(&topic(), None, listener.clone())
+            .await
+            .expect("should have been able to register listener");
+
+        // WHEN registering the same listener again
+        // THEN registration fails with ALREADY_EXISTS
+        assert!(registry
+            .register(&topic(), None, listener.clone())
+            .await
+            .is_err_and(|e| e.get_code() == UCode::ALREADY_EXISTS));
+
+        // WHEN registering another listener
+        // THEN registration fails with RESOURCE_EXHAUSTED
+        assert!(registry
+            .register(&UUri::any(), None, Arc::new(MockUListener::new()))
+            .await
+            .is_err_and(|e| e.get_code() == UCode::RESOURCE_EXHAUSTED));
+
+        // WHEN unregistering the listener twice
+        // THEN the second attempt fails with NOT_FOUND
+        assert!(registry
+            .unregister(&topic(), None, listener.clone())
+            .await
+            .is_ok());
+        assert!(registry
+            .unregister(&topic(), None, listener)
+            .await
+            .is_err_and(|e| e.get_code() == UCode::NOT_FOUND));
+        assert!(registry.is_empty().await);
+    }
+
+    struct SelfUnregisteringListener {
+        registry: Arc<ListenerRegistry>,
+        me: std::sync::OnceLock<std::sync::Weak<SelfUnregisteringListener>>,
+    }
+
+    #[async_trait::async_trait]
+    impl UListener for SelfUnregisteringListener {
+        async fn on_receive(&self, _msg: UMessage) {
+            if let Some(me) = self.me.get().and_then(|me| me.upgrade()) {
+                let result = self.registry.unregister(&topic(), None, me).await;
+                assert!(result.is_ok());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_listener_can_unregister_itself_during_dispatch() {
+        // GIVEN a registry with a listener that unregisters itself when invoked
+        let registry = Arc::new(ListenerRegistry::new());
+        let listener = Arc::new(SelfUnregisteringListener {
+            registry: registry.clone(),
+            me: std::sync::OnceLock::new(),
+        });
+        let _ = listener.me.set(Arc::downgrade(&listener));
+        registry
+            .register(&topic(), None, listener.clone())
+            .await
+            .unwrap();
+
+        // WHEN dispatching a message to the listener
+        let message = UMessageBuilder::publish(topic()).build().unwrap();
+        let dispatched = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            registry.dispatch(message.clone()),
+        )
+        .await;
+
+        // THEN dispatching completes without deadlocking
+        assert_eq!(dispatched, Ok(1));
+        // and the listener is no longer registered
+        assert!(registry.is_empty().await);
+        assert_eq!(registry.dispatch(message).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_loop_invokes_matching_listeners() {
+        // GIVEN a registry with a listener using a wildcard filter
+        let registry = Arc::new(ListenerRegistry::new());
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (notify_tx, mut notify_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut matching_listener = MockUListener::new();
+        matching_listener
+            .expect_on_receive()
+            .once()
+            .returning(move |msg| {
+                notify_tx.send(msg).unwrap();
+            });
+        let mut other_listener = MockUListener::new();
+        other_listener.expect_on_receive().never();
+        registry
+            .register(&UUri::any(), None, Arc::new(matching_listener))
+            .await
+            .unwrap();
+        registry
+            .register(&topic(), Some(&UUri::any()), Arc::new(other_listener))
+            .await
+            .unwrap();
+        let tasks = TaskTracker::new("test");
+        let dispatch_loop = spawn_dispatch_loop(&tasks, registry, rx);
+
+        // WHEN a message is received
+        let message = UMessageBuilder::publish(topic()).build().unwrap();
+        tx.send(message.clone()).unwrap();
+
+        // THEN it is dispatched to the matching listener only
+        assert_eq!(notify_rx.recv().await, Some(message));
+
+        // and the dispatch loop stops once the channel has been closed
+        drop(tx);
+        assert!(dispatch_loop.await.is_ok());
+    }
+}
@@ -12,9 +12,10 @@
  ********************************************************************************/
 
 use rand::RngCore;
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 use std::{hash::Hash, str::FromStr};
 
+use crate::time_source::TimeSource;
 pub use crate::up_core_api::uuid::UUID;
 
 use uuid_simd::{AsciiCase, Out};
@@ -146,9 +147,12 @@ impl UUID {
 
     /// Creates a new UUID that can be used for uProtocol messages.
     ///
+    /// The timestamp is taken from the [configured time source](`crate::time_source`),
+    /// which is the system clock by default.
+    ///
     /// # Panics
     ///
-    /// if the system clock is set to an instant before the UNIX Epoch.
+    /// if the time source's clock is set to an instant before the UNIX Epoch.
     ///
     /// # Examples
     ///
@@ -161,9 +165,33 @@ impl UUID {
     // [impl->dsn~uuid-spec~1]
     // [utest->dsn~uuid-spec~1]
     pub fn build() -> UUID {
-        let duration_since_unix_epoch = SystemTime::UNIX_EPOCH
-            .elapsed()
-            .expect("current system time is set to a point in time before UNIX Epoch");
+        let duration_since_unix_epoch = crate::time_source::now()
+            .expect("current time is set to a point in time before UNIX Epoch");
+        Self::build_for_timestamp(duration_since_unix_epoch)
+    }
+
+    /// Creates a new UUID that can be used for uProtocol messages, using a particular time source.
+    ///
+    /// [`UUID::build`] uses the [globally configured](`crate::time_source::set_time_source`) time
+    /// source instead.
+    ///
+    /// # Panics
+    ///
+    /// if the time source cannot determine the current time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use up_rust::{time_source::SystemTimeSource, UUID};
+    ///
+    /// let uuid = UUID::build_with_time_source(&SystemTimeSource);
+    /// assert!(uuid.is_uprotocol_uuid());
+    /// ```
+    // [impl->dsn~uuid-spec~1]
+    pub fn build_with_time_source(time_source: &dyn TimeSource) -> UUID {
+        let duration_since_unix_epoch = time_source
+            .now()
+            .expect("time source cannot determine current time");
         Self::build_for_timestamp(duration_since_unix_epoch)
     }
 
@@ -270,6 +298,22 @@ impl UUID {
 
 impl Eq for UUID {}
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for UUID {
+    /// Formats this UUID like [`UUID::to_hyphenated_string`].
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{=u32:08x}-{=u16:04x}-{=u16:04x}-{=u16:04x}-{=u64:012x}",
+            (self.msb >> 32) as u32,
+            (self.msb >> 16) as u16,
+            self.msb as u16,
+            (self.lsb >> 48) as u16,
+            self.lsb & 0xFFFF_FFFF_FFFF
+        );
+    }
+}
+
 impl Hash for UUID {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         let bytes = (self.msb, self.lsb);
@@ -0,0 +1,291 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+/*!
+Provides means to audit the IDs of messages that are sent or received by a uEntity.
+
+The uProtocol specification requires message IDs to be unique and to contain the point in time
+at which the message has been created. A [`UuidAudit`] keeps track of recently observed IDs and
+flags IDs that have been seen before or whose creation time lies before the creation time of an
+ID previously seen from the same source. Such findings usually indicate a buggy transport
+(re-delivering messages) or multiple processes using the same uEntity identity.
+*/
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{UMessage, UUri, UUID};
+
+/// A problem detected with a message ID.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuditFinding {
+    /// The ID is not a valid uProtocol UUID.
+    InvalidId,
+    /// The ID has already been observed before.
+    Duplicate,
+    /// The ID's creation time lies before the creation time of an ID that has previously been
+    /// observed from the same source.
+    ClockRegression {
+        /// The creation time of the latest ID observed from the source (milliseconds since UNIX epoch).
+        latest_time: u64,
+        /// The creation time of the observed ID (milliseconds since UNIX epoch).
+        time: u64,
+    },
+}
+
+impl std::fmt::Display for AuditFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditFinding::InvalidId => f.write_str("ID is not a valid uProtocol UUID"),
+            AuditFinding::Duplicate => f.write_str("ID has been observed before"),
+            AuditFinding::ClockRegression { latest_time, time } => write!(
+                f,
+                "ID has been created at {} which is before the latest ID from the same source ({})",
+                time, latest_time
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AuditFinding {}
+
+/// Keeps track of recently observed message IDs.
+///
+/// The audit remembers a bounded number of the most recently observed IDs only. Duplicates
+/// of IDs that have been evicted from the audit's memory therefore go undetected.
+/// The creation time of the latest ID is remembered for each source, regardless.
+///
+/// # Examples
+///
+/// ```rust
+/// use up_rust::{uuid_audit::{AuditFinding, UuidAudit}, UMessageBuilder, UUri};
+///
+/// let mut audit = UuidAudit::new(100);
+/// let topic = UUri::try_from("//my-vehicle/D45/1/A001").unwrap();
+/// let message = UMessageBuilder::publish(topic).build().unwrap();
+///
+/// assert!(audit.observe_message(&message).is_ok());
+/// assert_eq!(audit.observe_message(&message), Err(AuditFinding::Duplicate));
+/// ```
+pub struct UuidAudit {
+    capacity: usize,
+    recent_ids: HashSet<UUID>,
+    observation_order: VecDeque<UUID>,
+    latest_time_per_source: HashMap<UUri, u64>,
+}
+
+impl UuidAudit {
+    /// Creates a new audit.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of IDs to remember for detecting duplicates.
+    ///
+    /// # Panics
+    ///
+    /// if the capacity is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than 0");
+        UuidAudit {
+            capacity,
+            recent_ids: HashSet::with_capacity(capacity),
+            observation_order: VecDeque::with_capacity(capacity),
+            latest_time_per_source: HashMap::new(),
+        }
+    }
+
+    /// Records the observation of a message ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The message ID.
+    /// * `source` - The address of the uEntity that has created the ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns the problem detected with the ID, if any. IDs that are not valid uProtocol UUIDs
+    /// are not recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use up_rust::{uuid_audit::{AuditFinding, UuidAudit}, UUri, UUID};
+    ///
+    /// let mut audit = UuidAudit::new(100);
+    /// let source = UUri::try_from("//my-vehicle/D45/1/0").unwrap();
+    /// let newer_id = UUID::build();
+    /// let older_id = UUID {
+    ///     // timestamp = 1, ver = 0b0111
+    ///     msb: 0x0000000000017000,
+    ///     // variant = 0b10
+    ///     lsb: 0x8000000000000000,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert!(audit.observe(&newer_id, &source).is_ok());
+    /// assert!(matches!(
+    ///     audit.observe(&older_id, &source),
+    ///     Err(AuditFinding::ClockRegression { time: 1, .. })
+    /// ));
+    /// ```
+    pub fn observe(&mut self, id: &UUID, source: &UUri) -> Result<(), AuditFinding> {
+        let Some(time) = id.get_time() else {
+            return Err(AuditFinding::InvalidId);
+        };
+        if self.recent_ids.contains(id) {
+            return Err(AuditFinding::Duplicate);
+        }
+        self.remember(id);
+
+        let latest_time = self
+            .latest_time_per_source
+            .entry(source.to_owned())
+            .or_insert(time);
+        if time < *latest_time {
+            return Err(AuditFinding::ClockRegression {
+                latest_time: *latest_time,
+                time,
+            });
+        }
+        *latest_time = time;
+        Ok(())
+    }
+
+    /// Records the observation of a message's ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns the problem detected with the message's ID, if any.
+    /// A message without ID or source is reported as [`AuditFinding::InvalidId`].
+    pub fn observe_message(&mut self, message: &UMessage) -> Result<(), AuditFinding> {
+        let attributes = message.attributes.as_ref();
+        let (Some(id), Some(source)) = (
+            attributes.and_then(|attribs| attribs.id.as_ref()),
+            attributes.and_then(|attribs| attribs.source.as_ref()),
+        ) else {
+            return Err(AuditFinding::InvalidId);
+        };
+        self.observe(id, source)
+    }
+
+    /// Forgets all observed IDs.
+    pub fn clear(&mut self) {
+        self.recent_ids.clear();
+        self.observation_order.clear();
+        self.latest_time_per_source.clear();
+    }
+
+    fn remember(&mut self, id: &UUID) {
+        if self.observation_order.len() == self.capacity {
+            if let Some(oldest_id) = self.observation_order.pop_front() {
+                self.recent_ids.remove(&oldest_id);
+            }
+        }
+        self.observation_order.push_back(id.to_owned());
+        self.recent_ids.insert(id.to_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uuid_for_time(time: u64, random: u64) -> UUID {
+        UUID {
+            msb: time << 16 | 0x7000,
+            lsb: 0x8000000000000000 | random,
+            ..Default::default()
+        }
+    }
+
+    fn source(ue_id: u32) -> UUri {
+        UUri::try_from_parts("my-vehicle", ue_id, 0x01, 0x0000).unwrap()
+    }
+
+    #[test]
+    fn test_observe_detects_duplicates() {
+        let mut audit = UuidAudit::new(10);
+        let id = uuid_for_time(100, 1);
+        assert!(audit.observe(&id, &source(0x1000)).is_ok());
+        assert_eq!(
+            audit.observe(&id, &source(0x2000)),
+            Err(AuditFinding::Duplicate)
+        );
+    }
+
+    #[test]
+    fn test_observe_forgets_ids_beyond_capacity() {
+        // GIVEN an audit that remembers two IDs only
+        let mut audit = UuidAudit::new(2);
+        let first_id = uuid_for_time(100, 1);
+
+        // WHEN observing three IDs
+        assert!(audit.observe(&first_id, &source(0x1000)).is_ok());
+        assert!(audit
+            .observe(&uuid_for_time(101, 1), &source(0x1000))
+            .is_ok());
+        assert!(audit
+            .observe(&uuid_for_time(102, 1), &source(0x1000))
+            .is_ok());
+
+        // THEN the first ID is no longer considered a duplicate
+        // but still violates the source's creation time order
+        assert_eq!(
+            audit.observe(&first_id, &source(0x1000)),
+            Err(AuditFinding::ClockRegression {
+                latest_time: 102,
+                time: 100
+            })
+        );
+    }
+
+    #[test]
+    fn test_observe_tracks_creation_time_per_source() {
+        let mut audit = UuidAudit::new(10);
+        assert!(audit
+            .observe(&uuid_for_time(100, 1), &source(0x1000))
+            .is_ok());
+        // same creation time is allowed
+        assert!(audit
+            .observe(&uuid_for_time(100, 2), &source(0x1000))
+            .is_ok());
+        // older IDs from other sources are allowed
+        assert!(audit
+            .observe(&uuid_for_time(50, 1), &source(0x2000))
+            .is_ok());
+        assert_eq!(
+            audit.observe(&uuid_for_time(99, 1), &source(0x1000)),
+            Err(AuditFinding::ClockRegression {
+                latest_time: 100,
+                time: 99
+            })
+        );
+    }
+
+    #[test]
+    fn test_observe_rejects_invalid_id() {
+        let mut audit = UuidAudit::new(10);
+        let id = UUID {
+            msb: 0x000000000001C000,
+            lsb: 0x8000000000000000,
+            ..Default::default()
+        };
+        assert_eq!(
+            audit.observe(&id, &source(0x1000)),
+            Err(AuditFinding::InvalidId)
+        );
+        assert_eq!(
+            audit.observe_message(&UMessage::default()),
+            Err(AuditFinding::InvalidId)
+        );
+    }
+}
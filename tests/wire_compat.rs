@@ -0,0 +1,44 @@
+/********************************************************************************
+ * Copyright (c) 2024 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+// Compares the wire format of representative objects against the checked-in snapshots.
+//
+// A failure indicates that up-rust can no longer communicate with uEntities built from
+// an earlier version. If the change of the wire format is intentional, the snapshots can be
+// re-created by running the test with environment variable UP_RUST_UPDATE_WIRE_SNAPSHOTS set.
+
+use std::path::PathBuf;
+
+use up_rust::compat::WireFixture;
+
+fn snapshot_path(fixture: WireFixture) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots/wire")
+        .join(format!("{}.bin", fixture.name()))
+}
+
+#[test]
+fn test_wire_format_matches_snapshots() {
+    let update = std::env::var_os("UP_RUST_UPDATE_WIRE_SNAPSHOTS").is_some();
+    for fixture in WireFixture::ALL {
+        let path = snapshot_path(fixture);
+        if update {
+            std::fs::write(&path, fixture.encode()).expect("failed to write snapshot");
+        }
+        let snapshot = std::fs::read(&path)
+            .unwrap_or_else(|e| panic!("failed to read snapshot {}: {}", path.display(), e));
+        if let Err(mismatch) = fixture.verify(&snapshot) {
+            panic!("{mismatch}");
+        }
+    }
+}